@@ -27,3 +27,86 @@ impl std::fmt::Display for TickCount {
         write!(f, "{}T", self.0)
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RangeKind {
+    /// Bars closing once their high to low spans the size
+    Range,
+    /// Bricks of exactly the size, a new one needs price to move a brick beyond the last
+    /// close, or two for a reversal
+    Renko,
+}
+
+impl RangeKind {
+    pub const ALL: [RangeKind; 2] = [RangeKind::Range, RangeKind::Renko];
+}
+
+impl std::fmt::Display for RangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RangeKind::Range => write!(f, "Range"),
+            RangeKind::Renko => write!(f, "Renko"),
+        }
+    }
+}
+
+/// Bars built from price movement, sized in ticks of the chart's price grouping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangeBar {
+    pub kind: RangeKind,
+    pub ticks: u16,
+}
+
+impl RangeBar {
+    pub const TICKS: [u16; 6] = [2, 5, 10, 20, 50, 100];
+
+    pub fn new(kind: RangeKind, ticks: u16) -> Self {
+        Self { kind, ticks }
+    }
+
+    pub fn size(&self, tick_size: f32) -> f32 {
+        f32::from(self.ticks) * tick_size
+    }
+
+    pub fn is_custom(&self) -> bool {
+        !Self::TICKS.contains(&self.ticks)
+    }
+}
+
+impl std::fmt::Display for RangeBar {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.kind {
+            RangeKind::Range => write!(f, "{}R", self.ticks),
+            RangeKind::Renko => write!(f, "{} Renko", self.ticks),
+        }
+    }
+}
+
+/// What completes a bar of a trade based chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeBar {
+    Count(TickCount),
+    Range(RangeBar),
+}
+
+impl TradeBar {
+    /// Units of the x axis each bar takes, trade counts keep counting trades there
+    pub fn x_step(&self) -> u64 {
+        match self {
+            TradeBar::Count(count) => u64::from(count.0),
+            TradeBar::Range(_) => 1,
+        }
+    }
+}
+
+impl From<TickCount> for TradeBar {
+    fn from(count: TickCount) -> Self {
+        TradeBar::Count(count)
+    }
+}
+
+impl From<RangeBar> for TradeBar {
+    fn from(range: RangeBar) -> Self {
+        TradeBar::Range(range)
+    }
+}
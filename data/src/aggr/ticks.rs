@@ -2,7 +2,7 @@ use exchange::{Kline, Trade};
 use ordered_float::OrderedFloat;
 use std::collections::BTreeMap;
 
-use crate::aggr;
+use crate::aggr::{self, RangeBar, RangeKind};
 use crate::chart::kline::{ClusterKind, KlineTrades, NPoc};
 use crate::util::round_to_tick;
 
@@ -37,6 +37,22 @@ impl TickAccumulation {
         }
     }
 
+    /// A renko brick price skipped over in a single move, it has no trades of its own
+    fn empty_brick(time: u64, open: f32, close: f32) -> Self {
+        Self {
+            tick_count: 0,
+            kline: Kline {
+                time,
+                open,
+                high: open.max(close),
+                low: open.min(close),
+                close,
+                volume: (0.0, 0.0),
+            },
+            footprint: KlineTrades::new(),
+        }
+    }
+
     pub fn update_with_trade(&mut self, trade: &Trade, tick_size: f32) {
         self.tick_count += 1;
         self.kline.high = self.kline.high.max(trade.price);
@@ -93,16 +109,19 @@ impl TickAccumulation {
 
 pub struct TickAggr {
     pub datapoints: Vec<TickAccumulation>,
-    pub interval: aggr::TickCount,
+    pub interval: aggr::TradeBar,
     pub tick_size: f32,
+    /// Renko bricks before this index are complete, the one at it is still forming
+    closed_bricks: usize,
 }
 
 impl TickAggr {
-    pub fn new(interval: aggr::TickCount, tick_size: f32, raw_trades: &[Trade]) -> Self {
+    pub fn new(interval: impl Into<aggr::TradeBar>, tick_size: f32, raw_trades: &[Trade]) -> Self {
         let mut tick_aggr = Self {
             datapoints: Vec::new(),
-            interval,
+            interval: interval.into(),
             tick_size,
+            closed_bricks: 0,
         };
 
         if !raw_trades.is_empty() {
@@ -116,6 +135,7 @@ impl TickAggr {
         self.tick_size = tick_size;
 
         self.datapoints.clear();
+        self.closed_bricks = 0;
 
         if !raw_trades.is_empty() {
             self.insert_trades(raw_trades);
@@ -137,6 +157,15 @@ impl TickAggr {
         let mut updated_indices = Vec::new();
 
         for trade in buffer {
+            if let aggr::TradeBar::Range(RangeBar {
+                kind: RangeKind::Renko,
+                ..
+            }) = self.interval
+            {
+                self.insert_renko_trade(trade, &mut updated_indices);
+                continue;
+            }
+
             if self.datapoints.is_empty() {
                 self.datapoints
                     .push(TickAccumulation::new(trade, self.tick_size));
@@ -144,7 +173,18 @@ impl TickAggr {
             } else {
                 let last_idx = self.datapoints.len() - 1;
 
-                if self.datapoints[last_idx].is_full(self.interval) {
+                let is_complete = match self.interval {
+                    aggr::TradeBar::Count(count) => self.datapoints[last_idx].is_full(count),
+                    aggr::TradeBar::Range(range) => {
+                        let kline = &self.datapoints[last_idx].kline;
+                        let span = kline.high.max(trade.price) - kline.low.min(trade.price);
+
+                        // half a tick of slack against float noise on the bar size
+                        span > range.size(self.tick_size) + self.tick_size * 0.5
+                    }
+                };
+
+                if is_complete {
                     self.datapoints
                         .push(TickAccumulation::new(trade, self.tick_size));
                     updated_indices.push(self.datapoints.len() - 1);
@@ -166,6 +206,65 @@ impl TickAggr {
         self.update_poc_status();
     }
 
+    /// Adds the trade to the forming brick, closing it along with any bricks the price jumped
+    /// over once it moved a brick past the last close, or two for a reversal
+    fn insert_renko_trade(&mut self, trade: &Trade, updated_indices: &mut Vec<usize>) {
+        let aggr::TradeBar::Range(range) = self.interval else {
+            return;
+        };
+        let brick = range.size(self.tick_size);
+
+        if self.datapoints.len() == self.closed_bricks {
+            self.datapoints
+                .push(TickAccumulation::new(trade, self.tick_size));
+        } else if let Some(forming) = self.datapoints.last_mut() {
+            forming.update_with_trade(trade, self.tick_size);
+        }
+
+        let forming_idx = self.datapoints.len() - 1;
+        if !updated_indices.contains(&forming_idx) {
+            updated_indices.push(forming_idx);
+        }
+
+        // the first brick is measured from the first trade it got
+        let (up_from, down_from) = match self.closed_bricks.checked_sub(1) {
+            Some(last_idx) => {
+                let last = &self.datapoints[last_idx].kline;
+                (last.open.max(last.close), last.open.min(last.close))
+            }
+            None => {
+                let open = self.datapoints[forming_idx].kline.open;
+                (open, open)
+            }
+        };
+
+        let (from, bricks, direction) = if trade.price >= up_from + brick {
+            (up_from, ((trade.price - up_from) / brick).floor(), 1.0)
+        } else if trade.price <= down_from - brick {
+            (down_from, ((down_from - trade.price) / brick).floor(), -1.0)
+        } else {
+            return;
+        };
+
+        let forming = &mut self.datapoints[forming_idx];
+        let close = from + direction * brick;
+        forming.kline.open = from;
+        forming.kline.close = close;
+        forming.kline.high = forming.kline.high.max(from.max(close));
+        forming.kline.low = forming.kline.low.min(from.min(close));
+
+        for step in 1..bricks as usize {
+            let open = from + direction * brick * step as f32;
+            self.datapoints.push(TickAccumulation::empty_brick(
+                trade.time,
+                open,
+                open + direction * brick,
+            ));
+        }
+
+        self.closed_bricks = self.datapoints.len();
+    }
+
     pub fn update_poc_status(&mut self) {
         let updates = self
             .datapoints
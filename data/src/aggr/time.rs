@@ -177,15 +177,21 @@ impl TimeSeries<KlineDataPoint> {
         self.update_poc_status();
     }
 
+    pub fn kline_data(&self) -> BTreeMap<u64, Kline> {
+        self.datapoints
+            .iter()
+            .map(|(time, dp)| (*time, dp.kline))
+            .collect()
+    }
+
     pub fn insert_trades(&mut self, buffer: &[Trade]) {
         if buffer.is_empty() {
             return;
         }
-        let aggr_time = self.interval.to_milliseconds();
         let mut updated_times = Vec::new();
 
         buffer.iter().for_each(|trade| {
-            let rounded_time = (trade.time / aggr_time) * aggr_time;
+            let rounded_time = self.interval.open_time(trade.time);
 
             if !updated_times.contains(&rounded_time) {
                 updated_times.push(rounded_time);
@@ -334,7 +340,7 @@ impl TimeSeries<HeatmapDataPoint> {
     pub fn new(basis: Basis, tick_size: f32) -> Self {
         let timeframe = match basis {
             Basis::Time(interval) => interval,
-            Basis::Tick(_) => unimplemented!(),
+            Basis::Tick(_) | Basis::Range(_) => unimplemented!(),
         };
 
         Self {
@@ -0,0 +1,303 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use exchange::{SerTicker, Trade};
+use serde::{Deserialize, Serialize};
+
+const MINUTE_MS: u64 = 60_000;
+
+/// What an alert watches its ticker's trades for
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Condition {
+    /// Last traded price reaching the level from either side
+    PriceCross(f32),
+    /// Price moving at least `pct` percent within the last `window_mins`
+    PercentChange { pct: f32, window_mins: u32 },
+    /// Volume of the current minute exceeding the average minute of the lookback by `multiplier`
+    VolumeSpike { multiplier: f32, lookback_mins: u32 },
+}
+
+impl std::fmt::Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Condition::PriceCross(price) => write!(f, "Crosses {price}"),
+            Condition::PercentChange { pct, window_mins } => {
+                write!(f, "Moves {pct}% in {window_mins}m")
+            }
+            Condition::VolumeSpike {
+                multiplier,
+                lookback_mins,
+            } => write!(f, "Volume {multiplier}x the {lookback_mins}m average"),
+        }
+    }
+}
+
+/// Session of the day, in the user's timezone, an alert is allowed to fire in
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct ActiveHours {
+    pub start: chrono::NaiveTime,
+    pub end: chrono::NaiveTime,
+}
+
+impl ActiveHours {
+    /// Parses `HH:MM-HH:MM`, a session ending before it starts runs past midnight
+    pub fn parse(input: &str) -> Option<Self> {
+        let (start, end) = input.split_once('-')?;
+        let parse = |s: &str| chrono::NaiveTime::parse_from_str(s.trim(), "%H:%M").ok();
+
+        Some(ActiveHours {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+        .filter(|hours| hours.start != hours.end)
+    }
+
+    pub fn contains(&self, time: chrono::NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl std::fmt::Display for ActiveHours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Alert {
+    pub id: u64,
+    pub ticker: SerTicker,
+    pub condition: Condition,
+    pub play_sound: bool,
+    /// Sound played when it fires, the built-in one when `None`
+    #[serde(default)]
+    pub sound: Option<String>,
+    /// Alerts fire once, then stay disarmed until re-armed
+    pub is_armed: bool,
+    /// Unix time in milliseconds the alert stays muted until
+    #[serde(default)]
+    pub snoozed_until: Option<u64>,
+    #[serde(default)]
+    pub active_hours: Option<ActiveHours>,
+}
+
+impl Alert {
+    pub fn fired_message(&self) -> String {
+        let (symbol, _) = self.ticker.ticker.display_symbol_and_type();
+        format!("{symbol} ({}): {}", self.ticker.exchange, self.condition)
+    }
+
+    pub fn is_snoozed(&self, now_ms: u64) -> bool {
+        self.snoozed_until.is_some_and(|until| until > now_ms)
+    }
+}
+
+/// Per alert state built from the live trades, starts over whenever the app does
+enum Watch {
+    LastPrice(f32),
+    Prices(VecDeque<(u64, f32)>),
+    MinuteVolumes(BTreeMap<u64, f32>),
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Alerts {
+    alerts: Vec<Alert>,
+    #[serde(skip)]
+    watches: HashMap<u64, Watch>,
+}
+
+impl Clone for Alerts {
+    fn clone(&self) -> Self {
+        Self {
+            alerts: self.alerts.clone(),
+            watches: HashMap::new(),
+        }
+    }
+}
+
+impl Alerts {
+    pub fn iter(&self) -> impl Iterator<Item = &Alert> {
+        self.alerts.iter()
+    }
+
+    pub fn add(
+        &mut self,
+        ticker: SerTicker,
+        condition: Condition,
+        play_sound: bool,
+        sound: Option<String>,
+        active_hours: Option<ActiveHours>,
+    ) {
+        let id = self
+            .alerts
+            .iter()
+            .map(|alert| alert.id + 1)
+            .max()
+            .unwrap_or(0);
+
+        self.alerts.push(Alert {
+            id,
+            ticker,
+            condition,
+            play_sound,
+            sound,
+            is_armed: true,
+            snoozed_until: None,
+            active_hours,
+        });
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        self.alerts.retain(|alert| alert.id != id);
+        self.watches.remove(&id);
+    }
+
+    pub fn rearm(&mut self, id: u64) {
+        if let Some(alert) = self.alerts.iter_mut().find(|alert| alert.id == id) {
+            alert.is_armed = true;
+            // a price level re-armed right where price sits shouldn't fire on the next trade
+            self.watches.remove(&id);
+        }
+    }
+
+    /// Mutes the alert until `until_ms`, it keeps watching the trades meanwhile
+    pub fn snooze(&mut self, id: u64, until_ms: Option<u64>) {
+        if let Some(alert) = self.alerts.iter_mut().find(|alert| alert.id == id) {
+            alert.snoozed_until = until_ms;
+        }
+    }
+
+    /// Moves a price cross alert to another level, other conditions have no level to move
+    pub fn move_price(&mut self, id: u64, price: f32) {
+        if let Some(alert) = self.alerts.iter_mut().find(|alert| alert.id == id) {
+            if let Condition::PriceCross(level) = &mut alert.condition {
+                *level = price;
+                // the last price seen was relative to the old level
+                self.watches.remove(&id);
+            }
+        }
+    }
+
+    /// Checks the armed alerts of the ticker against a batch of its trades, returning the ones
+    /// that fired, which get disarmed
+    ///
+    /// Snoozed alerts and the ones outside their active hours at `now`, the wall clock time in
+    /// the user's timezone, are still watched but can't fire
+    pub fn evaluate(
+        &mut self,
+        ticker: SerTicker,
+        trades: &[Trade],
+        now: chrono::NaiveDateTime,
+    ) -> Vec<Alert> {
+        let Some(last) = trades.last() else {
+            return vec![];
+        };
+
+        let mut fired = vec![];
+
+        for alert in self
+            .alerts
+            .iter_mut()
+            .filter(|alert| alert.is_armed && alert.ticker == ticker)
+        {
+            let is_triggered = match alert.condition {
+                Condition::PriceCross(level) => {
+                    let previous = match self.watches.get(&alert.id) {
+                        Some(Watch::LastPrice(price)) => Some(*price),
+                        _ => None,
+                    };
+                    self.watches.insert(alert.id, Watch::LastPrice(last.price));
+
+                    // any trade of the batch counts, price may have gone through the level and back
+                    previous.is_some_and(|previous| {
+                        trades.iter().any(|trade| {
+                            (previous < level && trade.price >= level)
+                                || (previous > level && trade.price <= level)
+                        })
+                    })
+                }
+                Condition::PercentChange { pct, window_mins } => {
+                    let window = u64::from(window_mins) * MINUTE_MS;
+
+                    let Watch::Prices(prices) = self
+                        .watches
+                        .entry(alert.id)
+                        .or_insert_with(|| Watch::Prices(VecDeque::new()))
+                    else {
+                        continue;
+                    };
+
+                    prices.push_back((last.time, last.price));
+                    while prices
+                        .front()
+                        .is_some_and(|(time, _)| last.time.saturating_sub(*time) > window)
+                    {
+                        prices.pop_front();
+                    }
+
+                    prices.front().is_some_and(|(_, start)| {
+                        *start > 0.0 && ((last.price - start) / start).abs() * 100.0 >= pct
+                    })
+                }
+                Condition::VolumeSpike {
+                    multiplier,
+                    lookback_mins,
+                } => {
+                    let Watch::MinuteVolumes(volumes) = self
+                        .watches
+                        .entry(alert.id)
+                        .or_insert_with(|| Watch::MinuteVolumes(BTreeMap::new()))
+                    else {
+                        continue;
+                    };
+
+                    for trade in trades {
+                        *volumes.entry(trade.time / MINUTE_MS).or_default() += trade.qty;
+                    }
+
+                    let current_minute = last.time / MINUTE_MS;
+                    let oldest = current_minute.saturating_sub(u64::from(lookback_mins));
+                    volumes.retain(|minute, _| *minute >= oldest);
+
+                    let lookback = volumes
+                        .range(..current_minute)
+                        .map(|(_, volume)| *volume)
+                        .collect::<Vec<_>>();
+
+                    // only compares once a full lookback has been seen since the app started
+                    lookback.len() >= lookback_mins as usize && {
+                        let average = lookback.iter().sum::<f32>() / lookback.len() as f32;
+                        average > 0.0
+                            && volumes.get(&current_minute).copied().unwrap_or_default()
+                                >= average * multiplier
+                    }
+                }
+            };
+
+            let is_muted = alert.is_snoozed(last.time)
+                || alert
+                    .active_hours
+                    .is_some_and(|hours| !hours.contains(now.time()));
+
+            if is_triggered && !is_muted {
+                alert.is_armed = false;
+                fired.push(alert.clone());
+            }
+        }
+
+        for alert in &fired {
+            self.watches.remove(&alert.id);
+        }
+
+        fired
+    }
+}
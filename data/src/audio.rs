@@ -2,7 +2,9 @@ use exchange::SerTicker;
 use rodio::{Decoder, OutputStream, OutputStreamHandle, Source};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 
+use crate::data_path;
 use crate::util::ok_or_default;
 
 pub const BUY_SOUND_DATA: &[u8] = include_bytes!("../../assets/sounds/hard-typewriter-click.wav");
@@ -18,10 +20,18 @@ pub const HARD_SELL_SOUND: &str = "fall-on-foam-splash.wav";
 
 pub const DEFAULT_SOUNDS: &[&str] = &[BUY_SOUND, SELL_SOUND, HARD_BUY_SOUND, HARD_SELL_SOUND];
 
+/// User provided sounds are picked up from here, under the data folder
+pub const SOUNDS_FOLDER: &str = "sounds";
+const SOUND_EXTENSIONS: [&str; 2] = ["wav", "ogg"];
+/// Every sound is kept decoded in memory, anything longer than a short cue is refused
+const MAX_SOUND_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
 pub struct SoundCache {
     _stream: OutputStream,
     stream_handle: OutputStreamHandle,
     sample_buffers: HashMap<String, rodio::buffer::SamplesBuffer<i16>>,
+    /// Names of the sounds loaded from the sounds folder, in the order found
+    user_sounds: Vec<String>,
     volume: Option<f32>,
 }
 
@@ -36,6 +46,7 @@ impl SoundCache {
             _stream: stream,
             stream_handle,
             sample_buffers: HashMap::new(),
+            user_sounds: vec![],
             volume,
         })
     }
@@ -82,22 +93,110 @@ impl SoundCache {
         Ok(())
     }
 
+    /// Loads every WAV and OGG file of the sounds folder, replacing the ones loaded before.
+    /// Returns why each file that couldn't be loaded was skipped
+    pub fn load_user_sounds(&mut self) -> Vec<String> {
+        for name in self.user_sounds.drain(..) {
+            self.sample_buffers.remove(&name);
+        }
+
+        let folder = data_path(Some(SOUNDS_FOLDER));
+        let Ok(entries) = std::fs::read_dir(&folder) else {
+            return vec![];
+        };
+
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut errors = vec![];
+        for path in paths {
+            match self.load_sound_file(&path) {
+                Ok(name) => self.user_sounds.push(name),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        errors
+    }
+
+    fn load_sound_file(&mut self, path: &Path) -> Result<String, String> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| format!("Invalid sound path {path:?}"))?;
+
+        let has_sound_extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .is_some_and(|ext| SOUND_EXTENSIONS.contains(&ext.as_str()));
+        if !has_sound_extension {
+            return Err(format!("'{name}' is not a WAV or OGG file"));
+        }
+
+        if DEFAULT_SOUNDS.contains(&name.as_str()) {
+            return Err(format!("'{name}' has the name of a built-in sound"));
+        }
+
+        let size = std::fs::metadata(path)
+            .map_err(|err| format!("Failed to read '{name}': {err}"))?
+            .len();
+        if size > MAX_SOUND_FILE_BYTES {
+            return Err(format!(
+                "'{name}' is over the {} MB limit",
+                MAX_SOUND_FILE_BYTES / (1024 * 1024)
+            ));
+        }
+
+        let data = std::fs::read(path).map_err(|err| format!("Failed to read '{name}': {err}"))?;
+        self.load_sound_from_memory(&name, &data)
+            .map_err(|err| format!("'{name}': {err}"))?;
+
+        Ok(name)
+    }
+
+    /// Built-in sounds followed by the ones loaded from the sounds folder
+    pub fn sounds(&self) -> Vec<String> {
+        DEFAULT_SOUNDS
+            .iter()
+            .map(|sound| sound.to_string())
+            .chain(self.user_sounds.iter().cloned())
+            .collect()
+    }
+
+    pub fn user_sounds(&self) -> &[String] {
+        &self.user_sounds
+    }
+
     pub fn play(&self, path: &str) -> Result<(), String> {
+        self.play_scaled(path, 100.0)
+    }
+
+    /// Plays the sound at a percentage of the cache's volume
+    pub fn play_scaled(&self, path: &str, level: f32) -> Result<(), String> {
         let Some(volume) = self.volume else {
             return Ok(());
         };
 
-        let buffer = self
-            .sample_buffers
-            .get(path)
-            .ok_or(format!("Sound '{}' not loaded in cache", path))?;
+        // a user sound may have been removed from the folder since it was picked
+        let buffer = match self.sample_buffers.get(path) {
+            Some(buffer) => buffer,
+            None => {
+                log::warn!("Sound '{path}' not loaded in cache, playing the built-in one");
+                self.sample_buffers
+                    .get(HARD_BUY_SOUND)
+                    .ok_or(format!("Sound '{}' not loaded in cache", path))?
+            }
+        };
 
         let sink = match rodio::Sink::try_new(&self.stream_handle) {
             Ok(sink) => sink,
             Err(err) => return Err(format!("Failed to create audio sink: {}", err)),
         };
 
-        sink.set_volume(volume / 100.0);
+        sink.set_volume(volume / 100.0 * level.clamp(0.0, 100.0) / 100.0);
 
         sink.append(buffer.clone());
         sink.detach();
@@ -141,10 +240,41 @@ impl std::fmt::Display for Threshold {
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+/// Sound played when a single trade's notional reaches `min_notional`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SizeBucket {
+    pub min_notional: f32,
+    pub sound: String,
+    /// Percentage of the master volume
+    pub volume: f32,
+}
+
+impl Default for SizeBucket {
+    fn default() -> Self {
+        SizeBucket {
+            min_notional: 100_000.0,
+            sound: HARD_BUY_SOUND.to_string(),
+            volume: 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct StreamCfg {
     pub enabled: bool,
     pub threshold: Threshold,
+    #[serde(deserialize_with = "ok_or_default", default)]
+    pub size_buckets: Vec<SizeBucket>,
+}
+
+impl StreamCfg {
+    /// The largest bucket a trade of this notional reaches, if any
+    pub fn size_bucket(&self, notional: f32) -> Option<&SizeBucket> {
+        self.size_buckets
+            .iter()
+            .filter(|bucket| notional >= bucket.min_notional)
+            .max_by(|a, b| a.min_notional.total_cmp(&b.min_notional))
+    }
 }
 
 impl Default for StreamCfg {
@@ -152,6 +282,7 @@ impl Default for StreamCfg {
         StreamCfg {
             enabled: true,
             threshold: Threshold::Count(10),
+            size_buckets: vec![],
         }
     }
 }
@@ -0,0 +1,108 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use chrono::NaiveDateTime;
+
+use crate::{InternalError, SAVED_STATE_PATH, data_path};
+
+/// How many timestamped copies of the saved state are kept before the oldest get pruned
+pub const BACKUP_LIMIT: usize = 10;
+
+const BACKUPS_FOLDER: &str = "backups";
+const FILE_PREFIX: &str = "saved-state_";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A rotating copy of the saved state, taken right before it gets overwritten
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub created_at: NaiveDateTime,
+}
+
+impl fmt::Display for Backup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.created_at.format("%Y-%m-%d %H:%M:%S"))
+    }
+}
+
+fn backups_path(file_name: Option<&str>) -> PathBuf {
+    let folder = data_path(Some(BACKUPS_FOLDER));
+    match file_name {
+        Some(file_name) => folder.join(file_name),
+        None => folder,
+    }
+}
+
+/// Copies the current saved state into the backups folder and prunes the oldest copies
+/// past [`BACKUP_LIMIT`]. Nothing to do on a first launch, when there's no state yet.
+pub fn rotate() -> Result<Option<Backup>, InternalError> {
+    let source = data_path(Some(SAVED_STATE_PATH));
+    if !source.exists() {
+        return Ok(None);
+    }
+
+    std::fs::create_dir_all(backups_path(None))
+        .map_err(|err| InternalError::Backup(format!("Failed to create backups folder: {err}")))?;
+
+    let created_at = chrono::Local::now().naive_local();
+    let path = backups_path(Some(&format!(
+        "{FILE_PREFIX}{}.json",
+        created_at.format(TIMESTAMP_FORMAT)
+    )));
+
+    std::fs::copy(&source, &path)
+        .map_err(|err| InternalError::Backup(format!("Failed to back up saved state: {err}")))?;
+
+    for stale in list().into_iter().skip(BACKUP_LIMIT) {
+        if let Err(err) = std::fs::remove_file(&stale.path) {
+            log::warn!(
+                "Failed to remove old backup {}: {err}",
+                stale.path.display()
+            );
+        }
+    }
+
+    Ok(Some(Backup { path, created_at }))
+}
+
+/// Available backups, newest first
+pub fn list() -> Vec<Backup> {
+    let Ok(entries) = std::fs::read_dir(backups_path(None)) else {
+        return vec![];
+    };
+
+    let mut backups = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let timestamp = path
+                .file_stem()?
+                .to_str()?
+                .strip_prefix(FILE_PREFIX)?
+                .to_string();
+
+            NaiveDateTime::parse_from_str(&timestamp, TIMESTAMP_FORMAT)
+                .ok()
+                .map(|created_at| Backup { path, created_at })
+        })
+        .collect::<Vec<_>>();
+
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    backups
+}
+
+/// Replaces the saved state with the backup, after making sure it still parses.
+/// Takes effect on the next launch, so the caller has to exit without saving.
+pub fn restore(backup: &Backup) -> Result<(), InternalError> {
+    let contents = std::fs::read_to_string(&backup.path)
+        .map_err(|err| InternalError::Backup(format!("Failed to read backup: {err}")))?;
+
+    crate::config::migration::parse_state(&contents)
+        .map_err(|err| InternalError::Backup(format!("Backup is not a valid state: {err}")))?;
+
+    std::fs::copy(&backup.path, data_path(Some(SAVED_STATE_PATH)))
+        .map_err(|err| InternalError::Backup(format!("Failed to restore backup: {err}")))?;
+
+    log::info!("Restored saved state from {}", backup.path.display());
+    Ok(())
+}
@@ -1,6 +1,10 @@
+pub mod anomaly;
+pub mod day_levels;
+pub mod drawing;
 pub mod heatmap;
 pub mod indicator;
 pub mod kline;
+pub mod ladder;
 pub mod timeandsales;
 
 use exchange::{Timeframe, adapter::Exchange};
@@ -46,10 +50,47 @@ impl<D: DataPoint> PlotData<D> {
     }
 }
 
+impl PlotData<kline::KlineDataPoint> {
+    /// Lowest and highest delta the bars in range reached, zero always included
+    pub fn visible_delta_range(
+        &self,
+        start_interval: u64,
+        end_interval: u64,
+    ) -> Option<(f32, f32)> {
+        let deltas = match self {
+            PlotData::TimeBased(timeseries) => timeseries
+                .datapoints
+                .range(start_interval..=end_interval)
+                .map(|(time, dp)| dp.footprint.delta_kline(*time))
+                .collect::<Vec<_>>(),
+            PlotData::TickBased(tick_aggr) => tick_aggr
+                .datapoints
+                .iter()
+                .rev()
+                .enumerate()
+                .filter(|(index, _)| {
+                    *index >= start_interval as usize && *index <= end_interval as usize
+                })
+                .map(|(_, dp)| dp.footprint.delta_kline(dp.kline.time))
+                .collect::<Vec<_>>(),
+        };
+
+        if deltas.is_empty() {
+            return None;
+        }
+
+        Some(deltas.iter().fold((0.0f32, 0.0f32), |(low, high), kline| {
+            (low.min(kline.low), high.max(kline.high))
+        }))
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct ViewConfig {
     pub splits: Vec<f32>,
     pub autoscale: Option<Autoscale>,
+    #[serde(default)]
+    pub drawings: Vec<drawing::Drawing>,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq)]
@@ -64,6 +105,7 @@ pub enum VisualConfig {
     Heatmap(heatmap::Config),
     TimeAndSales(timeandsales::Config),
     Kline(kline::Config),
+    Ladder(ladder::Config),
 }
 
 impl VisualConfig {
@@ -87,6 +129,13 @@ impl VisualConfig {
             _ => None,
         }
     }
+
+    pub fn ladder(&self) -> Option<ladder::Config> {
+        match self {
+            Self::Ladder(cfg) => Some(*cfg),
+            _ => None,
+        }
+    }
 }
 
 /// Defines how chart data is aggregated and displayed along the x-axis.
@@ -99,6 +148,10 @@ pub enum Basis {
     ///
     /// The u16 value represents the number of trades per aggregation unit.
     Tick(aggr::TickCount),
+
+    /// Trade-based aggregation where each datapoint closes on price movement, as range bars
+    /// or renko bricks.
+    Range(aggr::RangeBar),
 }
 
 impl Basis {
@@ -106,16 +159,27 @@ impl Basis {
         matches!(self, Basis::Time(_))
     }
 
+    /// How the datapoints of a trade based basis are built, `None` for time based ones
+    pub fn trade_bar(&self) -> Option<aggr::TradeBar> {
+        match self {
+            Basis::Time(_) => None,
+            Basis::Tick(count) => Some(aggr::TradeBar::Count(*count)),
+            Basis::Range(range) => Some(aggr::TradeBar::Range(*range)),
+        }
+    }
+
     pub fn default_heatmap_time(ticker_info: Option<exchange::TickerInfo>) -> Self {
-        let interval = ticker_info.map_or(Timeframe::MS100, |info| {
-            if info.exchange() == Exchange::BybitSpot {
-                Timeframe::MS200
-            } else {
-                Timeframe::MS100
+        let interval = crate::config::chart_defaults().heatmap;
+
+        // Bybit spot depth only comes every 200ms
+        match ticker_info {
+            Some(info)
+                if info.exchange() == Exchange::BybitSpot && interval == Timeframe::MS100 =>
+            {
+                Timeframe::MS200.into()
             }
-        });
-
-        interval.into()
+            _ => interval.into(),
+        }
     }
 }
 
@@ -124,6 +188,7 @@ impl std::fmt::Display for Basis {
         match self {
             Basis::Time(timeframe) => write!(f, "{timeframe}"),
             Basis::Tick(count) => write!(f, "{count}"),
+            Basis::Range(range) => write!(f, "{range}"),
         }
     }
 }
@@ -139,3 +204,30 @@ pub enum Study {
     Heatmap(Vec<heatmap::HeatmapStudy>),
     Footprint(Vec<kline::FootprintStudy>),
 }
+
+const STUDY_CLIPBOARD_PREFIX: &str = "flowsurface-studies:";
+
+/// Study setup of a chart as it goes through the system clipboard, so it can be pasted
+/// onto another pane of the same kind without copying the rest of its settings
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StudyClipboard {
+    pub studies: Study,
+    /// Only carried by footprint charts
+    #[serde(default)]
+    pub clusters: Option<kline::ClusterKind>,
+}
+
+impl StudyClipboard {
+    pub fn to_clipboard(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self).map(|json| format!("{STUDY_CLIPBOARD_PREFIX}{json}"))
+    }
+
+    /// `None` when the clipboard holds anything other than a copied study setup
+    pub fn from_clipboard(contents: &str) -> Option<Self> {
+        let json = contents.trim().strip_prefix(STUDY_CLIPBOARD_PREFIX)?;
+
+        serde_json::from_str(json)
+            .inspect_err(|err| log::warn!("Failed to parse copied studies: {err}"))
+            .ok()
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use exchange::Kline;
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::total_volume;
+
+/// Bars a value is compared against, the average of them is what counts as usual
+const LOOKBACK: usize = 20;
+/// How many times the usual a value has to be to get flagged
+const SPIKE_RATIO: f32 = 3.0;
+
+/// Which anomalies get marked on a chart, each one can be turned on by itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Detectors {
+    pub volume_spike: bool,
+    pub spread_blowout: bool,
+    pub oi_dump: bool,
+}
+
+impl Detectors {
+    pub fn any(&self) -> bool {
+        self.volume_spike || self.spread_blowout || self.oi_dump
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// Bar volume far above the bars before it
+    VolumeSpike,
+    /// Bar spread, its high to low range, far wider than the bars before it
+    SpreadBlowout,
+    /// Open interest dropping far faster than it usually changes
+    OiDump,
+}
+
+impl std::fmt::Display for AnomalyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnomalyKind::VolumeSpike => write!(f, "Volume spike"),
+            AnomalyKind::SpreadBlowout => write!(f, "Spread blowout"),
+            AnomalyKind::OiDump => write!(f, "OI dump"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    /// Multiple of the lookback average the bar reached
+    pub ratio: f32,
+}
+
+impl std::fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:.1}x avg", self.kind, self.ratio)
+    }
+}
+
+/// Anomalies of each bar, from the bars and the open interest the chart already keeps
+pub fn detect(
+    klines: &BTreeMap<u64, Kline>,
+    open_interest: Option<&BTreeMap<u64, f32>>,
+    detectors: Detectors,
+) -> BTreeMap<u64, Vec<Anomaly>> {
+    let mut anomalies: BTreeMap<u64, Vec<Anomaly>> = BTreeMap::new();
+    let mut mark = |kind: AnomalyKind, spikes: Vec<(u64, f32)>| {
+        for (time, ratio) in spikes {
+            anomalies
+                .entry(time)
+                .or_default()
+                .push(Anomaly { kind, ratio });
+        }
+    };
+
+    if detectors.volume_spike {
+        let volumes = klines
+            .iter()
+            .map(|(time, kline)| (*time, total_volume(kline.volume.0, kline.volume.1)))
+            .collect::<Vec<_>>();
+
+        mark(AnomalyKind::VolumeSpike, spikes(&volumes));
+    }
+
+    if detectors.spread_blowout {
+        let spreads = klines
+            .iter()
+            .map(|(time, kline)| (*time, kline.high - kline.low))
+            .collect::<Vec<_>>();
+
+        mark(AnomalyKind::SpreadBlowout, spikes(&spreads));
+    }
+
+    if let (true, Some(open_interest)) = (detectors.oi_dump, open_interest) {
+        let changes = open_interest
+            .iter()
+            .zip(open_interest.values().skip(1))
+            .map(|((_, prev), (time, value))| (*time, value - prev))
+            .collect::<Vec<_>>();
+        let magnitudes = changes
+            .iter()
+            .map(|(time, change)| (*time, change.abs()))
+            .collect::<Vec<_>>();
+
+        // only the drops count, a spike in new positions is no dump
+        let dumps = spikes(&magnitudes)
+            .into_iter()
+            .filter(|(time, _)| {
+                changes
+                    .binary_search_by_key(time, |(time, _)| *time)
+                    .is_ok_and(|idx| changes[idx].1 < 0.0)
+            })
+            .collect::<Vec<_>>();
+
+        // open interest points don't have to line up with the bars, each goes to the bar it's in
+        let dumps = dumps
+            .into_iter()
+            .filter_map(|(time, ratio)| {
+                klines
+                    .range(..=time)
+                    .next_back()
+                    .map(|(bar_time, _)| (*bar_time, ratio))
+            })
+            .collect();
+
+        mark(AnomalyKind::OiDump, dumps);
+    }
+
+    anomalies
+}
+
+/// Values at least [`SPIKE_RATIO`] times the average of the [`LOOKBACK`] values before them
+fn spikes(values: &[(u64, f32)]) -> Vec<(u64, f32)> {
+    if values.len() <= LOOKBACK {
+        return vec![];
+    }
+
+    values
+        .windows(LOOKBACK + 1)
+        .filter_map(|window| {
+            let (time, value) = window[LOOKBACK];
+            let average = window[..LOOKBACK].iter().map(|(_, v)| v).sum::<f32>() / LOOKBACK as f32;
+
+            if average <= 0.0 {
+                return None;
+            }
+
+            let ratio = value / average;
+            (ratio >= SPIKE_RATIO).then_some((time, ratio))
+        })
+        .collect()
+}
@@ -0,0 +1,55 @@
+use exchange::Kline;
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Reference levels of a ticker's daily session, drawn as lines over its charts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DayLevels {
+    /// Start of the UTC day the levels belong to
+    pub session_start: u64,
+    pub open: f32,
+    pub prev_close: Option<f32>,
+    pub high: f32,
+    pub low: f32,
+}
+
+impl DayLevels {
+    /// From daily klines in chronological order, the last of them the session in progress
+    pub fn from_daily_klines(klines: &[Kline]) -> Option<Self> {
+        let (current, previous) = match klines {
+            [.., previous, current] => (current, Some(previous)),
+            [current] => (current, None),
+            [] => return None,
+        };
+
+        Some(DayLevels {
+            session_start: current.time - (current.time % DAY_MS),
+            open: current.open,
+            prev_close: previous.map(|kline| kline.close),
+            high: current.high,
+            low: current.low,
+        })
+    }
+
+    /// Widens the day's range with a price printed after the klines were fetched
+    pub fn update(&mut self, price: f32) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+    }
+
+    /// A new session started since, the levels need to be fetched again
+    pub fn is_stale(&self, now: u64) -> bool {
+        now >= self.session_start + DAY_MS
+    }
+
+    /// Label and price of each level
+    pub fn lines(&self) -> Vec<(&'static str, f32)> {
+        let mut lines = vec![("Open", self.open), ("High", self.high), ("Low", self.low)];
+
+        if let Some(prev_close) = self.prev_close {
+            lines.push(("Prev close", prev_close));
+        }
+
+        lines
+    }
+}
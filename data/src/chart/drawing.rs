@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+/// Point a drawing is pinned to, in chart coordinates so it stays put through panning and zooming
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Anchor {
+    pub time: u64,
+    pub price: f32,
+}
+
+impl Anchor {
+    pub fn new(time: u64, price: f32) -> Self {
+        Anchor { time, price }
+    }
+
+    fn shifted(self, time_delta: i64, price_delta: f32) -> Self {
+        Anchor {
+            time: self.time.saturating_add_signed(time_delta),
+            price: self.price + price_delta,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Tool {
+    TrendLine,
+    HorizontalRay,
+    Rectangle,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 3] = [Tool::TrendLine, Tool::HorizontalRay, Tool::Rectangle];
+}
+
+impl std::fmt::Display for Tool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tool::TrendLine => write!(f, "Trendline"),
+            Tool::HorizontalRay => write!(f, "Horizontal ray"),
+            Tool::Rectangle => write!(f, "Rectangle"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Drawing {
+    TrendLine {
+        from: Anchor,
+        to: Anchor,
+    },
+    /// Level running from its anchor to the right edge of the chart
+    HorizontalRay {
+        from: Anchor,
+    },
+    Rectangle {
+        from: Anchor,
+        to: Anchor,
+    },
+}
+
+impl Drawing {
+    /// A drawing of zero size at `anchor`, to be stretched out with [`Drawing::with_end`]
+    pub fn new(tool: Tool, anchor: Anchor) -> Self {
+        match tool {
+            Tool::TrendLine => Drawing::TrendLine {
+                from: anchor,
+                to: anchor,
+            },
+            Tool::HorizontalRay => Drawing::HorizontalRay { from: anchor },
+            Tool::Rectangle => Drawing::Rectangle {
+                from: anchor,
+                to: anchor,
+            },
+        }
+    }
+
+    pub fn tool(&self) -> Tool {
+        match self {
+            Drawing::TrendLine { .. } => Tool::TrendLine,
+            Drawing::HorizontalRay { .. } => Tool::HorizontalRay,
+            Drawing::Rectangle { .. } => Tool::Rectangle,
+        }
+    }
+
+    /// Moves the second anchor while the drawing is being placed, a ray follows with its level
+    pub fn with_end(self, end: Anchor) -> Self {
+        match self {
+            Drawing::TrendLine { from, .. } => Drawing::TrendLine { from, to: end },
+            Drawing::HorizontalRay { from } => Drawing::HorizontalRay {
+                from: Anchor::new(from.time, end.price),
+            },
+            Drawing::Rectangle { from, .. } => Drawing::Rectangle { from, to: end },
+        }
+    }
+
+    pub fn translated(self, time_delta: i64, price_delta: f32) -> Self {
+        match self {
+            Drawing::TrendLine { from, to } => Drawing::TrendLine {
+                from: from.shifted(time_delta, price_delta),
+                to: to.shifted(time_delta, price_delta),
+            },
+            Drawing::HorizontalRay { from } => Drawing::HorizontalRay {
+                from: from.shifted(time_delta, price_delta),
+            },
+            Drawing::Rectangle { from, to } => Drawing::Rectangle {
+                from: from.shifted(time_delta, price_delta),
+                to: to.shifted(time_delta, price_delta),
+            },
+        }
+    }
+}
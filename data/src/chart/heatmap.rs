@@ -6,16 +6,188 @@ use exchange::{adapter::MarketKind, depth::Depth};
 
 use super::Basis;
 use super::aggr::time::DataPoint;
+use super::kline::OverlayColor;
+
+pub mod snapshot;
 
 pub const CLEANUP_THRESHOLD: usize = 4800;
 const GRACE_PERIOD_MS: u64 = 500;
 
+/// Columns of depth history kept at full resolution, older runs move to the compressed tier
+const FULL_RES_COLUMNS: u64 = 1200;
+/// Columns compressed at once, so the pass doesn't run on every depth update
+const COMPRESS_BATCH_COLUMNS: u64 = 300;
+/// Price levels merged into a single bin of the compressed tier
+pub const COMPRESSED_PRICE_TICKS: u16 = 4;
+/// Columns merged into a single time bucket of the compressed tier
+const COMPRESSED_TIME_COLUMNS: u64 = 4;
+
+/// Percent from the mid price the imbalance indicator looks at, unless configured otherwise
+pub const DEFAULT_IMBALANCE_RANGE: f32 = 0.5;
+
 #[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Config {
     pub trade_size_filter: f32,
     pub order_size_filter: f32,
     pub trade_size_scale: Option<i32>,
     pub coalescing: Option<CoalesceKind>,
+    /// Bubble size scaling of liquidations, hidden when `None`
+    pub liquidation_scale: Option<i32>,
+    /// Color the depth by how much resting size was added or pulled, instead of its size
+    #[serde(default)]
+    pub depth_delta: bool,
+    #[serde(default)]
+    pub palette: DepthPalette,
+    #[serde(default)]
+    pub intensity_scale: IntensityScale,
+    /// Order size in USD that gets the full color, the largest one in view does when `None`
+    #[serde(default)]
+    pub depth_clamp: Option<f32>,
+    /// Size weighted mid price line, smoothed over this many columns, hidden when `None`
+    #[serde(default)]
+    pub micro_price: Option<u16>,
+    /// Percent from the mid price the order book imbalance indicator sums the depth within
+    #[serde(default = "default_imbalance_range")]
+    pub imbalance_range: f32,
+    /// Lines at the session open, previous day close and the day's high and low
+    #[serde(default)]
+    pub day_levels: bool,
+}
+
+fn default_imbalance_range() -> f32 {
+    DEFAULT_IMBALANCE_RANGE
+}
+
+impl Config {
+    /// Share of the full color a resting order gets, from 0 to 1
+    pub fn depth_intensity(
+        &self,
+        price: f32,
+        qty: f32,
+        max_depth_qty: f32,
+        market_type: MarketKind,
+    ) -> f32 {
+        match self.depth_clamp {
+            Some(clamp) => {
+                let order_size = match market_type {
+                    MarketKind::InversePerps => qty,
+                    _ => price * qty,
+                };
+                self.intensity_scale.apply(order_size, clamp)
+            }
+            None => self.intensity_scale.apply(qty, max_depth_qty),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum IntensityScale {
+    #[default]
+    Linear,
+    /// Brings out the smaller orders next to a few large ones
+    Log,
+}
+
+impl IntensityScale {
+    pub const ALL: [IntensityScale; 2] = [IntensityScale::Linear, IntensityScale::Log];
+
+    pub fn apply(&self, value: f32, max: f32) -> f32 {
+        if max <= 0.0 {
+            return 0.0;
+        }
+
+        let intensity = match self {
+            IntensityScale::Linear => value / max,
+            IntensityScale::Log => value.max(0.0).ln_1p() / max.ln_1p(),
+        };
+        intensity.clamp(0.0, 1.0)
+    }
+}
+
+impl std::fmt::Display for IntensityScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntensityScale::Linear => write!(f, "Linear"),
+            IntensityScale::Log => write!(f, "Log"),
+        }
+    }
+}
+
+const VIRIDIS: [(u8, u8, u8); 5] = [
+    (68, 1, 84),
+    (59, 82, 139),
+    (33, 145, 140),
+    (94, 201, 98),
+    (253, 231, 37),
+];
+
+const INFERNO: [(u8, u8, u8); 5] = [
+    (0, 0, 4),
+    (87, 16, 110),
+    (188, 55, 84),
+    (249, 142, 9),
+    (252, 255, 164),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum DepthPalette {
+    /// Bids and asks in the theme's colors, their size sets the opacity
+    #[default]
+    Sides,
+    Viridis,
+    Inferno,
+    /// From the first color at the smallest sizes to the second at the largest, for both sides
+    TwoColor(OverlayColor, OverlayColor),
+}
+
+impl DepthPalette {
+    pub const ALL: [DepthPalette; 4] = [
+        DepthPalette::Sides,
+        DepthPalette::Viridis,
+        DepthPalette::Inferno,
+        DepthPalette::TwoColor(OverlayColor::Blue, OverlayColor::Yellow),
+    ];
+
+    /// Color at `intensity` along the gradient, `None` for the side colors the theme decides
+    pub fn gradient_color(&self, intensity: f32) -> Option<iced_core::Color> {
+        let (r, g, b) = match self {
+            DepthPalette::Sides => return None,
+            DepthPalette::Viridis => interpolate_stops(&VIRIDIS, intensity),
+            DepthPalette::Inferno => interpolate_stops(&INFERNO, intensity),
+            DepthPalette::TwoColor(low, high) => {
+                interpolate_stops(&[low.rgb8(), high.rgb8()], intensity)
+            }
+        };
+
+        Some(iced_core::Color::from_rgb(r, g, b))
+    }
+}
+
+impl std::fmt::Display for DepthPalette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DepthPalette::Sides => write!(f, "Bid/Ask"),
+            DepthPalette::Viridis => write!(f, "Viridis"),
+            DepthPalette::Inferno => write!(f, "Inferno"),
+            DepthPalette::TwoColor(_, _) => write!(f, "Two color"),
+        }
+    }
+}
+
+/// Linear blend between the two stops around `t`, as rgb from 0 to 1
+fn interpolate_stops(stops: &[(u8, u8, u8)], t: f32) -> (f32, f32, f32) {
+    let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+    let index = (position.floor() as usize).min(stops.len() - 2);
+    let frac = position - index as f32;
+
+    let (from, to) = (stops[index], stops[index + 1]);
+    let blend = |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * frac) / 255.0;
+
+    (
+        blend(from.0, to.0),
+        blend(from.1, to.1),
+        blend(from.2, to.2),
+    )
 }
 
 impl Default for Config {
@@ -25,6 +197,14 @@ impl Default for Config {
             order_size_filter: 0.0,
             trade_size_scale: Some(100),
             coalescing: Some(CoalesceKind::Average(0.15)),
+            liquidation_scale: Some(100),
+            depth_delta: false,
+            palette: DepthPalette::default(),
+            intensity_scale: IntensityScale::default(),
+            depth_clamp: None,
+            micro_price: None,
+            imbalance_range: DEFAULT_IMBALANCE_RANGE,
+            day_levels: false,
         }
     }
 }
@@ -113,6 +293,15 @@ pub struct OrderRun {
 }
 
 impl OrderRun {
+    pub fn new(start_time: u64, until_time: u64, qty: f32, is_bid: bool) -> Self {
+        Self {
+            start_time,
+            until_time,
+            qty: OrderedFloat(qty),
+            is_bid,
+        }
+    }
+
     pub fn qty(&self) -> f32 {
         self.qty.into_inner()
     }
@@ -129,6 +318,15 @@ impl OrderRun {
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct HistoricalDepth {
     price_levels: BTreeMap<OrderedFloat<f32>, Vec<OrderRun>>,
+    /// Runs older than [`FULL_RES_COLUMNS`], merged into wider price bins and time buckets
+    /// and keyed by the center of their bin
+    compressed_levels: BTreeMap<OrderedFloat<f32>, Vec<OrderRun>>,
+    compressed_until: u64,
+    /// Resting size and side of each level at the previous depth update
+    prev_frame: HashMap<OrderedFloat<f32>, (f32, bool)>,
+    /// Net change of resting size at each level, summed over each column. Only kept for the
+    /// full resolution span, pulls are negative
+    depth_deltas: BTreeMap<u64, BTreeMap<OrderedFloat<f32>, f32>>,
     aggr_time: u64,
     tick_size: f32,
     min_order_qty: f32,
@@ -138,9 +336,13 @@ impl HistoricalDepth {
     pub fn new(min_order_qty: f32, tick_size: f32, basis: Basis) -> Self {
         Self {
             price_levels: BTreeMap::new(),
+            compressed_levels: BTreeMap::new(),
+            compressed_until: 0,
+            prev_frame: HashMap::new(),
+            depth_deltas: BTreeMap::new(),
             aggr_time: match basis {
                 Basis::Time(interval) => interval.into(),
-                Basis::Tick(_) => unimplemented!(),
+                Basis::Tick(_) | Basis::Range(_) => unimplemented!(),
             },
             tick_size,
             min_order_qty,
@@ -149,13 +351,109 @@ impl HistoricalDepth {
 
     pub fn insert_latest_depth(&mut self, depth: &Depth, time: u64) {
         let tick_size = self.tick_size;
+        let mut frame = HashMap::new();
 
-        self.process_side(&depth.bids, time, true, |price| {
+        self.process_side(&depth.bids, time, true, &mut frame, |price| {
             ((price * (1.0 / tick_size)).floor()) * tick_size
         });
-        self.process_side(&depth.asks, time, false, |price| {
+        self.process_side(&depth.asks, time, false, &mut frame, |price| {
             ((price * (1.0 / tick_size)).ceil()) * tick_size
         });
+
+        self.record_deltas(frame, time);
+
+        let full_res_span = self.aggr_time * FULL_RES_COLUMNS;
+        if time > self.compressed_until + full_res_span + self.aggr_time * COMPRESS_BATCH_COLUMNS {
+            self.compress_before(time - full_res_span);
+        }
+    }
+
+    /// Moves the runs that ended before `before` into the compressed tier. Within a bin, runs
+    /// of the same side starting in the same time bucket become one, sized by the largest.
+    fn compress_before(&mut self, before: u64) {
+        let bin_size = self.tick_size * f32::from(COMPRESSED_PRICE_TICKS);
+        let bin_center_offset = self.tick_size * f32::from(COMPRESSED_PRICE_TICKS - 1) / 2.0;
+
+        let mut binned: BTreeMap<OrderedFloat<f32>, Vec<OrderRun>> = BTreeMap::new();
+
+        for (price, runs) in &mut self.price_levels {
+            let expired = runs
+                .iter()
+                .take_while(|run| run.until_time < before)
+                .count();
+
+            if expired > 0 {
+                let bin = (price.into_inner() / bin_size).floor() * bin_size + bin_center_offset;
+                binned
+                    .entry(OrderedFloat(bin))
+                    .or_default()
+                    .extend(runs.drain(..expired));
+            }
+        }
+
+        self.price_levels.retain(|_, runs| !runs.is_empty());
+
+        let bucket_span = self.aggr_time * COMPRESSED_TIME_COLUMNS;
+
+        for (bin, mut runs) in binned {
+            runs.sort_by_key(|run| run.start_time);
+
+            let compressed = self.compressed_levels.entry(bin).or_default();
+
+            for run in runs {
+                match compressed.last_mut() {
+                    Some(last)
+                        if last.is_bid == run.is_bid
+                            && run.start_time < last.start_time + bucket_span =>
+                    {
+                        last.until_time = last.until_time.max(run.until_time);
+                        last.qty = last.qty.max(run.qty);
+                    }
+                    _ => compressed.push(run),
+                }
+            }
+        }
+
+        self.compressed_until = before;
+        self.depth_deltas = self.depth_deltas.split_off(&before);
+    }
+
+    /// Adds the change in resting size of each level since the previous update to its
+    /// column. Levels gone from the book count as pulled, as long as they're still within
+    /// the price range the book covers, otherwise price just moved away from them
+    fn record_deltas(&mut self, frame: HashMap<OrderedFloat<f32>, (f32, bool)>, time: u64) {
+        if self.prev_frame.is_empty() {
+            self.prev_frame = frame;
+            return;
+        }
+
+        let (lowest, highest) = frame
+            .keys()
+            .fold((f32::MAX, f32::MIN), |(low, high), price| {
+                (low.min(price.0), high.max(price.0))
+            });
+
+        let column = time - time % self.aggr_time;
+        let deltas = self.depth_deltas.entry(column).or_default();
+
+        for (price, (qty, is_bid)) in &frame {
+            let delta = match self.prev_frame.get(price) {
+                Some((prev_qty, prev_is_bid)) if prev_is_bid == is_bid => qty - prev_qty,
+                _ => *qty,
+            };
+
+            if delta != 0.0 {
+                *deltas.entry(*price).or_default() += delta;
+            }
+        }
+
+        for (price, (prev_qty, _)) in &self.prev_frame {
+            if !frame.contains_key(price) && (lowest..=highest).contains(&price.0) {
+                *deltas.entry(*price).or_default() -= prev_qty;
+            }
+        }
+
+        self.prev_frame = frame;
     }
 
     fn process_side<F>(
@@ -163,6 +461,7 @@ impl HistoricalDepth {
         side: &BTreeMap<OrderedFloat<f32>, f32>,
         time: u64,
         is_bid: bool,
+        frame: &mut HashMap<OrderedFloat<f32>, (f32, bool)>,
         round_price: F,
     ) where
         F: Fn(f32) -> f32,
@@ -178,6 +477,7 @@ impl HistoricalDepth {
             } else {
                 if let Some(price) = current_price {
                     self.update_price_level(time, price, current_qty, is_bid);
+                    frame.insert(OrderedFloat(price), (current_qty, is_bid));
                 }
                 current_price = Some(rounded_price);
                 current_qty = *qty;
@@ -186,6 +486,7 @@ impl HistoricalDepth {
 
         if let Some(price) = current_price {
             self.update_price_level(time, price, current_qty, is_bid);
+            frame.insert(OrderedFloat(price), (current_qty, is_bid));
         }
     }
 
@@ -268,6 +569,23 @@ impl HistoricalDepth {
             })
     }
 
+    /// Runs of the compressed tier overlapping the given range, each spanning
+    /// [`COMPRESSED_PRICE_TICKS`] price levels around its key
+    pub fn iter_compressed(
+        &self,
+        earliest: u64,
+        latest: u64,
+        highest: f32,
+        lowest: f32,
+    ) -> impl Iterator<Item = (&OrderedFloat<f32>, &OrderRun)> {
+        self.compressed_levels
+            .range(OrderedFloat(lowest)..=OrderedFloat(highest))
+            .flat_map(move |(price, runs)| {
+                runs.iter()
+                    .filter_map(move |run| run.with_range(earliest, latest).map(|run| (price, run)))
+            })
+    }
+
     pub fn latest_order_runs(
         &self,
         highest: f32,
@@ -283,12 +601,62 @@ impl HistoricalDepth {
             })
     }
 
+    /// Runs that overlap the given time range, grouped by price level
+    pub fn runs_in_range(&self, earliest: u64, latest: u64) -> Vec<(f32, Vec<OrderRun>)> {
+        self.price_levels
+            .iter()
+            .filter_map(|(price, runs)| {
+                let runs = runs
+                    .iter()
+                    .filter_map(|run| run.with_range(earliest, latest).copied())
+                    .collect::<Vec<_>>();
+
+                (!runs.is_empty()).then_some((price.into_inner(), runs))
+            })
+            .collect()
+    }
+
+    pub fn insert_runs(&mut self, price: f32, runs: Vec<OrderRun>) {
+        self.price_levels
+            .entry(OrderedFloat(price))
+            .or_default()
+            .extend(runs);
+    }
+
     pub fn cleanup_old_price_levels(&mut self, oldest_time: u64) {
         self.price_levels.iter_mut().for_each(|(_, runs)| {
             runs.retain(|run| run.until_time >= oldest_time);
         });
 
         self.price_levels.retain(|_, runs| !runs.is_empty());
+
+        self.compressed_levels.iter_mut().for_each(|(_, runs)| {
+            runs.retain(|run| run.until_time >= oldest_time);
+        });
+
+        self.compressed_levels.retain(|_, runs| !runs.is_empty());
+
+        self.depth_deltas = self.depth_deltas.split_off(&oldest_time);
+    }
+
+    /// Net resting size change of each level per column overlapping the range, as
+    /// `(column, price, delta)`
+    pub fn deltas_in_range(
+        &self,
+        earliest: u64,
+        latest: u64,
+        highest: f32,
+        lowest: f32,
+    ) -> impl Iterator<Item = (u64, f32, f32)> {
+        let from = earliest.saturating_sub(self.aggr_time);
+
+        self.depth_deltas
+            .range(from..=latest)
+            .flat_map(move |(column, levels)| {
+                levels
+                    .range(OrderedFloat(lowest)..=OrderedFloat(highest))
+                    .map(move |(price, delta)| (*column, price.into_inner(), *delta))
+            })
     }
 
     pub fn coalesced_runs(
@@ -487,6 +855,18 @@ impl HistoricalDepth {
                     });
             });
 
+        self.iter_compressed(earliest, latest, highest, lowest)
+            .filter(|(price, run)| {
+                let order_size = match market_type {
+                    MarketKind::InversePerps => run.qty(),
+                    _ => ***price * run.qty(),
+                };
+                order_size > order_size_filter
+            })
+            .for_each(|(_, run)| {
+                max_depth_qty = max_depth_qty.max(run.qty());
+            });
+
         max_depth_qty
     }
 }
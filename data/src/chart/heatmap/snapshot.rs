@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use exchange::TickerInfo;
+
+use super::{GroupedTrade, HeatmapDataPoint, OrderRun};
+use crate::{InternalError, chart::Basis, data_path};
+
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// A frozen copy of a heatmap's time window, stored as plain tuples to keep the file small
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Snapshot {
+    pub ticker_info: TickerInfo,
+    pub basis: Basis,
+    pub tick_size: f32,
+    pub created_at: u64,
+    /// `(price, [(start_time, until_time, qty, is_bid)])`
+    depth: Vec<(f32, Vec<(u64, u64, f32, bool)>)>,
+    /// `(time, [(price, qty, is_sell)])`
+    trades: Vec<(u64, Vec<(f32, f32, bool)>)>,
+}
+
+impl Snapshot {
+    pub fn new<'a>(
+        ticker_info: TickerInfo,
+        basis: Basis,
+        tick_size: f32,
+        depth: Vec<(f32, Vec<OrderRun>)>,
+        trades: impl Iterator<Item = (u64, &'a HeatmapDataPoint)>,
+    ) -> Self {
+        Self {
+            ticker_info,
+            basis,
+            tick_size,
+            created_at: chrono::Utc::now().timestamp_millis() as u64,
+            depth: depth
+                .into_iter()
+                .map(|(price, runs)| {
+                    let runs = runs
+                        .iter()
+                        .map(|run| (run.start_time, run.until_time, run.qty(), run.is_bid))
+                        .collect();
+                    (price, runs)
+                })
+                .collect(),
+            trades: trades
+                .map(|(time, dp)| {
+                    let trades = dp
+                        .grouped_trades
+                        .iter()
+                        .map(|t| (t.price, t.qty, t.is_sell))
+                        .collect();
+                    (time, trades)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn depth(&self) -> impl Iterator<Item = (f32, Vec<OrderRun>)> {
+        self.depth.iter().map(|(price, runs)| {
+            let runs = runs
+                .iter()
+                .map(|&(start_time, until_time, qty, is_bid)| {
+                    OrderRun::new(start_time, until_time, qty, is_bid)
+                })
+                .collect();
+            (*price, runs)
+        })
+    }
+
+    pub fn trades(&self) -> impl Iterator<Item = (u64, HeatmapDataPoint)> {
+        self.trades.iter().map(|(time, trades)| {
+            let mut buy_sell = (0.0, 0.0);
+
+            let grouped_trades = trades
+                .iter()
+                .map(|&(price, qty, is_sell)| {
+                    if is_sell {
+                        buy_sell.1 += qty;
+                    } else {
+                        buy_sell.0 += qty;
+                    }
+                    GroupedTrade {
+                        is_sell,
+                        price,
+                        qty,
+                    }
+                })
+                .collect();
+
+            (
+                *time,
+                HeatmapDataPoint {
+                    grouped_trades,
+                    buy_sell,
+                },
+            )
+        })
+    }
+
+    pub fn time_range(&self) -> Option<(u64, u64)> {
+        let earliest = self.trades.first().map(|(time, _)| *time)?;
+        let latest = self.trades.last().map(|(time, _)| *time)?;
+        Some((earliest, latest))
+    }
+
+    /// Writes the snapshot into the snapshots folder, returning the file name it can be reopened with
+    pub fn save(&self) -> Result<String, InternalError> {
+        let folder = data_path(Some(SNAPSHOTS_DIR));
+        std::fs::create_dir_all(&folder).map_err(|err| {
+            InternalError::Snapshot(format!("Failed to create snapshots folder: {err}"))
+        })?;
+
+        let created_at = chrono::DateTime::from_timestamp_millis(self.created_at as i64)
+            .unwrap_or_default()
+            .format("%Y%m%d-%H%M%S");
+        let file_name = format!(
+            "heatmap-{}-{created_at}.json",
+            self.ticker_info.ticker.display_symbol_and_type().0
+        );
+
+        let json = serde_json::to_string(self).map_err(|err| {
+            InternalError::Snapshot(format!("Failed to serialize snapshot: {err}"))
+        })?;
+
+        std::fs::write(folder.join(&file_name), json)
+            .map_err(|err| InternalError::Snapshot(format!("Failed to write snapshot: {err}")))?;
+
+        Ok(file_name)
+    }
+
+    pub fn load(file_name: &str) -> Result<Self, InternalError> {
+        let path = snapshot_path(file_name);
+
+        let json = std::fs::read_to_string(&path).map_err(|err| {
+            InternalError::Snapshot(format!("Failed to read '{}': {err}", path.display()))
+        })?;
+
+        serde_json::from_str(&json).map_err(|err| {
+            InternalError::Snapshot(format!("Invalid snapshot '{file_name}': {err}"))
+        })
+    }
+}
+
+/// Snapshot file names in the snapshots folder, newest first
+pub fn list() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(data_path(Some(SNAPSHOTS_DIR))) else {
+        return vec![];
+    };
+
+    let mut snapshots = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+            name.ends_with(".json").then_some((modified, name))
+        })
+        .collect::<Vec<_>>();
+
+    snapshots.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+    snapshots.into_iter().map(|(_, name)| name).collect()
+}
+
+fn snapshot_path(file_name: &str) -> PathBuf {
+    data_path(Some(SNAPSHOTS_DIR)).join(file_name)
+}
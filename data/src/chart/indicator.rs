@@ -13,6 +13,9 @@ pub trait Indicator: PartialEq + Display + 'static {
 pub enum KlineIndicator {
     Volume,
     OpenInterest,
+    RelativeVolume,
+    Rsi,
+    Ema,
 }
 
 impl Indicator for KlineIndicator {
@@ -25,8 +28,22 @@ impl Indicator for KlineIndicator {
 }
 
 impl KlineIndicator {
-    const SPOT: [KlineIndicator; 1] = [KlineIndicator::Volume];
-    const PERPS: [KlineIndicator; 2] = [KlineIndicator::Volume, KlineIndicator::OpenInterest];
+    /// Every indicator a kline chart of any market can show
+    pub const ALL: [KlineIndicator; 5] = Self::PERPS;
+
+    const SPOT: [KlineIndicator; 4] = [
+        KlineIndicator::Volume,
+        KlineIndicator::RelativeVolume,
+        KlineIndicator::Rsi,
+        KlineIndicator::Ema,
+    ];
+    const PERPS: [KlineIndicator; 5] = [
+        KlineIndicator::Volume,
+        KlineIndicator::OpenInterest,
+        KlineIndicator::RelativeVolume,
+        KlineIndicator::Rsi,
+        KlineIndicator::Ema,
+    ];
 }
 
 impl Display for KlineIndicator {
@@ -34,6 +51,9 @@ impl Display for KlineIndicator {
         match self {
             KlineIndicator::Volume => write!(f, "Volume"),
             KlineIndicator::OpenInterest => write!(f, "Open Interest"),
+            KlineIndicator::RelativeVolume => write!(f, "Relative Volume"),
+            KlineIndicator::Rsi => write!(f, "RSI"),
+            KlineIndicator::Ema => write!(f, "EMA"),
         }
     }
 }
@@ -41,6 +61,8 @@ impl Display for KlineIndicator {
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, Eq, Hash)]
 pub enum HeatmapIndicator {
     Volume,
+    /// Bid to ask size ratio of the resting depth near the mid price
+    Imbalance,
 }
 
 impl Indicator for HeatmapIndicator {
@@ -53,14 +75,15 @@ impl Indicator for HeatmapIndicator {
 }
 
 impl HeatmapIndicator {
-    const SPOT: [HeatmapIndicator; 1] = [HeatmapIndicator::Volume];
-    const PERPS: [HeatmapIndicator; 1] = [HeatmapIndicator::Volume];
+    const SPOT: [HeatmapIndicator; 2] = [HeatmapIndicator::Volume, HeatmapIndicator::Imbalance];
+    const PERPS: [HeatmapIndicator; 2] = [HeatmapIndicator::Volume, HeatmapIndicator::Imbalance];
 }
 
 impl Display for HeatmapIndicator {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             HeatmapIndicator::Volume => write!(f, "Volume"),
+            HeatmapIndicator::Imbalance => write!(f, "Imbalance"),
         }
     }
 }
@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use exchange::{Kline, Trade};
+use exchange::{Kline, Trade, adapter::MarketKind};
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
@@ -139,6 +139,8 @@ impl GroupedTrades {
 pub struct KlineTrades {
     pub trades: HashMap<OrderedFloat<f32>, GroupedTrades>,
     pub poc: Option<PointOfControl>,
+    /// Running delta of the trades in the order they came in, as (current, highest, lowest)
+    delta: (f32, f32, f32),
 }
 
 impl KlineTrades {
@@ -146,6 +148,22 @@ impl KlineTrades {
         Self {
             trades: HashMap::new(),
             poc: None,
+            delta: (0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Candle of the bar's order flow, opening at zero and closing at its delta, with wicks
+    /// at the most positive and negative delta it reached along the way
+    pub fn delta_kline(&self, time: u64) -> Kline {
+        let (close, high, low) = self.delta;
+
+        Kline {
+            time,
+            open: 0.0,
+            high,
+            low,
+            close,
+            volume: (0.0, 0.0),
         }
     }
 
@@ -160,6 +178,11 @@ impl KlineTrades {
     pub fn add_trade_at_price_level(&mut self, trade: &Trade, tick_size: f32) {
         let price_level = OrderedFloat(round_to_tick(trade.price, tick_size));
 
+        let (delta, high, low) = &mut self.delta;
+        *delta += if trade.is_sell { -trade.qty } else { trade.qty };
+        *high = high.max(*delta);
+        *low = low.min(*delta);
+
         if let Some(group) = self.trades.get_mut(&price_level) {
             group.add_trade(trade);
         } else {
@@ -213,9 +236,55 @@ impl KlineTrades {
         self.poc.map(|poc| poc.price)
     }
 
+    /// Highest and lowest price levels of the bar, each only when both buyers and sellers
+    /// traded there, the auction didn't run out at that extreme
+    pub fn unfinished_auctions(&self) -> (Option<f32>, Option<f32>) {
+        let is_unfinished = |price: &OrderedFloat<f32>| {
+            self.trades
+                .get(price)
+                .is_some_and(|group| group.buy_qty > 0.0 && group.sell_qty > 0.0)
+        };
+
+        let high = self
+            .trades
+            .keys()
+            .max()
+            .filter(|price| is_unfinished(price));
+        let low = self
+            .trades
+            .keys()
+            .min()
+            .filter(|price| is_unfinished(price));
+
+        (high.map(|p| p.0), low.map(|p| p.0))
+    }
+
+    /// Price levels with at least `threshold` percent of the bar's average volume per level
+    pub fn high_volume_nodes(&self, threshold: usize) -> Vec<f32> {
+        // a couple of levels don't have an average worth standing out from
+        if self.trades.len() < 3 {
+            return vec![];
+        }
+
+        let average = self
+            .trades
+            .values()
+            .map(GroupedTrades::total_qty)
+            .sum::<f32>()
+            / self.trades.len() as f32;
+        let min_qty = average * threshold as f32 / 100.0;
+
+        self.trades
+            .iter()
+            .filter(|(_, group)| group.total_qty() >= min_qty)
+            .map(|(price, _)| price.0)
+            .collect()
+    }
+
     pub fn clear(&mut self) {
         self.trades.clear();
         self.poc = None;
+        self.delta = (0.0, 0.0, 0.0);
     }
 }
 
@@ -227,55 +296,167 @@ pub enum KlineChartKind {
         clusters: ClusterKind,
         studies: Vec<FootprintStudy>,
     },
+    /// Market profile of each daily session, one letter per 30 minute bracket at every price
+    /// level the bracket traded at
+    Tpo,
+    /// Candles of each bar's buy minus sell volume instead of its price, built from the same
+    /// trades as footprints
+    DeltaCandles,
+    /// Candles of two tickers' klines combined into one series, the second leg being kept in
+    /// the pane settings
+    Spread(SpreadMode),
 }
 
 impl KlineChartKind {
     pub fn min_scaling(&self) -> f32 {
         match self {
-            KlineChartKind::Footprint { .. } => 0.4,
-            KlineChartKind::Candles => 0.6,
+            KlineChartKind::Footprint { .. } | KlineChartKind::Tpo => 0.4,
+            KlineChartKind::Candles | KlineChartKind::DeltaCandles | KlineChartKind::Spread(_) => {
+                0.6
+            }
         }
     }
 
     pub fn max_scaling(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 1.2,
-            KlineChartKind::Candles => 2.5,
+            KlineChartKind::Candles
+            | KlineChartKind::Tpo
+            | KlineChartKind::DeltaCandles
+            | KlineChartKind::Spread(_) => 2.5,
         }
     }
 
     pub fn max_cell_width(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 360.0,
-            KlineChartKind::Candles => 16.0,
+            KlineChartKind::Candles | KlineChartKind::DeltaCandles | KlineChartKind::Spread(_) => {
+                16.0
+            }
+            KlineChartKind::Tpo => 40.0,
         }
     }
 
     pub fn min_cell_width(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 80.0,
-            KlineChartKind::Candles => 1.0,
+            KlineChartKind::Candles | KlineChartKind::DeltaCandles | KlineChartKind::Spread(_) => {
+                1.0
+            }
+            KlineChartKind::Tpo => 2.0,
         }
     }
 
     pub fn max_cell_height(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 90.0,
-            KlineChartKind::Candles => 8.0,
+            KlineChartKind::Candles | KlineChartKind::DeltaCandles | KlineChartKind::Spread(_) => {
+                8.0
+            }
+            KlineChartKind::Tpo => 40.0,
         }
     }
 
     pub fn min_cell_height(&self) -> f32 {
         match self {
-            KlineChartKind::Footprint { .. } => 1.0,
-            KlineChartKind::Candles => 0.001,
+            KlineChartKind::Footprint { .. } | KlineChartKind::Tpo => 1.0,
+            KlineChartKind::Candles | KlineChartKind::DeltaCandles | KlineChartKind::Spread(_) => {
+                0.001
+            }
         }
     }
 
     pub fn default_cell_width(&self) -> f32 {
         match self {
             KlineChartKind::Footprint { .. } => 80.0,
-            KlineChartKind::Candles => 4.0,
+            KlineChartKind::Candles | KlineChartKind::DeltaCandles | KlineChartKind::Spread(_) => {
+                4.0
+            }
+            KlineChartKind::Tpo => 12.0,
+        }
+    }
+
+    /// Kinds drawn from live trades, which need a trade stream alongside the klines
+    pub fn is_order_flow(&self) -> bool {
+        matches!(
+            self,
+            KlineChartKind::Footprint { .. } | KlineChartKind::DeltaCandles
+        )
+    }
+}
+
+/// How the klines of a spread chart's two legs are combined
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum SpreadMode {
+    #[default]
+    Difference,
+    Ratio,
+    /// Percent the first leg trades above the second, the basis premium when it's a perp over
+    /// its spot market
+    Premium,
+}
+
+impl SpreadMode {
+    pub const ALL: [SpreadMode; 3] = [
+        SpreadMode::Difference,
+        SpreadMode::Ratio,
+        SpreadMode::Premium,
+    ];
+
+    /// Perps spread against spot open on their premium, anything else on the plain difference
+    pub fn default_for(first: MarketKind, second: MarketKind) -> Self {
+        match (first, second) {
+            (MarketKind::LinearPerps | MarketKind::InversePerps, MarketKind::Spot) => {
+                SpreadMode::Premium
+            }
+            _ => SpreadMode::Difference,
+        }
+    }
+
+    /// Tick size the combined series is plotted at, ratios and premiums move in steps far
+    /// smaller than the legs' own ticks
+    pub fn tick_size(self, leg_tick_size: f32) -> f32 {
+        match self {
+            SpreadMode::Difference => leg_tick_size,
+            SpreadMode::Ratio => 0.00001,
+            SpreadMode::Premium => 0.001,
+        }
+    }
+
+    fn combine_price(self, first: f32, second: f32) -> Option<f32> {
+        match self {
+            SpreadMode::Difference => Some(first - second),
+            SpreadMode::Ratio => (second > 0.0).then(|| first / second),
+            SpreadMode::Premium => (second > 0.0).then(|| (first / second - 1.0) * 100.0),
+        }
+    }
+
+    /// The spread bar of two legs' klines of the same time, carrying the first leg's volume.
+    /// Legs rarely print their extremes together, so high and low pair up the legs' own and
+    /// only get widened to contain the open and close.
+    pub fn combine(self, first: &Kline, second: &Kline) -> Option<Kline> {
+        let open = self.combine_price(first.open, second.open)?;
+        let close = self.combine_price(first.close, second.close)?;
+        let highs = self.combine_price(first.high, second.high)?;
+        let lows = self.combine_price(first.low, second.low)?;
+
+        Some(Kline {
+            time: first.time,
+            open,
+            high: highs.max(lows).max(open).max(close),
+            low: lows.min(highs).min(open).min(close),
+            close,
+            volume: first.volume,
+        })
+    }
+}
+
+impl std::fmt::Display for SpreadMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpreadMode::Difference => write!(f, "Difference"),
+            SpreadMode::Ratio => write!(f, "Ratio"),
+            SpreadMode::Premium => write!(f, "Premium %"),
         }
     }
 }
@@ -306,8 +487,164 @@ impl std::fmt::Display for ClusterKind {
     }
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Deserialize, Serialize)]
-pub struct Config {}
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Marker size scaling of liquidations, hidden when `None`
+    pub liquidation_scale: Option<i32>,
+    /// Thin bar under each candle splitting its volume into bought and sold
+    pub volume_ratio_strip: bool,
+    /// Fixed slots so the config stays `Copy`, empty ones are `None`
+    pub moving_averages: [Option<MovingAverage>; MAX_MOVING_AVERAGES],
+    /// Anomalies marked over the bars
+    pub anomalies: super::anomaly::Detectors,
+    /// Time left until the current bar closes, next to the last price label
+    pub candle_countdown: bool,
+    /// Strip summing up the bought, sold and net volume of the day so far
+    pub session_delta: bool,
+    /// Lines at the session open, previous day close and the day's high and low
+    pub day_levels: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            liquidation_scale: Some(100),
+            volume_ratio_strip: false,
+            moving_averages: [None; MAX_MOVING_AVERAGES],
+            anomalies: super::anomaly::Detectors::default(),
+            candle_countdown: true,
+            session_delta: false,
+            day_levels: false,
+        }
+    }
+}
+
+pub const MAX_MOVING_AVERAGES: usize = 4;
+
+/// Moving average of the closes drawn over the bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MovingAverage {
+    pub kind: MaKind,
+    pub period: u16,
+    pub color: OverlayColor,
+}
+
+impl MovingAverage {
+    pub const MIN_PERIOD: u16 = 2;
+    pub const MAX_PERIOD: u16 = 400;
+
+    /// New slots cycle through the colors so overlays stay apart
+    pub fn new(slot: usize) -> Self {
+        MovingAverage {
+            kind: MaKind::Ema,
+            period: [9, 21, 50, 200][slot % 4],
+            color: OverlayColor::ALL[slot % OverlayColor::ALL.len()],
+        }
+    }
+
+    pub fn series(&self, closes: &BTreeMap<u64, f32>) -> BTreeMap<u64, f32> {
+        let period = usize::from(self.period);
+
+        match self.kind {
+            MaKind::Sma => crate::metrics::sma(closes, period),
+            MaKind::Ema => crate::metrics::ema(closes, period),
+            MaKind::Wma => crate::metrics::wma(closes, period),
+        }
+    }
+
+    /// Value at the latest bar from its trailing closes in chronological order, and the
+    /// average at the bar before it which the EMA carries on from. Without one the EMA is
+    /// seeded with the simple average, like the full series
+    pub fn next_value(&self, closes: &[f32], prev: Option<f32>) -> Option<f32> {
+        let period = usize::from(self.period);
+        let window = closes.get(closes.len().checked_sub(period)?..)?;
+        let last = *window.last()?;
+        let average = window.iter().sum::<f32>() / period as f32;
+
+        match self.kind {
+            MaKind::Sma => Some(average),
+            MaKind::Wma => Some(crate::metrics::weighted_average(window)),
+            MaKind::Ema => {
+                let alpha = 2.0 / (period as f32 + 1.0);
+                Some(prev.map_or(average, |prev| alpha * last + (1.0 - alpha) * prev))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for MovingAverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.kind, self.period)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum MaKind {
+    Sma,
+    Ema,
+    Wma,
+}
+
+impl MaKind {
+    pub const ALL: [MaKind; 3] = [MaKind::Sma, MaKind::Ema, MaKind::Wma];
+}
+
+impl std::fmt::Display for MaKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaKind::Sma => write!(f, "SMA"),
+            MaKind::Ema => write!(f, "EMA"),
+            MaKind::Wma => write!(f, "WMA"),
+        }
+    }
+}
+
+/// Fixed line colors for overlays, readable on both light and dark themes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OverlayColor {
+    Yellow,
+    Blue,
+    Purple,
+    Orange,
+    Teal,
+    Pink,
+}
+
+impl OverlayColor {
+    pub const ALL: [OverlayColor; 6] = [
+        OverlayColor::Yellow,
+        OverlayColor::Blue,
+        OverlayColor::Purple,
+        OverlayColor::Orange,
+        OverlayColor::Teal,
+        OverlayColor::Pink,
+    ];
+
+    pub fn rgb8(&self) -> (u8, u8, u8) {
+        match self {
+            OverlayColor::Yellow => (230, 194, 41),
+            OverlayColor::Blue => (66, 135, 245),
+            OverlayColor::Purple => (163, 102, 230),
+            OverlayColor::Orange => (240, 128, 48),
+            OverlayColor::Teal => (38, 179, 163),
+            OverlayColor::Pink => (230, 92, 158),
+        }
+    }
+}
+
+impl std::fmt::Display for OverlayColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverlayColor::Yellow => write!(f, "Yellow"),
+            OverlayColor::Blue => write!(f, "Blue"),
+            OverlayColor::Purple => write!(f, "Purple"),
+            OverlayColor::Orange => write!(f, "Orange"),
+            OverlayColor::Teal => write!(f, "Teal"),
+            OverlayColor::Pink => write!(f, "Pink"),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum FootprintStudy {
@@ -319,6 +656,33 @@ pub enum FootprintStudy {
         color_scale: Option<usize>,
         ignore_zeros: bool,
     },
+    Vwap {
+        session: VwapSession,
+        bands: bool,
+    },
+    /// Volume per price level of the visible bars, drawn along the right edge
+    VolumeProfile {
+        poc: bool,
+        /// Share of the profile's volume, in percent, the VAH/VAL lines enclose
+        value_area: Option<usize>,
+    },
+    /// Moving average of closes with bands `std_dev` tenths of a standard deviation around it
+    BollingerBands {
+        period: usize,
+        std_dev: usize,
+    },
+    /// Bars making a new high or low that the cumulative delta doesn't confirm, `threshold` is
+    /// how far in percent of its range the delta has to lag
+    DeltaDivergence {
+        lookback: usize,
+        threshold: usize,
+    },
+    /// Bar extremes where both buyers and sellers traded
+    UnfinishedAuction,
+    /// Price levels of a bar with `threshold` percent of its average volume per level or more
+    HighVolumeNodes {
+        threshold: usize,
+    },
 }
 
 impl FootprintStudy {
@@ -330,18 +694,57 @@ impl FootprintStudy {
                     FootprintStudy::Imbalance { .. },
                     FootprintStudy::Imbalance { .. }
                 )
+                | (FootprintStudy::Vwap { .. }, FootprintStudy::Vwap { .. })
+                | (
+                    FootprintStudy::VolumeProfile { .. },
+                    FootprintStudy::VolumeProfile { .. }
+                )
+                | (
+                    FootprintStudy::BollingerBands { .. },
+                    FootprintStudy::BollingerBands { .. }
+                )
+                | (
+                    FootprintStudy::DeltaDivergence { .. },
+                    FootprintStudy::DeltaDivergence { .. }
+                )
+                | (
+                    FootprintStudy::UnfinishedAuction,
+                    FootprintStudy::UnfinishedAuction
+                )
+                | (
+                    FootprintStudy::HighVolumeNodes { .. },
+                    FootprintStudy::HighVolumeNodes { .. }
+                )
         )
     }
 }
 
 impl FootprintStudy {
-    pub const ALL: [FootprintStudy; 2] = [
+    pub const ALL: [FootprintStudy; 8] = [
         FootprintStudy::NPoC { lookback: 80 },
         FootprintStudy::Imbalance {
             threshold: 200,
             color_scale: Some(400),
             ignore_zeros: true,
         },
+        FootprintStudy::Vwap {
+            session: VwapSession::Daily,
+            bands: false,
+        },
+        FootprintStudy::VolumeProfile {
+            poc: true,
+            value_area: Some(70),
+        },
+        FootprintStudy::BollingerBands {
+            period: 20,
+            std_dev: 20,
+        },
+        FootprintStudy::DeltaDivergence {
+            lookback: 10,
+            threshold: 20,
+        },
+        FootprintStudy::UnfinishedAuction,
+        FootprintStudy::HighVolumeNodes { threshold: 200 },
     ];
 }
 
@@ -350,6 +753,482 @@ impl std::fmt::Display for FootprintStudy {
         match self {
             FootprintStudy::NPoC { .. } => write!(f, "Naked Point of Control"),
             FootprintStudy::Imbalance { .. } => write!(f, "Imbalance"),
+            FootprintStudy::Vwap { session, .. } => write!(f, "VWAP ({session})"),
+            FootprintStudy::VolumeProfile { .. } => write!(f, "Volume Profile (Visible range)"),
+            FootprintStudy::BollingerBands { .. } => write!(f, "Bollinger Bands"),
+            FootprintStudy::DeltaDivergence { .. } => write!(f, "Delta Divergence"),
+            FootprintStudy::UnfinishedAuction => write!(f, "Unfinished Auctions"),
+            FootprintStudy::HighVolumeNodes { .. } => write!(f, "High Volume Nodes"),
+        }
+    }
+}
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Where the VWAP accumulation starts over, sessions are in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum VwapSession {
+    #[default]
+    Daily,
+    Weekly,
+    /// Accumulates from the first loaded bar, without ever resetting
+    Anchored,
+}
+
+impl VwapSession {
+    pub const ALL: [VwapSession; 3] = [
+        VwapSession::Daily,
+        VwapSession::Weekly,
+        VwapSession::Anchored,
+    ];
+
+    fn session_start(&self, time: u64) -> u64 {
+        match self {
+            VwapSession::Daily => time - (time % DAY_MS),
+            // the unix epoch fell on a thursday, weeks start on monday
+            VwapSession::Weekly => time - ((time + 3 * DAY_MS) % (7 * DAY_MS)),
+            VwapSession::Anchored => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for VwapSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VwapSession::Daily => write!(f, "Daily"),
+            VwapSession::Weekly => write!(f, "Weekly"),
+            VwapSession::Anchored => write!(f, "Anchored"),
+        }
+    }
+}
+
+/// Bought and sold volume of the daily session the last of the klines falls in, klines
+/// without a taker side split are left out
+pub fn session_volume<'a>(klines: impl DoubleEndedIterator<Item = &'a Kline>) -> (f32, f32) {
+    let mut session_start = None;
+    let (mut buy_volume, mut sell_volume) = (0.0, 0.0);
+
+    for kline in klines.rev() {
+        let start =
+            *session_start.get_or_insert_with(|| VwapSession::Daily.session_start(kline.time));
+        if kline.time < start {
+            break;
+        }
+
+        let (buy, sell) = kline.volume;
+        if buy != -1.0 {
+            buy_volume += buy;
+            sell_volume += sell;
+        }
+    }
+
+    (buy_volume, sell_volume)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct VwapPoint {
+    pub vwap: f32,
+    /// Volume weighted standard deviation of prices around the VWAP
+    pub std_dev: f32,
+    /// First bar of a new session, its line shouldn't connect to the previous bar
+    pub is_session_start: bool,
+}
+
+/// Running VWAP at each bar, keyed the same as the bars it's given in chronological order.
+///
+/// Volume at each price level of the footprint is used when trades were aggregated into it,
+/// otherwise the bar's whole volume gets placed at its typical price.
+pub fn vwap_series<'a>(
+    bars: impl Iterator<Item = (u64, &'a Kline, &'a KlineTrades)>,
+    session: VwapSession,
+) -> Vec<(u64, VwapPoint)> {
+    let mut series = vec![];
+
+    let mut current_session = None;
+    let (mut sum_qty, mut sum_pq, mut sum_p2q) = (0.0_f64, 0.0_f64, 0.0_f64);
+
+    for (key, kline, footprint) in bars {
+        let session_start = session.session_start(kline.time);
+        let is_session_start = current_session != Some(session_start);
+
+        if is_session_start {
+            current_session = Some(session_start);
+            (sum_qty, sum_pq, sum_p2q) = (0.0, 0.0, 0.0);
+        }
+
+        let mut add = |price: f32, qty: f32| {
+            let (price, qty) = (f64::from(price), f64::from(qty));
+            sum_qty += qty;
+            sum_pq += price * qty;
+            sum_p2q += price * price * qty;
+        };
+
+        if footprint.trades.is_empty() {
+            let typical_price = (kline.high + kline.low + kline.close) / 3.0;
+            add(
+                typical_price,
+                crate::metrics::total_volume(kline.volume.0, kline.volume.1),
+            );
+        } else {
+            for (price, group) in &footprint.trades {
+                add(price.0, group.buy_qty + group.sell_qty);
+            }
+        }
+
+        if sum_qty <= 0.0 {
+            continue;
+        }
+
+        let vwap = sum_pq / sum_qty;
+        let variance = (sum_p2q / sum_qty - vwap * vwap).max(0.0);
+
+        series.push((
+            key,
+            VwapPoint {
+                vwap: vwap as f32,
+                std_dev: variance.sqrt() as f32,
+                is_session_start,
+            },
+        ));
+    }
+
+    series
+}
+
+/// Buy and sell volume per price level, summed over a range of bars
+#[derive(Debug, Default)]
+pub struct VolumeProfile {
+    pub levels: BTreeMap<OrderedFloat<f32>, (f32, f32)>,
+    pub max_volume: f32,
+}
+
+impl VolumeProfile {
+    pub fn from_footprints<'a>(footprints: impl Iterator<Item = &'a KlineTrades>) -> Self {
+        let mut levels: BTreeMap<OrderedFloat<f32>, (f32, f32)> = BTreeMap::new();
+
+        for footprint in footprints {
+            for (price, group) in &footprint.trades {
+                let level = levels.entry(*price).or_default();
+                level.0 += group.buy_qty;
+                level.1 += group.sell_qty;
+            }
+        }
+
+        let max_volume = levels
+            .values()
+            .map(|(buy, sell)| buy + sell)
+            .fold(0.0, f32::max);
+
+        Self { levels, max_volume }
+    }
+
+    /// Adds a bar's footprint, its levels merged into ones `tick_size` apart
+    pub fn add_footprint(&mut self, footprint: &KlineTrades, tick_size: f32) {
+        for (price, group) in &footprint.trades {
+            let level = self
+                .levels
+                .entry(OrderedFloat(round_to_tick(price.0, tick_size)))
+                .or_default();
+            level.0 += group.buy_qty;
+            level.1 += group.sell_qty;
+        }
+
+        self.update_max_volume();
+    }
+
+    /// Adds a bar without trades, its volume spread evenly over the levels it traded through
+    pub fn add_kline(&mut self, kline: &Kline, tick_size: f32) {
+        let low = round_to_tick(kline.low, tick_size);
+        let high = round_to_tick(kline.high, tick_size);
+
+        let count = (((high - low) / tick_size).round() as usize + 1).min(TPO_MAX_LEVELS_PER_BAR);
+
+        // without a taker side split, both sides get half
+        let (buy, sell) = if kline.volume.0 == -1.0 {
+            (kline.volume.1 / 2.0, kline.volume.1 / 2.0)
+        } else {
+            kline.volume
+        };
+
+        for index in 0..count {
+            let price = round_to_tick(low + index as f32 * tick_size, tick_size);
+            let level = self.levels.entry(OrderedFloat(price)).or_default();
+            level.0 += buy / count as f32;
+            level.1 += sell / count as f32;
+        }
+
+        self.update_max_volume();
+    }
+
+    fn update_max_volume(&mut self) {
+        self.max_volume = self
+            .levels
+            .values()
+            .map(|(buy, sell)| buy + sell)
+            .fold(0.0, f32::max);
+    }
+
+    /// Price level with the most volume
+    pub fn poc(&self) -> Option<f32> {
+        self.levels
+            .iter()
+            .max_by(|a, b| (a.1.0 + a.1.1).total_cmp(&(b.1.0 + b.1.1)))
+            .map(|(price, _)| price.0)
+    }
+
+    /// Lowest and highest price of the value area, grown from the POC towards whichever
+    /// neighbouring level has more volume until it holds `percent` of the total
+    pub fn value_area(&self, percent: usize) -> Option<(f32, f32)> {
+        let volumes = self
+            .levels
+            .iter()
+            .map(|(price, (buy, sell))| (price.0, buy + sell))
+            .collect::<Vec<_>>();
+
+        let poc_index = volumes
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.1.total_cmp(&b.1.1))
+            .map(|(index, _)| index)?;
+
+        let total = volumes.iter().map(|(_, volume)| volume).sum::<f32>();
+        let target = total * (percent.min(100) as f32 / 100.0);
+
+        let (mut low, mut high) = (poc_index, poc_index);
+        let mut enclosed = volumes[poc_index].1;
+
+        while enclosed < target && (low > 0 || high + 1 < volumes.len()) {
+            let below = low.checked_sub(1).map(|index| volumes[index].1);
+            let above = volumes.get(high + 1).map(|(_, volume)| *volume);
+
+            match (below, above) {
+                (Some(below), Some(above)) if above >= below => {
+                    high += 1;
+                    enclosed += above;
+                }
+                (Some(below), _) => {
+                    low -= 1;
+                    enclosed += below;
+                }
+                (None, Some(above)) => {
+                    high += 1;
+                    enclosed += above;
+                }
+                (None, None) => break,
+            }
+        }
+
+        Some((volumes[low].0, volumes[high].0))
+    }
+}
+
+pub const TPO_BRACKET_MS: u64 = 30 * 60 * 1000;
+/// Value area of the TPO sessions, as a share of their letters in percent
+pub const TPO_VALUE_AREA: usize = 70;
+/// Bars with a wider range are clipped to this many levels, the profile would be unreadable
+const TPO_MAX_LEVELS_PER_BAR: usize = 1000;
+
+/// Letter of a bracket within its session, `A` to `Z` followed by `a` to `z`
+pub fn tpo_letter(bracket: usize) -> char {
+    const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+    LETTERS[bracket % LETTERS.len()] as char
+}
+
+/// One daily UTC session of the TPO profile
+#[derive(Debug)]
+pub struct TpoSession {
+    pub start: u64,
+    /// Brackets that traded at each price level, in the order they did
+    pub levels: BTreeMap<OrderedFloat<f32>, Vec<usize>>,
+    /// Letter count per level, which the point of control and value area come from
+    pub profile: VolumeProfile,
+}
+
+/// Builds the TPO sessions from bars in chronological order.
+///
+/// A bracket goes through the levels its trades were grouped into when it has a footprint,
+/// otherwise through every level between the bar's low and high.
+pub fn tpo_sessions<'a>(
+    bars: impl Iterator<Item = (&'a Kline, &'a KlineTrades)>,
+    tick_size: f32,
+) -> Vec<TpoSession> {
+    let mut sessions: Vec<TpoSession> = vec![];
+
+    if tick_size <= 0.0 {
+        return sessions;
+    }
+
+    for (kline, footprint) in bars {
+        let start = VwapSession::Daily.session_start(kline.time);
+
+        if sessions.last().is_none_or(|session| session.start != start) {
+            sessions.push(TpoSession {
+                start,
+                levels: BTreeMap::new(),
+                profile: VolumeProfile::default(),
+            });
+        }
+        let Some(session) = sessions.last_mut() else {
+            continue;
+        };
+
+        let bracket = ((kline.time - start) / TPO_BRACKET_MS) as usize;
+
+        let mut mark = |price: f32| {
+            let brackets = session.levels.entry(OrderedFloat(price)).or_default();
+            if brackets.last() != Some(&bracket) {
+                brackets.push(bracket);
+            }
+        };
+
+        if footprint.trades.is_empty() {
+            let low = round_to_tick(kline.low, tick_size);
+            let steps = ((round_to_tick(kline.high, tick_size) - low) / tick_size).round() as usize;
+
+            for step in 0..=steps.min(TPO_MAX_LEVELS_PER_BAR) {
+                mark(round_to_tick(low + step as f32 * tick_size, tick_size));
+            }
+        } else {
+            for price in footprint.trades.keys() {
+                mark(price.0);
+            }
+        }
+    }
+
+    for session in &mut sessions {
+        session.profile.levels = session
+            .levels
+            .iter()
+            .map(|(price, brackets)| (*price, (brackets.len() as f32, 0.0)))
+            .collect();
+        session.profile.max_volume = session
+            .levels
+            .values()
+            .map(|brackets| brackets.len() as f32)
+            .fold(0.0, f32::max);
+    }
+
+    sessions
+}
+
+/// Named set of imbalance study parameters
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ImbalancePreset {
+    pub name: String,
+    pub threshold: usize,
+    pub color_scale: Option<usize>,
+    pub ignore_zeros: bool,
+}
+
+impl ImbalancePreset {
+    pub fn new(threshold: usize, color_scale: Option<usize>, ignore_zeros: bool) -> Self {
+        Self {
+            name: format!("{threshold}%"),
+            threshold,
+            color_scale,
+            ignore_zeros,
+        }
+    }
+
+    pub fn study(&self) -> FootprintStudy {
+        FootprintStudy::Imbalance {
+            threshold: self.threshold,
+            color_scale: self.color_scale,
+            ignore_zeros: self.ignore_zeros,
+        }
+    }
+
+    pub fn matches(&self, study: &FootprintStudy) -> bool {
+        *study == self.study()
+    }
+}
+
+impl std::fmt::Display for ImbalancePreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Imbalance presets saved in config, along with the preset the study starts from
+/// when it gets enabled on a chart of each market type
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ImbalancePresets {
+    pub presets: Vec<ImbalancePreset>,
+    pub spot_default: Option<String>,
+    pub linear_default: Option<String>,
+    pub inverse_default: Option<String>,
+}
+
+impl Default for ImbalancePresets {
+    fn default() -> Self {
+        Self {
+            presets: vec![
+                ImbalancePreset::new(200, Some(400), true),
+                ImbalancePreset::new(300, Some(600), true),
+                ImbalancePreset::new(400, Some(800), true),
+            ],
+            spot_default: None,
+            linear_default: None,
+            inverse_default: None,
+        }
+    }
+}
+
+impl ImbalancePresets {
+    pub fn get(&self, name: &str) -> Option<&ImbalancePreset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    pub fn default_name(&self, market: MarketKind) -> Option<&String> {
+        match market {
+            MarketKind::Spot => self.spot_default.as_ref(),
+            MarketKind::LinearPerps => self.linear_default.as_ref(),
+            MarketKind::InversePerps => self.inverse_default.as_ref(),
+        }
+    }
+
+    pub fn set_default(&mut self, market: MarketKind, name: Option<String>) {
+        match market {
+            MarketKind::Spot => self.spot_default = name,
+            MarketKind::LinearPerps => self.linear_default = name,
+            MarketKind::InversePerps => self.inverse_default = name,
+        }
+    }
+
+    pub fn default_for(&self, market: MarketKind) -> Option<&ImbalancePreset> {
+        self.default_name(market).and_then(|name| self.get(name))
+    }
+
+    /// Preset the active imbalance study was set from, if it's still unchanged
+    pub fn matching(&self, studies: &[FootprintStudy]) -> Option<&ImbalancePreset> {
+        studies
+            .iter()
+            .find_map(|study| self.presets.iter().find(|preset| preset.matches(study)))
+    }
+
+    /// Adds the preset, replacing the one saved under the same name
+    pub fn save(&mut self, preset: ImbalancePreset) {
+        match self.presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => {
+                self.presets.push(preset);
+                self.presets.sort_by_key(|preset| preset.threshold);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.presets.retain(|preset| preset.name != name);
+
+        for default in [
+            &mut self.spot_default,
+            &mut self.linear_default,
+            &mut self.inverse_default,
+        ] {
+            if default.as_deref() == Some(name) {
+                *default = None;
+            }
         }
     }
 }
@@ -0,0 +1,22 @@
+use exchange::TickMultiplier;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_VOLUME_WINDOW_MINS: u32 = 10;
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Minimum ticks grouped into each row of the ladder
+    pub tick_multiply: TickMultiplier,
+    /// How far back traded volume is summed up per price level
+    pub volume_window_mins: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            tick_multiply: TickMultiplier(1),
+            volume_window_mins: DEFAULT_VOLUME_WINDOW_MINS,
+        }
+    }
+}
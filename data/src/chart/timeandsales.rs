@@ -1,3 +1,4 @@
+use exchange::adapter::MarketKind;
 use serde::{Deserialize, Serialize};
 
 use crate::util::ok_or_default;
@@ -11,6 +12,20 @@ pub struct Config {
     pub buffer_filter: usize,
     #[serde(deserialize_with = "ok_or_default", default)]
     pub stacked_bar_ratio: StackedBarRatio,
+    #[serde(deserialize_with = "ok_or_default", default)]
+    pub side_filter: SideFilter,
+    /// Adds a column with each trade's value in quote currency
+    #[serde(default)]
+    pub show_notional: bool,
+    /// Adds a column with the venue the trade printed on
+    #[serde(default)]
+    pub show_exchange: bool,
+    /// Value a trade has to reach to be highlighted as a large print, 0 turns it off
+    #[serde(default)]
+    pub large_print: f32,
+    /// Value above which a large print is highlighted more strongly, 0 turns it off
+    #[serde(default)]
+    pub huge_print: f32,
 }
 
 impl Default for Config {
@@ -19,21 +34,137 @@ impl Default for Config {
             trade_size_filter: 0.0,
             buffer_filter: DEFAULT_BUFFER_SIZE,
             stacked_bar_ratio: StackedBarRatio::default(),
+            side_filter: SideFilter::default(),
+            show_notional: false,
+            show_exchange: false,
+            large_print: 0.0,
+            huge_print: 0.0,
+        }
+    }
+}
+
+impl Config {
+    /// Whether the trade passes both the size and the side filter
+    pub fn shows(&self, trade: &TradeDisplay, market: MarketKind) -> bool {
+        let side_shown = match self.side_filter {
+            SideFilter::All => true,
+            SideFilter::Buys => !trade.is_sell,
+            SideFilter::Sells => trade.is_sell,
+        };
+
+        side_shown && trade.notional(market) >= self.trade_size_filter
+    }
+
+    pub fn print_size(&self, trade: &TradeDisplay, market: MarketKind) -> PrintSize {
+        let notional = trade.notional(market);
+
+        if self.huge_print > 0.0 && notional >= self.huge_print {
+            PrintSize::Huge
+        } else if self.large_print > 0.0 && notional >= self.large_print {
+            PrintSize::Large
+        } else {
+            PrintSize::Regular
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub enum SideFilter {
+    #[default]
+    All,
+    Buys,
+    Sells,
+}
+
+impl SideFilter {
+    pub const ALL: [SideFilter; 3] = [SideFilter::All, SideFilter::Buys, SideFilter::Sells];
+}
+
+impl std::fmt::Display for SideFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SideFilter::All => write!(f, "Buys & Sells"),
+            SideFilter::Buys => write!(f, "Buys only"),
+            SideFilter::Sells => write!(f, "Sells only"),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintSize {
+    Regular,
+    Large,
+    Huge,
+}
+
 fn default_buffer_filter() -> usize {
     DEFAULT_BUFFER_SIZE
 }
 
 pub struct TradeDisplay {
+    /// Trade time in milliseconds, `time_str` only shows its minutes and seconds
+    pub time: u64,
     pub time_str: String,
     pub price: f32,
     pub qty: f32,
     pub is_sell: bool,
 }
 
+impl TradeDisplay {
+    /// Value of the trade in quote currency, inverse contracts are already sized in it
+    pub fn notional(&self, market: MarketKind) -> f32 {
+        match market {
+            MarketKind::InversePerps => self.qty,
+            MarketKind::Spot | MarketKind::LinearPerps => self.qty * self.price,
+        }
+    }
+}
+
+/// Trades as delimited rows with a header line, e.g. tab-separated for the clipboard
+pub fn trades_table<'a>(
+    trades: impl IntoIterator<Item = &'a TradeDisplay>,
+    delimiter: char,
+) -> String {
+    let mut table = ["time", "price", "qty", "side"].join(&delimiter.to_string());
+
+    for trade in trades {
+        let time = chrono::DateTime::from_timestamp_millis(trade.time as i64)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+            .unwrap_or_else(|| trade.time.to_string());
+        let side = if trade.is_sell { "sell" } else { "buy" };
+
+        table.push('\n');
+        table.push_str(
+            &[
+                time,
+                trade.price.to_string(),
+                trade.qty.to_string(),
+                side.to_string(),
+            ]
+            .join(&delimiter.to_string()),
+        );
+    }
+
+    table
+}
+
+/// Writes the trades as a CSV file into the `exports` folder, returning its path
+pub fn export_csv<'a>(
+    trades: impl IntoIterator<Item = &'a TradeDisplay>,
+    name: &str,
+) -> std::io::Result<std::path::PathBuf> {
+    let dir = crate::data_path(Some("exports"));
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!(
+        "{name}_{}.csv",
+        chrono::Local::now().format("%Y-%m-%d_%H%M%S")
+    ));
+    std::fs::write(&path, trades_table(trades, ','))?;
+
+    Ok(path)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default, Copy)]
 pub enum StackedBarRatio {
     #[default]
@@ -1,9 +1,16 @@
+use std::sync::RwLock;
+
+use exchange::Timeframe;
 use serde::{Deserialize, Serialize};
 
+use crate::aggr::TickCount;
+
+pub mod migration;
 pub mod sidebar;
 pub mod state;
 pub mod theme;
 pub mod timezone;
+pub mod validation;
 
 pub const MIN_SCALE: f64 = 0.8;
 pub const MAX_SCALE: f64 = 1.5;
@@ -28,3 +35,356 @@ impl From<ScaleFactor> for f64 {
         value.0
     }
 }
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct StreamBudget {
+    pub max_depth_streams: usize,
+    pub max_kline_streams: usize,
+    pub max_msg_rate: f32,
+}
+
+impl Default for StreamBudget {
+    fn default() -> Self {
+        StreamBudget {
+            max_depth_streams: 8,
+            max_kline_streams: 24,
+            max_msg_rate: 150.0,
+        }
+    }
+}
+
+impl StreamBudget {
+    /// Describes every limit exceeded by the given stream counts and message rate
+    pub fn violations(
+        &self,
+        depth_streams: usize,
+        kline_streams: usize,
+        msg_rate: f32,
+    ) -> Vec<String> {
+        let mut violations = vec![];
+
+        if depth_streams > self.max_depth_streams {
+            violations.push(format!(
+                "{depth_streams} depth streams (budget {})",
+                self.max_depth_streams
+            ));
+        }
+        if kline_streams > self.max_kline_streams {
+            violations.push(format!(
+                "{kline_streams} kline streams (budget {})",
+                self.max_kline_streams
+            ));
+        }
+        if msg_rate > self.max_msg_rate {
+            violations.push(format!(
+                "~{msg_rate:.0} msg/s (budget {:.0})",
+                self.max_msg_rate
+            ));
+        }
+
+        violations
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct SnapshotSchedule {
+    pub is_enabled: bool,
+    /// Times of day, in the user's timezone, to capture the selected panes at
+    pub times: Vec<chrono::NaiveTime>,
+}
+
+impl Default for SnapshotSchedule {
+    fn default() -> Self {
+        SnapshotSchedule {
+            is_enabled: false,
+            times: vec![chrono::NaiveTime::MIN],
+        }
+    }
+}
+
+impl SnapshotSchedule {
+    /// Parses a comma separated list of `HH:MM` times
+    pub fn parse_times(input: &str) -> Option<Vec<chrono::NaiveTime>> {
+        input
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| chrono::NaiveTime::parse_from_str(s, "%H:%M").ok())
+            .collect()
+    }
+
+    pub fn times_string(&self) -> String {
+        self.times
+            .iter()
+            .map(|time| time.format("%H:%M").to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The minute slot to capture in, if `now` falls on one of the scheduled times
+    pub fn due_slot(&self, now: chrono::NaiveDateTime) -> Option<chrono::NaiveDateTime> {
+        use chrono::Timelike;
+
+        if !self.is_enabled {
+            return None;
+        }
+
+        let slot = now.with_second(0)?.with_nanosecond(0)?;
+
+        self.times
+            .iter()
+            .any(|time| time.hour() == slot.hour() && time.minute() == slot.minute())
+            .then_some(slot)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum GpuPreference {
+    #[default]
+    HighPerformance,
+    LowPower,
+}
+
+impl GpuPreference {
+    pub const ALL: [GpuPreference; 2] = [GpuPreference::HighPerformance, GpuPreference::LowPower];
+
+    /// Value understood by wgpu's `WGPU_POWER_PREF` variable
+    pub fn env_value(self) -> &'static str {
+        match self {
+            GpuPreference::HighPerformance => "high",
+            GpuPreference::LowPower => "low",
+        }
+    }
+}
+
+impl std::fmt::Display for GpuPreference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GpuPreference::HighPerformance => write!(f, "High performance"),
+            GpuPreference::LowPower => write!(f, "Low power"),
+        }
+    }
+}
+
+/// Spacing of the interface, independent of the scale factor which zooms everything alike
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+pub enum UiDensity {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+impl UiDensity {
+    pub const ALL: [UiDensity; 2] = [UiDensity::Comfortable, UiDensity::Compact];
+
+    /// Paddings and row heights, compact trims a quarter off
+    pub fn scale(self, value: f32) -> f32 {
+        match self {
+            UiDensity::Comfortable => value,
+            UiDensity::Compact => (value * 0.75).round(),
+        }
+    }
+
+    pub fn default_text_size(self) -> f32 {
+        match self {
+            UiDensity::Comfortable => 12.0,
+            UiDensity::Compact => 11.0,
+        }
+    }
+}
+
+impl std::fmt::Display for UiDensity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UiDensity::Comfortable => write!(f, "Comfortable"),
+            UiDensity::Compact => write!(f, "Compact"),
+        }
+    }
+}
+
+/// Monospace font of the chart labels, the bundled one or a family looked up on the system
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+pub enum ChartFont {
+    #[default]
+    AzeretMono,
+    SystemMonospace,
+    JetBrainsMono,
+    FiraCode,
+    Consolas,
+    Menlo,
+}
+
+impl ChartFont {
+    pub const ALL: [ChartFont; 6] = [
+        ChartFont::AzeretMono,
+        ChartFont::SystemMonospace,
+        ChartFont::JetBrainsMono,
+        ChartFont::FiraCode,
+        ChartFont::Consolas,
+        ChartFont::Menlo,
+    ];
+
+    /// `None` leaves the pick to the system's default monospace family
+    pub fn family_name(self) -> Option<&'static str> {
+        match self {
+            ChartFont::AzeretMono => Some("Azeret Mono"),
+            ChartFont::SystemMonospace => None,
+            ChartFont::JetBrainsMono => Some("JetBrains Mono"),
+            ChartFont::FiraCode => Some("Fira Code"),
+            ChartFont::Consolas => Some("Consolas"),
+            ChartFont::Menlo => Some("Menlo"),
+        }
+    }
+
+    pub fn is_bundled(self) -> bool {
+        self == ChartFont::AzeretMono
+    }
+}
+
+impl std::fmt::Display for ChartFont {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChartFont::AzeretMono => write!(f, "Azeret Mono"),
+            ChartFont::SystemMonospace => write!(f, "System monospace"),
+            ChartFont::JetBrainsMono => write!(f, "JetBrains Mono"),
+            ChartFont::FiraCode => write!(f, "Fira Code"),
+            ChartFont::Consolas => write!(f, "Consolas"),
+            ChartFont::Menlo => write!(f, "Menlo"),
+        }
+    }
+}
+
+pub const MIN_CHART_TEXT_SIZE: f32 = 9.0;
+pub const MAX_CHART_TEXT_SIZE: f32 = 16.0;
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ChartText {
+    pub font: ChartFont,
+    /// Size of the axis labels, the rest of the chart text is sized relative to it
+    pub size: f32,
+}
+
+impl ChartText {
+    pub fn clamped_size(self) -> f32 {
+        self.size.clamp(MIN_CHART_TEXT_SIZE, MAX_CHART_TEXT_SIZE)
+    }
+}
+
+impl Default for ChartText {
+    fn default() -> Self {
+        ChartText {
+            font: ChartFont::default(),
+            size: 12.0,
+        }
+    }
+}
+
+static CHART_DEFAULTS: RwLock<Option<ChartDefaults>> = RwLock::new(None);
+
+pub fn set_chart_defaults(defaults: ChartDefaults) {
+    if let Ok(mut current) = CHART_DEFAULTS.write() {
+        *current = Some(defaults);
+    }
+}
+
+pub fn chart_defaults() -> ChartDefaults {
+    CHART_DEFAULTS
+        .read()
+        .ok()
+        .and_then(|defaults| *defaults)
+        .unwrap_or_default()
+}
+
+/// Basis a new pane of each chart kind starts out with, until one is picked for it
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ChartDefaults {
+    pub candlestick: Timeframe,
+    pub footprint: Timeframe,
+    pub delta: Timeframe,
+    pub tpo: Timeframe,
+    /// Filled in when switching a chart over to a tick basis
+    pub tick_count: TickCount,
+    /// Venues that don't stream depth this often fall back to their fastest bucket
+    pub heatmap: Timeframe,
+}
+
+impl Default for ChartDefaults {
+    fn default() -> Self {
+        ChartDefaults {
+            candlestick: Timeframe::M15,
+            footprint: Timeframe::M5,
+            delta: Timeframe::M5,
+            tpo: Timeframe::M30,
+            tick_count: TickCount(100),
+            heatmap: Timeframe::MS100,
+        }
+    }
+}
+
+/// Opt-in recording of the depth streams to disk, see [`crate::recorder`]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct RecorderSettings {
+    pub is_enabled: bool,
+    /// How far back charts get rebuilt from the recordings when a layout loads
+    pub restore_hours: u8,
+    /// How far back a heatmap opened on a recorded ticker gets filled in
+    pub backfill_minutes: u8,
+}
+
+impl Default for RecorderSettings {
+    fn default() -> Self {
+        RecorderSettings {
+            is_enabled: false,
+            restore_hours: 4,
+            backfill_minutes: 15,
+        }
+    }
+}
+
+impl RecorderSettings {
+    pub const RESTORE_HOURS: [u8; 5] = [0, 1, 4, 12, 24];
+    pub const BACKFILL_MINUTES: [u8; 5] = [0, 5, 15, 30, 60];
+
+    /// Start of the window to restore, `None` when restoring is turned off
+    pub fn restore_since(&self, now_ms: u64) -> Option<u64> {
+        (self.is_enabled && self.restore_hours > 0)
+            .then(|| now_ms.saturating_sub(u64::from(self.restore_hours) * 3_600_000))
+    }
+
+    /// Start of the window a freshly opened heatmap is filled in from, `None` when turned off
+    pub fn backfill_since(&self, now_ms: u64) -> Option<u64> {
+        (self.is_enabled && self.backfill_minutes > 0)
+            .then(|| now_ms.saturating_sub(u64::from(self.backfill_minutes) * 60_000))
+    }
+}
+
+/// Renderer options the GPU backend is created with, only read once at startup
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct RendererSettings {
+    pub gpu_preference: GpuPreference,
+    pub vsync: bool,
+    pub antialiasing: bool,
+}
+
+impl Default for RendererSettings {
+    fn default() -> Self {
+        RendererSettings {
+            gpu_preference: GpuPreference::default(),
+            vsync: true,
+            antialiasing: true,
+        }
+    }
+}
+
+impl RendererSettings {
+    /// Value understood by iced's `ICED_PRESENT_MODE` variable
+    pub fn present_mode(&self) -> &'static str {
+        if self.vsync { "vsync" } else { "no_vsync" }
+    }
+}
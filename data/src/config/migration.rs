@@ -0,0 +1,142 @@
+use serde_json::{Map, Value};
+
+use super::state::State;
+use super::validation::InvalidField;
+
+/// Version written into newly saved states, bump it together with a new entry in `MIGRATIONS`
+pub const STATE_VERSION: u32 = 2;
+
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// Steps upgrading a saved state from version `i` to `i + 1`
+const MIGRATIONS: [Migration; STATE_VERSION as usize] = [v0_to_v1, v1_to_v2];
+
+#[derive(thiserror::Error, Debug)]
+pub enum MigrationError {
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Saved state root is not an object")]
+    NotAnObject,
+    #[error("Migration from v{0} failed: {1}")]
+    Step(u32, String),
+}
+
+/// Parses a saved state of any known version, upgrading it to the current schema first.
+/// Sections that still fail to parse are reset to their defaults and reported back.
+pub fn parse_state(json: &str) -> Result<(State, Vec<InvalidField>), MigrationError> {
+    let mut value: Value = serde_json::from_str(json)?;
+    let root = value.as_object_mut().ok_or(MigrationError::NotAnObject)?;
+
+    let version = root
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v as u32);
+
+    if version > STATE_VERSION {
+        log::warn!(
+            "Saved state is v{version}, newer than the supported v{STATE_VERSION}. Loading it as is"
+        );
+    }
+
+    for from in version..STATE_VERSION {
+        MIGRATIONS[from as usize](root).map_err(|reason| MigrationError::Step(from, reason))?;
+        log::info!("Migrated saved state from v{from} to v{}", from + 1);
+    }
+
+    root.insert(
+        "version".to_string(),
+        Value::from(STATE_VERSION.max(version)),
+    );
+
+    match serde_json::from_value(value.clone()) {
+        Ok(state) => Ok((state, vec![])),
+        Err(err) => {
+            log::warn!("Saved state has invalid fields ({err}), recovering valid sections");
+
+            let Value::Object(root) = value else {
+                return Err(MigrationError::NotAnObject);
+            };
+            Ok(super::validation::recover(root))
+        }
+    }
+}
+
+/// Unversioned states already have the v1 shape, the version field is all v1 added
+fn v0_to_v1(_root: &mut Map<String, Value>) -> Result<(), String> {
+    Ok(())
+}
+
+/// The single trade fetch toggle only ever applied to Binance, it becomes consent for each of
+/// its markets
+fn v1_to_v2(root: &mut Map<String, Value>) -> Result<(), String> {
+    if let Some(Value::Bool(enabled)) = root.remove("trade_fetch_enabled") {
+        let markets = if enabled {
+            serde_json::json!(["BinanceLinear", "BinanceInverse", "BinanceSpot"])
+        } else {
+            serde_json::json!([])
+        };
+        root.insert("trade_fetch".to_string(), markets);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::sidebar::Position;
+    use exchange::adapter::Exchange;
+
+    const V0: &str = include_str!("../../tests/fixtures/saved-state-v0.json");
+    const V1: &str = include_str!("../../tests/fixtures/saved-state-v1.json");
+
+    fn parse_valid(json: &str) -> State {
+        let (state, invalid) = parse_state(json).expect("fixture parses");
+        assert!(
+            invalid.is_empty(),
+            "fixture has invalid fields: {invalid:?}"
+        );
+        state
+    }
+
+    #[test]
+    fn migrates_unversioned_state() {
+        let state = parse_valid(V0);
+
+        assert_eq!(state.version, STATE_VERSION);
+        assert_eq!(
+            state.trade_fetch,
+            vec![
+                Exchange::BinanceLinear,
+                Exchange::BinanceInverse,
+                Exchange::BinanceSpot
+            ]
+        );
+        assert_eq!(state.sidebar.position, Position::Right);
+        assert_eq!(state.layout_manager.layouts.len(), 2);
+        assert_eq!(state.layout_manager.active_layout, "Layout 2");
+        assert_eq!(state.layout_manager.layouts[0].dashboard.popout.len(), 1);
+        assert_eq!(f64::from(state.scale_factor), 1.25);
+    }
+
+    #[test]
+    fn migrates_v1_trade_fetch_toggle() {
+        let state = parse_valid(V1);
+
+        assert_eq!(state.version, STATE_VERSION);
+        assert!(state.trade_fetch.is_empty());
+        assert_eq!(state.layout_manager.active_layout, "Scalping");
+    }
+
+    #[test]
+    fn migrated_states_round_trip() {
+        for fixture in [V0, V1] {
+            let migrated = serde_json::to_value(parse_valid(fixture)).unwrap();
+            let json = serde_json::to_string(&migrated).unwrap();
+            let reparsed = serde_json::to_value(parse_valid(&json)).unwrap();
+
+            assert_eq!(migrated, reparsed);
+            assert!(migrated.get("trade_fetch_enabled").is_none());
+        }
+    }
+}
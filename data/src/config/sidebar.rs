@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 #[serde(default)]
 pub struct Sidebar {
     pub position: Position,
+    /// Collapses to a thin strip until hovered or toggled with the hotkey
+    pub auto_hide: bool,
+    pub tickers_table: TableSizing,
     #[serde(skip)]
     pub active_menu: Option<Menu>,
 }
@@ -26,11 +29,56 @@ impl Default for Sidebar {
     fn default() -> Self {
         Sidebar {
             position: Position::Left,
+            auto_hide: false,
+            tickers_table: TableSizing::default(),
             active_menu: None,
         }
     }
 }
 
+/// Widths of the tickers table and its stat columns, dragged from their edges
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TableSizing {
+    pub width: f32,
+    pub metric_column: f32,
+    pub stats_column: f32,
+}
+
+impl TableSizing {
+    const WIDTH_RANGE: (f32, f32) = (160.0, 480.0);
+    const COLUMN_RANGE: (f32, f32) = (24.0, 160.0);
+
+    pub fn resize(&mut self, delta: f32) {
+        self.width = (self.width + delta).clamp(Self::WIDTH_RANGE.0, Self::WIDTH_RANGE.1);
+    }
+
+    pub fn resize_column(&mut self, column: TableColumn, delta: f32) {
+        let width = match column {
+            TableColumn::Metric => &mut self.metric_column,
+            TableColumn::Stats => &mut self.stats_column,
+        };
+        *width = (*width + delta).clamp(Self::COLUMN_RANGE.0, Self::COLUMN_RANGE.1);
+    }
+}
+
+impl Default for TableSizing {
+    fn default() -> Self {
+        TableSizing {
+            width: 200.0,
+            metric_column: 40.0,
+            stats_column: 56.0,
+        }
+    }
+}
+
+/// Fixed width columns of the ticker cards, the symbol and price take what's left
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableColumn {
+    Metric,
+    Stats,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Copy, Deserialize, Serialize)]
 pub enum Position {
     #[default]
@@ -53,4 +101,7 @@ pub enum Menu {
     Settings,
     Audio,
     ThemeEditor,
+    Journal,
+    Alerts,
+    Profile,
 }
@@ -1,31 +1,68 @@
+use crate::alert::Alerts;
+use crate::chart::kline::ImbalancePresets;
 use crate::layout::WindowSpec;
 use crate::{AudioStream, Layout, Theme};
 use exchange::{Ticker, adapter::Exchange};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use super::ScaleFactor;
 use super::sidebar::Sidebar;
+use super::theme::ChartThemes;
 use super::timezone::UserTimezone;
+use super::{
+    ChartDefaults, ChartText, RecorderSettings, RendererSettings, ScaleFactor, SnapshotSchedule,
+    StreamBudget, UiDensity,
+};
 
 #[derive(Clone, Serialize, Deserialize, Default)]
 pub struct Layouts {
     pub layouts: Vec<Layout>,
     pub active_layout: String,
+    /// Layouts running in their own window besides the main one, by name
+    #[serde(default)]
+    pub detached: Vec<(String, WindowSpec)>,
 }
 
 #[derive(Default, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct State {
+    pub version: u32,
     pub layout_manager: Layouts,
     pub selected_theme: Theme,
     pub custom_theme: Option<Theme>,
+    pub chart_themes: ChartThemes,
     pub favorited_tickers: Vec<(Exchange, Ticker)>,
     pub main_window: Option<WindowSpec>,
     pub timezone: UserTimezone,
     pub sidebar: Sidebar,
     pub scale_factor: ScaleFactor,
     pub audio_cfg: AudioStream,
-    pub trade_fetch_enabled: bool,
+    /// Markets the user agreed to fetch historical trades from
+    pub trade_fetch: Vec<Exchange>,
+    pub depth_fallback: exchange::depth::DepthFallback,
+    pub chart_defaults: ChartDefaults,
+    pub reconnect_policy: exchange::connect::ReconnectPolicy,
+    pub rate_limit_share: exchange::fetcher::RateLimitShare,
+    /// Proxy REST requests and websockets go through, connecting directly without one
+    pub proxy: Option<exchange::connect::Proxy>,
+    /// Markets calling other addresses than their exchange's own
+    pub endpoints: Vec<(Exchange, exchange::adapter::Endpoints)>,
+    pub stream_budget: StreamBudget,
+    pub pause_hidden_popouts: bool,
+    pub snapshot_schedule: SnapshotSchedule,
+    pub recent_tickers: Vec<(Exchange, Ticker)>,
+    pub ticker_hotkeys: BTreeMap<u8, (Exchange, Ticker)>,
+    pub show_status_bar: bool,
+    pub renderer: RendererSettings,
+    pub imbalance_presets: ImbalancePresets,
+    pub recorder: RecorderSettings,
+    pub ui_density: UiDensity,
+    pub alerts: Alerts,
+    pub chart_text: ChartText,
+    /// Saving a report to the data folder on panics, off unless the user opts in
+    pub crash_reports: bool,
+    /// Markets whose adapters connect to the exchange testnet instead
+    pub testnet: Vec<Exchange>,
 }
 
 impl State {
@@ -33,24 +70,60 @@ impl State {
         layout_manager: Layouts,
         selected_theme: Theme,
         custom_theme: Option<Theme>,
+        chart_themes: ChartThemes,
         favorited_tickers: Vec<(Exchange, Ticker)>,
         main_window: Option<WindowSpec>,
         timezone: UserTimezone,
         sidebar: Sidebar,
         scale_factor: ScaleFactor,
         audio_cfg: AudioStream,
+        stream_budget: StreamBudget,
+        pause_hidden_popouts: bool,
+        snapshot_schedule: SnapshotSchedule,
+        recent_tickers: Vec<(Exchange, Ticker)>,
+        ticker_hotkeys: BTreeMap<u8, (Exchange, Ticker)>,
+        show_status_bar: bool,
+        renderer: RendererSettings,
+        imbalance_presets: ImbalancePresets,
+        recorder: RecorderSettings,
+        ui_density: UiDensity,
+        alerts: Alerts,
+        chart_text: ChartText,
+        testnet: Vec<Exchange>,
     ) -> Self {
         State {
+            version: super::migration::STATE_VERSION,
             layout_manager,
             selected_theme: Theme(selected_theme.0),
             custom_theme: custom_theme.map(|t| Theme(t.0)),
+            chart_themes,
             favorited_tickers,
             main_window,
             timezone,
             sidebar,
             scale_factor,
             audio_cfg,
-            trade_fetch_enabled: exchange::fetcher::is_trade_fetch_enabled(),
+            trade_fetch: exchange::fetcher::trade_fetch_consents(),
+            depth_fallback: exchange::depth::depth_fallback(),
+            chart_defaults: super::chart_defaults(),
+            reconnect_policy: exchange::connect::reconnect_policy(),
+            rate_limit_share: exchange::fetcher::rate_limit_share(),
+            proxy: exchange::connect::proxy(),
+            endpoints: exchange::adapter::custom_endpoints(),
+            stream_budget,
+            pause_hidden_popouts,
+            snapshot_schedule,
+            recent_tickers,
+            ticker_hotkeys,
+            show_status_bar,
+            renderer,
+            imbalance_presets,
+            recorder,
+            ui_density,
+            alerts,
+            chart_text,
+            crash_reports: crate::crash::is_enabled(),
+            testnet,
         }
     }
 }
@@ -11,6 +11,9 @@ use palette::{
     rgb::{Rgb, Rgba},
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::chart::KlineChartKind;
 
 #[derive(Debug, Clone)]
 pub struct Theme(pub iced_core::Theme);
@@ -158,6 +161,70 @@ impl<'de> Deserialize<'de> for Theme {
     }
 }
 
+/// Chart kinds that can be drawn in a theme of their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum ThemedChart {
+    Heatmap,
+    Candlestick,
+    Footprint,
+    Delta,
+    Tpo,
+    Spread,
+}
+
+impl ThemedChart {
+    pub const ALL: [ThemedChart; 6] = [
+        ThemedChart::Heatmap,
+        ThemedChart::Candlestick,
+        ThemedChart::Footprint,
+        ThemedChart::Delta,
+        ThemedChart::Tpo,
+        ThemedChart::Spread,
+    ];
+}
+
+impl From<&KlineChartKind> for ThemedChart {
+    fn from(kind: &KlineChartKind) -> Self {
+        match kind {
+            KlineChartKind::Candles => ThemedChart::Candlestick,
+            KlineChartKind::Footprint { .. } => ThemedChart::Footprint,
+            KlineChartKind::DeltaCandles => ThemedChart::Delta,
+            KlineChartKind::Tpo => ThemedChart::Tpo,
+            KlineChartKind::Spread(_) => ThemedChart::Spread,
+        }
+    }
+}
+
+impl std::fmt::Display for ThemedChart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemedChart::Heatmap => write!(f, "Heatmap"),
+            ThemedChart::Candlestick => write!(f, "Candlestick"),
+            ThemedChart::Footprint => write!(f, "Footprint"),
+            ThemedChart::Delta => write!(f, "Delta"),
+            ThemedChart::Tpo => write!(f, "TPO"),
+            ThemedChart::Spread => write!(f, "Spread"),
+        }
+    }
+}
+
+/// Themes assigned to chart kinds, the kinds left out follow the app's theme
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ChartThemes(BTreeMap<ThemedChart, Theme>);
+
+impl ChartThemes {
+    pub fn get(&self, chart: ThemedChart) -> Option<&Theme> {
+        self.0.get(&chart)
+    }
+
+    pub fn set(&mut self, chart: ThemedChart, theme: Option<Theme>) {
+        match theme {
+            Some(theme) => self.0.insert(chart, theme),
+            None => self.0.remove(&chart),
+        };
+    }
+}
+
 pub fn hex_to_color(hex: &str) -> Option<Color> {
     if hex.len() == 7 || hex.len() == 9 {
         let hash = &hex[0..1];
@@ -69,6 +69,34 @@ impl UserTimezone {
             String::new()
         }
     }
+    /// Parses `YYYY-MM-DD HH:MM` or `YYYY-MM-DD` in this timezone into a UTC timestamp in milliseconds
+    pub fn parse_datetime(&self, input: &str) -> Option<i64> {
+        let input = input.trim();
+
+        let naive = chrono::NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+            .ok()
+            .or_else(|| {
+                chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                    .ok()
+                    .map(|date| date.and_time(chrono::NaiveTime::MIN))
+            })?;
+
+        match self {
+            UserTimezone::Local => naive
+                .and_local_timezone(chrono::Local)
+                .earliest()
+                .map(|datetime| datetime.timestamp_millis()),
+            UserTimezone::Utc => Some(naive.and_utc().timestamp_millis()),
+        }
+    }
+
+    /// Current wall clock time in this timezone
+    pub fn now(&self) -> chrono::NaiveDateTime {
+        match self {
+            UserTimezone::Local => chrono::Local::now().naive_local(),
+            UserTimezone::Utc => chrono::Utc::now().naive_utc(),
+        }
+    }
 }
 
 impl fmt::Display for UserTimezone {
@@ -0,0 +1,97 @@
+use serde_json::{Map, Value, json};
+
+use super::state::State;
+
+#[derive(Debug, Clone)]
+pub struct InvalidField {
+    pub path: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for InvalidField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+/// Drops every section of the saved state that fails to parse so the rest can still be loaded.
+///
+/// Since `State` is `#[serde(default)]`, each top-level field can be checked on its own by
+/// parsing an object that contains only that field.
+pub fn recover(mut root: Map<String, Value>) -> (State, Vec<InvalidField>) {
+    let mut invalid = vec![];
+
+    if let Some(Value::Object(layouts)) = root.get_mut("layout_manager") {
+        recover_layouts(layouts, &mut invalid);
+    }
+
+    let keys = root.keys().cloned().collect::<Vec<_>>();
+
+    for key in keys {
+        let Some(value) = root.get(&key) else {
+            continue;
+        };
+
+        let mut section = Map::new();
+        section.insert(key.clone(), value.clone());
+
+        if let Err(err) = serde_json::from_value::<State>(Value::Object(section)) {
+            invalid.push(InvalidField {
+                path: key.clone(),
+                reason: err.to_string(),
+            });
+            root.remove(&key);
+        }
+    }
+
+    let state = serde_json::from_value(Value::Object(root)).unwrap_or_else(|err| {
+        log::error!("Failed to parse saved state after dropping invalid sections: {err}");
+        State::default()
+    });
+
+    (state, invalid)
+}
+
+/// Layouts are validated one by one, a broken layout shouldn't take the others with it
+fn recover_layouts(layouts: &mut Map<String, Value>, invalid: &mut Vec<InvalidField>) {
+    let Some(Value::Array(list)) = layouts.get_mut("layouts") else {
+        return;
+    };
+
+    list.retain(|layout| {
+        let section = json!({ "layout_manager": { "layouts": [layout], "active_layout": "" } });
+
+        match serde_json::from_value::<State>(section) {
+            Ok(_) => true,
+            Err(err) => {
+                let name = layout
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unnamed");
+
+                invalid.push(InvalidField {
+                    path: format!("layout_manager.layouts[\"{name}\"]"),
+                    reason: err.to_string(),
+                });
+                false
+            }
+        }
+    });
+
+    let names = list
+        .iter()
+        .filter_map(|layout| layout.get("name").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    let is_active_missing = layouts
+        .get("active_layout")
+        .and_then(Value::as_str)
+        .is_none_or(|active| !names.iter().any(|name| name == active));
+
+    if is_active_missing {
+        if let Some(first) = names.first() {
+            layouts.insert("active_layout".to_string(), Value::from(first.as_str()));
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Opt-in crash reports, written to the data folder when the app panics so they can be attached
+//! to a bug report. Nothing is ever sent anywhere
+
+use std::collections::VecDeque;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, TryLockError};
+
+use crate::data_path;
+
+const REPORTS_FOLDER: &str = "crash_reports";
+/// Holds the file name of the latest report the user hasn't been told about yet
+const UNSEEN_MARKER: &str = "unseen";
+const MAX_EVENTS: usize = 30;
+
+static IS_ENABLED: AtomicBool = AtomicBool::new(false);
+static CONTEXT: Mutex<Context> = Mutex::new(Context::new());
+
+/// What the app was doing, kept up to date only while reports are enabled
+struct Context {
+    layout: String,
+    panes: Vec<String>,
+    events: VecDeque<(chrono::DateTime<chrono::Utc>, String)>,
+}
+
+impl Context {
+    const fn new() -> Self {
+        Self {
+            layout: String::new(),
+            panes: Vec::new(),
+            events: VecDeque::new(),
+        }
+    }
+}
+
+/// Hooks into panics, reports only get written while enabled, see [`set_enabled`]
+pub fn install(is_enabled: bool, app_version: &'static str) {
+    set_enabled(is_enabled);
+
+    let default_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        if self::is_enabled() {
+            match write_report(info, app_version) {
+                Ok(path) => log::error!("Crash report saved to {}", path.display()),
+                Err(err) => log::error!("Failed to write crash report: {err}"),
+            }
+        }
+
+        default_hook(info);
+    }));
+}
+
+pub fn set_enabled(is_enabled: bool) {
+    IS_ENABLED.store(is_enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    IS_ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_layout(name: &str, panes: Vec<String>) {
+    if !is_enabled() {
+        return;
+    }
+
+    if let Ok(mut context) = CONTEXT.lock() {
+        name.clone_into(&mut context.layout);
+        context.panes = panes;
+    }
+}
+
+pub fn record_event(event: impl Into<String>) {
+    if !is_enabled() {
+        return;
+    }
+
+    if let Ok(mut context) = CONTEXT.lock() {
+        if context.events.len() >= MAX_EVENTS {
+            context.events.pop_front();
+        }
+        context.events.push_back((chrono::Utc::now(), event.into()));
+    }
+}
+
+/// The report of a crash since the last time this was called, if any
+pub fn take_unseen_report() -> Option<PathBuf> {
+    let marker = data_path(Some(REPORTS_FOLDER)).join(UNSEEN_MARKER);
+    let file_name = fs::read_to_string(&marker).ok()?;
+    let _ = fs::remove_file(&marker);
+
+    let path = data_path(Some(REPORTS_FOLDER)).join(file_name.trim());
+    path.exists().then_some(path)
+}
+
+fn write_report(info: &PanicHookInfo, app_version: &str) -> std::io::Result<PathBuf> {
+    let created_at = chrono::Utc::now();
+
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(ToString::to_string)
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Unknown panic".to_string());
+    let location = info
+        .location()
+        .map_or("unknown location".to_string(), |location| {
+            format!("{}:{}", location.file(), location.line())
+        });
+
+    let mut content = format!(
+        "Flowsurface {app_version}\nOS: {} ({})\nCreated at: {}\nThread: {}\n\nPanicked at {location}:\n{message}\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        created_at.to_rfc3339(),
+        std::thread::current().name().unwrap_or("unnamed"),
+    );
+
+    // the panic may have happened while the context was held on this very thread
+    let context = match CONTEXT.try_lock() {
+        Ok(context) => Some(context),
+        Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    };
+
+    match context {
+        Some(context) => {
+            content.push_str(&format!("\nActive layout: {}\n", context.layout));
+            for pane in &context.panes {
+                content.push_str(&format!("  {pane}\n"));
+            }
+
+            content.push_str("\n--- Last events ---\n");
+            for (time, event) in &context.events {
+                content.push_str(&format!("{} {event}\n", time.format("%H:%M:%S%.3f")));
+            }
+        }
+        None => content.push_str("\nContext unavailable, it was in use when panicking\n"),
+    }
+
+    content.push_str(&format!(
+        "\n--- Backtrace ---\n{}\n",
+        std::backtrace::Backtrace::force_capture()
+    ));
+
+    let folder = data_path(Some(REPORTS_FOLDER));
+    fs::create_dir_all(&folder)?;
+
+    let file_name = format!("crash-{}.txt", created_at.format("%Y%m%d-%H%M%S"));
+    let path = folder.join(&file_name);
+
+    fs::write(&path, content)?;
+    fs::write(folder.join(UNSEEN_MARKER), file_name)?;
+
+    Ok(path)
+}
@@ -0,0 +1,209 @@
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+use exchange::adapter::{Exchange, ServerStatus};
+
+use crate::{InternalError, SAVED_STATE_PATH, data_path};
+
+const CLOCK_SKEW_WARN_MS: i64 = 1000;
+const LATENCY_WARN_MS: u64 = 2000;
+const LOG_TAIL_BYTES: u64 = 32 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Ok => write!(f, "OK"),
+            Severity::Warning => write!(f, "WARN"),
+            Severity::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub severity: Severity,
+    pub label: String,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(severity: Severity, label: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            severity,
+            label: label.into(),
+            detail: detail.into(),
+        }
+    }
+
+    pub fn from_connectivity(exchange: Exchange, result: &Result<ServerStatus, String>) -> Self {
+        let label = exchange.to_string();
+
+        match result {
+            Ok(status) if status.clock_skew_ms.abs() >= CLOCK_SKEW_WARN_MS => Check::new(
+                Severity::Warning,
+                label,
+                format!(
+                    "Clock skew {:.1}s, sync your system clock",
+                    status.clock_skew_ms as f32 / 1000.0
+                ),
+            ),
+            Ok(status) if status.latency_ms >= LATENCY_WARN_MS => Check::new(
+                Severity::Warning,
+                label,
+                format!("Slow response ({}ms)", status.latency_ms),
+            ),
+            Ok(status) => Check::new(
+                Severity::Ok,
+                label,
+                format!(
+                    "Reachable ({}ms, skew {}ms)",
+                    status.latency_ms, status.clock_skew_ms
+                ),
+            ),
+            Err(err) => Check::new(Severity::Error, label, format!("Unreachable: {err}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub checks: Vec<Check>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Report {
+    pub fn new(connectivity: &[(Exchange, Result<ServerStatus, String>)]) -> Self {
+        let mut checks = check_data_folder();
+
+        checks.extend(
+            connectivity
+                .iter()
+                .map(|(exchange, result)| Check::from_connectivity(*exchange, result)),
+        );
+
+        Self {
+            checks,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    pub fn issues(&self) -> impl Iterator<Item = &Check> {
+        self.checks
+            .iter()
+            .filter(|check| check.severity != Severity::Ok)
+    }
+
+    pub fn has_issues(&self) -> bool {
+        self.issues().next().is_some()
+    }
+
+    /// Writes the report along with system info and the latest log lines into the data folder
+    pub fn export(&self, app_version: &str) -> Result<PathBuf, InternalError> {
+        let mut content = format!(
+            "Flowsurface {app_version}\nOS: {} ({})\nData folder: {}\nCreated at: {}\n\n",
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            data_path(None).display(),
+            self.created_at.to_rfc3339(),
+        );
+
+        for check in &self.checks {
+            content.push_str(&format!(
+                "[{}] {}: {}\n",
+                check.severity, check.label, check.detail
+            ));
+        }
+
+        if let Some(log_tail) = read_log_tail() {
+            content.push_str("\n--- Log ---\n");
+            content.push_str(&log_tail);
+        }
+
+        let file_name = format!(
+            "diagnostics-{}.txt",
+            self.created_at.format("%Y%m%d-%H%M%S")
+        );
+        let path = data_path(Some(&file_name));
+
+        fs::write(&path, content).map_err(|err| {
+            InternalError::Diagnostics(format!("Failed to write diagnostics file: {err}"))
+        })?;
+
+        Ok(path)
+    }
+}
+
+fn check_data_folder() -> Vec<Check> {
+    let mut checks = vec![];
+    let folder = data_path(None);
+
+    if let Err(err) = fs::create_dir_all(&folder) {
+        checks.push(Check::new(
+            Severity::Error,
+            "Data folder",
+            format!("Can't create {}: {err}", folder.display()),
+        ));
+        return checks;
+    }
+
+    let probe = folder.join(".write-probe");
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            checks.push(Check::new(Severity::Ok, "Data folder", "Writable"));
+        }
+        Err(err) => checks.push(Check::new(
+            Severity::Error,
+            "Data folder",
+            format!("Not writable, settings won't be saved: {err}"),
+        )),
+    }
+
+    let saved_state = data_path(Some(SAVED_STATE_PATH));
+    if saved_state.exists() {
+        match fs::read_to_string(&saved_state)
+            .map_err(|err| err.to_string())
+            .and_then(|json| {
+                serde_json::from_str::<serde_json::Value>(&json).map_err(|err| err.to_string())
+            }) {
+            Ok(_) => checks.push(Check::new(Severity::Ok, "Saved state", "Valid")),
+            Err(err) => checks.push(Check::new(
+                Severity::Error,
+                "Saved state",
+                format!("Unreadable: {err}"),
+            )),
+        }
+    }
+
+    let backup = SAVED_STATE_PATH.replace(".json", "_old.json");
+    if data_path(Some(&backup)).exists() {
+        checks.push(Check::new(
+            Severity::Warning,
+            "Saved state",
+            format!("A corrupted state was backed up to {backup}, restore or delete it"),
+        ));
+    }
+
+    checks
+}
+
+fn read_log_tail() -> Option<String> {
+    let mut file = fs::File::open(crate::log::path().ok()?).ok()?;
+    let len = file.metadata().ok()?.len();
+
+    file.seek(SeekFrom::Start(len.saturating_sub(LOG_TAIL_BYTES)))
+        .ok()?;
+
+    let mut buf = vec![];
+    file.read_to_end(&mut buf).ok()?;
+
+    Some(String::from_utf8_lossy(&buf).into_owned())
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use exchange::{Ticker, adapter::Exchange};
+
+use crate::{InternalError, journal_path};
+
+const TAGS_FILE: &str = "tags.json";
+
+pub const PRESET_LABELS: [&str; 4] = ["long entry", "short entry", "exit", "missed setup"];
+
+/// A labeled time range on a ticker's chart
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Tag {
+    pub exchange: Exchange,
+    pub ticker: Ticker,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub label: String,
+}
+
+impl Tag {
+    pub fn day(&self) -> chrono::NaiveDate {
+        chrono::DateTime::from_timestamp_millis(self.start_time as i64)
+            .unwrap_or_default()
+            .date_naive()
+    }
+
+    pub fn mid_time(&self) -> u64 {
+        self.start_time + (self.end_time.saturating_sub(self.start_time)) / 2
+    }
+}
+
+/// Tags are kept in their own file inside the journal folder, next to the scheduled snapshots
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Journal {
+    pub tags: Vec<Tag>,
+}
+
+impl Journal {
+    pub fn load() -> Self {
+        let path = journal_path(Some(TAGS_FILE));
+
+        match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+                log::error!("Failed to parse journal tags: {err}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), InternalError> {
+        std::fs::create_dir_all(journal_path(None)).map_err(|err| {
+            InternalError::Journal(format!("Failed to create journal folder: {err}"))
+        })?;
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|err| InternalError::Journal(format!("Failed to serialize tags: {err}")))?;
+
+        std::fs::write(journal_path(Some(TAGS_FILE)), json)
+            .map_err(|err| InternalError::Journal(format!("Failed to write tags: {err}")))
+    }
+
+    pub fn add(&mut self, tag: Tag) {
+        let index = self
+            .tags
+            .partition_point(|existing| existing.start_time <= tag.start_time);
+        self.tags.insert(index, tag);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Tag> {
+        (index < self.tags.len()).then(|| self.tags.remove(index))
+    }
+
+    pub fn tags_for(&self, exchange: Exchange, ticker: Ticker) -> impl Iterator<Item = &Tag> {
+        self.tags
+            .iter()
+            .filter(move |tag| tag.exchange == exchange && tag.ticker == ticker)
+    }
+
+    /// Tags with their indices, grouped by the UTC day they start on, latest day first
+    pub fn by_day(&self) -> Vec<(chrono::NaiveDate, Vec<(usize, &Tag)>)> {
+        let mut days: BTreeMap<chrono::NaiveDate, Vec<(usize, &Tag)>> = BTreeMap::new();
+
+        for (index, tag) in self.tags.iter().enumerate() {
+            days.entry(tag.day()).or_default().push((index, tag));
+        }
+
+        days.into_iter().rev().collect()
+    }
+}
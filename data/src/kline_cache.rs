@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use exchange::{
+    Kline, Ticker, Timeframe,
+    adapter::{Exchange, KlineSource},
+};
+
+use crate::data_path;
+
+/// Closed klines of every fetch are kept here, so reopening a chart only fetches what's new
+const KLINES_FOLDER: &str = "market_data/klines";
+/// Bars a chart opens with, the same amount the exchanges serve without a range
+const LATEST_BARS: u64 = 400;
+/// Oldest bars are dropped past this, a single file is rewritten on every fetch
+const MAX_CACHED_BARS: usize = 20_000;
+
+/// Open, high, low, close and the buy and sell volume of a bar, keyed by its open time
+type CachedKline = (f32, f32, f32, f32, (f32, f32));
+
+fn cache_path(
+    exchange: Exchange,
+    ticker: Ticker,
+    timeframe: Timeframe,
+    source: KlineSource,
+) -> PathBuf {
+    let (symbol, _) = ticker.to_full_symbol_and_type();
+
+    data_path(Some(&format!("{KLINES_FOLDER}/{exchange:?}")))
+        .join(format!("{symbol}_{timeframe}_{source:?}.json"))
+}
+
+/// Cached klines of a ticker's stream, empty if none were stored yet
+pub fn load(
+    exchange: Exchange,
+    ticker: Ticker,
+    timeframe: Timeframe,
+    source: KlineSource,
+) -> BTreeMap<u64, Kline> {
+    let path = cache_path(exchange, ticker, timeframe, source);
+
+    let Ok(json) = std::fs::read_to_string(&path) else {
+        return BTreeMap::new();
+    };
+
+    match serde_json::from_str::<BTreeMap<u64, CachedKline>>(&json) {
+        Ok(cached) => cached
+            .into_iter()
+            .map(|(time, (open, high, low, close, volume))| {
+                (
+                    time,
+                    Kline {
+                        time,
+                        open,
+                        high,
+                        low,
+                        close,
+                        volume,
+                    },
+                )
+            })
+            .collect(),
+        Err(err) => {
+            log::warn!("Failed to parse kline cache {path:?}: {err}");
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Merges the closed bars of a fetch into `cached` and writes it out, the bar still forming
+/// at `now` is left out as it keeps changing
+pub fn store(
+    exchange: Exchange,
+    ticker: Ticker,
+    timeframe: Timeframe,
+    source: KlineSource,
+    cached: &mut BTreeMap<u64, Kline>,
+    fetched: &[Kline],
+    now: u64,
+) -> std::io::Result<()> {
+    let forming = timeframe.open_time(now);
+
+    let len_before = cached.len();
+    cached.extend(
+        fetched
+            .iter()
+            .filter(|kline| kline.time < forming)
+            .map(|kline| (kline.time, *kline)),
+    );
+
+    if cached.len() == len_before {
+        return Ok(());
+    }
+
+    while cached.len() > MAX_CACHED_BARS {
+        cached.pop_first();
+    }
+
+    let path = cache_path(exchange, ticker, timeframe, source);
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder)?;
+    }
+
+    let rows = cached
+        .iter()
+        .map(|(time, kline)| {
+            (
+                *time,
+                (kline.open, kline.high, kline.low, kline.close, kline.volume),
+            )
+        })
+        .collect::<BTreeMap<u64, CachedKline>>();
+
+    // written aside first, two panes of the same stream may be storing at once
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, serde_json::to_string(&rows)?)?;
+    std::fs::rename(&temp_path, &path)
+}
+
+/// What has to be fetched on top of the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Missing {
+    /// The cache covers all of it
+    Nothing,
+    /// Only the bars opening within these times
+    Range(u64, u64),
+    /// The cache can't help, the fetch goes out as asked
+    Everything,
+}
+
+/// Bars of `range` the cache doesn't have, or the ones after its latest bar without a range.
+/// The bar forming at `now` is never cached, so a range reaching it always fetches that one.
+pub fn missing(
+    cached: &BTreeMap<u64, Kline>,
+    timeframe: Timeframe,
+    range: Option<(u64, u64)>,
+    now: u64,
+) -> Missing {
+    let interval = timeframe.to_milliseconds();
+
+    let Some((from, to)) = range else {
+        return match cached.keys().next_back() {
+            Some(latest) if latest + LATEST_BARS * interval >= now => {
+                Missing::Range(latest + interval, now)
+            }
+            _ => Missing::Everything,
+        };
+    };
+
+    let mut first_missing = None;
+    let mut last_missing = None;
+
+    let mut time = timeframe.open_time(from);
+    if time < from {
+        time += interval;
+    }
+
+    while time <= to {
+        if !cached.contains_key(&time) {
+            first_missing.get_or_insert(time);
+            last_missing = Some(time);
+        }
+        time += interval;
+    }
+
+    match (first_missing, last_missing) {
+        (Some(first), Some(last)) => Missing::Range(first, last),
+        _ => Missing::Nothing,
+    }
+}
+
+/// Cached klines within `range`, or the unbroken run of the latest ones without one
+pub fn in_range(
+    cached: &BTreeMap<u64, Kline>,
+    timeframe: Timeframe,
+    range: Option<(u64, u64)>,
+) -> Vec<Kline> {
+    if let Some((from, to)) = range {
+        return cached.range(from..=to).map(|(_, kline)| *kline).collect();
+    }
+
+    let interval = timeframe.to_milliseconds();
+
+    let mut latest = cached
+        .values()
+        .rev()
+        .take(LATEST_BARS as usize)
+        .collect::<Vec<_>>();
+
+    // older bars past a gap would leave a hole in the chart
+    if let Some(gap) = latest
+        .windows(2)
+        .position(|pair| pair[0].time - pair[1].time > interval)
+    {
+        latest.truncate(gap + 1);
+    }
+
+    latest.into_iter().rev().copied().collect()
+}
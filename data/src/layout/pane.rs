@@ -1,4 +1,7 @@
-use exchange::{TickMultiplier, TickerInfo, adapter::StreamKind};
+use exchange::{
+    TickMultiplier, TickerInfo,
+    adapter::{Exchange, KlineSource, StreamKind},
+};
 use serde::{Deserialize, Serialize};
 
 use crate::util::ok_or_default;
@@ -40,6 +43,8 @@ pub enum Pane {
         indicators: Vec<HeatmapIndicator>,
         #[serde(deserialize_with = "ok_or_default", default)]
         link_group: Option<LinkGroup>,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        snapshot: Option<String>,
     },
     KlineChart {
         layout: ViewConfig,
@@ -59,6 +64,24 @@ pub enum Pane {
         #[serde(deserialize_with = "ok_or_default", default)]
         link_group: Option<LinkGroup>,
     },
+    DomLadder {
+        stream_type: Vec<StreamKind>,
+        settings: Settings,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        link_group: Option<LinkGroup>,
+    },
+    Watchlist {
+        #[serde(deserialize_with = "ok_or_default", default)]
+        tickers: Vec<TickerInfo>,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        link_group: Option<LinkGroup>,
+    },
+    WhaleTape {
+        /// Minimum notional of the prints shown, in quote currency
+        threshold: f32,
+        #[serde(deserialize_with = "ok_or_default", default)]
+        link_group: Option<LinkGroup>,
+    },
 }
 
 impl Default for Pane {
@@ -74,6 +97,20 @@ pub struct Settings {
     pub tick_multiply: Option<TickMultiplier>,
     pub visual_config: Option<VisualConfig>,
     pub selected_basis: Option<Basis>,
+    /// Include the pane in scheduled snapshots
+    pub auto_snapshot: bool,
+    /// Stamp the ticker and timeframe on charts exported as PNG
+    pub watermark_exports: bool,
+    /// Market the pane switches its streams to while the ticker's own exchange is down
+    pub failover: Option<Exchange>,
+    /// Ticker a spread chart combines its own ticker's klines with
+    pub spread_leg: Option<TickerInfo>,
+    /// Price a candlestick chart of a perpetual is drawn from
+    pub kline_source: KlineSource,
+    /// Title bar and notifications are hidden, leaving only the content for screen capture
+    pub clean_mode: bool,
+    /// Clean mode hides a chart's axes as well
+    pub clean_hides_axes: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -1,9 +1,19 @@
 pub mod aggr;
+pub mod alert;
 pub mod audio;
+pub mod backup;
 pub mod chart;
 pub mod config;
+pub mod crash;
+pub mod diagnostics;
+pub mod journal;
+pub mod kline_cache;
 pub mod layout;
 pub mod log;
+pub mod metrics;
+pub mod oi_cache;
+pub mod recorder;
+pub mod ticker_specs;
 pub mod util;
 
 use std::fs::File;
@@ -16,6 +26,7 @@ pub use config::sidebar::{self, Sidebar};
 pub use config::state::{Layouts, State};
 pub use config::theme::Theme;
 pub use config::timezone::UserTimezone;
+pub use config::validation::InvalidField;
 
 use ::log::{error, info, warn};
 pub use layout::{Dashboard, Layout, Pane};
@@ -28,6 +39,16 @@ pub enum InternalError {
     Fetch(String),
     #[error("Layout error: {0}")]
     Layout(String),
+    #[error("Recorder error: {0}")]
+    Recorder(String),
+    #[error("Backup error: {0}")]
+    Backup(String),
+    #[error("Snapshot error: {0}")]
+    Snapshot(String),
+    #[error("Journal error: {0}")]
+    Journal(String),
+    #[error("Diagnostics error: {0}")]
+    Diagnostics(String),
 }
 
 pub fn write_json_to_file(json: &str, file_name: &str) -> std::io::Result<()> {
@@ -37,7 +58,9 @@ pub fn write_json_to_file(json: &str, file_name: &str) -> std::io::Result<()> {
     Ok(())
 }
 
-pub fn read_from_file(file_name: &str) -> Result<State, Box<dyn std::error::Error>> {
+pub fn read_from_file(
+    file_name: &str,
+) -> Result<(State, Vec<InvalidField>), Box<dyn std::error::Error>> {
     let path = data_path(Some(file_name));
 
     let file_open_result = File::open(&path);
@@ -51,20 +74,18 @@ pub fn read_from_file(file_name: &str) -> Result<State, Box<dyn std::error::Erro
         return Err(Box::new(e));
     }
 
-    match serde_json::from_str(&contents) {
-        Ok(state) => Ok(state),
+    match config::migration::parse_state(&contents) {
+        Ok((state, invalid_fields)) => {
+            for field in &invalid_fields {
+                warn!("Reset invalid saved state section {field}");
+            }
+            Ok((state, invalid_fields))
+        }
         Err(e) => {
             // If parsing fails, backup the file
             drop(file); // Close the file before renaming
 
-            // Create backup file with different name to prevent overwriting it
-            let backup_file_name = if let Some(pos) = file_name.rfind('.') {
-                format!("{}_old{}", &file_name[..pos], &file_name[pos..])
-            } else {
-                format!("{}_old", file_name)
-            };
-
-            let backup_path = data_path(Some(&backup_file_name));
+            let backup_path = data_path(Some(&backup_file_name(file_name)));
 
             if let Err(rename_err) = std::fs::rename(&path, &backup_path) {
                 warn!(
@@ -85,6 +106,56 @@ pub fn read_from_file(file_name: &str) -> Result<State, Box<dyn std::error::Erro
     }
 }
 
+/// Reads only the renderer section of the saved state, without the side effects of
+/// [`read_from_file`], since the renderer has to be configured before the app boots
+pub fn read_renderer_settings() -> config::RendererSettings {
+    read_saved_section("renderer")
+}
+
+/// Panics can happen before the saved state is loaded, so whether to report them is read first
+pub fn read_crash_reports() -> bool {
+    read_saved_section("crash_reports")
+}
+
+/// The default text size is an app setting, so the density is also needed before booting
+pub fn read_ui_density() -> config::UiDensity {
+    read_saved_section("ui_density")
+}
+
+fn read_saved_section<T: serde::de::DeserializeOwned + Default>(key: &str) -> T {
+    std::fs::read_to_string(data_path(Some(SAVED_STATE_PATH)))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .and_then(|mut state| serde_json::from_value(state.get_mut(key)?.take()).ok())
+        .unwrap_or_default()
+}
+
+/// Copies the file next to itself so it survives being overwritten on exit
+pub fn backup_file(file_name: &str) -> Result<PathBuf, InternalError> {
+    let path = data_path(Some(file_name));
+    let backup_path = data_path(Some(&backup_file_name(file_name)));
+
+    std::fs::copy(&path, &backup_path).map_err(|err| {
+        InternalError::Layout(format!("Failed to backup '{}': {err}", path.display()))
+    })?;
+
+    info!(
+        "Backed up '{}' to '{}'",
+        path.display(),
+        backup_path.display()
+    );
+    Ok(backup_path)
+}
+
+fn backup_file_name(file_name: &str) -> String {
+    // Create backup file with different name to prevent overwriting it
+    if let Some(pos) = file_name.rfind('.') {
+        format!("{}_old{}", &file_name[..pos], &file_name[pos..])
+    } else {
+        format!("{}_old", file_name)
+    }
+}
+
 pub fn open_data_folder() -> Result<(), InternalError> {
     let pathbuf = data_path(None);
 
@@ -119,6 +190,15 @@ pub fn data_path(path_name: Option<&str>) -> PathBuf {
     }
 }
 
+/// Folder where automatic chart snapshots and journal entries are kept
+pub fn journal_path(file_name: Option<&str>) -> PathBuf {
+    let folder = data_path(Some("journal"));
+    match file_name {
+        Some(file_name) => folder.join(file_name),
+        None => folder,
+    }
+}
+
 fn cleanup_directory(data_path: &PathBuf) -> usize {
     if !data_path.exists() {
         warn!("Data path {:?} does not exist, skipping cleanup", data_path);
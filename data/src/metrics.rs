@@ -0,0 +1,373 @@
+use std::collections::BTreeMap;
+
+use exchange::Kline;
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// How many previous days the relative volume baseline is averaged over
+pub const RVOL_LOOKBACK_DAYS: u64 = 10;
+
+/// Span of history a bar needs behind it for a full relative volume baseline
+pub fn rvol_baseline_span(interval: u64, days: u64) -> u64 {
+    days * DAY_MS.max(interval)
+}
+
+/// Relative volume of each bar: its volume divided by the average volume of the bars
+/// at the same time-of-day over the previous `days` days.
+///
+/// Bars without any baseline yet (the first day of loaded data) are left out.
+pub fn relative_volume(
+    volumes: &BTreeMap<u64, (f32, f32)>,
+    interval: u64,
+    days: u64,
+) -> BTreeMap<u64, f32> {
+    volumes
+        .keys()
+        .filter_map(|time| {
+            relative_volume_at(volumes, *time, interval, days).map(|rvol| (*time, rvol))
+        })
+        .collect()
+}
+
+pub fn relative_volume_at(
+    volumes: &BTreeMap<u64, (f32, f32)>,
+    time: u64,
+    interval: u64,
+    days: u64,
+) -> Option<f32> {
+    let (buy, sell) = volumes.get(&time)?;
+
+    // same time-of-day on daily and higher timeframes is simply the previous bars
+    let step = DAY_MS.max(interval);
+
+    let (sum, count) = (1..=days)
+        .filter_map(|day| time.checked_sub(day * step))
+        .filter_map(|prev| volumes.get(&prev))
+        .fold((0.0, 0), |(sum, count), (buy, sell)| {
+            (sum + total_volume(*buy, *sell), count + 1)
+        });
+
+    if count == 0 || sum <= 0.0 {
+        return None;
+    }
+
+    Some(total_volume(*buy, *sell) / (sum / count as f32))
+}
+
+/// Klines without a taker side split carry `-1.0` as buy volume and the total as sell volume
+pub(crate) fn total_volume(buy: f32, sell: f32) -> f32 {
+    if buy == -1.0 { sell } else { buy + sell }
+}
+
+/// Today's cumulative volume against the average cumulative volume of the previous `days` days
+/// up to the same time-of-day, computed from hourly (or finer) klines.
+///
+/// The bar in progress at `now`'s time-of-day is prorated for the baseline days so a partial
+/// hour today isn't compared against complete hours.
+pub fn session_relative_volume(
+    klines: &[Kline],
+    interval: u64,
+    now: u64,
+    days: u64,
+) -> Option<f32> {
+    let today_start = now - (now % DAY_MS);
+    let elapsed = now - today_start;
+
+    let cumulative_until = |day_start: u64| -> f32 {
+        klines
+            .iter()
+            .filter(|kline| kline.time >= day_start && kline.time < day_start + elapsed)
+            .map(|kline| {
+                let volume = total_volume(kline.volume.0, kline.volume.1);
+                let bar_end = kline.time + interval;
+
+                if day_start == today_start || bar_end <= day_start + elapsed {
+                    volume
+                } else {
+                    volume * ((day_start + elapsed - kline.time) as f32 / interval as f32)
+                }
+            })
+            .sum()
+    };
+
+    let baselines = (1..=days)
+        .filter_map(|day| today_start.checked_sub(day * DAY_MS))
+        .filter(|day_start| {
+            klines
+                .iter()
+                .any(|kline| (*day_start..day_start + DAY_MS).contains(&kline.time))
+        })
+        .map(cumulative_until)
+        .filter(|volume| *volume > 0.0)
+        .collect::<Vec<f32>>();
+
+    if baselines.is_empty() {
+        return None;
+    }
+
+    let average = baselines.iter().sum::<f32>() / baselines.len() as f32;
+
+    Some(cumulative_until(today_start) / average)
+}
+
+/// Number of daily bars ATR and realized volatility are measured over
+pub const VOLATILITY_PERIOD: usize = 14;
+
+/// Average true range of the last `period` daily klines, as a percentage of the last close
+pub fn atr_pct(daily_klines: &[Kline], period: usize) -> Option<f32> {
+    if daily_klines.len() < period + 1 {
+        return None;
+    }
+
+    let window = &daily_klines[daily_klines.len() - period - 1..];
+
+    let atr = window
+        .windows(2)
+        .map(|pair| {
+            let (prev_close, kline) = (pair[0].close, &pair[1]);
+
+            (kline.high - kline.low)
+                .max((kline.high - prev_close).abs())
+                .max((kline.low - prev_close).abs())
+        })
+        .sum::<f32>()
+        / period as f32;
+
+    let last_close = window.last()?.close;
+
+    (last_close > 0.0).then(|| atr / last_close * 100.0)
+}
+
+/// Annualized standard deviation of the last `period` daily log returns, in percent.
+/// Crypto trades every day, so a year is 365 bars.
+pub fn realized_volatility(daily_klines: &[Kline], period: usize) -> Option<f32> {
+    if daily_klines.len() < period + 1 || period < 2 {
+        return None;
+    }
+
+    let returns = daily_klines[daily_klines.len() - period - 1..]
+        .windows(2)
+        .filter(|pair| pair[0].close > 0.0 && pair[1].close > 0.0)
+        .map(|pair| (pair[1].close / pair[0].close).ln())
+        .collect::<Vec<f32>>();
+
+    if returns.len() < 2 {
+        return None;
+    }
+
+    let mean = returns.iter().sum::<f32>() / returns.len() as f32;
+    let variance =
+        returns.iter().map(|r| (r - mean).powi(2)).sum::<f32>() / (returns.len() - 1) as f32;
+
+    Some(variance.sqrt() * 365.0_f32.sqrt() * 100.0)
+}
+
+/// Simple moving average, the first `period - 1` values have none
+pub fn sma(values: &BTreeMap<u64, f32>, period: usize) -> BTreeMap<u64, f32> {
+    window_average(values, period, |window| {
+        window.iter().sum::<f32>() / period as f32
+    })
+}
+
+/// Linearly weighted moving average, the latest value weighs `period` times the oldest
+pub fn wma(values: &BTreeMap<u64, f32>, period: usize) -> BTreeMap<u64, f32> {
+    window_average(values, period, weighted_average)
+}
+
+/// Weighted average of a window of values in chronological order
+pub fn weighted_average(window: &[f32]) -> f32 {
+    let weights = (window.len() * (window.len() + 1) / 2) as f32;
+
+    window
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| value * (idx + 1) as f32)
+        .sum::<f32>()
+        / weights
+}
+
+fn window_average(
+    values: &BTreeMap<u64, f32>,
+    period: usize,
+    average: impl Fn(&[f32]) -> f32,
+) -> BTreeMap<u64, f32> {
+    if period == 0 {
+        return BTreeMap::new();
+    }
+
+    let (times, values): (Vec<u64>, Vec<f32>) = values.iter().map(|(t, v)| (*t, *v)).unzip();
+
+    values
+        .windows(period)
+        .zip(times.iter().skip(period - 1))
+        .map(|(window, time)| (*time, average(window)))
+        .collect()
+}
+
+/// Exponential moving average seeded with the simple average of the first `period` values
+pub fn ema(values: &BTreeMap<u64, f32>, period: usize) -> BTreeMap<u64, f32> {
+    if period == 0 || values.len() < period {
+        return BTreeMap::new();
+    }
+
+    let alpha = 2.0 / (period as f32 + 1.0);
+    let seed = values.values().take(period).sum::<f32>() / period as f32;
+
+    let mut last = seed;
+    let mut output = BTreeMap::new();
+
+    for (idx, (time, value)) in values.iter().enumerate().skip(period - 1) {
+        if idx >= period {
+            last = alpha * value + (1.0 - alpha) * last;
+        }
+        output.insert(*time, last);
+    }
+
+    output
+}
+
+/// Wilder's relative strength index over `period` bars, from 0 to 100
+pub fn rsi(closes: &BTreeMap<u64, f32>, period: usize) -> BTreeMap<u64, f32> {
+    rsi_averages(closes, period)
+        .into_iter()
+        .map(|(time, (avg_gain, avg_loss))| (time, rsi_value(avg_gain, avg_loss)))
+        .collect()
+}
+
+/// Wilder's smoothed average gain and loss at each bar, which [`rsi`] is taken from
+pub fn rsi_averages(closes: &BTreeMap<u64, f32>, period: usize) -> BTreeMap<u64, (f32, f32)> {
+    if period == 0 || closes.len() <= period {
+        return BTreeMap::new();
+    }
+
+    let changes = closes
+        .keys()
+        .skip(1)
+        .zip(closes.values().zip(closes.values().skip(1)))
+        .map(|(time, (prev, close))| (*time, close - prev))
+        .collect::<Vec<(u64, f32)>>();
+
+    let (avg_gain, avg_loss) = changes[..period]
+        .iter()
+        .fold((0.0, 0.0), |(gain, loss), (_, change)| {
+            (gain + change.max(0.0), loss + (-change).max(0.0))
+        });
+    let mut averages = (avg_gain / period as f32, avg_loss / period as f32);
+
+    let mut output = BTreeMap::new();
+    output.insert(changes[period - 1].0, averages);
+
+    for (time, change) in &changes[period..] {
+        averages = rsi_next(averages, *change, period);
+        output.insert(*time, averages);
+    }
+
+    output
+}
+
+/// Averages of a bar carried on from the ones of the bar before it by its change in close
+pub fn rsi_next((avg_gain, avg_loss): (f32, f32), change: f32, period: usize) -> (f32, f32) {
+    (
+        (avg_gain * (period - 1) as f32 + change.max(0.0)) / period as f32,
+        (avg_loss * (period - 1) as f32 + (-change).max(0.0)) / period as f32,
+    )
+}
+
+pub fn rsi_value(avg_gain: f32, avg_loss: f32) -> f32 {
+    if avg_loss == 0.0 {
+        if avg_gain == 0.0 { 50.0 } else { 100.0 }
+    } else {
+        100.0 - 100.0 / (1.0 + avg_gain / avg_loss)
+    }
+}
+
+/// Simple moving average of `period` values with the population standard deviation of
+/// the same window, the bands lie `multiplier` deviations away from it
+pub fn bollinger_bands(
+    values: &BTreeMap<u64, f32>,
+    period: usize,
+    multiplier: f32,
+) -> BTreeMap<u64, (f32, f32, f32)> {
+    if period == 0 {
+        return BTreeMap::new();
+    }
+
+    let (times, values): (Vec<u64>, Vec<f32>) = values.iter().map(|(t, v)| (*t, *v)).unzip();
+
+    values
+        .windows(period)
+        .zip(times.iter().skip(period - 1))
+        .map(|(window, time)| {
+            let mean = window.iter().sum::<f32>() / period as f32;
+            let std_dev =
+                (window.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / period as f32).sqrt();
+
+            (
+                *time,
+                (
+                    mean - multiplier * std_dev,
+                    mean,
+                    mean + multiplier * std_dev,
+                ),
+            )
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Divergence {
+    /// New high while the cumulative delta stays under its earlier peak
+    Bearish,
+    /// New low while the cumulative delta stays over its earlier trough
+    Bullish,
+}
+
+/// Bars making a new high or low over the `lookback` bars before them while the cumulative
+/// delta doesn't follow, it has to fall short of its own extreme by `threshold` percent of
+/// its range over the same bars. Takes the high, low and delta of each bar.
+pub fn delta_divergences(
+    bars: &BTreeMap<u64, (f32, f32, f32)>,
+    lookback: usize,
+    threshold: usize,
+) -> BTreeMap<u64, Divergence> {
+    if lookback == 0 || bars.len() <= lookback {
+        return BTreeMap::new();
+    }
+
+    let mut cumulative_delta = 0.0;
+    let points = bars
+        .iter()
+        .map(|(time, (high, low, delta))| {
+            cumulative_delta += delta;
+            (*time, *high, *low, cumulative_delta)
+        })
+        .collect::<Vec<_>>();
+
+    let min_gap = threshold as f32 / 100.0;
+
+    points
+        .windows(lookback + 1)
+        .filter_map(|window| {
+            let (time, high, low, cvd) = window[lookback];
+            let prior = &window[..lookback];
+
+            let prior_high = prior.iter().map(|p| p.1).fold(f32::MIN, f32::max);
+            let prior_low = prior.iter().map(|p| p.2).fold(f32::MAX, f32::min);
+            let cvd_peak = prior.iter().map(|p| p.3).fold(f32::MIN, f32::max);
+            let cvd_trough = prior.iter().map(|p| p.3).fold(f32::MAX, f32::min);
+
+            let cvd_range = cvd_peak - cvd_trough;
+            if cvd_range <= 0.0 {
+                return None;
+            }
+
+            if high > prior_high && (cvd_peak - cvd) / cvd_range >= min_gap {
+                Some((time, Divergence::Bearish))
+            } else if low < prior_low && (cvd - cvd_trough) / cvd_range >= min_gap {
+                Some((time, Divergence::Bullish))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
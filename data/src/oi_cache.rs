@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use exchange::{OpenInterest, Ticker, Timeframe, adapter::Exchange};
+
+use crate::data_path;
+
+/// Every fetched open interest point is kept here, Binance only serves the last 30 days of
+/// them so older history can only come from earlier fetches
+const OI_FOLDER: &str = "market_data/open_interest";
+
+fn cache_path(exchange: Exchange, ticker: Ticker, timeframe: Timeframe) -> PathBuf {
+    let (symbol, _) = ticker.to_full_symbol_and_type();
+
+    data_path(Some(&format!("{OI_FOLDER}/{exchange:?}"))).join(format!("{symbol}_{timeframe}.json"))
+}
+
+/// Cached points of a ticker's open interest at a period, empty if none were stored yet
+pub fn load(exchange: Exchange, ticker: Ticker, timeframe: Timeframe) -> BTreeMap<u64, f32> {
+    let path = cache_path(exchange, ticker, timeframe);
+
+    match std::fs::read_to_string(&path) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+            log::warn!("Failed to parse open interest cache {path:?}: {err}");
+            BTreeMap::new()
+        }),
+        Err(_) => BTreeMap::new(),
+    }
+}
+
+/// Merges freshly fetched points into the cache, returning everything it holds afterwards
+pub fn store(
+    exchange: Exchange,
+    ticker: Ticker,
+    timeframe: Timeframe,
+    fetched: &[OpenInterest],
+) -> std::io::Result<BTreeMap<u64, f32>> {
+    let mut cached = load(exchange, ticker, timeframe);
+
+    let len_before = cached.len();
+    cached.extend(fetched.iter().map(|oi| (oi.time, oi.value)));
+
+    if cached.len() > len_before {
+        let path = cache_path(exchange, ticker, timeframe);
+        if let Some(folder) = path.parent() {
+            std::fs::create_dir_all(folder)?;
+        }
+
+        // written aside first, two panes of the same ticker may be storing at once
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string(&cached)?)?;
+        std::fs::rename(&temp_path, &path)?;
+    }
+
+    Ok(cached)
+}
+
+/// Cached points within `range`, or all of them without one
+pub fn in_range(cached: &BTreeMap<u64, f32>, range: Option<(u64, u64)>) -> Vec<OpenInterest> {
+    let (from, to) = range.unwrap_or((0, u64::MAX));
+
+    cached
+        .range(from..=to)
+        .map(|(time, value)| OpenInterest {
+            time: *time,
+            value: *value,
+        })
+        .collect()
+}
@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, NaiveDate};
+use exchange::{Ticker, Trade, adapter::Exchange, depth::Depth};
+use ordered_float::OrderedFloat;
+
+use crate::{InternalError, data_path};
+
+const RECORDINGS_FOLDER: &str = "market_data/recordings";
+/// Pending records get compressed into a frame and appended this often
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+/// Orderbooks are sampled rather than stored on every update, trades in between are all kept
+const DEPTH_INTERVAL_MS: u64 = 1000;
+/// Levels stored per side, nearest to the spread, enough for what a heatmap shows around price
+const DEPTH_LEVELS: usize = 250;
+const ZSTD_LEVEL: i32 = 3;
+
+const TRADES_TAG: u8 = 0;
+const DEPTH_TAG: u8 = 1;
+
+/// Depth snapshot restored from a recording, with the trades that came in since the previous one
+#[derive(Debug, Clone)]
+pub struct RecordedPoint {
+    pub time: u64,
+    pub depth: Depth,
+    pub trades: Box<[Trade]>,
+}
+
+#[derive(Default)]
+struct Pending {
+    bytes: Vec<u8>,
+    last_depth_time: u64,
+}
+
+/// Appends the trades and sampled orderbooks of the depth streams to one zstd compressed file
+/// per ticker and UTC day, so charts can be rebuilt from them after a restart
+pub struct Recorder {
+    pending: HashMap<(Exchange, Ticker, NaiveDate), Pending>,
+    last_flush: Instant,
+}
+
+/// Compressed records ready to be appended, written off the UI thread
+pub struct Batch(Vec<(PathBuf, Vec<u8>)>);
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self {
+            pending: HashMap::new(),
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Trades that came in with the depth update at `depth_update_t`, recorded before its book
+    pub fn record_trades(
+        &mut self,
+        exchange: Exchange,
+        ticker: Ticker,
+        depth_update_t: u64,
+        trades: &[Trade],
+    ) {
+        if trades.is_empty() {
+            return;
+        }
+        let Some(pending) = self.pending(exchange, ticker, depth_update_t) else {
+            return;
+        };
+
+        pending.bytes.push(TRADES_TAG);
+        pending.bytes.extend(depth_update_t.to_le_bytes());
+        pending.bytes.extend((trades.len() as u32).to_le_bytes());
+
+        for trade in trades {
+            pending.bytes.extend(trade.time.to_le_bytes());
+            pending.bytes.extend(trade.price.to_le_bytes());
+            pending.bytes.extend(trade.qty.to_le_bytes());
+            pending.bytes.push(u8::from(trade.is_sell));
+        }
+    }
+
+    /// Orderbook of a depth update, kept once per [`DEPTH_INTERVAL_MS`]
+    pub fn record_depth(
+        &mut self,
+        exchange: Exchange,
+        ticker: Ticker,
+        depth_update_t: u64,
+        depth: &Depth,
+    ) {
+        let Some(pending) = self.pending(exchange, ticker, depth_update_t) else {
+            return;
+        };
+
+        if depth_update_t.saturating_sub(pending.last_depth_time) >= DEPTH_INTERVAL_MS {
+            pending.last_depth_time = depth_update_t;
+
+            pending.bytes.push(DEPTH_TAG);
+            pending.bytes.extend(depth_update_t.to_le_bytes());
+            encode_levels(&mut pending.bytes, depth.bids.iter().rev());
+            encode_levels(&mut pending.bytes, depth.asks.iter());
+        }
+    }
+
+    fn pending(&mut self, exchange: Exchange, ticker: Ticker, time: u64) -> Option<&mut Pending> {
+        let date = DateTime::from_timestamp_millis(time as i64)?.date_naive();
+        Some(self.pending.entry((exchange, ticker, date)).or_default())
+    }
+
+    /// Everything recorded since the last flush, once [`FLUSH_INTERVAL`] has passed
+    pub fn take_due(&mut self, now: Instant) -> Option<Batch> {
+        if now.saturating_duration_since(self.last_flush) < FLUSH_INTERVAL {
+            return None;
+        }
+        self.take_all(now)
+    }
+
+    /// Everything recorded since the last flush regardless of the interval, e.g. before exiting
+    pub fn take_all(&mut self, now: Instant) -> Option<Batch> {
+        self.last_flush = now;
+
+        let files = self
+            .pending
+            .iter_mut()
+            .filter(|(_, pending)| !pending.bytes.is_empty())
+            .map(|((exchange, ticker, date), pending)| {
+                (
+                    recording_path(*exchange, *ticker, Some(*date)),
+                    std::mem::take(&mut pending.bytes),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // days that ended only keep their sampling state around until their last flush
+        let today = chrono::Utc::now().date_naive();
+        self.pending.retain(|(_, _, date), _| *date >= today);
+
+        (!files.is_empty()).then_some(Batch(files))
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Batch {
+    /// Compresses each file's records into a zstd frame appended to it, zstd readers go
+    /// through concatenated frames as one stream
+    pub fn write(self) -> Result<(), InternalError> {
+        for (path, bytes) in self.0 {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|err| {
+                    InternalError::Recorder(format!("Failed to create recordings folder: {err}"))
+                })?;
+            }
+
+            let frame = zstd::encode_all(bytes.as_slice(), ZSTD_LEVEL).map_err(|err| {
+                InternalError::Recorder(format!("Failed to compress recording: {err}"))
+            })?;
+
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| file.write_all(&frame))
+                .map_err(|err| {
+                    InternalError::Recorder(format!(
+                        "Failed to write recording {}: {err}",
+                        path.display()
+                    ))
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+fn recording_path(exchange: Exchange, ticker: Ticker, date: Option<NaiveDate>) -> PathBuf {
+    let (symbol, _) = ticker.to_full_symbol_and_type();
+    let folder = data_path(Some(&format!("{RECORDINGS_FOLDER}/{exchange:?}/{symbol}")));
+
+    match date {
+        Some(date) => folder.join(format!("{}.zst", date.format("%Y-%m-%d"))),
+        None => folder,
+    }
+}
+
+/// Days with a recording of the ticker, oldest first, none while the market is on its testnet
+/// as only the real market gets recorded
+pub fn recorded_dates(exchange: Exchange, ticker: Ticker) -> Vec<NaiveDate> {
+    if exchange::adapter::is_testnet(exchange) {
+        return vec![];
+    }
+
+    let Ok(entries) = std::fs::read_dir(recording_path(exchange, ticker, None)) else {
+        return vec![];
+    };
+
+    let mut dates = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let stem = path.file_stem()?.to_str()?;
+            NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()
+        })
+        .collect::<Vec<_>>();
+
+    dates.sort();
+    dates
+}
+
+/// Recorded points of the ticker from `since` onwards, read from the files of the days it spans
+pub fn load_since(
+    exchange: Exchange,
+    ticker: Ticker,
+    since: u64,
+) -> Result<Vec<RecordedPoint>, InternalError> {
+    let Some(first_day) =
+        DateTime::from_timestamp_millis(since as i64).map(|datetime| datetime.date_naive())
+    else {
+        return Ok(vec![]);
+    };
+
+    let mut points = vec![];
+
+    for date in recorded_dates(exchange, ticker)
+        .into_iter()
+        .filter(|date| *date >= first_day)
+    {
+        let path = recording_path(exchange, ticker, Some(date));
+
+        let bytes = std::fs::File::open(&path)
+            .and_then(zstd::decode_all)
+            .map_err(|err| {
+                InternalError::Recorder(format!(
+                    "Failed to read recording {}: {err}",
+                    path.display()
+                ))
+            })?;
+
+        decode_points(&bytes, since, &mut points);
+    }
+
+    Ok(points)
+}
+
+fn encode_levels<'a>(
+    bytes: &mut Vec<u8>,
+    levels: impl Iterator<Item = (&'a OrderedFloat<f32>, &'a f32)>,
+) {
+    let levels = levels.take(DEPTH_LEVELS).collect::<Vec<_>>();
+
+    bytes.extend((levels.len() as u16).to_le_bytes());
+    for (price, qty) in levels {
+        bytes.extend(price.0.to_le_bytes());
+        bytes.extend(qty.to_le_bytes());
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, rest) = self.bytes.split_first_chunk::<N>()?;
+        self.bytes = rest;
+        Some(*head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.take().map(f32::from_le_bytes)
+    }
+
+    fn levels(&mut self) -> Option<std::collections::BTreeMap<OrderedFloat<f32>, f32>> {
+        (0..self.u16()?)
+            .map(|_| Some((OrderedFloat(self.f32()?), self.f32()?)))
+            .collect()
+    }
+}
+
+/// Groups the trades of a recording under the depth snapshot that follows them, the way
+/// the live stream delivers them. A record cut off by a crash ends the decoding.
+fn decode_points(bytes: &[u8], since: u64, points: &mut Vec<RecordedPoint>) {
+    let mut reader = Reader { bytes };
+    let mut trades = vec![];
+
+    while let Some(tag) = reader.u8() {
+        let Some(time) = reader.u64() else {
+            break;
+        };
+
+        match tag {
+            TRADES_TAG => {
+                let Some(len) = reader.u32() else {
+                    break;
+                };
+
+                for _ in 0..len {
+                    let trade = (|| {
+                        Some(Trade {
+                            time: reader.u64()?,
+                            price: reader.f32()?,
+                            qty: reader.f32()?,
+                            is_sell: reader.u8()? != 0,
+                        })
+                    })();
+
+                    match trade {
+                        Some(trade) if time >= since => trades.push(trade),
+                        Some(_) => {}
+                        None => return,
+                    }
+                }
+            }
+            DEPTH_TAG => {
+                let (Some(bids), Some(asks)) = (reader.levels(), reader.levels()) else {
+                    break;
+                };
+
+                if time >= since {
+                    points.push(RecordedPoint {
+                        time,
+                        depth: Depth { bids, asks },
+                        trades: std::mem::take(&mut trades).into_boxed_slice(),
+                    });
+                }
+            }
+            _ => {
+                log::warn!("Unknown record in recording, skipping the rest of it");
+                break;
+            }
+        }
+    }
+}
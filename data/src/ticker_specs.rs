@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use exchange::{Ticker, TickerInfo, TickerSpecs, adapter::Exchange};
+
+/// Contract specs of every listed ticker, replaced market by market as their exchange info
+/// gets fetched
+#[derive(Default)]
+pub struct SpecsCache {
+    specs: HashMap<Ticker, TickerSpecs>,
+}
+
+impl SpecsCache {
+    pub fn update(&mut self, exchange: Exchange, specs: HashMap<Ticker, TickerSpecs>) {
+        self.specs.retain(|ticker, _| ticker.exchange != exchange);
+        self.specs.extend(specs);
+    }
+
+    pub fn get(&self, ticker: &Ticker) -> Option<&TickerSpecs> {
+        self.specs.get(ticker)
+    }
+}
+
+/// Label and value of each spec shown for a ticker, "-" where its venue doesn't list one
+pub fn spec_rows(info: &TickerInfo, specs: Option<&TickerSpecs>) -> Vec<(&'static str, String)> {
+    let specs = specs.copied().unwrap_or_default();
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+    vec![
+        ("Tick size", info.min_ticksize.to_string()),
+        ("Min qty", info.min_qty.to_string()),
+        (
+            "Contract size",
+            or_dash(specs.contract_size.map(|size| size.to_string())),
+        ),
+        (
+            "Funding interval",
+            or_dash(
+                specs
+                    .funding_interval_hours
+                    .map(|hours| format!("{hours}h")),
+            ),
+        ),
+        (
+            "Listed",
+            or_dash(
+                specs
+                    .listed_at
+                    .and_then(|time| chrono::DateTime::from_timestamp_millis(time as i64))
+                    .map(|time| time.format("%Y-%m-%d").to_string()),
+            ),
+        ),
+    ]
+}
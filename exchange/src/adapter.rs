@@ -1,14 +1,125 @@
 use super::{Ticker, Timeframe};
-use crate::{Kline, OpenInterest, TickerInfo, TickerStats, Trade, depth::Depth};
+use crate::{
+    FundingRate, Kline, Liquidation, OpenInterest, TickerInfo, TickerSpecs, TickerStats, Trade,
+    depth::Depth,
+};
 
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     str::FromStr,
+    sync::{
+        LazyLock, PoisonError, RwLock,
+        atomic::{AtomicU16, Ordering},
+    },
+    time::Duration,
 };
 
 pub mod binance;
 pub mod bybit;
+pub mod okx;
+
+/// One bit per exchange market whose REST and websocket calls go to its testnet
+static TESTNET: AtomicU16 = AtomicU16::new(0);
+
+fn testnet_bit(exchange: Exchange) -> u16 {
+    1 << (exchange as u16)
+}
+
+/// Only read when a connection or request is made, so switching affects new ones
+pub fn set_testnet(exchange: Exchange, enabled: bool) {
+    if enabled && exchange.supports_testnet() {
+        TESTNET.fetch_or(testnet_bit(exchange), Ordering::Relaxed);
+    } else {
+        TESTNET.fetch_and(!testnet_bit(exchange), Ordering::Relaxed);
+    }
+}
+
+pub fn is_testnet(exchange: Exchange) -> bool {
+    TESTNET.load(Ordering::Relaxed) & testnet_bit(exchange) != 0
+}
+
+/// Markets currently pointed at their testnet, in the order of `Exchange::ALL`
+pub fn testnet_markets() -> Vec<Exchange> {
+    Exchange::ALL
+        .into_iter()
+        .filter(|exchange| is_testnet(*exchange))
+        .collect()
+}
+
+/// Base addresses an exchange market's calls go to instead of its own, e.g. a regional mirror
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Endpoints {
+    /// Like `https://api.example.com`, empty keeps the default
+    pub rest: String,
+    /// Host of the websocket, with a port if it isn't 443, empty keeps the default
+    pub websocket: String,
+}
+
+impl Endpoints {
+    pub fn is_default(&self) -> bool {
+        self.rest.trim().is_empty() && self.websocket.trim().is_empty()
+    }
+}
+
+static ENDPOINTS: LazyLock<RwLock<HashMap<Exchange, Endpoints>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Only read when a connection or request is made, same as the testnet switch
+pub fn set_endpoints(exchange: Exchange, endpoints: Endpoints) {
+    let mut overrides = ENDPOINTS.write().unwrap_or_else(PoisonError::into_inner);
+
+    if endpoints.is_default() {
+        overrides.remove(&exchange);
+    } else {
+        overrides.insert(exchange, endpoints);
+    }
+}
+
+pub fn endpoints(exchange: Exchange) -> Endpoints {
+    ENDPOINTS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&exchange)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Markets with overridden endpoints, in the order of `Exchange::ALL`
+pub fn custom_endpoints() -> Vec<(Exchange, Endpoints)> {
+    Exchange::ALL
+        .into_iter()
+        .map(|exchange| (exchange, endpoints(exchange)))
+        .filter(|(_, endpoints)| !endpoints.is_default())
+        .collect()
+}
+
+/// Overridden REST base of the market, a testnet it's pointed at takes precedence
+fn rest_override(exchange: Exchange) -> Option<String> {
+    if is_testnet(exchange) {
+        return None;
+    }
+
+    let rest = endpoints(exchange).rest;
+    let rest = rest.trim().trim_end_matches('/');
+    (!rest.is_empty()).then(|| rest.to_string())
+}
+
+/// Overridden websocket host of the market, a testnet it's pointed at takes precedence
+fn ws_override(exchange: Exchange) -> Option<String> {
+    if is_testnet(exchange) {
+        return None;
+    }
+
+    let websocket = endpoints(exchange).websocket;
+    let websocket = websocket.trim();
+    let websocket = websocket
+        .strip_prefix("wss://")
+        .unwrap_or(websocket)
+        .trim_end_matches('/');
+    (!websocket.is_empty()).then(|| websocket.to_string())
+}
 
 #[derive(thiserror::Error, Debug)]
 pub enum AdapterError {
@@ -20,6 +131,58 @@ pub enum AdapterError {
     WebsocketError(String),
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
+    #[error("{0}")]
+    Api(ApiError),
+    #[error("Trade store: {0}")]
+    Storage(String),
+}
+
+/// Error body an exchange's REST API answered with in place of the requested data
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub exchange: Exchange,
+    pub code: i64,
+    pub message: String,
+}
+
+impl ApiError {
+    /// Plain explanation of the codes users run into the most
+    pub fn friendly_text(&self) -> Option<&'static str> {
+        let text = match self.exchange {
+            Exchange::BinanceLinear | Exchange::BinanceInverse | Exchange::BinanceSpot => {
+                match self.code {
+                    -1003 => "Too many requests, the IP is rate limited or banned for a while",
+                    -1021 => "Request timestamp is off, check the system clock",
+                    -1100 | -1102 | -1104 => "Request had invalid or missing parameters",
+                    -1121 => "Unknown symbol, it might have been delisted",
+                    -1000 | -1001 | -1006 | -1007 => "Exchange had an internal error, try again",
+                    _ => return None,
+                }
+            }
+            Exchange::BybitLinear | Exchange::BybitInverse | Exchange::BybitSpot => {
+                match self.code {
+                    10001 => "Request had invalid or missing parameters",
+                    10002 => "Request timestamp is off, check the system clock",
+                    10006 | 10018 => "Too many requests, the IP is rate limited for a while",
+                    10016 => "Exchange had an internal error, try again",
+                    110_001 | 170_121 => "Unknown symbol, it might have been delisted",
+                    _ => return None,
+                }
+            }
+            Exchange::OkxLinear | Exchange::OkxInverse | Exchange::OkxSpot => return None,
+        };
+
+        Some(text)
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.friendly_text() {
+            Some(text) => write!(f, "{}: {text} ({})", self.exchange, self.code),
+            None => write!(f, "{} error {}: {}", self.exchange, self.code, self.message),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
@@ -49,11 +212,17 @@ pub enum StreamKind {
         exchange: Exchange,
         ticker: Ticker,
         timeframe: Timeframe,
+        #[serde(default)]
+        source: KlineSource,
     },
     DepthAndTrades {
         exchange: Exchange,
         ticker: Ticker,
     },
+    Liquidations {
+        exchange: Exchange,
+        ticker: Ticker,
+    },
 }
 
 impl StreamKind {
@@ -62,7 +231,8 @@ impl StreamKind {
             StreamKind::Kline {
                 exchange, ticker, ..
             }
-            | StreamKind::DepthAndTrades { exchange, ticker } => (*exchange, *ticker),
+            | StreamKind::DepthAndTrades { exchange, ticker }
+            | StreamKind::Liquidations { exchange, ticker } => (*exchange, *ticker),
         }
     }
 
@@ -73,18 +243,72 @@ impl StreamKind {
         }
     }
 
-    pub fn as_kline_stream(&self) -> Option<(Exchange, Ticker, Timeframe)> {
+    pub fn as_liquidation_stream(&self) -> Option<(Exchange, Ticker)> {
+        match self {
+            StreamKind::Liquidations { exchange, ticker } => Some((*exchange, *ticker)),
+            _ => None,
+        }
+    }
+
+    pub fn as_kline_stream(&self) -> Option<(Exchange, Ticker, Timeframe, KlineSource)> {
         match self {
             StreamKind::Kline {
                 exchange,
                 ticker,
                 timeframe,
-            } => Some((*exchange, *ticker, *timeframe)),
+                source,
+            } => Some((*exchange, *ticker, *timeframe, *source)),
             _ => None,
         }
     }
 }
 
+/// Price the klines of a stream are built from
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum KlineSource {
+    #[default]
+    Last,
+    /// Mark price klines of a perpetual, these carry no volume
+    Mark,
+}
+
+impl KlineSource {
+    pub const ALL: [KlineSource; 2] = [KlineSource::Last, KlineSource::Mark];
+
+    pub fn is_supported(self, exchange: Exchange) -> bool {
+        match self {
+            KlineSource::Last => true,
+            KlineSource::Mark => {
+                matches!(exchange, Exchange::BinanceLinear | Exchange::BinanceInverse)
+            }
+        }
+    }
+
+    /// The source itself where the exchange serves it, last price klines otherwise
+    pub fn fallback_for(self, exchange: Exchange) -> Self {
+        if self.is_supported(exchange) {
+            self
+        } else {
+            KlineSource::Last
+        }
+    }
+}
+
+impl std::fmt::Display for KlineSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KlineSource::Last => write!(f, "Last price"),
+            KlineSource::Mark => write!(f, "Mark price"),
+        }
+    }
+}
+
+/// Approximate websocket messages per second pushed by a single stream
+const DEPTH_STREAM_MSG_RATE: f32 = 10.0;
+const KLINE_STREAM_MSG_RATE: f32 = 4.0;
+/// Liquidation snapshots are pushed at most once a second per symbol
+const LIQUIDATION_STREAM_MSG_RATE: f32 = 1.0;
+
 #[derive(Debug, Default)]
 pub struct UniqueStreams {
     streams: HashMap<Exchange, HashMap<Ticker, HashSet<StreamKind>>>,
@@ -108,12 +332,7 @@ impl UniqueStreams {
     }
 
     pub fn add(&mut self, stream: StreamKind) {
-        let (exchange, ticker) = match stream {
-            StreamKind::Kline {
-                exchange, ticker, ..
-            }
-            | StreamKind::DepthAndTrades { exchange, ticker } => (exchange, ticker),
-        };
+        let (exchange, ticker) = stream.exchange_and_ticker();
 
         self.streams
             .entry(exchange)
@@ -134,12 +353,14 @@ impl UniqueStreams {
     fn update_specs_for_exchange(&mut self, exchange: Exchange) {
         let depth_streams = self.depth_streams(Some(exchange));
         let kline_streams = self.kline_streams(Some(exchange));
+        let liquidation_streams = self.liquidation_streams(Some(exchange));
 
         self.specs.insert(
             exchange,
             StreamSpecs {
                 depth: depth_streams,
                 kline: kline_streams,
+                liquidations: liquidation_streams,
             },
         );
     }
@@ -178,26 +399,58 @@ impl UniqueStreams {
         })
     }
 
+    pub fn liquidation_streams(
+        &self,
+        exchange_filter: Option<Exchange>,
+    ) -> Vec<(Exchange, Ticker)> {
+        self.streams(exchange_filter, |exchange, stream| {
+            stream
+                .as_liquidation_stream()
+                .map(|(_, ticker)| (exchange, ticker))
+        })
+    }
+
     pub fn kline_streams(
         &self,
         exchange_filter: Option<Exchange>,
-    ) -> Vec<(Exchange, Ticker, Timeframe)> {
+    ) -> Vec<(Exchange, Ticker, Timeframe, KlineSource)> {
         self.streams(exchange_filter, |exchange, stream| {
             stream
                 .as_kline_stream()
-                .map(|(_, ticker, timeframe)| (exchange, ticker, timeframe))
+                .map(|(_, ticker, timeframe, source)| (exchange, ticker, timeframe, source))
         })
     }
 
+    pub fn contains(&self, stream: &StreamKind) -> bool {
+        let (exchange, ticker) = stream.exchange_and_ticker();
+
+        self.streams
+            .get(&exchange)
+            .and_then(|ticker_map| ticker_map.get(&ticker))
+            .is_some_and(|streams| streams.contains(stream))
+    }
+
     pub fn combined(&self) -> &HashMap<Exchange, StreamSpecs> {
         &self.specs
     }
+
+    pub fn estimated_msg_rate(&self) -> f32 {
+        self.specs
+            .values()
+            .map(|specs| {
+                specs.depth.len() as f32 * DEPTH_STREAM_MSG_RATE
+                    + specs.kline.len() as f32 * KLINE_STREAM_MSG_RATE
+                    + specs.liquidations.len() as f32 * LIQUIDATION_STREAM_MSG_RATE
+            })
+            .sum()
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct StreamSpecs {
     pub depth: Vec<(Exchange, Ticker)>,
-    pub kline: Vec<(Exchange, Ticker, Timeframe)>,
+    pub kline: Vec<(Exchange, Ticker, Timeframe, KlineSource)>,
+    pub liquidations: Vec<(Exchange, Ticker)>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -208,6 +461,9 @@ pub enum Exchange {
     BybitLinear,
     BybitInverse,
     BybitSpot,
+    OkxLinear,
+    OkxInverse,
+    OkxSpot,
 }
 
 impl std::fmt::Display for Exchange {
@@ -222,6 +478,9 @@ impl std::fmt::Display for Exchange {
                 Exchange::BybitLinear => "Bybit Linear",
                 Exchange::BybitInverse => "Bybit Inverse",
                 Exchange::BybitSpot => "Bybit Spot",
+                Exchange::OkxLinear => "OKX Linear",
+                Exchange::OkxInverse => "OKX Inverse",
+                Exchange::OkxSpot => "OKX Spot",
             }
         )
     }
@@ -238,36 +497,113 @@ impl FromStr for Exchange {
             "Bybit Linear" => Ok(Exchange::BybitLinear),
             "Bybit Inverse" => Ok(Exchange::BybitInverse),
             "Bybit Spot" => Ok(Exchange::BybitSpot),
+            "OKX Linear" => Ok(Exchange::OkxLinear),
+            "OKX Inverse" => Ok(Exchange::OkxInverse),
+            "OKX Spot" => Ok(Exchange::OkxSpot),
             _ => Err(format!("Invalid exchange: {}", s)),
         }
     }
 }
 
 impl Exchange {
-    pub const ALL: [Exchange; 6] = [
+    pub const ALL: [Exchange; 9] = [
         Exchange::BinanceLinear,
         Exchange::BinanceInverse,
         Exchange::BinanceSpot,
         Exchange::BybitLinear,
         Exchange::BybitInverse,
         Exchange::BybitSpot,
+        Exchange::OkxLinear,
+        Exchange::OkxInverse,
+        Exchange::OkxSpot,
     ];
 
     pub fn market_type(&self) -> MarketKind {
         match self {
-            Exchange::BinanceLinear | Exchange::BybitLinear => MarketKind::LinearPerps,
-            Exchange::BinanceInverse | Exchange::BybitInverse => MarketKind::InversePerps,
-            Exchange::BinanceSpot | Exchange::BybitSpot => MarketKind::Spot,
+            Exchange::BinanceLinear | Exchange::BybitLinear | Exchange::OkxLinear => {
+                MarketKind::LinearPerps
+            }
+            Exchange::BinanceInverse | Exchange::BybitInverse | Exchange::OkxInverse => {
+                MarketKind::InversePerps
+            }
+            Exchange::BinanceSpot | Exchange::BybitSpot | Exchange::OkxSpot => MarketKind::Spot,
+        }
+    }
+
+    /// Kline intervals the venue has, Bybit lacks the 3 day one
+    pub fn supports_kline_timeframe(&self, timeframe: Timeframe) -> bool {
+        let is_bybit = matches!(
+            self,
+            Exchange::BybitLinear | Exchange::BybitInverse | Exchange::BybitSpot
+        );
+
+        Timeframe::KLINE.contains(&timeframe) && !(is_bybit && timeframe == Timeframe::D3)
+    }
+
+    /// Markets with a public testnet, OKX only has demo trading behind an account header
+    pub fn supports_testnet(&self) -> bool {
+        !matches!(
+            self,
+            Exchange::OkxLinear | Exchange::OkxInverse | Exchange::OkxSpot
+        )
+    }
+
+    /// Markets with historical trades to backfill footprint charts with
+    pub fn supports_trade_fetch(&self) -> bool {
+        matches!(
+            self,
+            Exchange::BinanceLinear | Exchange::BinanceInverse | Exchange::BinanceSpot
+        )
+    }
+
+    /// Rough download size of a day of trades for a major ticker, as (low, high) in megabytes.
+    /// Smaller tickers land well below the low end
+    pub fn daily_trades_estimate_mb(&self) -> Option<(u32, u32)> {
+        if !self.supports_trade_fetch() {
+            return None;
+        }
+
+        Some(match self.market_type() {
+            MarketKind::LinearPerps => (10, 150),
+            MarketKind::Spot => (5, 100),
+            MarketKind::InversePerps => (1, 15),
+        })
+    }
+
+    /// Venues with a public stream of forced liquidation orders
+    pub fn supports_liquidations(&self) -> bool {
+        matches!(self, Exchange::BinanceLinear | Exchange::BinanceInverse)
+    }
+
+    /// The spot or linear perp market of the same venue, where symbols are named alike
+    pub fn spot_perp_counterpart(&self) -> Option<Exchange> {
+        match self {
+            Exchange::BinanceSpot => Some(Exchange::BinanceLinear),
+            Exchange::BinanceLinear => Some(Exchange::BinanceSpot),
+            Exchange::BybitSpot => Some(Exchange::BybitLinear),
+            Exchange::BybitLinear => Some(Exchange::BybitSpot),
+            Exchange::OkxSpot => Some(Exchange::OkxLinear),
+            Exchange::OkxLinear => Some(Exchange::OkxSpot),
+            Exchange::BinanceInverse | Exchange::BybitInverse | Exchange::OkxInverse => None,
         }
     }
 }
 
 #[derive(Debug, Clone)]
 pub enum Event {
-    Connected(Exchange),
-    Disconnected(Exchange, String),
+    /// Websocket of the exchange carrying the streams opened
+    Connected(Exchange, Box<[StreamKind]>),
+    /// Websocket carrying the streams dropped or couldn't be set up, with the reason
+    Disconnected(Exchange, Box<[StreamKind]>, String),
     DepthReceived(StreamKind, u64, Depth, Box<[Trade]>),
     KlineReceived(StreamKind, Kline),
+    LiquidationsReceived(StreamKind, Box<[Liquidation]>),
+    /// Depth stream switched to periodic REST snapshots for the given reason, `None` once it's
+    /// back on the live diffs
+    DepthDegraded(StreamKind, Option<String>),
+    /// Connection of the stream failed and is tried again after the delay, `None` once it ran
+    /// out of retries and stopped
+    Reconnecting(StreamKind, Option<Duration>),
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -283,9 +619,45 @@ impl<I> StreamConfig<I> {
     }
 }
 
-pub async fn fetch_ticker_info(
-    exchange: Exchange,
-) -> Result<HashMap<Ticker, Option<TickerInfo>>, AdapterError> {
+#[derive(Debug, Clone, Copy)]
+pub struct ServerStatus {
+    pub latency_ms: u64,
+    /// Local clock minus server clock, compensated for half the round trip
+    pub clock_skew_ms: i64,
+}
+
+pub async fn check_connectivity(exchange: Exchange) -> Result<ServerStatus, AdapterError> {
+    let market_type = exchange.market_type();
+    let started = std::time::Instant::now();
+
+    let server_time = match exchange {
+        Exchange::BinanceLinear | Exchange::BinanceInverse | Exchange::BinanceSpot => {
+            binance::fetch_server_time(market_type).await?
+        }
+        Exchange::BybitLinear | Exchange::BybitInverse | Exchange::BybitSpot => {
+            bybit::fetch_server_time(market_type).await?
+        }
+        Exchange::OkxLinear | Exchange::OkxInverse | Exchange::OkxSpot => {
+            okx::fetch_server_time(market_type).await?
+        }
+    };
+
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let local_time = chrono::Utc::now().timestamp_millis() - (latency_ms / 2) as i64;
+
+    Ok(ServerStatus {
+        latency_ms,
+        clock_skew_ms: local_time - server_time as i64,
+    })
+}
+
+/// Tick sizes of a market's tickers, `None` for the ones listed without one, and their contract specs
+pub type MarketInfo = (
+    HashMap<Ticker, Option<TickerInfo>>,
+    HashMap<Ticker, TickerSpecs>,
+);
+
+pub async fn fetch_ticker_info(exchange: Exchange) -> Result<MarketInfo, AdapterError> {
     let market_type = exchange.market_type();
 
     match exchange {
@@ -295,6 +667,9 @@ pub async fn fetch_ticker_info(
         Exchange::BybitLinear | Exchange::BybitInverse | Exchange::BybitSpot => {
             bybit::fetch_ticksize(market_type).await
         }
+        Exchange::OkxLinear | Exchange::OkxInverse | Exchange::OkxSpot => {
+            okx::fetch_ticksize(market_type).await
+        }
     }
 }
 
@@ -310,6 +685,9 @@ pub async fn fetch_ticker_prices(
         Exchange::BybitLinear | Exchange::BybitInverse | Exchange::BybitSpot => {
             bybit::fetch_ticker_prices(market_type).await
         }
+        Exchange::OkxLinear | Exchange::OkxInverse | Exchange::OkxSpot => {
+            okx::fetch_ticker_prices(market_type).await
+        }
     }
 }
 
@@ -317,15 +695,41 @@ pub async fn fetch_klines(
     exchange: Exchange,
     ticker: Ticker,
     timeframe: Timeframe,
+    source: KlineSource,
     range: Option<(u64, u64)>,
 ) -> Result<Vec<Kline>, AdapterError> {
+    if !source.is_supported(exchange) {
+        return Err(AdapterError::InvalidRequest(format!(
+            "{source} klines aren't served on {exchange}"
+        )));
+    }
+
     match exchange {
         Exchange::BinanceLinear | Exchange::BinanceInverse | Exchange::BinanceSpot => {
-            binance::fetch_klines(ticker, timeframe, range).await
+            binance::fetch_klines(ticker, timeframe, source, range).await
         }
         Exchange::BybitLinear | Exchange::BybitInverse | Exchange::BybitSpot => {
             bybit::fetch_klines(ticker, timeframe, range).await
         }
+        Exchange::OkxLinear | Exchange::OkxInverse | Exchange::OkxSpot => {
+            okx::fetch_klines(ticker, timeframe, range).await
+        }
+    }
+}
+
+pub async fn fetch_funding_rate(
+    exchange: Exchange,
+    ticker: Ticker,
+) -> Result<FundingRate, AdapterError> {
+    match exchange {
+        Exchange::BinanceLinear | Exchange::BinanceInverse => {
+            binance::fetch_funding_rate(ticker).await
+        }
+        Exchange::BybitLinear | Exchange::BybitInverse => bybit::fetch_funding_rate(ticker).await,
+        Exchange::OkxLinear | Exchange::OkxInverse => okx::fetch_funding_rate(ticker).await,
+        Exchange::BinanceSpot | Exchange::BybitSpot | Exchange::OkxSpot => Err(
+            AdapterError::InvalidRequest("Funding is only paid on perpetual markets".to_string()),
+        ),
     }
 }
 
@@ -1,15 +1,19 @@
 use super::{
     super::{
-        Exchange, Kline, MarketKind, OpenInterest, StreamKind, Ticker, TickerInfo, TickerStats,
-        Timeframe, Trade,
-        connect::{State, setup_tcp_connection, setup_tls_connection, setup_websocket_connection},
+        Exchange, FundingRate, Kline, Liquidation, MarketKind, OpenInterest, StreamKind, Ticker,
+        TickerInfo, TickerSpecs, TickerStats, Timeframe, Trade,
+        connect::{
+            Backoff, State, setup_tcp_connection, setup_tls_connection, setup_websocket_connection,
+            wait_to_reconnect,
+        },
         de_string_to_f32,
-        depth::{DepthPayload, DepthUpdate, LocalDepthCache, Order},
+        depth::{DepthPayload, DepthUpdate, DesyncGuard, LocalDepthCache, Order},
         is_symbol_supported,
         limiter::{self, RateLimiter},
         str_f32_parse,
+        trade_store::{self, TradeStore},
     },
-    AdapterError, Event,
+    AdapterError, ApiError, Event, KlineSource, MarketInfo,
 };
 
 use csv::ReaderBuilder;
@@ -22,17 +26,28 @@ use iced_futures::{
 };
 use serde::Deserialize;
 use sonic_rs::{FastStr, to_object_iter_unchecked};
-use std::{collections::HashMap, io::BufReader, path::PathBuf, sync::LazyLock, time::Duration};
+use std::{
+    collections::HashMap,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::Duration,
+};
 use tokio::sync::Mutex;
 
 const SPOT_DOMAIN: &str = "https://api.binance.com";
 const LINEAR_PERP_DOMAIN: &str = "https://fapi.binance.com";
 const INVERSE_PERP_DOMAIN: &str = "https://dapi.binance.com";
 
+const SPOT_TESTNET_DOMAIN: &str = "https://testnet.binance.vision";
+const PERP_TESTNET_DOMAIN: &str = "https://testnet.binancefuture.com";
+
 const SPOT_LIMIT: usize = 6000;
 const PERP_LIMIT: usize = 2400;
 
 const REFILL_RATE: Duration = Duration::from_secs(60);
+/// Daily trade archives of busy tickers run into the hundreds of megabytes
+const ARCHIVE_TIMEOUT: Duration = Duration::from_secs(300);
 const LIMITER_BUFFER_PCT: f32 = 0.03;
 
 static SPOT_LIMITER: LazyLock<Mutex<BinanceLimiter>> =
@@ -72,7 +87,7 @@ impl RateLimiter for BinanceLimiter {
         }
     }
 
-    fn should_exit_on_response(&self, response: &reqwest::Response) -> bool {
+    fn is_blocked_response(&self, response: &reqwest::Response) -> bool {
         let status = response.status();
         status == 429 || status == 418
     }
@@ -86,6 +101,65 @@ fn exchange_from_market_type(market: MarketKind) -> Exchange {
     }
 }
 
+fn rest_domain(market: MarketKind) -> String {
+    let exchange = exchange_from_market_type(market);
+
+    if let Some(domain) = super::rest_override(exchange) {
+        return domain;
+    }
+
+    let domain = match (market, super::is_testnet(exchange)) {
+        (MarketKind::Spot, false) => SPOT_DOMAIN,
+        (MarketKind::LinearPerps, false) => LINEAR_PERP_DOMAIN,
+        (MarketKind::InversePerps, false) => INVERSE_PERP_DOMAIN,
+        (MarketKind::Spot, true) => SPOT_TESTNET_DOMAIN,
+        (MarketKind::LinearPerps | MarketKind::InversePerps, true) => PERP_TESTNET_DOMAIN,
+    };
+    domain.to_string()
+}
+
+fn ws_domain(market: MarketKind) -> String {
+    let exchange = exchange_from_market_type(market);
+
+    if let Some(domain) = super::ws_override(exchange) {
+        return domain;
+    }
+
+    let domain = match (market, super::is_testnet(exchange)) {
+        (MarketKind::Spot, false) => "stream.binance.com",
+        (MarketKind::LinearPerps, false) => "fstream.binance.com",
+        (MarketKind::InversePerps, false) => "dstream.binance.com",
+        (MarketKind::Spot, true) => "stream.testnet.binance.vision",
+        (MarketKind::LinearPerps, true) => "fstream.binancefuture.com",
+        (MarketKind::InversePerps, true) => "dstream.binancefuture.com",
+    };
+    domain.to_string()
+}
+
+/// Body Binance answers a failed request with, e.g. `{"code":-1121,"msg":"Invalid symbol."}`
+#[derive(Deserialize)]
+struct ErrorBody {
+    code: i64,
+    msg: String,
+}
+
+/// Error of a response that didn't parse as `what`, taken from its body if Binance sent one
+fn parse_error(
+    market: MarketKind,
+    text: &str,
+    err: impl std::fmt::Display,
+    what: &str,
+) -> AdapterError {
+    match serde_json::from_str::<ErrorBody>(text) {
+        Ok(body) => AdapterError::Api(ApiError {
+            exchange: exchange_from_market_type(market),
+            code: body.code,
+            message: body.msg,
+        }),
+        Err(_) => AdapterError::ParseError(format!("Failed to parse {what}: {err}")),
+    }
+}
+
 fn limiter_from_market_type(market: MarketKind) -> &'static Mutex<BinanceLimiter> {
     match market {
         MarketKind::Spot => &SPOT_LIMITER,
@@ -144,6 +218,22 @@ struct SonicKlineWrap {
     kline: SonicKline,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+struct SonicMarkKlineWrap {
+    #[serde(rename = "k")]
+    kline: SonicKline,
+}
+
+#[derive(Deserialize, Debug)]
+struct SonicMarkPrice {
+    #[serde(rename = "E")]
+    time: u64,
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "p", deserialize_with = "de_string_to_f32")]
+    price: f32,
+}
+
 #[derive(Deserialize, Debug)]
 struct SonicTrade {
     #[serde(rename = "T")]
@@ -156,6 +246,24 @@ struct SonicTrade {
     is_sell: bool,
 }
 
+#[derive(Deserialize, Debug)]
+struct SonicLiquidation {
+    #[serde(rename = "T")]
+    time: u64,
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "ap", deserialize_with = "de_string_to_f32")]
+    avg_price: f32,
+    #[serde(rename = "z", deserialize_with = "de_string_to_f32")]
+    filled_qty: f32,
+}
+
+#[derive(Deserialize, Debug)]
+struct SonicLiquidationWrap {
+    #[serde(rename = "o")]
+    order: SonicLiquidation,
+}
+
 enum SonicDepth {
     Spot(SpotDepth),
     Perp(PerpDepth),
@@ -195,12 +303,18 @@ enum StreamData {
     Trade(SonicTrade),
     Depth(SonicDepth),
     Kline(Ticker, SonicKline),
+    MarkKline(Ticker, SonicKline),
+    MarkPrice(Ticker, SonicMarkPrice),
+    Liquidation(SonicLiquidation),
 }
 
 enum StreamWrapper {
     Trade,
     Depth,
     Kline,
+    MarkKline,
+    MarkPrice,
+    Liquidation,
 }
 
 impl StreamWrapper {
@@ -212,6 +326,9 @@ impl StreamWrapper {
                 s if s.starts_with("de") => Some(StreamWrapper::Depth),
                 s if s.starts_with("ag") => Some(StreamWrapper::Trade),
                 s if s.starts_with("kl") => Some(StreamWrapper::Kline),
+                s if s.starts_with("markPriceKline") => Some(StreamWrapper::MarkKline),
+                s if s.starts_with("markPrice") => Some(StreamWrapper::MarkPrice),
+                s if s.starts_with("fo") => Some(StreamWrapper::Liquidation),
                 _ => None,
             })
     }
@@ -221,15 +338,23 @@ fn feed_de(slice: &[u8], market: MarketKind) -> Result<StreamData, AdapterError>
     let exchange = exchange_from_market_type(market);
 
     let mut stream_type: Option<StreamWrapper> = None;
+    let mut stream_symbol: Option<String> = None;
     let iter: sonic_rs::ObjectJsonIter = unsafe { to_object_iter_unchecked(slice) };
 
     for elem in iter {
         let (k, v) = elem.map_err(|e| AdapterError::ParseError(e.to_string()))?;
 
         if k == "stream" {
-            if let Some(s) = StreamWrapper::from_stream_type(&v.as_raw_faststr()) {
+            let stream_name = v.as_raw_faststr();
+            if let Some(s) = StreamWrapper::from_stream_type(&stream_name) {
                 stream_type = Some(s);
             }
+            // mark price klines name their pair in the payload, the symbol is only in the topic
+            stream_symbol = stream_name
+                .trim_matches('"')
+                .split('@')
+                .next()
+                .map(str::to_uppercase);
         } else if k == "data" {
             match stream_type {
                 Some(StreamWrapper::Trade) => {
@@ -261,6 +386,35 @@ fn feed_de(slice: &[u8], market: MarketKind) -> Result<StreamData, AdapterError>
                         kline_wrap.kline,
                     ));
                 }
+                Some(StreamWrapper::MarkKline) => {
+                    let kline_wrap: SonicMarkKlineWrap = sonic_rs::from_str(&v.as_raw_faststr())
+                        .map_err(|e| AdapterError::ParseError(e.to_string()))?;
+
+                    let symbol = stream_symbol.as_deref().ok_or_else(|| {
+                        AdapterError::ParseError("Mark price kline without a topic".to_string())
+                    })?;
+
+                    return Ok(StreamData::MarkKline(
+                        Ticker::new(symbol, exchange),
+                        kline_wrap.kline,
+                    ));
+                }
+                Some(StreamWrapper::MarkPrice) => {
+                    let mark_price: SonicMarkPrice = sonic_rs::from_str(&v.as_raw_faststr())
+                        .map_err(|e| AdapterError::ParseError(e.to_string()))?;
+
+                    return Ok(StreamData::MarkPrice(
+                        Ticker::new(&mark_price.symbol, exchange),
+                        mark_price,
+                    ));
+                }
+                Some(StreamWrapper::Liquidation) => {
+                    let liquidation_wrap: SonicLiquidationWrap =
+                        sonic_rs::from_str(&v.as_raw_faststr())
+                            .map_err(|e| AdapterError::ParseError(e.to_string()))?;
+
+                    return Ok(StreamData::Liquidation(liquidation_wrap.order));
+                }
                 _ => {
                     log::error!("Unknown stream type");
                 }
@@ -309,6 +463,7 @@ async fn try_resync(
             let _ = output
                 .send(Event::Disconnected(
                     exchange,
+                    Box::from([StreamKind::DepthAndTrades { exchange, ticker }]),
                     format!("Depth fetch failed: {e}"),
                 ))
                 .await;
@@ -319,6 +474,7 @@ async fn try_resync(
             output
                 .send(Event::Disconnected(
                     exchange,
+                    Box::from([StreamKind::DepthAndTrades { exchange, ticker }]),
                     format!("Failed to send fetched depth for {ticker}, error: {e}"),
                 ))
                 .await
@@ -343,21 +499,21 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
         let mut trades_buffer: Vec<Trade> = Vec::new();
         let mut already_fetching: bool = false;
         let mut prev_id: u64 = 0;
+        let mut desync_guard = DesyncGuard::new();
+        let stream_kind = StreamKind::DepthAndTrades { exchange, ticker };
 
         let streams = format!("{stream_1}/{stream_2}");
 
-        let domain = match market {
-            MarketKind::Spot => "stream.binance.com",
-            MarketKind::LinearPerps => "fstream.binance.com",
-            MarketKind::InversePerps => "dstream.binance.com",
-        };
+        let domain = ws_domain(market);
 
         let contract_size = get_contract_size(&ticker, market);
+        let mut backoff = Backoff::new();
 
         loop {
             match &mut state {
                 State::Disconnected => {
-                    if let Ok(websocket) = connect(domain, streams.as_str()).await {
+                    if let Ok(websocket) = connect(&domain, streams.as_str()).await {
+                        backoff.reset();
                         let (tx, rx) = tokio::sync::oneshot::channel();
 
                         tokio::spawn(async move {
@@ -371,12 +527,15 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
 
                                 state = State::Connected(websocket);
 
-                                let _ = output.send(Event::Connected(exchange)).await;
+                                let _ = output
+                                    .send(Event::Connected(exchange, Box::from([stream_kind])))
+                                    .await;
                             }
                             Ok(Err(e)) => {
                                 let _ = output
                                     .send(Event::Disconnected(
                                         exchange,
+                                        Box::from([stream_kind]),
                                         format!("Depth fetch failed: {e}"),
                                     ))
                                     .await;
@@ -385,20 +544,24 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
                                 let _ = output
                                     .send(Event::Disconnected(
                                         exchange,
+                                        Box::from([stream_kind]),
                                         format!("Channel error: {e}"),
                                     ))
                                     .await;
                             }
                         }
                     } else {
-                        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-
                         let _ = output
                             .send(Event::Disconnected(
                                 exchange,
+                                Box::from([stream_kind]),
                                 "Failed to connect to websocket".to_string(),
                             ))
                             .await;
+
+                        if !wait_to_reconnect(&mut backoff, &[stream_kind], &mut output).await {
+                            return;
+                        }
                     }
                 }
                 State::Connected(ws) => {
@@ -425,6 +588,58 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
                                                 continue;
                                             }
 
+                                            if desync_guard.is_degraded() {
+                                                if desync_guard.try_recover() {
+                                                    try_resync(
+                                                        exchange,
+                                                        ticker,
+                                                        &mut orderbook,
+                                                        &mut state,
+                                                        &mut output,
+                                                        &mut already_fetching,
+                                                    )
+                                                    .await;
+                                                    prev_id = 0;
+
+                                                    let _ = output
+                                                        .send(Event::DepthDegraded(
+                                                            stream_kind,
+                                                            None,
+                                                        ))
+                                                        .await;
+                                                } else {
+                                                    // diffs can't be trusted, the last snapshot
+                                                    // goes out along with the live trades
+                                                    if desync_guard.snapshot_due() {
+                                                        try_resync(
+                                                            exchange,
+                                                            ticker,
+                                                            &mut orderbook,
+                                                            &mut state,
+                                                            &mut output,
+                                                            &mut already_fetching,
+                                                        )
+                                                        .await;
+                                                    }
+
+                                                    let depth_time = match &depth_type {
+                                                        SonicDepth::Perp(de_depth) => de_depth.time,
+                                                        SonicDepth::Spot(de_depth) => de_depth.time,
+                                                    };
+
+                                                    let _ = output
+                                                        .send(Event::DepthReceived(
+                                                            stream_kind,
+                                                            depth_time,
+                                                            orderbook.depth.clone(),
+                                                            std::mem::take(&mut trades_buffer)
+                                                                .into_boxed_slice(),
+                                                        ))
+                                                        .await;
+                                                    continue;
+                                                }
+                                            }
+
                                             let last_update_id = orderbook.last_update_id;
 
                                             match depth_type {
@@ -443,6 +658,15 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
                                                             "Out of sync at first event. Trying to resync...\n"
                                                         );
 
+                                                        if desync_guard.record_desync() {
+                                                            let _ = output
+                                                                .send(Event::DepthDegraded(
+                                                                    stream_kind,
+                                                                    Some("Kept going out of sync at first event".to_string()),
+                                                                ))
+                                                                .await;
+                                                        }
+
                                                         try_resync(
                                                             exchange,
                                                             ticker,
@@ -479,13 +703,30 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
 
                                                         prev_id = de_depth.final_id;
                                                     } else {
-                                                        state = State::Disconnected;
-                                                        let _ = output.send(
-                                                                Event::Disconnected(
+                                                        let reason = format!(
+                                                            "Out of sync. Expected update_id: {}, got: {}",
+                                                            de_depth.prev_final_id, prev_id
+                                                        );
+
+                                                        if desync_guard.is_degraded() {
+                                                            // already fell back at the first event
+                                                        } else if desync_guard.record_desync() {
+                                                            let _ = output
+                                                                .send(Event::DepthDegraded(
+                                                                    stream_kind,
+                                                                    Some(reason),
+                                                                ))
+                                                                .await;
+                                                        } else {
+                                                            state = State::Disconnected;
+                                                            let _ = output
+                                                                .send(Event::Disconnected(
                                                                     exchange,
-                                                                    format!("Out of sync. Expected update_id: {}, got: {}", de_depth.prev_final_id, prev_id)
-                                                                )
-                                                            ).await;
+                                                                    Box::from([stream_kind]),
+                                                                    reason,
+                                                                ))
+                                                                .await;
+                                                        }
                                                     }
                                                 }
                                                 SonicDepth::Spot(ref de_depth) => {
@@ -503,6 +744,15 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
                                                             "Out of sync at first event. Trying to resync...\n"
                                                         );
 
+                                                        if desync_guard.record_desync() {
+                                                            let _ = output
+                                                                .send(Event::DepthDegraded(
+                                                                    stream_kind,
+                                                                    Some("Kept going out of sync at first event".to_string()),
+                                                                ))
+                                                                .await;
+                                                        }
+
                                                         try_resync(
                                                             exchange,
                                                             ticker,
@@ -539,13 +789,30 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
 
                                                         prev_id = de_depth.final_id;
                                                     } else {
-                                                        state = State::Disconnected;
-                                                        let _ = output.send(
-                                                                Event::Disconnected(
+                                                        let reason = format!(
+                                                            "Out of sync. Expected update_id: {}, got: {}",
+                                                            de_depth.final_id, prev_id
+                                                        );
+
+                                                        if desync_guard.is_degraded() {
+                                                            // already fell back at the first event
+                                                        } else if desync_guard.record_desync() {
+                                                            let _ = output
+                                                                .send(Event::DepthDegraded(
+                                                                    stream_kind,
+                                                                    Some(reason),
+                                                                ))
+                                                                .await;
+                                                        } else {
+                                                            state = State::Disconnected;
+                                                            let _ = output
+                                                                .send(Event::Disconnected(
                                                                     exchange,
-                                                                    format!("Out of sync. Expected update_id: {}, got: {}", de_depth.final_id, prev_id)
-                                                                )
-                                                            ).await;
+                                                                    Box::from([stream_kind]),
+                                                                    reason,
+                                                                ))
+                                                                .await;
+                                                        }
                                                     }
                                                 }
                                             }
@@ -559,6 +826,7 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
                                 let _ = output
                                     .send(Event::Disconnected(
                                         exchange,
+                                        Box::from([stream_kind]),
                                         "Connection closed".to_string(),
                                     ))
                                     .await;
@@ -570,6 +838,7 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
                             let _ = output
                                 .send(Event::Disconnected(
                                     exchange,
+                                    Box::from([stream_kind]),
                                     "Error reading frame: ".to_string() + &e.to_string(),
                                 ))
                                 .await;
@@ -582,7 +851,7 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
 }
 
 pub fn connect_kline_stream(
-    streams: Vec<(Ticker, Timeframe)>,
+    streams: Vec<(Ticker, Timeframe, KlineSource)>,
     market: MarketKind,
 ) -> impl Stream<Item = Event> {
     stream::channel(100, async move |mut output| {
@@ -592,45 +861,67 @@ pub fn connect_kline_stream(
 
         let stream_str = streams
             .iter()
-            .map(|(ticker, timeframe)| {
+            .map(|(ticker, timeframe, source)| {
+                let symbol = ticker.to_full_symbol_and_type().0.to_lowercase();
                 let timeframe_str = timeframe.to_string();
-                format!(
-                    "{}@kline_{timeframe_str}",
-                    ticker.to_full_symbol_and_type().0.to_lowercase()
-                )
+
+                match (source, market) {
+                    // USD-M futures have no mark price klines to subscribe to, they're
+                    // built from the mark price pushed every second instead
+                    (KlineSource::Mark, MarketKind::LinearPerps) => {
+                        format!("{symbol}@markPrice@1s")
+                    }
+                    (KlineSource::Mark, _) => format!("{symbol}@markPriceKline_{timeframe_str}"),
+                    (KlineSource::Last, _) => format!("{symbol}@kline_{timeframe_str}"),
+                }
             })
             .collect::<Vec<String>>()
             .join("/");
+        let stream_kinds = streams
+            .iter()
+            .map(|(ticker, timeframe, source)| StreamKind::Kline {
+                exchange,
+                ticker: *ticker,
+                timeframe: *timeframe,
+                source: *source,
+            })
+            .collect::<Vec<_>>();
+        let mut backoff = Backoff::new();
+
+        let mut mark_klines: HashMap<(Ticker, Timeframe), Kline> = HashMap::new();
 
         loop {
             match &mut state {
                 State::Disconnected => {
-                    let domain = match market {
-                        MarketKind::Spot => "stream.binance.com",
-                        MarketKind::LinearPerps => "fstream.binance.com",
-                        MarketKind::InversePerps => "dstream.binance.com",
-                    };
+                    let domain = ws_domain(market);
 
-                    if let Ok(websocket) = connect(domain, stream_str.as_str()).await {
+                    if let Ok(websocket) = connect(&domain, stream_str.as_str()).await {
+                        backoff.reset();
                         state = State::Connected(websocket);
-                        let _ = output.send(Event::Connected(exchange)).await;
+                        let _ = output
+                            .send(Event::Connected(
+                                exchange,
+                                Box::from(stream_kinds.as_slice()),
+                            ))
+                            .await;
                     } else {
-                        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-
                         let _ = output
                             .send(Event::Disconnected(
                                 exchange,
+                                Box::from(stream_kinds.as_slice()),
                                 "Failed to connect to websocket".to_string(),
                             ))
                             .await;
+
+                        if !wait_to_reconnect(&mut backoff, &stream_kinds, &mut output).await {
+                            return;
+                        }
                     }
                 }
                 State::Connected(ws) => match ws.read_frame().await {
                     Ok(msg) => match msg.opcode {
-                        OpCode::Text => {
-                            if let Ok(StreamData::Kline(ticker, de_kline)) =
-                                feed_de(&msg.payload[..], market)
-                            {
+                        OpCode::Text => match feed_de(&msg.payload[..], market) {
+                            Ok(StreamData::Kline(ticker, de_kline)) => {
                                 let (buy_volume, sell_volume) = {
                                     let buy_volume = de_kline.taker_buy_base_asset_volume;
                                     let sell_volume = de_kline.volume - buy_volume;
@@ -651,28 +942,196 @@ pub fn connect_kline_stream(
                                     volume: (buy_volume, sell_volume),
                                 };
 
-                                if let Some(timeframe) = streams
-                                    .iter()
-                                    .find(|(_, tf)| tf.to_string() == de_kline.interval)
-                                {
+                                if let Some(timeframe) = streams.iter().find(|(_, tf, source)| {
+                                    *source == KlineSource::Last
+                                        && tf.to_string() == de_kline.interval
+                                }) {
                                     let _ = output
                                         .send(Event::KlineReceived(
                                             StreamKind::Kline {
                                                 exchange,
                                                 ticker,
                                                 timeframe: timeframe.1,
+                                                source: KlineSource::Last,
                                             },
                                             kline,
                                         ))
                                         .await;
                                 }
                             }
+                            Ok(StreamData::MarkKline(ticker, de_kline)) => {
+                                let kline = Kline {
+                                    time: de_kline.time,
+                                    open: de_kline.open,
+                                    high: de_kline.high,
+                                    low: de_kline.low,
+                                    close: de_kline.close,
+                                    volume: (0.0, 0.0),
+                                };
+
+                                if let Some(timeframe) = streams.iter().find(|(_, tf, source)| {
+                                    *source == KlineSource::Mark
+                                        && tf.to_string() == de_kline.interval
+                                }) {
+                                    let _ = output
+                                        .send(Event::KlineReceived(
+                                            StreamKind::Kline {
+                                                exchange,
+                                                ticker,
+                                                timeframe: timeframe.1,
+                                                source: KlineSource::Mark,
+                                            },
+                                            kline,
+                                        ))
+                                        .await;
+                                }
+                            }
+                            Ok(StreamData::MarkPrice(ticker, mark_price)) => {
+                                for (_, timeframe, _) in streams.iter().filter(|(t, _, source)| {
+                                    *t == ticker && *source == KlineSource::Mark
+                                }) {
+                                    let open_time = timeframe.open_time(mark_price.time);
+                                    let price = mark_price.price;
+
+                                    let opened = Kline {
+                                        time: open_time,
+                                        open: price,
+                                        high: price,
+                                        low: price,
+                                        close: price,
+                                        volume: (0.0, 0.0),
+                                    };
+
+                                    // the bar forming while connecting only spans the prices
+                                    // pushed since
+                                    let kline = mark_klines
+                                        .entry((ticker, *timeframe))
+                                        .and_modify(|kline| {
+                                            if kline.time == open_time {
+                                                kline.high = kline.high.max(price);
+                                                kline.low = kline.low.min(price);
+                                                kline.close = price;
+                                            } else {
+                                                *kline = opened;
+                                            }
+                                        })
+                                        .or_insert(opened);
+
+                                    let _ = output
+                                        .send(Event::KlineReceived(
+                                            StreamKind::Kline {
+                                                exchange,
+                                                ticker,
+                                                timeframe: *timeframe,
+                                                source: KlineSource::Mark,
+                                            },
+                                            *kline,
+                                        ))
+                                        .await;
+                                }
+                            }
+                            _ => {}
+                        },
+                        OpCode::Close => {
+                            state = State::Disconnected;
+                            let _ = output
+                                .send(Event::Disconnected(
+                                    exchange,
+                                    Box::from(stream_kinds.as_slice()),
+                                    "Connection closed".to_string(),
+                                ))
+                                .await;
+                        }
+                        _ => {}
+                    },
+                    Err(e) => {
+                        state = State::Disconnected;
+                        let _ = output
+                            .send(Event::Disconnected(
+                                exchange,
+                                Box::from(stream_kinds.as_slice()),
+                                "Error reading frame: ".to_string() + &e.to_string(),
+                            ))
+                            .await;
+                    }
+                },
+            }
+        }
+    })
+}
+
+/// Forced orders of a perpetual market, each message is the largest liquidation
+/// of the symbol within the last second
+pub fn connect_liquidation_stream(ticker: Ticker) -> impl Stream<Item = Event> {
+    stream::channel(100, async move |mut output| {
+        let mut state = State::Disconnected;
+
+        let (symbol_str, market) = ticker.to_full_symbol_and_type();
+        let exchange = exchange_from_market_type(market);
+
+        let stream_str = format!("{}@forceOrder", symbol_str.to_lowercase());
+
+        let domain = match market {
+            MarketKind::InversePerps => ws_domain(MarketKind::InversePerps),
+            MarketKind::Spot | MarketKind::LinearPerps => ws_domain(MarketKind::LinearPerps),
+        };
+
+        let contract_size = get_contract_size(&ticker, market);
+        let stream_kind = StreamKind::Liquidations { exchange, ticker };
+        let mut backoff = Backoff::new();
+
+        loop {
+            match &mut state {
+                State::Disconnected => {
+                    if let Ok(websocket) = connect(&domain, stream_str.as_str()).await {
+                        backoff.reset();
+                        state = State::Connected(websocket);
+                        let _ = output
+                            .send(Event::Connected(exchange, Box::from([stream_kind])))
+                            .await;
+                    } else {
+                        let _ = output
+                            .send(Event::Disconnected(
+                                exchange,
+                                Box::from([stream_kind]),
+                                "Failed to connect to websocket".to_string(),
+                            ))
+                            .await;
+
+                        if !wait_to_reconnect(&mut backoff, &[stream_kind], &mut output).await {
+                            return;
+                        }
+                    }
+                }
+                State::Connected(ws) => match ws.read_frame().await {
+                    Ok(msg) => match msg.opcode {
+                        OpCode::Text => {
+                            if let Ok(StreamData::Liquidation(de_liquidation)) =
+                                feed_de(&msg.payload[..], market)
+                            {
+                                let liquidation = Liquidation {
+                                    time: de_liquidation.time,
+                                    is_sell: de_liquidation.side == "SELL",
+                                    price: de_liquidation.avg_price,
+                                    qty: contract_size.map_or(de_liquidation.filled_qty, |size| {
+                                        de_liquidation.filled_qty * size
+                                    }),
+                                };
+
+                                let _ = output
+                                    .send(Event::LiquidationsReceived(
+                                        stream_kind,
+                                        Box::new([liquidation]),
+                                    ))
+                                    .await;
+                            }
                         }
                         OpCode::Close => {
                             state = State::Disconnected;
                             let _ = output
                                 .send(Event::Disconnected(
                                     exchange,
+                                    Box::from([stream_kind]),
                                     "Connection closed".to_string(),
                                 ))
                                 .await;
@@ -684,6 +1143,7 @@ pub fn connect_kline_stream(
                         let _ = output
                             .send(Event::Disconnected(
                                 exchange,
+                                Box::from([stream_kind]),
                                 "Error reading frame: ".to_string() + &e.to_string(),
                             ))
                             .await;
@@ -736,10 +1196,11 @@ fn new_depth_cache(depth: &SonicDepth, contract_size: Option<f32>) -> DepthPaylo
 async fn fetch_depth(ticker: &Ticker) -> Result<DepthPayload, AdapterError> {
     let (symbol_str, market_type) = ticker.to_full_symbol_and_type();
 
+    let domain = rest_domain(market_type);
     let base_url = match market_type {
-        MarketKind::Spot => SPOT_DOMAIN.to_string() + "/api/v3/depth",
-        MarketKind::LinearPerps => LINEAR_PERP_DOMAIN.to_string() + "/fapi/v1/depth",
-        MarketKind::InversePerps => INVERSE_PERP_DOMAIN.to_string() + "/dapi/v1/depth",
+        MarketKind::Spot => format!("{domain}/api/v3/depth"),
+        MarketKind::LinearPerps => format!("{domain}/fapi/v1/depth"),
+        MarketKind::InversePerps => format!("{domain}/dapi/v1/depth"),
     };
 
     let depth_limit = match market_type {
@@ -776,8 +1237,8 @@ async fn fetch_depth(ticker: &Ticker) -> Result<DepthPayload, AdapterError> {
 
     match market_type {
         MarketKind::Spot => {
-            let fetched_depth: FetchedSpotDepth =
-                serde_json::from_str(&text).map_err(|e| AdapterError::ParseError(e.to_string()))?;
+            let fetched_depth: FetchedSpotDepth = serde_json::from_str(&text)
+                .map_err(|e| parse_error(market_type, &text, e, "depth"))?;
 
             let depth = DepthPayload {
                 last_update_id: fetched_depth.update_id,
@@ -789,8 +1250,8 @@ async fn fetch_depth(ticker: &Ticker) -> Result<DepthPayload, AdapterError> {
             Ok(depth)
         }
         MarketKind::LinearPerps | MarketKind::InversePerps => {
-            let fetched_depth: FetchedPerpDepth =
-                serde_json::from_str(&text).map_err(|e| AdapterError::ParseError(e.to_string()))?;
+            let fetched_depth: FetchedPerpDepth = serde_json::from_str(&text)
+                .map_err(|e| parse_error(market_type, &text, e, "depth"))?;
 
             let depth = DepthPayload {
                 last_update_id: fetched_depth.update_id,
@@ -839,15 +1300,23 @@ impl From<FetchedKlines> for Kline {
 pub async fn fetch_klines(
     ticker: Ticker,
     timeframe: Timeframe,
+    source: KlineSource,
     range: Option<(u64, u64)>,
 ) -> Result<Vec<Kline>, AdapterError> {
     let (symbol_str, market_type) = ticker.to_full_symbol_and_type();
     let timeframe_str = timeframe.to_string();
 
-    let base_url = match market_type {
-        MarketKind::Spot => SPOT_DOMAIN.to_string() + "/api/v3/klines",
-        MarketKind::LinearPerps => LINEAR_PERP_DOMAIN.to_string() + "/fapi/v1/klines",
-        MarketKind::InversePerps => INVERSE_PERP_DOMAIN.to_string() + "/dapi/v1/klines",
+    let domain = rest_domain(market_type);
+    let base_url = match (market_type, source) {
+        (MarketKind::Spot, _) => format!("{domain}/api/v3/klines"),
+        (MarketKind::LinearPerps, KlineSource::Last) => format!("{domain}/fapi/v1/klines"),
+        (MarketKind::LinearPerps, KlineSource::Mark) => {
+            format!("{domain}/fapi/v1/markPriceKlines")
+        }
+        (MarketKind::InversePerps, KlineSource::Last) => format!("{domain}/dapi/v1/klines"),
+        (MarketKind::InversePerps, KlineSource::Mark) => {
+            format!("{domain}/dapi/v1/markPriceKlines")
+        }
     };
 
     let mut url = format!("{base_url}?symbol={symbol_str}&interval={timeframe_str}");
@@ -890,8 +1359,8 @@ pub async fn fetch_klines(
     let limiter = limiter_from_market_type(market_type);
     let text = crate::limiter::http_request_with_limiter(&url, limiter, weight).await?;
 
-    let fetched_klines: Vec<FetchedKlines> = serde_json::from_str(&text)
-        .map_err(|e| AdapterError::ParseError(format!("Failed to parse klines: {e}")))?;
+    let fetched_klines: Vec<FetchedKlines> =
+        serde_json::from_str(&text).map_err(|e| parse_error(market_type, &text, e, "klines"))?;
 
     let klines: Vec<_> = fetched_klines
         .into_iter()
@@ -923,33 +1392,27 @@ pub async fn fetch_klines(
     Ok(klines)
 }
 
-pub async fn fetch_ticksize(
-    market: MarketKind,
-) -> Result<HashMap<Ticker, Option<TickerInfo>>, AdapterError> {
-    let (url, _weight) = match market {
-        MarketKind::Spot => (SPOT_DOMAIN.to_string() + "/api/v3/exchangeInfo", 20),
-        MarketKind::LinearPerps => (LINEAR_PERP_DOMAIN.to_string() + "/fapi/v1/exchangeInfo", 1),
-        MarketKind::InversePerps => (INVERSE_PERP_DOMAIN.to_string() + "/dapi/v1/exchangeInfo", 1),
+pub async fn fetch_ticksize(market: MarketKind) -> Result<MarketInfo, AdapterError> {
+    let domain = rest_domain(market);
+    let (url, weight) = match market {
+        MarketKind::Spot => (format!("{domain}/api/v3/exchangeInfo"), 20),
+        MarketKind::LinearPerps => (format!("{domain}/fapi/v1/exchangeInfo"), 1),
+        MarketKind::InversePerps => (format!("{domain}/dapi/v1/exchangeInfo"), 1),
     };
 
-    let response_text = crate::limiter::HTTP_CLIENT
-        .get(&url)
-        .send()
-        .await
-        .map_err(AdapterError::FetchError)?
-        .text()
-        .await
-        .map_err(AdapterError::FetchError)?;
+    let limiter = limiter_from_market_type(market);
+    let response_text = crate::limiter::http_request_with_limiter(&url, limiter, weight).await?;
 
     let exchange_info: serde_json::Value = serde_json::from_str(&response_text)
-        .map_err(|e| AdapterError::ParseError(format!("Failed to parse exchange info: {e}")))?;
+        .map_err(|e| parse_error(market, &response_text, e, "exchange info"))?;
 
     let symbols = exchange_info["symbols"]
         .as_array()
-        .ok_or_else(|| AdapterError::ParseError("Missing symbols array".to_string()))?;
+        .ok_or_else(|| parse_error(market, &response_text, "missing symbols", "exchange info"))?;
 
     let exchange = exchange_from_market_type(market);
     let mut ticker_info_map = HashMap::new();
+    let mut specs_map = HashMap::new();
 
     for item in symbols {
         let symbol_str = item["symbol"]
@@ -978,6 +1441,16 @@ pub async fn fetch_ticksize(
             }
         }
 
+        // the funding interval isn't part of the exchange info, only of the funding info endpoint
+        specs_map.insert(
+            Ticker::new(symbol_str, exchange),
+            TickerSpecs {
+                contract_size: item["contractSize"].as_f64().map(|size| size as f32),
+                funding_interval_hours: None,
+                listed_at: item["onboardDate"].as_u64(),
+            },
+        );
+
         let filters = item["filters"]
             .as_array()
             .ok_or_else(|| AdapterError::ParseError("Missing filters array".to_string()))?;
@@ -1018,23 +1491,24 @@ pub async fn fetch_ticksize(
         }
     }
 
-    Ok(ticker_info_map)
+    Ok((ticker_info_map, specs_map))
 }
 
 pub async fn fetch_ticker_prices(
     market: MarketKind,
 ) -> Result<HashMap<Ticker, TickerStats>, AdapterError> {
+    let domain = rest_domain(market);
     let (url, weight) = match market {
-        MarketKind::Spot => (SPOT_DOMAIN.to_string() + "/api/v3/ticker/24hr", 80),
-        MarketKind::LinearPerps => (LINEAR_PERP_DOMAIN.to_string() + "/fapi/v1/ticker/24hr", 40),
-        MarketKind::InversePerps => (INVERSE_PERP_DOMAIN.to_string() + "/dapi/v1/ticker/24hr", 40),
+        MarketKind::Spot => (format!("{domain}/api/v3/ticker/24hr"), 80),
+        MarketKind::LinearPerps => (format!("{domain}/fapi/v1/ticker/24hr"), 40),
+        MarketKind::InversePerps => (format!("{domain}/dapi/v1/ticker/24hr"), 40),
     };
 
     let limiter = limiter_from_market_type(market);
     let text = crate::limiter::http_request_with_limiter(&url, limiter, weight).await?;
 
-    let value: Vec<serde_json::Value> = serde_json::from_str(&text)
-        .map_err(|e| AdapterError::ParseError(format!("Failed to parse prices: {e}")))?;
+    let value: Vec<serde_json::Value> =
+        serde_json::from_str(&text).map_err(|e| parse_error(market, &text, e, "prices"))?;
 
     let exchange = exchange_from_market_type(market);
     let mut ticker_price_map = HashMap::new();
@@ -1099,6 +1573,85 @@ pub async fn fetch_ticker_prices(
     Ok(ticker_price_map)
 }
 
+pub async fn fetch_server_time(market: MarketKind) -> Result<u64, AdapterError> {
+    let domain = rest_domain(market);
+    let url = match market {
+        MarketKind::Spot => format!("{domain}/api/v3/time"),
+        MarketKind::LinearPerps => format!("{domain}/fapi/v1/time"),
+        MarketKind::InversePerps => format!("{domain}/dapi/v1/time"),
+    };
+
+    let response = crate::limiter::http_client()?
+        .get(&url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(AdapterError::FetchError)?;
+
+    if !response.status().is_success() {
+        return Err(AdapterError::InvalidRequest(format!(
+            "HTTP {} from {url}",
+            response.status()
+        )));
+    }
+
+    let text = response.text().await.map_err(AdapterError::FetchError)?;
+
+    let value: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|e| AdapterError::ParseError(format!("Failed to parse server time: {e}")))?;
+
+    value["serverTime"]
+        .as_u64()
+        .ok_or_else(|| AdapterError::ParseError("Server time not found".to_string()))
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DePremiumIndex {
+    #[serde(deserialize_with = "de_string_to_f32")]
+    last_funding_rate: f32,
+    next_funding_time: u64,
+}
+
+pub async fn fetch_funding_rate(ticker: Ticker) -> Result<FundingRate, AdapterError> {
+    let (symbol_str, market) = ticker.to_full_symbol_and_type();
+
+    let domain = rest_domain(market);
+    let (url, weight) = match market {
+        MarketKind::LinearPerps => (
+            format!("{domain}/fapi/v1/premiumIndex?symbol={symbol_str}"),
+            1,
+        ),
+        MarketKind::InversePerps => (
+            format!("{domain}/dapi/v1/premiumIndex?symbol={symbol_str}"),
+            10,
+        ),
+        MarketKind::Spot => {
+            return Err(AdapterError::InvalidRequest(
+                "Funding is only paid on perpetual markets".to_string(),
+            ));
+        }
+    };
+
+    let limiter = limiter_from_market_type(market);
+    let text = crate::limiter::http_request_with_limiter(&url, limiter, weight).await?;
+
+    // the coin-m endpoint lists every contract of the pair even when asked for one symbol
+    let premium_index = match market {
+        MarketKind::InversePerps => {
+            serde_json::from_str::<Vec<DePremiumIndex>>(&text).map(|list| list.into_iter().next())
+        }
+        _ => serde_json::from_str::<DePremiumIndex>(&text).map(Some),
+    }
+    .map_err(|e| parse_error(market, &text, e, "funding rate"))?
+    .ok_or_else(|| AdapterError::ParseError("Funding rate not found".to_string()))?;
+
+    Ok(FundingRate {
+        rate: premium_index.last_funding_rate,
+        next_funding_time: premium_index.next_funding_time,
+    })
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct DeOpenInterest {
@@ -1121,14 +1674,15 @@ pub async fn fetch_historical_oi(
     let (ticker_str, market) = ticker.to_full_symbol_and_type();
     let period_str = period.to_string();
 
+    let domain = rest_domain(market);
     let (base_url, pair_str, weight) = match market {
         MarketKind::LinearPerps => (
-            LINEAR_PERP_DOMAIN.to_string() + "/futures/data/openInterestHist",
+            format!("{domain}/futures/data/openInterestHist"),
             format!("?symbol={ticker_str}",),
             12,
         ),
         MarketKind::InversePerps => (
-            INVERSE_PERP_DOMAIN.to_string() + "/futures/data/openInterestHist",
+            format!("{domain}/futures/data/openInterestHist"),
             format!(
                 "?pair={}&contractType=PERPETUAL",
                 ticker_str
@@ -1194,7 +1748,7 @@ pub async fn fetch_historical_oi(
             e,
             text
         );
-        AdapterError::ParseError(format!("Failed to parse open interest: {e}"))
+        parse_error(market, &text, e, "open interest")
     })?;
 
     let contract_size = get_contract_size(&ticker, market);
@@ -1221,15 +1775,12 @@ pub async fn fetch_trades(
         .unwrap()
         .and_utc();
 
-    if from_time as i64 >= today_midnight.timestamp_millis() {
+    // the daily archives only hold mainnet trades
+    if from_time as i64 >= today_midnight.timestamp_millis() || super::is_testnet(ticker.exchange) {
         return fetch_intraday_trades(ticker, from_time).await;
     }
 
-    let from_date = chrono::DateTime::from_timestamp_millis(from_time as i64)
-        .ok_or_else(|| AdapterError::ParseError("Invalid timestamp".into()))?
-        .date_naive();
-
-    match get_hist_trades(ticker, from_date, data_path).await {
+    match get_hist_trades(ticker, from_time, data_path).await {
         Ok(trades) => Ok(trades),
         Err(e) => {
             log::warn!(
@@ -1244,10 +1795,11 @@ pub async fn fetch_trades(
 pub async fn fetch_intraday_trades(ticker: Ticker, from: u64) -> Result<Vec<Trade>, AdapterError> {
     let (symbol_str, market_type) = ticker.to_full_symbol_and_type();
 
+    let domain = rest_domain(market_type);
     let (base_url, weight) = match market_type {
-        MarketKind::Spot => (SPOT_DOMAIN.to_string() + "/api/v3/aggTrades", 4),
-        MarketKind::LinearPerps => (LINEAR_PERP_DOMAIN.to_string() + "/fapi/v1/aggTrades", 20),
-        MarketKind::InversePerps => (INVERSE_PERP_DOMAIN.to_string() + "/dapi/v1/aggTrades", 20),
+        MarketKind::Spot => (format!("{domain}/api/v3/aggTrades"), 4),
+        MarketKind::LinearPerps => (format!("{domain}/fapi/v1/aggTrades"), 20),
+        MarketKind::InversePerps => (format!("{domain}/dapi/v1/aggTrades"), 20),
     };
 
     let mut url = format!("{base_url}?symbol={symbol_str}&limit=1000",);
@@ -1257,8 +1809,8 @@ pub async fn fetch_intraday_trades(ticker: Ticker, from: u64) -> Result<Vec<Trad
     let text = crate::limiter::http_request_with_limiter(&url, limiter, weight).await?;
 
     let trades: Vec<Trade> = {
-        let de_trades: Vec<SonicTrade> = sonic_rs::from_str(&text)
-            .map_err(|e| AdapterError::ParseError(format!("Failed to parse trades: {e}")))?;
+        let de_trades: Vec<SonicTrade> =
+            sonic_rs::from_str(&text).map_err(|e| parse_error(market_type, &text, e, "trades"))?;
 
         de_trades
             .into_iter()
@@ -1274,104 +1826,247 @@ pub async fn fetch_intraday_trades(ticker: Ticker, from: u64) -> Result<Vec<Trad
     Ok(trades)
 }
 
-pub async fn get_hist_trades(
-    ticker: Ticker,
-    date: chrono::NaiveDate,
-    base_path: PathBuf,
-) -> Result<Vec<Trade>, AdapterError> {
+/// Folder of the ticker's daily trade archives, mirroring the data.binance.vision layout
+fn hist_trades_subpath(ticker: Ticker) -> String {
     let (symbol, market_type) = ticker.to_full_symbol_and_type();
 
-    let market_subpath = match market_type {
+    match market_type {
         MarketKind::Spot => format!("data/spot/daily/aggTrades/{symbol}"),
         MarketKind::LinearPerps => format!("data/futures/um/daily/aggTrades/{symbol}"),
         MarketKind::InversePerps => format!("data/futures/cm/daily/aggTrades/{symbol}"),
-    };
+    }
+}
 
-    let zip_file_name = format!(
+fn hist_trades_file_name(ticker: Ticker, date: chrono::NaiveDate) -> String {
+    format!(
         "{}-aggTrades-{}.zip",
-        symbol.to_uppercase(),
+        ticker.to_full_symbol_and_type().0.to_uppercase(),
         date.format("%Y-%m-%d"),
-    );
+    )
+}
 
-    let base_path = base_path.join(&market_subpath);
+/// Days of the ticker that can be read without going to the network, oldest first. Archives
+/// downloaded before the trade store existed count too, they get ingested once read.
+pub fn cached_trade_dates(ticker: Ticker, base_path: &Path) -> Vec<chrono::NaiveDate> {
+    let mut dates = TradeStore::open(base_path)
+        .and_then(|store| store.days(ticker))
+        .unwrap_or_else(|err| {
+            log::warn!("Failed to list stored trade days: {err}");
+            vec![]
+        });
+
+    dates.extend(archived_trade_dates(ticker, base_path));
+
+    dates.sort();
+    dates.dedup();
+    dates
+}
 
-    std::fs::create_dir_all(&base_path)
-        .map_err(|e| AdapterError::ParseError(format!("Failed to create directories: {e}")))?;
+/// Days with a trade archive on disk that wasn't ingested into the trade store yet
+fn archived_trade_dates(ticker: Ticker, base_path: &Path) -> Vec<chrono::NaiveDate> {
+    let Ok(entries) = std::fs::read_dir(base_path.join(hist_trades_subpath(ticker))) else {
+        return vec![];
+    };
 
-    let zip_path = format!("{market_subpath}/{zip_file_name}",);
-    let base_zip_path = base_path.join(&zip_file_name);
+    let prefix = format!(
+        "{}-aggTrades-",
+        ticker.to_full_symbol_and_type().0.to_uppercase()
+    );
 
-    if std::fs::metadata(&base_zip_path).is_ok() {
-        log::info!("Using cached {}", zip_path);
-    } else {
-        let url = format!("https://data.binance.vision/{zip_path}");
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let date = file_name
+                .to_str()?
+                .strip_prefix(&prefix)?
+                .strip_suffix(".zip")?
+                .to_string();
+
+            chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()
+        })
+        .collect()
+}
 
-        log::info!("Downloading from {}", url);
+/// Trades of a stored day, without ever going to the network
+pub async fn read_cached_trades(
+    ticker: Ticker,
+    date: chrono::NaiveDate,
+    base_path: PathBuf,
+) -> Result<Vec<Trade>, AdapterError> {
+    let _day_lock = trade_store::lock_day(ticker, date).await;
 
-        let resp = reqwest::get(&url).await.map_err(AdapterError::FetchError)?;
+    TradeStore::run(base_path.clone(), move |store| {
+        if !store.has_day(ticker, date)? {
+            let zip_path = base_path
+                .join(hist_trades_subpath(ticker))
+                .join(hist_trades_file_name(ticker, date));
 
-        if !resp.status().is_success() {
-            return Err(AdapterError::InvalidRequest(format!(
-                "Failed to fetch from {}: {}",
-                url,
-                resp.status()
-            )));
+            ingest_trades_archive(store, ticker, date, &zip_path)?;
         }
 
-        let body = resp.bytes().await.map_err(AdapterError::FetchError)?;
+        let (day_start, day_end) = day_bounds(date);
+        store.trades_in_range(ticker, day_start, day_end)
+    })
+    .await
+}
+
+/// Unix milliseconds the UTC day starts and ends at
+fn day_bounds(date: chrono::NaiveDate) -> (u64, u64) {
+    let start = date
+        .and_hms_opt(0, 0, 0)
+        .expect("Midnight is a valid time")
+        .and_utc()
+        .timestamp_millis() as u64;
+
+    (start, start + 24 * 60 * 60 * 1000)
+}
 
-        std::fs::write(&base_zip_path, &body).map_err(|e| {
-            AdapterError::ParseError(format!("Failed to write zip file: {e}, {base_zip_path:?}"))
-        })?;
+/// Moves a day's archive into the trade store, the archive itself isn't needed afterwards
+fn ingest_trades_archive(
+    store: &mut TradeStore,
+    ticker: Ticker,
+    date: chrono::NaiveDate,
+    zip_path: &Path,
+) -> Result<(), AdapterError> {
+    let trades = read_trades_archive(zip_path)?;
+    store.ingest(ticker, date, &trades)?;
+
+    if let Err(e) = std::fs::remove_file(zip_path) {
+        log::warn!("Failed to remove ingested archive {zip_path:?}: {e}");
     }
 
-    match std::fs::File::open(&base_zip_path) {
-        Ok(file) => {
-            let mut archive = zip::ZipArchive::new(file)
-                .map_err(|e| AdapterError::ParseError(format!("Failed to unzip file: {e}")))?;
-
-            let mut trades = Vec::new();
-            for i in 0..archive.len() {
-                let csv_file = archive
-                    .by_index(i)
-                    .map_err(|e| AdapterError::ParseError(format!("Failed to read csv: {e}")))?;
-
-                let mut csv_reader = ReaderBuilder::new()
-                    .has_headers(false)
-                    .from_reader(BufReader::new(csv_file));
-
-                trades.extend(csv_reader.records().filter_map(|record| {
-                    record.ok().and_then(|record| {
-                        let time = record[5].parse::<u64>().ok()?;
-                        let is_sell = record[6].parse::<bool>().ok()?;
-                        let price = str_f32_parse(&record[1]);
-                        let qty = str_f32_parse(&record[2]);
-
-                        Some(Trade {
-                            time,
-                            is_sell,
-                            price,
-                            qty,
-                        })
-                    })
-                }));
-            }
+    Ok(())
+}
 
-            if let Some(latest_trade) = trades.last() {
-                match fetch_intraday_trades(ticker, latest_trade.time).await {
-                    Ok(intraday_trades) => {
-                        trades.extend(intraday_trades);
-                    }
-                    Err(e) => {
-                        log::error!("Failed to fetch intraday trades: {}", e);
-                    }
-                }
+/// Trades of a daily archive, each with its aggregate trade id
+fn read_trades_archive(path: &Path) -> Result<Vec<(u64, Trade)>, AdapterError> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| AdapterError::ParseError(format!("Failed to open compressed file: {e}")))?;
+
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| AdapterError::ParseError(format!("Failed to unzip file: {e}")))?;
+
+    let mut trades = Vec::new();
+    for i in 0..archive.len() {
+        let csv_file = archive
+            .by_index(i)
+            .map_err(|e| AdapterError::ParseError(format!("Failed to read csv: {e}")))?;
+
+        let mut csv_reader = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(BufReader::new(csv_file));
+
+        trades.extend(csv_reader.records().filter_map(|record| {
+            record.ok().and_then(|record| {
+                let agg_id = record[0].parse::<u64>().ok()?;
+                let time = record[5].parse::<u64>().ok()?;
+                let is_sell = record[6].parse::<bool>().ok()?;
+                let price = str_f32_parse(&record[1]);
+                let qty = str_f32_parse(&record[2]);
+
+                Some((
+                    agg_id,
+                    Trade {
+                        time,
+                        is_sell,
+                        price,
+                        qty,
+                    },
+                ))
+            })
+        }));
+    }
+
+    Ok(trades)
+}
+
+/// Trades from `from_time` to the end of its day, out of the trade store. The day's archive
+/// gets downloaded and ingested first if it isn't stored yet.
+pub async fn get_hist_trades(
+    ticker: Ticker,
+    from_time: u64,
+    base_path: PathBuf,
+) -> Result<Vec<Trade>, AdapterError> {
+    let date = chrono::DateTime::from_timestamp_millis(from_time as i64)
+        .ok_or_else(|| AdapterError::ParseError("Invalid timestamp".into()))?
+        .date_naive();
+
+    // held until the day is stored, a pane fetching the same day meanwhile waits for it
+    let day_lock = trade_store::lock_day(ticker, date).await;
+
+    let is_stored =
+        TradeStore::run(base_path.clone(), move |store| store.has_day(ticker, date)).await?;
+
+    if !is_stored {
+        let market_subpath = hist_trades_subpath(ticker);
+        let zip_file_name = hist_trades_file_name(ticker, date);
+
+        let folder = base_path.join(&market_subpath);
+
+        std::fs::create_dir_all(&folder)
+            .map_err(|e| AdapterError::ParseError(format!("Failed to create directories: {e}")))?;
+
+        let zip_path = format!("{market_subpath}/{zip_file_name}",);
+        let base_zip_path = folder.join(&zip_file_name);
+
+        if std::fs::metadata(&base_zip_path).is_ok() {
+            log::info!("Using cached {}", zip_path);
+        } else {
+            let url = format!("https://data.binance.vision/{zip_path}");
+
+            log::info!("Downloading from {}", url);
+
+            // a day of trades can take longer to download than a regular request
+            let resp = crate::limiter::http_client()?
+                .get(&url)
+                .timeout(ARCHIVE_TIMEOUT)
+                .send()
+                .await
+                .map_err(AdapterError::FetchError)?;
+
+            if !resp.status().is_success() {
+                return Err(AdapterError::InvalidRequest(format!(
+                    "Failed to fetch from {}: {}",
+                    url,
+                    resp.status()
+                )));
             }
 
-            Ok(trades)
+            let body = resp.bytes().await.map_err(AdapterError::FetchError)?;
+
+            std::fs::write(&base_zip_path, &body).map_err(|e| {
+                AdapterError::ParseError(format!(
+                    "Failed to write zip file: {e}, {base_zip_path:?}"
+                ))
+            })?;
         }
-        Err(e) => Err(AdapterError::ParseError(format!(
-            "Failed to open compressed file: {e}"
-        ))),
+
+        TradeStore::run(base_path.clone(), move |store| {
+            ingest_trades_archive(store, ticker, date, &base_zip_path)
+        })
+        .await?;
     }
+    drop(day_lock);
+
+    let (_, day_end) = day_bounds(date);
+    let mut trades = TradeStore::run(base_path, move |store| {
+        store.trades_in_range(ticker, from_time, day_end)
+    })
+    .await?;
+
+    let Some(latest_trade) = trades.last() else {
+        return fetch_intraday_trades(ticker, from_time).await;
+    };
+
+    match fetch_intraday_trades(ticker, latest_trade.time).await {
+        Ok(intraday_trades) => {
+            trades.extend(intraday_trades);
+        }
+        Err(e) => {
+            log::error!("Failed to fetch intraday trades: {}", e);
+        }
+    }
+
+    Ok(trades)
 }
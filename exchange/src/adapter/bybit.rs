@@ -2,14 +2,17 @@ use crate::limiter::{self, http_request_with_limiter};
 
 use super::{
     super::{
-        Exchange, Kline, MarketKind, OpenInterest, StreamKind, Ticker, TickerInfo, TickerStats,
-        Timeframe, Trade,
-        connect::{State, setup_tcp_connection, setup_tls_connection, setup_websocket_connection},
+        Exchange, FundingRate, Kline, MarketKind, OpenInterest, StreamKind, Ticker, TickerInfo,
+        TickerSpecs, TickerStats, Timeframe, Trade,
+        connect::{
+            Backoff, State, setup_tcp_connection, setup_tls_connection, setup_websocket_connection,
+            wait_to_reconnect,
+        },
         de_string_to_f32, de_string_to_u64,
         depth::{DepthPayload, DepthUpdate, LocalDepthCache, Order},
         is_symbol_supported,
     },
-    AdapterError, Event,
+    AdapterError, ApiError, Event, KlineSource, MarketInfo,
 };
 
 use fastwebsockets::{FragmentCollector, Frame, OpCode};
@@ -52,15 +55,26 @@ impl limiter::RateLimiter for BybitLimiter {
         self.bucket.calculate_wait_time(weight)
     }
 
-    fn update_from_response(&mut self, _response: &reqwest::Response, weight: usize) {
-        self.bucket.consume_tokens(weight);
-    }
+    // the weight was taken from the bucket when the request got prepared
+    fn update_from_response(&mut self, _response: &reqwest::Response, _weight: usize) {}
 
-    fn should_exit_on_response(&self, response: &reqwest::Response) -> bool {
+    fn is_blocked_response(&self, response: &reqwest::Response) -> bool {
         response.status() == 403
     }
 }
 
+/// Bybit answers failed requests with a non-zero `retCode`, the reason being in `retMsg`
+fn check_ret_code(market: MarketKind, content: &Value) -> Result<(), AdapterError> {
+    match content["retCode"].as_i64() {
+        Some(0) | None => Ok(()),
+        Some(code) => Err(AdapterError::Api(ApiError {
+            exchange: exchange_from_market_type(market),
+            code,
+            message: content["retMsg"].as_str().unwrap_or_default().to_string(),
+        })),
+    }
+}
+
 fn exchange_from_market_type(market: MarketKind) -> Exchange {
     match market {
         MarketKind::Spot => Exchange::BybitSpot,
@@ -69,6 +83,30 @@ fn exchange_from_market_type(market: MarketKind) -> Exchange {
     }
 }
 
+fn rest_domain(market: MarketKind) -> String {
+    let exchange = exchange_from_market_type(market);
+
+    if let Some(domain) = super::rest_override(exchange) {
+        domain
+    } else if super::is_testnet(exchange) {
+        "https://api-testnet.bybit.com".to_string()
+    } else {
+        "https://api.bybit.com".to_string()
+    }
+}
+
+fn ws_domain(market: MarketKind) -> String {
+    let exchange = exchange_from_market_type(market);
+
+    if let Some(domain) = super::ws_override(exchange) {
+        domain
+    } else if super::is_testnet(exchange) {
+        "stream-testnet.bybit.com".to_string()
+    } else {
+        "stream.bybit.com".to_string()
+    }
+}
+
 #[derive(Deserialize)]
 struct SonicDepth {
     #[serde(rename = "u")]
@@ -253,7 +291,7 @@ async fn connect(
     let tcp_stream = setup_tcp_connection(domain).await?;
     let tls_stream = setup_tls_connection(domain, tcp_stream).await?;
     let url = format!(
-        "wss://stream.bybit.com/v5/public/{}",
+        "wss://{domain}/v5/public/{}",
         match market_type {
             MarketKind::Spot => "spot",
             MarketKind::LinearPerps => "linear",
@@ -263,18 +301,22 @@ async fn connect(
     setup_websocket_connection(domain, tls_stream, &url).await
 }
 
+/// Connects and subscribes, waiting out the backoff after a failure. `None` once the
+/// connection ran out of retries
 async fn try_connect(
     streams: &Value,
     market_type: MarketKind,
+    stream_kinds: &[StreamKind],
+    backoff: &mut Backoff,
     output: &mut mpsc::Sender<Event>,
-) -> State {
+) -> Option<State> {
     let exchange = match market_type {
         MarketKind::Spot => Exchange::BybitSpot,
         MarketKind::LinearPerps => Exchange::BybitLinear,
         MarketKind::InversePerps => Exchange::BybitInverse,
     };
 
-    match connect("stream.bybit.com", market_type).await {
+    match connect(&ws_domain(market_type), market_type).await {
         Ok(mut websocket) => {
             if let Err(e) = websocket
                 .write_frame(Frame::text(fastwebsockets::Payload::Borrowed(
@@ -285,25 +327,34 @@ async fn try_connect(
                 let _ = output
                     .send(Event::Disconnected(
                         exchange,
+                        Box::from(stream_kinds),
                         format!("Failed subscribing: {e}"),
                     ))
                     .await;
-                return State::Disconnected;
+
+                return wait_to_reconnect(backoff, stream_kinds, output)
+                    .await
+                    .then_some(State::Disconnected);
             }
 
-            let _ = output.send(Event::Connected(exchange)).await;
-            State::Connected(websocket)
+            backoff.reset();
+            let _ = output
+                .send(Event::Connected(exchange, Box::from(stream_kinds)))
+                .await;
+            Some(State::Connected(websocket))
         }
         Err(err) => {
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-
             let _ = output
                 .send(Event::Disconnected(
                     exchange,
+                    Box::from(stream_kinds),
                     format!("Failed to connect: {err}"),
                 ))
                 .await;
-            State::Disconnected
+
+            wait_to_reconnect(backoff, stream_kinds, output)
+                .await
+                .then_some(State::Disconnected)
         }
     }
 }
@@ -333,11 +384,24 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
 
         let mut trades_buffer: Vec<Trade> = Vec::new();
         let mut orderbook = LocalDepthCache::default();
+        let stream_kinds = [StreamKind::DepthAndTrades { exchange, ticker }];
+        let mut backoff = Backoff::new();
 
         loop {
             match &mut state {
                 State::Disconnected => {
-                    state = try_connect(&subscribe_message, market_type, &mut output).await;
+                    match try_connect(
+                        &subscribe_message,
+                        market_type,
+                        &stream_kinds,
+                        &mut backoff,
+                        &mut output,
+                    )
+                    .await
+                    {
+                        Some(next) => state = next,
+                        None => return,
+                    }
                 }
                 State::Connected(websocket) => match websocket.read_frame().await {
                     Ok(msg) => match msg.opcode {
@@ -406,6 +470,7 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
                             let _ = output
                                 .send(Event::Disconnected(
                                     exchange,
+                                    Box::from(stream_kinds.as_slice()),
                                     "Connection closed".to_string(),
                                 ))
                                 .await;
@@ -417,6 +482,7 @@ pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
                         let _ = output
                             .send(Event::Disconnected(
                                 exchange,
+                                Box::from(stream_kinds.as_slice()),
                                 "Error reading frame: ".to_string() + &e.to_string(),
                             ))
                             .await;
@@ -438,18 +504,12 @@ pub fn connect_kline_stream(
 
         let stream_str = streams
             .iter()
-            .map(|(ticker, timeframe)| {
-                let timeframe_str = {
-                    if Timeframe::D1 == *timeframe {
-                        "D".to_string()
-                    } else {
-                        timeframe.to_minutes().to_string()
-                    }
-                };
-                format!(
+            .filter_map(|(ticker, timeframe)| {
+                let timeframe_str = timeframe_to_interval(*timeframe)?;
+                Some(format!(
                     "kline.{timeframe_str}.{}",
                     ticker.to_full_symbol_and_type().0
-                )
+                ))
             })
             .collect::<Vec<String>>();
 
@@ -457,11 +517,32 @@ pub fn connect_kline_stream(
             "op": "subscribe",
             "args": stream_str
         });
+        let stream_kinds = streams
+            .iter()
+            .map(|(ticker, timeframe)| StreamKind::Kline {
+                exchange,
+                ticker: *ticker,
+                timeframe: *timeframe,
+                source: KlineSource::Last,
+            })
+            .collect::<Vec<_>>();
+        let mut backoff = Backoff::new();
 
         loop {
             match &mut state {
                 State::Disconnected => {
-                    state = try_connect(&subscribe_message, market_type, &mut output).await;
+                    match try_connect(
+                        &subscribe_message,
+                        market_type,
+                        &stream_kinds,
+                        &mut backoff,
+                        &mut output,
+                    )
+                    .await
+                    {
+                        Some(next) => state = next,
+                        None => return,
+                    }
                 }
                 State::Connected(websocket) => match websocket.read_frame().await {
                     Ok(msg) => match msg.opcode {
@@ -487,6 +568,7 @@ pub fn connect_kline_stream(
                                                     exchange,
                                                     ticker,
                                                     timeframe,
+                                                    source: KlineSource::Last,
                                                 },
                                                 kline,
                                             ))
@@ -506,6 +588,7 @@ pub fn connect_kline_stream(
                             let _ = output
                                 .send(Event::Disconnected(
                                     exchange,
+                                    Box::from(stream_kinds.as_slice()),
                                     "Connection closed".to_string(),
                                 ))
                                 .await;
@@ -517,6 +600,7 @@ pub fn connect_kline_stream(
                         let _ = output
                             .send(Event::Disconnected(
                                 exchange,
+                                Box::from(stream_kinds.as_slice()),
                                 "Error reading frame: ".to_string() + &e.to_string(),
                             ))
                             .await;
@@ -527,18 +611,20 @@ pub fn connect_kline_stream(
     })
 }
 
+/// Kline interval as Bybit names it, `None` for the ones it doesn't have like 3 days
+fn timeframe_to_interval(timeframe: Timeframe) -> Option<String> {
+    match timeframe {
+        Timeframe::D1 => Some("D".to_string()),
+        Timeframe::W1 => Some("W".to_string()),
+        Timeframe::D3 => None,
+        _ => Some(timeframe.to_minutes().to_string()),
+    }
+}
+
 fn string_to_timeframe(interval: &str) -> Option<Timeframe> {
     Timeframe::KLINE
         .iter()
-        .find(|&tf| {
-            tf.to_minutes().to_string() == interval || {
-                if tf == &Timeframe::D1 {
-                    interval == "D"
-                } else {
-                    false
-                }
-            }
-        })
+        .find(|&tf| timeframe_to_interval(*tf).as_deref() == Some(interval))
         .copied()
 }
 
@@ -551,15 +637,14 @@ struct DeOpenInterest {
     pub timestamp: u64,
 }
 
-/// # Panics
-///
-/// Will panic if the `period` is not one of the supported timeframes for open interest
 pub async fn fetch_historical_oi(
     ticker: Ticker,
     range: Option<(u64, u64)>,
     period: Timeframe,
 ) -> Result<Vec<OpenInterest>, AdapterError> {
-    let ticker_str = ticker.to_full_symbol_and_type().0.to_uppercase();
+    let (ticker_str, market_type) = ticker.to_full_symbol_and_type();
+    let ticker_str = ticker_str.to_uppercase();
+
     let period_str = match period {
         Timeframe::M5 => "5min",
         Timeframe::M15 => "15min",
@@ -567,16 +652,31 @@ pub async fn fetch_historical_oi(
         Timeframe::H1 => "1h",
         Timeframe::H4 => "4h",
         Timeframe::D1 => "1d",
-        _ => panic!("Unsupported timeframe for open interest: {period}"),
+        _ => {
+            return Err(AdapterError::InvalidRequest(format!(
+                "Unsupported timeframe for Bybit open interest: {period}"
+            )));
+        }
+    };
+
+    let category = match market_type {
+        MarketKind::LinearPerps => "linear",
+        MarketKind::InversePerps => "inverse",
+        MarketKind::Spot => {
+            return Err(AdapterError::InvalidRequest(
+                "Open interest is only available for perpetuals".to_string(),
+            ));
+        }
     };
 
     let mut url = format!(
-        "https://api.bybit.com/v5/market/open-interest?category=linear&symbol={ticker_str}&intervalTime={period_str}",
+        "{}/v5/market/open-interest?category={category}&symbol={ticker_str}&intervalTime={period_str}",
+        rest_domain(market_type),
     );
 
     if let Some((start, end)) = range {
         let interval_ms = period.to_milliseconds();
-        let num_intervals = ((end - start) / interval_ms).min(200);
+        let num_intervals = ((end - start) / interval_ms).clamp(1, 200);
 
         url.push_str(&format!(
             "&startTime={start}&endTime={end}&limit={num_intervals}"
@@ -596,6 +696,7 @@ pub async fn fetch_historical_oi(
         );
         AdapterError::ParseError(e.to_string())
     })?;
+    check_ret_code(market_type, &content)?;
 
     let result_list = content["result"]["list"].as_array().ok_or_else(|| {
         log::error!("Result list is not an array in response: {}", response_text);
@@ -664,13 +765,9 @@ pub async fn fetch_klines(
     range: Option<(u64, u64)>,
 ) -> Result<Vec<Kline>, AdapterError> {
     let (symbol_str, market_type) = &ticker.to_full_symbol_and_type();
-    let timeframe_str = {
-        if Timeframe::D1 == timeframe {
-            "D".to_string()
-        } else {
-            timeframe.to_minutes().to_string()
-        }
-    };
+    let timeframe_str = timeframe_to_interval(timeframe).ok_or_else(|| {
+        AdapterError::InvalidRequest(format!("Unsupported timeframe for Bybit: {timeframe}"))
+    })?;
 
     let market = match market_type {
         MarketKind::Spot => "spot",
@@ -679,7 +776,8 @@ pub async fn fetch_klines(
     };
 
     let mut url = format!(
-        "https://api.bybit.com/v5/market/kline?category={}&symbol={}&interval={}",
+        "{}/v5/market/kline?category={}&symbol={}&interval={}",
+        rest_domain(*market_type),
         market,
         symbol_str.to_uppercase(),
         timeframe_str
@@ -696,8 +794,12 @@ pub async fn fetch_klines(
 
     let response_text = http_request_with_limiter(&url, &BYBIT_LIMITER, 1).await?;
 
-    let value: ApiResponse =
+    let content: Value =
         sonic_rs::from_str(&response_text).map_err(|e| AdapterError::ParseError(e.to_string()))?;
+    check_ret_code(*market_type, &content)?;
+
+    let value: ApiResponse =
+        serde_json::from_value(content).map_err(|e| AdapterError::ParseError(e.to_string()))?;
 
     let klines: Result<Vec<Kline>, AdapterError> = value
         .result
@@ -725,9 +827,7 @@ pub async fn fetch_klines(
     klines
 }
 
-pub async fn fetch_ticksize(
-    market_type: MarketKind,
-) -> Result<HashMap<Ticker, Option<TickerInfo>>, AdapterError> {
+pub async fn fetch_ticksize(market_type: MarketKind) -> Result<MarketInfo, AdapterError> {
     let exchange = exchange_from_market_type(market_type);
 
     let market = match market_type {
@@ -736,26 +836,23 @@ pub async fn fetch_ticksize(
         MarketKind::InversePerps => "inverse",
     };
 
-    let url =
-        format!("https://api.bybit.com/v5/market/instruments-info?category={market}&limit=1000",);
+    let url = format!(
+        "{}/v5/market/instruments-info?category={market}&limit=1000",
+        rest_domain(market_type)
+    );
 
-    let response_text = crate::limiter::HTTP_CLIENT
-        .get(&url)
-        .send()
-        .await
-        .map_err(AdapterError::FetchError)?
-        .text()
-        .await
-        .map_err(AdapterError::FetchError)?;
+    let response_text = http_request_with_limiter(&url, &BYBIT_LIMITER, 1).await?;
 
     let exchange_info: Value =
         sonic_rs::from_str(&response_text).map_err(|e| AdapterError::ParseError(e.to_string()))?;
+    check_ret_code(market_type, &exchange_info)?;
 
     let result_list: &Vec<Value> = exchange_info["result"]["list"]
         .as_array()
         .ok_or_else(|| AdapterError::ParseError("Result list is not an array".to_string()))?;
 
     let mut ticker_info_map = HashMap::new();
+    let mut specs_map = HashMap::new();
 
     for item in result_list {
         let symbol = item["symbol"]
@@ -800,6 +897,21 @@ pub async fn fetch_ticksize(
 
         let ticker = Ticker::new(symbol, exchange);
 
+        // funding intervals are listed in minutes, spot instruments have none
+        specs_map.insert(
+            ticker,
+            TickerSpecs {
+                contract_size: None,
+                funding_interval_hours: item["fundingInterval"]
+                    .as_u64()
+                    .filter(|minutes| *minutes > 0)
+                    .map(|minutes| (minutes / 60) as u32),
+                listed_at: item["launchTime"]
+                    .as_str()
+                    .and_then(|time| time.parse::<u64>().ok()),
+            },
+        );
+
         ticker_info_map.insert(
             ticker,
             Some(TickerInfo {
@@ -810,7 +922,75 @@ pub async fn fetch_ticksize(
         );
     }
 
-    Ok(ticker_info_map)
+    Ok((ticker_info_map, specs_map))
+}
+
+pub async fn fetch_server_time(market_type: MarketKind) -> Result<u64, AdapterError> {
+    let url = format!("{}/v5/market/time", rest_domain(market_type));
+
+    let response = limiter::http_client()?
+        .get(&url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(AdapterError::FetchError)?;
+
+    if !response.status().is_success() {
+        return Err(AdapterError::InvalidRequest(format!(
+            "HTTP {} from {url}",
+            response.status()
+        )));
+    }
+
+    let text = response.text().await.map_err(AdapterError::FetchError)?;
+
+    let value: Value =
+        sonic_rs::from_str(&text).map_err(|e| AdapterError::ParseError(e.to_string()))?;
+
+    value["time"]
+        .as_u64()
+        .ok_or_else(|| AdapterError::ParseError("Server time not found".to_string()))
+}
+
+pub async fn fetch_funding_rate(ticker: Ticker) -> Result<FundingRate, AdapterError> {
+    let (symbol_str, market_type) = ticker.to_full_symbol_and_type();
+
+    let category = match market_type {
+        MarketKind::LinearPerps => "linear",
+        MarketKind::InversePerps => "inverse",
+        MarketKind::Spot => {
+            return Err(AdapterError::InvalidRequest(
+                "Funding is only paid on perpetual markets".to_string(),
+            ));
+        }
+    };
+
+    let url = format!(
+        "{}/v5/market/tickers?category={category}&symbol={symbol_str}",
+        rest_domain(market_type)
+    );
+
+    let response_text = http_request_with_limiter(&url, &BYBIT_LIMITER, 1).await?;
+
+    let content: Value =
+        sonic_rs::from_str(&response_text).map_err(|e| AdapterError::ParseError(e.to_string()))?;
+    check_ret_code(market_type, &content)?;
+
+    let item = &content["result"]["list"][0];
+
+    let rate = item["fundingRate"]
+        .as_str()
+        .and_then(|rate| rate.parse::<f32>().ok())
+        .ok_or_else(|| AdapterError::ParseError("Funding rate not found".to_string()))?;
+    let next_funding_time = item["nextFundingTime"]
+        .as_str()
+        .and_then(|time| time.parse::<u64>().ok())
+        .ok_or_else(|| AdapterError::ParseError("Next funding time not found".to_string()))?;
+
+    Ok(FundingRate {
+        rate,
+        next_funding_time,
+    })
 }
 
 pub async fn fetch_ticker_prices(
@@ -824,12 +1004,16 @@ pub async fn fetch_ticker_prices(
         MarketKind::InversePerps => "inverse",
     };
 
-    let url = format!("https://api.bybit.com/v5/market/tickers?category={market}");
+    let url = format!(
+        "{}/v5/market/tickers?category={market}",
+        rest_domain(market_type)
+    );
 
     let response_text = http_request_with_limiter(&url, &BYBIT_LIMITER, 1).await?;
 
     let exchange_info: Value =
         sonic_rs::from_str(&response_text).map_err(|e| AdapterError::ParseError(e.to_string()))?;
+    check_ret_code(market_type, &exchange_info)?;
 
     let result_list: &Vec<Value> = exchange_info["result"]["list"]
         .as_array()
@@ -0,0 +1,1160 @@
+use crate::limiter::{self, http_request_with_limiter};
+
+use super::{
+    super::{
+        Exchange, FundingRate, Kline, MarketKind, StreamKind, Ticker, TickerInfo, TickerSpecs,
+        TickerStats, Timeframe, Trade,
+        connect::{
+            Backoff, State, setup_tcp_connection, setup_tls_connection, setup_websocket_connection,
+            wait_to_reconnect,
+        },
+        de_string_to_f32, de_string_to_u64,
+        depth::{DepthPayload, DepthUpdate, LocalDepthCache, Order},
+        is_symbol_supported,
+    },
+    AdapterError, Event, KlineSource, MarketInfo,
+};
+
+use fastwebsockets::{FragmentCollector, Frame, OpCode};
+use hyper::upgrade::Upgraded;
+use hyper_util::rt::TokioIo;
+use iced_futures::{
+    futures::{SinkExt, Stream, channel::mpsc},
+    stream,
+};
+use ordered_float::OrderedFloat;
+use serde_json::Value;
+use sonic_rs::to_object_iter_unchecked;
+use sonic_rs::{Deserialize, JsonValueTrait};
+use tokio::sync::Mutex;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{LazyLock, PoisonError, RwLock},
+    time::Duration,
+};
+
+const REST_DOMAIN: &str = "https://www.okx.com";
+const WS_DOMAIN: &str = "ws.okx.com:8443";
+
+/// Public market data endpoints allow 20 requests per 2 seconds per IP
+const LIMIT: usize = 20;
+
+const REFILL_RATE: Duration = Duration::from_secs(2);
+const LIMITER_BUFFER_PCT: f32 = 0.05;
+
+/// OKX drops connections that stay silent for 30 seconds, quiet streams keep alive with a ping
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Levels of the book the checksum is computed over
+const CHECKSUM_DEPTH: usize = 25;
+
+static OKX_LIMITER: LazyLock<Mutex<OkxLimiter>> =
+    LazyLock::new(|| Mutex::new(OkxLimiter::new(LIMIT, REFILL_RATE)));
+
+/// What a contract of each swap is worth, in base currency for linear and USD for inverse ones
+static CONTRACT_VALUES: LazyLock<RwLock<HashMap<Ticker, f32>>> = LazyLock::new(Default::default);
+
+pub struct OkxLimiter {
+    bucket: limiter::FixedWindowBucket,
+}
+
+impl OkxLimiter {
+    pub fn new(limit: usize, refill_rate: Duration) -> Self {
+        let effective_limit = (limit as f32 * (1.0 - LIMITER_BUFFER_PCT)) as usize;
+        Self {
+            bucket: limiter::FixedWindowBucket::new(effective_limit, refill_rate),
+        }
+    }
+}
+
+impl limiter::RateLimiter for OkxLimiter {
+    fn prepare_request(&mut self, weight: usize) -> Option<Duration> {
+        self.bucket.calculate_wait_time(weight)
+    }
+
+    // the weight was taken from the bucket when the request got prepared
+    fn update_from_response(&mut self, _response: &reqwest::Response, _weight: usize) {}
+
+    fn is_blocked_response(&self, response: &reqwest::Response) -> bool {
+        response.status() == 403
+    }
+}
+
+fn exchange_from_market_type(market: MarketKind) -> Exchange {
+    match market {
+        MarketKind::Spot => Exchange::OkxSpot,
+        MarketKind::LinearPerps => Exchange::OkxLinear,
+        MarketKind::InversePerps => Exchange::OkxInverse,
+    }
+}
+
+fn rest_domain(market: MarketKind) -> String {
+    super::rest_override(exchange_from_market_type(market))
+        .unwrap_or_else(|| REST_DOMAIN.to_string())
+}
+
+fn ws_domain(market: MarketKind) -> String {
+    super::ws_override(exchange_from_market_type(market)).unwrap_or_else(|| WS_DOMAIN.to_string())
+}
+
+fn inst_type(market: MarketKind) -> &'static str {
+    match market {
+        MarketKind::Spot => "SPOT",
+        MarketKind::LinearPerps | MarketKind::InversePerps => "SWAP",
+    }
+}
+
+fn quote_currency(market: MarketKind) -> &'static str {
+    match market {
+        MarketKind::Spot | MarketKind::LinearPerps => "USDT",
+        MarketKind::InversePerps => "USD",
+    }
+}
+
+/// Tickers drop the dashes of OKX instrument ids, so `BTC-USDT` and `BTC-USDT-SWAP` are both
+/// stored as `BTCUSDT` and told apart by their exchange, like the other venues
+fn inst_id(ticker: Ticker) -> String {
+    let (symbol, market_type) = ticker.to_full_symbol_and_type();
+    let quote = quote_currency(market_type);
+    let base = symbol.strip_suffix(quote).unwrap_or(&symbol);
+
+    match market_type {
+        MarketKind::Spot => format!("{base}-{quote}"),
+        MarketKind::LinearPerps | MarketKind::InversePerps => format!("{base}-{quote}-SWAP"),
+    }
+}
+
+/// The market an instrument id belongs to, if it's one of the supported quote currencies
+fn market_of_inst_id(inst_id: &str) -> Option<MarketKind> {
+    let parts = inst_id.split('-').collect::<Vec<_>>();
+
+    match parts.as_slice() {
+        [_, "USDT"] => Some(MarketKind::Spot),
+        [_, "USDT", "SWAP"] => Some(MarketKind::LinearPerps),
+        [_, "USD", "SWAP"] => Some(MarketKind::InversePerps),
+        _ => None,
+    }
+}
+
+fn symbol_from_inst_id(inst_id: &str) -> String {
+    inst_id.split('-').take(2).collect()
+}
+
+fn timeframe_to_bar(timeframe: Timeframe) -> Option<&'static str> {
+    // the plain 6H and above bars are aligned to Hong Kong time
+    match timeframe {
+        Timeframe::M1 => Some("1m"),
+        Timeframe::M3 => Some("3m"),
+        Timeframe::M5 => Some("5m"),
+        Timeframe::M15 => Some("15m"),
+        Timeframe::M30 => Some("30m"),
+        Timeframe::H1 => Some("1H"),
+        Timeframe::H2 => Some("2H"),
+        Timeframe::H4 => Some("4H"),
+        Timeframe::H6 => Some("6Hutc"),
+        Timeframe::H12 => Some("12Hutc"),
+        Timeframe::D1 => Some("1Dutc"),
+        Timeframe::D3 => Some("3Dutc"),
+        Timeframe::W1 => Some("1Wutc"),
+        _ => None,
+    }
+}
+
+fn bar_to_timeframe(bar: &str) -> Option<Timeframe> {
+    Timeframe::KLINE
+        .iter()
+        .find(|tf| timeframe_to_bar(**tf) == Some(bar))
+        .copied()
+}
+
+#[derive(Deserialize, Debug)]
+struct WsArg {
+    channel: String,
+    #[serde(rename = "instId")]
+    inst_id: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct SonicTrade {
+    #[serde(rename = "ts", deserialize_with = "de_string_to_u64")]
+    pub time: u64,
+    #[serde(rename = "px", deserialize_with = "de_string_to_f32")]
+    pub price: f32,
+    #[serde(rename = "sz", deserialize_with = "de_string_to_f32")]
+    pub qty: f32,
+    pub side: String,
+}
+
+/// Price levels come as `[price, size, deprecated, order count]`
+#[derive(Deserialize)]
+struct SonicDepth {
+    #[serde(rename = "ts", deserialize_with = "de_string_to_u64")]
+    pub time: u64,
+    #[serde(rename = "seqId", default)]
+    pub seq_id: i64,
+    /// `-1` on snapshots, the `seqId` of the previous message on updates
+    #[serde(rename = "prevSeqId", default)]
+    pub prev_seq_id: i64,
+    #[serde(default)]
+    pub checksum: Option<i64>,
+    pub bids: Vec<Vec<String>>,
+    pub asks: Vec<Vec<String>>,
+}
+
+/// Sizes come in contracts for swaps, `contract_size` turns them into what trades are sized in
+fn parse_levels(levels: &[Vec<String>], contract_size: Option<f32>) -> Vec<Order> {
+    levels
+        .iter()
+        .filter_map(|level| {
+            let qty: f32 = level.get(1)?.parse().ok()?;
+
+            Some(Order {
+                price: level.first()?.parse().ok()?,
+                qty: contract_size.map_or(qty, |size| qty * size),
+            })
+        })
+        .collect()
+}
+
+/// Levels as OKX sent them, its checksum is computed over the strings and not the parsed values
+#[derive(Default)]
+struct ChecksumBook {
+    bids: BTreeMap<OrderedFloat<f64>, (String, String)>,
+    asks: BTreeMap<OrderedFloat<f64>, (String, String)>,
+}
+
+impl ChecksumBook {
+    fn update(&mut self, depth: &SonicDepth, is_snapshot: bool) {
+        if is_snapshot {
+            self.bids.clear();
+            self.asks.clear();
+        }
+
+        for (side, levels) in [(&mut self.bids, &depth.bids), (&mut self.asks, &depth.asks)] {
+            for level in levels {
+                let (Some(price), Some(size)) = (level.first(), level.get(1)) else {
+                    continue;
+                };
+                let Ok(key) = price.parse::<f64>() else {
+                    continue;
+                };
+
+                if size.parse::<f64>().is_ok_and(|size| size == 0.0) {
+                    side.remove(&OrderedFloat(key));
+                } else {
+                    side.insert(OrderedFloat(key), (price.clone(), size.clone()));
+                }
+            }
+        }
+    }
+
+    /// CRC32 of the best levels, alternating bid and ask as `price:size`
+    fn checksum(&self) -> i32 {
+        let mut bids = self.bids.values().rev();
+        let mut asks = self.asks.values();
+
+        let mut fields = Vec::with_capacity(CHECKSUM_DEPTH * 4);
+        for _ in 0..CHECKSUM_DEPTH {
+            for (price, size) in [bids.next(), asks.next()].into_iter().flatten() {
+                fields.push(price.as_str());
+                fields.push(size.as_str());
+            }
+        }
+
+        crc32(fields.join(":").as_bytes()) as i32
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Candles come as `[ts, o, h, l, c, vol, volCcy, volCcyQuote, confirm]`, `vol` is in contracts
+/// for swaps and base currency for spot, `contract_size` sizes it like trades
+fn parse_candle(candle: &[String], contract_size: Option<f32>) -> Result<Kline, AdapterError> {
+    let field = |index: usize| -> Result<f32, AdapterError> {
+        candle
+            .get(index)
+            .and_then(|value| value.parse::<f32>().ok())
+            .ok_or_else(|| AdapterError::ParseError("Failed to parse kline".to_string()))
+    };
+
+    let time = candle
+        .first()
+        .and_then(|value| value.parse::<u64>().ok())
+        .ok_or_else(|| AdapterError::ParseError("Failed to parse kline time".to_string()))?;
+
+    Ok(Kline {
+        time,
+        open: field(1)?,
+        high: field(2)?,
+        low: field(3)?,
+        close: field(4)?,
+        volume: (
+            -1.0,
+            contract_size.map_or(field(5)?, |size| field(5)? * size),
+        ),
+    })
+}
+
+/// What a contract of the ticker is worth, `None` for spot which doesn't trade in contracts
+async fn contract_value(ticker: Ticker) -> Result<Option<f32>, AdapterError> {
+    let market_type = ticker.market_type();
+    if market_type == MarketKind::Spot {
+        return Ok(None);
+    }
+
+    if let Some(value) = CONTRACT_VALUES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&ticker)
+    {
+        return Ok(Some(*value));
+    }
+
+    let url = format!(
+        "{}/api/v5/public/instruments?instType={}&instId={}",
+        rest_domain(market_type),
+        inst_type(market_type),
+        inst_id(ticker)
+    );
+
+    let response_text = http_request_with_limiter(&url, &OKX_LIMITER, 1).await?;
+
+    let value = parse_response::<DeInstrument>(&response_text)?
+        .first()
+        .and_then(|instrument| instrument.contract_value.parse::<f32>().ok())
+        .ok_or_else(|| AdapterError::ParseError("Contract value not found".to_string()))?;
+
+    CONTRACT_VALUES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(ticker, value);
+
+    Ok(Some(value))
+}
+
+/// Contract values of the tickers, needed before connecting as the stream sizes everything in
+/// contracts. Waits out the backoff after a failure, `None` once the retries ran out
+async fn contract_values_or_wait(
+    exchange: Exchange,
+    tickers: &[Ticker],
+    stream_kinds: &[StreamKind],
+    backoff: &mut Backoff,
+    output: &mut mpsc::Sender<Event>,
+) -> Option<HashMap<Ticker, f32>> {
+    'retry: loop {
+        let mut values = HashMap::new();
+
+        for ticker in tickers {
+            match contract_value(*ticker).await {
+                Ok(Some(value)) => {
+                    values.insert(*ticker, value);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    let _ = output
+                        .send(Event::Disconnected(
+                            exchange,
+                            Box::from(stream_kinds),
+                            format!("Failed to fetch contract value: {err}"),
+                        ))
+                        .await;
+
+                    if wait_to_reconnect(backoff, stream_kinds, output).await {
+                        continue 'retry;
+                    }
+                    return None;
+                }
+            }
+        }
+
+        return Some(values);
+    }
+}
+
+/// Subscribes to the book again, OKX answers with a fresh snapshot
+async fn resubscribe_books(
+    websocket: &mut FragmentCollector<TokioIo<Upgraded>>,
+    inst_id: &str,
+) -> Result<(), fastwebsockets::WebSocketError> {
+    for op in ["unsubscribe", "subscribe"] {
+        let message = serde_json::json!({
+            "op": op,
+            "args": [{ "channel": "books", "instId": inst_id }]
+        });
+
+        websocket
+            .write_frame(Frame::text(fastwebsockets::Payload::Borrowed(
+                message.to_string().as_bytes(),
+            )))
+            .await?;
+    }
+
+    Ok(())
+}
+
+enum StreamData {
+    Trade(Vec<SonicTrade>),
+    Depth(Vec<SonicDepth>, String),
+    Kline(Ticker, Timeframe, Vec<Vec<String>>),
+}
+
+fn feed_de(slice: &[u8], market_type: MarketKind) -> Result<StreamData, AdapterError> {
+    let mut arg: Option<WsArg> = None;
+    let mut action = String::new();
+
+    let iter: sonic_rs::ObjectJsonIter = unsafe { to_object_iter_unchecked(slice) };
+
+    for elem in iter {
+        let (k, v) = elem.map_err(|e| AdapterError::ParseError(e.to_string()))?;
+
+        if k == "arg" {
+            arg = Some(
+                sonic_rs::from_str(&v.as_raw_faststr())
+                    .map_err(|e| AdapterError::ParseError(e.to_string()))?,
+            );
+        } else if k == "action" {
+            if let Some(val) = v.as_str() {
+                val.clone_into(&mut action);
+            }
+        } else if k == "data" {
+            let Some(arg) = &arg else {
+                return Err(AdapterError::ParseError(
+                    "Data arrived before its channel".to_string(),
+                ));
+            };
+
+            let raw = v.as_raw_faststr();
+
+            return match arg.channel.as_str() {
+                "trades" => sonic_rs::from_str(&raw)
+                    .map(StreamData::Trade)
+                    .map_err(|e| AdapterError::ParseError(e.to_string())),
+                "books" => sonic_rs::from_str(&raw)
+                    .map(|depth| StreamData::Depth(depth, action))
+                    .map_err(|e| AdapterError::ParseError(e.to_string())),
+                channel => {
+                    let timeframe = channel
+                        .strip_prefix("candle")
+                        .and_then(bar_to_timeframe)
+                        .ok_or_else(|| {
+                            AdapterError::ParseError(format!("Unknown channel: {channel}"))
+                        })?;
+
+                    let ticker = Ticker::new(
+                        &symbol_from_inst_id(&arg.inst_id),
+                        exchange_from_market_type(market_type),
+                    );
+
+                    sonic_rs::from_str(&raw)
+                        .map(|candles| StreamData::Kline(ticker, timeframe, candles))
+                        .map_err(|e| AdapterError::ParseError(e.to_string()))
+                }
+            };
+        }
+    }
+
+    Err(AdapterError::ParseError("Unknown data".to_string()))
+}
+
+async fn connect(
+    path: &str,
+    market_type: MarketKind,
+) -> Result<FragmentCollector<TokioIo<Upgraded>>, AdapterError> {
+    let domain = ws_domain(market_type);
+    let tcp_stream = setup_tcp_connection(&domain).await?;
+    let tls_stream = setup_tls_connection(&domain, tcp_stream).await?;
+    let url = format!("wss://{domain}/ws/v5/{path}");
+    setup_websocket_connection(&domain, tls_stream, &url).await
+}
+
+/// Connects and subscribes, waiting out the backoff after a failure. `None` once the
+/// connection ran out of retries
+async fn try_connect(
+    streams: &Value,
+    path: &str,
+    market_type: MarketKind,
+    stream_kinds: &[StreamKind],
+    backoff: &mut Backoff,
+    output: &mut mpsc::Sender<Event>,
+) -> Option<State> {
+    let exchange = exchange_from_market_type(market_type);
+
+    match connect(path, market_type).await {
+        Ok(mut websocket) => {
+            if let Err(e) = websocket
+                .write_frame(Frame::text(fastwebsockets::Payload::Borrowed(
+                    streams.to_string().as_bytes(),
+                )))
+                .await
+            {
+                let _ = output
+                    .send(Event::Disconnected(
+                        exchange,
+                        Box::from(stream_kinds),
+                        format!("Failed subscribing: {e}"),
+                    ))
+                    .await;
+
+                return wait_to_reconnect(backoff, stream_kinds, output)
+                    .await
+                    .then_some(State::Disconnected);
+            }
+
+            backoff.reset();
+            let _ = output
+                .send(Event::Connected(exchange, Box::from(stream_kinds)))
+                .await;
+            Some(State::Connected(websocket))
+        }
+        Err(err) => {
+            let _ = output
+                .send(Event::Disconnected(
+                    exchange,
+                    Box::from(stream_kinds),
+                    format!("Failed to connect: {err}"),
+                ))
+                .await;
+
+            wait_to_reconnect(backoff, stream_kinds, output)
+                .await
+                .then_some(State::Disconnected)
+        }
+    }
+}
+
+async fn send_ping(
+    websocket: &mut FragmentCollector<TokioIo<Upgraded>>,
+) -> Result<(), fastwebsockets::WebSocketError> {
+    websocket
+        .write_frame(Frame::text(fastwebsockets::Payload::Borrowed(b"ping")))
+        .await
+}
+
+pub fn connect_market_stream(ticker: Ticker) -> impl Stream<Item = Event> {
+    stream::channel(100, async move |mut output| {
+        let mut state: State = State::Disconnected;
+
+        let market_type = ticker.market_type();
+        let exchange = exchange_from_market_type(market_type);
+
+        let inst_id = inst_id(ticker);
+
+        let subscribe_message = serde_json::json!({
+            "op": "subscribe",
+            "args": [
+                { "channel": "trades", "instId": inst_id },
+                { "channel": "books", "instId": inst_id },
+            ]
+        });
+
+        let mut trades_buffer: Vec<Trade> = Vec::new();
+        let mut orderbook = LocalDepthCache::default();
+        let mut checksum_book = ChecksumBook::default();
+        // `seqId` of the last book message applied, `None` until a snapshot arrives
+        let mut last_seq_id: Option<i64> = None;
+        let stream_kinds = [StreamKind::DepthAndTrades { exchange, ticker }];
+        let mut backoff = Backoff::new();
+
+        let Some(contract_values) = contract_values_or_wait(
+            exchange,
+            &[ticker],
+            &stream_kinds,
+            &mut backoff,
+            &mut output,
+        )
+        .await
+        else {
+            return;
+        };
+        let contract_size = contract_values.get(&ticker).copied();
+
+        loop {
+            match &mut state {
+                State::Disconnected => {
+                    last_seq_id = None;
+
+                    match try_connect(
+                        &subscribe_message,
+                        "public",
+                        market_type,
+                        &stream_kinds,
+                        &mut backoff,
+                        &mut output,
+                    )
+                    .await
+                    {
+                        Some(next) => state = next,
+                        None => return,
+                    }
+                }
+                State::Connected(websocket) => {
+                    let Ok(frame) =
+                        tokio::time::timeout(PING_INTERVAL, websocket.read_frame()).await
+                    else {
+                        if let Err(e) = send_ping(websocket).await {
+                            state = State::Disconnected;
+                            let _ = output
+                                .send(Event::Disconnected(
+                                    exchange,
+                                    Box::from(stream_kinds.as_slice()),
+                                    format!("Failed to send ping: {e}"),
+                                ))
+                                .await;
+                        }
+                        continue;
+                    };
+
+                    let mut is_book_broken = false;
+
+                    match frame {
+                        Ok(msg) => match msg.opcode {
+                            OpCode::Text if &msg.payload[..] == b"pong" => {}
+                            OpCode::Text => {
+                                if let Ok(data) = feed_de(&msg.payload[..], market_type) {
+                                    match data {
+                                        StreamData::Trade(de_trade_vec) => {
+                                            for de_trade in &de_trade_vec {
+                                                trades_buffer.push(Trade {
+                                                    time: de_trade.time,
+                                                    is_sell: de_trade.side == "sell",
+                                                    price: de_trade.price,
+                                                    qty: contract_size
+                                                        .map_or(de_trade.qty, |size| {
+                                                            de_trade.qty * size
+                                                        }),
+                                                });
+                                            }
+                                        }
+                                        StreamData::Depth(de_depth_vec, action) => {
+                                            for de_depth in de_depth_vec {
+                                                let time = de_depth.time;
+                                                let is_snapshot = action == "snapshot";
+
+                                                // a missed update leaves the book wrong for
+                                                // good, it's resubscribed for a new snapshot
+                                                if !is_snapshot
+                                                    && last_seq_id != Some(de_depth.prev_seq_id)
+                                                {
+                                                    if last_seq_id.is_some() {
+                                                        log::warn!(
+                                                            "OKX book of {ticker} skipped an update, resyncing"
+                                                        );
+                                                        is_book_broken = true;
+                                                    }
+                                                    break;
+                                                }
+
+                                                checksum_book.update(&de_depth, is_snapshot);
+                                                if de_depth.checksum.is_some_and(|checksum| {
+                                                    checksum != i64::from(checksum_book.checksum())
+                                                }) {
+                                                    log::warn!(
+                                                        "OKX book of {ticker} failed its checksum, resyncing"
+                                                    );
+                                                    is_book_broken = true;
+                                                    break;
+                                                }
+                                                last_seq_id = Some(de_depth.seq_id);
+
+                                                let depth = DepthPayload {
+                                                    last_update_id: de_depth.seq_id as u64,
+                                                    time,
+                                                    bids: parse_levels(
+                                                        &de_depth.bids,
+                                                        contract_size,
+                                                    ),
+                                                    asks: parse_levels(
+                                                        &de_depth.asks,
+                                                        contract_size,
+                                                    ),
+                                                };
+
+                                                if is_snapshot {
+                                                    orderbook.update(DepthUpdate::Snapshot(depth));
+                                                } else {
+                                                    orderbook.update(DepthUpdate::Diff(depth));
+
+                                                    let _ = output
+                                                        .send(Event::DepthReceived(
+                                                            StreamKind::DepthAndTrades {
+                                                                exchange,
+                                                                ticker,
+                                                            },
+                                                            time,
+                                                            orderbook.depth.clone(),
+                                                            std::mem::take(&mut trades_buffer)
+                                                                .into_boxed_slice(),
+                                                        ))
+                                                        .await;
+                                                }
+                                            }
+                                        }
+                                        StreamData::Kline(..) => {
+                                            log::warn!("Unexpected kline data on a depth stream");
+                                        }
+                                    }
+                                }
+                            }
+                            OpCode::Close => {
+                                state = State::Disconnected;
+                                let _ = output
+                                    .send(Event::Disconnected(
+                                        exchange,
+                                        Box::from(stream_kinds.as_slice()),
+                                        "Connection closed".to_string(),
+                                    ))
+                                    .await;
+                            }
+                            _ => {}
+                        },
+                        Err(e) => {
+                            state = State::Disconnected;
+                            let _ = output
+                                .send(Event::Disconnected(
+                                    exchange,
+                                    Box::from(stream_kinds.as_slice()),
+                                    "Error reading frame: ".to_string() + &e.to_string(),
+                                ))
+                                .await;
+                        }
+                    }
+
+                    if is_book_broken {
+                        last_seq_id = None;
+
+                        if let State::Connected(websocket) = &mut state {
+                            if let Err(e) = resubscribe_books(websocket, &inst_id).await {
+                                state = State::Disconnected;
+                                let _ = output
+                                    .send(Event::Disconnected(
+                                        exchange,
+                                        Box::from(stream_kinds.as_slice()),
+                                        format!("Failed to resubscribe to the book: {e}"),
+                                    ))
+                                    .await;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+pub fn connect_kline_stream(
+    streams: Vec<(Ticker, Timeframe)>,
+    market_type: MarketKind,
+) -> impl Stream<Item = Event> {
+    stream::channel(100, async move |mut output| {
+        let mut state = State::Disconnected;
+
+        let exchange = exchange_from_market_type(market_type);
+
+        let args = streams
+            .iter()
+            .filter_map(|(ticker, timeframe)| {
+                let bar = timeframe_to_bar(*timeframe)?;
+
+                Some(serde_json::json!({
+                    "channel": format!("candle{bar}"),
+                    "instId": inst_id(*ticker),
+                }))
+            })
+            .collect::<Vec<Value>>();
+
+        let subscribe_message = serde_json::json!({
+            "op": "subscribe",
+            "args": args
+        });
+        let stream_kinds = streams
+            .iter()
+            .map(|(ticker, timeframe)| StreamKind::Kline {
+                exchange,
+                ticker: *ticker,
+                timeframe: *timeframe,
+                source: KlineSource::Last,
+            })
+            .collect::<Vec<_>>();
+        let mut backoff = Backoff::new();
+
+        let tickers = streams
+            .iter()
+            .map(|(ticker, _)| *ticker)
+            .collect::<Vec<_>>();
+        let Some(contract_values) =
+            contract_values_or_wait(exchange, &tickers, &stream_kinds, &mut backoff, &mut output)
+                .await
+        else {
+            return;
+        };
+
+        loop {
+            match &mut state {
+                State::Disconnected => {
+                    match try_connect(
+                        &subscribe_message,
+                        "business",
+                        market_type,
+                        &stream_kinds,
+                        &mut backoff,
+                        &mut output,
+                    )
+                    .await
+                    {
+                        Some(next) => state = next,
+                        None => return,
+                    }
+                }
+                State::Connected(websocket) => {
+                    let Ok(frame) =
+                        tokio::time::timeout(PING_INTERVAL, websocket.read_frame()).await
+                    else {
+                        if let Err(e) = send_ping(websocket).await {
+                            state = State::Disconnected;
+                            let _ = output
+                                .send(Event::Disconnected(
+                                    exchange,
+                                    Box::from(stream_kinds.as_slice()),
+                                    format!("Failed to send ping: {e}"),
+                                ))
+                                .await;
+                        }
+                        continue;
+                    };
+
+                    match frame {
+                        Ok(msg) => match msg.opcode {
+                            OpCode::Text if &msg.payload[..] == b"pong" => {}
+                            OpCode::Text => {
+                                if let Ok(StreamData::Kline(ticker, timeframe, candles)) =
+                                    feed_de(&msg.payload[..], market_type)
+                                {
+                                    for candle in &candles {
+                                        match parse_candle(
+                                            candle,
+                                            contract_values.get(&ticker).copied(),
+                                        ) {
+                                            Ok(kline) => {
+                                                let _ = output
+                                                    .send(Event::KlineReceived(
+                                                        StreamKind::Kline {
+                                                            exchange,
+                                                            ticker,
+                                                            timeframe,
+                                                            source: KlineSource::Last,
+                                                        },
+                                                        kline,
+                                                    ))
+                                                    .await;
+                                            }
+                                            Err(e) => {
+                                                log::error!("Failed to parse OKX candle: {e}");
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            OpCode::Close => {
+                                state = State::Disconnected;
+                                let _ = output
+                                    .send(Event::Disconnected(
+                                        exchange,
+                                        Box::from(stream_kinds.as_slice()),
+                                        "Connection closed".to_string(),
+                                    ))
+                                    .await;
+                            }
+                            _ => {}
+                        },
+                        Err(e) => {
+                            state = State::Disconnected;
+                            let _ = output
+                                .send(Event::Disconnected(
+                                    exchange,
+                                    Box::from(stream_kinds.as_slice()),
+                                    "Error reading frame: ".to_string() + &e.to_string(),
+                                ))
+                                .await;
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[derive(Deserialize, Debug)]
+struct ApiResponse<T> {
+    code: String,
+    msg: String,
+    data: Vec<T>,
+}
+
+impl<T> ApiResponse<T> {
+    fn into_data(self) -> Result<Vec<T>, AdapterError> {
+        if self.code == "0" {
+            Ok(self.data)
+        } else {
+            Err(AdapterError::InvalidRequest(format!(
+                "OKX error {}: {}",
+                self.code, self.msg
+            )))
+        }
+    }
+}
+
+fn parse_response<T: for<'de> Deserialize<'de>>(text: &str) -> Result<Vec<T>, AdapterError> {
+    sonic_rs::from_str::<ApiResponse<T>>(text)
+        .map_err(|e| AdapterError::ParseError(e.to_string()))?
+        .into_data()
+}
+
+pub async fn fetch_klines(
+    ticker: Ticker,
+    timeframe: Timeframe,
+    range: Option<(u64, u64)>,
+) -> Result<Vec<Kline>, AdapterError> {
+    let inst_id = inst_id(ticker);
+    let domain = rest_domain(ticker.market_type());
+    let bar = timeframe_to_bar(timeframe).ok_or_else(|| {
+        AdapterError::InvalidRequest(format!("Unsupported timeframe for OKX: {timeframe}"))
+    })?;
+
+    // `after` and `before` are exclusive bounds, the history endpoint pages back up to 100 bars
+    let url = if let Some((start, end)) = range {
+        format!(
+            "{domain}/api/v5/market/history-candles?instId={inst_id}&bar={bar}&after={}&before={}&limit=100",
+            end + 1,
+            start.saturating_sub(1),
+        )
+    } else {
+        format!("{domain}/api/v5/market/candles?instId={inst_id}&bar={bar}&limit=300")
+    };
+
+    let contract_size = contract_value(ticker).await?;
+    let response_text = http_request_with_limiter(&url, &OKX_LIMITER, 1).await?;
+
+    parse_response::<Vec<String>>(&response_text)?
+        .iter()
+        .map(|candle| parse_candle(candle, contract_size))
+        .collect()
+}
+
+#[derive(Deserialize, Debug)]
+struct DeInstrument {
+    #[serde(rename = "instId")]
+    inst_id: String,
+    #[serde(rename = "tickSz", deserialize_with = "de_string_to_f32")]
+    tick_size: f32,
+    #[serde(rename = "minSz", deserialize_with = "de_string_to_f32")]
+    min_qty: f32,
+    state: String,
+    /// Empty for spot, which doesn't trade in contracts
+    #[serde(rename = "ctVal", default)]
+    contract_value: String,
+    #[serde(rename = "listTime", default)]
+    list_time: String,
+}
+
+pub async fn fetch_ticksize(market_type: MarketKind) -> Result<MarketInfo, AdapterError> {
+    let exchange = exchange_from_market_type(market_type);
+
+    let url = format!(
+        "{}/api/v5/public/instruments?instType={}",
+        rest_domain(market_type),
+        inst_type(market_type)
+    );
+
+    let response_text = http_request_with_limiter(&url, &OKX_LIMITER, 1).await?;
+
+    let mut ticker_info_map = HashMap::new();
+    let mut specs_map = HashMap::new();
+
+    for instrument in parse_response::<DeInstrument>(&response_text)? {
+        if instrument.state != "live" || market_of_inst_id(&instrument.inst_id) != Some(market_type)
+        {
+            continue;
+        }
+
+        let symbol = symbol_from_inst_id(&instrument.inst_id);
+
+        if !is_symbol_supported(&symbol, exchange, true) {
+            continue;
+        }
+
+        let ticker = Ticker::new(&symbol, exchange);
+        let contract_size = instrument.contract_value.parse::<f32>().ok();
+
+        if let Some(value) = contract_size {
+            CONTRACT_VALUES
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(ticker, value);
+        }
+
+        // funding intervals aren't part of the instruments, only of the funding rate endpoint
+        specs_map.insert(
+            ticker,
+            TickerSpecs {
+                contract_size,
+                funding_interval_hours: None,
+                listed_at: instrument.list_time.parse::<u64>().ok(),
+            },
+        );
+
+        ticker_info_map.insert(
+            ticker,
+            Some(TickerInfo {
+                ticker,
+                min_ticksize: instrument.tick_size,
+                // sized in contracts for swaps, like their trades
+                min_qty: contract_size.map_or(instrument.min_qty, |size| instrument.min_qty * size),
+            }),
+        );
+    }
+
+    Ok((ticker_info_map, specs_map))
+}
+
+#[derive(Deserialize, Debug)]
+struct DeTicker {
+    #[serde(rename = "instId")]
+    inst_id: String,
+    last: String,
+    #[serde(rename = "open24h")]
+    open_24h: String,
+    #[serde(rename = "volCcy24h")]
+    vol_ccy_24h: String,
+}
+
+pub async fn fetch_ticker_prices(
+    market_type: MarketKind,
+) -> Result<HashMap<Ticker, TickerStats>, AdapterError> {
+    let exchange = exchange_from_market_type(market_type);
+
+    let url = format!(
+        "{}/api/v5/market/tickers?instType={}",
+        rest_domain(market_type),
+        inst_type(market_type)
+    );
+
+    let response_text = http_request_with_limiter(&url, &OKX_LIMITER, 1).await?;
+
+    let mut ticker_prices_map = HashMap::new();
+
+    for item in parse_response::<DeTicker>(&response_text)? {
+        if market_of_inst_id(&item.inst_id) != Some(market_type) {
+            continue;
+        }
+
+        let symbol = symbol_from_inst_id(&item.inst_id);
+
+        if !is_symbol_supported(&symbol, exchange, false) {
+            continue;
+        }
+
+        // freshly listed instruments may not have traded yet and report empty strings
+        let (Ok(mark_price), Ok(open_price), Ok(daily_volume)) = (
+            item.last.parse::<f32>(),
+            item.open_24h.parse::<f32>(),
+            item.vol_ccy_24h.parse::<f32>(),
+        ) else {
+            continue;
+        };
+
+        let daily_price_chg = if open_price > 0.0 {
+            (mark_price - open_price) / open_price * 100.0
+        } else {
+            0.0
+        };
+
+        // spot reports the 24h volume in quote currency, swaps in base currency
+        let volume_in_usd = match market_type {
+            MarketKind::Spot => daily_volume,
+            MarketKind::LinearPerps | MarketKind::InversePerps => daily_volume * mark_price,
+        };
+
+        ticker_prices_map.insert(
+            Ticker::new(&symbol, exchange),
+            TickerStats {
+                mark_price,
+                daily_price_chg,
+                daily_volume: volume_in_usd,
+            },
+        );
+    }
+
+    Ok(ticker_prices_map)
+}
+
+#[derive(Deserialize, Debug)]
+struct DeFundingRate {
+    #[serde(rename = "fundingRate", deserialize_with = "de_string_to_f32")]
+    funding_rate: f32,
+    /// Settlement of the interval in progress, `nextFundingTime` is the one after it
+    #[serde(rename = "fundingTime", deserialize_with = "de_string_to_u64")]
+    funding_time: u64,
+}
+
+pub async fn fetch_funding_rate(ticker: Ticker) -> Result<FundingRate, AdapterError> {
+    let url = format!(
+        "{}/api/v5/public/funding-rate?instId={}",
+        rest_domain(ticker.market_type()),
+        inst_id(ticker)
+    );
+
+    let response_text = http_request_with_limiter(&url, &OKX_LIMITER, 1).await?;
+
+    parse_response::<DeFundingRate>(&response_text)?
+        .first()
+        .map(|item| FundingRate {
+            rate: item.funding_rate,
+            next_funding_time: item.funding_time,
+        })
+        .ok_or_else(|| AdapterError::ParseError("Funding rate not found".to_string()))
+}
+
+#[derive(Deserialize, Debug)]
+struct DeServerTime {
+    #[serde(deserialize_with = "de_string_to_u64")]
+    ts: u64,
+}
+
+pub async fn fetch_server_time(market_type: MarketKind) -> Result<u64, AdapterError> {
+    let url = format!("{}/api/v5/public/time", rest_domain(market_type));
+
+    let response = limiter::http_client()?
+        .get(&url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(AdapterError::FetchError)?;
+
+    if !response.status().is_success() {
+        return Err(AdapterError::InvalidRequest(format!(
+            "HTTP {} from {url}",
+            response.status()
+        )));
+    }
+
+    let text = response.text().await.map_err(AdapterError::FetchError)?;
+
+    parse_response::<DeServerTime>(&text)?
+        .first()
+        .map(|time| time.ts)
+        .ok_or_else(|| AdapterError::ParseError("Server time not found".to_string()))
+}
@@ -1,4 +1,4 @@
-use crate::adapter::AdapterError;
+use crate::adapter::{AdapterError, Event, StreamKind};
 use bytes::Bytes;
 use fastwebsockets::FragmentCollector;
 use http_body_util::Empty;
@@ -8,6 +8,13 @@ use hyper::{
     upgrade::Upgraded,
 };
 use hyper_util::rt::TokioIo;
+use iced_futures::futures::{SinkExt, channel::mpsc};
+use serde::{Deserialize, Serialize};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 use tokio_rustls::{
     TlsConnector,
@@ -20,6 +27,213 @@ pub enum State {
     Connected(FragmentCollector<TokioIo<Upgraded>>),
 }
 
+/// How long adapters wait between attempts at bringing a dropped websocket back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReconnectPolicy {
+    /// Wait after the first failed attempt, doubled by every further one
+    pub base_secs: u16,
+    pub max_secs: u16,
+    /// Failed attempts in a row a connection gives up after, `0` keeps it retrying forever
+    pub max_retries: u16,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            base_secs: 1,
+            max_secs: 60,
+            max_retries: 0,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    pub const MAX_WAIT_SECS: u16 = 300;
+
+    fn pack(self) -> u64 {
+        u64::from(self.base_secs) << 32
+            | u64::from(self.max_secs) << 16
+            | u64::from(self.max_retries)
+    }
+
+    fn unpack(packed: u64) -> Self {
+        Self {
+            base_secs: (packed >> 32) as u16,
+            max_secs: (packed >> 16) as u16,
+            max_retries: packed as u16,
+        }
+    }
+}
+
+static RECONNECT_POLICY: AtomicU64 = AtomicU64::new(u64::MAX);
+
+pub fn set_reconnect_policy(policy: ReconnectPolicy) {
+    RECONNECT_POLICY.store(policy.pack(), Ordering::Relaxed);
+}
+
+pub fn reconnect_policy() -> ReconnectPolicy {
+    match RECONNECT_POLICY.load(Ordering::Relaxed) {
+        u64::MAX => ReconnectPolicy::default(),
+        packed => ReconnectPolicy::unpack(packed),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ProxyKind {
+    Http,
+    Socks5,
+}
+
+/// Proxy every REST request and websocket connection goes through
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Proxy {
+    pub kind: ProxyKind,
+    pub host: String,
+    pub port: u16,
+}
+
+impl Proxy {
+    /// From `http://host:port` or `socks5://host:port`
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+
+        let (kind, address) = if let Some(address) = input.strip_prefix("http://") {
+            (ProxyKind::Http, address)
+        } else if let Some(address) = input.strip_prefix("socks5://") {
+            (ProxyKind::Socks5, address)
+        } else {
+            return Err("Proxy has to start with http:// or socks5://".to_string());
+        };
+
+        let (host, port) = address
+            .trim_end_matches('/')
+            .rsplit_once(':')
+            .ok_or_else(|| "Proxy is missing its port".to_string())?;
+
+        if host.is_empty() {
+            return Err("Proxy is missing its host".to_string());
+        }
+
+        let port = port
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid proxy port: {port}"))?;
+
+        Ok(Proxy {
+            kind,
+            host: host.to_string(),
+            port,
+        })
+    }
+
+    /// Url of the proxy for the HTTP client, SOCKS proxies resolve the exchange hosts themselves
+    pub(crate) fn client_url(&self) -> String {
+        match self.kind {
+            ProxyKind::Http => format!("http://{}:{}", self.host, self.port),
+            ProxyKind::Socks5 => format!("socks5h://{}:{}", self.host, self.port),
+        }
+    }
+}
+
+impl std::fmt::Display for Proxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scheme = match self.kind {
+            ProxyKind::Http => "http",
+            ProxyKind::Socks5 => "socks5",
+        };
+        write!(f, "{scheme}://{}:{}", self.host, self.port)
+    }
+}
+
+static PROXY: RwLock<Option<Proxy>> = RwLock::new(None);
+
+/// Requests already sent and connections already open keep going around it or through the
+/// previous one, new ones pick it up. A proxy the client can't be built for is kept anyway, so
+/// nothing goes out directly until it's replaced
+pub fn set_proxy(proxy: Option<Proxy>) -> Result<(), String> {
+    let result = crate::limiter::rebuild_http_client(proxy.as_ref());
+    *PROXY
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = proxy;
+    result
+}
+
+/// Why the current proxy is unusable, REST requests fail with it until another one is set
+pub fn proxy_error() -> Option<String> {
+    crate::limiter::http_client_error()
+}
+
+pub fn proxy() -> Option<Proxy> {
+    PROXY
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone()
+}
+
+/// Failed attempts in a row of one connection, handing out the waits before its next ones
+#[derive(Default)]
+pub struct Backoff {
+    attempts: u32,
+}
+
+impl Backoff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait before the next attempt, `None` once the policy's retries ran out. It's drawn
+    /// between half and all of the exponential step, so connections that dropped together
+    /// don't all come back at the same instant.
+    pub fn next_delay(&mut self) -> Option<Duration> {
+        let policy = reconnect_policy();
+
+        if policy.max_retries > 0 && self.attempts >= u32::from(policy.max_retries) {
+            return None;
+        }
+
+        let base = f64::from(policy.base_secs.max(1));
+        let step = (base * 2f64.powi(self.attempts.min(16) as i32))
+            .min(f64::from(policy.max_secs.max(policy.base_secs)));
+        self.attempts += 1;
+
+        Some(Duration::from_secs_f64(step * (0.5 + 0.5 * jitter())))
+    }
+
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+    }
+}
+
+/// Uniform in `0.0..1.0`, every `RandomState` is seeded anew
+fn jitter() -> f64 {
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Waits out the next backoff delay of a failed connection, announcing it on each of the
+/// streams it carries first. `false` once the retries ran out and the connection should stop.
+pub async fn wait_to_reconnect(
+    backoff: &mut Backoff,
+    streams: &[StreamKind],
+    output: &mut mpsc::Sender<Event>,
+) -> bool {
+    let delay = backoff.next_delay();
+
+    for stream in streams {
+        let _ = output.send(Event::Reconnecting(*stream, delay)).await;
+    }
+
+    match delay {
+        Some(delay) => {
+            tokio::time::sleep(delay).await;
+            true
+        }
+        None => false,
+    }
+}
+
 struct SpawnExecutor;
 
 impl<Fut> hyper::rt::Executor<Fut> for SpawnExecutor
@@ -51,11 +265,133 @@ pub fn tls_connector() -> Result<TlsConnector, AdapterError> {
     Ok(TlsConnector::from(std::sync::Arc::new(config)))
 }
 
+/// Host and port of a `host[:port]` domain, 443 if it doesn't carry one
+fn split_port(domain: &str) -> (&str, u16) {
+    match domain.rsplit_once(':') {
+        Some((host, port)) => match port.parse() {
+            Ok(port) => (host, port),
+            Err(_) => (domain, 443),
+        },
+        None => (domain, 443),
+    }
+}
+
+fn websocket_error(err: impl std::fmt::Display) -> AdapterError {
+    AdapterError::WebsocketError(err.to_string())
+}
+
+/// Connects to a `host[:port]` domain, through the proxy if one is set
 pub async fn setup_tcp_connection(domain: &str) -> Result<TcpStream, AdapterError> {
-    let addr = format!("{domain}:443");
-    TcpStream::connect(&addr)
+    let (host, port) = split_port(domain);
+    setup_tcp_connection_on_port(host, port).await
+}
+
+pub async fn setup_tcp_connection_on_port(
+    domain: &str,
+    port: u16,
+) -> Result<TcpStream, AdapterError> {
+    let Some(proxy) = proxy() else {
+        return TcpStream::connect((domain, port))
+            .await
+            .map_err(websocket_error);
+    };
+
+    let mut stream = TcpStream::connect((proxy.host.as_str(), proxy.port))
         .await
-        .map_err(|e| AdapterError::WebsocketError(e.to_string()))
+        .map_err(|e| AdapterError::WebsocketError(format!("Proxy unreachable: {e}")))?;
+
+    match proxy.kind {
+        ProxyKind::Http => http_tunnel(&mut stream, domain, port).await?,
+        ProxyKind::Socks5 => socks5_tunnel(&mut stream, domain, port).await?,
+    }
+
+    Ok(stream)
+}
+
+/// Asks an HTTP proxy to open a tunnel with `CONNECT`, the TLS handshake then runs through it
+async fn http_tunnel(stream: &mut TcpStream, host: &str, port: u16) -> Result<(), AdapterError> {
+    let request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(websocket_error)?;
+
+    // read byte by byte so nothing past the headers is taken from the tunnel
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() > 8192 {
+            return Err(websocket_error("Proxy response too large"));
+        }
+        response.push(stream.read_u8().await.map_err(websocket_error)?);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status = response.lines().next().unwrap_or_default();
+
+    if status.split_whitespace().nth(1) == Some("200") {
+        Ok(())
+    } else {
+        Err(AdapterError::WebsocketError(format!(
+            "Proxy refused tunnel: {status}"
+        )))
+    }
+}
+
+/// SOCKS5 handshake without authentication, the proxy resolves the host
+async fn socks5_tunnel(stream: &mut TcpStream, host: &str, port: u16) -> Result<(), AdapterError> {
+    let host_len = u8::try_from(host.len()).map_err(|_| websocket_error("Host name too long"))?;
+
+    stream
+        .write_all(&[0x05, 0x01, 0x00])
+        .await
+        .map_err(websocket_error)?;
+
+    let mut greeting = [0u8; 2];
+    stream
+        .read_exact(&mut greeting)
+        .await
+        .map_err(websocket_error)?;
+    if greeting != [0x05, 0x00] {
+        return Err(websocket_error(
+            "SOCKS proxy requires an unsupported authentication",
+        ));
+    }
+
+    let mut request = vec![0x05, 0x01, 0x00, 0x03, host_len];
+    request.extend_from_slice(host.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await.map_err(websocket_error)?;
+
+    let mut reply = [0u8; 4];
+    stream
+        .read_exact(&mut reply)
+        .await
+        .map_err(websocket_error)?;
+    if reply[1] != 0x00 {
+        return Err(AdapterError::WebsocketError(format!(
+            "SOCKS proxy refused connection, code {}",
+            reply[1]
+        )));
+    }
+
+    // the bound address that follows isn't needed, only skipped
+    let address_len = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => usize::from(stream.read_u8().await.map_err(websocket_error)?),
+        other => {
+            return Err(AdapterError::WebsocketError(format!(
+                "SOCKS proxy sent unknown address type {other}"
+            )));
+        }
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream
+        .read_exact(&mut bound)
+        .await
+        .map_err(websocket_error)?;
+
+    Ok(())
 }
 
 pub async fn setup_tls_connection(
@@ -63,9 +399,9 @@ pub async fn setup_tls_connection(
     tcp_stream: TcpStream,
 ) -> Result<tokio_rustls::client::TlsStream<TcpStream>, AdapterError> {
     let tls_connector: TlsConnector = tls_connector()?;
-    let domain: tokio_rustls::rustls::ServerName =
-        tokio_rustls::rustls::ServerName::try_from(domain)
-            .map_err(|_| AdapterError::ParseError("invalid dnsname".to_string()))?;
+    let (host, _) = split_port(domain);
+    let domain: tokio_rustls::rustls::ServerName = tokio_rustls::rustls::ServerName::try_from(host)
+        .map_err(|_| AdapterError::ParseError("invalid dnsname".to_string()))?;
     tls_connector
         .connect(domain, tcp_stream)
         .await
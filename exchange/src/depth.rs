@@ -1,5 +1,8 @@
 use ordered_float::OrderedFloat;
-use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
 
 use super::de_string_to_f32;
 
@@ -75,6 +78,40 @@ impl Depth {
             _ => None,
         }
     }
+
+    /// Mid price weighted by the top of book sizes, leaning towards the side with less resting
+    pub fn micro_price(&self) -> Option<f32> {
+        let ((ask_price, ask_qty), (bid_price, bid_qty)) =
+            (self.asks.first_key_value()?, self.bids.last_key_value()?);
+
+        let total_qty = ask_qty + bid_qty;
+        if total_qty <= 0.0 {
+            return self.mid_price();
+        }
+
+        Some((bid_price.into_inner() * ask_qty + ask_price.into_inner() * bid_qty) / total_qty)
+    }
+
+    /// Resting bids minus asks over their sum, within `range_pct` percent of the mid price,
+    /// from -1 all asks to 1 all bids
+    pub fn imbalance(&self, range_pct: f32) -> Option<f32> {
+        let mid_price = self.mid_price()?;
+        let offset = mid_price * range_pct / 100.0;
+
+        let bid_qty: f32 = self
+            .bids
+            .range(OrderedFloat(mid_price - offset)..)
+            .map(|(_, qty)| qty)
+            .sum();
+        let ask_qty: f32 = self
+            .asks
+            .range(..=OrderedFloat(mid_price + offset))
+            .map(|(_, qty)| qty)
+            .sum();
+
+        let total_qty = bid_qty + ask_qty;
+        (total_qty > 0.0).then(|| (bid_qty - ask_qty) / total_qty)
+    }
 }
 
 #[derive(Default)]
@@ -100,3 +137,138 @@ impl LocalDepthCache {
         }
     }
 }
+
+/// Desyncs are only counted towards the fallback within this window
+const DESYNC_WINDOW: Duration = Duration::from_secs(120);
+/// How long a degraded stream stays on snapshots before trying the diff stream again
+const DEGRADED_SPAN: Duration = Duration::from_secs(300);
+
+/// Low rate REST snapshots to fall back on when a depth stream keeps going out of sync,
+/// instead of reconnecting over and over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DepthFallback {
+    pub enabled: bool,
+    /// Desyncs within two minutes that switch the stream over to snapshots
+    pub desyncs: u8,
+    pub refresh_secs: u16,
+}
+
+impl Default for DepthFallback {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            desyncs: 3,
+            refresh_secs: 10,
+        }
+    }
+}
+
+impl DepthFallback {
+    pub const MIN_REFRESH_SECS: u16 = 5;
+    pub const MAX_REFRESH_SECS: u16 = 60;
+
+    fn pack(self) -> u32 {
+        u32::from(self.enabled) << 24 | u32::from(self.desyncs) << 16 | u32::from(self.refresh_secs)
+    }
+
+    fn unpack(packed: u32) -> Self {
+        Self {
+            enabled: packed >> 24 != 0,
+            desyncs: (packed >> 16) as u8,
+            refresh_secs: packed as u16,
+        }
+    }
+}
+
+static DEPTH_FALLBACK: AtomicU32 = AtomicU32::new(u32::MAX);
+
+pub fn set_depth_fallback(fallback: DepthFallback) {
+    DEPTH_FALLBACK.store(fallback.pack(), Ordering::Relaxed);
+}
+
+pub fn depth_fallback() -> DepthFallback {
+    match DEPTH_FALLBACK.load(Ordering::Relaxed) {
+        u32::MAX => DepthFallback::default(),
+        packed => DepthFallback::unpack(packed),
+    }
+}
+
+/// Counts the desyncs of one depth stream and decides when it runs on snapshots
+pub struct DesyncGuard {
+    desyncs: VecDeque<Instant>,
+    degraded_until: Option<Instant>,
+    next_snapshot: Instant,
+}
+
+impl DesyncGuard {
+    pub fn new() -> Self {
+        Self {
+            desyncs: VecDeque::new(),
+            degraded_until: None,
+            next_snapshot: Instant::now(),
+        }
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.degraded_until.is_some()
+    }
+
+    /// Records a desync, true when it tipped the stream over to the snapshot fallback
+    pub fn record_desync(&mut self) -> bool {
+        let fallback = depth_fallback();
+        let now = Instant::now();
+
+        self.desyncs.push_back(now);
+        while self
+            .desyncs
+            .front()
+            .is_some_and(|at| now.duration_since(*at) > DESYNC_WINDOW)
+        {
+            self.desyncs.pop_front();
+        }
+
+        if !fallback.enabled || self.is_degraded() || self.desyncs.len() < fallback.desyncs as usize
+        {
+            return false;
+        }
+
+        self.desyncs.clear();
+        self.degraded_until = Some(now + DEGRADED_SPAN);
+        self.next_snapshot = now;
+        true
+    }
+
+    /// Whether a degraded stream should fetch its next snapshot now
+    pub fn snapshot_due(&mut self) -> bool {
+        let now = Instant::now();
+        if !self.is_degraded() || now < self.next_snapshot {
+            return false;
+        }
+
+        let refresh_secs = depth_fallback().refresh_secs.clamp(
+            DepthFallback::MIN_REFRESH_SECS,
+            DepthFallback::MAX_REFRESH_SECS,
+        );
+        self.next_snapshot = now + Duration::from_secs(u64::from(refresh_secs));
+        true
+    }
+
+    /// True once a degraded stream has waited long enough to try the diff stream again, or the
+    /// fallback got turned off meanwhile
+    pub fn try_recover(&mut self) -> bool {
+        let is_over = |until: Instant| !depth_fallback().enabled || Instant::now() >= until;
+
+        if self.degraded_until.is_some_and(is_over) {
+            self.degraded_until = None;
+            return true;
+        }
+        false
+    }
+}
+
+impl Default for DesyncGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,16 +1,75 @@
+use crate::adapter::{Exchange, StreamKind};
 use crate::{Kline, OpenInterest, Trade};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU8, AtomicU16, Ordering};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-static TRADE_FETCH_ENABLED: AtomicBool = AtomicBool::new(false);
+/// One bit per exchange market the user agreed to fetch historical trades from
+static TRADE_FETCH_CONSENT: AtomicU16 = AtomicU16::new(0);
 
-pub fn toggle_trade_fetch(value: bool) {
-    TRADE_FETCH_ENABLED.store(value, Ordering::Relaxed);
+fn consent_bit(exchange: Exchange) -> u16 {
+    1 << (exchange as u16)
 }
 
-pub fn is_trade_fetch_enabled() -> bool {
-    TRADE_FETCH_ENABLED.load(Ordering::Relaxed)
+pub fn toggle_trade_fetch(exchange: Exchange, value: bool) {
+    if value {
+        TRADE_FETCH_CONSENT.fetch_or(consent_bit(exchange), Ordering::Relaxed);
+    } else {
+        TRADE_FETCH_CONSENT.fetch_and(!consent_bit(exchange), Ordering::Relaxed);
+    }
+}
+
+pub fn is_trade_fetch_enabled(exchange: Exchange) -> bool {
+    exchange.supports_trade_fetch()
+        && TRADE_FETCH_CONSENT.load(Ordering::Relaxed) & consent_bit(exchange) != 0
+}
+
+/// Markets trades are currently fetched for, in the order of `Exchange::ALL`
+pub fn trade_fetch_consents() -> Vec<Exchange> {
+    Exchange::ALL
+        .into_iter()
+        .filter(|exchange| is_trade_fetch_enabled(*exchange))
+        .collect()
+}
+
+/// Share of each venue's REST rate limit the requests may use up, the rest is left to other
+/// apps calling from the same IP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitShare {
+    pub percent: u8,
+}
+
+impl RateLimitShare {
+    pub const MIN_PERCENT: u8 = 10;
+
+    /// Portion of a venue's limit, never less than a single unit of it
+    pub fn of(self, limit: usize) -> usize {
+        (limit * usize::from(self.percent) / 100).max(1)
+    }
+}
+
+impl Default for RateLimitShare {
+    fn default() -> Self {
+        Self { percent: 100 }
+    }
+}
+
+static RATE_LIMIT_SHARE: AtomicU8 = AtomicU8::new(100);
+
+pub fn set_rate_limit_share(share: RateLimitShare) {
+    RATE_LIMIT_SHARE.store(
+        share.percent.clamp(RateLimitShare::MIN_PERCENT, 100),
+        Ordering::Relaxed,
+    );
+}
+
+pub fn rate_limit_share() -> RateLimitShare {
+    RateLimitShare {
+        percent: RATE_LIMIT_SHARE.load(Ordering::Relaxed),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -142,3 +201,79 @@ impl FetchRequest {
         }
     }
 }
+
+/// A kline stream and the range fetched of it, `None` for its latest klines
+pub type KlineFetch = (StreamKind, Option<(u64, u64)>);
+
+/// A fetch in flight for longer than this is taken for dead, the next one asking for the same
+/// data makes the request again rather than waiting on it
+const IN_FLIGHT_DEADLINE: Duration = Duration::from_secs(30);
+
+struct InFlight<W> {
+    leader: W,
+    followers: Vec<W>,
+    since: Instant,
+}
+
+/// Identical fetches asked for while one of them is still in flight. Only the first one goes
+/// out, whoever asks after it waits for its result instead of making the same request again.
+pub struct FetchCoalescer<K, W> {
+    in_flight: HashMap<K, InFlight<W>>,
+}
+
+impl<K: Eq + Hash, W: PartialEq> FetchCoalescer<K, W> {
+    /// `true` if the fetch has to be made, with `waiter` leading it, otherwise it was added to
+    /// the ones waiting on the fetch already in flight
+    pub fn join(&mut self, key: K, waiter: W) -> bool {
+        let now = Instant::now();
+
+        match self.in_flight.get_mut(&key) {
+            Some(fetch) if now.duration_since(fetch.since) < IN_FLIGHT_DEADLINE => {
+                fetch.followers.push(waiter);
+                false
+            }
+            // whoever waited on the dead fetch gets the result of the new one
+            Some(fetch) => {
+                fetch.leader = waiter;
+                fetch.since = now;
+                true
+            }
+            None => {
+                self.in_flight.insert(
+                    key,
+                    InFlight {
+                        leader: waiter,
+                        followers: vec![],
+                        since: now,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Ends the fetch `leader` made, handing back who waited on it to fan the result out to.
+    /// Empty for a fetch that didn't go through the coalescer.
+    pub fn finish(&mut self, leader: &W) -> Vec<W> {
+        let mut followers = vec![];
+
+        self.in_flight.retain(|_, fetch| {
+            if fetch.leader == *leader {
+                followers.append(&mut fetch.followers);
+                false
+            } else {
+                true
+            }
+        });
+
+        followers
+    }
+}
+
+impl<K, W> Default for FetchCoalescer<K, W> {
+    fn default() -> Self {
+        FetchCoalescer {
+            in_flight: HashMap::new(),
+        }
+    }
+}
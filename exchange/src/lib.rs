@@ -3,6 +3,7 @@ pub mod connect;
 pub mod depth;
 pub mod fetcher;
 mod limiter;
+mod trade_store;
 
 pub use adapter::Event;
 use adapter::{Exchange, MarketKind, StreamKind};
@@ -38,6 +39,8 @@ impl std::fmt::Display for Timeframe {
                 Timeframe::H6 => "6h",
                 Timeframe::H12 => "12h",
                 Timeframe::D1 => "1d",
+                Timeframe::D3 => "3d",
+                Timeframe::W1 => "1w",
             }
         )
     }
@@ -60,10 +63,12 @@ pub enum Timeframe {
     H6,
     H12,
     D1,
+    D3,
+    W1,
 }
 
 impl Timeframe {
-    pub const KLINE: [Timeframe; 11] = [
+    pub const KLINE: [Timeframe; 13] = [
         Timeframe::M1,
         Timeframe::M3,
         Timeframe::M5,
@@ -75,6 +80,8 @@ impl Timeframe {
         Timeframe::H6,
         Timeframe::H12,
         Timeframe::D1,
+        Timeframe::D3,
+        Timeframe::W1,
     ];
 
     pub const HEATMAP: [Timeframe; 4] = [
@@ -100,6 +107,8 @@ impl Timeframe {
             Timeframe::H6 => 360,
             Timeframe::H12 => 720,
             Timeframe::D1 => 1440,
+            Timeframe::D3 => 4320,
+            Timeframe::W1 => 10080,
             _ => panic!("Invalid timeframe: {:?}", self),
         }
     }
@@ -116,6 +125,22 @@ impl Timeframe {
             }
         }
     }
+
+    /// Open time of the interval `time` falls in. Weekly ones start on Mondays like the
+    /// exchanges have them, not on the Thursday the epoch fell on
+    pub fn open_time(self, time: u64) -> u64 {
+        const EPOCH_TO_MONDAY_MS: u64 = 4 * 24 * 60 * 60 * 1000;
+
+        let interval = self.to_milliseconds();
+
+        match self {
+            Timeframe::W1 => {
+                let since_monday = time.saturating_sub(EPOCH_TO_MONDAY_MS);
+                (since_monday / interval) * interval + EPOCH_TO_MONDAY_MS
+            }
+            _ => (time / interval) * interval,
+        }
+    }
 }
 
 impl From<Timeframe> for f32 {
@@ -172,6 +197,9 @@ impl SerTicker {
             Exchange::BybitLinear => "BybitLinear",
             Exchange::BybitInverse => "BybitInverse",
             Exchange::BybitSpot => "BybitSpot",
+            Exchange::OkxLinear => "OkxLinear",
+            Exchange::OkxInverse => "OkxInverse",
+            Exchange::OkxSpot => "OkxSpot",
         }
     }
 
@@ -183,6 +211,9 @@ impl SerTicker {
             "BybitLinear" => Ok(Exchange::BybitLinear),
             "BybitInverse" => Ok(Exchange::BybitInverse),
             "BybitSpot" => Ok(Exchange::BybitSpot),
+            "OkxLinear" => Ok(Exchange::OkxLinear),
+            "OkxInverse" => Ok(Exchange::OkxInverse),
+            "OkxSpot" => Ok(Exchange::OkxSpot),
             _ => Err(format!("Unknown exchange: {}", s)),
         }
     }
@@ -356,6 +387,26 @@ impl TickerInfo {
     }
 }
 
+/// Contract details listed next to the tick size in the exchange info, `None` where the venue's
+/// endpoint doesn't list one
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TickerSpecs {
+    /// Value of a single contract, for markets trading in contracts
+    pub contract_size: Option<f32>,
+    pub funding_interval_hours: Option<u32>,
+    /// When the market went live, in unix milliseconds
+    pub listed_at: Option<u64>,
+}
+
+/// Funding of a perpetual market as its venue last reported it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FundingRate {
+    /// Rate of the interval in progress as a fraction, `0.0001` being 0.01%
+    pub rate: f32,
+    /// When the interval in progress gets settled, in unix milliseconds
+    pub next_funding_time: u64,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize)]
 pub struct Trade {
     pub time: u64,
@@ -365,6 +416,15 @@ pub struct Trade {
     pub qty: f32,
 }
 
+/// A forced closing order, `is_sell` when a long position got liquidated
+#[derive(Debug, Clone, Copy)]
+pub struct Liquidation {
+    pub time: u64,
+    pub is_sell: bool,
+    pub price: f32,
+    pub qty: f32,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Kline {
     pub time: u64,
@@ -1,20 +1,77 @@
 use crate::adapter::AdapterError;
+use crate::fetcher::rate_limit_share;
 
 use reqwest::{Client, Response};
-use std::sync::LazyLock;
+use std::sync::{LazyLock, PoisonError, RwLock};
 use std::time::{Duration, Instant};
 
-pub static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
+/// A stalled request fails after this instead of leaving whoever waits on it hanging
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Without a usable client for the proxy that's set, requests fail instead of going out directly
+static HTTP_CLIENT: LazyLock<RwLock<Result<Client, String>>> = LazyLock::new(|| {
+    RwLock::new(build_client(None).map_err(|err| format!("Failed to set up HTTP client: {err}")))
+});
+
+/// Client every REST request goes out with, cloning it shares the connection pool
+pub fn http_client() -> Result<Client, AdapterError> {
+    HTTP_CLIENT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .map_err(AdapterError::InvalidRequest)
+}
+
+/// Why the proxy that's set can't be used, requests are refused until another one is set
+pub(crate) fn http_client_error() -> Option<String> {
+    HTTP_CLIENT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .err()
+        .cloned()
+}
+
+fn build_client(proxy: Option<reqwest::Proxy>) -> reqwest::Result<Client> {
+    let builder = Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT);
+
+    match proxy {
+        Some(proxy) => builder.proxy(proxy).build(),
+        None => builder.build(),
+    }
+}
+
+/// Swaps the shared client for one routed through the proxy, or a direct one without it
+pub(crate) fn rebuild_http_client(proxy: Option<&crate::connect::Proxy>) -> Result<(), String> {
+    let client = match proxy {
+        Some(proxy) => reqwest::Proxy::all(proxy.client_url())
+            .and_then(|proxy| build_client(Some(proxy)))
+            .map_err(|err| format!("Failed to set up proxy {proxy}, refusing to connect: {err}")),
+        None => build_client(None).map_err(|err| format!("Failed to set up HTTP client: {err}")),
+    };
+
+    if let Err(err) = &client {
+        log::error!("{err}");
+    }
+
+    let result = client.as_ref().map(|_| ()).map_err(Clone::clone);
+    *HTTP_CLIENT.write().unwrap_or_else(PoisonError::into_inner) = client;
+    result
+}
 
 pub trait RateLimiter: Send + Sync {
-    /// Prepare for a request with given weight. Returns wait time if needed.
+    /// Reserves room for a request with given weight, or returns how long to wait before
+    /// trying again if there's none
     fn prepare_request(&mut self, weight: usize) -> Option<Duration>;
 
     /// Update the limiter with response data (e.g., rate limit headers)
     fn update_from_response(&mut self, response: &Response, weight: usize);
 
-    /// Check if response indicates rate limiting and should exit
-    fn should_exit_on_response(&self, response: &Response) -> bool;
+    /// Check if response indicates the client got rate limited or blocked
+    fn is_blocked_response(&self, response: &Response) -> bool;
 }
 
 pub async fn http_request_with_limiter<L: RateLimiter>(
@@ -22,25 +79,34 @@ pub async fn http_request_with_limiter<L: RateLimiter>(
     limiter: &tokio::sync::Mutex<L>,
     weight: usize,
 ) -> Result<String, AdapterError> {
-    let mut limiter_guard = limiter.lock().await;
-
-    if let Some(wait_time) = limiter_guard.prepare_request(weight) {
-        log::warn!("Rate limit hit for: {url}. Waiting for {:?}", wait_time);
-        tokio::time::sleep(wait_time).await;
+    // requests queue up on the lock only while reserving their weight, once there's room
+    // they go out side by side instead of one after the other
+    loop {
+        let wait_time = limiter.lock().await.prepare_request(weight);
+
+        match wait_time {
+            None => break,
+            Some(wait_time) => {
+                log::warn!("Rate limit hit for: {url}. Waiting for {:?}", wait_time);
+                tokio::time::sleep(wait_time).await;
+            }
+        }
     }
 
-    let response = HTTP_CLIENT
+    let response = http_client()?
         .get(url)
         .send()
         .await
         .map_err(AdapterError::FetchError)?;
 
-    if limiter_guard.should_exit_on_response(&response) {
-        let status = response.status();
-        eprintln!(
-            "HTTP error {status} for: {url}. Exiting. (This may be a rate limit, geo-block, or other access issue.)",
+    let mut limiter_guard = limiter.lock().await;
+
+    // the body usually says why, so it's passed on for the adapter to surface
+    if limiter_guard.is_blocked_response(&response) {
+        log::error!(
+            "HTTP error {} for: {url}. (This may be a rate limit, geo-block, or other access issue.)",
+            response.status()
         );
-        std::process::exit(1);
     }
 
     limiter_guard.update_from_response(&response, weight);
@@ -60,7 +126,7 @@ impl FixedWindowBucket {
     pub fn new(max_tokens: usize, refill_rate: Duration) -> Self {
         Self {
             max_tokens,
-            available_tokens: max_tokens,
+            available_tokens: rate_limit_share().of(max_tokens),
             last_refill: Instant::now(),
             refill_rate,
         }
@@ -75,7 +141,7 @@ impl FixedWindowBucket {
 
             let elapsed = now.duration_since(self.last_refill);
             if elapsed >= self.refill_rate || seconds_in_current_period < 1 {
-                self.available_tokens = self.max_tokens;
+                self.available_tokens = rate_limit_share().of(self.max_tokens);
                 self.last_refill = now;
             }
         }
@@ -84,6 +150,9 @@ impl FixedWindowBucket {
     pub fn calculate_wait_time(&mut self, tokens: usize) -> Option<Duration> {
         self.refill();
 
+        // a request heavier than the whole share would otherwise wait forever
+        let tokens = tokens.min(rate_limit_share().of(self.max_tokens));
+
         if self.available_tokens >= tokens {
             self.available_tokens -= tokens;
             return None;
@@ -94,11 +163,6 @@ impl FixedWindowBucket {
             .saturating_sub(Instant::now().duration_since(self.last_refill));
         Some(wait_time)
     }
-
-    pub fn consume_tokens(&mut self, tokens: usize) {
-        self.refill();
-        self.available_tokens -= tokens.min(self.available_tokens);
-    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -151,12 +215,15 @@ impl DynamicBucket {
     }
 
     fn prepare_with_header_data(
-        &self,
+        &mut self,
         weight: usize,
     ) -> (Option<Duration>, Option<DynamicLimitReason>) {
-        let available = self.max_weight.saturating_sub(self.current_used_weight);
+        let budget = rate_limit_share().of(self.max_weight);
+        let available = budget.saturating_sub(self.current_used_weight);
 
-        if available >= weight {
+        // counted right away, requests sent before the next header comes back would overshoot
+        if available >= weight.min(budget) {
+            self.current_used_weight += weight;
             return (None, None);
         }
 
@@ -0,0 +1,204 @@
+use crate::{Ticker, Trade, adapter::AdapterError};
+
+use rusqlite::{Connection, params};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, PoisonError},
+    time::Duration,
+};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// Trades of the downloaded daily archives, each ingested once and queried by time range from
+/// then on instead of unzipping and parsing the whole day again
+const DB_FILE: &str = "trades.sqlite3";
+/// Stores of an older version get dropped, the days in them are downloaded again
+const SCHEMA_VERSION: i32 = 1;
+/// How long a write waits for another connection's transaction before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(60);
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS trades (
+        ticker TEXT NOT NULL,
+        agg_id INTEGER NOT NULL,
+        time INTEGER NOT NULL,
+        is_sell INTEGER NOT NULL,
+        price REAL NOT NULL,
+        qty REAL NOT NULL,
+        UNIQUE (ticker, agg_id)
+    );
+    CREATE INDEX IF NOT EXISTS trades_by_time ON trades (ticker, time);
+    CREATE TABLE IF NOT EXISTS ingested_days (
+        ticker TEXT NOT NULL,
+        day TEXT NOT NULL,
+        PRIMARY KEY (ticker, day)
+    );
+";
+
+fn storage_error(err: rusqlite::Error) -> AdapterError {
+    AdapterError::Storage(err.to_string())
+}
+
+/// Tickers of different markets can share a symbol, so the exchange is part of the key
+fn ticker_key(ticker: Ticker) -> String {
+    format!(
+        "{:?}:{}",
+        ticker.exchange,
+        ticker.to_full_symbol_and_type().0
+    )
+}
+
+/// Days being downloaded or ingested, keyed by ticker and day
+static DAY_LOCKS: LazyLock<std::sync::Mutex<HashMap<(Ticker, chrono::NaiveDate), Arc<Mutex<()>>>>> =
+    LazyLock::new(Default::default);
+
+/// Held while a day gets checked and ingested, so a second pane fetching the same day waits
+/// for the first one and then reads what it stored instead of ingesting it again
+pub(crate) async fn lock_day(ticker: Ticker, date: chrono::NaiveDate) -> OwnedMutexGuard<()> {
+    let lock = {
+        let mut locks = DAY_LOCKS.lock().unwrap_or_else(PoisonError::into_inner);
+        // nobody but the map holds these anymore
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        Arc::clone(locks.entry((ticker, date)).or_default())
+    };
+
+    lock.lock_owned().await
+}
+
+pub(crate) struct TradeStore {
+    conn: Connection,
+}
+
+impl TradeStore {
+    /// Opens the store kept in `base_path`, creating it on first use
+    pub fn open(base_path: &Path) -> Result<Self, AdapterError> {
+        std::fs::create_dir_all(base_path)
+            .map_err(|e| AdapterError::Storage(format!("Failed to create directories: {e}")))?;
+
+        let conn = Connection::open(base_path.join(DB_FILE)).map_err(storage_error)?;
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(storage_error)?;
+
+        let version: i32 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(storage_error)?;
+        if version < SCHEMA_VERSION {
+            // trades stored without their aggregate id can't be told apart from duplicates
+            conn.execute_batch("DROP TABLE IF EXISTS trades; DROP TABLE IF EXISTS ingested_days;")
+                .map_err(storage_error)?;
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+                .map_err(storage_error)?;
+        }
+
+        conn.execute_batch(SCHEMA).map_err(storage_error)?;
+
+        Ok(TradeStore { conn })
+    }
+
+    /// Opens the store and runs `f` on it off the async executor, a day's ingest alone
+    /// inserts millions of rows
+    pub async fn run<T: Send + 'static>(
+        base_path: PathBuf,
+        f: impl FnOnce(&mut TradeStore) -> Result<T, AdapterError> + Send + 'static,
+    ) -> Result<T, AdapterError> {
+        tokio::task::spawn_blocking(move || f(&mut TradeStore::open(&base_path)?))
+            .await
+            .map_err(|e| AdapterError::Storage(format!("Trade store task failed: {e}")))?
+    }
+
+    pub fn has_day(&self, ticker: Ticker, date: chrono::NaiveDate) -> Result<bool, AdapterError> {
+        self.conn
+            .prepare_cached("SELECT 1 FROM ingested_days WHERE ticker = ?1 AND day = ?2")
+            .and_then(|mut stmt| stmt.exists(params![ticker_key(ticker), date.to_string()]))
+            .map_err(storage_error)
+    }
+
+    /// Days ingested for the ticker, oldest first
+    pub fn days(&self, ticker: Ticker) -> Result<Vec<chrono::NaiveDate>, AdapterError> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT day FROM ingested_days WHERE ticker = ?1 ORDER BY day")
+            .map_err(storage_error)?;
+
+        let days = stmt
+            .query_map(params![ticker_key(ticker)], |row| row.get::<_, String>(0))
+            .map_err(storage_error)?
+            .filter_map(Result::ok)
+            .filter_map(|day| chrono::NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok())
+            .collect();
+
+        Ok(days)
+    }
+
+    /// Stores a day's trades, each with its aggregate trade id, and marks the day as ingested,
+    /// all or nothing. Trades stored already are skipped.
+    pub fn ingest(
+        &mut self,
+        ticker: Ticker,
+        date: chrono::NaiveDate,
+        trades: &[(u64, Trade)],
+    ) -> Result<(), AdapterError> {
+        let key = ticker_key(ticker);
+        let tx = self.conn.transaction().map_err(storage_error)?;
+
+        {
+            let mut insert = tx
+                .prepare_cached(
+                    "INSERT OR IGNORE INTO trades (ticker, agg_id, time, is_sell, price, qty)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                )
+                .map_err(storage_error)?;
+
+            for (agg_id, trade) in trades {
+                insert
+                    .execute(params![
+                        key,
+                        *agg_id as i64,
+                        trade.time as i64,
+                        trade.is_sell,
+                        f64::from(trade.price),
+                        f64::from(trade.qty)
+                    ])
+                    .map_err(storage_error)?;
+            }
+        }
+
+        tx.execute(
+            "INSERT OR IGNORE INTO ingested_days (ticker, day) VALUES (?1, ?2)",
+            params![key, date.to_string()],
+        )
+        .map_err(storage_error)?;
+
+        tx.commit().map_err(storage_error)
+    }
+
+    /// Stored trades of the ticker within `from..to`, in time order
+    pub fn trades_in_range(
+        &self,
+        ticker: Ticker,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<Trade>, AdapterError> {
+        let mut stmt = self
+            .conn
+            .prepare_cached(
+                "SELECT time, is_sell, price, qty FROM trades
+                 WHERE ticker = ?1 AND time >= ?2 AND time < ?3 ORDER BY time, rowid",
+            )
+            .map_err(storage_error)?;
+
+        let trades = stmt
+            .query_map(params![ticker_key(ticker), from as i64, to as i64], |row| {
+                Ok(Trade {
+                    time: row.get::<_, i64>(0)? as u64,
+                    is_sell: row.get(1)?,
+                    price: row.get::<_, f64>(2)? as f32,
+                    qty: row.get::<_, f64>(3)? as f32,
+                })
+            })
+            .map_err(storage_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(storage_error)?;
+
+        Ok(trades)
+    }
+}
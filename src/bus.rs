@@ -0,0 +1,170 @@
+//! Market and alert events fanned out to whoever subscribed to them, so a new subsystem only
+//! has to subscribe instead of getting called from the update loop one by one.
+
+use data::{alert::Alert, recorder::Recorder};
+use exchange::{
+    Kline, Trade,
+    adapter::{self, StreamKind},
+    depth::Depth,
+};
+
+use crate::modal::audio::AudioStream;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topic {
+    Trades,
+    Depth,
+    Kline,
+    Alert,
+}
+
+/// Borrows the data it carries, subscribers copy out what they want to keep
+#[derive(Debug, Clone, Copy)]
+pub enum Event<'a> {
+    /// Trades that came in with the depth update at the time, published before its book
+    Trades(&'a StreamKind, u64, &'a [Trade]),
+    Depth(&'a StreamKind, u64, &'a Depth),
+    /// Nothing subscribes to klines yet, charts still get them through the dashboard
+    #[allow(dead_code)]
+    Kline(&'a StreamKind, &'a Kline),
+    AlertFired(&'a Alert),
+}
+
+impl Event<'_> {
+    pub fn topic(&self) -> Topic {
+        match self {
+            Event::Trades(..) => Topic::Trades,
+            Event::Depth(..) => Topic::Depth,
+            Event::Kline(..) => Topic::Kline,
+            Event::AlertFired(_) => Topic::Alert,
+        }
+    }
+}
+
+/// What the app owns that subscribers act on, lent to them for each publish
+pub struct Sinks<'a> {
+    pub audio: &'a AudioStream,
+    /// `None` while recording is turned off
+    pub recorder: Option<&'a mut Recorder>,
+}
+
+pub trait Subscriber {
+    /// Only events of these get delivered
+    fn topics(&self) -> &'static [Topic];
+
+    fn on_event(&mut self, event: Event<'_>, sinks: &mut Sinks<'_>);
+}
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Vec<Box<dyn Subscriber>>,
+}
+
+impl EventBus {
+    pub fn subscribe(&mut self, subscriber: impl Subscriber + 'static) {
+        self.subscribers.push(Box::new(subscriber));
+    }
+
+    pub fn publish(&mut self, event: Event<'_>, sinks: &mut Sinks<'_>) {
+        let topic = event.topic();
+
+        for subscriber in &mut self.subscribers {
+            if subscriber.topics().contains(&topic) {
+                subscriber.on_event(event, sinks);
+            }
+        }
+    }
+}
+
+/// Keeps fired alerts in the events a crash report lists
+pub struct CrashTrail;
+
+impl Subscriber for CrashTrail {
+    fn topics(&self) -> &'static [Topic] {
+        &[Topic::Alert]
+    }
+
+    fn on_event(&mut self, event: Event<'_>, _: &mut Sinks<'_>) {
+        if let Event::AlertFired(alert) = event {
+            data::crash::record_event(format!("alert fired: {}", alert.fired_message()));
+        }
+    }
+}
+
+/// Plays the sound of fired alerts that have one turned on
+pub struct AlertSound;
+
+impl Subscriber for AlertSound {
+    fn topics(&self) -> &'static [Topic] {
+        &[Topic::Alert]
+    }
+
+    fn on_event(&mut self, event: Event<'_>, sinks: &mut Sinks<'_>) {
+        let Event::AlertFired(alert) = event else {
+            return;
+        };
+        if !alert.play_sound {
+            return;
+        }
+
+        let sound = alert
+            .sound
+            .as_deref()
+            .unwrap_or(data::audio::HARD_BUY_SOUND);
+
+        if let Err(err) = sinks.audio.play(sound) {
+            log::error!("Failed to play alert sound: {err}");
+        }
+    }
+}
+
+/// Plays the sounds set up for the trades of a stream
+pub struct TradeSound;
+
+impl Subscriber for TradeSound {
+    fn topics(&self) -> &'static [Topic] {
+        &[Topic::Trades]
+    }
+
+    fn on_event(&mut self, event: Event<'_>, sinks: &mut Sinks<'_>) {
+        let Event::Trades(stream, _, trades) = event else {
+            return;
+        };
+
+        if let Err(err) = sinks.audio.try_play_sound(stream, trades) {
+            log::error!("Failed to play sound: {err}");
+        }
+    }
+}
+
+/// Hands the trades and books of depth streams to the recorder while it's on
+pub struct Recording;
+
+impl Subscriber for Recording {
+    fn topics(&self) -> &'static [Topic] {
+        &[Topic::Trades, Topic::Depth]
+    }
+
+    fn on_event(&mut self, event: Event<'_>, sinks: &mut Sinks<'_>) {
+        let Some(recorder) = sinks.recorder.as_deref_mut() else {
+            return;
+        };
+
+        match event {
+            Event::Trades(stream, depth_update_t, trades) => {
+                let (exchange, ticker) = stream.exchange_and_ticker();
+                // testnet books would end up drawn on the real market's heatmaps
+                if !adapter::is_testnet(exchange) {
+                    recorder.record_trades(exchange, ticker, depth_update_t, trades);
+                }
+            }
+            Event::Depth(stream, depth_update_t, depth) => {
+                let (exchange, ticker) = stream.exchange_and_ticker();
+                if !adapter::is_testnet(exchange) {
+                    recorder.record_depth(exchange, ticker, depth_update_t, depth);
+                }
+            }
+            Event::Kline(..) | Event::AlertFired(_) => {}
+        }
+    }
+}
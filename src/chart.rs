@@ -1,14 +1,18 @@
 pub mod heatmap;
 pub mod indicator;
 pub mod kline;
+pub mod profiler;
 mod scale;
 
 use crate::style;
 use crate::widget::multi_split::{DRAG_SIZE, MultiSplit};
 use crate::widget::tooltip;
-use data::chart::{Autoscale, Basis, PlotData, ViewConfig, indicator::Indicator};
+use data::chart::drawing::{Anchor, Drawing, Tool};
+use data::chart::{
+    Autoscale, Basis, PlotData, ViewConfig, day_levels::DayLevels, indicator::Indicator,
+};
 use exchange::fetcher::{FetchRange, RequestHandler};
-use exchange::{TickerInfo, Timeframe};
+use exchange::{Liquidation, TickerInfo, Timeframe, adapter::MarketKind};
 use scale::linear::PriceInfoLabel;
 use scale::{AxisLabelsX, AxisLabelsY};
 
@@ -22,7 +26,17 @@ use iced::{
 };
 
 const ZOOM_SENSITIVITY: f32 = 30.0;
-const TEXT_SIZE: f32 = 12.0;
+/// Pixels above or below an alert line a press still grabs it from
+const ALERT_GRAB_DISTANCE: f32 = 4.0;
+/// Pixels away from a drawing's outline a press still selects it from
+const DRAWING_GRAB_DISTANCE: f32 = 6.0;
+
+/// Level of an armed price alert of the chart's ticker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlertLine {
+    pub id: u64,
+    pub price: f32,
+}
 
 #[derive(Default, Debug, Clone, Copy)]
 pub enum Interaction {
@@ -35,6 +49,17 @@ pub enum Interaction {
         translation: Vector,
         start: Point,
     },
+    DraggingAlert {
+        id: u64,
+        price: f32,
+    },
+    PlacingDrawing {
+        index: usize,
+    },
+    MovingDrawing {
+        index: usize,
+        last: Anchor,
+    },
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -48,12 +73,23 @@ pub enum Message {
     Translated(Vector),
     Scaled(f32, Vector),
     AutoscaleToggled,
+    AutoFollowToggled,
+    JumpToLatest,
     CrosshairMoved,
     YScaling(f32, f32, bool),
     XScaling(f32, f32, bool),
     BoundsChanged(Rectangle),
     SplitDragged(usize, f32),
     DoubleClick(AxisScaleClicked),
+    ScrollToTime(u64),
+    AlertLineDragged(u64, f32),
+    /// The alert should be moved to the price its line was let go at
+    AlertLineReleased(u64, f32),
+    DrawingToolSelected(Option<Tool>),
+    DrawingPlaced(Drawing),
+    DrawingEdited(usize, Drawing),
+    DrawingSelected(Option<usize>),
+    DrawingDeleted(usize),
 }
 
 pub trait Chart: PlotConstants + canvas::Program<Message> {
@@ -92,29 +128,144 @@ fn canvas_interaction<T: Chart>(
     }
 
     if let Event::Mouse(mouse::Event::ButtonReleased(_)) = event {
-        *interaction = Interaction::None;
+        match std::mem::take(interaction) {
+            Interaction::DraggingAlert { id, price } => {
+                return Some(canvas::Action::publish(Message::AlertLineReleased(
+                    id, price,
+                )));
+            }
+            Interaction::PlacingDrawing { .. } => {
+                return Some(canvas::Action::publish(Message::DrawingToolSelected(None)));
+            }
+            _ => {}
+        }
     }
 
     match event {
+        Event::Keyboard(iced::keyboard::Event::KeyPressed { key, .. }) => {
+            let state = chart.state();
+
+            if !cursor.is_over(bounds) {
+                return None;
+            }
+
+            let message = match key.as_ref() {
+                iced::keyboard::Key::Named(
+                    iced::keyboard::key::Named::Delete | iced::keyboard::key::Named::Backspace,
+                ) => state.selected_drawing.map(Message::DrawingDeleted),
+                iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape) => {
+                    if state.drawing_tool.is_some() {
+                        Some(Message::DrawingToolSelected(None))
+                    } else {
+                        state
+                            .selected_drawing
+                            .map(|_| Message::DrawingSelected(None))
+                    }
+                }
+                _ => None,
+            };
+
+            message.map(|message| canvas::Action::publish(message).and_capture())
+        }
         Event::Mouse(mouse_event) => {
             let cursor_position = cursor.position_in(bounds.shrink(DRAG_SIZE * 4.0))?;
             let state = chart.state();
 
             match mouse_event {
                 mouse::Event::ButtonPressed(button) => {
+                    let mut message = None;
+
                     if let mouse::Button::Left = button {
-                        *interaction = Interaction::Panning {
-                            translation: state.translation,
-                            start: cursor_position,
+                        let position = cursor.position_in(bounds);
+                        let grabbed = position.and_then(|position| state.alert_line_at(position.y));
+
+                        *interaction = match (grabbed, position) {
+                            (Some(line), _) => Interaction::DraggingAlert {
+                                id: line.id,
+                                price: line.price,
+                            },
+                            (None, Some(position)) if state.can_draw() => {
+                                let anchor = state.anchor_at(position);
+
+                                match (state.drawing_tool, state.drawing_at(position)) {
+                                    (Some(tool), _) => {
+                                        message = Some(Message::DrawingPlaced(Drawing::new(
+                                            tool, anchor,
+                                        )));
+                                        Interaction::PlacingDrawing {
+                                            index: state.layout.drawings.len(),
+                                        }
+                                    }
+                                    (None, Some(index)) => {
+                                        message = Some(Message::DrawingSelected(Some(index)));
+                                        Interaction::MovingDrawing {
+                                            index,
+                                            last: anchor,
+                                        }
+                                    }
+                                    (None, None) => {
+                                        if state.selected_drawing.is_some() {
+                                            message = Some(Message::DrawingSelected(None));
+                                        }
+                                        Interaction::Panning {
+                                            translation: state.translation,
+                                            start: cursor_position,
+                                        }
+                                    }
+                                }
+                            }
+                            _ => Interaction::Panning {
+                                translation: state.translation,
+                                start: cursor_position,
+                            },
                         };
                     }
-                    Some(canvas::Action::request_redraw().and_capture())
+
+                    Some(
+                        message
+                            .map_or(canvas::Action::request_redraw(), canvas::Action::publish)
+                            .and_capture(),
+                    )
                 }
                 mouse::Event::CursorMoved { .. } => {
                     let message = match *interaction {
                         Interaction::Panning { translation, start } => Some(Message::Translated(
                             translation + (cursor_position - start) * (1.0 / state.scaling),
                         )),
+                        Interaction::DraggingAlert { id, .. } => {
+                            let price = cursor
+                                .position_in(bounds)
+                                .map(|position| state.alert_price_at(position.y))?;
+                            *interaction = Interaction::DraggingAlert { id, price };
+
+                            Some(Message::AlertLineDragged(id, price))
+                        }
+                        Interaction::PlacingDrawing { index } => {
+                            let anchor = cursor
+                                .position_in(bounds)
+                                .map(|position| state.anchor_at(position))?;
+                            let drawing = state.layout.drawings.get(index)?;
+
+                            Some(Message::DrawingEdited(index, drawing.with_end(anchor)))
+                        }
+                        Interaction::MovingDrawing { index, last } => {
+                            let anchor = cursor
+                                .position_in(bounds)
+                                .map(|position| state.anchor_at(position))?;
+                            let drawing = state.layout.drawings.get(index)?;
+                            *interaction = Interaction::MovingDrawing {
+                                index,
+                                last: anchor,
+                            };
+
+                            Some(Message::DrawingEdited(
+                                index,
+                                drawing.translated(
+                                    anchor.time as i64 - last.time as i64,
+                                    anchor.price - last.price,
+                                ),
+                            ))
+                        }
                         Interaction::None => Some(Message::CrosshairMoved),
                         _ => None,
                     };
@@ -269,6 +420,10 @@ pub fn update<T: Chart>(chart: &mut T, message: Message) {
         Message::Translated(translation) => {
             let state = chart.mut_state();
 
+            if translation.x != state.translation.x {
+                state.follow_latest = false;
+            }
+
             if let Some(Autoscale::FitToVisible) = state.layout.autoscale {
                 state.translation.x = translation.x;
             } else {
@@ -282,6 +437,7 @@ pub fn update<T: Chart>(chart: &mut T, message: Message) {
             state.translation = translation;
 
             state.layout.autoscale = None;
+            state.follow_latest = false;
         }
         Message::AutoscaleToggled => {
             let supports_fit_autoscaling = chart.supports_fit_autoscaling();
@@ -306,6 +462,20 @@ pub fn update<T: Chart>(chart: &mut T, message: Message) {
                 state.scaling = 1.0;
             }
         }
+        Message::AutoFollowToggled => {
+            let autoscaled_coords = chart.autoscaled_coords();
+            let state = chart.mut_state();
+
+            state.follow_latest = !state.follow_latest;
+
+            if state.follow_latest {
+                state.translation.x = autoscaled_coords.x;
+            }
+        }
+        Message::JumpToLatest => {
+            let autoscaled_coords = chart.autoscaled_coords();
+            chart.mut_state().translation.x = autoscaled_coords.x;
+        }
         Message::XScaling(delta, cursor_to_center_x, is_wheel_scroll) => {
             let min_cell_width = T::min_cell_width(chart);
             let max_cell_width = T::max_cell_width(chart);
@@ -372,7 +542,7 @@ pub fn update<T: Chart>(chart: &mut T, message: Message) {
 
                         state.interval_to_x(cursor_time)
                     }
-                    Basis::Tick(_) => {
+                    Basis::Tick(_) | Basis::Range(_) => {
                         let tick_index = cursor_chart_x / state.cell_width;
                         state.cell_width = new_width;
 
@@ -449,15 +619,105 @@ pub fn update<T: Chart>(chart: &mut T, message: Message) {
                 *split = (size * 100.0).round() / 100.0;
             }
         }
+        Message::ScrollToTime(time) => {
+            let state = chart.mut_state();
+
+            if let Basis::Tick(_) | Basis::Range(_) = state.basis {
+                log::warn!("Can't scroll a tick based chart to a timestamp");
+                return;
+            }
+
+            state.layout.autoscale = None;
+            state.translation.x = -state.interval_to_x(time);
+        }
+        Message::AlertLineDragged(id, price) => {
+            let state = chart.mut_state();
+            state.dragged_alert = Some(id);
+
+            if let Some(line) = state.alert_lines.iter_mut().find(|line| line.id == id) {
+                line.price = price;
+            }
+        }
+        Message::AlertLineReleased(_, _) => chart.mut_state().dragged_alert = None,
+        Message::DrawingToolSelected(tool) => {
+            let state = chart.mut_state();
+            state.drawing_tool = tool;
+
+            if tool.is_some() {
+                state.selected_drawing = None;
+            }
+        }
+        Message::DrawingPlaced(drawing) => {
+            let state = chart.mut_state();
+            state.layout.drawings.push(drawing);
+            state.selected_drawing = Some(state.layout.drawings.len() - 1);
+        }
+        Message::DrawingEdited(index, drawing) => {
+            if let Some(existing) = chart.mut_state().layout.drawings.get_mut(index) {
+                *existing = drawing;
+            }
+        }
+        Message::DrawingSelected(index) => chart.mut_state().selected_drawing = index,
+        Message::DrawingDeleted(index) => {
+            let state = chart.mut_state();
+
+            if index < state.layout.drawings.len() {
+                state.layout.drawings.remove(index);
+            }
+            state.selected_drawing = None;
+        }
         Message::CrosshairMoved => return chart.invalidate_crosshair(),
     }
     chart.invalidate_all();
 }
 
+/// Hides the crosshair and draws the watermark while `export` is set, for the next frame to be
+/// captured as a PNG
+pub fn set_export<T: Chart>(chart: &mut T, export: Option<Option<String>>) {
+    chart.mut_state().export = export;
+    chart.invalidate_all();
+}
+
+/// Window area of the chart's main canvas, without its axis labels
+pub fn canvas_bounds<T: Chart>(chart: &T) -> Rectangle {
+    chart.state().bounds
+}
+
+/// Tool the next press on the chart starts a drawing with
+pub fn drawing_tool<T: Chart>(chart: &T) -> Option<Tool> {
+    chart.state().drawing_tool
+}
+
+/// Replaces the day's levels the chart draws, `None` takes them off
+pub fn set_day_levels<T: Chart>(chart: &mut T, levels: Option<DayLevels>) {
+    let state = chart.mut_state();
+
+    if state.day_levels == levels {
+        return;
+    }
+
+    state.day_levels = levels;
+    chart.invalidate_all();
+}
+
+/// Replaces the alert lines the chart draws, unless one of them is being dragged right now
+pub fn set_alert_lines<T: Chart>(chart: &mut T, lines: Vec<AlertLine>) {
+    let state = chart.mut_state();
+
+    if state.dragged_alert.is_some() || state.alert_lines == lines {
+        return;
+    }
+
+    state.alert_lines = lines;
+    chart.invalidate_all();
+}
+
+/// Chart with its axes and indicator panels, `show_axes` off leaves the plot on its own
 pub fn view<'a, T: Chart>(
     chart: &'a T,
     indicators: &'a [T::IndicatorType],
     timezone: data::UserTimezone,
+    show_axes: bool,
 ) -> Element<'a, Message> {
     if chart.is_empty() {
         return center(text("Waiting for data...").size(16)).into();
@@ -498,15 +758,56 @@ pub fn view<'a, T: Chart>(
         .on_press(Message::AutoscaleToggled)
         .style(move |theme: &Theme, status| style::button::transparent(theme, status, is_active));
 
-        row![
-            iced::widget::horizontal_space(),
-            tooltip(
+        let is_following = state.follow_latest;
+
+        let follow_button = button(
+            text("F")
+                .size(10)
+                .align_x(Alignment::Center)
+                .align_y(Alignment::Center),
+        )
+        .height(Length::Fill)
+        .on_press(Message::AutoFollowToggled)
+        .style(move |theme: &Theme, status| {
+            style::button::transparent(theme, status, is_following)
+        });
+
+        let mut buttons = row![iced::widget::horizontal_space()];
+
+        if !state.is_latest_visible() {
+            let jump_button = button(
+                text(">>")
+                    .size(10)
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .height(Length::Fill)
+            .on_press(Message::JumpToLatest)
+            .style(|theme: &Theme, status| style::button::transparent(theme, status, false));
+
+            buttons = buttons.push(tooltip(
+                jump_button,
+                Some("Jump to now"),
+                iced::widget::tooltip::Position::Top,
+            ));
+        }
+
+        buttons
+            .push(tooltip(
+                follow_button,
+                Some(if is_following {
+                    "Following latest, click to stop"
+                } else {
+                    "Follow latest"
+                }),
+                iced::widget::tooltip::Position::Top,
+            ))
+            .push(tooltip(
                 autoscale_button,
                 autoscale_btn_tooltip,
-                iced::widget::tooltip::Position::Top
-            ),
-        ]
-        .padding(2)
+                iced::widget::tooltip::Position::Top,
+            ))
+            .padding(2)
     };
 
     let y_labels_width = state.y_labels_width();
@@ -518,7 +819,8 @@ pub fn view<'a, T: Chart>(
             scaling: state.scaling,
             decimals: state.decimals,
             min: state.base_price_y,
-            last_price: state.last_price,
+            last_price: state.shown_last_price(),
+            show_countdown: state.show_countdown,
             tick_size: state.tick_size,
             cell_height: state.cell_height,
             basis: state.basis,
@@ -527,19 +829,25 @@ pub fn view<'a, T: Chart>(
         .width(Length::Fill)
         .height(Length::Fill);
 
-        let main_chart: Element<_> = row![
-            container(Canvas::new(chart).width(Length::Fill).height(Length::Fill))
-                .width(Length::FillPortion(10))
-                .height(Length::FillPortion(120)),
-            vertical_rule(1).style(style::split_ruler),
-            container(
-                mouse_area(axis_labels_y)
-                    .on_double_click(Message::DoubleClick(AxisScaleClicked::Y))
-            )
-            .width(y_labels_width)
-            .height(Length::FillPortion(120))
-        ]
-        .into();
+        let plot = container(Canvas::new(chart).width(Length::Fill).height(Length::Fill))
+            .width(Length::FillPortion(10))
+            .height(Length::FillPortion(120));
+
+        let main_chart: Element<_> = if show_axes {
+            row![
+                plot,
+                vertical_rule(1).style(style::split_ruler),
+                container(
+                    mouse_area(axis_labels_y)
+                        .on_double_click(Message::DoubleClick(AxisScaleClicked::Y))
+                )
+                .width(y_labels_width)
+                .height(Length::FillPortion(120))
+            ]
+            .into()
+        } else {
+            plot.into()
+        };
 
         let indicators = chart.view_indicators(indicators);
 
@@ -557,6 +865,12 @@ pub fn view<'a, T: Chart>(
         }
     };
 
+    if !show_axes {
+        return container(content)
+            .padding(padding::left(1).right(1).bottom(1))
+            .into();
+    }
+
     column![
         content,
         horizontal_rule(1).style(style::split_ruler),
@@ -623,6 +937,19 @@ pub struct ViewState {
     decimals: usize,
     ticker_info: Option<TickerInfo>,
     layout: ViewConfig,
+    alert_lines: Vec<AlertLine>,
+    dragged_alert: Option<u64>,
+    drawing_tool: Option<Tool>,
+    selected_drawing: Option<usize>,
+    /// Set while the chart gets captured for a PNG export, holding its watermark if any
+    export: Option<Option<String>>,
+    /// Bars after this time are hidden while replaying bar by bar
+    replay_cutoff: Option<u64>,
+    /// Time left on the current bar is shown with the last price on time based charts
+    show_countdown: bool,
+    day_levels: Option<DayLevels>,
+    /// Keeps the latest bar in view horizontally, whatever the autoscale mode
+    follow_latest: bool,
 }
 
 impl Default for ViewState {
@@ -642,6 +969,15 @@ impl Default for ViewState {
             decimals: 0,
             ticker_info: None,
             layout: ViewConfig::default(),
+            alert_lines: vec![],
+            dragged_alert: None,
+            drawing_tool: None,
+            selected_drawing: None,
+            export: None,
+            replay_cutoff: None,
+            show_countdown: true,
+            day_levels: None,
+            follow_latest: false,
         }
     }
 }
@@ -665,23 +1001,41 @@ impl ViewState {
         interval_x >= region.x && interval_x <= region.x + region.width
     }
 
+    fn is_latest_visible(&self) -> bool {
+        let latest_x = match self.basis {
+            Basis::Time(_) => self.interval_to_x(self.latest_x),
+            Basis::Tick(_) | Basis::Range(_) => 0.0,
+        };
+
+        self.is_interval_x_visible(latest_x)
+    }
+
     fn interval_range(&self, region: &Rectangle) -> (u64, u64) {
         match self.basis {
-            Basis::Tick(_) => (
+            Basis::Tick(_) | Basis::Range(_) => (
                 self.x_to_interval(region.x + region.width),
                 self.x_to_interval(region.x),
             ),
             Basis::Time(timeframe) => {
                 let interval = timeframe.to_milliseconds();
+                let latest = self
+                    .x_to_interval(region.x + region.width)
+                    .saturating_add(interval / 2);
+
                 (
                     self.x_to_interval(region.x).saturating_sub(interval / 2),
-                    self.x_to_interval(region.x + region.width)
-                        .saturating_add(interval / 2),
+                    self.replay_cutoff
+                        .map_or(latest, |cutoff| latest.min(cutoff)),
                 )
             }
         }
     }
 
+    /// The last price gives away where a replayed chart is heading, it's left out until the end
+    fn shown_last_price(&self) -> Option<PriceInfoLabel> {
+        self.last_price.filter(|_| self.replay_cutoff.is_none())
+    }
+
     fn price_range(&self, region: &Rectangle) -> (f32, f32) {
         let highest = self.y_to_price(region.y);
         let lowest = self.y_to_price(region.y + region.height);
@@ -698,7 +1052,7 @@ impl ViewState {
                 let diff = value as f64 - self.latest_x as f64;
                 (diff / interval * cell_width) as f32
             }
-            Basis::Tick(_) => -((value as f32) * self.cell_width),
+            Basis::Tick(_) | Basis::Range(_) => -((value as f32) * self.cell_width),
         }
     }
 
@@ -715,7 +1069,7 @@ impl ViewState {
                     self.latest_x.saturating_add(diff)
                 }
             }
-            Basis::Tick(_) => {
+            Basis::Tick(_) | Basis::Range(_) => {
                 let tick = -(x / self.cell_width);
                 tick.round() as u64
             }
@@ -730,6 +1084,230 @@ impl ViewState {
         self.base_price_y - (y / self.cell_height) * self.tick_size
     }
 
+    /// Alert line within grabbing distance of a y position relative to the chart's bounds
+    fn alert_line_at(&self, y: f32) -> Option<AlertLine> {
+        self.alert_lines.iter().copied().find(|line| {
+            let line_y = (self.price_to_y(line.price) + self.translation.y) * self.scaling
+                + self.bounds.height / 2.0;
+            (line_y - y).abs() <= ALERT_GRAB_DISTANCE
+        })
+    }
+
+    /// Tick rounded price a dragged alert line lands on at a y position relative to the bounds
+    fn alert_price_at(&self, y: f32) -> f32 {
+        let chart_y = (y - self.bounds.height / 2.0) / self.scaling - self.translation.y;
+        data::util::round_to_tick(self.y_to_price(chart_y), self.tick_size)
+    }
+
+    fn draw_alert_lines(&self, frame: &mut canvas::Frame, palette: &Extended, region: Rectangle) {
+        let color = palette.warning.base.color;
+
+        for line in &self.alert_lines {
+            let y_pos = self.price_to_y(line.price);
+            let is_dragged = self.dragged_alert == Some(line.id);
+
+            frame.stroke(
+                &Path::line(
+                    Point::new(region.x, y_pos),
+                    Point::new(region.x + region.width, y_pos),
+                ),
+                Stroke::with_color(
+                    Stroke {
+                        width: if is_dragged { 2.0 } else { 1.0 },
+                        ..Default::default()
+                    },
+                    color.scale_alpha(if is_dragged { 1.0 } else { 0.7 }),
+                ),
+            );
+        }
+    }
+
+    /// Dashed line across the region at each of the day's levels, labeled at its right edge
+    fn draw_day_levels(&self, frame: &mut canvas::Frame, palette: &Extended, region: Rectangle) {
+        let Some(levels) = self.day_levels else {
+            return;
+        };
+
+        let color = palette.primary.base.color.scale_alpha(0.8);
+        let stroke = Stroke::with_color(
+            Stroke {
+                width: 1.0,
+                line_dash: LineDash {
+                    segments: &[6.0, 4.0],
+                    offset: 0,
+                },
+                ..Default::default()
+            },
+            color,
+        );
+        let text_size = style::chart_text_size() / self.scaling;
+
+        for (label, price) in levels.lines() {
+            let y_pos = self.price_to_y(price);
+
+            frame.stroke(
+                &Path::line(
+                    Point::new(region.x, y_pos),
+                    Point::new(region.x + region.width, y_pos),
+                ),
+                stroke,
+            );
+
+            frame.fill_text(canvas::Text {
+                content: label.to_string(),
+                position: Point::new(region.x + region.width - 4.0 / self.scaling, y_pos),
+                size: iced::Pixels(text_size),
+                color,
+                align_x: Alignment::End.into(),
+                align_y: Alignment::End.into(),
+                font: style::chart_font(),
+                ..canvas::Text::default()
+            });
+        }
+    }
+
+    /// Drawings are pinned to timestamps, which tick based bars don't have a fixed one of
+    fn can_draw(&self) -> bool {
+        matches!(self.basis, Basis::Time(_))
+    }
+
+    /// Chart coordinates under a position relative to the chart's bounds
+    fn anchor_at(&self, position: Point) -> Anchor {
+        let chart_x = (position.x - self.bounds.width / 2.0) / self.scaling - self.translation.x;
+        let chart_y = (position.y - self.bounds.height / 2.0) / self.scaling - self.translation.y;
+
+        Anchor::new(self.x_to_interval(chart_x), self.y_to_price(chart_y))
+    }
+
+    /// Position of an anchor relative to the chart's bounds
+    fn anchor_position(&self, anchor: Anchor) -> Point {
+        Point::new(
+            (self.interval_to_x(anchor.time) + self.translation.x) * self.scaling
+                + self.bounds.width / 2.0,
+            (self.price_to_y(anchor.price) + self.translation.y) * self.scaling
+                + self.bounds.height / 2.0,
+        )
+    }
+
+    /// Topmost drawing within grabbing distance of a position relative to the chart's bounds
+    fn drawing_at(&self, position: Point) -> Option<usize> {
+        self.layout
+            .drawings
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, drawing)| match **drawing {
+                Drawing::TrendLine { from, to } => {
+                    let (a, b) = (self.anchor_position(from), self.anchor_position(to));
+                    distance_to_segment(position, a, b) <= DRAWING_GRAB_DISTANCE
+                }
+                Drawing::HorizontalRay { from } => {
+                    let start = self.anchor_position(from);
+                    position.x >= start.x - DRAWING_GRAB_DISTANCE
+                        && (position.y - start.y).abs() <= DRAWING_GRAB_DISTANCE
+                }
+                Drawing::Rectangle { from, to } => {
+                    let (a, b) = (self.anchor_position(from), self.anchor_position(to));
+                    position.x >= a.x.min(b.x) - DRAWING_GRAB_DISTANCE
+                        && position.x <= a.x.max(b.x) + DRAWING_GRAB_DISTANCE
+                        && position.y >= a.y.min(b.y) - DRAWING_GRAB_DISTANCE
+                        && position.y <= a.y.max(b.y) + DRAWING_GRAB_DISTANCE
+                }
+            })
+            .map(|(index, _)| index)
+    }
+
+    fn draw_drawings(&self, frame: &mut canvas::Frame, palette: &Extended, region: Rectangle) {
+        if !self.can_draw() {
+            return;
+        }
+
+        let color = palette.primary.strong.color;
+        let point = |anchor: Anchor| {
+            Point::new(
+                self.interval_to_x(anchor.time),
+                self.price_to_y(anchor.price),
+            )
+        };
+
+        for (index, drawing) in self.layout.drawings.iter().enumerate() {
+            let is_selected = self.selected_drawing == Some(index);
+            let stroke = Stroke::with_color(
+                Stroke {
+                    width: if is_selected { 2.0 } else { 1.0 },
+                    ..Default::default()
+                },
+                color,
+            );
+
+            let anchors = match *drawing {
+                Drawing::TrendLine { from, to } => {
+                    frame.stroke(&Path::line(point(from), point(to)), stroke);
+                    vec![from, to]
+                }
+                Drawing::HorizontalRay { from } => {
+                    let start = point(from);
+                    frame.stroke(
+                        &Path::line(start, Point::new(region.x + region.width, start.y)),
+                        stroke,
+                    );
+                    vec![from]
+                }
+                Drawing::Rectangle { from, to } => {
+                    let (a, b) = (point(from), point(to));
+                    let rect = Path::rectangle(
+                        Point::new(a.x.min(b.x), a.y.min(b.y)),
+                        Size::new((a.x - b.x).abs(), (a.y - b.y).abs()),
+                    );
+
+                    frame.fill(&rect, color.scale_alpha(0.1));
+                    frame.stroke(&rect, stroke);
+                    vec![from, to]
+                }
+            };
+
+            if is_selected {
+                let handle = 4.0 / self.scaling;
+
+                for anchor in anchors {
+                    let center = point(anchor);
+                    frame.fill_rectangle(
+                        Point::new(center.x - handle / 2.0, center.y - handle / 2.0),
+                        Size::new(handle, handle),
+                        color,
+                    );
+                }
+            }
+        }
+    }
+
+    fn is_exporting(&self) -> bool {
+        self.export.is_some()
+    }
+
+    /// Ticker and timeframe label in the bottom left corner of an exported chart
+    fn draw_watermark(
+        &self,
+        renderer: &iced::Renderer,
+        palette: &Extended,
+        bounds_size: Size,
+    ) -> Option<canvas::Geometry> {
+        let watermark = self.export.as_ref()?.as_ref()?;
+        let mut frame = Frame::new(renderer, bounds_size);
+
+        frame.fill_text(canvas::Text {
+            content: watermark.clone(),
+            position: Point::new(8.0, bounds_size.height - 8.0),
+            size: iced::Pixels(16.0),
+            color: palette.background.base.text.scale_alpha(0.4),
+            align_y: Alignment::End.into(),
+            font: style::chart_font(),
+            ..canvas::Text::default()
+        });
+
+        Some(frame.into_geometry())
+    }
+
     fn draw_crosshair(
         &self,
         frame: &mut Frame,
@@ -785,7 +1363,8 @@ impl ViewState {
 
                 (rounded_price, rounded_timestamp)
             }
-            Basis::Tick(aggregation) => {
+            Basis::Tick(_) | Basis::Range(_) => {
+                let x_step = self.basis.trade_bar().map_or(1, |bar| bar.x_step());
                 let crosshair_ratio = cursor_position.x / bounds.width;
 
                 let (chart_x_min, chart_x_max) = (region.x, region.x + region.width);
@@ -797,7 +1376,7 @@ impl ViewState {
 
                 let snap_ratio = (snapped_crosshair - chart_x_min) / (chart_x_max - chart_x_min);
 
-                let rounded_tick = (-cell_index as u64) * (u64::from(aggregation.0));
+                let rounded_tick = (-cell_index as u64) * x_step;
 
                 frame.stroke(
                     &Path::line(
@@ -818,7 +1397,7 @@ impl ViewState {
         palette: &Extended,
         region: Rectangle,
     ) {
-        if let Some(price) = &self.last_price {
+        if let Some(price) = &self.shown_last_price() {
             let (mut y_pos, line_color) = price.get_with_color(palette);
             y_pos = self.price_to_y(y_pos);
 
@@ -849,6 +1428,7 @@ impl ViewState {
         ViewConfig {
             splits: layout.splits.clone(),
             autoscale: layout.autoscale,
+            drawings: layout.drawings.clone(),
         }
     }
 
@@ -857,7 +1437,7 @@ impl ViewState {
         let decimals = self.decimals;
 
         let value = format!("{base_value:.decimals$}");
-        let width = (value.len() as f32 * TEXT_SIZE * 0.8).max(72.0);
+        let width = (value.len() as f32 * style::chart_text_size() * 0.8).max(72.0);
 
         Length::Fixed(width.ceil())
     }
@@ -943,3 +1523,92 @@ fn draw_volume_bar(
         }
     }
 }
+
+/// Latest liquidations a chart holds on to, older ones are dropped first
+const LIQUIDATIONS_LIMIT: usize = 2000;
+const MAX_LIQUIDATION_RADIUS: f32 = 14.0;
+
+fn push_liquidations(buffer: &mut Vec<Liquidation>, liquidations: &[Liquidation]) {
+    buffer.extend_from_slice(liquidations);
+
+    if buffer.len() > LIQUIDATIONS_LIMIT {
+        buffer.drain(..buffer.len() - LIQUIDATIONS_LIMIT);
+    }
+}
+
+/// Draws liquidations within `earliest..=latest` as bubbles sized by their notional value,
+/// relative to the largest one in view. `time_to_x` places them on the chart's own x-axis.
+fn draw_liquidations(
+    frame: &mut canvas::Frame,
+    palette: &Extended,
+    liquidations: &[Liquidation],
+    (earliest, latest): (u64, u64),
+    size_scale: i32,
+    market_type: MarketKind,
+    price_to_y: impl Fn(f32) -> f32,
+    time_to_x: impl Fn(u64) -> f32,
+) {
+    let notional = |liquidation: &Liquidation| match market_type {
+        MarketKind::InversePerps => liquidation.qty,
+        _ => liquidation.qty * liquidation.price,
+    };
+
+    let visible = liquidations
+        .iter()
+        .filter(|liquidation| (earliest..=latest).contains(&liquidation.time))
+        .collect::<Vec<_>>();
+
+    let max_notional = visible
+        .iter()
+        .map(|liquidation| notional(liquidation))
+        .fold(0.0, f32::max);
+
+    if max_notional <= 0.0 {
+        return;
+    }
+
+    let scale_factor = size_scale as f32 / 100.0;
+
+    for liquidation in visible {
+        let color = if liquidation.is_sell {
+            palette.danger.strong.color
+        } else {
+            palette.success.strong.color
+        };
+
+        let radius = 2.0
+            + (notional(liquidation) / max_notional).sqrt()
+                * (MAX_LIQUIDATION_RADIUS - 2.0)
+                * scale_factor;
+
+        let circle = Path::circle(
+            Point::new(time_to_x(liquidation.time), price_to_y(liquidation.price)),
+            radius,
+        );
+
+        frame.fill(&circle, color.scale_alpha(0.3));
+        frame.stroke(
+            &circle,
+            Stroke::with_color(
+                Stroke {
+                    width: 1.5,
+                    ..Default::default()
+                },
+                color,
+            ),
+        );
+    }
+}
+
+fn distance_to_segment(point: Point, a: Point, b: Point) -> f32 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_squared = dx * dx + dy * dy;
+
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((point.x - a.x) * dx + (point.y - a.y) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+
+    point.distance(Point::new(a.x + t * dx, a.y + t * dy))
+}
@@ -1,6 +1,7 @@
-use super::{Chart, Interaction, Message, PlotConstants, ViewState, scale::linear::PriceInfoLabel};
+use super::{
+    Chart, Interaction, Message, PlotConstants, ViewState, profiler, scale::linear::PriceInfoLabel,
+};
 use crate::{
-    chart::TEXT_SIZE,
     modal::pane::settings::study::{self, Study},
     style,
 };
@@ -13,13 +14,13 @@ use data::{
     chart::{
         Basis, ViewConfig,
         heatmap::{
-            CLEANUP_THRESHOLD, Config, HeatmapDataPoint, HeatmapStudy, HistoricalDepth,
-            ProfileKind, QtyScale,
+            CLEANUP_THRESHOLD, COMPRESSED_PRICE_TICKS, Config, DepthPalette, HeatmapDataPoint,
+            HeatmapStudy, HistoricalDepth, ProfileKind, QtyScale, snapshot::Snapshot,
         },
         indicator::HeatmapIndicator,
     },
 };
-use exchange::{TickerInfo, Trade, adapter::MarketKind, depth::Depth};
+use exchange::{Liquidation, TickerInfo, Trade, adapter::MarketKind, depth::Depth};
 
 use iced::widget::canvas::{self, Event, Geometry, Path};
 use iced::{
@@ -28,7 +29,10 @@ use iced::{
 };
 
 use ordered_float::OrderedFloat;
-use std::{collections::HashMap, time::Instant};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    time::Instant,
+};
 
 const MIN_SCALING: f32 = 0.6;
 const MAX_SCALING: f32 = 1.2;
@@ -47,6 +51,9 @@ const TOOLTIP_PADDING: f32 = 12.0;
 
 const MAX_CIRCLE_RADIUS: f32 = 16.0;
 
+/// Columns the imbalance ratio is averaged over
+const IMBALANCE_WINDOW: usize = 20;
+
 impl Chart for HeatmapChart {
     type IndicatorType = HeatmapIndicator;
 
@@ -133,6 +140,7 @@ impl PlotConstants for HeatmapChart {
 
 enum IndicatorData {
     Volume,
+    Imbalance,
 }
 
 pub struct HeatmapChart {
@@ -141,10 +149,17 @@ pub struct HeatmapChart {
     indicators: HashMap<HeatmapIndicator, IndicatorData>,
     pause_buffer: Vec<(u64, Box<[Trade]>, Depth)>,
     heatmap: HistoricalDepth,
+    liquidations: Vec<Liquidation>,
+    /// Micro price at the last depth update of each column
+    micro_prices: BTreeMap<u64, f32>,
+    /// Order book imbalance at the last depth update of each column
+    imbalances: BTreeMap<u64, f32>,
     visual_config: Config,
     study_configurator: study::Configurator<HeatmapStudy>,
     last_tick: Instant,
     pub studies: Vec<HeatmapStudy>,
+    /// File name of the snapshot shown, viewer charts don't receive live data
+    snapshot_source: Option<String>,
 }
 
 impl HeatmapChart {
@@ -174,6 +189,7 @@ impl HeatmapChart {
                     .map(|&indicator| {
                         let data = match indicator {
                             HeatmapIndicator::Volume => IndicatorData::Volume,
+                            HeatmapIndicator::Imbalance => IndicatorData::Imbalance,
                         };
                         (indicator, data)
                     })
@@ -186,13 +202,81 @@ impl HeatmapChart {
                 basis,
             ),
             trades: TimeSeries::<HeatmapDataPoint>::new(basis, tick_size),
+            liquidations: vec![],
+            micro_prices: BTreeMap::new(),
+            imbalances: BTreeMap::new(),
             visual_config: config.unwrap_or_default(),
             study_configurator: study::Configurator::new(),
             studies,
             last_tick: Instant::now(),
+            snapshot_source: None,
         }
     }
 
+    pub fn from_snapshot(
+        layout: ViewConfig,
+        snapshot: &Snapshot,
+        file_name: String,
+        enabled_indicators: &[HeatmapIndicator],
+        config: Option<Config>,
+        studies: Vec<HeatmapStudy>,
+    ) -> Self {
+        let mut chart = Self::new(
+            layout,
+            snapshot.basis,
+            snapshot.tick_size,
+            enabled_indicators,
+            Some(snapshot.ticker_info),
+            config,
+            studies,
+        );
+
+        for (price, runs) in snapshot.depth() {
+            chart.heatmap.insert_runs(price, runs);
+        }
+        chart.trades.datapoints.extend(snapshot.trades());
+
+        if let Some((_, latest)) = snapshot.time_range() {
+            chart.chart.latest_x = latest;
+        }
+        if let Some(datapoint) = chart.trades.datapoints.values().last() {
+            let tick_size = chart.chart.tick_size;
+            chart.chart.base_price_y = (datapoint.last_price() / tick_size).round() * tick_size;
+            chart.chart.last_price = Some(PriceInfoLabel::Neutral(chart.chart.base_price_y));
+        }
+
+        chart.snapshot_source = Some(file_name);
+        chart
+    }
+
+    /// Captures the depth history and trades of the visible time range
+    pub fn snapshot(&self) -> Option<Snapshot> {
+        let ticker_info = self.chart.ticker_info?;
+        let (earliest, latest) = self.visible_timerange();
+
+        Some(Snapshot::new(
+            ticker_info,
+            self.chart.basis,
+            self.chart.tick_size,
+            self.heatmap.runs_in_range(earliest, latest),
+            self.trades
+                .datapoints
+                .range(earliest..=latest)
+                .map(|(time, dp)| (*time, dp)),
+        ))
+    }
+
+    /// Time range covered by the trades received so far
+    pub fn timerange(&self) -> Option<(u64, u64)> {
+        let earliest = self.trades.datapoints.keys().next()?;
+        let latest = self.trades.datapoints.keys().next_back()?;
+        Some((*earliest, *latest))
+    }
+
+    pub fn snapshot_source(&self) -> Option<&str> {
+        self.snapshot_source.as_deref()
+    }
+
     pub fn insert_datapoint(
         &mut self,
         trades_buffer: &[Trade],
@@ -228,6 +312,10 @@ impl HeatmapChart {
         self.process_datapoint(trades_buffer, depth_update_t, depth);
     }
 
+    pub fn insert_liquidations(&mut self, liquidations: &[Liquidation]) {
+        super::push_liquidations(&mut self.liquidations, liquidations);
+    }
+
     fn cleanup_old_data(&mut self) {
         if self.trades.datapoints.len() > CLEANUP_THRESHOLD {
             let keys_to_remove = self
@@ -244,6 +332,10 @@ impl HeatmapChart {
 
             if let Some(oldest_time) = self.trades.datapoints.keys().next().copied() {
                 self.heatmap.cleanup_old_price_levels(oldest_time);
+                self.liquidations
+                    .retain(|liquidation| liquidation.time >= oldest_time);
+                self.micro_prices = self.micro_prices.split_off(&oldest_time);
+                self.imbalances = self.imbalances.split_off(&oldest_time);
             }
         }
     }
@@ -253,7 +345,7 @@ impl HeatmapChart {
 
         let aggregate_time: u64 = match chart.basis {
             Basis::Time(interval) => interval.into(),
-            Basis::Tick(_) => todo!(),
+            Basis::Tick(_) | Basis::Range(_) => todo!(),
         };
 
         let rounded_depth_update = (depth_update / aggregate_time) * aggregate_time;
@@ -276,6 +368,13 @@ impl HeatmapChart {
         self.heatmap
             .insert_latest_depth(depth, rounded_depth_update);
 
+        if let Some(micro_price) = depth.micro_price() {
+            self.micro_prices.insert(rounded_depth_update, micro_price);
+        }
+        if let Some(imbalance) = depth.imbalance(self.visual_config.imbalance_range) {
+            self.imbalances.insert(rounded_depth_update, imbalance);
+        }
+
         {
             let mid_price = depth.mid_price().unwrap_or(chart.base_price_y);
             chart.base_price_y = (mid_price / (chart.tick_size)).round() * (chart.tick_size);
@@ -347,7 +446,7 @@ impl HeatmapChart {
     pub fn basis_interval(&self) -> Option<u64> {
         match self.chart.basis {
             Basis::Time(interval) => Some(interval.into()),
-            Basis::Tick(_) => None,
+            Basis::Tick(_) | Basis::Range(_) => None,
         }
     }
 
@@ -387,6 +486,7 @@ impl HeatmapChart {
             std::collections::hash_map::Entry::Vacant(entry) => {
                 let data = match indicator {
                     HeatmapIndicator::Volume => IndicatorData::Volume,
+                    HeatmapIndicator::Imbalance => IndicatorData::Imbalance,
                 };
                 entry.insert(data);
             }
@@ -394,6 +494,10 @@ impl HeatmapChart {
     }
 
     pub fn invalidate(&mut self, now: Option<Instant>) -> Option<super::Action> {
+        if self.chart.follow_latest {
+            self.chart.translation.x = self.autoscaled_coords().x;
+        }
+
         let chart = &mut self.chart;
 
         if chart.layout.autoscale.is_some() {
@@ -480,8 +584,11 @@ impl canvas::Program<Message> for HeatmapChart {
         let palette = theme.extended_palette();
 
         let volume_indicator = self.indicators.contains_key(&HeatmapIndicator::Volume);
+        let imbalance_indicator = self.indicators.contains_key(&HeatmapIndicator::Imbalance);
 
         let heatmap = chart.cache.main.draw(renderer, bounds_size, |frame| {
+            let _timer = profiler::time("Heatmap");
+
             frame.translate(center);
             frame.scale(chart.scaling);
             frame.translate(chart.translation);
@@ -502,7 +609,18 @@ impl canvas::Program<Message> for HeatmapChart {
             let (max_aggr_volume, max_trade_qty) =
                 (qty_scales.max_aggr_volume, qty_scales.max_trade_qty);
 
-            if let Some(merge_strat) = self.visual_config().coalescing {
+            if self.visual_config.depth_delta {
+                draw_depth_deltas(
+                    frame,
+                    chart,
+                    &self.heatmap,
+                    (earliest, latest),
+                    (highest, lowest),
+                    market_type,
+                    self.visual_config.order_size_filter,
+                    palette,
+                );
+            } else if let Some(merge_strat) = self.visual_config().coalescing {
                 let coalesced_visual_runs = self.heatmap.coalesced_runs(
                     earliest,
                     latest,
@@ -529,12 +647,22 @@ impl canvas::Program<Message> for HeatmapChart {
                     let width = end_x - start_x;
 
                     if width > 0.001 {
-                        let color_alpha = (visual_run.qty() / max_depth_qty).min(1.0);
+                        let intensity = self.visual_config.depth_intensity(
+                            price_of_run.into_inner(),
+                            visual_run.qty(),
+                            max_depth_qty,
+                            market_type,
+                        );
 
                         frame.fill_rectangle(
                             Point::new(start_x, y_position - (cell_height / 2.0)),
                             Size::new(width, cell_height),
-                            depth_color(palette, visual_run.is_bid, color_alpha),
+                            level_color(
+                                palette,
+                                self.visual_config.palette,
+                                visual_run.is_bid,
+                                intensity,
+                            ),
                         );
                     }
                 }
@@ -559,17 +687,75 @@ impl canvas::Program<Message> for HeatmapChart {
 
                                 let width = end_x - start_x;
 
-                                let color_alpha = (run.qty() / max_depth_qty).min(1.0);
+                                let intensity = self.visual_config.depth_intensity(
+                                    price.0,
+                                    run.qty(),
+                                    max_depth_qty,
+                                    market_type,
+                                );
 
                                 frame.fill_rectangle(
                                     Point::new(start_x, y_position - (cell_height / 2.0)),
                                     Size::new(width, cell_height),
-                                    depth_color(palette, run.is_bid, color_alpha),
+                                    level_color(
+                                        palette,
+                                        self.visual_config.palette,
+                                        run.is_bid,
+                                        intensity,
+                                    ),
                                 );
                             });
                     });
             }
 
+            let bin_height = cell_height * f32::from(COMPRESSED_PRICE_TICKS);
+
+            // the deltas only span the full resolution history, compressed runs would mix in
+            self.heatmap
+                .iter_compressed(earliest, latest, highest, lowest)
+                .filter(|_| !self.visual_config.depth_delta)
+                .filter(|(price, run)| {
+                    let order_size = match market_type {
+                        MarketKind::InversePerps => run.qty(),
+                        _ => ***price * run.qty(),
+                    };
+                    order_size > self.visual_config.order_size_filter
+                })
+                .for_each(|(price, run)| {
+                    let y_position = chart.price_to_y(price.into_inner());
+
+                    let start_x = chart.interval_to_x(run.start_time.max(earliest));
+                    let end_x = chart.interval_to_x(run.until_time.min(latest)).min(0.0);
+
+                    let width = end_x - start_x;
+
+                    if width > 0.001 {
+                        let intensity = self.visual_config.depth_intensity(
+                            price.into_inner(),
+                            run.qty(),
+                            max_depth_qty,
+                            market_type,
+                        );
+
+                        frame.fill_rectangle(
+                            Point::new(start_x, y_position - (bin_height / 2.0)),
+                            Size::new(width, bin_height),
+                            level_color(palette, self.visual_config.palette, run.is_bid, intensity),
+                        );
+                    }
+                });
+
+            if let Some(smoothing) = self.visual_config.micro_price {
+                draw_micro_price(
+                    frame,
+                    chart,
+                    &self.micro_prices,
+                    (earliest, latest),
+                    smoothing,
+                    palette,
+                );
+            }
+
             if let Some(latest_timestamp) = self.trades.latest_timestamp() {
                 let max_qty = self
                     .heatmap
@@ -604,7 +790,7 @@ impl canvas::Program<Message> for HeatmapChart {
                         position: text_position,
                         size: iced::Pixels(text_size),
                         color: palette.background.base.text,
-                        font: style::AZERET_MONO,
+                        font: style::chart_font(),
                         ..canvas::Text::default()
                     });
                 }
@@ -673,6 +859,43 @@ impl canvas::Program<Message> for HeatmapChart {
                     }
                 });
 
+            if let Some(liquidation_scale) = self.visual_config.liquidation_scale {
+                super::draw_liquidations(
+                    frame,
+                    palette,
+                    &self.liquidations,
+                    (earliest, latest),
+                    liquidation_scale,
+                    market_type,
+                    |price| chart.price_to_y(price),
+                    |time| chart.interval_to_x(time),
+                );
+            }
+
+            if imbalance_indicator {
+                let area_height = (bounds.height / chart.scaling) * 0.08;
+                // stacked above the volume bars when both are shown
+                let volume_height = if volume_indicator {
+                    (bounds.height / chart.scaling) * 0.1
+                } else {
+                    0.0
+                };
+
+                draw_imbalance_strip(
+                    frame,
+                    chart,
+                    &self.imbalances,
+                    (earliest, latest),
+                    Rectangle {
+                        x: region.x,
+                        y: (region.y + region.height) - volume_height - area_height,
+                        width: region.width,
+                        height: area_height,
+                    },
+                    palette,
+                );
+            }
+
             if volume_indicator && max_aggr_volume > 0.0 {
                 let text_size = 9.0 / chart.scaling;
                 let text_content = abbr_large_numbers(max_aggr_volume);
@@ -688,7 +911,7 @@ impl canvas::Program<Message> for HeatmapChart {
                     position: text_position,
                     size: text_size.into(),
                     color: palette.background.base.text,
-                    font: style::AZERET_MONO,
+                    font: style::chart_font(),
                     ..canvas::Text::default()
                 });
             }
@@ -727,6 +950,10 @@ impl canvas::Program<Message> for HeatmapChart {
                 );
             }
 
+            chart.draw_day_levels(frame, palette, region);
+            chart.draw_drawings(frame, palette, region);
+            chart.draw_alert_lines(frame, palette, region);
+
             let is_paused = chart.translation.x * chart.scaling > chart.bounds.width / 2.0;
             if is_paused {
                 let bar_width = 8.0 / chart.scaling;
@@ -756,6 +983,12 @@ impl canvas::Program<Message> for HeatmapChart {
             }
         });
 
+        if chart.is_exporting() {
+            return std::iter::once(heatmap)
+                .chain(chart.draw_watermark(renderer, palette, bounds_size))
+                .collect();
+        }
+
         if !self.is_empty() {
             let crosshair = chart.cache.crosshair.draw(renderer, bounds_size, |frame| {
                 if let Some(cursor_position) = cursor.position_in(bounds) {
@@ -764,7 +997,7 @@ impl canvas::Program<Message> for HeatmapChart {
 
                     let aggr_time: u64 = match chart.basis {
                         Basis::Time(interval) => interval.into(),
-                        Basis::Tick(_) => return,
+                        Basis::Tick(_) | Basis::Range(_) => return,
                     };
                     let tick_size = chart.tick_size;
 
@@ -856,9 +1089,9 @@ impl canvas::Program<Message> for HeatmapChart {
                                 frame.fill_text(canvas::Text {
                                     content: text_content,
                                     position: Point::new(text_pos_x, text_pos_y),
-                                    size: iced::Pixels(TEXT_SIZE - 2.0),
+                                    size: iced::Pixels(style::chart_text_size() - 2.0),
                                     color,
-                                    font: style::AZERET_MONO,
+                                    font: style::chart_font(),
                                     align_y: Alignment::Center.into(),
                                     align_x: Alignment::Center.into(),
                                     ..canvas::Text::default()
@@ -884,7 +1117,23 @@ impl canvas::Program<Message> for HeatmapChart {
         match interaction {
             Interaction::Panning { .. } => mouse::Interaction::Grabbing,
             Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
+            Interaction::DraggingAlert { .. } => mouse::Interaction::ResizingVertically,
+            Interaction::PlacingDrawing { .. } => mouse::Interaction::Crosshair,
+            Interaction::MovingDrawing { .. } => mouse::Interaction::Grabbing,
             Interaction::None => {
+                if cursor
+                    .position_in(bounds)
+                    .is_some_and(|position| self.chart.alert_line_at(position.y).is_some())
+                {
+                    return mouse::Interaction::ResizingVertically;
+                }
+                if self.chart.drawing_tool.is_none()
+                    && cursor
+                        .position_in(bounds)
+                        .is_some_and(|position| self.chart.drawing_at(position).is_some())
+                {
+                    return mouse::Interaction::Grab;
+                }
                 if cursor.is_over(bounds) {
                     return mouse::Interaction::Crosshair;
                 }
@@ -894,6 +1143,181 @@ impl canvas::Program<Message> for HeatmapChart {
     }
 }
 
+/// Each level's net change in resting size per column, adds in green and pulls in red
+fn draw_depth_deltas(
+    frame: &mut canvas::Frame,
+    chart: &ViewState,
+    heatmap: &HistoricalDepth,
+    (earliest, latest): (u64, u64),
+    (highest, lowest): (f32, f32),
+    market_type: MarketKind,
+    order_size_filter: f32,
+    palette: &Extended,
+) {
+    let Basis::Time(interval) = chart.basis else {
+        return;
+    };
+    let column_span = u64::from(interval);
+
+    let deltas = heatmap
+        .deltas_in_range(earliest, latest, highest, lowest)
+        .filter(|(_, price, delta)| {
+            let order_size = match market_type {
+                MarketKind::InversePerps => delta.abs(),
+                _ => price * delta.abs(),
+            };
+            order_size > order_size_filter
+        })
+        .collect::<Vec<_>>();
+
+    let max_delta = deltas
+        .iter()
+        .map(|(_, _, delta)| delta.abs())
+        .fold(0.0f32, f32::max);
+
+    if max_delta <= 0.0 {
+        return;
+    }
+
+    for (column, price, delta) in deltas {
+        let start_x = chart.interval_to_x(column.max(earliest));
+        let end_x = chart
+            .interval_to_x((column + column_span).min(latest))
+            .min(0.0);
+
+        let width = end_x - start_x;
+
+        if width > 0.001 {
+            frame.fill_rectangle(
+                Point::new(start_x, chart.price_to_y(price) - (chart.cell_height / 2.0)),
+                Size::new(width, chart.cell_height),
+                depth_color(palette, delta > 0.0, (delta.abs() / max_delta).min(1.0)),
+            );
+        }
+    }
+}
+
+/// Micro price of each column as an exponential average over `smoothing` columns, carried
+/// in from the columns before the visible range so the line doesn't restart at its edge
+fn draw_micro_price(
+    frame: &mut canvas::Frame,
+    chart: &ViewState,
+    micro_prices: &BTreeMap<u64, f32>,
+    (earliest, latest): (u64, u64),
+    smoothing: u16,
+    palette: &Extended,
+) {
+    let alpha = 2.0 / (f32::from(smoothing) + 1.0);
+    let mut smoothed: Option<f32> = None;
+
+    let line = Path::new(|builder| {
+        let mut is_started = false;
+
+        for (time, price) in micro_prices.range(..=latest) {
+            let value = smoothed.map_or(*price, |prev| alpha * price + (1.0 - alpha) * prev);
+            smoothed = Some(value);
+
+            if *time < earliest {
+                continue;
+            }
+
+            let point = Point::new(chart.interval_to_x(*time), chart.price_to_y(value));
+            if is_started {
+                builder.line_to(point);
+            } else {
+                builder.move_to(point);
+                is_started = true;
+            }
+        }
+    });
+
+    frame.stroke(
+        &line,
+        canvas::Stroke::with_color(
+            canvas::Stroke {
+                width: 1.5,
+                ..Default::default()
+            },
+            palette.background.base.text.scale_alpha(0.8),
+        ),
+    );
+}
+
+/// Oscillator of the imbalance averaged over the last [`IMBALANCE_WINDOW`] columns, bars above
+/// the middle when bids outweigh asks
+fn draw_imbalance_strip(
+    frame: &mut canvas::Frame,
+    chart: &ViewState,
+    imbalances: &BTreeMap<u64, f32>,
+    (earliest, latest): (u64, u64),
+    area: Rectangle,
+    palette: &Extended,
+) {
+    let mid_y = area.y + area.height / 2.0;
+    let bar_width = (chart.cell_width / 2.0) * 0.9;
+
+    frame.fill_rectangle(
+        area.position(),
+        area.size(),
+        palette.background.weakest.color.scale_alpha(0.4),
+    );
+    frame.fill_rectangle(
+        Point::new(area.x, mid_y),
+        Size::new(area.width, 1.0 / chart.scaling),
+        palette.background.strong.color,
+    );
+
+    let mut window = VecDeque::with_capacity(IMBALANCE_WINDOW);
+    let mut sum = 0.0;
+
+    for (time, imbalance) in imbalances.range(..=latest) {
+        window.push_back(*imbalance);
+        sum += imbalance;
+        if window.len() > IMBALANCE_WINDOW {
+            sum -= window.pop_front().unwrap_or_default();
+        }
+
+        if *time < earliest {
+            continue;
+        }
+
+        let average = sum / window.len() as f32;
+        let bar_height = average.abs() * (area.height / 2.0);
+        let color = if average >= 0.0 {
+            palette.success.base.color
+        } else {
+            palette.danger.base.color
+        };
+
+        frame.fill_rectangle(
+            Point::new(
+                chart.interval_to_x(*time) - bar_width / 2.0,
+                if average >= 0.0 {
+                    mid_y - bar_height
+                } else {
+                    mid_y
+                },
+            ),
+            Size::new(bar_width, bar_height),
+            color.scale_alpha(0.8),
+        );
+    }
+}
+
+/// Resting order colored along the configured gradient, the faintest still visible over the
+/// background
+fn level_color(
+    palette: &Extended,
+    depth_palette: DepthPalette,
+    is_bid: bool,
+    intensity: f32,
+) -> Color {
+    match depth_palette.gradient_color(intensity) {
+        Some(color) => color.scale_alpha(0.25 + 0.75 * intensity),
+        None => depth_color(palette, is_bid, intensity),
+    }
+}
+
 fn depth_color(palette: &Extended, is_bid: bool, alpha: f32) -> Color {
     if is_bid {
         palette.success.strong.color.scale_alpha(alpha)
@@ -923,7 +1347,7 @@ fn draw_volume_profile(
         ProfileKind::FixedWindow(datapoints) => {
             let basis_interval: u64 = match chart.basis {
                 Basis::Time(interval) => interval.into(),
-                Basis::Tick(_) => return,
+                Basis::Tick(_) | Basis::Range(_) => return,
             };
 
             let latest = chart
@@ -1010,7 +1434,7 @@ fn draw_volume_profile(
             position: text_position,
             size: iced::Pixels(text_size),
             color: palette.background.base.text,
-            font: style::AZERET_MONO,
+            font: style::chart_font(),
             ..canvas::Text::default()
         });
     }
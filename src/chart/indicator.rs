@@ -1,19 +1,106 @@
+pub mod ema;
+pub mod line;
 pub mod open_interest;
+pub mod relative_volume;
+pub mod rsi;
 pub mod volume;
 
+use std::collections::BTreeMap;
+
 use iced::{
-    Event, Rectangle, Renderer, Theme, mouse,
+    Element, Event, Rectangle, Renderer, Theme, mouse,
     widget::canvas::{self, Cache, Geometry},
 };
 
 use super::scale::linear;
-use crate::chart::{
-    TEXT_SIZE,
-    scale::{AxisLabel, LabelContent, calc_label_rect},
-};
+use crate::chart::scale::{AxisLabel, LabelContent, calc_label_rect};
+use crate::style;
+use data::aggr::{ticks::TickAggr, time::TimeSeries};
+use data::chart::{PlotData, indicator::KlineIndicator, kline::KlineDataPoint};
 use data::util::{abbr_large_numbers, round_to_tick};
+use exchange::{Kline, OpenInterest as OIData};
+
+use super::{Caches, Interaction, Message, ViewState};
+
+/// Bars of the chart an indicator derives its data from
+#[derive(Clone, Copy)]
+pub enum Bars<'a> {
+    Time(&'a TimeSeries<KlineDataPoint>),
+    Tick(&'a TickAggr),
+}
+
+impl<'a> From<&'a PlotData<KlineDataPoint>> for Bars<'a> {
+    fn from(data: &'a PlotData<KlineDataPoint>) -> Self {
+        match data {
+            PlotData::TimeBased(timeseries) => Bars::Time(timeseries),
+            PlotData::TickBased(tick_aggr) => Bars::Tick(tick_aggr),
+        }
+    }
+}
+
+/// A kline indicator that keeps what it derives from the bars and draws it in its own panel.
+///
+/// `KlineChart` only tells it how the bars changed, so a new indicator needs an implementation
+/// of this and an entry in [`register`].
+pub trait Indicator {
+    /// Derives everything again from all of the bars, e.g. once the chart got rebuilt
+    fn rebuild(&mut self, bars: Bars<'_>);
+
+    /// Fetched klines were merged into the time based bars, they can land anywhere in the
+    /// history so everything is derived again unless the indicator knows better
+    fn insert_klines(&mut self, timeseries: &TimeSeries<KlineDataPoint>, _klines: &[Kline]) {
+        self.rebuild(Bars::Time(timeseries));
+    }
+
+    /// The live kline changed the latest time based bar, only it needs to be brought up to date
+    fn update_latest(&mut self, timeseries: &TimeSeries<KlineDataPoint>, kline: &Kline);
+
+    /// Tick based bars from index `from` on took in new trades
+    fn update_ticks(&mut self, _tick_aggr: &TickAggr, _from: usize) {}
 
-use super::{Interaction, Message};
+    fn insert_open_interest(&mut self, _points: &[OIData]) {}
+
+    /// Open interest it keeps, for the anomaly detectors and to know it has to be fetched
+    fn open_interest(&self) -> Option<&BTreeMap<u64, f32>> {
+        None
+    }
+
+    /// How far before the visible bars it needs bars loaded, e.g. for a baseline
+    fn lookback(&self, _interval: u64) -> u64 {
+        0
+    }
+
+    fn caches(&self) -> &Caches;
+
+    fn element<'a>(
+        &'a self,
+        chart_state: &'a ViewState,
+        earliest: u64,
+        latest: u64,
+    ) -> Element<'a, Message>;
+}
+
+/// Builds the indicator over the chart's current bars
+pub fn register(indicator: KlineIndicator, bars: Bars<'_>) -> Box<dyn Indicator> {
+    let mut plugin: Box<dyn Indicator> = match indicator {
+        KlineIndicator::Volume => Box::new(volume::Volume::default()),
+        KlineIndicator::OpenInterest => Box::new(open_interest::OpenInterest::default()),
+        KlineIndicator::RelativeVolume => Box::new(relative_volume::RelativeVolume::default()),
+        KlineIndicator::Rsi => Box::new(rsi::Rsi::default()),
+        KlineIndicator::Ema => Box::new(ema::Ema::default()),
+    };
+    plugin.rebuild(bars);
+    plugin
+}
+
+/// Closes of the time based bars, keyed by their open time
+fn closes(timeseries: &TimeSeries<KlineDataPoint>) -> BTreeMap<u64, f32> {
+    timeseries
+        .datapoints
+        .iter()
+        .map(|(time, dp)| (*time, dp.kline.close))
+        .collect()
+}
 
 pub struct IndicatorLabel<'a> {
     pub label_cache: &'a Cache,
@@ -55,7 +142,7 @@ impl canvas::Program<Message> for IndicatorLabel<'_> {
                 bounds,
                 self.min,
                 self.max,
-                TEXT_SIZE,
+                style::chart_text_size(),
                 palette.background.base.text,
                 None,
             );
@@ -77,13 +164,13 @@ impl canvas::Program<Message> for IndicatorLabel<'_> {
                     content: abbr_large_numbers(rounded_value),
                     background_color: Some(palette.secondary.base.color),
                     text_color: palette.secondary.base.text,
-                    text_size: TEXT_SIZE,
+                    text_size: style::chart_text_size(),
                 };
 
                 let y_position = bounds.height - ((rounded_value - lowest) / range * bounds.height);
 
                 all_labels.push(AxisLabel::Y {
-                    bounds: calc_label_rect(y_position, 1, TEXT_SIZE, bounds),
+                    bounds: calc_label_rect(y_position, 1, style::chart_text_size(), bounds),
                     value_label: label,
                     timer_label: None,
                 });
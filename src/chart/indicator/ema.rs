@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use data::aggr::time::TimeSeries;
+use data::chart::kline::KlineDataPoint;
+use data::metrics;
+use exchange::Kline;
+use iced::Element;
+
+use super::{Bars, Indicator, line};
+use crate::chart::{Caches, Message, ViewState};
+
+pub struct Ema {
+    period: usize,
+    cache: Caches,
+    series: BTreeMap<u64, f32>,
+}
+
+impl Default for Ema {
+    fn default() -> Self {
+        Ema {
+            period: 21,
+            cache: Caches::default(),
+            series: BTreeMap::new(),
+        }
+    }
+}
+
+impl Indicator for Ema {
+    fn rebuild(&mut self, bars: Bars<'_>) {
+        self.series = match bars {
+            Bars::Time(timeseries) => metrics::ema(&super::closes(timeseries), self.period),
+            Bars::Tick(_) => BTreeMap::new(),
+        };
+    }
+
+    fn update_latest(&mut self, timeseries: &TimeSeries<KlineDataPoint>, kline: &Kline) {
+        let prev = self
+            .series
+            .range(..kline.time)
+            .next_back()
+            .map(|(_, value)| *value);
+
+        // the seed needs the first `period` closes, which only the full series has
+        let Some(prev) = prev else {
+            self.rebuild(Bars::Time(timeseries));
+            return;
+        };
+
+        let alpha = 2.0 / (self.period as f32 + 1.0);
+        self.series
+            .insert(kline.time, alpha * kline.close + (1.0 - alpha) * prev);
+    }
+
+    fn caches(&self) -> &Caches {
+        &self.cache
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart_state: &'a ViewState,
+        earliest: u64,
+        latest: u64,
+    ) -> Element<'a, Message> {
+        line::indicator_elem(
+            chart_state,
+            &self.cache,
+            &self.series,
+            earliest,
+            latest,
+            line::Style {
+                name: "EMA",
+                value_range: None,
+                levels: &[],
+            },
+        )
+    }
+}
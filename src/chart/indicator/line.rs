@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+
+use iced::widget::canvas::{self, Cache, Event, Geometry, Path, Stroke};
+use iced::widget::{Canvas, center, container, row, text, vertical_rule};
+use iced::{Element, Length, Point, Rectangle, Renderer, Size, Theme, Vector, mouse};
+
+use crate::chart::{Basis, Caches, Interaction, Message, ViewState, profiler};
+use crate::style::{self, dashed_line};
+
+/// How a plugin's series is laid out in its panel
+#[derive(Clone, Copy)]
+pub struct Style {
+    pub name: &'static str,
+    pub value_range: Option<(f32, f32)>,
+    pub levels: &'static [f32],
+}
+
+pub fn indicator_elem<'a>(
+    chart_state: &'a ViewState,
+    cache: &'a Caches,
+    series: &'a BTreeMap<u64, f32>,
+    earliest: u64,
+    latest: u64,
+    line_style: Style,
+) -> Element<'a, Message> {
+    if let Basis::Tick(_) | Basis::Range(_) = chart_state.basis {
+        return center(text(format!(
+            "{} is not available for tick charts.",
+            line_style.name
+        )))
+        .into();
+    }
+
+    if latest < earliest {
+        return row![].into();
+    }
+
+    let (min_value, max_value) = line_style.value_range.unwrap_or_else(|| {
+        let (min, max) = series
+            .range(earliest..=latest)
+            .fold((f32::MAX, f32::MIN), |(min, max), (_, value)| {
+                (min.min(*value), max.max(*value))
+            });
+
+        let padding = (max - min) * 0.05;
+        (min - padding, max + padding)
+    });
+
+    if min_value >= max_value {
+        return row![].into();
+    }
+
+    let indi_chart = Canvas::new(LineSeries {
+        indicator_cache: &cache.main,
+        crosshair_cache: &cache.crosshair,
+        chart_state,
+        series,
+        min_value,
+        max_value,
+        style: line_style,
+    })
+    .height(Length::Fill)
+    .width(Length::Fill);
+
+    let indi_labels = Canvas::new(super::IndicatorLabel {
+        label_cache: &cache.y_labels,
+        max: max_value,
+        min: min_value,
+        chart_bounds: chart_state.bounds,
+    })
+    .height(Length::Fill)
+    .width(chart_state.y_labels_width());
+
+    row![
+        indi_chart,
+        vertical_rule(1).style(style::split_ruler),
+        container(indi_labels),
+    ]
+    .into()
+}
+
+pub struct LineSeries<'a> {
+    pub indicator_cache: &'a Cache,
+    pub crosshair_cache: &'a Cache,
+    pub chart_state: &'a ViewState,
+    pub series: &'a BTreeMap<u64, f32>,
+    pub min_value: f32,
+    pub max_value: f32,
+    pub style: Style,
+}
+
+impl LineSeries<'_> {
+    fn visible_region(&self, size: Size) -> Rectangle {
+        let width = size.width / self.chart_state.scaling;
+        let height = size.height / self.chart_state.scaling;
+
+        Rectangle {
+            x: -self.chart_state.translation.x - width / 2.0,
+            y: 0.0,
+            width,
+            height,
+        }
+    }
+
+    fn value_to_y(&self, value: f32, height: f32) -> f32 {
+        height - ((value - self.min_value) / (self.max_value - self.min_value)) * height
+    }
+}
+
+impl canvas::Program<Message> for LineSeries<'_> {
+    type State = Interaction;
+
+    fn update(
+        &self,
+        interaction: &mut Interaction,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let message = match *interaction {
+                    Interaction::None => {
+                        if cursor.is_over(bounds) {
+                            Some(Message::CrosshairMoved)
+                        } else {
+                            None
+                        }
+                    }
+                    _ => None,
+                };
+
+                let action =
+                    message.map_or(canvas::Action::request_redraw(), canvas::Action::publish);
+
+                Some(match interaction {
+                    Interaction::None => action,
+                    _ => action.and_capture(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let chart_state = self.chart_state;
+
+        if chart_state.bounds.width == 0.0 {
+            return vec![];
+        }
+
+        let timeframe: u64 = match chart_state.basis {
+            Basis::Time(interval) => interval.into(),
+            Basis::Tick(_) | Basis::Range(_) => return vec![],
+        };
+
+        let center = Vector::new(bounds.width / 2.0, bounds.height / 2.0);
+        let palette = theme.extended_palette();
+
+        let indicator = self.indicator_cache.draw(renderer, bounds.size(), |frame| {
+            let _timer = profiler::time(self.style.name);
+
+            frame.translate(center);
+            frame.scale(chart_state.scaling);
+            frame.translate(Vector::new(
+                chart_state.translation.x,
+                (-bounds.height / chart_state.scaling) / 2.0,
+            ));
+
+            let region = self.visible_region(frame.size());
+            let (earliest, latest) = chart_state.interval_range(&region);
+
+            if latest < earliest {
+                return;
+            }
+
+            let height = bounds.height / chart_state.scaling;
+
+            for level in self.style.levels {
+                let y = self.value_to_y(*level, height);
+                frame.stroke(
+                    &Path::line(
+                        Point::new(region.x, y),
+                        Point::new(region.x + region.width, y),
+                    ),
+                    dashed_line(theme),
+                );
+            }
+
+            // one bar of margin on both sides so the line runs off the panel edges
+            let from = earliest.saturating_sub(timeframe);
+            let to = latest.saturating_add(timeframe);
+
+            let line = Path::new(|builder| {
+                let mut points = self.series.range(from..=to).map(|(timestamp, value)| {
+                    Point::new(
+                        chart_state.interval_to_x(*timestamp),
+                        self.value_to_y(*value, height),
+                    )
+                });
+
+                if let Some(first) = points.next() {
+                    builder.move_to(first);
+                    points.for_each(|point| builder.line_to(point));
+                }
+            });
+
+            frame.stroke(
+                &line,
+                Stroke::with_color(
+                    Stroke {
+                        width: 1.0,
+                        ..Stroke::default()
+                    },
+                    palette.secondary.strong.color,
+                ),
+            );
+        });
+
+        let crosshair = self.crosshair_cache.draw(renderer, bounds.size(), |frame| {
+            let Some(cursor_position) = cursor.position_in(chart_state.bounds) else {
+                return;
+            };
+
+            let region = self.visible_region(frame.size());
+
+            let earliest = chart_state.x_to_interval(region.x) as f64;
+            let latest = chart_state.x_to_interval(region.x + region.width) as f64;
+
+            let crosshair_ratio = f64::from(cursor_position.x / bounds.width);
+            let crosshair_millis = earliest + crosshair_ratio * (latest - earliest);
+
+            let rounded_timestamp =
+                (crosshair_millis / (timeframe as f64)).round() as u64 * timeframe;
+            let snap_ratio = ((rounded_timestamp as f64 - earliest) / (latest - earliest)) as f32;
+
+            frame.stroke(
+                &Path::line(
+                    Point::new(snap_ratio * bounds.width, 0.0),
+                    Point::new(snap_ratio * bounds.width, bounds.height),
+                ),
+                dashed_line(theme),
+            );
+
+            if let Some(value) = self.series.get(&rounded_timestamp) {
+                let tooltip_text = format!("{}: {value:.2}", self.style.name);
+
+                frame.fill_rectangle(
+                    Point::new(4.0, 0.0),
+                    Size::new(tooltip_text.len() as f32 * 8.0, 14.0),
+                    palette.background.weakest.color.scale_alpha(0.9),
+                );
+
+                frame.fill_text(canvas::Text {
+                    content: tooltip_text,
+                    position: Point::new(8.0, 2.0),
+                    size: iced::Pixels(style::chart_text_size() - 2.0),
+                    color: palette.background.base.text,
+                    font: style::chart_font(),
+                    ..canvas::Text::default()
+                });
+            }
+        });
+
+        vec![indicator, crosshair]
+    }
+
+    fn mouse_interaction(
+        &self,
+        interaction: &Interaction,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match interaction {
+            Interaction::Panning { .. } => mouse::Interaction::Grabbing,
+            Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
+            Interaction::None if cursor.is_over(bounds) => mouse::Interaction::Crosshair,
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
@@ -4,10 +4,50 @@ use iced::widget::canvas::{self, Cache, Event, Geometry, Path, Stroke};
 use iced::widget::{Canvas, center, container, row, text, vertical_rule};
 use iced::{Element, Length, Point, Rectangle, Renderer, Size, Theme, Vector, mouse};
 
-use crate::chart::{Basis, Caches, Interaction, Message, ViewState};
+use super::{Bars, Indicator};
+use crate::chart::{Basis, Caches, Interaction, Message, ViewState, profiler};
 use crate::style::{self, dashed_line};
+use data::aggr::time::TimeSeries;
+use data::chart::kline::KlineDataPoint;
 use data::util::{format_with_commas, guesstimate_ticks, round_to_tick};
-use exchange::Timeframe;
+use exchange::{Kline, OpenInterest as OIData, Timeframe};
+
+/// Open interest fetched for the chart, it comes separately from the bars
+#[derive(Default)]
+pub struct OpenInterest {
+    cache: Caches,
+    datapoints: BTreeMap<u64, f32>,
+}
+
+impl Indicator for OpenInterest {
+    fn rebuild(&mut self, _: Bars<'_>) {}
+
+    fn insert_klines(&mut self, _: &TimeSeries<KlineDataPoint>, _: &[Kline]) {}
+
+    fn update_latest(&mut self, _: &TimeSeries<KlineDataPoint>, _: &Kline) {}
+
+    fn insert_open_interest(&mut self, points: &[OIData]) {
+        self.datapoints
+            .extend(points.iter().map(|oi| (oi.time, oi.value)));
+    }
+
+    fn open_interest(&self) -> Option<&BTreeMap<u64, f32>> {
+        Some(&self.datapoints)
+    }
+
+    fn caches(&self) -> &Caches {
+        &self.cache
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart_state: &'a ViewState,
+        earliest: u64,
+        latest: u64,
+    ) -> Element<'a, Message> {
+        indicator_elem(chart_state, &self.cache, &self.datapoints, earliest, latest)
+    }
+}
 
 pub fn indicator_elem<'a>(
     chart_state: &'a ViewState,
@@ -38,7 +78,7 @@ pub fn indicator_elem<'a>(
                         })
                 }
             }
-            Basis::Tick(_) => {
+            Basis::Tick(_) | Basis::Range(_) => {
                 return center(text("WIP: Open Interest is not available for tick charts.")).into();
             }
         }
@@ -49,7 +89,7 @@ pub fn indicator_elem<'a>(
     max_value += padding;
     min_value -= padding;
 
-    let indi_chart = Canvas::new(OpenInterest {
+    let indi_chart = Canvas::new(OpenInterestPlot {
         indicator_cache: &cache.main,
         crosshair_cache: &cache.crosshair,
         chart_state,
@@ -76,7 +116,7 @@ pub fn indicator_elem<'a>(
     .into()
 }
 
-pub struct OpenInterest<'a> {
+pub struct OpenInterestPlot<'a> {
     pub indicator_cache: &'a Cache,
     pub crosshair_cache: &'a Cache,
     pub chart_state: &'a ViewState,
@@ -84,7 +124,7 @@ pub struct OpenInterest<'a> {
     pub timeseries: &'a BTreeMap<u64, f32>,
 }
 
-impl OpenInterest<'_> {
+impl OpenInterestPlot<'_> {
     fn visible_region(&self, size: Size) -> Rectangle {
         let width = size.width / self.chart_state.scaling;
         let height = size.height / self.chart_state.scaling;
@@ -98,7 +138,7 @@ impl OpenInterest<'_> {
     }
 }
 
-impl canvas::Program<Message> for OpenInterest<'_> {
+impl canvas::Program<Message> for OpenInterestPlot<'_> {
     type State = Interaction;
 
     fn update(
@@ -149,7 +189,7 @@ impl canvas::Program<Message> for OpenInterest<'_> {
 
         let timeframe: u64 = match chart_state.basis {
             Basis::Time(interval) => interval.into(),
-            Basis::Tick(_) => {
+            Basis::Tick(_) | Basis::Range(_) => {
                 // TODO: implement
                 return vec![];
             }
@@ -163,6 +203,8 @@ impl canvas::Program<Message> for OpenInterest<'_> {
         let palette = theme.extended_palette();
 
         let indicator = self.indicator_cache.draw(renderer, bounds.size(), |frame| {
+            let _timer = profiler::time("Open interest indicator");
+
             frame.translate(center);
             frame.scale(chart_state.scaling);
             frame.translate(Vector::new(
@@ -296,9 +338,9 @@ impl canvas::Program<Message> for OpenInterest<'_> {
                     let text = canvas::Text {
                         content: tooltip_text,
                         position: Point::new(8.0, 2.0),
-                        size: iced::Pixels(10.0),
+                        size: iced::Pixels(style::chart_text_size() - 2.0),
                         color: palette.background.base.text,
-                        font: style::AZERET_MONO,
+                        font: style::chart_font(),
                         ..canvas::Text::default()
                     };
                     frame.fill_text(text);
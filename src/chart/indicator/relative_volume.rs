@@ -0,0 +1,333 @@
+use std::collections::BTreeMap;
+
+use iced::widget::canvas::{self, Cache, Event, Geometry, Path};
+use iced::widget::{Canvas, center, container, row, text, vertical_rule};
+use iced::{Element, Length, Point, Rectangle, Renderer, Size, Theme, Vector, mouse};
+
+use super::{Bars, Indicator};
+use crate::chart::{Basis, Caches, Interaction, Message, ViewState, profiler};
+use crate::style::{self, dashed_line};
+use data::aggr::time::TimeSeries;
+use data::chart::kline::KlineDataPoint;
+use data::metrics;
+use exchange::{Kline, Timeframe};
+
+/// Volume of each bar against the average of the same time of day over the baseline days
+#[derive(Default)]
+pub struct RelativeVolume {
+    cache: Caches,
+    /// Keeps its own copy of the bar volumes, the baseline of a bar is looked up from them
+    volumes: BTreeMap<u64, (f32, f32)>,
+    datapoints: BTreeMap<u64, f32>,
+    interval: u64,
+}
+
+impl Indicator for RelativeVolume {
+    fn rebuild(&mut self, bars: Bars<'_>) {
+        match bars {
+            Bars::Time(timeseries) => {
+                self.interval = timeseries.interval.to_milliseconds();
+                self.volumes = timeseries.volume_data();
+                self.datapoints = metrics::relative_volume(
+                    &self.volumes,
+                    self.interval,
+                    metrics::RVOL_LOOKBACK_DAYS,
+                );
+            }
+            Bars::Tick(_) => {
+                self.volumes.clear();
+                self.datapoints.clear();
+            }
+        }
+    }
+
+    fn insert_klines(&mut self, _: &TimeSeries<KlineDataPoint>, klines: &[Kline]) {
+        self.volumes.extend(
+            klines
+                .iter()
+                .map(|kline| (kline.time, (kline.volume.0, kline.volume.1))),
+        );
+
+        // a backfilled batch can complete the baseline of bars that were already loaded
+        self.datapoints =
+            metrics::relative_volume(&self.volumes, self.interval, metrics::RVOL_LOOKBACK_DAYS);
+    }
+
+    fn update_latest(&mut self, _: &TimeSeries<KlineDataPoint>, kline: &Kline) {
+        self.volumes
+            .insert(kline.time, (kline.volume.0, kline.volume.1));
+
+        match metrics::relative_volume_at(
+            &self.volumes,
+            kline.time,
+            self.interval,
+            metrics::RVOL_LOOKBACK_DAYS,
+        ) {
+            Some(value) => self.datapoints.insert(kline.time, value),
+            None => self.datapoints.remove(&kline.time),
+        };
+    }
+
+    /// Baseline days behind the visible bars, skipped on 1m where it would take too many
+    /// requests to backfill
+    fn lookback(&self, interval: u64) -> u64 {
+        if interval >= Timeframe::M5.to_milliseconds() {
+            metrics::rvol_baseline_span(interval, metrics::RVOL_LOOKBACK_DAYS)
+        } else {
+            0
+        }
+    }
+
+    fn caches(&self) -> &Caches {
+        &self.cache
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart_state: &'a ViewState,
+        earliest: u64,
+        latest: u64,
+    ) -> Element<'a, Message> {
+        indicator_elem(chart_state, &self.cache, &self.datapoints, earliest, latest)
+    }
+}
+
+pub fn indicator_elem<'a>(
+    chart_state: &'a ViewState,
+    cache: &'a Caches,
+    datapoints: &'a BTreeMap<u64, f32>,
+    earliest: u64,
+    latest: u64,
+) -> Element<'a, Message> {
+    if let Basis::Tick(_) | Basis::Range(_) = chart_state.basis {
+        return center(text("Relative Volume is not available for tick charts.")).into();
+    }
+
+    if latest < earliest {
+        return row![].into();
+    }
+
+    // keep the 1x baseline in view even when every visible bar is below it
+    let max_value = datapoints
+        .range(earliest..=latest)
+        .map(|(_, rvol)| *rvol)
+        .fold(1.0_f32, f32::max)
+        * 1.05;
+
+    let indi_chart = Canvas::new(RelativeVolumePlot {
+        indicator_cache: &cache.main,
+        crosshair_cache: &cache.crosshair,
+        chart_state,
+        max_value,
+        datapoints,
+    })
+    .height(Length::Fill)
+    .width(Length::Fill);
+
+    let indi_labels = Canvas::new(super::IndicatorLabel {
+        label_cache: &cache.y_labels,
+        max: max_value,
+        min: 0.0,
+        chart_bounds: chart_state.bounds,
+    })
+    .height(Length::Fill)
+    .width(chart_state.y_labels_width());
+
+    row![
+        indi_chart,
+        vertical_rule(1).style(style::split_ruler),
+        container(indi_labels),
+    ]
+    .into()
+}
+
+pub struct RelativeVolumePlot<'a> {
+    pub indicator_cache: &'a Cache,
+    pub crosshair_cache: &'a Cache,
+    pub chart_state: &'a ViewState,
+    pub max_value: f32,
+    pub datapoints: &'a BTreeMap<u64, f32>,
+}
+
+impl RelativeVolumePlot<'_> {
+    fn visible_region(&self, size: Size) -> Rectangle {
+        let width = size.width / self.chart_state.scaling;
+        let height = size.height / self.chart_state.scaling;
+
+        Rectangle {
+            x: -self.chart_state.translation.x - width / 2.0,
+            y: 0.0,
+            width,
+            height,
+        }
+    }
+}
+
+impl canvas::Program<Message> for RelativeVolumePlot<'_> {
+    type State = Interaction;
+
+    fn update(
+        &self,
+        interaction: &mut Interaction,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let message = match *interaction {
+                    Interaction::None => {
+                        if cursor.is_over(bounds) {
+                            Some(Message::CrosshairMoved)
+                        } else {
+                            None
+                        }
+                    }
+                    _ => None,
+                };
+
+                let action =
+                    message.map_or(canvas::Action::request_redraw(), canvas::Action::publish);
+
+                Some(match interaction {
+                    Interaction::None => action,
+                    _ => action.and_capture(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let chart_state = self.chart_state;
+
+        if chart_state.bounds.width == 0.0 {
+            return vec![];
+        }
+
+        let timeframe: u64 = match chart_state.basis {
+            Basis::Time(interval) => interval.into(),
+            Basis::Tick(_) | Basis::Range(_) => return vec![],
+        };
+
+        let center = Vector::new(bounds.width / 2.0, bounds.height / 2.0);
+        let palette = theme.extended_palette();
+
+        let indicator = self.indicator_cache.draw(renderer, bounds.size(), |frame| {
+            let _timer = profiler::time("Relative volume indicator");
+
+            frame.translate(center);
+            frame.scale(chart_state.scaling);
+            frame.translate(Vector::new(
+                chart_state.translation.x,
+                (-bounds.height / chart_state.scaling) / 2.0,
+            ));
+
+            let region = self.visible_region(frame.size());
+            let (earliest, latest) = chart_state.interval_range(&region);
+
+            if latest < earliest {
+                return;
+            }
+
+            let height = bounds.height / chart_state.scaling;
+            let bar_width = chart_state.cell_width * 0.9;
+
+            self.datapoints
+                .range(earliest..=latest)
+                .for_each(|(timestamp, rvol)| {
+                    let x_position = chart_state.interval_to_x(*timestamp);
+                    let bar_height = (rvol / self.max_value) * height;
+
+                    let color = if *rvol >= 1.0 {
+                        palette.secondary.strong.color
+                    } else {
+                        palette.secondary.weak.color
+                    };
+
+                    frame.fill_rectangle(
+                        Point::new(x_position - (bar_width / 2.0), height - bar_height),
+                        Size::new(bar_width, bar_height),
+                        color,
+                    );
+                });
+
+            let baseline_y = height - (height / self.max_value);
+            frame.stroke(
+                &Path::line(
+                    Point::new(region.x, baseline_y),
+                    Point::new(region.x + region.width, baseline_y),
+                ),
+                dashed_line(theme),
+            );
+        });
+
+        let crosshair = self.crosshair_cache.draw(renderer, bounds.size(), |frame| {
+            let Some(cursor_position) = cursor.position_in(chart_state.bounds) else {
+                return;
+            };
+
+            let region = self.visible_region(frame.size());
+
+            let earliest = chart_state.x_to_interval(region.x) as f64;
+            let latest = chart_state.x_to_interval(region.x + region.width) as f64;
+
+            let crosshair_ratio = f64::from(cursor_position.x / bounds.width);
+            let crosshair_millis = earliest + crosshair_ratio * (latest - earliest);
+
+            let rounded_timestamp =
+                (crosshair_millis / (timeframe as f64)).round() as u64 * timeframe;
+            let snap_ratio = ((rounded_timestamp as f64 - earliest) / (latest - earliest)) as f32;
+
+            frame.stroke(
+                &Path::line(
+                    Point::new(snap_ratio * bounds.width, 0.0),
+                    Point::new(snap_ratio * bounds.width, bounds.height),
+                ),
+                dashed_line(theme),
+            );
+
+            if let Some(rvol) = self.datapoints.get(&rounded_timestamp) {
+                let tooltip_text = format!("RVOL: {rvol:.2}x");
+
+                frame.fill_rectangle(
+                    Point::new(4.0, 0.0),
+                    Size::new(tooltip_text.len() as f32 * 8.0, 14.0),
+                    palette.background.weakest.color.scale_alpha(0.9),
+                );
+
+                frame.fill_text(canvas::Text {
+                    content: tooltip_text,
+                    position: Point::new(8.0, 2.0),
+                    size: iced::Pixels(style::chart_text_size() - 2.0),
+                    color: palette.background.base.text,
+                    font: style::chart_font(),
+                    ..canvas::Text::default()
+                });
+            }
+        });
+
+        vec![indicator, crosshair]
+    }
+
+    fn mouse_interaction(
+        &self,
+        interaction: &Interaction,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match interaction {
+            Interaction::Panning { .. } => mouse::Interaction::Grabbing,
+            Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
+            Interaction::None if cursor.is_over(bounds) => mouse::Interaction::Crosshair,
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use data::aggr::time::TimeSeries;
+use data::chart::kline::KlineDataPoint;
+use data::metrics;
+use exchange::Kline;
+use iced::Element;
+
+use super::{Bars, Indicator, line};
+use crate::chart::{Caches, Message, ViewState};
+
+pub struct Rsi {
+    period: usize,
+    cache: Caches,
+    /// Smoothed average gain and loss of each bar, the next bar carries on from them
+    averages: BTreeMap<u64, (f32, f32)>,
+    series: BTreeMap<u64, f32>,
+}
+
+impl Default for Rsi {
+    fn default() -> Self {
+        Rsi {
+            period: 14,
+            cache: Caches::default(),
+            averages: BTreeMap::new(),
+            series: BTreeMap::new(),
+        }
+    }
+}
+
+impl Indicator for Rsi {
+    fn rebuild(&mut self, bars: Bars<'_>) {
+        self.averages = match bars {
+            Bars::Time(timeseries) => {
+                metrics::rsi_averages(&super::closes(timeseries), self.period)
+            }
+            Bars::Tick(_) => BTreeMap::new(),
+        };
+        self.series = self
+            .averages
+            .iter()
+            .map(|(time, (avg_gain, avg_loss))| (*time, metrics::rsi_value(*avg_gain, *avg_loss)))
+            .collect();
+    }
+
+    fn update_latest(&mut self, timeseries: &TimeSeries<KlineDataPoint>, kline: &Kline) {
+        let prev = timeseries
+            .datapoints
+            .range(..kline.time)
+            .next_back()
+            .and_then(|(time, dp)| Some((dp.kline.close, *self.averages.get(time)?)));
+
+        // the first averages are plain ones over `period` changes, only the full series has them
+        let Some((prev_close, prev_averages)) = prev else {
+            self.rebuild(Bars::Time(timeseries));
+            return;
+        };
+
+        let averages = metrics::rsi_next(prev_averages, kline.close - prev_close, self.period);
+        self.averages.insert(kline.time, averages);
+        self.series
+            .insert(kline.time, metrics::rsi_value(averages.0, averages.1));
+    }
+
+    fn caches(&self) -> &Caches {
+        &self.cache
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart_state: &'a ViewState,
+        earliest: u64,
+        latest: u64,
+    ) -> Element<'a, Message> {
+        line::indicator_elem(
+            chart_state,
+            &self.cache,
+            &self.series,
+            earliest,
+            latest,
+            line::Style {
+                name: "RSI",
+                value_range: Some((0.0, 100.0)),
+                levels: &[30.0, 70.0],
+            },
+        )
+    }
+}
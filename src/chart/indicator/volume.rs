@@ -5,10 +5,63 @@ use iced::widget::{Canvas, container, row, vertical_rule};
 use iced::{Element, Length};
 use iced::{Point, Rectangle, Renderer, Size, Theme, Vector, mouse};
 
-use crate::chart::{Basis, Caches, Interaction, Message, ViewState};
+use super::{Bars, Indicator};
+use crate::chart::{Basis, Caches, Interaction, Message, ViewState, profiler};
 use crate::style::{self, dashed_line};
 
+use data::aggr::{ticks::TickAggr, time::TimeSeries};
+use data::chart::kline::KlineDataPoint;
 use data::util::{format_with_commas, round_to_tick};
+use exchange::Kline;
+
+/// Buy and sell volume of each bar, keyed by open time or by index on tick based bars
+#[derive(Default)]
+pub struct Volume {
+    cache: Caches,
+    datapoints: BTreeMap<u64, (f32, f32)>,
+}
+
+impl Indicator for Volume {
+    fn rebuild(&mut self, bars: Bars<'_>) {
+        self.datapoints = match bars {
+            Bars::Time(timeseries) => timeseries.volume_data(),
+            Bars::Tick(tick_aggr) => tick_aggr.volume_data(),
+        };
+    }
+
+    fn insert_klines(&mut self, _: &TimeSeries<KlineDataPoint>, klines: &[Kline]) {
+        self.datapoints.extend(
+            klines
+                .iter()
+                .map(|kline| (kline.time, (kline.volume.0, kline.volume.1))),
+        );
+    }
+
+    fn update_latest(&mut self, _: &TimeSeries<KlineDataPoint>, kline: &Kline) {
+        self.datapoints
+            .insert(kline.time, (kline.volume.0, kline.volume.1));
+    }
+
+    fn update_ticks(&mut self, tick_aggr: &TickAggr, from: usize) {
+        for (idx, dp) in tick_aggr.datapoints.iter().enumerate().skip(from) {
+            self.datapoints
+                .insert(idx as u64, (dp.kline.volume.0, dp.kline.volume.1));
+        }
+    }
+
+    fn caches(&self) -> &Caches {
+        &self.cache
+    }
+
+    fn element<'a>(
+        &'a self,
+        chart_state: &'a ViewState,
+        earliest: u64,
+        latest: u64,
+    ) -> Element<'a, Message> {
+        indicator_elem(chart_state, &self.cache, &self.datapoints, earliest, latest)
+    }
+}
 
 pub fn indicator_elem<'a>(
     chart_state: &'a ViewState,
@@ -29,7 +82,7 @@ pub fn indicator_elem<'a>(
                     .max_by(|a, b| a.partial_cmp(b).unwrap())
                     .unwrap_or(0.0)
             }
-            Basis::Tick(_) => {
+            Basis::Tick(_) | Basis::Range(_) => {
                 let mut max_volume: f32 = 0.0;
                 let earliest = earliest as usize;
                 let latest = latest as usize;
@@ -156,6 +209,8 @@ impl canvas::Program<Message> for VolumeIndicator<'_> {
         let palette = theme.extended_palette();
 
         let indicator = self.indicator_cache.draw(renderer, bounds.size(), |frame| {
+            let _timer = profiler::time("Volume indicator");
+
             frame.translate(center);
             frame.scale(chart_state.scaling);
             frame.translate(Vector::new(
@@ -220,7 +275,7 @@ impl canvas::Program<Message> for VolumeIndicator<'_> {
                         },
                     );
                 }
-                Basis::Tick(_) => {
+                Basis::Tick(_) | Basis::Range(_) => {
                     let earliest = earliest as usize;
                     let latest = latest as usize;
 
@@ -288,7 +343,7 @@ impl canvas::Program<Message> for VolumeIndicator<'_> {
 
                         (rounded_timestamp, snap_ratio)
                     }
-                    Basis::Tick(_) => {
+                    Basis::Tick(_) | Basis::Range(_) => {
                         let chart_x_min = region.x;
                         let chart_x_max = region.x + region.width;
 
@@ -330,7 +385,7 @@ impl canvas::Program<Message> for VolumeIndicator<'_> {
                             exact_match
                         }
                     }
-                    Basis::Tick(_) => {
+                    Basis::Tick(_) | Basis::Range(_) => {
                         let index_from_end = rounded_interval as usize;
 
                         if index_from_end < self.datapoints.len() {
@@ -378,9 +433,9 @@ impl canvas::Program<Message> for VolumeIndicator<'_> {
                     let text = canvas::Text {
                         content: tooltip_text,
                         position: Point::new(8.0, 2.0),
-                        size: iced::Pixels(10.0),
+                        size: iced::Pixels(style::chart_text_size() - 2.0),
                         color: palette.background.base.text,
-                        font: style::AZERET_MONO,
+                        font: style::chart_font(),
                         ..canvas::Text::default()
                     };
                     frame.fill_text(text);
@@ -1,19 +1,23 @@
 use super::{
-    Action, Basis, Caches, Chart, Interaction, Message, PlotConstants, PlotData, ViewState,
-    indicator, request_fetch, scale::linear::PriceInfoLabel,
+    Action, Basis, Chart, Interaction, Message, PlotConstants, PlotData, ViewState, indicator,
+    profiler, request_fetch, scale::linear::PriceInfoLabel,
 };
-use crate::chart::TEXT_SIZE;
 use crate::{modal::pane::settings::study, style};
 use data::aggr::ticks::TickAggr;
 use data::aggr::time::TimeSeries;
 use data::chart::{
     KlineChartKind, ViewConfig,
+    anomaly::{self, Anomaly},
     indicator::{Indicator, KlineIndicator},
-    kline::{ClusterKind, FootprintStudy, KlineDataPoint, KlineTrades, NPoc, PointOfControl},
+    kline::{
+        ClusterKind, Config, FootprintStudy, KlineDataPoint, KlineTrades, MovingAverage, NPoc,
+        PointOfControl, SpreadMode, TPO_BRACKET_MS, TPO_VALUE_AREA, VolumeProfile, session_volume,
+        tpo_letter, tpo_sessions, vwap_series,
+    },
 };
 use data::util::{abbr_large_numbers, count_decimals, round_to_tick};
 use exchange::{
-    Kline, OpenInterest as OIData, TickerInfo, Timeframe, Trade,
+    Kline, Liquidation, OpenInterest as OIData, TickerInfo, Timeframe, Trade,
     fetcher::{FetchRange, RequestHandler},
 };
 
@@ -41,7 +45,7 @@ impl Chart for KlineChart {
     fn invalidate_crosshair(&mut self) {
         self.chart.cache.clear_crosshair();
         self.indicators.iter_mut().for_each(|(_, data)| {
-            data.clear_crosshair();
+            data.caches().clear_crosshair();
         });
     }
 
@@ -72,7 +76,7 @@ impl Chart for KlineChart {
             }
 
             if let Some(data) = self.indicators.get(selected_indicator) {
-                indicators.push(data.indicator_elem(chart_state, earliest, latest));
+                indicators.push(data.element(chart_state, earliest, latest));
             }
         }
 
@@ -94,7 +98,7 @@ impl Chart for KlineChart {
 
                 (earliest, latest)
             }
-            Basis::Tick(_) => {
+            Basis::Tick(_) | Basis::Range(_) => {
                 unimplemented!()
             }
         }
@@ -119,7 +123,10 @@ impl Chart for KlineChart {
             KlineChartKind::Footprint { .. } => {
                 0.5 * (chart.bounds.width / chart.scaling) - (chart.cell_width / chart.scaling)
             }
-            KlineChartKind::Candles => {
+            KlineChartKind::Candles
+            | KlineChartKind::Tpo
+            | KlineChartKind::DeltaCandles
+            | KlineChartKind::Spread(_) => {
                 0.5 * (chart.bounds.width / chart.scaling)
                     - (8.0 * chart.cell_width / chart.scaling)
             }
@@ -139,45 +146,6 @@ impl Chart for KlineChart {
     }
 }
 
-enum IndicatorData {
-    Volume(Caches, BTreeMap<u64, (f32, f32)>),
-    OpenInterest(Caches, BTreeMap<u64, f32>),
-}
-
-impl IndicatorData {
-    fn clear_all(&mut self) {
-        match self {
-            IndicatorData::Volume(caches, _) | IndicatorData::OpenInterest(caches, _) => {
-                caches.clear_all();
-            }
-        }
-    }
-
-    fn clear_crosshair(&mut self) {
-        match self {
-            IndicatorData::Volume(caches, _) | IndicatorData::OpenInterest(caches, _) => {
-                caches.clear_crosshair();
-            }
-        }
-    }
-
-    fn indicator_elem<'a>(
-        &'a self,
-        chart: &'a ViewState,
-        earliest: u64,
-        latest: u64,
-    ) -> Element<'a, Message> {
-        match self {
-            IndicatorData::Volume(cache, data) => {
-                indicator::volume::indicator_elem(chart, cache, data, earliest, latest)
-            }
-            IndicatorData::OpenInterest(cache, data) => {
-                indicator::open_interest::indicator_elem(chart, cache, data, earliest, latest)
-            }
-        }
-    }
-}
-
 impl PlotConstants for KlineChart {
     fn min_scaling(&self) -> f32 {
         self.kind.min_scaling()
@@ -212,12 +180,20 @@ pub struct KlineChart {
     chart: ViewState,
     data_source: PlotData<KlineDataPoint>,
     raw_trades: Vec<Trade>,
-    indicators: HashMap<KlineIndicator, IndicatorData>,
+    indicators: HashMap<KlineIndicator, Box<dyn indicator::Indicator>>,
     fetching_trades: (bool, Option<Handle>),
     kind: KlineChartKind,
     request_handler: RequestHandler,
     study_configurator: study::Configurator<FootprintStudy>,
     last_tick: Instant,
+    liquidations: Vec<Liquidation>,
+    visual_config: Config,
+    /// Series of the configured moving averages, only kept for time based bars
+    moving_averages: Vec<(MovingAverage, BTreeMap<u64, f32>)>,
+    /// Anomalies found on each time based bar by the enabled detectors
+    anomalies: BTreeMap<u64, Vec<Anomaly>>,
+    /// Second the candle countdown was last drawn at
+    countdown_second: u64,
 }
 
 impl KlineChart {
@@ -236,12 +212,20 @@ impl KlineChart {
                 let timeseries =
                     TimeSeries::<KlineDataPoint>::new(interval, tick_size, &raw_trades, klines_raw);
 
-                let base_price_y = timeseries.base_price();
+                // delta candles sit around zero rather than the price
+                let base_price_y = if matches!(kind, KlineChartKind::DeltaCandles) {
+                    0.0
+                } else {
+                    timeseries.base_price()
+                };
                 let latest_x = timeseries.latest_timestamp().unwrap_or(0);
                 let (scale_high, scale_low) = timeseries.price_scale({
                     match kind {
                         KlineChartKind::Footprint { .. } => 12,
-                        KlineChartKind::Candles => 60,
+                        KlineChartKind::Candles
+                        | KlineChartKind::DeltaCandles
+                        | KlineChartKind::Spread(_) => 60,
+                        KlineChartKind::Tpo => 48,
                     }
                 });
 
@@ -252,27 +236,19 @@ impl KlineChart {
                     .map(|indicator| {
                         (
                             *indicator,
-                            match indicator {
-                                KlineIndicator::Volume => IndicatorData::Volume(
-                                    Caches::default(),
-                                    timeseries.volume_data(),
-                                ),
-                                KlineIndicator::OpenInterest => {
-                                    IndicatorData::OpenInterest(Caches::default(), BTreeMap::new())
-                                }
-                            },
+                            indicator::register(*indicator, indicator::Bars::Time(&timeseries)),
                         )
                     })
                     .collect();
 
                 let mut chart = ViewState {
-                    cell_width: match kind {
-                        KlineChartKind::Footprint { .. } => 80.0,
-                        KlineChartKind::Candles => 4.0,
-                    },
+                    cell_width: kind.default_cell_width(),
                     cell_height: match kind {
                         KlineChartKind::Footprint { .. } => 800.0 / y_ticks,
-                        KlineChartKind::Candles => 200.0 / y_ticks,
+                        KlineChartKind::Candles
+                        | KlineChartKind::DeltaCandles
+                        | KlineChartKind::Spread(_) => 200.0 / y_ticks,
+                        KlineChartKind::Tpo => 400.0 / y_ticks,
                     },
                     base_price_y,
                     latest_x,
@@ -289,7 +265,10 @@ impl KlineChart {
                         0.5 * (chart.bounds.width / chart.scaling)
                             - (chart.cell_width / chart.scaling)
                     }
-                    KlineChartKind::Candles => {
+                    KlineChartKind::Candles
+                    | KlineChartKind::Tpo
+                    | KlineChartKind::DeltaCandles
+                    | KlineChartKind::Spread(_) => {
                         0.5 * (chart.bounds.width / chart.scaling)
                             - (8.0 * chart.cell_width / chart.scaling)
                     }
@@ -306,37 +285,37 @@ impl KlineChart {
                     kind: kind.clone(),
                     study_configurator: study::Configurator::new(),
                     last_tick: Instant::now(),
+                    liquidations: vec![],
+                    visual_config: Config::default(),
+                    moving_averages: vec![],
+                    anomalies: BTreeMap::new(),
+                    countdown_second: 0,
                 }
             }
-            Basis::Tick(interval) => {
-                let tick_aggr = TickAggr::new(interval, tick_size, &raw_trades);
+            Basis::Tick(_) | Basis::Range(_) => {
+                let Some(trade_bar) = basis.trade_bar() else {
+                    unreachable!()
+                };
+                let tick_aggr = TickAggr::new(trade_bar, tick_size, &raw_trades);
 
                 let enabled_indicators = enabled_indicators
                     .iter()
                     .map(|indicator| {
                         (
                             *indicator,
-                            match indicator {
-                                KlineIndicator::Volume => IndicatorData::Volume(
-                                    Caches::default(),
-                                    tick_aggr.volume_data(),
-                                ),
-                                KlineIndicator::OpenInterest => {
-                                    IndicatorData::OpenInterest(Caches::default(), BTreeMap::new())
-                                }
-                            },
+                            indicator::register(*indicator, indicator::Bars::Tick(&tick_aggr)),
                         )
                     })
                     .collect();
 
                 let mut chart = ViewState {
-                    cell_width: match kind {
-                        KlineChartKind::Footprint { .. } => 80.0,
-                        KlineChartKind::Candles => 4.0,
-                    },
+                    cell_width: kind.default_cell_width(),
                     cell_height: match kind {
                         KlineChartKind::Footprint { .. } => 90.0,
-                        KlineChartKind::Candles => 8.0,
+                        KlineChartKind::Candles
+                        | KlineChartKind::DeltaCandles
+                        | KlineChartKind::Spread(_) => 8.0,
+                        KlineChartKind::Tpo => 12.0,
                     },
                     tick_size,
                     decimals: count_decimals(tick_size),
@@ -351,7 +330,10 @@ impl KlineChart {
                         0.5 * (chart.bounds.width / chart.scaling)
                             - (chart.cell_width / chart.scaling)
                     }
-                    KlineChartKind::Candles => {
+                    KlineChartKind::Candles
+                    | KlineChartKind::Tpo
+                    | KlineChartKind::DeltaCandles
+                    | KlineChartKind::Spread(_) => {
                         0.5 * (chart.bounds.width / chart.scaling)
                             - (8.0 * chart.cell_width / chart.scaling)
                     }
@@ -360,11 +342,7 @@ impl KlineChart {
 
                 KlineChart {
                     chart,
-                    data_source: PlotData::TickBased(TickAggr::new(
-                        interval,
-                        tick_size,
-                        &raw_trades,
-                    )),
+                    data_source: PlotData::TickBased(tick_aggr),
                     raw_trades,
                     indicators: enabled_indicators,
                     fetching_trades: (false, None),
@@ -372,6 +350,11 @@ impl KlineChart {
                     kind: kind.clone(),
                     study_configurator: study::Configurator::new(),
                     last_tick: Instant::now(),
+                    liquidations: vec![],
+                    visual_config: Config::default(),
+                    moving_averages: vec![],
+                    anomalies: BTreeMap::new(),
+                    countdown_second: 0,
                 }
             }
         }
@@ -382,11 +365,9 @@ impl KlineChart {
             PlotData::TimeBased(ref mut timeseries) => {
                 timeseries.insert_klines(&[kline.to_owned()]);
 
-                if let Some(IndicatorData::Volume(_, data)) =
-                    self.indicators.get_mut(&KlineIndicator::Volume)
-                {
-                    data.insert(kline.time, (kline.volume.0, kline.volume.1));
-                };
+                for data in self.indicators.values_mut() {
+                    data.update_latest(timeseries, kline);
+                }
 
                 let chart = self.mut_state();
 
@@ -398,12 +379,111 @@ impl KlineChart {
             }
             PlotData::TickBased(_) => {}
         }
+
+        self.update_moving_averages(kline.time);
+        self.rebuild_anomalies();
     }
 
     pub fn kind(&self) -> &KlineChartKind {
         &self.kind
     }
 
+    pub fn visual_config(&self) -> Config {
+        self.visual_config
+    }
+
+    pub fn set_visual_config(&mut self, visual_config: Config) {
+        let rebuild = visual_config.moving_averages != self.visual_config.moving_averages;
+        let redetect = visual_config.anomalies != self.visual_config.anomalies;
+        self.visual_config = visual_config;
+        self.chart.show_countdown = visual_config.candle_countdown;
+
+        if rebuild {
+            self.rebuild_moving_averages();
+        }
+        if redetect {
+            self.rebuild_anomalies();
+        }
+        self.invalidate(None);
+    }
+
+    pub fn with_visual_config(mut self, visual_config: Option<Config>) -> Self {
+        self.visual_config = visual_config.unwrap_or_default();
+        self.chart.show_countdown = self.visual_config.candle_countdown;
+        self.rebuild_moving_averages();
+        self.rebuild_anomalies();
+        self
+    }
+
+    fn rebuild_anomalies(&mut self) {
+        let detectors = self.visual_config.anomalies;
+
+        self.anomalies = match &self.data_source {
+            PlotData::TimeBased(timeseries) if detectors.any() => {
+                let open_interest = self
+                    .indicators
+                    .values()
+                    .find_map(|data| data.open_interest());
+
+                anomaly::detect(&timeseries.kline_data(), open_interest, detectors)
+            }
+            _ => BTreeMap::new(),
+        };
+    }
+
+    fn rebuild_moving_averages(&mut self) {
+        self.moving_averages = match &self.data_source {
+            PlotData::TimeBased(timeseries) => {
+                let closes = timeseries
+                    .datapoints
+                    .iter()
+                    .map(|(time, dp)| (*time, dp.kline.close))
+                    .collect();
+
+                self.visual_config
+                    .moving_averages
+                    .iter()
+                    .flatten()
+                    .map(|ma| (*ma, ma.series(&closes)))
+                    .collect()
+            }
+            PlotData::TickBased(_) => vec![],
+        };
+    }
+
+    /// Extends the moving averages by the bar at `time` without going over the whole history
+    fn update_moving_averages(&mut self, time: u64) {
+        let PlotData::TimeBased(timeseries) = &self.data_source else {
+            return;
+        };
+
+        for (ma, series) in &mut self.moving_averages {
+            let mut closes = timeseries
+                .datapoints
+                .range(..=time)
+                .rev()
+                .take(usize::from(ma.period))
+                .map(|(_, dp)| dp.kline.close)
+                .collect::<Vec<f32>>();
+            closes.reverse();
+
+            let prev = series.range(..time).next_back().map(|(_, value)| *value);
+
+            match ma.next_value(&closes, prev) {
+                Some(value) => series.insert(time, value),
+                None => series.remove(&time),
+            };
+        }
+    }
+
+    pub fn insert_liquidations(&mut self, liquidations: &[Liquidation]) {
+        super::push_liquidations(&mut self.liquidations, liquidations);
+
+        if self.visual_config.liquidation_scale.is_some() {
+            self.invalidate(None);
+        }
+    }
+
     fn missing_data_task(&mut self) -> Option<Action> {
         match &self.data_source {
             PlotData::TimeBased(timeseries) => {
@@ -422,7 +502,32 @@ impl KlineChart {
                     }
                 }
 
-                if !self.fetching_trades.0 && exchange::fetcher::is_trade_fetch_enabled() {
+                // bars behind the visible ones that indicators need, e.g. a baseline
+                let lookback = self
+                    .indicators
+                    .values()
+                    .map(|data| data.lookback(timeframe))
+                    .max()
+                    .unwrap_or(0);
+
+                if lookback > 0 {
+                    let baseline_earliest = visible_earliest.saturating_sub(lookback);
+
+                    if baseline_earliest < kline_earliest {
+                        let range = FetchRange::Kline(baseline_earliest, kline_earliest);
+
+                        if let Some(action) = request_fetch(&mut self.request_handler, range) {
+                            return Some(action);
+                        }
+                    }
+                }
+
+                let fetches_trades = self
+                    .chart
+                    .ticker_info
+                    .is_some_and(|info| exchange::fetcher::is_trade_fetch_enabled(info.exchange()));
+
+                if !self.fetching_trades.0 && fetches_trades {
                     if let Some((fetch_from, fetch_to)) =
                         timeseries.suggest_trade_fetch_range(visible_earliest, visible_latest)
                     {
@@ -436,8 +541,10 @@ impl KlineChart {
 
                 // priority 2, Open Interest data
                 for data in self.indicators.values() {
-                    if let IndicatorData::OpenInterest(_, _) = data {
+                    if data.open_interest().is_some() {
+                        // open interest history only goes from 5 minute to daily periods
                         if timeframe >= Timeframe::M5.to_milliseconds()
+                            && timeframe <= Timeframe::D1.to_milliseconds()
                             && self.chart.ticker_info.is_some_and(|t| t.is_perps())
                         {
                             let (oi_earliest, oi_latest) = self.oi_timerange(kline_latest);
@@ -489,6 +596,14 @@ impl KlineChart {
         None
     }
 
+    /// No klines got loaded yet, as when the initial fetch never came back
+    pub fn is_empty(&self) -> bool {
+        match &self.data_source {
+            PlotData::TimeBased(timeseries) => timeseries.latest_timestamp().is_none(),
+            PlotData::TickBased(_) => false,
+        }
+    }
+
     pub fn reset_request_handler(&mut self) {
         self.request_handler = RequestHandler::new();
         self.fetching_trades = (false, None);
@@ -523,6 +638,22 @@ impl KlineChart {
         self.chart.tick_size
     }
 
+    /// Every loaded bar with its footprint, which is empty unless the chart keeps trades
+    pub fn bars(&self) -> Vec<(&Kline, &KlineTrades)> {
+        match &self.data_source {
+            PlotData::TimeBased(timeseries) => timeseries
+                .datapoints
+                .values()
+                .map(|dp| (&dp.kline, &dp.footprint))
+                .collect(),
+            PlotData::TickBased(tick_aggr) => tick_aggr
+                .datapoints
+                .iter()
+                .map(|dp| (&dp.kline, &dp.footprint))
+                .collect(),
+        }
+    }
+
     pub fn study_configurator(&self) -> &study::Configurator<FootprintStudy> {
         &self.study_configurator
     }
@@ -557,6 +688,55 @@ impl KlineChart {
         self.invalidate(None);
     }
 
+    /// First and last loaded bar with the bar interval, bar replay only scrubs through these
+    pub fn replay_bounds(&self) -> Option<(u64, u64, u64)> {
+        let PlotData::TimeBased(timeseries) = &self.data_source else {
+            return None;
+        };
+
+        let (first, _) = timeseries.datapoints.first_key_value()?;
+        let (last, _) = timeseries.datapoints.last_key_value()?;
+
+        Some((*first, *last, timeseries.interval.to_milliseconds()))
+    }
+
+    pub fn replay_cutoff(&self) -> Option<u64> {
+        self.chart.replay_cutoff
+    }
+
+    /// Hides the bars after `cutoff` and scrolls it into view, `None` shows every bar again
+    pub fn set_replay_cutoff(&mut self, cutoff: Option<u64>) {
+        let Some((first, last, interval)) = self.replay_bounds() else {
+            return;
+        };
+
+        // snapped onto a bar, scrubbing lands anywhere in between
+        let cutoff =
+            cutoff.map(|time| (time.clamp(first, last) - first) / interval * interval + first);
+        self.chart.replay_cutoff = cutoff;
+
+        if let Some(cutoff) = cutoff {
+            self.chart.layout.autoscale = None;
+
+            let anchor = self.autoscaled_coords();
+            self.chart.translation.x = anchor.x - self.chart.interval_to_x(cutoff);
+        }
+
+        self.invalidate(None);
+    }
+
+    /// Moves the replay by `bars`, past the last loaded bar ends it
+    pub fn step_replay(&mut self, bars: i64) {
+        let (Some(cutoff), Some((_, last, interval))) =
+            (self.replay_cutoff(), self.replay_bounds())
+        else {
+            return;
+        };
+
+        let next = cutoff.saturating_add_signed(bars * interval as i64);
+        self.set_replay_cutoff((next <= last).then_some(next));
+    }
+
     pub fn chart_layout(&self) -> ViewConfig {
         self.chart.layout()
     }
@@ -572,6 +752,18 @@ impl KlineChart {
         self.invalidate(None);
     }
 
+    /// Switches how a spread combines its legs, the klines have to be recombined and
+    /// inserted anew afterwards
+    pub fn set_spread_mode(&mut self, mode: SpreadMode) {
+        if let KlineChartKind::Spread(ref mut current) = self.kind {
+            *current = mode;
+
+            if let Some(ticker_info) = self.chart.ticker_info {
+                self.chart.tick_size = mode.tick_size(ticker_info.min_ticksize);
+            }
+        }
+    }
+
     pub fn basis(&self) -> Basis {
         self.chart.basis
     }
@@ -595,16 +787,22 @@ impl KlineChart {
         self.invalidate(None);
     }
 
-    pub fn set_tick_basis(&mut self, tick_basis: data::aggr::TickCount) {
-        self.chart.basis = Basis::Tick(tick_basis);
+    /// Rebuilds the chart from its raw trades on a tick count or range basis
+    pub fn set_trade_basis(&mut self, basis: Basis) {
+        let Some(trade_bar) = basis.trade_bar() else {
+            return;
+        };
+        self.chart.basis = basis;
 
-        let new_tick_aggr = TickAggr::new(tick_basis, self.chart.tick_size, &self.raw_trades);
+        let new_tick_aggr = TickAggr::new(trade_bar, self.chart.tick_size, &self.raw_trades);
 
-        if let Some(indicator) = self.indicators.get_mut(&KlineIndicator::Volume) {
-            *indicator = IndicatorData::Volume(Caches::default(), new_tick_aggr.volume_data());
+        for data in self.indicators.values_mut() {
+            data.rebuild(indicator::Bars::Tick(&new_tick_aggr));
         }
 
         self.data_source = PlotData::TickBased(new_tick_aggr);
+        self.moving_averages.clear();
+        self.anomalies.clear();
 
         self.invalidate(None);
     }
@@ -631,8 +829,10 @@ impl KlineChart {
         let mut from_time = latest_kline;
         let mut to_time = u64::MIN;
 
-        if let Some(IndicatorData::OpenInterest(_, data)) =
-            self.indicators.get(&KlineIndicator::OpenInterest)
+        if let Some(data) = self
+            .indicators
+            .values()
+            .find_map(|data| data.open_interest())
         {
             data.iter().for_each(|(time, _)| {
                 from_time = from_time.min(*time);
@@ -652,13 +852,8 @@ impl KlineChart {
 
                 tick_aggr.insert_trades(trades_buffer);
 
-                if let Some(IndicatorData::Volume(_, data)) =
-                    self.indicators.get_mut(&KlineIndicator::Volume)
-                {
-                    let start_idx = old_dp_len.saturating_sub(1);
-                    for (idx, dp) in tick_aggr.datapoints.iter().enumerate().skip(start_idx) {
-                        data.insert(idx as u64, (dp.kline.volume.0, dp.kline.volume.1));
-                    }
+                for data in self.indicators.values_mut() {
+                    data.update_ticks(tick_aggr, old_dp_len.saturating_sub(1));
                 }
 
                 if let Some(last_dp) = tick_aggr.datapoints.last() {
@@ -698,15 +893,9 @@ impl KlineChart {
             PlotData::TimeBased(ref mut timeseries) => {
                 timeseries.insert_klines(klines_raw);
 
-                if let Some(IndicatorData::Volume(_, data)) =
-                    self.indicators.get_mut(&KlineIndicator::Volume)
-                {
-                    data.extend(
-                        klines_raw
-                            .iter()
-                            .map(|kline| (kline.time, (kline.volume.0, kline.volume.1))),
-                    );
-                };
+                for data in self.indicators.values_mut() {
+                    data.insert_klines(timeseries, klines_raw);
+                }
 
                 if klines_raw.is_empty() {
                     self.request_handler
@@ -717,6 +906,10 @@ impl KlineChart {
             }
             PlotData::TickBased(_) => {}
         }
+
+        // fetched batches can land before the loaded bars, which shifts everything after them
+        self.rebuild_moving_averages();
+        self.rebuild_anomalies();
     }
 
     pub fn insert_open_interest(&mut self, req_id: Option<uuid::Uuid>, oi_data: &[OIData]) {
@@ -729,11 +922,13 @@ impl KlineChart {
             }
         }
 
-        if let Some(IndicatorData::OpenInterest(_, data)) =
-            self.indicators.get_mut(&KlineIndicator::OpenInterest)
-        {
-            data.extend(oi_data.iter().map(|oi| (oi.time, oi.value)));
-        };
+        for data in self.indicators.values_mut() {
+            data.insert_open_interest(oi_data);
+        }
+
+        if self.visual_config.anomalies.oi_dump {
+            self.rebuild_anomalies();
+        }
     }
 
     fn calc_qty_scales(
@@ -775,10 +970,40 @@ impl KlineChart {
         self.last_tick
     }
 
+    /// Redraws the price axis once the second shown on the candle countdown has passed,
+    /// the rest of the chart keeps to its own redraw interval
+    pub fn tick_countdown(&mut self) {
+        if !self.chart.show_countdown || !matches!(self.chart.basis, Basis::Time(_)) {
+            return;
+        }
+
+        let second = chrono::Utc::now().timestamp() as u64;
+        if second != self.countdown_second {
+            self.countdown_second = second;
+            self.chart.cache.y_labels.clear();
+        }
+    }
+
     pub fn invalidate(&mut self, now: Option<Instant>) -> Option<Action> {
+        if self.chart.follow_latest {
+            self.chart.translation.x = self.autoscaled_coords().x;
+        }
+
         let chart = &mut self.chart;
 
-        if let Some(autoscale) = chart.layout.autoscale {
+        // deltas have no level to center on, they're fit to the visible bars either way
+        let autoscale = match chart.layout.autoscale {
+            Some(super::Autoscale::CenterLatest)
+                if matches!(self.kind, KlineChartKind::DeltaCandles) =>
+            {
+                chart.translation.x = 0.5 * (chart.bounds.width / chart.scaling)
+                    - (8.0 * chart.cell_width / chart.scaling);
+                Some(super::Autoscale::FitToVisible)
+            }
+            autoscale => autoscale,
+        };
+
+        if let Some(autoscale) = autoscale {
             match autoscale {
                 super::Autoscale::CenterLatest => {
                     let x_translation = match &self.kind {
@@ -786,7 +1011,10 @@ impl KlineChart {
                             0.5 * (chart.bounds.width / chart.scaling)
                                 - (chart.cell_width / chart.scaling)
                         }
-                        KlineChartKind::Candles => {
+                        KlineChartKind::Candles
+                        | KlineChartKind::Tpo
+                        | KlineChartKind::DeltaCandles
+                        | KlineChartKind::Spread(_) => {
                             0.5 * (chart.bounds.width / chart.scaling)
                                 - (8.0 * chart.cell_width / chart.scaling)
                         }
@@ -825,10 +1053,15 @@ impl KlineChart {
                     let visible_region = chart.visible_region(chart.bounds.size());
                     let (start_interval, end_interval) = chart.interval_range(&visible_region);
 
-                    if let Some((lowest, highest)) = self
-                        .data_source
-                        .visible_price_range(start_interval, end_interval)
-                    {
+                    let visible_range = if matches!(self.kind, KlineChartKind::DeltaCandles) {
+                        self.data_source
+                            .visible_delta_range(start_interval, end_interval)
+                    } else {
+                        self.data_source
+                            .visible_price_range(start_interval, end_interval)
+                    };
+
+                    if let Some((lowest, highest)) = visible_range {
                         let padding = (highest - lowest) * 0.05;
                         let price_span = (highest - lowest) + (2.0 * padding);
 
@@ -850,7 +1083,7 @@ impl KlineChart {
 
         chart.cache.clear_all();
         self.indicators.iter_mut().for_each(|(_, data)| {
-            data.clear_all();
+            data.caches().clear_all();
         });
 
         if let Some(t) = now {
@@ -869,23 +1102,15 @@ impl KlineChart {
                 entry.remove();
             }
             Entry::Vacant(entry) => {
-                let data = match indicator {
-                    KlineIndicator::Volume => match &self.data_source {
-                        PlotData::TimeBased(timeseries) => {
-                            IndicatorData::Volume(Caches::default(), timeseries.into())
-                        }
-                        PlotData::TickBased(tick_aggr) => {
-                            IndicatorData::Volume(Caches::default(), tick_aggr.into())
-                        }
-                    },
-                    KlineIndicator::OpenInterest => {
-                        IndicatorData::OpenInterest(Caches::default(), BTreeMap::new())
-                    }
-                };
-                entry.insert(data);
+                entry.insert(indicator::register(indicator, (&self.data_source).into()));
             }
         }
 
+        // the open interest dump detector goes by the open interest an indicator keeps
+        if self.visual_config.anomalies.oi_dump {
+            self.rebuild_anomalies();
+        }
+
         if let Some(main_split) = self.chart.layout.splits.first() {
             let current_indi_count = self.indicators.len();
             self.chart.layout.splits = data::util::calc_panel_splits(
@@ -930,6 +1155,14 @@ impl canvas::Program<Message> for KlineChart {
         let palette = theme.extended_palette();
 
         let klines = chart.cache.main.draw(renderer, bounds_size, |frame| {
+            let _timer = profiler::time(match self.kind {
+                KlineChartKind::Footprint { .. } => "Footprint",
+                KlineChartKind::Candles => "Candles",
+                KlineChartKind::Tpo => "TPO",
+                KlineChartKind::DeltaCandles => "Delta candles",
+                KlineChartKind::Spread(_) => "Spread",
+            });
+
             frame.translate(center);
             frame.scale(chart.scaling);
             frame.translate(chart.translation);
@@ -957,9 +1190,11 @@ impl canvas::Program<Message> for KlineChart {
                     let cell_width_unscaled = chart.cell_width * chart.scaling;
 
                     let text_size = {
-                        let text_size_from_height = cell_height_unscaled.round().min(16.0) - 3.0;
+                        let max_size = style::chart_text_size() + 4.0;
+                        let text_size_from_height =
+                            cell_height_unscaled.round().min(max_size) - 3.0;
                         let text_size_from_width =
-                            (cell_width_unscaled * 0.1).round().min(16.0) - 3.0;
+                            (cell_width_unscaled * 0.1).round().min(max_size) - 3.0;
 
                         text_size_from_height.min(text_size_from_width)
                     };
@@ -979,6 +1214,8 @@ impl canvas::Program<Message> for KlineChart {
                         }
                     });
 
+                    let auction_markers = AuctionMarkers::from_studies(studies);
+
                     draw_all_npocs(
                         &self.data_source,
                         frame,
@@ -991,6 +1228,37 @@ impl canvas::Program<Message> for KlineChart {
                         studies,
                     );
 
+                    draw_vwap(
+                        &self.data_source,
+                        frame,
+                        price_to_y,
+                        interval_to_x,
+                        (earliest, latest),
+                        palette,
+                        studies,
+                    );
+
+                    draw_bollinger_bands(
+                        &self.data_source,
+                        frame,
+                        price_to_y,
+                        interval_to_x,
+                        (earliest, latest),
+                        palette,
+                        studies,
+                    );
+
+                    draw_delta_divergences(
+                        &self.data_source,
+                        frame,
+                        price_to_y,
+                        interval_to_x,
+                        (earliest, latest),
+                        chart.cell_width,
+                        palette,
+                        studies,
+                    );
+
                     render_data_source(
                         &self.data_source,
                         frame,
@@ -1016,11 +1284,34 @@ impl canvas::Program<Message> for KlineChart {
                                 trades,
                                 *clusters,
                             );
+
+                            draw_auction_markers(
+                                frame,
+                                price_to_y,
+                                x_position,
+                                candle_width,
+                                chart.cell_height,
+                                trades,
+                                auction_markers,
+                                palette,
+                            );
                         },
                     );
+
+                    draw_visible_volume_profile(
+                        &self.data_source,
+                        frame,
+                        &region,
+                        price_to_y,
+                        (earliest, latest),
+                        chart.cell_height,
+                        palette,
+                        studies,
+                    );
                 }
-                KlineChartKind::Candles => {
+                KlineChartKind::Candles | KlineChartKind::Spread(_) => {
                     let candle_width = chart.cell_width * 0.8;
+                    let ratio_strip = self.visual_config.volume_ratio_strip;
 
                     render_data_source(
                         &self.data_source,
@@ -1037,24 +1328,164 @@ impl canvas::Program<Message> for KlineChart {
                                 x_position,
                                 kline,
                             );
+
+                            if ratio_strip {
+                                draw_volume_ratio_strip(
+                                    frame,
+                                    price_to_y,
+                                    candle_width,
+                                    chart.scaling,
+                                    palette,
+                                    x_position,
+                                    kline,
+                                );
+                            }
+                        },
+                    );
+                }
+                KlineChartKind::DeltaCandles => {
+                    let candle_width = chart.cell_width * 0.8;
+
+                    let zero_y = price_to_y(0.0);
+                    frame.fill_rectangle(
+                        Point::new(region.x, zero_y),
+                        Size::new(region.width, 1.0 / chart.scaling),
+                        palette.background.strong.color,
+                    );
+
+                    render_data_source(
+                        &self.data_source,
+                        frame,
+                        earliest,
+                        latest,
+                        interval_to_x,
+                        |frame, x_position, kline, footprint| {
+                            draw_candle_dp(
+                                frame,
+                                price_to_y,
+                                candle_width,
+                                palette,
+                                x_position,
+                                &footprint.delta_kline(kline.time),
+                            );
                         },
                     );
                 }
+                KlineChartKind::Tpo => {
+                    if let (PlotData::TimeBased(timeseries), Basis::Time(timeframe)) =
+                        (&self.data_source, chart.basis)
+                    {
+                        draw_tpo_sessions(
+                            frame,
+                            timeseries,
+                            timeframe,
+                            (earliest, latest),
+                            price_to_y,
+                            interval_to_x,
+                            chart.cell_width,
+                            chart.cell_height,
+                            chart.scaling,
+                            chart.tick_size,
+                            palette,
+                        );
+                    }
+                }
+            }
+
+            if !matches!(self.kind, KlineChartKind::DeltaCandles) {
+                draw_moving_averages(
+                    &self.data_source,
+                    &self.moving_averages,
+                    &self.visual_config.moving_averages,
+                    frame,
+                    price_to_y,
+                    interval_to_x,
+                    (earliest, latest),
+                );
+            }
+
+            if !matches!(self.kind, KlineChartKind::DeltaCandles) {
+                draw_anomaly_markers(
+                    &self.data_source,
+                    &self.anomalies,
+                    frame,
+                    price_to_y,
+                    interval_to_x,
+                    (earliest, latest),
+                    chart.scaling,
+                    palette,
+                );
+            }
+
+            // liquidations only have a place on time based bars
+            if let (Some(liquidation_scale), Basis::Time(timeframe), Some(ticker_info)) = (
+                self.visual_config.liquidation_scale,
+                chart.basis,
+                chart.ticker_info,
+            ) {
+                let interval = timeframe.to_milliseconds();
+
+                super::draw_liquidations(
+                    frame,
+                    palette,
+                    &self.liquidations,
+                    (earliest, latest + interval),
+                    liquidation_scale,
+                    ticker_info.market_type(),
+                    price_to_y,
+                    |time| interval_to_x(timeframe.open_time(time)),
+                );
+            }
+
+            // the y axis reads delta there, a price line would land anywhere
+            if !matches!(self.kind, KlineChartKind::DeltaCandles) {
+                chart.draw_last_price_line(frame, palette, region);
             }
 
-            chart.draw_last_price_line(frame, palette, region);
+            chart.draw_day_levels(frame, palette, region);
+            chart.draw_drawings(frame, palette, region);
+            chart.draw_alert_lines(frame, palette, region);
         });
 
+        if chart.is_exporting() {
+            return std::iter::once(klines)
+                .chain(chart.draw_watermark(renderer, palette, bounds_size))
+                .collect();
+        }
+
         let crosshair = chart.cache.crosshair.draw(renderer, bounds_size, |frame| {
             if let Some(cursor_position) = cursor.position_in(bounds) {
                 let (_, rounded_aggregation) =
                     chart.draw_crosshair(frame, theme, bounds_size, cursor_position);
 
-                draw_crosshair_tooltip(&self.data_source, frame, palette, rounded_aggregation);
+                let at_interval = chart.replay_cutoff.map_or(rounded_aggregation, |cutoff| {
+                    rounded_aggregation.min(cutoff)
+                });
+
+                draw_crosshair_tooltip(&self.data_source, frame, palette, at_interval);
+
+                if let Some(anomalies) = self.anomalies.get(&at_interval) {
+                    draw_anomaly_tooltip(anomalies, frame, palette);
+                }
             }
         });
 
-        vec![klines, crosshair]
+        let mut geometries = vec![klines, crosshair];
+
+        if self.visual_config.session_delta {
+            let volume = match &self.data_source {
+                PlotData::TimeBased(timeseries) => {
+                    session_volume(timeseries.datapoints.values().map(|dp| &dp.kline))
+                }
+                PlotData::TickBased(tick_aggr) => {
+                    session_volume(tick_aggr.datapoints.iter().map(|dp| &dp.kline))
+                }
+            };
+
+            geometries.push(draw_session_delta(renderer, bounds_size, palette, volume));
+        }
+
+        geometries
     }
 
     fn mouse_interaction(
@@ -1066,9 +1497,23 @@ impl canvas::Program<Message> for KlineChart {
         match interaction {
             Interaction::Panning { .. } => mouse::Interaction::Grabbing,
             Interaction::Zoomin { .. } => mouse::Interaction::ZoomIn,
+            Interaction::DraggingAlert { .. } => mouse::Interaction::ResizingVertically,
+            Interaction::PlacingDrawing { .. } => mouse::Interaction::Crosshair,
+            Interaction::MovingDrawing { .. } => mouse::Interaction::Grabbing,
             Interaction::None => {
-                if cursor.is_over(bounds) {
-                    mouse::Interaction::Crosshair
+                if cursor
+                    .position_in(bounds)
+                    .is_some_and(|position| self.chart.alert_line_at(position.y).is_some())
+                {
+                    mouse::Interaction::ResizingVertically
+                } else if self.chart.drawing_tool.is_none()
+                    && cursor
+                        .position_in(bounds)
+                        .is_some_and(|position| self.chart.drawing_at(position).is_some())
+                {
+                    mouse::Interaction::Grab
+                } else if cursor.is_over(bounds) {
+                    mouse::Interaction::Crosshair
                 } else {
                     mouse::Interaction::default()
                 }
@@ -1158,6 +1603,41 @@ fn draw_candle_dp(
     );
 }
 
+/// Splits the candle's width below its low into bought and sold volume. Venues that only
+/// report total kline volume mark the buy side negative, those candles get no strip
+fn draw_volume_ratio_strip(
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(f32) -> f32,
+    candle_width: f32,
+    scaling: f32,
+    palette: &Extended,
+    x_position: f32,
+    kline: &Kline,
+) {
+    let (buy_volume, sell_volume) = kline.volume;
+    let total = buy_volume + sell_volume;
+
+    if buy_volume < 0.0 || total <= 0.0 {
+        return;
+    }
+
+    let height = 3.0 / scaling;
+    let top = price_to_y(kline.low) + 2.0 / scaling;
+    let left = x_position - (candle_width / 2.0);
+    let buy_width = candle_width * (buy_volume / total);
+
+    frame.fill_rectangle(
+        Point::new(left, top),
+        Size::new(buy_width, height),
+        palette.success.base.color,
+    );
+    frame.fill_rectangle(
+        Point::new(left + buy_width, top),
+        Size::new(candle_width - buy_width, height),
+        palette.danger.base.color,
+    );
+}
+
 fn render_data_source<F>(
     data_source: &PlotData<KlineDataPoint>,
     frame: &mut canvas::Frame,
@@ -1202,6 +1682,739 @@ fn render_data_source<F>(
     }
 }
 
+fn draw_moving_averages(
+    data_source: &PlotData<KlineDataPoint>,
+    time_based: &[(MovingAverage, BTreeMap<u64, f32>)],
+    configured: &[Option<MovingAverage>],
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(f32) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    (earliest, latest): (u64, u64),
+) {
+    // tick bars are keyed by their distance to the latest one, so they shift with every new bar
+    let tick_based: Vec<(MovingAverage, BTreeMap<u64, f32>)> = match data_source {
+        PlotData::TickBased(tick_aggr) => {
+            let last_index = tick_aggr.datapoints.len().saturating_sub(1) as u64;
+            let closes = tick_aggr
+                .datapoints
+                .iter()
+                .enumerate()
+                .map(|(index, dp)| (index as u64, dp.kline.close))
+                .collect::<BTreeMap<u64, f32>>();
+
+            configured
+                .iter()
+                .flatten()
+                .map(|ma| {
+                    let series = ma
+                        .series(&closes)
+                        .into_iter()
+                        .map(|(index, value)| (last_index - index, value))
+                        .collect();
+
+                    (*ma, series)
+                })
+                .collect()
+        }
+        PlotData::TimeBased(_) => vec![],
+    };
+
+    let series = match data_source {
+        PlotData::TickBased(_) => &tick_based,
+        PlotData::TimeBased(_) => time_based,
+    };
+
+    for (ma, values) in series {
+        // one point past each edge, so the line runs off the visible region
+        let points = values
+            .range(..earliest)
+            .next_back()
+            .into_iter()
+            .chain(values.range(earliest..=latest))
+            .chain(values.range(latest.saturating_add(1)..).next());
+
+        let line = Path::new(|builder| {
+            for (index, (key, value)) in points.enumerate() {
+                let position = Point::new(interval_to_x(*key), price_to_y(*value));
+
+                if index == 0 {
+                    builder.move_to(position);
+                } else {
+                    builder.line_to(position);
+                }
+            }
+        });
+
+        let (r, g, b) = ma.color.rgb8();
+
+        frame.stroke(
+            &line,
+            Stroke::with_color(
+                Stroke {
+                    width: 1.0,
+                    ..Default::default()
+                },
+                iced::Color::from_rgb8(r, g, b),
+            ),
+        );
+    }
+}
+
+fn draw_bollinger_bands(
+    data_source: &PlotData<KlineDataPoint>,
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(f32) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    (earliest, latest): (u64, u64),
+    palette: &Extended,
+    studies: &[FootprintStudy],
+) {
+    let Some((period, std_dev)) = studies.iter().find_map(|study| {
+        if let FootprintStudy::BollingerBands { period, std_dev } = study {
+            Some((*period, *std_dev as f32 / 10.0))
+        } else {
+            None
+        }
+    }) else {
+        return;
+    };
+
+    let bands = match data_source {
+        PlotData::TickBased(tick_aggr) => {
+            let last_index = tick_aggr.datapoints.len().saturating_sub(1) as u64;
+            let closes = tick_aggr
+                .datapoints
+                .iter()
+                .enumerate()
+                .map(|(index, dp)| (index as u64, dp.kline.close))
+                .collect::<BTreeMap<u64, f32>>();
+
+            data::metrics::bollinger_bands(&closes, period, std_dev)
+                .into_iter()
+                .map(|(index, bands)| (last_index - index, bands))
+                .collect::<BTreeMap<u64, (f32, f32, f32)>>()
+        }
+        PlotData::TimeBased(timeseries) => {
+            // only the visible bars and the window behind the first of them
+            let closes = timeseries
+                .datapoints
+                .range(..earliest)
+                .rev()
+                .take(period)
+                .chain(timeseries.datapoints.range(earliest..))
+                .map(|(time, dp)| (*time, dp.kline.close))
+                .collect::<BTreeMap<u64, f32>>();
+
+            data::metrics::bollinger_bands(&closes, period, std_dev)
+        }
+    };
+
+    // one point past each edge, so the bands run off the visible region
+    let visible = bands
+        .range(..earliest)
+        .next_back()
+        .into_iter()
+        .chain(bands.range(earliest..=latest))
+        .chain(bands.range(latest.saturating_add(1)..).next())
+        .map(|(key, (lower, middle, upper))| {
+            (
+                interval_to_x(*key),
+                price_to_y(*lower),
+                price_to_y(*middle),
+                price_to_y(*upper),
+            )
+        })
+        .collect::<Vec<(f32, f32, f32, f32)>>();
+
+    if visible.len() < 2 {
+        return;
+    }
+
+    let line = |y: fn(&(f32, f32, f32, f32)) -> f32| {
+        Path::new(|builder| {
+            for (index, point) in visible.iter().enumerate() {
+                let position = Point::new(point.0, y(point));
+
+                if index == 0 {
+                    builder.move_to(position);
+                } else {
+                    builder.line_to(position);
+                }
+            }
+        })
+    };
+
+    let fill = Path::new(|builder| {
+        for (index, (x, _, _, upper)) in visible.iter().enumerate() {
+            if index == 0 {
+                builder.move_to(Point::new(*x, *upper));
+            } else {
+                builder.line_to(Point::new(*x, *upper));
+            }
+        }
+        for (x, lower, _, _) in visible.iter().rev() {
+            builder.line_to(Point::new(*x, *lower));
+        }
+        builder.close();
+    });
+
+    let color = palette.secondary.strong.color;
+    let stroke = |alpha: f32| {
+        Stroke::with_color(
+            Stroke {
+                width: 1.0,
+                ..Default::default()
+            },
+            color.scale_alpha(alpha),
+        )
+    };
+
+    frame.fill(&fill, color.scale_alpha(0.08));
+    frame.stroke(&line(|point| point.3), stroke(0.7));
+    frame.stroke(&line(|point| point.1), stroke(0.7));
+    frame.stroke(&line(|point| point.2), stroke(0.4));
+}
+
+/// Bought and sold volume of the day stacked in one bar, the net delta in a bar under it,
+/// drawn in the top left corner regardless of the chart's scale
+fn draw_session_delta(
+    renderer: &Renderer,
+    bounds_size: Size,
+    palette: &Extended,
+    (buy_volume, sell_volume): (f32, f32),
+) -> Geometry {
+    let mut frame = canvas::Frame::new(renderer, bounds_size);
+
+    let text_size = style::chart_text_size();
+    let width = (bounds_size.width - 16.0).clamp(0.0, 220.0);
+    let bar_height = 5.0;
+    let origin = Point::new(8.0, 8.0);
+
+    let total_volume = buy_volume + sell_volume;
+    let delta = buy_volume - sell_volume;
+
+    frame.fill_rectangle(
+        origin,
+        Size::new(width, bar_height * 2.0 + 2.0),
+        palette.background.weakest.color.scale_alpha(0.6),
+    );
+
+    if total_volume > 0.0 {
+        let buy_width = width * (buy_volume / total_volume);
+
+        frame.fill_rectangle(
+            origin,
+            Size::new(buy_width, bar_height),
+            palette.success.base.color,
+        );
+        frame.fill_rectangle(
+            Point::new(origin.x + buy_width, origin.y),
+            Size::new(width - buy_width, bar_height),
+            palette.danger.base.color,
+        );
+
+        // the net bar grows out of the middle, to the right when buyers lead
+        let delta_width = (width / 2.0) * (delta.abs() / total_volume);
+        let mid_x = origin.x + width / 2.0;
+        let (delta_x, delta_color) = if delta >= 0.0 {
+            (mid_x, palette.success.strong.color)
+        } else {
+            (mid_x - delta_width, palette.danger.strong.color)
+        };
+
+        frame.fill_rectangle(
+            Point::new(delta_x, origin.y + bar_height + 2.0),
+            Size::new(delta_width, bar_height),
+            delta_color,
+        );
+    }
+
+    let sign = if delta >= 0.0 { "+" } else { "-" };
+    frame.fill_text(canvas::Text {
+        content: format!(
+            "Session  B {}  S {}  Δ {sign}{}",
+            abbr_large_numbers(buy_volume),
+            abbr_large_numbers(sell_volume),
+            abbr_large_numbers(delta.abs()),
+        ),
+        position: Point::new(origin.x, origin.y + bar_height * 2.0 + 6.0),
+        size: iced::Pixels(text_size),
+        color: palette.background.base.text,
+        font: style::chart_font(),
+        ..canvas::Text::default()
+    });
+
+    frame.into_geometry()
+}
+
+/// Tick marks of the footprint studies flagging single price levels of a bar
+#[derive(Clone, Copy)]
+struct AuctionMarkers {
+    unfinished_auctions: bool,
+    high_volume_nodes: Option<usize>,
+}
+
+impl AuctionMarkers {
+    fn from_studies(studies: &[FootprintStudy]) -> Self {
+        AuctionMarkers {
+            unfinished_auctions: studies
+                .iter()
+                .any(|study| matches!(study, FootprintStudy::UnfinishedAuction)),
+            high_volume_nodes: studies.iter().find_map(|study| {
+                if let FootprintStudy::HighVolumeNodes { threshold } = study {
+                    Some(*threshold)
+                } else {
+                    None
+                }
+            }),
+        }
+    }
+}
+
+/// Short ticks left of the candle body, at the bar's unfinished extremes and its high volume
+/// nodes
+fn draw_auction_markers(
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(f32) -> f32,
+    x_position: f32,
+    candle_width: f32,
+    cell_height: f32,
+    footprint: &KlineTrades,
+    markers: AuctionMarkers,
+    palette: &Extended,
+) {
+    let tick_height = cell_height.min(1.0);
+    let tick_width = candle_width * 0.75;
+    let start_x = x_position - (candle_width / 8.0) - tick_width;
+
+    let mut draw_tick = |price: f32, color: iced::Color| {
+        frame.fill_rectangle(
+            Point::new(start_x, price_to_y(price) - tick_height / 2.0),
+            Size::new(tick_width, tick_height),
+            color,
+        );
+    };
+
+    if let Some(threshold) = markers.high_volume_nodes {
+        for price in footprint.high_volume_nodes(threshold) {
+            draw_tick(price, palette.primary.strong.color);
+        }
+    }
+
+    if markers.unfinished_auctions {
+        let (high, low) = footprint.unfinished_auctions();
+
+        for price in high.into_iter().chain(low) {
+            draw_tick(price, palette.warning.strong.color);
+        }
+    }
+}
+
+/// A triangle above the high of bearish divergences and below the low of bullish ones
+fn draw_delta_divergences(
+    data_source: &PlotData<KlineDataPoint>,
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(f32) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    (earliest, latest): (u64, u64),
+    cell_width: f32,
+    palette: &Extended,
+    studies: &[FootprintStudy],
+) {
+    let Some((lookback, threshold)) = studies.iter().find_map(|study| {
+        if let FootprintStudy::DeltaDivergence {
+            lookback,
+            threshold,
+        } = study
+        {
+            Some((*lookback, *threshold))
+        } else {
+            None
+        }
+    }) else {
+        return;
+    };
+
+    // klines without a taker side split carry -1.0 as buy volume, they add no delta
+    let bar = |kline: &Kline| {
+        let (buy, sell) = kline.volume;
+        let delta = if buy == -1.0 { 0.0 } else { buy - sell };
+        (kline.high, kline.low, delta)
+    };
+
+    let (divergences, extremes) = match data_source {
+        PlotData::TickBased(tick_aggr) => {
+            let last_index = tick_aggr.datapoints.len().saturating_sub(1) as u64;
+            let bars = tick_aggr
+                .datapoints
+                .iter()
+                .enumerate()
+                .map(|(index, dp)| (index as u64, bar(&dp.kline)))
+                .collect::<BTreeMap<u64, (f32, f32, f32)>>();
+
+            (
+                data::metrics::delta_divergences(&bars, lookback, threshold)
+                    .into_iter()
+                    .map(|(index, divergence)| (last_index - index, divergence))
+                    .collect::<BTreeMap<_, _>>(),
+                bars.into_iter()
+                    .map(|(index, (high, low, _))| (last_index - index, (high, low)))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+        }
+        PlotData::TimeBased(timeseries) => {
+            // only the visible bars and the lookback behind the first of them
+            let bars = timeseries
+                .datapoints
+                .range(..earliest)
+                .rev()
+                .take(lookback)
+                .chain(timeseries.datapoints.range(earliest..=latest))
+                .map(|(time, dp)| (*time, bar(&dp.kline)))
+                .collect::<BTreeMap<u64, (f32, f32, f32)>>();
+
+            (
+                data::metrics::delta_divergences(&bars, lookback, threshold),
+                bars.into_iter()
+                    .map(|(time, (high, low, _))| (time, (high, low)))
+                    .collect(),
+            )
+        }
+    };
+
+    let half_width = (cell_width * 0.2).max(2.0);
+    let height = half_width * 1.5;
+
+    for (key, divergence) in divergences.range(earliest..=latest) {
+        let Some((high, low)) = extremes.get(key) else {
+            continue;
+        };
+        let x = interval_to_x(*key);
+
+        let (tip_y, base_y, color) = match divergence {
+            data::metrics::Divergence::Bearish => {
+                let tip_y = price_to_y(*high) - half_width;
+                (tip_y, tip_y - height, palette.danger.base.color)
+            }
+            data::metrics::Divergence::Bullish => {
+                let tip_y = price_to_y(*low) + half_width;
+                (tip_y, tip_y + height, palette.success.base.color)
+            }
+        };
+
+        let triangle = Path::new(|builder| {
+            builder.move_to(Point::new(x, tip_y));
+            builder.line_to(Point::new(x - half_width, base_y));
+            builder.line_to(Point::new(x + half_width, base_y));
+            builder.close();
+        });
+
+        frame.fill(&triangle, color);
+    }
+}
+
+fn draw_vwap(
+    data_source: &PlotData<KlineDataPoint>,
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(f32) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    (earliest, latest): (u64, u64),
+    palette: &Extended,
+    studies: &[FootprintStudy],
+) {
+    let Some((session, bands)) = studies.iter().find_map(|study| {
+        if let FootprintStudy::Vwap { session, bands } = study {
+            Some((*session, *bands))
+        } else {
+            None
+        }
+    }) else {
+        return;
+    };
+
+    // sessions accumulate from their first bar, so the whole history goes in
+    let series = match data_source {
+        PlotData::TickBased(tick_aggr) => {
+            let last_index = tick_aggr.datapoints.len().saturating_sub(1);
+
+            vwap_series(
+                tick_aggr
+                    .datapoints
+                    .iter()
+                    .enumerate()
+                    .map(|(index, dp)| ((last_index - index) as u64, &dp.kline, &dp.footprint)),
+                session,
+            )
+        }
+        PlotData::TimeBased(timeseries) => vwap_series(
+            timeseries
+                .datapoints
+                .iter()
+                .map(|(time, dp)| (*time, &dp.kline, &dp.footprint)),
+            session,
+        ),
+    };
+
+    let is_visible = |key: u64| key >= earliest && key <= latest;
+
+    // one point past each edge, so the line runs off the visible region
+    let Some(first) = series.iter().position(|(key, _)| is_visible(*key)) else {
+        return;
+    };
+    let last = series
+        .iter()
+        .rposition(|(key, _)| is_visible(*key))
+        .unwrap_or(first);
+    let visible = &series[first.saturating_sub(1)..(last + 2).min(series.len())];
+
+    let line = |offset: f32| {
+        Path::new(|builder| {
+            for (index, (key, point)) in visible.iter().enumerate() {
+                let position = Point::new(
+                    interval_to_x(*key),
+                    price_to_y(point.vwap + offset * point.std_dev),
+                );
+
+                if index == 0 || point.is_session_start {
+                    builder.move_to(position);
+                } else {
+                    builder.line_to(position);
+                }
+            }
+        })
+    };
+
+    let color = palette.primary.strong.color;
+
+    if bands {
+        for (deviations, alpha) in [(1.0, 0.6), (2.0, 0.35)] {
+            let band_stroke = Stroke::with_color(
+                Stroke {
+                    width: 1.0,
+                    ..Default::default()
+                },
+                color.scale_alpha(alpha),
+            );
+
+            frame.stroke(&line(deviations), band_stroke);
+            frame.stroke(&line(-deviations), band_stroke);
+        }
+    }
+
+    frame.stroke(
+        &line(0.0),
+        Stroke::with_color(
+            Stroke {
+                width: 1.5,
+                ..Default::default()
+            },
+            color,
+        ),
+    );
+}
+
+/// Collapsed market profile of each session overlapping the visible range, drawn from the
+/// session's start with one letter wide column per bracket
+fn draw_tpo_sessions(
+    frame: &mut canvas::Frame,
+    timeseries: &TimeSeries<KlineDataPoint>,
+    timeframe: Timeframe,
+    (earliest, latest): (u64, u64),
+    price_to_y: impl Fn(f32) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    cell_width: f32,
+    cell_height: f32,
+    scaling: f32,
+    tick_size: f32,
+    palette: &Extended,
+) {
+    const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+    let sessions = tpo_sessions(
+        timeseries
+            .datapoints
+            .range(earliest.saturating_sub(DAY_MS)..=latest)
+            .map(|(_, dp)| (&dp.kline, &dp.footprint)),
+        tick_size,
+    );
+
+    let letter_width = cell_width * (TPO_BRACKET_MS as f32 / timeframe.to_milliseconds() as f32);
+    let show_letters = letter_width * scaling >= 8.0 && cell_height * scaling >= 8.0;
+    let text_size = (cell_height * scaling).min(style::chart_text_size() + 2.0) / scaling;
+
+    let (inside_color, outside_color) = (palette.primary.base.color, palette.secondary.weak.color);
+    let poc_color = palette.warning.strong.color;
+
+    for session in sessions
+        .iter()
+        .filter(|session| session.start + DAY_MS > earliest && session.start <= latest)
+    {
+        let start_x = interval_to_x(session.start) - cell_width / 2.0;
+
+        let poc = session.profile.poc();
+        let value_area = session.profile.value_area(TPO_VALUE_AREA);
+
+        for (price, brackets) in &session.levels {
+            let is_value = value_area.is_some_and(|(val, vah)| price.0 >= val && price.0 <= vah);
+            let color = if poc == Some(price.0) {
+                poc_color
+            } else if is_value {
+                inside_color
+            } else {
+                outside_color
+            };
+
+            let y_position = price_to_y(price.0);
+
+            for (column, bracket) in brackets.iter().enumerate() {
+                let x_position = start_x + column as f32 * letter_width;
+
+                if show_letters {
+                    draw_cluster_text(
+                        frame,
+                        &tpo_letter(*bracket).to_string(),
+                        Point::new(x_position + letter_width / 2.0, y_position),
+                        text_size,
+                        color,
+                        Alignment::Center,
+                        Alignment::Center,
+                    );
+                } else {
+                    frame.fill_rectangle(
+                        Point::new(x_position, y_position - cell_height * 0.45),
+                        Size::new(letter_width * 0.9, cell_height * 0.9),
+                        color.scale_alpha(0.7),
+                    );
+                }
+            }
+        }
+
+        let session_width = session.profile.max_volume * letter_width;
+        let mut level_line = |price: f32, color: iced::Color| {
+            frame.stroke(
+                &Path::line(
+                    Point::new(start_x, price_to_y(price)),
+                    Point::new(start_x + session_width, price_to_y(price)),
+                ),
+                Stroke::with_color(
+                    Stroke {
+                        width: 1.0,
+                        ..Default::default()
+                    },
+                    color,
+                ),
+            );
+        };
+
+        // along the outer edges of the value area's rows
+        if let Some((val, vah)) = value_area {
+            level_line(vah + tick_size / 2.0, inside_color);
+            level_line(val - tick_size / 2.0, inside_color);
+        }
+    }
+}
+
+/// Histogram of the visible bars' volume per price level, growing leftwards from the right edge
+fn draw_visible_volume_profile(
+    data_source: &PlotData<KlineDataPoint>,
+    frame: &mut canvas::Frame,
+    region: &Rectangle,
+    price_to_y: impl Fn(f32) -> f32,
+    (earliest, latest): (u64, u64),
+    cell_height: f32,
+    palette: &Extended,
+    studies: &[FootprintStudy],
+) {
+    let Some((show_poc, value_area)) = studies.iter().find_map(|study| {
+        if let FootprintStudy::VolumeProfile { poc, value_area } = study {
+            Some((*poc, *value_area))
+        } else {
+            None
+        }
+    }) else {
+        return;
+    };
+
+    let profile = match data_source {
+        PlotData::TickBased(tick_aggr) => VolumeProfile::from_footprints(
+            tick_aggr
+                .datapoints
+                .iter()
+                .rev()
+                .enumerate()
+                .filter(|(index, _)| (earliest as usize..=latest as usize).contains(index))
+                .map(|(_, dp)| &dp.footprint),
+        ),
+        PlotData::TimeBased(timeseries) => {
+            if latest < earliest {
+                return;
+            }
+            VolumeProfile::from_footprints(
+                timeseries
+                    .datapoints
+                    .range(earliest..=latest)
+                    .map(|(_, dp)| &dp.footprint),
+            )
+        }
+    };
+
+    if profile.max_volume <= 0.0 {
+        return;
+    }
+
+    let right_edge = region.x + region.width;
+    let area_width = region.width * 0.2;
+    let bar_height = cell_height * 0.9;
+
+    for (price, (buy_qty, sell_qty)) in &profile.levels {
+        let y_position = price_to_y(price.0) - bar_height / 2.0;
+
+        let buy_width = (buy_qty / profile.max_volume) * area_width;
+        let sell_width = (sell_qty / profile.max_volume) * area_width;
+
+        frame.fill_rectangle(
+            Point::new(right_edge - buy_width - sell_width, y_position),
+            Size::new(sell_width, bar_height),
+            palette.danger.weak.color.scale_alpha(0.4),
+        );
+        frame.fill_rectangle(
+            Point::new(right_edge - buy_width, y_position),
+            Size::new(buy_width, bar_height),
+            palette.success.weak.color.scale_alpha(0.4),
+        );
+    }
+
+    let mut level_line = |price: f32, color: iced::Color| {
+        frame.stroke(
+            &Path::line(
+                Point::new(right_edge - area_width, price_to_y(price)),
+                Point::new(right_edge, price_to_y(price)),
+            ),
+            Stroke::with_color(
+                Stroke {
+                    width: 1.0,
+                    ..Default::default()
+                },
+                color,
+            ),
+        );
+    };
+
+    if let Some(percent) = value_area {
+        if let Some((val, vah)) = profile.value_area(percent) {
+            let color = palette.secondary.strong.color;
+            level_line(vah, color);
+            level_line(val, color);
+        }
+    }
+
+    if show_poc {
+        if let Some(poc) = profile.poc() {
+            level_line(poc, palette.warning.strong.color);
+        }
+    }
+}
+
 fn draw_all_npocs(
     data_source: &PlotData<KlineDataPoint>,
     frame: &mut canvas::Frame,
@@ -1602,11 +2815,85 @@ fn draw_cluster_text(
         color,
         align_x: align_x.into(),
         align_y: align_y.into(),
-        font: style::AZERET_MONO,
+        font: style::chart_font(),
         ..canvas::Text::default()
     });
 }
 
+fn anomaly_color(kind: anomaly::AnomalyKind, palette: &Extended) -> iced::Color {
+    match kind {
+        anomaly::AnomalyKind::VolumeSpike => palette.primary.strong.color,
+        anomaly::AnomalyKind::SpreadBlowout => palette.warning.strong.color,
+        anomaly::AnomalyKind::OiDump => palette.danger.strong.color,
+    }
+}
+
+/// A dot per anomaly stacked above the bar's high, described in the crosshair tooltip
+fn draw_anomaly_markers(
+    data_source: &PlotData<KlineDataPoint>,
+    anomalies: &BTreeMap<u64, Vec<Anomaly>>,
+    frame: &mut canvas::Frame,
+    price_to_y: impl Fn(f32) -> f32,
+    interval_to_x: impl Fn(u64) -> f32,
+    (earliest, latest): (u64, u64),
+    scaling: f32,
+    palette: &Extended,
+) {
+    let PlotData::TimeBased(timeseries) = data_source else {
+        return;
+    };
+
+    let radius = 3.0 / scaling;
+
+    for (time, bar_anomalies) in anomalies.range(earliest..=latest) {
+        let Some(dp) = timeseries.datapoints.get(time) else {
+            continue;
+        };
+
+        let x = interval_to_x(*time);
+        let top = price_to_y(dp.kline.high) - 4.0 * radius;
+
+        for (index, anomaly) in bar_anomalies.iter().enumerate() {
+            let center = Point::new(x, top - index as f32 * 3.0 * radius);
+
+            frame.fill(
+                &Path::circle(center, radius),
+                anomaly_color(anomaly.kind, palette),
+            );
+        }
+    }
+}
+
+/// Lists the hovered bar's anomalies under the OHLC tooltip
+fn draw_anomaly_tooltip(anomalies: &[Anomaly], frame: &mut canvas::Frame, palette: &Extended) {
+    let text_size = style::chart_text_size();
+    let line_height = text_size + 4.0;
+
+    let width = anomalies
+        .iter()
+        .map(|anomaly| anomaly.to_string().len() as f32 * (text_size * 0.7))
+        .fold(0.0, f32::max);
+
+    let position = Point::new(8.0, 8.0 + line_height);
+
+    frame.fill_rectangle(
+        position,
+        Size::new(width, line_height * anomalies.len() as f32),
+        palette.background.weakest.color.scale_alpha(0.9),
+    );
+
+    for (index, anomaly) in anomalies.iter().enumerate() {
+        frame.fill_text(canvas::Text {
+            content: anomaly.to_string(),
+            position: Point::new(position.x, position.y + index as f32 * line_height),
+            size: iced::Pixels(text_size),
+            color: anomaly_color(anomaly.kind, palette),
+            font: style::chart_font(),
+            ..canvas::Text::default()
+        });
+    }
+}
+
 fn draw_crosshair_tooltip(
     data: &PlotData<KlineDataPoint>,
     frame: &mut canvas::Frame,
@@ -1632,7 +2919,7 @@ fn draw_crosshair_tooltip(
                 }
             }),
         PlotData::TickBased(tick_aggr) => {
-            let index = (at_interval / u64::from(tick_aggr.interval.0)) as usize;
+            let index = (at_interval / tick_aggr.interval.x_step()) as usize;
             if index < tick_aggr.datapoints.len() {
                 Some(&tick_aggr.datapoints[tick_aggr.datapoints.len() - 1 - index].kline)
             } else {
@@ -1663,9 +2950,11 @@ fn draw_crosshair_tooltip(
             (&format!("{:+.2}%", change_pct), change_color, true),
         ];
 
+        let text_size = style::chart_text_size();
+
         let total_width: f32 = segments
             .iter()
-            .map(|(s, _, _)| s.len() as f32 * (TEXT_SIZE * 0.8))
+            .map(|(s, _, _)| s.len() as f32 * (text_size * 0.8))
             .sum();
 
         let position = Point::new(8.0, 8.0);
@@ -1674,7 +2963,7 @@ fn draw_crosshair_tooltip(
             x: position.x,
             y: position.y,
             width: total_width,
-            height: 16.0,
+            height: text_size + 4.0,
         };
 
         frame.fill_rectangle(
@@ -1688,12 +2977,12 @@ fn draw_crosshair_tooltip(
             frame.fill_text(canvas::Text {
                 content: text.to_string(),
                 position: Point::new(x, position.y),
-                size: iced::Pixels(12.0),
+                size: iced::Pixels(text_size),
                 color: seg_color,
-                font: style::AZERET_MONO,
+                font: style::chart_font(),
                 ..canvas::Text::default()
             });
-            x += text.len() as f32 * 8.0;
+            x += text.len() as f32 * (text_size * 2.0 / 3.0);
             x += if is_value { 6.0 } else { 2.0 };
         }
     }
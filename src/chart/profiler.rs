@@ -0,0 +1,62 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// How many of the latest redraws each label is summarized over
+const SAMPLE_WINDOW: usize = 120;
+
+static SAMPLES: LazyLock<Mutex<BTreeMap<&'static str, VecDeque<Duration>>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+/// Records the time until it's dropped under its label, meant to be held for the
+/// duration of a geometry cache's draw closure so only actual redraws get counted
+pub struct Timer {
+    label: &'static str,
+    started: Instant,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+
+        if let Ok(mut samples) = SAMPLES.lock() {
+            let samples = samples.entry(self.label).or_default();
+            if samples.len() == SAMPLE_WINDOW {
+                samples.pop_front();
+            }
+            samples.push_back(elapsed);
+        }
+    }
+}
+
+pub fn time(label: &'static str) -> Timer {
+    Timer {
+        label,
+        started: Instant::now(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub label: &'static str,
+    pub samples: usize,
+    pub avg: Duration,
+    pub max: Duration,
+}
+
+pub fn summaries() -> Vec<Summary> {
+    let Ok(samples) = SAMPLES.lock() else {
+        return vec![];
+    };
+
+    samples
+        .iter()
+        .filter(|(_, durations)| !durations.is_empty())
+        .map(|(label, durations)| Summary {
+            label: *label,
+            samples: durations.len(),
+            avg: durations.iter().sum::<Duration>() / durations.len() as u32,
+            max: durations.iter().max().copied().unwrap_or_default(),
+        })
+        .collect()
+}
@@ -1,7 +1,7 @@
 pub mod linear;
 pub mod timeseries;
 
-use crate::{chart::TEXT_SIZE, style::AZERET_MONO};
+use crate::style::{chart_font, chart_text_size};
 
 use super::{Basis, Interaction, Message};
 use data::{chart::Autoscale, util::round_to_tick};
@@ -68,7 +68,7 @@ impl AxisLabel {
         is_crosshair: bool,
         palette: &Extended,
     ) -> Self {
-        let content_width = text_content.len() as f32 * (TEXT_SIZE / 2.6);
+        let content_width = text_content.len() as f32 * (chart_text_size() / 2.6);
 
         let rect = Rectangle {
             x: center_x_position - content_width,
@@ -89,7 +89,7 @@ impl AxisLabel {
             } else {
                 palette.background.base.text
             },
-            text_size: TEXT_SIZE,
+            text_size: chart_text_size(),
         };
 
         AxisLabel::X {
@@ -150,7 +150,7 @@ impl AxisLabel {
                     color: label.text_color,
                     align_y: Alignment::Center.into(),
                     align_x: Alignment::Center.into(),
-                    font: AZERET_MONO,
+                    font: chart_font(),
                     ..canvas::Text::default()
                 };
 
@@ -175,7 +175,7 @@ impl AxisLabel {
                         position: Point::new(bounds.x + 4.0, bounds.y + 2.0),
                         color: value_label.text_color,
                         size: value_label.text_size.into(),
-                        font: AZERET_MONO,
+                        font: chart_font(),
                         ..canvas::Text::default()
                     };
 
@@ -183,10 +183,13 @@ impl AxisLabel {
 
                     let timer_label = canvas::Text {
                         content: timer_label.content.clone(),
-                        position: Point::new(bounds.x + 4.0, bounds.y + 15.0),
+                        position: Point::new(
+                            bounds.x + 4.0,
+                            bounds.y + timer_label.text_size + 4.0,
+                        ),
                         color: timer_label.text_color,
                         size: timer_label.text_size.into(),
-                        font: AZERET_MONO,
+                        font: chart_font(),
                         ..canvas::Text::default()
                     };
 
@@ -197,7 +200,7 @@ impl AxisLabel {
                         position: Point::new(bounds.x + 4.0, bounds.y + 4.0),
                         color: value_label.text_color,
                         size: value_label.text_size.into(),
-                        font: AZERET_MONO,
+                        font: chart_font(),
                         ..canvas::Text::default()
                     };
 
@@ -240,7 +243,7 @@ impl AxisLabelsX<'_> {
         palette: &Extended,
     ) -> Option<AxisLabel> {
         match self.basis {
-            Basis::Tick(interval) => {
+            Basis::Tick(_) | Basis::Range(_) => {
                 let Some(interval_keys) = &self.interval_keys else {
                     return None;
                 };
@@ -267,9 +270,10 @@ impl AxisLabelsX<'_> {
                 let array_index = last_index - offset;
 
                 if let Some(timestamp) = interval_keys.get(array_index) {
-                    let text_content = self
-                        .timezone
-                        .format_crosshair_timestamp(*timestamp as i64, interval.0.into());
+                    let text_content = self.timezone.format_crosshair_timestamp(
+                        *timestamp as i64,
+                        self.basis.trade_bar().map_or(1, |bar| bar.x_step()),
+                    );
 
                     return Some(AxisLabel::new_x(
                         snap_x,
@@ -347,7 +351,7 @@ impl AxisLabelsX<'_> {
                     self.max.saturating_add(diff)
                 }
             }
-            Basis::Tick(_) => {
+            Basis::Tick(_) | Basis::Range(_) => {
                 let tick = -(x / self.cell_width);
                 tick.round() as u64
             }
@@ -439,11 +443,11 @@ impl canvas::Program<Message> for AxisLabelsX<'_> {
         let labels = self.labels_cache.draw(renderer, bounds.size(), |frame| {
             let region = self.visible_region(frame.size());
 
-            let x_labels_can_fit = (bounds.width / (TEXT_SIZE * 16.0)) as i32;
+            let x_labels_can_fit = (bounds.width / (chart_text_size() * 16.0)) as i32;
             let mut labels: Vec<AxisLabel> = Vec::with_capacity(x_labels_can_fit as usize + 1);
 
             match self.basis {
-                Basis::Tick(_) => {
+                Basis::Tick(_) | Basis::Range(_) => {
                     if let Some(interval_keys) = &self.interval_keys {
                         if !interval_keys.is_empty() {
                             let x_min_region = region.x;
@@ -560,6 +564,7 @@ pub struct AxisLabelsY<'a> {
     pub scaling: f32,
     pub min: f32,
     pub last_price: Option<linear::PriceInfoLabel>,
+    pub show_countdown: bool,
     pub tick_size: f32,
     pub decimals: usize,
     pub cell_height: f32,
@@ -658,7 +663,7 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
         bounds: Rectangle,
         cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
-        let text_size = 12.0;
+        let text_size = chart_text_size();
         let palette = theme.extended_palette();
 
         let labels = self.labels_cache.draw(renderer, bounds.size(), |frame| {
@@ -681,11 +686,11 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
             // Last price (priority 2)
             if let Some(label) = self.last_price {
                 let candle_close_label = match self.basis {
-                    Basis::Time(timeframe) => {
+                    Basis::Time(timeframe) if self.show_countdown => {
                         let interval = timeframe.to_milliseconds();
 
                         let current_time = chrono::Utc::now().timestamp_millis() as u64;
-                        let next_kline_open = (current_time / interval + 1) * interval;
+                        let next_kline_open = timeframe.open_time(current_time) + interval;
 
                         let remaining_seconds = (next_kline_open - current_time) / 1000;
 
@@ -708,13 +713,13 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
                                 } else {
                                     Color::WHITE.scale_alpha(0.8)
                                 },
-                                text_size: 11.0,
+                                text_size: text_size - 1.0,
                             })
                         } else {
                             None
                         }
                     }
-                    Basis::Tick(_) => None,
+                    Basis::Time(_) | Basis::Tick(_) | Basis::Range(_) => None,
                 };
 
                 let (price, color) = label.get_with_color(palette);
@@ -733,7 +738,7 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
                             palette.primary.strong.text
                         }
                     },
-                    text_size: 12.0,
+                    text_size,
                 };
 
                 let y_pos = bounds.height - ((price - lowest) / range * bounds.height);
@@ -758,7 +763,7 @@ impl canvas::Program<Message> for AxisLabelsY<'_> {
                     content: format!("{:.*}", self.decimals, rounded_price),
                     background_color: Some(palette.secondary.base.color),
                     text_color: palette.secondary.base.text,
-                    text_size: 12.0,
+                    text_size,
                 };
 
                 all_labels.push(AxisLabel::Y {
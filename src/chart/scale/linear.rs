@@ -3,6 +3,11 @@ use data::util::abbr_large_numbers;
 
 const MAX_ITERATIONS: usize = 1000;
 
+/// Extra decimals past the tick size that labels may show when zoomed in beyond a tick
+const MAX_SUB_TICK_DECIMALS: usize = 2;
+/// Price labels above this get abbreviated, they'd otherwise crowd the axis
+const ABBREVIATE_ABOVE: f32 = 1_000_000.0;
+
 fn calc_optimal_ticks(highest: f32, lowest: f32, labels_can_fit: i32) -> (f32, f32) {
     let range = (highest - lowest).abs().max(f32::EPSILON);
     let labels = labels_can_fit.max(1) as f32;
@@ -22,6 +27,26 @@ fn calc_optimal_ticks(highest: f32, lowest: f32, labels_can_fit: i32) -> (f32, f
     (step, rounded_highest)
 }
 
+/// Fewest decimals that represent every multiple of the step
+fn step_decimals(step: f32) -> usize {
+    (0..=8)
+        .find(|decimals| {
+            let scaled = step * 10f32.powi(*decimals as i32);
+            (scaled - scaled.round()).abs() < scaled.abs().max(1.0) * 1e-4
+        })
+        .unwrap_or(8)
+}
+
+fn format_label(value: f32, decimals: Option<usize>) -> String {
+    match decimals {
+        Some(_) if value.abs() >= ABBREVIATE_ABOVE => abbr_large_numbers(value),
+        Some(decimals) => format!("{value:.decimals$}"),
+        None => abbr_large_numbers(value),
+    }
+}
+
+/// With `decimals` set to those of the tick size, labels drop the decimals that the step
+/// between them doesn't need, and show a few more when zoomed in past a single tick
 pub fn generate_labels(
     bounds: iced::Rectangle,
     lowest: f32,
@@ -42,11 +67,7 @@ pub fn generate_labels(
 
     if labels_can_fit <= 1 {
         let label = LabelContent {
-            content: if let Some(decimals) = decimals {
-                format!("{highest:.decimals$}")
-            } else {
-                abbr_large_numbers(highest)
-            },
+            content: format_label(highest, decimals),
             background_color: None,
             text_color,
             text_size,
@@ -61,6 +82,9 @@ pub fn generate_labels(
 
     let (step, max) = calc_optimal_ticks(highest, lowest, labels_can_fit);
 
+    let decimals = decimals
+        .map(|tick_decimals| step_decimals(step).min(tick_decimals + MAX_SUB_TICK_DECIMALS));
+
     let mut value = max;
     while value > highest {
         value -= step;
@@ -71,14 +95,8 @@ pub fn generate_labels(
 
     while value >= lowest && safety_counter < MAX_ITERATIONS {
         if value <= highest + step * 0.5 && value >= lowest - step * 0.5 {
-            let content = if let Some(decimals) = decimals {
-                format!("{value:.decimals$}")
-            } else {
-                abbr_large_numbers(value)
-            };
-
             let label = LabelContent {
-                content,
+                content: format_label(value, decimals),
                 background_color: None,
                 text_color,
                 text_size,
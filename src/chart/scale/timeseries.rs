@@ -1,4 +1,5 @@
-use crate::chart::scale::{AxisLabel, TEXT_SIZE};
+use crate::chart::scale::AxisLabel;
+use crate::style::chart_text_size;
 
 use chrono::{DateTime, Datelike, Months, Offset};
 use data::{
@@ -118,7 +119,8 @@ fn calc_x_pos(time_millis: u64, min_millis: u64, max_millis: u64, width: f32) ->
 }
 
 fn is_drawable(x_pos: f64, width: f32) -> bool {
-    x_pos >= (-TEXT_SIZE * 5.0).into() && x_pos <= f64::from(width) + f64::from(TEXT_SIZE * 5.0)
+    x_pos >= (-chart_text_size() * 5.0).into()
+        && x_pos <= f64::from(width) + f64::from(chart_text_size() * 5.0)
 }
 
 pub fn generate_time_labels(
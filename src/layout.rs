@@ -1,15 +1,23 @@
 use crate::chart::{heatmap::HeatmapChart, kline::KlineChart};
 use crate::modal::layout_manager::LayoutManager;
-use crate::screen::dashboard::{Dashboard, pane, panel::timeandsales::TimeAndSales};
+use crate::screen::dashboard::{
+    Dashboard, pane,
+    panel::{
+        ladder::DomLadder, timeandsales::TimeAndSales, watchlist::Watchlist, whale_tape::WhaleTape,
+    },
+};
 use data::{
     UserTimezone,
-    chart::Basis,
+    chart::{Basis, heatmap::snapshot::Snapshot},
     layout::{WindowSpec, pane::Axis},
 };
 use exchange::{TickMultiplier, Ticker, Timeframe, adapter::Exchange};
 
 use iced::widget::pane_grid::{self, Configuration};
-use std::{collections::HashMap, vec};
+use std::{
+    collections::{BTreeMap, HashMap},
+    vec,
+};
 use uuid::Uuid;
 
 #[derive(Eq, Hash, Debug, Clone, PartialEq)]
@@ -27,7 +35,23 @@ pub struct SavedState {
     pub sidebar: data::Sidebar,
     pub theme: data::Theme,
     pub custom_theme: Option<data::Theme>,
+    pub chart_themes: data::config::theme::ChartThemes,
     pub audio_cfg: data::AudioStream,
+    pub stream_budget: data::config::StreamBudget,
+    pub pause_hidden_popouts: bool,
+    pub snapshot_schedule: data::config::SnapshotSchedule,
+    pub recent_tickers: Vec<(Exchange, Ticker)>,
+    pub ticker_hotkeys: BTreeMap<u8, (Exchange, Ticker)>,
+    pub show_status_bar: bool,
+    pub detached_layouts: Vec<(Layout, WindowSpec)>,
+    pub renderer: data::config::RendererSettings,
+    pub imbalance_presets: data::chart::kline::ImbalancePresets,
+    pub recorder: data::config::RecorderSettings,
+    pub ui_density: data::config::UiDensity,
+    pub alerts: data::alert::Alerts,
+    pub chart_text: data::config::ChartText,
+    pub testnet: Vec<Exchange>,
+    pub invalid_fields: Vec<data::InvalidField>,
 }
 
 impl SavedState {
@@ -55,7 +79,23 @@ impl Default for SavedState {
             sidebar: data::Sidebar::default(),
             theme: data::Theme::default(),
             custom_theme: None,
+            chart_themes: data::config::theme::ChartThemes::default(),
             audio_cfg: data::AudioStream::default(),
+            stream_budget: data::config::StreamBudget::default(),
+            pause_hidden_popouts: false,
+            snapshot_schedule: data::config::SnapshotSchedule::default(),
+            recent_tickers: Vec::new(),
+            ticker_hotkeys: BTreeMap::new(),
+            show_status_bar: false,
+            detached_layouts: vec![],
+            renderer: data::config::RendererSettings::default(),
+            imbalance_presets: data::chart::kline::ImbalancePresets::default(),
+            recorder: data::config::RecorderSettings::default(),
+            ui_density: data::config::UiDensity::default(),
+            alerts: data::alert::Alerts::default(),
+            chart_text: data::config::ChartText::default(),
+            testnet: vec![],
+            invalid_fields: vec![],
         }
     }
 }
@@ -105,7 +145,17 @@ impl From<&Dashboard> for data::Dashboard {
 
 impl From<&pane::State> for data::Pane {
     fn from(pane: &pane::State) -> Self {
-        let streams = pane.streams.clone();
+        // a failed over pane is saved as it was, so it starts on its primary exchange again
+        let (streams, settings) = match &pane.failover {
+            Some(failover) => (
+                failover.streams.clone(),
+                data::layout::pane::Settings {
+                    ticker_info: Some(failover.ticker_info),
+                    ..pane.settings
+                },
+            ),
+            None => (pane.streams.clone(), pane.settings),
+        };
 
         match &pane.content {
             pane::Content::Starter => data::Pane::Starter {
@@ -114,22 +164,36 @@ impl From<&pane::State> for data::Pane {
             pane::Content::Heatmap(chart, indicators) => data::Pane::HeatmapChart {
                 layout: chart.chart_layout(),
                 stream_type: streams,
-                settings: pane.settings,
+                settings,
                 indicators: indicators.clone(),
                 studies: chart.studies.clone(),
                 link_group: pane.link_group,
+                snapshot: chart.snapshot_source().map(str::to_string),
             },
             pane::Content::Kline(chart, indicators) => data::Pane::KlineChart {
                 layout: chart.chart_layout(),
                 kind: chart.kind().clone(),
                 stream_type: streams,
-                settings: pane.settings,
+                settings,
                 indicators: indicators.clone(),
                 link_group: pane.link_group,
             },
             pane::Content::TimeAndSales(_) => data::Pane::TimeAndSales {
                 stream_type: streams,
-                settings: pane.settings,
+                settings,
+                link_group: pane.link_group,
+            },
+            pane::Content::DomLadder(_) => data::Pane::DomLadder {
+                stream_type: streams,
+                settings,
+                link_group: pane.link_group,
+            },
+            pane::Content::Watchlist(watchlist) => data::Pane::Watchlist {
+                tickers: watchlist.tickers().to_vec(),
+                link_group: pane.link_group,
+            },
+            pane::Content::WhaleTape(tape) => data::Pane::WhaleTape {
+                threshold: tape.threshold,
                 link_group: pane.link_group,
             },
         }
@@ -160,7 +224,35 @@ pub fn configuration(pane: data::Pane) -> Configuration<pane::State> {
             settings,
             indicators,
             link_group,
+            snapshot,
         } => {
+            if let Some(file_name) = snapshot {
+                let config = settings.visual_config.and_then(|cfg| cfg.heatmap());
+
+                return match Snapshot::load(&file_name) {
+                    Ok(snapshot) => Configuration::Pane(pane::State::from_config(
+                        pane::Content::Heatmap(
+                            HeatmapChart::from_snapshot(
+                                layout,
+                                &snapshot,
+                                file_name,
+                                &indicators,
+                                config,
+                                studies,
+                            ),
+                            indicators,
+                        ),
+                        vec![],
+                        settings,
+                        link_group,
+                    )),
+                    Err(err) => {
+                        log::warn!("Skipping a heatmap snapshot viewer: {err}");
+                        Configuration::Pane(pane::State::new())
+                    }
+                };
+            }
+
             if let Some(ticker_info) = settings.ticker_info {
                 let tick_size = settings
                     .tick_multiply
@@ -221,7 +313,8 @@ pub fn configuration(pane: data::Pane) -> Configuration<pane::State> {
                                 &indicators,
                                 settings.ticker_info,
                                 &kind,
-                            ),
+                            )
+                            .with_visual_config(settings.visual_config.and_then(|cfg| cfg.kline())),
                             indicators,
                         ),
                         stream_type,
@@ -235,14 +328,23 @@ pub fn configuration(pane: data::Pane) -> Configuration<pane::State> {
                     Configuration::Pane(pane::State::new())
                 }
             }
-            data::chart::KlineChartKind::Candles => {
+            // tpo panes are always saved with their basis and tick size
+            data::chart::KlineChartKind::Candles
+            | data::chart::KlineChartKind::Tpo
+            | data::chart::KlineChartKind::DeltaCandles
+            | data::chart::KlineChartKind::Spread(_) => {
                 if let Some(ticker_info) = settings.ticker_info {
                     let basis = settings.selected_basis.unwrap_or(Timeframe::M15.into());
 
-                    let tick_size = settings
-                        .tick_multiply
-                        .unwrap_or(TickMultiplier(1))
-                        .multiply_with_min_tick_size(ticker_info);
+                    let tick_size = match &kind {
+                        data::chart::KlineChartKind::Spread(mode) => {
+                            mode.tick_size(ticker_info.min_ticksize)
+                        }
+                        _ => settings
+                            .tick_multiply
+                            .unwrap_or(TickMultiplier(1))
+                            .multiply_with_min_tick_size(ticker_info),
+                    };
 
                     Configuration::Pane(pane::State::from_config(
                         pane::Content::Kline(
@@ -255,7 +357,8 @@ pub fn configuration(pane: data::Pane) -> Configuration<pane::State> {
                                 &indicators,
                                 settings.ticker_info,
                                 &kind,
-                            ),
+                            )
+                            .with_visual_config(settings.visual_config.and_then(|cfg| cfg.kline())),
                             indicators,
                         ),
                         stream_type,
@@ -264,7 +367,7 @@ pub fn configuration(pane: data::Pane) -> Configuration<pane::State> {
                     ))
                 } else {
                     log::info!(
-                        "Skipping a CandlestickChart initialization due to missing ticker info"
+                        "Skipping a {kind:?} chart initialization due to missing ticker info"
                     );
                     Configuration::Pane(pane::State::new())
                 }
@@ -289,12 +392,49 @@ pub fn configuration(pane: data::Pane) -> Configuration<pane::State> {
                 link_group,
             ))
         }
+        data::Pane::DomLadder {
+            stream_type,
+            settings,
+            link_group,
+        } => {
+            if settings.ticker_info.is_none() {
+                log::info!("Skipping a DomLadder initialization due to missing ticker info");
+                return Configuration::Pane(pane::State::new());
+            }
+
+            let config = settings.visual_config.and_then(|cfg| cfg.ladder());
+
+            Configuration::Pane(pane::State::from_config(
+                pane::Content::DomLadder(DomLadder::new(config, settings.ticker_info)),
+                stream_type,
+                settings,
+                link_group,
+            ))
+        }
+        data::Pane::Watchlist {
+            tickers,
+            link_group,
+        } => Configuration::Pane(pane::State::from_config(
+            pane::Content::Watchlist(Watchlist::new(tickers)),
+            vec![],
+            data::layout::pane::Settings::default(),
+            link_group,
+        )),
+        data::Pane::WhaleTape {
+            threshold,
+            link_group,
+        } => Configuration::Pane(pane::State::from_config(
+            pane::Content::WhaleTape(WhaleTape::new(threshold)),
+            vec![],
+            data::layout::pane::Settings::default(),
+            link_group,
+        )),
     }
 }
 
 pub fn load_saved_state() -> SavedState {
     match data::read_from_file(data::SAVED_STATE_PATH) {
-        Ok(state) => {
+        Ok((state, invalid_fields)) => {
             let mut de_layouts = vec![];
 
             for layout in &state.layout_manager.layouts {
@@ -316,7 +456,9 @@ pub fn load_saved_state() -> SavedState {
                 de_layouts.push((layout.name.clone(), layout_id, dashboard));
             }
 
-            let layout_manager: LayoutManager = {
+            let layout_manager: LayoutManager = if de_layouts.is_empty() {
+                LayoutManager::new()
+            } else {
                 let mut layouts = HashMap::new();
 
                 let active_layout = Layout {
@@ -345,11 +487,43 @@ pub fn load_saved_state() -> SavedState {
                 LayoutManager::from_config(layout_order, layouts, active_layout)
             };
 
-            exchange::fetcher::toggle_trade_fetch(state.trade_fetch_enabled);
+            let active_layout_id = layout_manager.active_layout().id;
+            let detached_layouts = state
+                .layout_manager
+                .detached
+                .iter()
+                .filter_map(|(name, window_spec)| {
+                    layout_manager
+                        .layout_order
+                        .iter()
+                        .filter_map(|id| layout_manager.get_layout(*id))
+                        .find(|(layout, _)| layout.name == *name && layout.id != active_layout_id)
+                        .map(|(layout, _)| (layout.clone(), *window_spec))
+                })
+                .collect();
+
+            for exchange in state.trade_fetch {
+                exchange::fetcher::toggle_trade_fetch(exchange, true);
+            }
+            exchange::depth::set_depth_fallback(state.depth_fallback);
+            data::config::set_chart_defaults(state.chart_defaults);
+            exchange::connect::set_reconnect_policy(state.reconnect_policy);
+            exchange::fetcher::set_rate_limit_share(state.rate_limit_share);
+            if state.proxy.is_some() {
+                // shown once the app is up, requests are refused in the meantime
+                let _ = exchange::connect::set_proxy(state.proxy);
+            }
+            for (exchange, endpoints) in state.endpoints {
+                exchange::adapter::set_endpoints(exchange, endpoints);
+            }
+            for exchange in &state.testnet {
+                exchange::adapter::set_testnet(*exchange, true);
+            }
 
             SavedState {
                 theme: state.selected_theme,
                 custom_theme: state.custom_theme,
+                chart_themes: state.chart_themes,
                 layout_manager,
                 favorited_tickers: state.favorited_tickers,
                 main_window: state.main_window,
@@ -357,6 +531,21 @@ pub fn load_saved_state() -> SavedState {
                 sidebar: state.sidebar,
                 scale_factor: state.scale_factor,
                 audio_cfg: state.audio_cfg,
+                stream_budget: state.stream_budget,
+                pause_hidden_popouts: state.pause_hidden_popouts,
+                snapshot_schedule: state.snapshot_schedule,
+                recent_tickers: state.recent_tickers,
+                ticker_hotkeys: state.ticker_hotkeys,
+                show_status_bar: state.show_status_bar,
+                detached_layouts,
+                renderer: state.renderer,
+                imbalance_presets: state.imbalance_presets,
+                recorder: state.recorder,
+                ui_density: state.ui_density,
+                alerts: state.alerts,
+                chart_text: state.chart_text,
+                testnet: state.testnet,
+                invalid_fields,
             }
         }
         Err(e) => {
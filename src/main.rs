@@ -1,10 +1,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod bus;
 mod chart;
 mod layout;
 mod logger;
 mod modal;
+mod replay;
 mod screen;
+mod screenshot;
+mod status_bar;
 mod style;
 mod widget;
 mod window;
@@ -20,6 +24,7 @@ use widget::{
     tooltip,
 };
 
+use exchange::adapter::{self, Exchange, ServerStatus};
 use iced::{
     Alignment, Element, Subscription, Task, keyboard, padding,
     widget::{
@@ -27,21 +32,29 @@ use iced::{
         tooltip::Position as TooltipPosition,
     },
 };
+use iced_futures::futures::TryFutureExt;
 use std::{borrow::Cow, collections::HashMap, vec};
 
 fn main() {
+    let renderer = data::read_renderer_settings();
+    apply_renderer_env(renderer);
+
+    let ui_density = data::read_ui_density();
+    style::set_density(ui_density);
+
     logger::setup(cfg!(debug_assertions)).expect("Failed to initialize logger");
+    data::crash::install(data::read_crash_reports(), env!("CARGO_PKG_VERSION"));
 
     std::thread::spawn(data::cleanup_old_market_data);
 
     let _ = iced::daemon(Flowsurface::new, Flowsurface::update, Flowsurface::view)
         .settings(iced::Settings {
-            antialiasing: true,
+            antialiasing: renderer.antialiasing,
             fonts: vec![
                 Cow::Borrowed(style::AZERET_MONO_BYTES),
                 Cow::Borrowed(style::ICONS_BYTES),
             ],
-            default_text_size: iced::Pixels(12.0),
+            default_text_size: iced::Pixels(ui_density.default_text_size()),
             ..Default::default()
         })
         .title(Flowsurface::title)
@@ -51,17 +64,81 @@ fn main() {
         .run();
 }
 
+/// How long an exchange has to stay disconnected before panes fail over to their fallback
+const FAILOVER_GRACE: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// The GPU adapter and present mode are only configurable through the environment,
+/// variables already set by the user take precedence over the saved settings
+fn apply_renderer_env(renderer: data::config::RendererSettings) {
+    let vars = [
+        ("WGPU_POWER_PREF", renderer.gpu_preference.env_value()),
+        ("ICED_PRESENT_MODE", renderer.present_mode()),
+    ];
+
+    for (key, value) in vars {
+        if std::env::var_os(key).is_none() {
+            // SAFETY: called first thing in `main`, before the logger or any other thread is spawned
+            unsafe { std::env::set_var(key, value) };
+        }
+    }
+}
+
+/// Confirmation shown before trades start getting fetched for a market, with what it costs
+fn trade_fetch_consent_prompt(exchange: Exchange) -> String {
+    let estimate = exchange
+        .daily_trades_estimate_mb()
+        .map(|(low, high)| {
+            format!(
+                "\n\nExpect around {low}-{high} MB of downloads per day of history for a major \
+                ticker, cached on disk in the market_data folder"
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        "Fetch trades of {exchange} footprint charts? This might be unreliable and take some \
+        time to complete{estimate}"
+    )
+}
+
 struct Flowsurface {
     main_window: window::Window,
     sidebar: dashboard::Sidebar,
     layout_manager: LayoutManager,
     theme_editor: ThemeEditor,
     audio_stream: audio::AudioStream,
+    journal: modal::journal::Journal,
+    alerts_manager: modal::alerts::AlertsManager,
+    profile_builder: modal::profile::ProfileBuilder,
+    command_palette: Option<modal::command::CommandPalette>,
     confirm_dialog: Option<(String, Box<Message>)>,
     scale_factor: data::ScaleFactor,
     timezone: data::UserTimezone,
     theme: data::Theme,
-    notifications: Vec<Toast>,
+    notifications: toast::Notifications,
+    stream_budget: data::config::StreamBudget,
+    pause_hidden_popouts: bool,
+    health_report: Option<(data::diagnostics::Report, bool)>,
+    snapshot_schedule: data::config::SnapshotSchedule,
+    snapshot_times_input: String,
+    last_snapshot_slot: Option<chrono::NaiveDateTime>,
+    status_bar: status_bar::StatusBar,
+    show_status_bar: bool,
+    backups: Vec<data::backup::Backup>,
+    renderer: data::config::RendererSettings,
+    imbalance_presets: data::chart::kline::ImbalancePresets,
+    chart_themes: data::config::theme::ChartThemes,
+    replay: Option<replay::ReplayPanel>,
+    recorder_settings: data::config::RecorderSettings,
+    recorder: Option<data::recorder::Recorder>,
+    ui_density: data::config::UiDensity,
+    chart_text: data::config::ChartText,
+    disconnected_since: HashMap<Exchange, std::time::Instant>,
+    event_bus: bus::EventBus,
+    testnet: Vec<Exchange>,
+    proxy_input: String,
+    /// Market whose endpoints the settings edit, with the addresses typed in so far
+    endpoints_input: (Exchange, exchange::adapter::Endpoints),
 }
 
 #[derive(Debug, Clone)]
@@ -75,14 +152,64 @@ enum Message {
     GoBack,
     DataFolderRequested,
     ThemeSelected(data::Theme),
+    ChartThemeSelected(data::config::theme::ThemedChart, Option<data::Theme>),
     ScaleFactorChanged(data::ScaleFactor),
     SetTimezone(data::UserTimezone),
-    ToggleTradeFetch(bool),
+    ToggleTradeFetch(Exchange, bool),
     RemoveNotification(usize),
+    ToggleNotificationDetails(usize),
     ToggleDialogModal(Option<(String, Box<Message>)>),
     ThemeEditor(modal::theme_editor::Message),
     Layouts(modal::layout_manager::Message),
     AudioStream(modal::audio::Message),
+    Journal(modal::journal::Message),
+    Alerts(modal::alerts::Message),
+    Profile(modal::profile::Message),
+    ToggleCommandPalette,
+    ToggleCleanMode,
+    StepBarReplay(i64),
+    CommandPalette(modal::command::Message),
+    LazyStreams(bool),
+    PopoutMinimized(window::Id, bool),
+    TogglePauseHiddenPopouts(bool),
+    RunHealthCheck,
+    HealthCheckCompleted(Vec<(Exchange, Result<ServerStatus, String>)>),
+    ToggleHealthReport(bool),
+    ExportDiagnostics,
+    BackupSavedState,
+    ToggleSnapshotSchedule(bool),
+    SnapshotTimesChanged(String),
+    ScheduledSnapshotTaken(
+        Vec<(iced::Rectangle, String)>,
+        chrono::NaiveDateTime,
+        iced::window::Screenshot,
+    ),
+    ScheduledSnapshotSaved(Result<std::path::PathBuf, String>),
+    ToggleStatusBar(bool),
+    ToggleCrashReports(bool),
+    StatusBar(status_bar::Message),
+    RendererChanged(data::config::RendererSettings),
+    ImbalancePresetRemoved(String),
+    ImbalanceDefaultChanged(exchange::adapter::MarketKind, Option<String>),
+    RestoreBackup(data::backup::Backup),
+    Replay(replay::Message),
+    ToggleRecorder(bool),
+    RecorderRestoreChanged(u8),
+    RecorderBackfillChanged(u8),
+    DepthFallbackChanged(exchange::depth::DepthFallback),
+    ReconnectPolicyChanged(exchange::connect::ReconnectPolicy),
+    RateLimitShareChanged(exchange::fetcher::RateLimitShare),
+    RecordingSaved(Result<(), String>),
+    UiDensityChanged(data::config::UiDensity),
+    ChartTextChanged(data::config::ChartText),
+    ChartDefaultsChanged(data::config::ChartDefaults),
+    SetIndicatorOnAll(data::chart::indicator::KlineIndicator, bool),
+    ToggleTestnet(Exchange, bool),
+    ProxyInputChanged(String),
+    ApplyProxy,
+    EndpointsMarketSelected(Exchange),
+    EndpointsInputChanged(exchange::adapter::Endpoints),
+    ApplyEndpoints,
 }
 
 impl Flowsurface {
@@ -102,43 +229,144 @@ impl Flowsurface {
 
         let (sidebar, launch_sidebar) = dashboard::Sidebar::new(&saved_state);
 
+        style::set_chart_text(saved_state.chart_text);
+
         let mut state = Self {
             main_window: window::Window::new(main_window_id),
             layout_manager: saved_state.layout_manager,
             theme_editor: ThemeEditor::new(saved_state.custom_theme),
             audio_stream: audio::AudioStream::new(saved_state.audio_cfg),
+            journal: modal::journal::Journal::new(),
+            alerts_manager: modal::alerts::AlertsManager::new(saved_state.alerts),
+            profile_builder: modal::profile::ProfileBuilder::default(),
+            command_palette: None,
             sidebar,
             confirm_dialog: None,
             timezone: saved_state.timezone,
             scale_factor: saved_state.scale_factor,
             theme: saved_state.theme,
-            notifications: vec![],
+            notifications: toast::Notifications::default(),
+            stream_budget: saved_state.stream_budget,
+            pause_hidden_popouts: saved_state.pause_hidden_popouts,
+            health_report: None,
+            snapshot_times_input: saved_state.snapshot_schedule.times_string(),
+            snapshot_schedule: saved_state.snapshot_schedule,
+            last_snapshot_slot: None,
+            status_bar: status_bar::StatusBar::new(),
+            show_status_bar: saved_state.show_status_bar,
+            renderer: saved_state.renderer,
+            imbalance_presets: saved_state.imbalance_presets,
+            chart_themes: saved_state.chart_themes,
+            replay: None,
+            ui_density: saved_state.ui_density,
+            chart_text: saved_state.chart_text,
+            recorder_settings: saved_state.recorder,
+            recorder: saved_state
+                .recorder
+                .is_enabled
+                .then(data::recorder::Recorder::new),
+            backups: data::backup::list(),
+            disconnected_since: HashMap::new(),
+            testnet: saved_state.testnet,
+            proxy_input: exchange::connect::proxy()
+                .map(|proxy| proxy.to_string())
+                .unwrap_or_default(),
+            endpoints_input: (
+                Exchange::ALL[0],
+                exchange::adapter::endpoints(Exchange::ALL[0]),
+            ),
+            event_bus: {
+                let mut event_bus = bus::EventBus::default();
+                event_bus.subscribe(bus::CrashTrail);
+                event_bus.subscribe(bus::AlertSound);
+                event_bus.subscribe(bus::TradeSound);
+                event_bus.subscribe(bus::Recording);
+                event_bus
+            },
         };
 
+        if let Some(err) = exchange::connect::proxy_error() {
+            state.notifications.push(Toast::error(err));
+        }
+
+        if !saved_state.invalid_fields.is_empty() {
+            let fields = saved_state
+                .invalid_fields
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            state.confirm_dialog = Some((
+                format!(
+                    "These saved settings couldn't be parsed and were reset to defaults:\n\n{fields}\n\nBack up the original file before it's overwritten?"
+                ),
+                Box::new(Message::BackupSavedState),
+            ));
+        }
+
+        if let Some(report) = data::crash::take_unseen_report() {
+            if state.confirm_dialog.is_none() {
+                state.confirm_dialog = Some((
+                    format!(
+                        "Flowsurface crashed last time, a report was saved to\n{}\n\nOpen the data folder to attach it to a bug report?",
+                        report.display()
+                    ),
+                    Box::new(Message::DataFolderRequested),
+                ));
+            }
+        }
+
         let last_active_layout = state.layout_manager.active_layout();
         let load_layout = state.load_layout(last_active_layout, main_window_id);
 
+        let open_detached = Task::batch(
+            saved_state
+                .detached_layouts
+                .into_iter()
+                .map(|(layout, window_spec)| state.open_detached_layout(layout, Some(window_spec)))
+                .collect::<Vec<_>>(),
+        );
+
         (
             state,
             open_main_window
                 .discard()
                 .chain(load_layout)
-                .chain(launch_sidebar.map(Message::Sidebar)),
+                .chain(open_detached)
+                .chain(launch_sidebar.map(Message::Sidebar))
+                .chain(Task::done(Message::RunHealthCheck)),
         )
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::MarketWsEvent(event) => {
-                let main_window_id = self.main_window.id;
-                let dashboard = self.active_dashboard_mut();
+                self.status_bar.record_event(&event);
+
+                let stream = match &event {
+                    exchange::Event::Connected(exchange, streams) => {
+                        log::info!("{} stream(s) connected to {exchange} WS", streams.len());
+                        data::crash::record_event(format!("{exchange} connected {streams:?}"));
+                        self.disconnected_since.remove(exchange);
 
-                match event {
-                    exchange::Event::Connected(exchange) => {
-                        log::info!("a stream connected to {exchange} WS");
+                        for (layout_id, window) in self.streaming_layouts() {
+                            if let Some(dashboard) = self.layout_manager.mut_dashboard(&layout_id) {
+                                dashboard.clear_reconnecting(*exchange, window.id);
+                            }
+                        }
+                        return Task::none();
                     }
-                    exchange::Event::Disconnected(exchange, reason) => {
-                        log::info!("a stream disconnected from {exchange} WS: {reason:?}");
+                    exchange::Event::Disconnected(exchange, streams, reason) => {
+                        log::info!(
+                            "{} stream(s) disconnected from {exchange} WS: {reason:?}",
+                            streams.len()
+                        );
+                        data::crash::record_event(format!("{exchange} disconnected: {reason}"));
+                        self.disconnected_since
+                            .entry(*exchange)
+                            .or_insert_with(std::time::Instant::now);
+                        return Task::none();
                     }
                     exchange::Event::DepthReceived(
                         stream,
@@ -146,69 +374,661 @@ impl Flowsurface {
                         depth,
                         trades_buffer,
                     ) => {
-                        let task = dashboard
-                            .update_depth_and_trades(
-                                &stream,
+                        let mut sinks = bus::Sinks {
+                            audio: &self.audio_stream,
+                            recorder: self.recorder.as_mut(),
+                        };
+                        self.event_bus.publish(
+                            bus::Event::Trades(stream, *depth_update_t, trades_buffer),
+                            &mut sinks,
+                        );
+                        self.event_bus.publish(
+                            bus::Event::Depth(stream, *depth_update_t, depth),
+                            &mut sinks,
+                        );
+
+                        let (exchange, ticker) = stream.exchange_and_ticker();
+
+                        for alert in self.alerts_manager.evaluate(
+                            exchange::SerTicker::from_parts(exchange, ticker),
+                            trades_buffer,
+                            self.timezone,
+                        ) {
+                            self.event_bus
+                                .publish(bus::Event::AlertFired(&alert), &mut sinks);
+                            self.notifications.push(Toast::warn(alert.fired_message()));
+                        }
+                        *stream
+                    }
+                    exchange::Event::DepthDegraded(stream, reason) => {
+                        data::crash::record_event(format!("{stream:?} degraded: {reason:?}"));
+                        match reason {
+                            Some(reason) => {
+                                log::warn!("{stream:?} fell back to snapshots: {reason}")
+                            }
+                            None => log::info!("{stream:?} is back on live depth updates"),
+                        }
+                        *stream
+                    }
+                    exchange::Event::Reconnecting(stream, delay) => {
+                        data::crash::record_event(format!("{stream:?} reconnecting: {delay:?}"));
+                        match delay {
+                            Some(delay) => log::info!("{stream:?} reconnecting in {delay:?}"),
+                            None => log::warn!("{stream:?} gave up reconnecting"),
+                        }
+                        *stream
+                    }
+                    exchange::Event::KlineReceived(stream, kline) => {
+                        let mut sinks = bus::Sinks {
+                            audio: &self.audio_stream,
+                            recorder: self.recorder.as_mut(),
+                        };
+                        self.event_bus
+                            .publish(bus::Event::Kline(stream, kline), &mut sinks);
+                        *stream
+                    }
+                    exchange::Event::LiquidationsReceived(stream, _) => *stream,
+                };
+
+                // layouts streaming side by side may share a subscription, so each one having the
+                // stream gets the event unless it's paused or replaying. Unclaimed streams go to
+                // the active one, which drops them
+                let claimed_by = self
+                    .streaming_layouts()
+                    .into_iter()
+                    .filter_map(|(layout_id, window)| {
+                        let dashboard = self.layout_manager.dashboard(&layout_id)?;
+                        dashboard.streams.contains(&stream).then_some((
+                            layout_id,
+                            window,
+                            dashboard.is_streams_paused(),
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+
+                let targets = if claimed_by.is_empty() {
+                    vec![(self.layout_manager.active_layout().id, self.main_window)]
+                } else {
+                    claimed_by
+                        .into_iter()
+                        .filter(|(_, _, is_paused)| !is_paused)
+                        .map(|(layout_id, window, _)| (layout_id, window))
+                        .collect()
+                };
+
+                let tasks = targets
+                    .into_iter()
+                    .filter_map(|(layout_id, window)| {
+                        let dashboard = self.layout_manager.mut_dashboard(&layout_id)?;
+
+                        let task = match &event {
+                            exchange::Event::DepthReceived(
+                                stream,
                                 depth_update_t,
-                                &depth,
-                                &trades_buffer,
-                                main_window_id,
-                            )
-                            .map(move |msg| Message::Dashboard(None, msg));
+                                depth,
+                                trades_buffer,
+                            ) => dashboard.update_depth_and_trades(
+                                stream,
+                                *depth_update_t,
+                                depth,
+                                trades_buffer,
+                                window.id,
+                            ),
+                            exchange::Event::KlineReceived(stream, kline) => {
+                                dashboard.update_latest_klines(stream, kline, window.id)
+                            }
+                            exchange::Event::LiquidationsReceived(stream, liquidations) => {
+                                dashboard.update_liquidations(stream, liquidations, window.id)
+                            }
+                            exchange::Event::DepthDegraded(stream, reason) => {
+                                dashboard.set_depth_degraded(stream, reason.as_deref(), window.id);
+                                return None;
+                            }
+                            exchange::Event::Reconnecting(stream, delay) => {
+                                dashboard.set_reconnecting(stream, *delay, window.id);
+                                return None;
+                            }
+                            _ => return None,
+                        };
 
-                        if let Err(err) = self.audio_stream.try_play_sound(&stream, &trades_buffer)
-                        {
-                            log::error!("Failed to play sound: {err}");
-                        }
+                        Some(task.map(move |msg| Message::Dashboard(Some(layout_id), msg)))
+                    })
+                    .collect::<Vec<_>>();
+
+                return Task::batch(tasks);
+            }
+            Message::Tick(now) => {
+                if self.active_dashboard_mut().take_panes_changed() {
+                    self.sync_crash_context();
+                }
 
-                        return task;
+                if self.show_status_bar {
+                    let active_streams = self
+                        .streaming_layouts()
+                        .into_iter()
+                        .filter_map(|(layout_id, _)| self.layout_manager.dashboard(&layout_id))
+                        .collect::<Vec<_>>();
+
+                    self.status_bar.tick(now, |stream| {
+                        active_streams
+                            .iter()
+                            .any(|dashboard| dashboard.streams.contains(stream))
+                    });
+                }
+
+                let alert_lines = self.alerts_manager.price_lines();
+
+                let mut tasks = self
+                    .streaming_layouts()
+                    .into_iter()
+                    .filter_map(|(layout_id, window)| {
+                        let dashboard = self.layout_manager.mut_dashboard(&layout_id)?;
+                        dashboard.set_alert_lines(&alert_lines, window.id);
+
+                        Some(
+                            dashboard
+                                .tick(now, window.id)
+                                .map(move |msg| Message::Dashboard(Some(layout_id), msg)),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                if let Some(panel) = &mut self.replay {
+                    if let Some(exchange::Event::DepthReceived(stream, time, depth, trades)) =
+                        panel.step(now)
+                    {
+                        // replayed trades only go to the layout that requested them, other
+                        // layouts showing the same stream keep streaming live
+                        let layout_id = panel.layout_id;
+                        let window = self.layout_window(&layout_id);
+
+                        if let Some(dashboard) = self.layout_manager.mut_dashboard(&layout_id) {
+                            tasks.push(
+                                dashboard
+                                    .update_depth_and_trades(
+                                        &stream, time, &depth, &trades, window.id,
+                                    )
+                                    .map(move |msg| Message::Dashboard(Some(layout_id), msg)),
+                            );
+                        }
                     }
-                    exchange::Event::KlineReceived(stream, kline) => {
-                        return dashboard
-                            .update_latest_klines(&stream, &kline, main_window_id)
-                            .map(move |msg| Message::Dashboard(None, msg));
+                }
+
+                if let Some(batch) = self
+                    .recorder
+                    .as_mut()
+                    .and_then(|recorder| recorder.take_due(now))
+                {
+                    tasks.push(Task::perform(
+                        async move { batch.write().map_err(|err| err.to_string()) },
+                        Message::RecordingSaved,
+                    ));
+                }
+
+                tasks.push(self.scheduled_snapshots());
+                tasks.push(self.fail_over_down_exchanges(now));
+
+                return Task::batch(tasks);
+            }
+            Message::Journal(message) => {
+                if let Some(modal::journal::Action::JumpTo(tag)) = self.journal.update(message) {
+                    let main_window = self.main_window.id;
+                    let is_shown = self.active_dashboard_mut().scroll_to_time(
+                        main_window,
+                        tag.exchange,
+                        tag.ticker,
+                        tag.mid_time(),
+                    );
+
+                    if is_shown {
+                        self.sidebar.set_menu(None);
+                    } else {
+                        self.notifications.push(Toast::warn(format!(
+                            "Open a time based chart of {} to jump to this tag",
+                            tag.ticker.display_symbol_and_type().0
+                        )));
                     }
                 }
             }
-            Message::Tick(now) => {
-                let main_window_id = self.main_window.id;
+            Message::Alerts(message) => self.alerts_manager.update(message),
+            Message::Profile(message) => {
+                if let Some(modal::profile::Action::Build(ticker)) =
+                    self.profile_builder.update(message)
+                {
+                    let profile = self
+                        .active_dashboard()
+                        .composite_profile(ticker, self.main_window.id);
+                    self.profile_builder.set_result(profile);
+                }
+            }
+            Message::ToggleCommandPalette => {
+                if self.command_palette.take().is_none() {
+                    let (palette, task) =
+                        modal::command::CommandPalette::new(&self.command_context());
+                    self.command_palette = Some(palette);
+
+                    return task.map(Message::CommandPalette);
+                }
+            }
+            Message::StepBarReplay(bars) => {
+                let main_window = self.main_window.id;
+                self.active_dashboard_mut()
+                    .step_focused_bar_replay(main_window, bars);
+            }
+            Message::ToggleCleanMode => {
+                let main_window = self.main_window.id;
 
                 return self
                     .active_dashboard_mut()
-                    .tick(now, main_window_id)
+                    .toggle_focused_clean_mode(main_window)
                     .map(move |msg| Message::Dashboard(None, msg));
             }
+            Message::CommandPalette(message) => {
+                let context = self.command_context();
+                let Some(palette) = &mut self.command_palette else {
+                    return Task::none();
+                };
+
+                if let Some(modal::command::Action::Run(command)) =
+                    palette.update(message, &context)
+                {
+                    self.command_palette = None;
+
+                    return Task::done(match command {
+                        modal::command::Command::OpenPane {
+                            content,
+                            exchange,
+                            ticker,
+                        } => Message::Sidebar(dashboard::sidebar::Message::TickersTable(
+                            dashboard::tickers_table::Message::TickerSelected(
+                                ticker,
+                                exchange,
+                                Some(content.to_string()),
+                            ),
+                        )),
+                        modal::command::Command::SwitchLayout(layout) => {
+                            Message::Layouts(modal::layout_manager::Message::SelectActive(layout))
+                        }
+                        modal::command::Command::ToggleIndicator {
+                            window,
+                            pane,
+                            indicator,
+                        } => Message::Dashboard(
+                            None,
+                            dashboard::Message::Pane(
+                                window,
+                                dashboard::pane::Message::ToggleIndicator(pane, indicator),
+                            ),
+                        ),
+                        modal::command::Command::ShowMenu(menu) => Message::Sidebar(
+                            dashboard::sidebar::Message::ToggleSidebarMenu(Some(menu)),
+                        ),
+                        modal::command::Command::SetIndicatorOnAll {
+                            indicator,
+                            is_enabled,
+                        } => Message::ToggleDialogModal(Some((
+                            format!(
+                                "Turn {indicator} {} on every kline chart of this layout?",
+                                if is_enabled { "on" } else { "off" }
+                            ),
+                            Box::new(Message::SetIndicatorOnAll(indicator, is_enabled)),
+                        ))),
+                    });
+                }
+            }
+            Message::Replay(message) => {
+                let Some(panel) = &mut self.replay else {
+                    return Task::none();
+                };
+                let (layout_id, stream) = (panel.layout_id, panel.stream);
+                let (task, action) = panel.update(message);
+                let task = task.map(Message::Replay);
+
+                let window = self.layout_window(&layout_id);
+
+                match action {
+                    Some(replay::Action::Started) => {
+                        if let Some(dashboard) = self.layout_manager.mut_dashboard(&layout_id) {
+                            return dashboard
+                                .begin_replay(&stream, window.id)
+                                .map(move |msg| Message::Dashboard(Some(layout_id), msg))
+                                .chain(task);
+                        }
+                    }
+                    Some(replay::Action::Closed) => return self.close_replay(),
+                    Some(replay::Action::ErrorOccurred(err)) => {
+                        self.notifications.push(Toast::error(err));
+                    }
+                    None => {}
+                }
+
+                return task;
+            }
+            Message::ToggleRecorder(is_enabled) => {
+                self.recorder_settings.is_enabled = is_enabled;
+
+                if is_enabled {
+                    self.recorder
+                        .get_or_insert_with(data::recorder::Recorder::new);
+                } else if let Some(batch) = self
+                    .recorder
+                    .take()
+                    .and_then(|mut recorder| recorder.take_all(std::time::Instant::now()))
+                {
+                    return Task::perform(
+                        async move { batch.write().map_err(|err| err.to_string()) },
+                        Message::RecordingSaved,
+                    );
+                }
+            }
+            Message::RecorderRestoreChanged(hours) => {
+                self.recorder_settings.restore_hours = hours;
+            }
+            Message::RecorderBackfillChanged(minutes) => {
+                self.recorder_settings.backfill_minutes = minutes;
+            }
+            Message::DepthFallbackChanged(fallback) => {
+                exchange::depth::set_depth_fallback(fallback);
+            }
+            Message::ChartDefaultsChanged(defaults) => {
+                data::config::set_chart_defaults(defaults);
+            }
+            Message::SetIndicatorOnAll(indicator, is_enabled) => {
+                self.confirm_dialog = None;
+
+                let main_window = self.main_window.id;
+                let changed = self.active_dashboard_mut().set_kline_indicator_on_all(
+                    main_window,
+                    indicator,
+                    is_enabled,
+                );
+
+                self.notifications.push(Toast::info(format!(
+                    "{indicator} turned {} on {changed} chart(s)",
+                    if is_enabled { "on" } else { "off" }
+                )));
+            }
+            Message::ReconnectPolicyChanged(policy) => {
+                exchange::connect::set_reconnect_policy(policy);
+            }
+            Message::RateLimitShareChanged(share) => {
+                exchange::fetcher::set_rate_limit_share(share);
+            }
+            Message::RecordingSaved(result) => {
+                if let Err(err) = result {
+                    log::error!("{err}");
+                }
+            }
+            Message::ToggleStatusBar(is_shown) => {
+                self.show_status_bar = is_shown;
+            }
+            Message::ToggleCrashReports(is_enabled) => {
+                data::crash::set_enabled(is_enabled);
+                self.sync_crash_context();
+            }
+            Message::StatusBar(message) => self.status_bar.update(message),
+            Message::RendererChanged(renderer) => {
+                self.renderer = renderer;
+            }
+            Message::ToggleTestnet(exchange, is_enabled) => {
+                self.testnet.retain(|market| *market != exchange);
+                if is_enabled {
+                    self.testnet.push(exchange);
+                }
+            }
+            Message::ProxyInputChanged(input) => {
+                self.proxy_input = input;
+            }
+            Message::ApplyProxy => {
+                let input = self.proxy_input.trim();
+
+                let proxy = if input.is_empty() {
+                    None
+                } else {
+                    match exchange::connect::Proxy::parse(input) {
+                        Ok(proxy) => Some(proxy),
+                        Err(err) => {
+                            self.notifications.push(Toast::error(err));
+                            return Task::none();
+                        }
+                    }
+                };
+
+                self.proxy_input = proxy
+                    .as_ref()
+                    .map(|proxy| proxy.to_string())
+                    .unwrap_or_default();
+                let applied = match &proxy {
+                    Some(proxy) => format!("New connections go through {proxy}"),
+                    None => "New connections go out directly".to_string(),
+                };
+                self.notifications
+                    .push(match exchange::connect::set_proxy(proxy) {
+                        Ok(()) => Toast::info(applied),
+                        Err(err) => Toast::error(err),
+                    });
+            }
+            Message::EndpointsMarketSelected(exchange) => {
+                self.endpoints_input = (exchange, exchange::adapter::endpoints(exchange));
+            }
+            Message::EndpointsInputChanged(endpoints) => {
+                self.endpoints_input.1 = endpoints;
+            }
+            Message::ApplyEndpoints => {
+                let (exchange, endpoints) = self.endpoints_input.clone();
+
+                if !endpoints.rest.trim().is_empty()
+                    && !endpoints.rest.trim().starts_with("https://")
+                {
+                    self.notifications
+                        .push(Toast::error("REST endpoint has to start with https://"));
+                    return Task::none();
+                }
+
+                self.notifications
+                    .push(Toast::info(if endpoints.is_default() {
+                        format!("{exchange} uses its default endpoints again")
+                    } else {
+                        format!("New connections of {exchange} use the custom endpoints")
+                    }));
+                exchange::adapter::set_endpoints(exchange, endpoints);
+            }
+            Message::ImbalancePresetRemoved(name) => {
+                self.imbalance_presets.remove(&name);
+            }
+            Message::ImbalanceDefaultChanged(market, name) => {
+                self.imbalance_presets.set_default(market, name);
+            }
+            Message::ToggleSnapshotSchedule(is_enabled) => {
+                self.snapshot_schedule.is_enabled = is_enabled;
+            }
+            Message::SnapshotTimesChanged(input) => {
+                if let Some(times) = data::config::SnapshotSchedule::parse_times(&input) {
+                    self.snapshot_schedule.times = times;
+                }
+                self.snapshot_times_input = input;
+            }
+            Message::ScheduledSnapshotTaken(panes, slot, screenshot) => {
+                return Task::batch(panes.into_iter().map(
+                    |(bounds, label)| match screenshot::crop(&screenshot, bounds) {
+                        Ok(image) => Task::perform(
+                            screenshot::save_scheduled(image, label, slot),
+                            Message::ScheduledSnapshotSaved,
+                        ),
+                        Err(err) => Task::done(Message::ScheduledSnapshotSaved(Err(err))),
+                    },
+                ));
+            }
+            Message::ScheduledSnapshotSaved(result) => match result {
+                Ok(path) => log::info!("Saved scheduled snapshot to {}", path.display()),
+                Err(err) => self.notifications.push(Toast::error(format!(
+                    "Failed to save scheduled snapshot: {err}"
+                ))),
+            },
             Message::WindowEvent(event) => match event {
                 window::Event::CloseRequested(window) => {
-                    let main_window = self.main_window.id;
-                    let dashboard = self.active_dashboard_mut();
+                    if window != self.main_window.id {
+                        if let Some(layout_id) = self.layout_manager.detached_layout(window) {
+                            return self.close_detached_layout(layout_id);
+                        }
 
-                    if window != main_window {
-                        dashboard.popout.remove(&window);
+                        if let Some((layout_id, _)) = self.window_owner(window) {
+                            if let Some(dashboard) = self.layout_manager.mut_dashboard(&layout_id) {
+                                dashboard.popout.remove(&window);
+                            }
+                        }
                         return window::close(window);
                     }
 
-                    let mut opened_windows = dashboard
-                        .popout
-                        .keys()
-                        .copied()
-                        .collect::<Vec<window::Id>>();
+                    let mut opened_windows = vec![];
 
-                    opened_windows.push(main_window);
+                    for (layout_id, host) in self.streaming_layouts() {
+                        opened_windows.push(host.id);
+
+                        if let Some(dashboard) = self.layout_manager.dashboard(&layout_id) {
+                            opened_windows.extend(dashboard.popout.keys().copied());
+                        }
+                    }
 
                     return window::collect_window_specs(opened_windows, Message::ExitRequested);
                 }
+                window::Event::Focused(window) => {
+                    if self.pause_hidden_popouts && self.is_popout(window) {
+                        return Task::done(Message::PopoutMinimized(window, false));
+                    }
+                }
+                window::Event::Unfocused(window) => {
+                    if self.pause_hidden_popouts && self.is_popout(window) {
+                        return iced::window::is_minimized(window).map(move |is_minimized| {
+                            Message::PopoutMinimized(window, is_minimized.unwrap_or(false))
+                        });
+                    }
+                }
             },
+            Message::PopoutMinimized(window, is_minimized) => {
+                if let Some((layout_id, host)) = self.window_owner(window) {
+                    if let Some(dashboard) = self.layout_manager.mut_dashboard(&layout_id) {
+                        return dashboard
+                            .set_popout_hidden(window, is_minimized, host.id)
+                            .map(move |msg| Message::Dashboard(Some(layout_id), msg));
+                    }
+                }
+            }
+            Message::RunHealthCheck => {
+                let checks = Exchange::ALL
+                    .iter()
+                    .map(|&exchange| {
+                        Task::perform(
+                            adapter::check_connectivity(exchange).map_err(|err| err.to_string()),
+                            move |result| (exchange, result),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                return Task::batch(checks)
+                    .collect()
+                    .map(Message::HealthCheckCompleted);
+            }
+            Message::HealthCheckCompleted(connectivity) => {
+                let report = data::diagnostics::Report::new(&connectivity);
+
+                for check in report.issues() {
+                    log::warn!("Health check: {} - {}", check.label, check.detail);
+                }
+
+                // show it if anything is off, or if it was explicitly requested
+                let is_visible = report.has_issues() || self.is_health_report_shown();
+
+                self.health_report = Some((report, is_visible));
+            }
+            Message::ToggleHealthReport(is_visible) => {
+                match &mut self.health_report {
+                    Some((_, shown)) => *shown = is_visible,
+                    None if is_visible => {
+                        self.health_report = Some((data::diagnostics::Report::new(&[]), true));
+                    }
+                    None => {}
+                }
+
+                if is_visible {
+                    self.sidebar.set_menu(None);
+                    return Task::done(Message::RunHealthCheck);
+                }
+            }
+            Message::BackupSavedState => {
+                self.confirm_dialog = None;
+
+                match data::backup_file(data::SAVED_STATE_PATH) {
+                    Ok(path) => self.notifications.push(Toast::info(format!(
+                        "Saved state backed up to {}",
+                        path.display()
+                    ))),
+                    Err(err) => self.notifications.push(Toast::error(err.to_string())),
+                }
+            }
+            Message::RestoreBackup(backup) => {
+                self.confirm_dialog = None;
+
+                match data::backup::restore(&backup) {
+                    // exiting through `ExitRequested` would overwrite the restored state
+                    Ok(()) => return iced::exit(),
+                    Err(err) => self.notifications.push(Toast::error(err.to_string())),
+                }
+            }
+            Message::ExportDiagnostics => {
+                if let Some((report, _)) = &self.health_report {
+                    match report.export(env!("CARGO_PKG_VERSION")) {
+                        Ok(path) => self.notifications.push(Toast::info(format!(
+                            "Diagnostics saved to {}",
+                            path.display()
+                        ))),
+                        Err(err) => self.notifications.push(Toast::error(err.to_string())),
+                    }
+                }
+            }
+            Message::TogglePauseHiddenPopouts(is_enabled) => {
+                self.pause_hidden_popouts = is_enabled;
+
+                if !is_enabled {
+                    let main_window = self.main_window.id;
+
+                    return self
+                        .active_dashboard_mut()
+                        .resume_hidden_popouts(main_window)
+                        .map(move |msg| Message::Dashboard(None, msg));
+                }
+            }
             Message::ExitRequested(windows) => {
-                self.active_dashboard_mut()
-                    .popout
-                    .iter_mut()
+                if let Some(batch) = self
+                    .recorder
+                    .as_mut()
+                    .and_then(|recorder| recorder.take_all(std::time::Instant::now()))
+                {
+                    if let Err(err) = batch.write() {
+                        log::error!("Failed to flush the recorder before exiting: {err}");
+                    }
+                }
+
+                self.layout_manager
+                    .iter_dashboards_mut()
+                    .flat_map(|dashboard| dashboard.popout.iter_mut())
                     .for_each(|(id, (_, window_spec))| {
                         if let Some(new_window_spec) = windows.get(id) {
                             *window_spec = *new_window_spec;
                         }
                     });
 
+                let detached = self
+                    .layout_manager
+                    .iter_detached()
+                    .filter_map(|(layout_id, host)| {
+                        let (layout, _) = self.layout_manager.get_layout(layout_id)?;
+                        let window_spec = windows.get(&host.id)?;
+
+                        Some((layout.name.clone(), *window_spec))
+                    })
+                    .collect();
+
                 let mut ser_layouts = vec![];
 
                 for id in &self.layout_manager.layout_order {
@@ -225,6 +1045,7 @@ impl Flowsurface {
                 let layouts = data::Layouts {
                     layouts: ser_layouts,
                     active_layout: self.layout_manager.active_layout().name.clone(),
+                    detached,
                 };
 
                 let main_window = windows
@@ -238,18 +1059,36 @@ impl Flowsurface {
                     layouts,
                     self.theme.clone(),
                     self.theme_editor.custom_theme.clone().map(data::Theme),
+                    self.chart_themes.clone(),
                     self.sidebar.favorited_tickers(),
                     main_window,
                     self.timezone,
                     self.sidebar.state,
                     self.scale_factor,
                     audio_cfg,
+                    self.stream_budget,
+                    self.pause_hidden_popouts,
+                    self.snapshot_schedule.clone(),
+                    self.sidebar.recent_tickers(),
+                    self.sidebar.ticker_hotkeys().clone(),
+                    self.show_status_bar,
+                    self.renderer,
+                    self.imbalance_presets.clone(),
+                    self.recorder_settings,
+                    self.ui_density,
+                    self.alerts_manager.alerts().clone(),
+                    self.chart_text,
+                    self.testnet.clone(),
                 );
 
                 match serde_json::to_string(&layout) {
                     Ok(layout_str) => {
                         let file_name = data::SAVED_STATE_PATH;
 
+                        if let Err(err) = data::backup::rotate() {
+                            log::error!("Failed to rotate saved state backups: {err}");
+                        }
+
                         if let Err(e) = data::write_json_to_file(&layout_str, file_name) {
                             log::error!("Failed to write layout state to file: {}", e);
                         } else {
@@ -264,8 +1103,12 @@ impl Flowsurface {
             Message::GoBack => {
                 let main_window = self.main_window.id;
 
-                if self.confirm_dialog.is_some() {
+                if self.command_palette.is_some() {
+                    self.command_palette = None;
+                } else if self.confirm_dialog.is_some() {
                     self.confirm_dialog = None;
+                } else if self.is_health_report_shown() {
+                    return Task::done(Message::ToggleHealthReport(false));
                 } else if self.sidebar.active_menu().is_some() {
                     self.sidebar.set_menu(None);
                 } else {
@@ -283,9 +1126,12 @@ impl Flowsurface {
             Message::ThemeSelected(theme) => {
                 self.theme = theme.clone();
             }
+            Message::ChartThemeSelected(chart, theme) => {
+                self.chart_themes.set(chart, theme);
+            }
             Message::Dashboard(id, message) => {
-                let main_window = self.main_window;
                 let layout_id = id.unwrap_or(self.layout_manager.active_layout().id);
+                let main_window = self.layout_window(&layout_id);
 
                 if let Some(dashboard) = self.layout_manager.mut_dashboard(&layout_id) {
                     let (main_task, event) = dashboard.update(message, &main_window, &layout_id);
@@ -303,6 +1149,60 @@ impl Flowsurface {
                             self.notifications.push(toast);
                             Task::none()
                         }
+                        Some(dashboard::Event::TagAdded(tag)) => {
+                            self.notifications
+                                .push(Toast::info(format!("Tagged \"{}\"", tag.label)));
+                            self.journal.add_tag(tag);
+                            Task::none()
+                        }
+                        Some(dashboard::Event::ReplayRequested(stream)) => {
+                            let close_previous = self.close_replay();
+
+                            match replay::ReplayPanel::new(layout_id, stream) {
+                                Some(panel) => self.replay = Some(panel),
+                                None => self.notifications.push(Toast::warn(
+                                    "Nothing stored of this ticker to replay. Binance trades fetched into the charts and recorder recordings can be replayed",
+                                )),
+                            }
+                            close_previous
+                        }
+                        Some(dashboard::Event::BackfillRequested(stream)) => {
+                            let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+
+                            match self.recorder_settings.backfill_since(now_ms) {
+                                Some(since) => {
+                                    // what's still pending has to be on disk before it's read back
+                                    let flush = match self.recorder.as_mut().and_then(|recorder| {
+                                        recorder.take_all(std::time::Instant::now())
+                                    }) {
+                                        Some(batch) => Task::perform(
+                                            async move { batch.write().map_err(|err| err.to_string()) },
+                                            Message::RecordingSaved,
+                                        ),
+                                        None => Task::none(),
+                                    };
+
+                                    flush.chain(
+                                        Dashboard::load_recording(stream, since).map(move |msg| {
+                                            Message::Dashboard(Some(layout_id), msg)
+                                        }),
+                                    )
+                                }
+                                None => Task::none(),
+                            }
+                        }
+                        Some(dashboard::Event::AlertMoved(id, price)) => {
+                            self.alerts_manager.move_price(id, price);
+                            Task::none()
+                        }
+                        Some(dashboard::Event::ImbalancePresetSaved(preset)) => {
+                            self.notifications.push(Toast::info(format!(
+                                "Saved imbalance preset \"{}\"",
+                                preset.name
+                            )));
+                            self.imbalance_presets.save(preset);
+                            Task::none()
+                        }
                         None => Task::none(),
                     };
 
@@ -311,10 +1211,9 @@ impl Flowsurface {
                         .chain(additional_task);
                 }
             }
-            Message::RemoveNotification(index) => {
-                if index < self.notifications.len() {
-                    self.notifications.remove(index);
-                }
+            Message::RemoveNotification(index) => self.notifications.remove(index),
+            Message::ToggleNotificationDetails(index) => {
+                self.notifications.toggle_details(index);
             }
             Message::SetTimezone(tz) => {
                 self.timezone = tz;
@@ -322,11 +1221,24 @@ impl Flowsurface {
             Message::ScaleFactorChanged(value) => {
                 self.scale_factor = value;
             }
-            Message::ToggleTradeFetch(checked) => {
+            Message::UiDensityChanged(density) => {
+                self.ui_density = density;
+                style::set_density(density);
+            }
+            Message::ChartTextChanged(chart_text) => {
+                self.chart_text = chart_text;
+                style::set_chart_text(chart_text);
+
+                let main_window = self.main_window.id;
+                self.layout_manager
+                    .iter_dashboards_mut()
+                    .for_each(|dashboard| dashboard.invalidate_all_panes(main_window));
+            }
+            Message::ToggleTradeFetch(exchange, checked) => {
                 self.layout_manager
                     .iter_dashboards_mut()
                     .for_each(|dashboard| {
-                        dashboard.toggle_trade_fetch(checked, &self.main_window);
+                        dashboard.toggle_trade_fetch(exchange, checked, &self.main_window);
                     });
 
                 if checked {
@@ -341,6 +1253,10 @@ impl Flowsurface {
 
                 match action {
                     Some(modal::layout_manager::Action::Select(layout)) => {
+                        if let Some(host) = self.layout_manager.detached_window(&layout.id) {
+                            return iced::window::gain_focus(host.id);
+                        }
+
                         let old_layout = self.layout_manager.active_layout().clone();
 
                         let active_popout_keys = self
@@ -366,10 +1282,24 @@ impl Flowsurface {
                         .chain(window_tasks)
                         .chain(self.load_layout(layout, self.main_window.id));
                     }
-                    None => {}
+                    Some(modal::layout_manager::Action::Detach(layout)) => {
+                        return self.open_detached_layout(layout, None);
+                    }
+                    // the active layout may have been renamed
+                    None => self.sync_crash_context(),
                 }
             }
             Message::AudioStream(message) => self.audio_stream.update(message),
+            Message::LazyStreams(is_enabled) => {
+                self.confirm_dialog = None;
+
+                let main_window = self.main_window.id;
+
+                return self
+                    .active_dashboard_mut()
+                    .set_lazy_streams(is_enabled, main_window)
+                    .map(move |msg| Message::Dashboard(None, msg));
+            }
             Message::DataFolderRequested => {
                 if let Err(err) = data::open_data_folder() {
                     self.notifications
@@ -402,7 +1332,31 @@ impl Flowsurface {
                         let main_window_id = self.main_window.id;
 
                         let task = {
-                            if let Some(content_str) = content {
+                            if content.as_deref() == Some("spread") {
+                                let (symbol, _) = ticker_info.ticker.to_full_symbol_and_type();
+                                let leg = ticker_info.exchange().spot_perp_counterpart().and_then(
+                                    |exchange| {
+                                        self.sidebar.ticker_info(
+                                            exchange,
+                                            exchange::Ticker::new(&symbol, exchange),
+                                        )
+                                    },
+                                );
+
+                                match leg {
+                                    Some(leg) => self.active_dashboard_mut().init_focused_spread(
+                                        main_window_id,
+                                        ticker_info,
+                                        leg,
+                                    ),
+                                    None => {
+                                        self.notifications.push(Toast::warn(format!(
+                                            "No spot or perp market of {symbol} to spread against"
+                                        )));
+                                        Task::none()
+                                    }
+                                }
+                            } else if let Some(content_str) = content {
                                 self.active_dashboard_mut().init_focused_pane(
                                     main_window_id,
                                     ticker_info,
@@ -419,6 +1373,27 @@ impl Flowsurface {
                     Some(dashboard::sidebar::Action::ErrorOccurred(err)) => {
                         self.notifications.push(Toast::error(err.to_string()));
                     }
+                    Some(dashboard::sidebar::Action::ToggleStreamsPaused) => {
+                        let paused = !self.all_streams_paused();
+                        let replaying = self.replay.as_ref().map(|panel| panel.layout_id);
+
+                        let tasks = self
+                            .streaming_layouts()
+                            .into_iter()
+                            // a replaying layout stays paused until its replay ends
+                            .filter(|(layout_id, _)| Some(*layout_id) != replaying)
+                            .filter_map(|(layout_id, window)| {
+                                let dashboard = self.layout_manager.mut_dashboard(&layout_id)?;
+                                Some(
+                                    dashboard
+                                        .set_streams_paused(paused, window.id)
+                                        .map(move |msg| Message::Dashboard(Some(layout_id), msg)),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+
+                        return Task::batch(tasks);
+                    }
                     None => {}
                 }
 
@@ -435,11 +1410,16 @@ impl Flowsurface {
         let content = if id == self.main_window.id {
             let sidebar_view = self
                 .sidebar
-                .view(self.audio_stream.volume())
+                .view(self.audio_stream.volume(), self.all_streams_paused())
                 .map(Message::Sidebar);
 
             let dashboard_view = dashboard
-                .view(&self.main_window, self.timezone)
+                .view(
+                    &self.main_window,
+                    self.timezone,
+                    &self.imbalance_presets,
+                    &self.chart_themes,
+                )
                 .map(move |msg| Message::Dashboard(None, msg));
 
             let header_title = {
@@ -464,7 +1444,7 @@ impl Flowsurface {
                 }
             };
 
-            let base = column![
+            let mut base = column![
                 header_title,
                 match sidebar_pos {
                     sidebar::Position::Left => row![sidebar_view, dashboard_view,],
@@ -474,16 +1454,77 @@ impl Flowsurface {
                 .padding(8),
             ];
 
-            if let Some(menu) = self.sidebar.active_menu() {
-                self.view_with_modal(base.into(), dashboard, menu)
-            } else {
-                base.into()
+            if let Some(panel) = &self.replay {
+                base = base.push(panel.view(self.timezone).map(Message::Replay));
+            }
+
+            if self.show_status_bar {
+                base = base.push(self.status_bar.view(self.timezone).map(Message::StatusBar));
+            }
+
+            match (self.sidebar.active_menu(), &self.confirm_dialog) {
+                _ if self.command_palette.is_some() => main_dialog_modal(
+                    base,
+                    self.command_palette
+                        .as_ref()
+                        .map_or_else(|| column![].into(), |palette| palette.view())
+                        .map(Message::CommandPalette),
+                    Message::ToggleCommandPalette,
+                ),
+                (Some(menu), _) => self.view_with_modal(base.into(), dashboard, menu),
+                (None, None) if self.is_health_report_shown() => main_dialog_modal(
+                    base,
+                    self.view_health_report(),
+                    Message::ToggleHealthReport(false),
+                ),
+                (None, Some((dialog, on_confirm))) => main_dialog_modal(
+                    base,
+                    confirm_dialog_container(
+                        dialog,
+                        *on_confirm.to_owned(),
+                        Message::ToggleDialogModal(None),
+                    ),
+                    Message::ToggleDialogModal(None),
+                ),
+                (None, None) => base.into(),
+            }
+        } else if let Some(layout_id) = self.layout_manager.detached_layout(id) {
+            let host = self.layout_window(&layout_id);
+
+            match self.layout_manager.dashboard(&layout_id) {
+                Some(dashboard) => container(
+                    dashboard
+                        .view(
+                            &host,
+                            self.timezone,
+                            &self.imbalance_presets,
+                            &self.chart_themes,
+                        )
+                        .map(move |msg| Message::Dashboard(Some(layout_id), msg)),
+                )
+                .padding(padding::all(8).top(style::TITLE_PADDING_TOP + 8.0))
+                .into(),
+                None => iced::widget::center(text("Layout not found")).into(),
             }
         } else {
+            let (layout_id, host) = self
+                .window_owner(id)
+                .unwrap_or((self.layout_manager.active_layout().id, self.main_window));
+            let dashboard = self
+                .layout_manager
+                .dashboard(&layout_id)
+                .unwrap_or(dashboard);
+
             container(
                 dashboard
-                    .view_window(id, &self.main_window, self.timezone)
-                    .map(move |msg| Message::Dashboard(None, msg)),
+                    .view_window(
+                        id,
+                        &host,
+                        self.timezone,
+                        &self.imbalance_presets,
+                        &self.chart_themes,
+                    )
+                    .map(move |msg| Message::Dashboard(Some(layout_id), msg)),
             )
             .padding(padding::top(style::TITLE_PADDING_TOP))
             .into()
@@ -497,6 +1538,7 @@ impl Flowsurface {
                 sidebar::Position::Right => Alignment::End,
             },
             Message::RemoveNotification,
+            Message::ToggleNotificationDetails,
         )
         .into()
     }
@@ -505,8 +1547,16 @@ impl Flowsurface {
         self.theme.clone().into()
     }
 
-    fn title(&self, _window: window::Id) -> String {
-        format!("Flowsurface [{}]", self.layout_manager.active_layout().name)
+    fn title(&self, window: window::Id) -> String {
+        let layout_name = self
+            .window_owner(window)
+            .and_then(|(layout_id, _)| self.layout_manager.get_layout(layout_id))
+            .map_or_else(
+                || self.layout_manager.active_layout().name,
+                |(layout, _)| layout.name.clone(),
+            );
+
+        format!("Flowsurface [{layout_name}]")
     }
 
     fn scale_factor(&self, _window: window::Id) -> f64 {
@@ -517,15 +1567,51 @@ impl Flowsurface {
         let window_events = window::events().map(Message::WindowEvent);
         let sidebar = self.sidebar.subscription().map(Message::Sidebar);
 
-        let exchange_streams = self
-            .active_dashboard()
-            .market_subscriptions()
-            .map(Message::MarketWsEvent);
+        let exchange_streams = Subscription::batch(
+            self.streaming_layouts()
+                .into_iter()
+                .filter_map(|(layout_id, _)| self.layout_manager.dashboard(&layout_id))
+                .map(Dashboard::market_subscriptions)
+                .collect::<Vec<_>>(),
+        )
+        .map(Message::MarketWsEvent);
 
         let tick = iced::time::every(std::time::Duration::from_millis(100)).map(Message::Tick);
 
-        let hotkeys = keyboard::on_key_press(|key, _| match key.as_ref() {
+        let hotkeys = keyboard::on_key_press(|key, modifiers| match key.as_ref() {
             keyboard::Key::Named(keyboard::key::Named::Escape) => Some(Message::GoBack),
+            keyboard::Key::Character("b") if modifiers.command() => {
+                Some(Message::Sidebar(dashboard::sidebar::Message::ToggleReveal))
+            }
+            keyboard::Key::Character("k") if modifiers.command() => {
+                Some(Message::ToggleCommandPalette)
+            }
+            keyboard::Key::Character("h") if modifiers.command() && modifiers.shift() => {
+                Some(Message::ToggleCleanMode)
+            }
+            // step the focused chart's bar replay, charts not replaying ignore them
+            keyboard::Key::Named(keyboard::key::Named::ArrowRight) if modifiers.shift() => {
+                Some(Message::StepBarReplay(1))
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowLeft) if modifiers.shift() => {
+                Some(Message::StepBarReplay(-1))
+            }
+            // only acted on while the command palette is open
+            keyboard::Key::Named(keyboard::key::Named::ArrowUp) => Some(Message::CommandPalette(
+                modal::command::Message::MoveSelection(-1),
+            )),
+            keyboard::Key::Named(keyboard::key::Named::ArrowDown) => Some(Message::CommandPalette(
+                modal::command::Message::MoveSelection(1),
+            )),
+            keyboard::Key::Character(digit) if modifiers.command() => digit
+                .parse::<u8>()
+                .ok()
+                .filter(|slot| (1..=9).contains(slot))
+                .map(|slot| {
+                    Message::Sidebar(dashboard::sidebar::Message::TickersTable(
+                        dashboard::tickers_table::Message::HotkeyPressed(slot),
+                    ))
+                }),
             _ => None,
         });
 
@@ -538,6 +1624,35 @@ impl Flowsurface {
         ])
     }
 
+    fn command_context(&self) -> modal::command::Context {
+        let layouts = self
+            .layout_manager
+            .layout_order
+            .iter()
+            .filter_map(|id| self.layout_manager.get_layout(*id))
+            .map(|(layout, _)| layout.clone())
+            .collect();
+
+        modal::command::Context {
+            tickers: self.sidebar.tickers_info(),
+            layouts,
+            indicators: self
+                .active_dashboard()
+                .focused_indicators(self.main_window.id),
+        }
+    }
+
+    /// Names the active layout and its panes in crash reports, kept up to date as layouts
+    /// load and their panes change rather than on every tick
+    fn sync_crash_context(&self) {
+        if data::crash::is_enabled() {
+            data::crash::set_layout(
+                &self.layout_manager.active_layout().name,
+                self.active_dashboard().pane_summary(self.main_window.id),
+            );
+        }
+    }
+
     fn active_dashboard(&self) -> &Dashboard {
         self.layout_manager
             .active_dashboard()
@@ -550,12 +1665,316 @@ impl Flowsurface {
             .expect("No active dashboard")
     }
 
-    fn load_layout(&mut self, layout: layout::Layout, main_window: window::Id) -> Task<Message> {
+    /// The window hosting a layout's pane grid, the main one unless it's detached
+    fn layout_window(&self, layout_id: &uuid::Uuid) -> window::Window {
+        self.layout_manager
+            .detached_window(layout_id)
+            .unwrap_or(self.main_window)
+    }
+
+    /// Layouts currently on screen and streaming, the active one first
+    /// Ends the replay if one is open, putting its layout back on the live streams
+    fn close_replay(&mut self) -> Task<Message> {
+        let Some(panel) = self.replay.take() else {
+            return Task::none();
+        };
+
+        if !panel.is_active() {
+            return Task::none();
+        }
+
+        let layout_id = panel.layout_id;
+        let window = self.layout_window(&layout_id);
+
+        match self.layout_manager.mut_dashboard(&layout_id) {
+            Some(dashboard) => dashboard
+                .end_replay(&panel.stream, window.id)
+                .map(move |msg| Message::Dashboard(Some(layout_id), msg)),
+            None => Task::none(),
+        }
+    }
+
+    /// Fails panes over once their exchange stayed disconnected for the grace period, the
+    /// exchange gets another one if it keeps failing to reconnect
+    fn fail_over_down_exchanges(&mut self, now: std::time::Instant) -> Task<Message> {
+        let down = self
+            .disconnected_since
+            .iter()
+            .filter(|(_, since)| now.duration_since(**since) >= FAILOVER_GRACE)
+            .map(|(exchange, _)| *exchange)
+            .collect::<Vec<_>>();
+
+        if down.is_empty() {
+            return Task::none();
+        }
+
+        let layouts = self.streaming_layouts();
+        let mut tasks = vec![];
+
+        for exchange in down {
+            self.disconnected_since.remove(&exchange);
+
+            for (layout_id, window) in &layouts {
+                let layout_id = *layout_id;
+                let sidebar = &self.sidebar;
+
+                if let Some(dashboard) = self.layout_manager.mut_dashboard(&layout_id) {
+                    tasks.push(
+                        dashboard
+                            .fail_over(
+                                exchange,
+                                |exchange, ticker| sidebar.ticker_info(exchange, ticker),
+                                window.id,
+                            )
+                            .map(move |msg| Message::Dashboard(Some(layout_id), msg)),
+                    );
+                }
+            }
+        }
+
+        Task::batch(tasks)
+    }
+
+    /// Whether every layout that could be streaming is paused, what "pause all" toggles
+    fn all_streams_paused(&self) -> bool {
+        self.streaming_layouts().iter().all(|(layout_id, _)| {
+            self.layout_manager
+                .dashboard(layout_id)
+                .is_none_or(|dashboard| dashboard.is_streams_paused())
+        })
+    }
+
+    fn streaming_layouts(&self) -> Vec<(uuid::Uuid, window::Window)> {
+        std::iter::once((self.layout_manager.active_layout().id, self.main_window))
+            .chain(self.layout_manager.iter_detached())
+            .collect()
+    }
+
+    /// The layout a window belongs to, either as its host window or as one of its popouts
+    fn window_owner(&self, window: window::Id) -> Option<(uuid::Uuid, window::Window)> {
+        self.streaming_layouts()
+            .into_iter()
+            .find(|(layout_id, host)| {
+                host.id == window
+                    || self
+                        .layout_manager
+                        .dashboard(layout_id)
+                        .is_some_and(|dashboard| dashboard.popout.contains_key(&window))
+            })
+    }
+
+    fn is_popout(&self, window: window::Id) -> bool {
+        self.window_owner(window)
+            .is_some_and(|(_, host)| host.id != window)
+    }
+
+    fn open_detached_layout(
+        &mut self,
+        layout: layout::Layout,
+        window_spec: Option<WindowSpec>,
+    ) -> Task<Message> {
+        let (position, size) = window_spec.map_or(
+            (window::Position::Default, window::default_size()),
+            |spec| (window::Position::Specific(spec.position()), spec.size()),
+        );
+
+        let (window_id, open_window) = window::open(window::Settings {
+            size,
+            position,
+            exit_on_close_request: false,
+            ..window::settings()
+        });
+
         self.layout_manager
+            .detach(layout.id, window::Window::new(window_id));
+
+        let Some(dashboard) = self.layout_manager.mut_dashboard(&layout.id) else {
+            return open_window.discard();
+        };
+
+        let load_layout = dashboard
+            .load_layout(window_id, layout.id)
+            .map(move |msg| Message::Dashboard(Some(layout.id), msg));
+
+        open_window.discard().chain(load_layout)
+    }
+
+    /// Closes a detached layout's window along with its popouts, keeping their specs
+    /// so they reopen in place once the layout is shown again
+    fn close_detached_layout(&mut self, layout_id: uuid::Uuid) -> Task<Message> {
+        let Some(host) = self.layout_manager.remove_detached(&layout_id) else {
+            return Task::none();
+        };
+
+        let popout_keys = self
+            .layout_manager
+            .dashboard(&layout_id)
+            .map(|dashboard| dashboard.popout.keys().copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let window_tasks = Task::batch(
+            popout_keys
+                .iter()
+                .copied()
+                .chain(std::iter::once(host.id))
+                .map(window::close)
+                .collect::<Vec<_>>(),
+        )
+        .then(|_: Task<window::Id>| Task::none());
+
+        window::collect_window_specs(popout_keys, dashboard::Message::SavePopoutSpecs)
+            .map(move |msg| Message::Dashboard(Some(layout_id), msg))
+            .chain(window_tasks)
+    }
+
+    fn load_layout(&mut self, layout: layout::Layout, main_window: window::Id) -> Task<Message> {
+        let dashboard = self
+            .layout_manager
             .set_active_layout(layout.clone())
-            .expect("Failed to set active layout")
+            .expect("Failed to set active layout");
+
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+        let restore = match self.recorder_settings.restore_since(now_ms) {
+            Some(since) => dashboard.restore_recordings(main_window, since),
+            None => Task::none(),
+        };
+
+        let task = dashboard
             .load_layout(main_window, layout.id)
-            .map(move |msg| Message::Dashboard(Some(layout.id), msg))
+            .chain(restore)
+            .map(move |msg| Message::Dashboard(Some(layout.id), msg));
+
+        let (depth_streams, kline_streams, msg_rate) = dashboard.stream_cost(main_window);
+        let violations = self
+            .stream_budget
+            .violations(depth_streams, kline_streams, msg_rate);
+
+        if !violations.is_empty() {
+            log::warn!(
+                "Layout {} exceeds stream budget: {}",
+                layout.name,
+                violations.join(", ")
+            );
+
+            if self.confirm_dialog.is_none() {
+                self.confirm_dialog = Some((
+                    format!(
+                        "Layout \"{}\" exceeds the stream budget with {}.\n\nStart streams only for visible panes?",
+                        layout.name,
+                        violations.join(", ")
+                    ),
+                    Box::new(Message::LazyStreams(true)),
+                ));
+            } else {
+                self.notifications.push(Toast::warn(format!(
+                    "Layout \"{}\" exceeds the stream budget with {}",
+                    layout.name,
+                    violations.join(", ")
+                )));
+            }
+        }
+
+        task
+    }
+
+    /// Captures the charts marked for scheduled snapshots once per due time slot, each window
+    /// gets captured once and every chart cut out of it into a file of its own
+    fn scheduled_snapshots(&mut self) -> Task<Message> {
+        let Some(slot) = self.snapshot_schedule.due_slot(self.timezone.now()) else {
+            return Task::none();
+        };
+        if self.last_snapshot_slot == Some(slot) {
+            return Task::none();
+        }
+        self.last_snapshot_slot = Some(slot);
+
+        let targets = self
+            .active_dashboard()
+            .auto_snapshot_targets(self.main_window.id);
+
+        Task::batch(targets.into_iter().map(|(window, panes)| {
+            iced::window::screenshot(window).map(move |screenshot| {
+                Message::ScheduledSnapshotTaken(panes.clone(), slot, screenshot)
+            })
+        }))
+    }
+
+    fn is_health_report_shown(&self) -> bool {
+        self.health_report.as_ref().is_some_and(|(_, shown)| *shown)
+    }
+
+    fn view_health_report(&self) -> Element<'_, Message> {
+        let mut checks = column![].spacing(6);
+
+        if let Some((report, _)) = &self.health_report {
+            for check in &report.checks {
+                let severity =
+                    text(check.severity.to_string()).style(move |theme: &iced::Theme| {
+                        let palette = theme.extended_palette();
+                        iced::widget::text::Style {
+                            color: Some(match check.severity {
+                                data::diagnostics::Severity::Ok => palette.success.base.color,
+                                data::diagnostics::Severity::Warning => palette.warning.base.color,
+                                data::diagnostics::Severity::Error => palette.danger.base.color,
+                            }),
+                        }
+                    });
+
+                checks = checks.push(
+                    row![
+                        container(severity).width(48),
+                        text(&check.label).width(120),
+                        text(&check.detail),
+                    ]
+                    .spacing(8),
+                );
+            }
+        }
+
+        let render_times = {
+            let summaries = chart::profiler::summaries();
+
+            let mut list = column![text("Render times").size(14)].spacing(6);
+
+            if summaries.is_empty() {
+                list = list.push(text("No charts drawn yet").size(12));
+            }
+
+            for summary in summaries {
+                list = list.push(
+                    row![
+                        text(summary.label).width(168),
+                        text(format!("avg {:.2}ms", summary.avg.as_secs_f64() * 1000.0)).width(96),
+                        text(format!("max {:.2}ms", summary.max.as_secs_f64() * 1000.0)).width(96),
+                        text(format!("last {} redraws", summary.samples)),
+                    ]
+                    .spacing(8),
+                );
+            }
+
+            list
+        };
+
+        container(
+            column![
+                text("Health check").size(16),
+                checks,
+                render_times,
+                row![
+                    button(text("Dismiss"))
+                        .style(|theme, status| style::button::transparent(theme, status, false))
+                        .on_press(Message::ToggleHealthReport(false)),
+                    button(text("Export diagnostics")).on_press(Message::ExportDiagnostics),
+                ]
+                .spacing(8),
+            ]
+            .align_x(Alignment::Center)
+            .spacing(16),
+        )
+        .max_width(520)
+        .padding(style::dense(24.0))
+        .style(style::dashboard_modal)
+        .into()
     }
 
     fn view_with_modal<'a>(
@@ -569,7 +1988,7 @@ impl Flowsurface {
         match menu {
             sidebar::Menu::Settings => {
                 let settings_modal = {
-                    let theme_picklist = {
+                    let themes = {
                         let mut themes: Vec<iced::Theme> = iced_core::Theme::ALL.to_vec();
 
                         let default_theme = iced_core::Theme::Custom(default_theme().into());
@@ -579,9 +1998,47 @@ impl Flowsurface {
                             themes.push(custom_theme.clone());
                         }
 
-                        pick_list(themes, Some(self.theme.0.clone()), |theme| {
+                        themes
+                    };
+
+                    let theme_picklist =
+                        pick_list(themes.clone(), Some(self.theme.0.clone()), |theme| {
                             Message::ThemeSelected(data::Theme(theme))
-                        })
+                        });
+
+                    let chart_themes = {
+                        let mut list = column![].spacing(4);
+
+                        for chart in data::config::theme::ThemedChart::ALL {
+                            let selected =
+                                self.chart_themes.get(chart).map(|theme| theme.0.clone());
+                            let is_set = selected.is_some();
+
+                            let clear = button(style::icon_text(style::Icon::Close, 10))
+                                .on_press_maybe(
+                                    is_set.then_some(Message::ChartThemeSelected(chart, None)),
+                                )
+                                .style(move |theme, status| {
+                                    style::button::transparent(theme, status, false)
+                                });
+
+                            list = list.push(
+                                row![
+                                    text(chart.to_string()).size(12),
+                                    iced::widget::horizontal_space(),
+                                    pick_list(themes.clone(), selected, move |theme| {
+                                        Message::ChartThemeSelected(chart, Some(data::Theme(theme)))
+                                    })
+                                    .placeholder("App theme")
+                                    .text_size(12),
+                                    clear,
+                                ]
+                                .spacing(8)
+                                .align_y(Alignment::Center),
+                            );
+                        }
+
+                        list
                     };
 
                     let toggle_theme_editor = button(text("Theme editor")).on_press(
@@ -604,6 +2061,68 @@ impl Flowsurface {
                         },
                     );
 
+                    let ui_density = column![
+                        pick_list(
+                            data::config::UiDensity::ALL,
+                            Some(self.ui_density),
+                            Message::UiDensityChanged,
+                        ),
+                        text("Text size changes apply on the next launch").size(11),
+                    ]
+                    .spacing(8);
+
+                    let chart_text = {
+                        let chart_text = self.chart_text;
+
+                        let font_picklist = pick_list(
+                            data::config::ChartFont::ALL,
+                            Some(chart_text.font),
+                            move |font| {
+                                Message::ChartTextChanged(data::config::ChartText {
+                                    font,
+                                    ..chart_text
+                                })
+                            },
+                        );
+
+                        let size = chart_text.clamped_size();
+
+                        let size_stepper = container(
+                            row![
+                                button(text("-")).on_press_maybe(
+                                    (size > data::config::MIN_CHART_TEXT_SIZE).then_some(
+                                        Message::ChartTextChanged(data::config::ChartText {
+                                            size: size - 1.0,
+                                            ..chart_text
+                                        })
+                                    )
+                                ),
+                                text(format!("{size:.0}px")).size(14),
+                                button(text("+")).on_press_maybe(
+                                    (size < data::config::MAX_CHART_TEXT_SIZE).then_some(
+                                        Message::ChartTextChanged(data::config::ChartText {
+                                            size: size + 1.0,
+                                            ..chart_text
+                                        })
+                                    )
+                                ),
+                            ]
+                            .align_y(Alignment::Center)
+                            .spacing(8)
+                            .padding(4),
+                        )
+                        .style(style::modal_container);
+
+                        let mut content = column![font_picklist, size_stepper].spacing(8);
+
+                        if !chart_text.font.is_bundled() {
+                            content = content
+                                .push(text("Falls back to another font if not installed").size(11));
+                        }
+
+                        content
+                    };
+
                     let scale_factor = {
                         let current_value: f64 = self.scale_factor.into();
 
@@ -634,29 +2153,649 @@ impl Flowsurface {
                         .style(style::modal_container)
                     };
 
-                    let trade_fetch_checkbox = {
-                        let is_active = exchange::fetcher::is_trade_fetch_enabled();
-
-                        let checkbox = iced::widget::checkbox("Fetch trades (Binance)", is_active)
-                            .on_toggle(|checked| {
-                                if checked {
-                                    Message::ToggleDialogModal(Some((
-                                        "This might be unreliable and take some time to complete"
-                                            .to_string(),
-                                        Box::new(Message::ToggleTradeFetch(true)),
-                                    )))
-                                } else {
-                                    Message::ToggleTradeFetch(false)
+                    let trade_fetch_checkboxes = {
+                        let checkboxes = Exchange::ALL
+                            .into_iter()
+                            .filter(Exchange::supports_trade_fetch)
+                            .map(|exchange| {
+                                let is_active = exchange::fetcher::is_trade_fetch_enabled(exchange);
+
+                                iced::widget::checkbox(exchange.to_string(), is_active)
+                                    .on_toggle(move |checked| {
+                                        if checked {
+                                            Message::ToggleDialogModal(Some((
+                                                trade_fetch_consent_prompt(exchange),
+                                                Box::new(Message::ToggleTradeFetch(exchange, true)),
+                                            )))
+                                        } else {
+                                            Message::ToggleTradeFetch(exchange, false)
+                                        }
+                                    })
+                                    .into()
+                            })
+                            .collect::<Vec<Element<_>>>();
+
+                        tooltip(
+                            column![text("Fetch trades").size(13), column(checkboxes).spacing(4)]
+                                .spacing(4),
+                            Some("Try to fetch trades for footprint charts"),
+                            TooltipPosition::Top,
+                        )
+                    };
+
+                    let testnet_checkboxes = {
+                        let checkboxes = Exchange::ALL
+                            .into_iter()
+                            .filter(Exchange::supports_testnet)
+                            .map(|exchange| {
+                                iced::widget::checkbox(
+                                    exchange.to_string(),
+                                    self.testnet.contains(&exchange),
+                                )
+                                .on_toggle(move |checked| Message::ToggleTestnet(exchange, checked))
+                                .into()
+                            })
+                            .collect::<Vec<Element<_>>>();
+
+                        column![
+                            tooltip(
+                                text("Use testnet").size(13),
+                                Some("Connect streams and requests of these markets to the exchange testnet, its tickers and prices differ from the live ones"),
+                                TooltipPosition::Top,
+                            ),
+                            column(checkboxes).spacing(4),
+                            text("Changes apply on the next launch").size(11),
+                        ]
+                        .spacing(4)
+                    };
+
+                    let status_bar_checkbox =
+                        iced::widget::checkbox("Show status bar", self.show_status_bar)
+                            .on_toggle(Message::ToggleStatusBar);
+
+                    let crash_reports_checkbox = tooltip(
+                        iced::widget::checkbox("Save crash reports", data::crash::is_enabled())
+                            .on_toggle(Message::ToggleCrashReports),
+                        Some(
+                            "Writes the panic, open panes and recent events to the data folder when the app crashes. Nothing is sent anywhere",
+                        ),
+                        TooltipPosition::Top,
+                    );
+
+                    let recorder_settings = {
+                        let checkbox = iced::widget::checkbox(
+                            "Record depth streams",
+                            self.recorder_settings.is_enabled,
+                        )
+                        .on_toggle(Message::ToggleRecorder);
+
+                        let restore_picklist = pick_list(
+                            data::config::RecorderSettings::RESTORE_HOURS,
+                            Some(self.recorder_settings.restore_hours),
+                            Message::RecorderRestoreChanged,
+                        );
+                        let backfill_picklist = pick_list(
+                            data::config::RecorderSettings::BACKFILL_MINUTES,
+                            Some(self.recorder_settings.backfill_minutes),
+                            Message::RecorderBackfillChanged,
+                        );
+
+                        column![
+                            tooltip(
+                                checkbox,
+                                Some("Store trades and orderbook snapshots on disk, compressed per ticker and day"),
+                                TooltipPosition::Top,
+                            ),
+                            row![
+                                text("Restore last hours").size(12),
+                                iced::widget::horizontal_space(),
+                                restore_picklist,
+                            ]
+                            .spacing(8)
+                            .align_y(Alignment::Center),
+                            row![
+                                text("Backfill opened heatmaps, minutes").size(12),
+                                iced::widget::horizontal_space(),
+                                backfill_picklist,
+                            ]
+                            .spacing(8)
+                            .align_y(Alignment::Center),
+                        ]
+                        .spacing(8)
+                    };
+
+                    let chart_defaults = {
+                        let defaults = data::config::chart_defaults();
+
+                        let timeframe_row =
+                            |label: &'static str,
+                             options: &'static [exchange::Timeframe],
+                             selected: exchange::Timeframe,
+                             on_select: fn(exchange::Timeframe) -> data::config::ChartDefaults| {
+                                row![
+                                    text(label).size(12),
+                                    iced::widget::horizontal_space(),
+                                    pick_list(options, Some(selected), move |timeframe| {
+                                        Message::ChartDefaultsChanged(on_select(timeframe))
+                                    }),
+                                ]
+                                .spacing(8)
+                                .align_y(Alignment::Center)
+                            };
+
+                        let tick_count_picklist = pick_list(
+                            data::aggr::TickCount::ALL,
+                            Some(defaults.tick_count),
+                            move |tick_count| {
+                                Message::ChartDefaultsChanged(data::config::ChartDefaults {
+                                    tick_count,
+                                    ..defaults
+                                })
+                            },
+                        );
+
+                        column![
+                            timeframe_row(
+                                "Candlestick",
+                                &exchange::Timeframe::KLINE,
+                                defaults.candlestick,
+                                |candlestick| data::config::ChartDefaults {
+                                    candlestick,
+                                    ..data::config::chart_defaults()
+                                },
+                            ),
+                            timeframe_row(
+                                "Footprint",
+                                &exchange::Timeframe::KLINE,
+                                defaults.footprint,
+                                |footprint| data::config::ChartDefaults {
+                                    footprint,
+                                    ..data::config::chart_defaults()
+                                },
+                            ),
+                            timeframe_row(
+                                "Delta",
+                                &exchange::Timeframe::KLINE,
+                                defaults.delta,
+                                |delta| data::config::ChartDefaults {
+                                    delta,
+                                    ..data::config::chart_defaults()
+                                },
+                            ),
+                            timeframe_row(
+                                "TPO",
+                                &exchange::Timeframe::KLINE,
+                                defaults.tpo,
+                                |tpo| data::config::ChartDefaults {
+                                    tpo,
+                                    ..data::config::chart_defaults()
+                                },
+                            ),
+                            timeframe_row(
+                                "Heatmap",
+                                &exchange::Timeframe::HEATMAP,
+                                defaults.heatmap,
+                                |heatmap| data::config::ChartDefaults {
+                                    heatmap,
+                                    ..data::config::chart_defaults()
+                                },
+                            ),
+                            row![
+                                text("Tick count").size(12),
+                                iced::widget::horizontal_space(),
+                                tick_count_picklist,
+                            ]
+                            .spacing(8)
+                            .align_y(Alignment::Center),
+                            text("Used by newly opened panes").size(11),
+                        ]
+                        .spacing(8)
+                    };
+
+                    let depth_fallback = {
+                        let fallback = exchange::depth::depth_fallback();
+
+                        let checkbox = iced::widget::checkbox(
+                            "Snapshots on repeated desyncs",
+                            fallback.enabled,
+                        )
+                        .on_toggle(move |enabled| {
+                            Message::DepthFallbackChanged(exchange::depth::DepthFallback {
+                                enabled,
+                                ..fallback
+                            })
+                        });
+
+                        let mut content = column![tooltip(
+                            checkbox,
+                            Some("Refresh the orderbook from REST at a low rate instead of reconnecting, Binance only"),
+                            TooltipPosition::Top,
+                        )]
+                        .spacing(8);
+
+                        if fallback.enabled {
+                            let desyncs = iced::widget::slider(
+                                2.0..=10.0,
+                                f32::from(fallback.desyncs),
+                                move |value| {
+                                    Message::DepthFallbackChanged(exchange::depth::DepthFallback {
+                                        desyncs: value as u8,
+                                        ..fallback
+                                    })
+                                },
+                            );
+
+                            let refresh = iced::widget::slider(
+                                f32::from(exchange::depth::DepthFallback::MIN_REFRESH_SECS)
+                                    ..=f32::from(exchange::depth::DepthFallback::MAX_REFRESH_SECS),
+                                f32::from(fallback.refresh_secs),
+                                move |value| {
+                                    Message::DepthFallbackChanged(exchange::depth::DepthFallback {
+                                        refresh_secs: value as u16,
+                                        ..fallback
+                                    })
+                                },
+                            )
+                            .step(5.0);
+
+                            content = content.push(
+                                column![
+                                    text(format!(
+                                        "After {} desyncs in 2 minutes",
+                                        fallback.desyncs
+                                    ))
+                                    .size(12),
+                                    desyncs,
+                                    text(format!("Refresh every {}s", fallback.refresh_secs))
+                                        .size(12),
+                                    refresh,
+                                ]
+                                .spacing(4),
+                            );
+                        }
+
+                        content
+                    };
+
+                    let reconnect_policy = {
+                        let policy = exchange::connect::reconnect_policy();
+                        let max_wait = f32::from(exchange::connect::ReconnectPolicy::MAX_WAIT_SECS);
+
+                        let base = iced::widget::slider(
+                            1.0..=30.0,
+                            f32::from(policy.base_secs),
+                            move |value| {
+                                Message::ReconnectPolicyChanged(
+                                    exchange::connect::ReconnectPolicy {
+                                        base_secs: value as u16,
+                                        ..policy
+                                    },
+                                )
+                            },
+                        );
+
+                        let max = iced::widget::slider(
+                            f32::from(policy.base_secs)..=max_wait,
+                            f32::from(policy.max_secs.max(policy.base_secs)),
+                            move |value| {
+                                Message::ReconnectPolicyChanged(
+                                    exchange::connect::ReconnectPolicy {
+                                        max_secs: value as u16,
+                                        ..policy
+                                    },
+                                )
+                            },
+                        )
+                        .step(5.0);
+
+                        let retries = iced::widget::slider(
+                            0.0..=50.0,
+                            f32::from(policy.max_retries),
+                            move |value| {
+                                Message::ReconnectPolicyChanged(
+                                    exchange::connect::ReconnectPolicy {
+                                        max_retries: value as u16,
+                                        ..policy
+                                    },
+                                )
+                            },
+                        );
+
+                        let retries_label = match policy.max_retries {
+                            0 => "Retry forever".to_string(),
+                            retries => format!("Give up after {retries} failed attempts"),
+                        };
+
+                        column![
+                            text(format!("First wait {}s, doubling", policy.base_secs)).size(12),
+                            base,
+                            text(format!("Up to {}s", policy.max_secs.max(policy.base_secs)))
+                                .size(12),
+                            max,
+                            text(retries_label).size(12),
+                            retries,
+                        ]
+                        .spacing(4)
+                    };
+
+                    let rate_limit_share = {
+                        let share = exchange::fetcher::rate_limit_share();
+
+                        let slider = iced::widget::slider(
+                            f32::from(exchange::fetcher::RateLimitShare::MIN_PERCENT)..=100.0,
+                            f32::from(share.percent),
+                            |value| {
+                                Message::RateLimitShareChanged(exchange::fetcher::RateLimitShare {
+                                    percent: value as u8,
+                                })
+                            },
+                        )
+                        .step(5.0);
+
+                        column![
+                            tooltip(
+                                text(format!("Use up to {}% of each exchange's limit", share.percent))
+                                    .size(12),
+                                Some("Lower it when other apps request from the same IP, fetches queue up sooner"),
+                                TooltipPosition::Top,
+                            ),
+                            slider,
+                        ]
+                        .spacing(4)
+                    };
+
+                    let network_settings = {
+                        let proxy =
+                            iced::widget::text_input("socks5://127.0.0.1:1080", &self.proxy_input)
+                                .on_input(Message::ProxyInputChanged)
+                                .on_submit(Message::ApplyProxy)
+                                .width(iced::Length::Fill);
+
+                        let (market, endpoints) = &self.endpoints_input;
+
+                        let market_picklist = pick_list(
+                            Exchange::ALL,
+                            Some(*market),
+                            Message::EndpointsMarketSelected,
+                        );
+
+                        let rest = iced::widget::text_input("Default REST", &endpoints.rest)
+                            .on_input({
+                                let endpoints = endpoints.clone();
+                                move |rest| {
+                                    Message::EndpointsInputChanged(exchange::adapter::Endpoints {
+                                        rest,
+                                        ..endpoints.clone()
+                                    })
                                 }
-                            });
+                            })
+                            .on_submit(Message::ApplyEndpoints)
+                            .width(iced::Length::Fill);
+
+                        let websocket =
+                            iced::widget::text_input("Default websocket", &endpoints.websocket)
+                                .on_input({
+                                    let endpoints = endpoints.clone();
+                                    move |websocket| {
+                                        Message::EndpointsInputChanged(
+                                            exchange::adapter::Endpoints {
+                                                websocket,
+                                                ..endpoints.clone()
+                                            },
+                                        )
+                                    }
+                                })
+                                .on_submit(Message::ApplyEndpoints)
+                                .width(iced::Length::Fill);
+
+                        column![
+                            tooltip(
+                                text("Proxy").size(12),
+                                Some("HTTP or SOCKS5 proxy for requests and streams, press Enter to apply, empty connects directly"),
+                                TooltipPosition::Top,
+                            ),
+                            proxy,
+                            tooltip(
+                                text("Endpoints").size(12),
+                                Some("Other addresses for a market, e.g. a regional mirror. Press Enter to apply, empty keeps the default, testnet takes precedence"),
+                                TooltipPosition::Top,
+                            ),
+                            market_picklist,
+                            rest,
+                            websocket,
+                        ]
+                        .spacing(4)
+                    };
+
+                    let sidebar_auto_hide = tooltip(
+                        iced::widget::checkbox("Auto-hide", self.sidebar.state.auto_hide)
+                            .on_toggle(|is_enabled| {
+                                Message::Sidebar(dashboard::sidebar::Message::SetAutoHide(
+                                    is_enabled,
+                                ))
+                            }),
+                        Some("Collapse to a thin strip, shown on hover or with Ctrl+B"),
+                        TooltipPosition::Top,
+                    );
+
+                    let pause_popouts_checkbox = {
+                        let checkbox = iced::widget::checkbox(
+                            "Pause minimized popouts",
+                            self.pause_hidden_popouts,
+                        )
+                        .on_toggle(Message::TogglePauseHiddenPopouts);
 
                         tooltip(
                             checkbox,
-                            Some("Try to fetch trades for footprint charts"),
+                            Some("Stop depth streams of popout windows while they're minimized"),
                             TooltipPosition::Top,
                         )
                     };
 
+                    let snapshot_schedule = {
+                        let checkbox = iced::widget::checkbox(
+                            "Scheduled snapshots",
+                            self.snapshot_schedule.is_enabled,
+                        )
+                        .on_toggle(Message::ToggleSnapshotSchedule);
+
+                        let times =
+                            iced::widget::text_input("00:00, 16:00", &self.snapshot_times_input)
+                                .on_input(Message::SnapshotTimesChanged)
+                                .width(iced::Length::Fill);
+
+                        column![
+                            tooltip(
+                                checkbox,
+                                Some("Save a PNG of panes marked in their settings into the journal folder"),
+                                TooltipPosition::Top,
+                            ),
+                            times,
+                        ]
+                        .spacing(8)
+                    };
+
+                    let ticker_hotkeys = {
+                        let hotkeys = self.sidebar.ticker_hotkeys();
+
+                        let mut list = column![].spacing(4);
+
+                        if hotkeys.is_empty() {
+                            list = list.push(
+                                text("Bind favorites from their card in the tickers table")
+                                    .size(12),
+                            );
+                        }
+
+                        for (slot, (exchange, ticker)) in hotkeys {
+                            let (symbol, _) = ticker.display_symbol_and_type();
+
+                            list = list.push(
+                                row![
+                                    text(dashboard::tickers_table::HotkeySlot(*slot).to_string())
+                                        .size(12),
+                                    text(format!("{symbol} ({exchange})")).size(12),
+                                    iced::widget::horizontal_space(),
+                                    button(style::icon_text(style::Icon::Close, 10))
+                                        .on_press(Message::Sidebar(
+                                            dashboard::sidebar::Message::TickersTable(
+                                                dashboard::tickers_table::Message::ClearHotkey(
+                                                    *slot,
+                                                ),
+                                            ),
+                                        ))
+                                        .style(move |theme, status| {
+                                            style::button::transparent(theme, status, false)
+                                        }),
+                                ]
+                                .spacing(8)
+                                .align_y(Alignment::Center),
+                            );
+                        }
+
+                        list
+                    };
+
+                    let renderer_settings = {
+                        let renderer = self.renderer;
+
+                        let gpu_picklist = pick_list(
+                            data::config::GpuPreference::ALL,
+                            Some(renderer.gpu_preference),
+                            move |gpu_preference| {
+                                Message::RendererChanged(data::config::RendererSettings {
+                                    gpu_preference,
+                                    ..renderer
+                                })
+                            },
+                        );
+
+                        let vsync_checkbox = iced::widget::checkbox("VSync", renderer.vsync)
+                            .on_toggle(move |vsync| {
+                                Message::RendererChanged(data::config::RendererSettings {
+                                    vsync,
+                                    ..renderer
+                                })
+                            });
+
+                        let antialiasing_checkbox =
+                            iced::widget::checkbox("Antialiasing", renderer.antialiasing)
+                                .on_toggle(move |antialiasing| {
+                                    Message::RendererChanged(data::config::RendererSettings {
+                                        antialiasing,
+                                        ..renderer
+                                    })
+                                });
+
+                        column![
+                            tooltip(
+                                gpu_picklist,
+                                Some("GPU to render with on machines that have more than one"),
+                                TooltipPosition::Top,
+                            ),
+                            vsync_checkbox,
+                            antialiasing_checkbox,
+                            text("Changes apply on the next launch").size(11),
+                        ]
+                        .spacing(8)
+                    };
+
+                    let imbalance_presets = {
+                        let presets = &self.imbalance_presets;
+                        let names = presets
+                            .presets
+                            .iter()
+                            .map(|preset| preset.name.clone())
+                            .collect::<Vec<_>>();
+
+                        let mut list = column![].spacing(4);
+
+                        for preset in &presets.presets {
+                            list = list.push(
+                                row![
+                                    text(preset.name.clone()).size(12),
+                                    text(format!(
+                                        "color scale {}{}",
+                                        preset
+                                            .color_scale
+                                            .map_or("off".to_string(), |scale| format!("{scale}x")),
+                                        if preset.ignore_zeros {
+                                            ", ignoring zeros"
+                                        } else {
+                                            ""
+                                        }
+                                    ))
+                                    .size(11),
+                                    iced::widget::horizontal_space(),
+                                    button(style::icon_text(style::Icon::Close, 10))
+                                        .on_press(Message::ImbalancePresetRemoved(
+                                            preset.name.clone(),
+                                        ))
+                                        .style(move |theme, status| {
+                                            style::button::transparent(theme, status, false)
+                                        }),
+                                ]
+                                .spacing(8)
+                                .align_y(Alignment::Center),
+                            );
+                        }
+
+                        for market in [
+                            exchange::adapter::MarketKind::Spot,
+                            exchange::adapter::MarketKind::LinearPerps,
+                            exchange::adapter::MarketKind::InversePerps,
+                        ] {
+                            let selected = presets.default_name(market).cloned();
+                            let is_set = selected.is_some();
+
+                            let clear = button(style::icon_text(style::Icon::Close, 10))
+                                .on_press_maybe(
+                                    is_set
+                                        .then_some(Message::ImbalanceDefaultChanged(market, None)),
+                                )
+                                .style(move |theme, status| {
+                                    style::button::transparent(theme, status, false)
+                                });
+
+                            list = list.push(
+                                row![
+                                    text(format!("{market} default")).size(12),
+                                    iced::widget::horizontal_space(),
+                                    pick_list(names.clone(), selected, move |name| {
+                                        Message::ImbalanceDefaultChanged(market, Some(name))
+                                    })
+                                    .placeholder("Built-in")
+                                    .text_size(12),
+                                    clear,
+                                ]
+                                .spacing(8)
+                                .align_y(Alignment::Center),
+                            );
+                        }
+
+                        list.push(
+                            text("Save new presets from a footprint chart's study settings")
+                                .size(11),
+                        )
+                    };
+
+                    let restore_backup: Element<_> = if self.backups.is_empty() {
+                        text("No backups yet, one is taken each time the app exits")
+                            .size(12)
+                            .into()
+                    } else {
+                        pick_list(self.backups.as_slice(), None::<data::backup::Backup>, |backup| {
+                            Message::ToggleDialogModal(Some((
+                                format!(
+                                    "Restore the saved state from {backup}?\n\nThe app will close without saving the current state, reopen it to load the backup."
+                                ),
+                                Box::new(Message::RestoreBackup(backup)),
+                            )))
+                        })
+                        .placeholder("Restore from backup")
+                        .into()
+                    };
+
                     let open_data_folder = {
                         let button =
                             button(text("Open data folder")).on_press(Message::DataFolderRequested);
@@ -668,15 +2807,54 @@ impl Flowsurface {
                         )
                     };
 
+                    let health_check = {
+                        let button = button(text("Health check"))
+                            .on_press(Message::ToggleHealthReport(true));
+
+                        tooltip(
+                            button,
+                            Some("Check exchange connectivity and the data folder"),
+                            TooltipPosition::Top,
+                        )
+                    };
+
                     let column_content = split_column![
-                        column![open_data_folder,].spacing(8),
-                        column![text("Sidebar position").size(14), sidebar_pos,].spacing(12),
+                        column![open_data_folder, health_check,].spacing(8),
+                        column![
+                            text("Sidebar").size(14),
+                            column![sidebar_pos, sidebar_auto_hide].spacing(8),
+                        ]
+                        .spacing(12),
+                        column![text("Status bar").size(14), status_bar_checkbox,].spacing(12),
                         column![text("Time zone").size(14), timezone_picklist,].spacing(12),
                         column![text("Theme").size(14), theme_picklist,].spacing(12),
+                        column![text("Chart themes").size(14), chart_themes,].spacing(12),
                         column![text("Interface scale").size(14), scale_factor,].spacing(12),
+                        column![text("Density").size(14), ui_density,].spacing(12),
+                        column![text("Chart text").size(14), chart_text,].spacing(12),
+                        column![text("Renderer").size(14), renderer_settings,].spacing(12),
+                        column![text("Snapshots").size(14), snapshot_schedule,].spacing(12),
+                        column![text("Chart defaults").size(14), chart_defaults,].spacing(12),
+                        column![text("Recorder").size(14), recorder_settings,].spacing(12),
+                        column![text("Depth fallback").size(14), depth_fallback,].spacing(12),
+                        column![text("Reconnecting").size(14), reconnect_policy,].spacing(12),
+                        column![text("Request rate").size(14), rate_limit_share,].spacing(12),
+                        column![text("Network").size(14), network_settings,].spacing(12),
+                        column![text("Imbalance presets").size(14), imbalance_presets,]
+                            .spacing(12),
+                        column![text("Ticker hotkeys").size(14), ticker_hotkeys,].spacing(12),
+                        column![text("Backups").size(14), restore_backup,].spacing(12),
+                        column![text("Crash reports").size(14), crash_reports_checkbox,]
+                            .spacing(12),
                         column![
                             text("Experimental").size(14),
-                            column![trade_fetch_checkbox, toggle_theme_editor,].spacing(8),
+                            column![
+                                trade_fetch_checkboxes,
+                                testnet_checkboxes,
+                                pause_popouts_checkbox,
+                                toggle_theme_editor,
+                            ]
+                            .spacing(8),
                         ]
                         .spacing(12),
                         ; spacing = 16, align_x = Alignment::Start
@@ -692,7 +2870,7 @@ impl Flowsurface {
                     container(content)
                         .align_x(Alignment::Start)
                         .max_width(240)
-                        .padding(24)
+                        .padding(style::dense(24.0))
                         .style(style::dashboard_modal)
                 };
 
@@ -816,7 +2994,7 @@ impl Flowsurface {
 
                     container(col.align_x(Alignment::Center).spacing(20))
                         .width(260)
-                        .padding(24)
+                        .padding(style::dense(24.0))
                         .style(style::dashboard_modal)
                 };
 
@@ -853,6 +3031,64 @@ impl Flowsurface {
                     align_x,
                 )
             }
+            sidebar::Menu::Journal => {
+                let (align_x, padding) = match sidebar_pos {
+                    sidebar::Position::Left => (Alignment::Start, padding::left(44).top(116)),
+                    sidebar::Position::Right => (Alignment::End, padding::right(44).top(116)),
+                };
+
+                dashboard_modal(
+                    base,
+                    self.journal.view(self.timezone).map(Message::Journal),
+                    Message::Sidebar(dashboard::sidebar::Message::ToggleSidebarMenu(None)),
+                    padding,
+                    Alignment::Start,
+                    align_x,
+                )
+            }
+            sidebar::Menu::Alerts => {
+                let (align_x, padding) = match sidebar_pos {
+                    sidebar::Position::Left => (Alignment::Start, padding::left(44).top(156)),
+                    sidebar::Position::Right => (Alignment::End, padding::right(44).top(156)),
+                };
+
+                let depth_streams_list = dashboard.streams.depth_streams(None);
+
+                dashboard_modal(
+                    base,
+                    self.alerts_manager
+                        .view(
+                            depth_streams_list,
+                            self.audio_stream.sounds(),
+                            self.timezone,
+                        )
+                        .map(Message::Alerts),
+                    Message::Sidebar(dashboard::sidebar::Message::ToggleSidebarMenu(None)),
+                    padding,
+                    Alignment::Start,
+                    align_x,
+                )
+            }
+            sidebar::Menu::Profile => {
+                let (align_x, padding) = match sidebar_pos {
+                    sidebar::Position::Left => (Alignment::Start, padding::left(44).top(196)),
+                    sidebar::Position::Right => (Alignment::End, padding::right(44).top(196)),
+                };
+
+                dashboard_modal(
+                    base,
+                    self.profile_builder
+                        .view(
+                            dashboard.profile_tickers(self.main_window.id),
+                            self.timezone,
+                        )
+                        .map(Message::Profile),
+                    Message::Sidebar(dashboard::sidebar::Message::ToggleSidebarMenu(None)),
+                    padding,
+                    Alignment::Start,
+                    align_x,
+                )
+            }
             sidebar::Menu::ThemeEditor => {
                 let (align_x, padding) = match sidebar_pos {
                     sidebar::Position::Left => (Alignment::Start, padding::left(44).bottom(4)),
@@ -1,6 +1,10 @@
+pub mod alerts;
 pub mod audio;
+pub mod command;
+pub mod journal;
 pub mod layout_manager;
 pub mod pane;
+pub mod profile;
 pub mod theme_editor;
 
 use iced::widget::{center, container, mouse_area, opaque, stack};
@@ -0,0 +1,394 @@
+use crate::chart::AlertLine;
+use crate::style::{self, Icon, icon_text};
+use data::UserTimezone;
+use data::alert::{ActiveHours, Alert, Alerts, Condition};
+use exchange::{SerTicker, Ticker, adapter::Exchange};
+
+use iced::widget::{
+    button, checkbox, column, container, horizontal_space, pick_list, row, scrollable, text,
+    text_input,
+};
+use iced::{Alignment, Element, Length};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionKind {
+    PriceCross,
+    PercentChange,
+    VolumeSpike,
+}
+
+impl ConditionKind {
+    const ALL: [ConditionKind; 3] = [
+        ConditionKind::PriceCross,
+        ConditionKind::PercentChange,
+        ConditionKind::VolumeSpike,
+    ];
+
+    fn value_label(self) -> &'static str {
+        match self {
+            ConditionKind::PriceCross => "Price",
+            ConditionKind::PercentChange => "Change %",
+            ConditionKind::VolumeSpike => "Multiplier",
+        }
+    }
+
+    fn default_minutes(self) -> Option<&'static str> {
+        match self {
+            ConditionKind::PriceCross => None,
+            ConditionKind::PercentChange => Some("5"),
+            ConditionKind::VolumeSpike => Some("30"),
+        }
+    }
+}
+
+impl std::fmt::Display for ConditionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConditionKind::PriceCross => write!(f, "Price cross"),
+            ConditionKind::PercentChange => write!(f, "% change"),
+            ConditionKind::VolumeSpike => write!(f, "Volume spike"),
+        }
+    }
+}
+
+/// How long an alert can be muted for from the list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnoozeOption(u64);
+
+impl SnoozeOption {
+    const ALL: [SnoozeOption; 4] = [
+        SnoozeOption(15),
+        SnoozeOption(60),
+        SnoozeOption(240),
+        SnoozeOption(1440),
+    ];
+}
+
+impl std::fmt::Display for SnoozeOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            mins if mins % 60 == 0 => write!(f, "{}h", mins / 60),
+            mins => write!(f, "{mins}m"),
+        }
+    }
+}
+
+/// A ticker with a live trade stream that alerts can be evaluated against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamOption(Exchange, Ticker);
+
+impl std::fmt::Display for StreamOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (symbol, _) = self.1.display_symbol_and_type();
+        write!(f, "{symbol} ({})", self.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    StreamSelected(StreamOption),
+    KindSelected(ConditionKind),
+    ValueChanged(String),
+    MinutesChanged(String),
+    HoursChanged(String),
+    ToggleSound(bool),
+    SoundSelected(String),
+    Add,
+    Remove(u64),
+    Rearm(u64),
+    Snooze(u64, SnoozeOption),
+    Unsnooze(u64),
+}
+
+pub struct AlertsManager {
+    alerts: Alerts,
+    stream: Option<StreamOption>,
+    kind: ConditionKind,
+    value_input: String,
+    minutes_input: String,
+    hours_input: String,
+    play_sound: bool,
+    sound: Option<String>,
+}
+
+impl AlertsManager {
+    pub fn new(alerts: Alerts) -> Self {
+        Self {
+            alerts,
+            stream: None,
+            kind: ConditionKind::PriceCross,
+            value_input: String::new(),
+            minutes_input: String::new(),
+            hours_input: String::new(),
+            play_sound: true,
+            sound: None,
+        }
+    }
+
+    pub fn alerts(&self) -> &Alerts {
+        &self.alerts
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::StreamSelected(stream) => self.stream = Some(stream),
+            Message::KindSelected(kind) => {
+                self.kind = kind;
+                self.value_input.clear();
+                self.minutes_input = kind.default_minutes().unwrap_or_default().to_string();
+            }
+            Message::ValueChanged(input) => self.value_input = input,
+            Message::MinutesChanged(input) => self.minutes_input = input,
+            Message::HoursChanged(input) => self.hours_input = input,
+            Message::ToggleSound(play_sound) => self.play_sound = play_sound,
+            Message::SoundSelected(sound) => self.sound = Some(sound),
+            Message::Add => {
+                if let (Some(StreamOption(exchange, ticker)), Some(condition), Some(active_hours)) =
+                    (self.stream, self.parsed_condition(), self.parsed_hours())
+                {
+                    self.alerts.add(
+                        SerTicker::from_parts(exchange, ticker),
+                        condition,
+                        self.play_sound,
+                        self.sound.clone(),
+                        active_hours,
+                    );
+                    self.value_input.clear();
+                }
+            }
+            Message::Remove(id) => self.alerts.remove(id),
+            Message::Rearm(id) => self.alerts.rearm(id),
+            Message::Snooze(id, SnoozeOption(mins)) => {
+                let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+                self.alerts.snooze(id, Some(now_ms + mins * 60_000));
+            }
+            Message::Unsnooze(id) => self.alerts.snooze(id, None),
+        }
+    }
+
+    /// Armed alerts of the ticker that fired on the trades, see [`Alerts::evaluate`]
+    pub fn evaluate(
+        &mut self,
+        ticker: SerTicker,
+        trades: &[exchange::Trade],
+        timezone: UserTimezone,
+    ) -> Vec<Alert> {
+        self.alerts.evaluate(ticker, trades, timezone.now())
+    }
+
+    pub fn move_price(&mut self, id: u64, price: f32) {
+        self.alerts.move_price(id, price);
+    }
+
+    /// Levels of the armed price cross alerts, drawn as draggable lines on the charts
+    pub fn price_lines(&self) -> Vec<(SerTicker, AlertLine)> {
+        self.alerts
+            .iter()
+            .filter(|alert| alert.is_armed)
+            .filter_map(|alert| match alert.condition {
+                Condition::PriceCross(price) => Some((
+                    alert.ticker.clone(),
+                    AlertLine {
+                        id: alert.id,
+                        price,
+                    },
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// `Some(None)` when left empty, the alert then fires at any time of day
+    fn parsed_hours(&self) -> Option<Option<ActiveHours>> {
+        let input = self.hours_input.trim();
+
+        if input.is_empty() {
+            Some(None)
+        } else {
+            ActiveHours::parse(input).map(Some)
+        }
+    }
+
+    fn parsed_condition(&self) -> Option<Condition> {
+        let value = self
+            .value_input
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|value| *value > 0.0)?;
+        let minutes = || {
+            self.minutes_input
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|minutes| *minutes > 0)
+        };
+
+        Some(match self.kind {
+            ConditionKind::PriceCross => Condition::PriceCross(value),
+            ConditionKind::PercentChange => Condition::PercentChange {
+                pct: value,
+                window_mins: minutes()?,
+            },
+            ConditionKind::VolumeSpike => Condition::VolumeSpike {
+                multiplier: value,
+                lookback_mins: minutes()?,
+            },
+        })
+    }
+
+    pub fn view(
+        &self,
+        active_streams: Vec<(Exchange, Ticker)>,
+        sounds: Vec<String>,
+        timezone: UserTimezone,
+    ) -> Element<'_, Message> {
+        let streams = active_streams
+            .into_iter()
+            .map(|(exchange, ticker)| StreamOption(exchange, ticker))
+            .collect::<Vec<_>>();
+
+        let form = {
+            let mut inputs = row![
+                text_input(self.kind.value_label(), &self.value_input)
+                    .on_input(Message::ValueChanged)
+                    .width(Length::Fill),
+            ]
+            .spacing(4);
+
+            if self.kind.default_minutes().is_some() {
+                inputs = inputs.push(
+                    text_input("Minutes", &self.minutes_input)
+                        .on_input(Message::MinutesChanged)
+                        .width(80),
+                );
+            }
+
+            let can_add = self.stream.is_some()
+                && self.parsed_condition().is_some()
+                && self.parsed_hours().is_some();
+
+            let mut sound_row = row![
+                checkbox("Play sound", self.play_sound).on_toggle(Message::ToggleSound),
+                horizontal_space(),
+            ]
+            .spacing(4)
+            .align_y(Alignment::Center);
+
+            if self.play_sound {
+                let selected = self
+                    .sound
+                    .clone()
+                    .unwrap_or(data::audio::HARD_BUY_SOUND.to_string());
+
+                sound_row = sound_row
+                    .push(pick_list(sounds, Some(selected), Message::SoundSelected).text_size(12));
+            }
+
+            sound_row =
+                sound_row.push(button(text("Add")).on_press_maybe(can_add.then_some(Message::Add)));
+
+            column![
+                pick_list(streams, self.stream, Message::StreamSelected)
+                    .placeholder("Ticker with a live trade stream")
+                    .width(Length::Fill),
+                pick_list(ConditionKind::ALL, Some(self.kind), Message::KindSelected)
+                    .width(Length::Fill),
+                inputs,
+                text_input(
+                    "Active hours, e.g. 08:00-16:30 (any time if empty)",
+                    &self.hours_input
+                )
+                .on_input(Message::HoursChanged)
+                .width(Length::Fill),
+                sound_row,
+            ]
+            .spacing(8)
+        };
+
+        let mut list = column![].spacing(4);
+
+        if self.alerts.iter().next().is_none() {
+            list = list.push(text("No alerts yet").size(12));
+        }
+
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+
+        for alert in self.alerts.iter() {
+            let (symbol, _) = alert.ticker.ticker.display_symbol_and_type();
+            let id = alert.id;
+
+            let snooze: Element<_> = match alert.snoozed_until {
+                Some(until) if alert.is_snoozed(now_ms) => button(
+                    text(format!(
+                        "Snoozed to {}",
+                        timezone.format_crosshair_timestamp(until as i64, 60_000)
+                    ))
+                    .size(11),
+                )
+                .on_press(Message::Unsnooze(id))
+                .style(|theme, status| style::button::transparent(theme, status, false))
+                .into(),
+                _ => pick_list(SnoozeOption::ALL, None::<SnoozeOption>, move |option| {
+                    Message::Snooze(id, option)
+                })
+                .placeholder("Snooze")
+                .text_size(11)
+                .into(),
+            };
+
+            let mut details = column![
+                text(format!("{symbol} ({})", alert.ticker.exchange)).size(12),
+                text(alert.condition.to_string()).size(11),
+            ]
+            .spacing(2);
+
+            if let Some(hours) = alert.active_hours {
+                details = details.push(text(format!("Active {hours}")).size(11));
+            }
+
+            let rearm: Element<_> = if alert.is_armed {
+                text("Armed").size(11).into()
+            } else {
+                button(text("Re-arm").size(11))
+                    .on_press(Message::Rearm(id))
+                    .style(|theme, status| style::button::transparent(theme, status, false))
+                    .into()
+            };
+
+            list = list.push(
+                container(
+                    row![
+                        details,
+                        horizontal_space(),
+                        snooze,
+                        rearm,
+                        button(icon_text(Icon::TrashBin, 12))
+                            .on_press(Message::Remove(id))
+                            .style(|theme, status| {
+                                style::button::transparent(theme, status, false)
+                            }),
+                    ]
+                    .spacing(4)
+                    .align_y(Alignment::Center),
+                )
+                .padding(4)
+                .style(style::modal_container),
+            );
+        }
+
+        container(
+            column![
+                text("Alerts").size(14),
+                form,
+                scrollable(list).height(Length::Shrink),
+            ]
+            .spacing(12),
+        )
+        .max_width(320)
+        .max_height(560)
+        .padding(style::dense(24.0))
+        .style(style::dashboard_modal)
+        .into()
+    }
+}
@@ -1,29 +1,36 @@
 use crate::TooltipPosition;
 use crate::style::{self, icon_text};
 use crate::widget::{labeled_slider, tooltip};
-use data::audio::{SoundCache, StreamCfg};
-use exchange::adapter::{Exchange, StreamKind};
+use data::audio::{SizeBucket, SoundCache, StreamCfg};
+use exchange::adapter::{Exchange, MarketKind, StreamKind};
 
 use exchange::Trade;
 use iced::widget::{button, column, container, row, text};
-use iced::widget::{checkbox, horizontal_space, slider};
+use iced::widget::{checkbox, horizontal_space, pick_list, slider};
 use iced::{Element, padding};
 use std::collections::HashMap;
 
 const HARD_THRESHOLD: usize = 4;
+const MAX_BUCKET_NOTIONAL: f32 = 10_000_000.0;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Message {
     SoundLevelChanged(f32),
     ToggleStream(bool, (Exchange, exchange::Ticker)),
     ToggleCard(Exchange, exchange::Ticker),
     SetThreshold(Exchange, exchange::Ticker, data::audio::Threshold),
+    AddSizeBucket(Exchange, exchange::Ticker),
+    RemoveSizeBucket(Exchange, exchange::Ticker, usize),
+    SizeBucketChanged(Exchange, exchange::Ticker, usize, SizeBucket),
+    ReloadSounds,
 }
 
 pub struct AudioStream {
     cache: SoundCache,
     streams: HashMap<Exchange, HashMap<exchange::Ticker, StreamCfg>>,
     expanded_card: Option<(Exchange, exchange::Ticker)>,
+    /// Why files of the sounds folder were skipped on the last load
+    sound_errors: Vec<String>,
 }
 
 impl AudioStream {
@@ -40,11 +47,19 @@ impl AudioStream {
                 .insert(ticker, stream_cfg);
         }
 
+        let mut cache =
+            SoundCache::with_default_sounds(cfg.volume).expect("Failed to create sound cache");
+
+        let sound_errors = cache.load_user_sounds();
+        for err in &sound_errors {
+            log::warn!("Skipped user sound: {err}");
+        }
+
         AudioStream {
-            cache: SoundCache::with_default_sounds(cfg.volume)
-                .expect("Failed to create sound cache"),
+            cache,
             streams,
             expanded_card: None,
+            sound_errors,
         }
     }
 
@@ -85,15 +100,66 @@ impl AudioStream {
                 };
             }
             Message::SetThreshold(exchange, ticker, threshold) => {
-                if let Some(streams) = self.streams.get_mut(&exchange) {
-                    if let Some(cfg) = streams.get_mut(&ticker) {
-                        cfg.threshold = threshold;
+                if let Some(cfg) = self.stream_cfg_mut(exchange, ticker) {
+                    cfg.threshold = threshold;
+                }
+            }
+            Message::AddSizeBucket(exchange, ticker) => {
+                if let Some(cfg) = self.stream_cfg_mut(exchange, ticker) {
+                    // each new bucket starts above the largest one so they don't overlap
+                    let min_notional = cfg
+                        .size_buckets
+                        .iter()
+                        .map(|bucket| bucket.min_notional * 2.0)
+                        .fold(SizeBucket::default().min_notional, f32::max)
+                        .min(MAX_BUCKET_NOTIONAL);
+
+                    cfg.size_buckets.push(SizeBucket {
+                        min_notional,
+                        ..SizeBucket::default()
+                    });
+                }
+            }
+            Message::RemoveSizeBucket(exchange, ticker, index) => {
+                if let Some(cfg) = self.stream_cfg_mut(exchange, ticker) {
+                    if index < cfg.size_buckets.len() {
+                        cfg.size_buckets.remove(index);
                     }
                 }
             }
+            Message::SizeBucketChanged(exchange, ticker, index, bucket) => {
+                let sound_changed = self
+                    .stream_cfg_mut(exchange, ticker)
+                    .and_then(|cfg| cfg.size_buckets.get_mut(index))
+                    .map(|current| {
+                        let sound_changed = current.sound != bucket.sound;
+                        *current = bucket.clone();
+                        sound_changed
+                    });
+
+                // a newly picked sample is previewed at the bucket's volume
+                if sound_changed == Some(true) {
+                    if let Err(err) = self.cache.play_scaled(&bucket.sound, bucket.volume) {
+                        log::error!("Failed to preview sound: {err}");
+                    }
+                }
+            }
+            Message::ReloadSounds => {
+                self.sound_errors = self.cache.load_user_sounds();
+            }
         }
     }
 
+    fn stream_cfg_mut(
+        &mut self,
+        exchange: Exchange,
+        ticker: exchange::Ticker,
+    ) -> Option<&mut StreamCfg> {
+        self.streams
+            .get_mut(&exchange)
+            .and_then(|streams| streams.get_mut(&ticker))
+    }
+
     pub fn view(&self, active_streams: Vec<(Exchange, exchange::Ticker)>) -> Element<'_, Message> {
         let volume_container = {
             let volume_slider = {
@@ -112,6 +178,36 @@ impl AudioStream {
             column![text("Sound").size(14), volume_slider,].spacing(8)
         };
 
+        let user_sounds = {
+            let loaded = match self.cache.user_sounds() {
+                [] => format!(
+                    "Put WAV or OGG files in the \"{}\" data folder to use them",
+                    data::audio::SOUNDS_FOLDER
+                ),
+                sounds => format!("Loaded {}", sounds.join(", ")),
+            };
+
+            let mut content = column![
+                row![
+                    text("Custom sounds").size(14),
+                    horizontal_space(),
+                    button(text("Reload").size(12)).on_press(Message::ReloadSounds),
+                ]
+                .align_y(iced::Alignment::Center),
+                text(loaded).size(12),
+            ]
+            .spacing(8);
+
+            for err in &self.sound_errors {
+                content =
+                    content.push(text(err).size(11).style(|theme: &iced::Theme| text::Style {
+                        color: Some(theme.extended_palette().danger.base.color),
+                    }));
+            }
+
+            content
+        };
+
         let audio_contents = {
             let mut available_streams = column![].spacing(4);
 
@@ -184,6 +280,13 @@ impl AudioStream {
                                     );
                                 }
                             }
+
+                            column = column.push(size_buckets_view(
+                                exchange,
+                                ticker,
+                                cfg,
+                                self.cache.sounds(),
+                            ));
                         }
                     }
 
@@ -195,9 +298,9 @@ impl AudioStream {
             column![text("Audio streams").size(14), available_streams,].spacing(8)
         };
 
-        container(column![volume_container, audio_contents,].spacing(20))
+        container(column![volume_container, user_sounds, audio_contents,].spacing(20))
             .max_width(320)
-            .padding(24)
+            .padding(style::dense(24.0))
             .style(style::dashboard_modal)
             .into()
     }
@@ -210,6 +313,11 @@ impl AudioStream {
         self.cache.play(sound)
     }
 
+    /// Built-in sounds and the ones loaded from the sounds folder
+    pub fn sounds(&self) -> Vec<String> {
+        self.cache.sounds()
+    }
+
     pub fn is_stream_audio_enabled(&self, stream: &StreamKind) -> bool {
         match stream {
             StreamKind::DepthAndTrades { exchange, ticker } => self
@@ -221,7 +329,7 @@ impl AudioStream {
         }
     }
 
-    pub fn should_play_sound(&self, stream: &StreamKind) -> Option<StreamCfg> {
+    pub fn should_play_sound(&self, stream: &StreamKind) -> Option<&StreamCfg> {
         if self.cache.is_muted() {
             return None;
         }
@@ -235,7 +343,7 @@ impl AudioStream {
             .get(exchange)
             .and_then(|streams| streams.get(ticker))
         {
-            Some(cfg) if cfg.enabled => Some(*cfg),
+            Some(cfg) if cfg.enabled => Some(cfg),
             _ => None,
         }
     }
@@ -249,6 +357,20 @@ impl AudioStream {
             return Ok(());
         };
 
+        // a print reaching a size bucket is called out with the bucket's own sound instead
+        if !cfg.size_buckets.is_empty() {
+            let market_type = stream.exchange_and_ticker().1.market_type();
+
+            let bucket = trades_buffer
+                .iter()
+                .filter_map(|trade| cfg.size_bucket(trade_notional(trade, market_type)))
+                .max_by(|a, b| a.min_notional.total_cmp(&b.min_notional));
+
+            if let Some(bucket) = bucket {
+                return self.cache.play_scaled(&bucket.sound, bucket.volume);
+            }
+        }
+
         match cfg.threshold {
             data::audio::Threshold::Count(v) => {
                 let (buy_count, sell_count) =
@@ -300,6 +422,96 @@ impl AudioStream {
     }
 }
 
+fn trade_notional(trade: &Trade, market_type: MarketKind) -> f32 {
+    match market_type {
+        MarketKind::InversePerps => trade.qty,
+        MarketKind::Spot | MarketKind::LinearPerps => trade.qty * trade.price,
+    }
+}
+
+/// Rows of the stream's trade size buckets, each with its own sound and volume
+fn size_buckets_view<'a>(
+    exchange: Exchange,
+    ticker: exchange::Ticker,
+    cfg: &'a StreamCfg,
+    sounds: Vec<String>,
+) -> Element<'a, Message> {
+    let mut buckets = column![text("Sounds by trade size")].spacing(8);
+
+    for (index, bucket) in cfg.size_buckets.iter().enumerate() {
+        let changed =
+            move |bucket: SizeBucket| Message::SizeBucketChanged(exchange, ticker, index, bucket);
+
+        let notional_slider = {
+            let bucket = bucket.clone();
+            labeled_slider(
+                "Size",
+                10_000.0..=MAX_BUCKET_NOTIONAL,
+                bucket.min_notional,
+                move |min_notional| {
+                    changed(SizeBucket {
+                        min_notional,
+                        ..bucket.clone()
+                    })
+                },
+                |value| format!("≥ ${}", data::util::abbr_large_numbers(*value)),
+                Some(10_000.0),
+            )
+        };
+
+        let volume_slider = {
+            let bucket = bucket.clone();
+            labeled_slider(
+                "Volume",
+                0.0..=100.0,
+                bucket.volume,
+                move |volume| {
+                    changed(SizeBucket {
+                        volume,
+                        ..bucket.clone()
+                    })
+                },
+                |value| format!("{value}%"),
+                Some(1.0),
+            )
+        };
+
+        let sound_picker = {
+            let bucket = bucket.clone();
+            pick_list(sounds.clone(), Some(bucket.sound.clone()), move |sound| {
+                changed(SizeBucket {
+                    sound,
+                    ..bucket.clone()
+                })
+            })
+            .text_size(12)
+        };
+
+        let remove = button(icon_text(style::Icon::Close, 10))
+            .on_press(Message::RemoveSizeBucket(exchange, ticker, index))
+            .style(|theme, status| style::button::transparent(theme, status, false));
+
+        buckets = buckets.push(
+            container(
+                column![
+                    row![sound_picker, horizontal_space(), remove].align_y(iced::Alignment::Center),
+                    notional_slider,
+                    volume_slider,
+                ]
+                .spacing(4),
+            )
+            .padding(4)
+            .style(style::modal_container),
+        );
+    }
+
+    buckets = buckets.push(
+        button(text("Add size bucket").size(12)).on_press(Message::AddSizeBucket(exchange, ticker)),
+    );
+
+    buckets.padding(8).into()
+}
+
 impl From<&AudioStream> for data::AudioStream {
     fn from(audio_stream: &AudioStream) -> Self {
         let mut streams = HashMap::new();
@@ -307,7 +519,7 @@ impl From<&AudioStream> for data::AudioStream {
         for (&exchange, ticker_map) in &audio_stream.streams {
             for (&ticker, cfg) in ticker_map {
                 let exchange_ticker = exchange::SerTicker::from_parts(exchange, ticker);
-                streams.insert(exchange_ticker, *cfg);
+                streams.insert(exchange_ticker, cfg.clone());
             }
         }
 
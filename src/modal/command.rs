@@ -0,0 +1,273 @@
+use crate::layout::Layout;
+use crate::style;
+use data::chart::indicator::KlineIndicator;
+use data::config::sidebar::Menu;
+use exchange::{Ticker, TickerInfo, adapter::Exchange};
+
+use iced::widget::{button, column, container, text, text_input};
+use iced::{Element, Length, Task, widget::pane_grid, window};
+
+/// Matches listed at once, the rest only show up as the query narrows down
+const MAX_RESULTS: usize = 8;
+
+const PANE_KINDS: [(&str, &str); 8] = [
+    ("heatmap", "heatmap"),
+    ("footprint", "footprint"),
+    ("candlestick", "candlestick"),
+    ("delta", "delta"),
+    ("tpo", "TPO"),
+    ("time&sales", "time & sales"),
+    ("ladder", "ladder"),
+    ("watchlist", "watchlist"),
+];
+
+const MENUS: [(Menu, &str); 7] = [
+    (Menu::Layout, "layouts"),
+    (Menu::Settings, "settings"),
+    (Menu::Audio, "audio"),
+    (Menu::ThemeEditor, "theme editor"),
+    (Menu::Journal, "journal"),
+    (Menu::Alerts, "alerts"),
+    (Menu::Profile, "composite profile"),
+];
+
+fn input_id() -> text_input::Id {
+    text_input::Id::new("command-palette")
+}
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Loads the ticker into the focused pane as the given content
+    OpenPane {
+        content: &'static str,
+        exchange: Exchange,
+        ticker: Ticker,
+    },
+    SwitchLayout(Layout),
+    ToggleIndicator {
+        window: window::Id,
+        pane: pane_grid::Pane,
+        indicator: String,
+    },
+    ShowMenu(Menu),
+    /// Turns the indicator on or off on every kline chart at once
+    SetIndicatorOnAll {
+        indicator: KlineIndicator,
+        is_enabled: bool,
+    },
+}
+
+/// What commands can currently be built from
+pub struct Context {
+    pub tickers: Vec<TickerInfo>,
+    pub layouts: Vec<Layout>,
+    /// Indicators the focused pane can toggle
+    pub indicators: Option<(window::Id, pane_grid::Pane, Vec<String>)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    QueryChanged(String),
+    MoveSelection(isize),
+    Submit,
+    Run(usize),
+}
+
+pub enum Action {
+    Run(Command),
+}
+
+#[derive(Default)]
+pub struct CommandPalette {
+    query: String,
+    selected: usize,
+    results: Vec<(String, Command)>,
+}
+
+impl CommandPalette {
+    pub fn new(context: &Context) -> (Self, Task<Message>) {
+        let mut palette = CommandPalette::default();
+        palette.results = matches("", context);
+
+        (palette, text_input::focus(input_id()))
+    }
+
+    pub fn update(&mut self, message: Message, context: &Context) -> Option<Action> {
+        match message {
+            Message::QueryChanged(query) => {
+                self.results = matches(&query, context);
+                self.query = query;
+                self.selected = 0;
+            }
+            Message::MoveSelection(step) => {
+                if !self.results.is_empty() {
+                    let len = self.results.len() as isize;
+                    self.selected = (self.selected as isize + step).rem_euclid(len) as usize;
+                }
+            }
+            Message::Submit => return self.update(Message::Run(self.selected), context),
+            Message::Run(index) => {
+                return self
+                    .results
+                    .get(index)
+                    .map(|(_, command)| Action::Run(command.clone()));
+            }
+        }
+
+        None
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let input = text_input(
+            "Open heatmap btcusdt bybit, switch layout, toggle volume...",
+            &self.query,
+        )
+        .id(input_id())
+        .on_input(Message::QueryChanged)
+        .on_submit(Message::Submit)
+        .size(14)
+        .padding(8);
+
+        let mut list = column![].spacing(2);
+        for (index, (label, _)) in self.results.iter().enumerate() {
+            let is_selected = index == self.selected;
+
+            list = list.push(
+                button(text(label).size(13))
+                    .on_press(Message::Run(index))
+                    .width(Length::Fill)
+                    .style(move |theme, status| {
+                        style::button::modifier(theme, status, is_selected)
+                    }),
+            );
+        }
+
+        if self.results.is_empty() {
+            list = list.push(text("No matching commands").size(12));
+        }
+
+        container(column![input, list].spacing(8))
+            .width(480)
+            .padding(style::dense(16.0))
+            .style(style::dashboard_modal)
+            .into()
+    }
+}
+
+/// Best matching commands for `query`, every word of it has to be found in a command's label
+fn matches(query: &str, context: &Context) -> Vec<(String, Command)> {
+    let query = query.to_lowercase();
+    let words = query.split_whitespace().collect::<Vec<_>>();
+
+    let mut candidates: Vec<(String, Command)> = vec![];
+
+    // every ticker times every pane kind is too much to score, only tickers named in the query
+    let named_tickers = context.tickers.iter().filter(|info| {
+        let symbol = info.ticker.to_full_symbol_and_type().0.to_lowercase();
+        words
+            .iter()
+            .any(|word| word.len() >= 2 && symbol.contains(word))
+    });
+    for info in named_tickers {
+        let (symbol, _) = info.ticker.display_symbol_and_type();
+        let exchange = info.exchange();
+
+        for (content, kind) in PANE_KINDS {
+            candidates.push((
+                format!("Open {kind} {symbol} {exchange}"),
+                Command::OpenPane {
+                    content,
+                    exchange,
+                    ticker: info.ticker,
+                },
+            ));
+        }
+    }
+
+    for layout in &context.layouts {
+        candidates.push((
+            format!("Switch layout {}", layout.name),
+            Command::SwitchLayout(layout.clone()),
+        ));
+    }
+
+    if let Some((window, pane, indicators)) = &context.indicators {
+        for indicator in indicators {
+            candidates.push((
+                format!("Toggle {indicator} indicator"),
+                Command::ToggleIndicator {
+                    window: *window,
+                    pane: *pane,
+                    indicator: indicator.clone(),
+                },
+            ));
+        }
+    }
+
+    for indicator in KlineIndicator::ALL {
+        for (is_enabled, verb) in [(true, "Enable"), (false, "Disable")] {
+            candidates.push((
+                format!("{verb} {indicator} on all charts"),
+                Command::SetIndicatorOnAll {
+                    indicator,
+                    is_enabled,
+                },
+            ));
+        }
+    }
+
+    for (menu, name) in MENUS {
+        candidates.push((format!("Show {name}"), Command::ShowMenu(menu)));
+    }
+
+    let mut scored = candidates
+        .into_iter()
+        .filter_map(|(label, command)| {
+            fuzzy_score(&label.to_lowercase(), &words).map(|score| (score, label, command))
+        })
+        .collect::<Vec<_>>();
+
+    scored.sort_by(|(a_score, a_label, _), (b_score, b_label, _)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| a_label.len().cmp(&b_label.len()))
+    });
+
+    scored
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, label, command)| (label, command))
+        .collect()
+}
+
+/// Each word matches when its characters appear in the label in order, runs of consecutive
+/// characters and matches at the start of a label's word score higher
+fn fuzzy_score(label: &str, words: &[&str]) -> Option<u32> {
+    let label = label.chars().collect::<Vec<_>>();
+    let mut total = 0;
+
+    for word in words {
+        let mut score = 0;
+        let mut from = 0;
+        let mut prev_match: Option<usize> = None;
+
+        for ch in word.chars() {
+            let idx = from + label[from..].iter().position(|c| *c == ch)?;
+
+            score += 1;
+            if prev_match.is_some_and(|prev| prev + 1 == idx) {
+                score += 3;
+            }
+            if idx == 0 || label[idx - 1] == ' ' {
+                score += 5;
+            }
+
+            prev_match = Some(idx);
+            from = idx + 1;
+        }
+
+        total += score;
+    }
+
+    Some(total)
+}
@@ -0,0 +1,114 @@
+use crate::style::{self, Icon, icon_text};
+use data::journal::Tag;
+
+use iced::widget::{button, column, container, horizontal_space, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+    JumpTo(usize),
+    Remove(usize),
+}
+
+pub enum Action {
+    JumpTo(Tag),
+}
+
+pub struct Journal {
+    inner: data::journal::Journal,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self {
+            inner: data::journal::Journal::load(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<Action> {
+        match message {
+            Message::JumpTo(index) => self.inner.tags.get(index).cloned().map(Action::JumpTo),
+            Message::Remove(index) => {
+                if self.inner.remove(index).is_some() {
+                    self.save();
+                }
+                None
+            }
+        }
+    }
+
+    pub fn add_tag(&mut self, tag: Tag) {
+        self.inner.add(tag);
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Err(err) = self.inner.save() {
+            log::error!("Failed to save journal: {err}");
+        }
+    }
+
+    pub fn view(&self, timezone: data::UserTimezone) -> Element<'_, Message> {
+        let mut days = column![].spacing(12);
+
+        if self.inner.tags.is_empty() {
+            days = days.push(text("Tag a chart's visible range from its pane controls"));
+        }
+
+        for (day, tags) in self.inner.by_day() {
+            let mut entries = column![].spacing(2);
+
+            for (index, tag) in tags {
+                let symbol = tag.ticker.display_symbol_and_type().0;
+
+                let entry = row![
+                    button(
+                        column![
+                            text(format!("{symbol} · {}", tag.label)).size(13),
+                            text(
+                                timezone
+                                    .format_crosshair_timestamp(tag.start_time as i64, u64::MAX)
+                            )
+                            .size(11),
+                        ]
+                        .spacing(2),
+                    )
+                    .width(Length::Fill)
+                    .on_press(Message::JumpTo(index))
+                    .style(|theme, status| style::button::menu_body(theme, status, false)),
+                    button(icon_text(Icon::TrashBin, 12))
+                        .on_press(Message::Remove(index))
+                        .style(|theme, status| style::button::transparent(theme, status, false)),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(4);
+
+                entries = entries.push(entry);
+            }
+
+            days = days.push(
+                column![
+                    row![
+                        text(day.format("%Y-%m-%d").to_string()).size(14),
+                        horizontal_space()
+                    ],
+                    container(entries).style(style::modal_container).padding(4),
+                ]
+                .spacing(4),
+            );
+        }
+
+        container(
+            column![
+                text("Journal").size(14),
+                scrollable(days).height(Length::Shrink),
+            ]
+            .spacing(12),
+        )
+        .max_width(320)
+        .max_height(560)
+        .padding(style::dense(24.0))
+        .style(style::dashboard_modal)
+        .into()
+    }
+}
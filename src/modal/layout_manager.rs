@@ -3,6 +3,7 @@ use crate::screen::dashboard::{Dashboard, pane};
 use crate::style::{Icon, icon_text};
 use crate::widget::column_drag::{self, DragEvent};
 use crate::widget::dragger_row;
+use crate::window::{self, Window};
 use crate::{style, tooltip};
 use data::layout::WindowSpec;
 
@@ -32,15 +33,19 @@ pub enum Message {
     ToggleEditMode(Editing),
     CloneLayout(Uuid),
     Reorder(DragEvent),
+    Detach(Layout),
 }
 
 pub enum Action {
     Select(Layout),
+    Detach(Layout),
 }
 
 pub struct LayoutManager {
     layouts: HashMap<Uuid, (Layout, Dashboard)>,
     active_layout: Layout,
+    /// Layouts streaming in a window of their own next to the active one
+    detached: HashMap<Uuid, Window>,
     pub layout_order: Vec<Uuid>,
     pub edit_mode: Editing,
 }
@@ -59,6 +64,7 @@ impl LayoutManager {
         LayoutManager {
             layouts,
             active_layout: layout1.clone(),
+            detached: HashMap::new(),
             layout_order: vec![layout1.id],
             edit_mode: Editing::None,
         }
@@ -72,6 +78,7 @@ impl LayoutManager {
         LayoutManager {
             layouts,
             active_layout,
+            detached: HashMap::new(),
             layout_order,
             edit_mode: Editing::None,
         }
@@ -142,6 +149,31 @@ impl LayoutManager {
         }
     }
 
+    pub fn detach(&mut self, layout_id: Uuid, window: Window) {
+        self.detached.insert(layout_id, window);
+    }
+
+    pub fn remove_detached(&mut self, layout_id: &Uuid) -> Option<Window> {
+        self.detached.remove(layout_id)
+    }
+
+    pub fn detached_window(&self, layout_id: &Uuid) -> Option<Window> {
+        self.detached.get(layout_id).copied()
+    }
+
+    /// The layout hosted by the given window, when it's a detached layout window
+    pub fn detached_layout(&self, window: window::Id) -> Option<Uuid> {
+        self.detached
+            .iter()
+            .find_map(|(layout_id, host)| (host.id == window).then_some(*layout_id))
+    }
+
+    pub fn iter_detached(&self) -> impl Iterator<Item = (Uuid, Window)> + '_ {
+        self.detached
+            .iter()
+            .map(|(layout_id, window)| (*layout_id, *window))
+    }
+
     pub fn update(&mut self, message: Message) -> Option<Action> {
         match message {
             Message::SelectActive(layout) => {
@@ -235,6 +267,11 @@ impl LayoutManager {
                 }
             }
             Message::Reorder(event) => column_drag::reorder_vec(&mut self.layout_order, &event),
+            Message::Detach(layout) => {
+                if layout.id != self.active_layout.id && !self.detached.contains_key(&layout.id) {
+                    return Some(Action::Detach(layout));
+                }
+            }
         }
 
         None
@@ -273,9 +310,10 @@ impl LayoutManager {
 
         for id_loop in &self.layout_order {
             if let Some((layout, _)) = self.layouts.get(id_loop) {
-                let mut layout_row = row![].height(iced::Length::Fixed(32.0)).padding(4);
+                let mut layout_row = row![].height(style::dense(32.0)).padding(4);
 
                 let is_active = self.active_layout.id == layout.id;
+                let is_detached = self.detached.contains_key(&layout.id);
 
                 match &self.edit_mode {
                     Editing::ConfirmingDelete(delete_id) => {
@@ -311,7 +349,7 @@ impl LayoutManager {
                             .push(create_clone_button(layout.id))
                             .push(create_rename_button(layout));
 
-                        if !is_active {
+                        if !is_active && !is_detached {
                             layout_row = layout_row.push(self.create_delete_button(layout.id));
                         }
                     }
@@ -324,6 +362,10 @@ impl LayoutManager {
                                 Some(Message::SelectActive(layout.clone()))
                             },
                         ));
+
+                        if !is_active {
+                            layout_row = layout_row.push(create_detach_button(layout, is_detached));
+                        }
                     }
                 }
 
@@ -441,6 +483,34 @@ fn create_clone_button<'a>(layout_id: Uuid) -> Element<'a, Message> {
     )
 }
 
+fn create_detach_button<'a>(layout: &Layout, is_detached: bool) -> Element<'a, Message> {
+    let (on_press, tooltip_text) = if is_detached {
+        (None, "Open in its own window")
+    } else {
+        (
+            Some(Message::Detach(layout.clone())),
+            "Open in a new window",
+        )
+    };
+
+    tooltip(
+        create_icon_button(
+            style::Icon::Popout,
+            12,
+            move |theme, status| {
+                if is_detached {
+                    style::button::transparent(theme, *status, true)
+                } else {
+                    style::button::layout_name(theme, *status)
+                }
+            },
+            on_press,
+        ),
+        Some(tooltip_text),
+        TooltipPosition::Top,
+    )
+}
+
 fn create_confirm_delete_buttons<'a>(
     layout: &Layout,
 ) -> (button::Button<'a, Message>, button::Button<'a, Message>) {
@@ -87,7 +87,7 @@ pub fn view<'a, I: Indicator>(
 
     container(content_row)
         .max_width(200)
-        .padding(16)
+        .padding(style::dense(16.0))
         .style(style::chart_modal)
         .into()
 }
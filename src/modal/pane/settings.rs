@@ -1,17 +1,21 @@
 use crate::screen::dashboard::pane::Message;
-use crate::screen::dashboard::panel::timeandsales;
+use crate::screen::dashboard::panel::{ladder, timeandsales};
 use crate::split_column;
 use crate::widget::{classic_slider_row, labeled_slider};
 use crate::{style, tooltip, widget::scrollable_content};
 use data::chart::heatmap::HeatmapStudy;
-use data::chart::kline::FootprintStudy;
+use data::chart::kline::{
+    FootprintStudy, ImbalancePresets, MaKind, MovingAverage, OverlayColor, SpreadMode,
+};
 use data::chart::{
     KlineChartKind, VisualConfig,
-    heatmap::{self, CoalesceKind},
+    anomaly::Detectors,
+    heatmap::{self, CoalesceKind, DepthPalette, IntensityScale},
     kline::ClusterKind,
-    timeandsales::StackedBarRatio,
+    timeandsales::{SideFilter, StackedBarRatio},
 };
 use data::util::format_with_commas;
+use exchange::TickMultiplier;
 use iced::{
     Alignment, Element, Length,
     widget::{
@@ -20,13 +24,16 @@ use iced::{
     },
 };
 
+/// Order size in USD getting the full color when clamping is turned on
+const DEFAULT_DEPTH_CLAMP: f32 = 500_000.0;
+
 fn cfg_view_container<'a, T>(max_width: u32, content: T) -> Element<'a, Message>
 where
     T: Into<Element<'a, Message>>,
 {
     container(scrollable_content(content))
         .width(Length::Shrink)
-        .padding(28)
+        .padding(style::dense(28.0))
         .max_width(max_width)
         .style(style::chart_modal)
         .into()
@@ -201,6 +208,141 @@ pub fn heatmap_cfg_view<'a>(
     ]
     .spacing(8);
 
+    let depth_column = {
+        let heatmap_cfg = move |cfg: heatmap::Config| {
+            Message::VisualConfigChanged(pane, VisualConfig::Heatmap(cfg), false)
+        };
+
+        let palette_row = row![
+            text("Palette"),
+            horizontal_space(),
+            pick_list(DepthPalette::ALL, Some(cfg.palette), move |palette| {
+                heatmap_cfg(heatmap::Config { palette, ..cfg })
+            }),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let scale_row = row![
+            text("Intensity"),
+            horizontal_space(),
+            pick_list(
+                IntensityScale::ALL,
+                Some(cfg.intensity_scale),
+                move |intensity_scale| heatmap_cfg(heatmap::Config {
+                    intensity_scale,
+                    ..cfg
+                })
+            ),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let mut depth_column = column![
+            text("Depth").size(14),
+            iced::widget::checkbox("Color by size added/pulled", cfg.depth_delta)
+                .on_toggle(move |depth_delta| heatmap_cfg(heatmap::Config { depth_delta, ..cfg })),
+            palette_row,
+        ]
+        .spacing(8);
+
+        if let DepthPalette::TwoColor(low, high) = cfg.palette {
+            depth_column = depth_column.push(
+                row![
+                    text("Colors"),
+                    horizontal_space(),
+                    pick_list(OverlayColor::ALL, Some(low), move |low| {
+                        heatmap_cfg(heatmap::Config {
+                            palette: DepthPalette::TwoColor(low, high),
+                            ..cfg
+                        })
+                    }),
+                    pick_list(OverlayColor::ALL, Some(high), move |high| {
+                        heatmap_cfg(heatmap::Config {
+                            palette: DepthPalette::TwoColor(low, high),
+                            ..cfg
+                        })
+                    }),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        depth_column = depth_column.push(scale_row).push(
+            iced::widget::checkbox("Clamp full color at a size", cfg.depth_clamp.is_some())
+                .on_toggle(move |is_checked| {
+                    heatmap_cfg(heatmap::Config {
+                        depth_clamp: is_checked.then_some(DEFAULT_DEPTH_CLAMP),
+                        ..cfg
+                    })
+                }),
+        );
+
+        if let Some(clamp) = cfg.depth_clamp {
+            depth_column = depth_column.push(labeled_slider(
+                "Clamp",
+                10_000.0..=5_000_000.0,
+                clamp,
+                move |value| {
+                    heatmap_cfg(heatmap::Config {
+                        depth_clamp: Some(value),
+                        ..cfg
+                    })
+                },
+                |value| format!("${}", format_with_commas(*value)),
+                Some(10_000.0),
+            ));
+        }
+
+        depth_column = depth_column.push(tooltip(
+            iced::widget::checkbox("Micro price line", cfg.micro_price.is_some()).on_toggle(
+                move |is_checked| {
+                    heatmap_cfg(heatmap::Config {
+                        micro_price: is_checked.then_some(1),
+                        ..cfg
+                    })
+                },
+            ),
+            Some("Mid price weighted by the best bid and ask sizes"),
+            TooltipPosition::Top,
+        ));
+
+        if let Some(smoothing) = cfg.micro_price {
+            depth_column = depth_column.push(classic_slider_row(
+                text("Smoothing"),
+                slider(1..=50, smoothing, move |value| {
+                    heatmap_cfg(heatmap::Config {
+                        micro_price: Some(value),
+                        ..cfg
+                    })
+                })
+                .into(),
+                Some(text(format!("{smoothing} cols")).size(13)),
+            ));
+        }
+
+        depth_column = depth_column.push(tooltip(
+            labeled_slider(
+                "Imbalance range",
+                0.05..=5.0,
+                cfg.imbalance_range,
+                move |imbalance_range| {
+                    heatmap_cfg(heatmap::Config {
+                        imbalance_range,
+                        ..cfg
+                    })
+                },
+                |value| format!("±{value:.2}%"),
+                Some(0.05),
+            ),
+            Some("Distance from the mid price the imbalance indicator sums the depth within"),
+            TooltipPosition::Top,
+        ));
+
+        depth_column
+    };
+
     let noise_filters_column = column![
         text("Noise filters").size(14),
         iced::widget::checkbox(
@@ -243,15 +385,40 @@ pub fn heatmap_cfg_view<'a>(
     ]
     .spacing(8);
 
+    let liquidations_column =
+        liquidations_cfg(pane, cfg.liquidation_scale, move |liquidation_scale| {
+            VisualConfig::Heatmap(heatmap::Config {
+                liquidation_scale,
+                ..cfg
+            })
+        });
+
     let study_cfg = study_config
         .view(studies, basis)
         .map(move |msg| Message::StudyConfigurator(pane, study::StudyMessage::Heatmap(msg)));
 
+    let reference_lines_column = column![
+        text("Reference lines").size(14),
+        iced::widget::checkbox("Day open/high/low and previous close", cfg.day_levels).on_toggle(
+            move |day_levels| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Heatmap(heatmap::Config { day_levels, ..cfg }),
+                    false,
+                )
+            }
+        ),
+    ]
+    .spacing(8);
+
     let content = split_column![
         size_filters_column,
+        depth_column,
         noise_filters_column,
         trade_viz_column,
-        column![text("Studies").size(14), study_cfg].spacing(8),
+        reference_lines_column,
+        liquidations_column,
+        column![studies_header(pane), study_cfg].spacing(8),
         row![
             horizontal_space(),
             sync_all_button(pane, VisualConfig::Heatmap(cfg))
@@ -289,7 +456,91 @@ pub fn timesales_cfg_view<'a>(
             )
         };
 
-        column![text("Size filter").size(14), slider,].spacing(8)
+        let side_picklist = pick_list(SideFilter::ALL, Some(cfg.side_filter), move |side| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::TimeAndSales(timeandsales::Config {
+                    side_filter: side,
+                    ..cfg
+                }),
+                false,
+            )
+        });
+
+        column![text("Size filter").size(14), slider, side_picklist].spacing(8)
+    };
+
+    let columns_column = {
+        let notional = iced::widget::checkbox("Notional value", cfg.show_notional).on_toggle(
+            move |is_enabled| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::TimeAndSales(timeandsales::Config {
+                        show_notional: is_enabled,
+                        ..cfg
+                    }),
+                    false,
+                )
+            },
+        );
+        let exchange =
+            iced::widget::checkbox("Exchange", cfg.show_exchange).on_toggle(move |is_enabled| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::TimeAndSales(timeandsales::Config {
+                        show_exchange: is_enabled,
+                        ..cfg
+                    }),
+                    false,
+                )
+            });
+
+        column![text("Extra columns").size(14), notional, exchange].spacing(8)
+    };
+
+    let large_prints_column = {
+        let print_slider =
+            |label: &'static str,
+             value: f32,
+             on_change: fn(f32, timeandsales::Config) -> timeandsales::Config| {
+                labeled_slider(
+                    label,
+                    0.0..=1_000_000.0,
+                    value,
+                    move |value| {
+                        Message::VisualConfigChanged(
+                            pane,
+                            VisualConfig::TimeAndSales(on_change(value, cfg)),
+                            false,
+                        )
+                    },
+                    |value| {
+                        if *value > 0.0 {
+                            format!(">${}", format_with_commas(*value))
+                        } else {
+                            "Off".to_string()
+                        }
+                    },
+                    Some(5000.0),
+                )
+            };
+
+        column![
+            text("Large print highlights").size(14),
+            print_slider("Large", cfg.large_print, |value, cfg| {
+                timeandsales::Config {
+                    large_print: value,
+                    ..cfg
+                }
+            }),
+            print_slider("Huge", cfg.huge_print, |value, cfg| {
+                timeandsales::Config {
+                    huge_print: value,
+                    ..cfg
+                }
+            }),
+        ]
+        .spacing(8)
     };
 
     let storage_buffer_column = {
@@ -350,6 +601,8 @@ pub fn timesales_cfg_view<'a>(
 
     let content = split_column![
         trade_size_column,
+        columns_column,
+        large_prints_column,
         storage_buffer_column,
         stacked_bar_ratio,
         row![
@@ -362,30 +615,244 @@ pub fn timesales_cfg_view<'a>(
     cfg_view_container(320, content)
 }
 
+pub fn ladder_cfg_view<'a>(cfg: ladder::Config, pane: pane_grid::Pane) -> Element<'a, Message> {
+    let grouping_column = {
+        let picklist = pick_list(TickMultiplier::ALL, Some(cfg.tick_multiply), move |value| {
+            Message::VisualConfigChanged(
+                pane,
+                VisualConfig::Ladder(ladder::Config {
+                    tick_multiply: value,
+                    ..cfg
+                }),
+                false,
+            )
+        });
+
+        column![text("Ticks per row").size(14), picklist].spacing(8)
+    };
+
+    let volume_window_column = {
+        let slider = labeled_slider(
+            "Minutes",
+            1.0..=60.0,
+            cfg.volume_window_mins as f32,
+            move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Ladder(ladder::Config {
+                        volume_window_mins: value as u32,
+                        ..cfg
+                    }),
+                    false,
+                )
+            },
+            |value| format!("{}m", *value as u32),
+            Some(1.0),
+        );
+
+        column![text("Traded volume window").size(14), slider].spacing(8)
+    };
+
+    let content = split_column![
+        grouping_column,
+        volume_window_column,
+        row![
+            horizontal_space(),
+            sync_all_button(pane, VisualConfig::Ladder(cfg))
+        ],
+        ; spacing = 12, align_x = Alignment::Start
+    ];
+
+    cfg_view_container(320, content)
+}
+
 pub fn kline_cfg_view<'a>(
     study_config: &'a study::Configurator<FootprintStudy>,
     cfg: data::chart::kline::Config,
     kind: &'a KlineChartKind,
     pane: pane_grid::Pane,
     basis: data::chart::Basis,
+    imbalance_presets: &'a ImbalancePresets,
+    market: Option<exchange::adapter::MarketKind>,
 ) -> Element<'a, Message> {
+    let liquidations_column = liquidations_cfg(pane, cfg.liquidation_scale, move |scale| {
+        VisualConfig::Kline(data::chart::kline::Config {
+            liquidation_scale: scale,
+            ..cfg
+        })
+    });
+
+    let moving_averages = moving_averages_cfg(pane, cfg);
+    let anomalies = anomalies_cfg(pane, cfg, market);
+
+    let countdown = {
+        let checkbox = iced::widget::checkbox("Countdown to bar close", cfg.candle_countdown);
+
+        // tick and range bars close on the trades, not the clock
+        let checkbox = match basis {
+            data::chart::Basis::Time(_) => checkbox.on_toggle(move |candle_countdown| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Kline(data::chart::kline::Config {
+                        candle_countdown,
+                        ..cfg
+                    }),
+                    false,
+                )
+            }),
+            data::chart::Basis::Tick(_) | data::chart::Basis::Range(_) => checkbox,
+        };
+
+        column![text("Price label").size(14), checkbox].spacing(8)
+    };
+
+    let session_delta = column![
+        text("Session").size(14),
+        iced::widget::checkbox("Buy/sell/delta summary of the day", cfg.session_delta).on_toggle(
+            move |session_delta| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Kline(data::chart::kline::Config {
+                        session_delta,
+                        ..cfg
+                    }),
+                    false,
+                )
+            }
+        ),
+    ]
+    .spacing(8);
+
+    let day_levels =
+        iced::widget::checkbox("Day open/high/low and previous close lines", cfg.day_levels)
+            .on_toggle(move |day_levels| {
+                Message::VisualConfigChanged(
+                    pane,
+                    VisualConfig::Kline(data::chart::kline::Config { day_levels, ..cfg }),
+                    false,
+                )
+            });
+
     let content = match kind {
-        KlineChartKind::Candles => column![text(
-            "This chart type doesn't have any configurations, WIP..."
-        )],
+        KlineChartKind::Candles => {
+            let ratio_strip = column![
+                text("Candles").size(14),
+                iced::widget::checkbox("Buy/sell volume strip", cfg.volume_ratio_strip).on_toggle(
+                    move |is_enabled| {
+                        Message::VisualConfigChanged(
+                            pane,
+                            VisualConfig::Kline(data::chart::kline::Config {
+                                volume_ratio_strip: is_enabled,
+                                ..cfg
+                            }),
+                            false,
+                        )
+                    }
+                ),
+            ]
+            .spacing(8);
+
+            split_column![
+                ratio_strip,
+                countdown,
+                session_delta.push(day_levels),
+                moving_averages,
+                anomalies,
+                liquidations_column,
+                row![
+                    horizontal_space(),
+                    sync_all_button(pane, VisualConfig::Kline(cfg))
+                ],
+                ; spacing = 12, align_x = Alignment::Start
+            ]
+        }
+        KlineChartKind::Spread(mode) => {
+            let mode_picklist = pick_list(SpreadMode::ALL, Some(*mode), move |new_mode| {
+                Message::SpreadModeSelected(pane, new_mode)
+            });
+
+            split_column![
+                column![text("Combine legs as").size(14), mode_picklist].spacing(8),
+                countdown,
+                moving_averages,
+                anomalies,
+                row![
+                    horizontal_space(),
+                    sync_all_button(pane, VisualConfig::Kline(cfg))
+                ],
+                ; spacing = 12, align_x = Alignment::Start
+            ]
+        }
+        KlineChartKind::Tpo => {
+            split_column![
+                countdown,
+                session_delta.push(day_levels),
+                moving_averages,
+                anomalies,
+                liquidations_column,
+                row![
+                    horizontal_space(),
+                    sync_all_button(pane, VisualConfig::Kline(cfg))
+                ],
+                ; spacing = 12, align_x = Alignment::Start
+            ]
+        }
+        KlineChartKind::DeltaCandles => {
+            split_column![
+                countdown,
+                session_delta,
+                liquidations_column,
+                row![
+                    horizontal_space(),
+                    sync_all_button(pane, VisualConfig::Kline(cfg))
+                ],
+                ; spacing = 12, align_x = Alignment::Start
+            ]
+        }
         KlineChartKind::Footprint { clusters, studies } => {
             let cluster_picklist =
                 pick_list(ClusterKind::ALL, Some(clusters), move |new_cluster_kind| {
                     Message::ClusterKindSelected(pane, new_cluster_kind)
                 });
 
+            // enabling imbalance starts it off from the market's default preset, if one is set
+            let default_imbalance = market
+                .and_then(|market| imbalance_presets.default_for(market))
+                .map(|preset| preset.study());
+
             let study_cfg = study_config.view(studies, basis).map(move |msg| {
+                let msg = match (msg, default_imbalance) {
+                    (
+                        study::Message::StudyToggled(FootprintStudy::Imbalance { .. }, true),
+                        Some(preset),
+                    ) => study::Message::StudyToggled(preset, true),
+                    (msg, _) => msg,
+                };
                 Message::StudyConfigurator(pane, study::StudyMessage::Footprint(msg))
             });
 
+            let imbalance = studies
+                .iter()
+                .find(|study| matches!(study, FootprintStudy::Imbalance { .. }));
+
+            let save_preset: Element<_> = match imbalance {
+                Some(study) if imbalance_presets.matching(&[*study]).is_none() => row![
+                    horizontal_space(),
+                    button(text("Save imbalance as preset").size(12))
+                        .on_press(Message::SaveImbalancePreset(pane)),
+                ]
+                .into(),
+                _ => row![].into(),
+            };
+
             split_column![
                 column![text("Cluster type").size(14), cluster_picklist].spacing(8),
-                column![text("Studies").size(14), study_cfg].spacing(8),
+                column![studies_header(pane), study_cfg, save_preset].spacing(8),
+                countdown,
+                session_delta.push(day_levels),
+                moving_averages,
+                anomalies,
+                liquidations_column,
                 row![
                     horizontal_space(),
                     sync_all_button(pane, VisualConfig::Kline(cfg))
@@ -398,6 +865,213 @@ pub fn kline_cfg_view<'a>(
     cfg_view_container(360, content)
 }
 
+/// Toggles of the anomaly detectors marking a kline chart's bars
+fn anomalies_cfg<'a>(
+    pane: pane_grid::Pane,
+    cfg: data::chart::kline::Config,
+    market: Option<exchange::adapter::MarketKind>,
+) -> Element<'a, Message> {
+    let with_detectors = move |anomalies: Detectors| {
+        Message::VisualConfigChanged(
+            pane,
+            VisualConfig::Kline(data::chart::kline::Config { anomalies, ..cfg }),
+            false,
+        )
+    };
+    let detectors = cfg.anomalies;
+
+    let mut content = column![
+        row![
+            text("Anomalies").size(14),
+            tooltip(
+                button("i").style(style::button::info),
+                Some("Marks bars at least 3x the average of the 20 bars before them"),
+                TooltipPosition::Top,
+            ),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(4),
+        iced::widget::checkbox("Volume spikes", detectors.volume_spike).on_toggle(
+            move |volume_spike| {
+                with_detectors(Detectors {
+                    volume_spike,
+                    ..detectors
+                })
+            }
+        ),
+        iced::widget::checkbox("Spread blowouts", detectors.spread_blowout).on_toggle(
+            move |spread_blowout| {
+                with_detectors(Detectors {
+                    spread_blowout,
+                    ..detectors
+                })
+            }
+        ),
+    ]
+    .spacing(8);
+
+    if market.is_some_and(|market| market != exchange::adapter::MarketKind::Spot) {
+        content = content.push(
+            iced::widget::checkbox(
+                "OI dumps, with the open interest indicator",
+                detectors.oi_dump,
+            )
+            .on_toggle(move |oi_dump| {
+                with_detectors(Detectors {
+                    oi_dump,
+                    ..detectors
+                })
+            }),
+        );
+    }
+
+    content.into()
+}
+
+/// Moving average overlays of a kline chart, one row per filled slot
+fn moving_averages_cfg<'a>(
+    pane: pane_grid::Pane,
+    cfg: data::chart::kline::Config,
+) -> Element<'a, Message> {
+    let with_slot = move |slot: usize, ma: Option<MovingAverage>| {
+        let mut moving_averages = cfg.moving_averages;
+        moving_averages[slot] = ma;
+
+        Message::VisualConfigChanged(
+            pane,
+            VisualConfig::Kline(data::chart::kline::Config {
+                moving_averages,
+                ..cfg
+            }),
+            false,
+        )
+    };
+
+    let mut content = column![text("Moving averages").size(14)].spacing(8);
+
+    for (slot, ma) in cfg.moving_averages.iter().enumerate() {
+        let Some(ma) = *ma else {
+            continue;
+        };
+
+        let kind_picklist = pick_list(MaKind::ALL, Some(ma.kind), move |kind| {
+            with_slot(slot, Some(MovingAverage { kind, ..ma }))
+        });
+        let color_picklist = pick_list(OverlayColor::ALL, Some(ma.color), move |color| {
+            with_slot(slot, Some(MovingAverage { color, ..ma }))
+        });
+        let remove_btn = button(style::icon_text(style::Icon::Close, 10))
+            .on_press(with_slot(slot, None))
+            .style(|theme, status| style::button::transparent(theme, status, false));
+
+        let period_slider = classic_slider_row(
+            text("Period"),
+            slider(
+                MovingAverage::MIN_PERIOD..=MovingAverage::MAX_PERIOD,
+                ma.period,
+                move |period| with_slot(slot, Some(MovingAverage { period, ..ma })),
+            )
+            .into(),
+            Some(text(ma.period.to_string()).size(13)),
+        );
+
+        content = content.push(
+            container(
+                column![
+                    row![
+                        kind_picklist,
+                        color_picklist,
+                        horizontal_space(),
+                        remove_btn
+                    ]
+                    .spacing(4)
+                    .align_y(Alignment::Center),
+                    period_slider,
+                ]
+                .spacing(4),
+            )
+            .padding(8)
+            .style(style::modal_container),
+        );
+    }
+
+    if let Some(slot) = cfg.moving_averages.iter().position(Option::is_none) {
+        content = content.push(
+            button(text("Add moving average").size(12))
+                .on_press(with_slot(slot, Some(MovingAverage::new(slot)))),
+        );
+    }
+
+    content.into()
+}
+
+/// Toggle and size scaling of the liquidation bubbles, shared by heatmap and kline charts
+fn liquidations_cfg<'a>(
+    pane: pane_grid::Pane,
+    liquidation_scale: Option<i32>,
+    to_config: impl Fn(Option<i32>) -> VisualConfig + Copy + 'a,
+) -> Element<'a, Message> {
+    let scaling_slider: Element<_> = if let Some(scale) = liquidation_scale {
+        classic_slider_row(
+            text("Bubble size scaling"),
+            slider(10..=200, scale, move |value| {
+                Message::VisualConfigChanged(pane, to_config(Some(value)), false)
+            })
+            .step(10)
+            .into(),
+            Some(text(format!("{scale}%")).size(13)),
+        )
+    } else {
+        container(row![]).into()
+    };
+
+    column![
+        row![
+            text("Liquidations").size(14),
+            tooltip(
+                button("i").style(style::button::info),
+                Some("Forced orders, streamed for Binance perpetuals"),
+                TooltipPosition::Top,
+            ),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(4),
+        iced::widget::checkbox("Show liquidations", liquidation_scale.is_some()).on_toggle(
+            move |value| {
+                Message::VisualConfigChanged(
+                    pane,
+                    to_config(if value { Some(100) } else { None }),
+                    false,
+                )
+            }
+        ),
+        scaling_slider,
+    ]
+    .spacing(8)
+    .into()
+}
+
+/// Studies title with buttons to copy them over to another pane through the clipboard
+fn studies_header<'a>(pane: pane_grid::Pane) -> Element<'a, Message> {
+    row![
+        text("Studies").size(14),
+        horizontal_space(),
+        tooltip(
+            button(text("Copy").size(12)).on_press(Message::CopyStudies(pane)),
+            Some("Copy studies and cluster type to the clipboard"),
+            TooltipPosition::Top,
+        ),
+        tooltip(
+            button(text("Paste").size(12)).on_press(Message::PasteStudies(pane)),
+            Some("Apply studies copied from another pane"),
+            TooltipPosition::Top,
+        ),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center)
+    .into()
+}
+
 fn sync_all_button<'a>(pane: pane_grid::Pane, config: VisualConfig) -> Element<'a, Message> {
     tooltip(
         button("Sync all").on_press(Message::VisualConfigChanged(pane, config, true)),
@@ -412,7 +1086,7 @@ pub mod study {
         style::{self, Icon, icon_text},
     };
     use data::chart::heatmap::{CLEANUP_THRESHOLD, HeatmapStudy, ProfileKind};
-    use data::chart::kline::FootprintStudy;
+    use data::chart::kline::{FootprintStudy, VwapSession};
     use iced::{
         Element, padding,
         widget::{button, column, container, horizontal_rule, horizontal_space, row, slider, text},
@@ -541,6 +1215,157 @@ pub mod study {
                         .padding(4)
                         .into()
                 }
+                FootprintStudy::Vwap { session, bands } => {
+                    let session_picklist = iced::widget::pick_list(
+                        VwapSession::ALL,
+                        Some(session),
+                        move |new_session| {
+                            on_change(FootprintStudy::Vwap {
+                                session: new_session,
+                                bands,
+                            })
+                        },
+                    );
+
+                    let bands_checkbox = iced::widget::checkbox("Standard deviation bands", bands)
+                        .on_toggle(move |is_checked| {
+                            on_change(FootprintStudy::Vwap {
+                                session,
+                                bands: is_checked,
+                            })
+                        });
+
+                    column![
+                        row![text("Session reset"), horizontal_space(), session_picklist]
+                            .align_y(iced::Alignment::Center),
+                        bands_checkbox,
+                    ]
+                    .padding(8)
+                    .spacing(8)
+                    .into()
+                }
+                FootprintStudy::VolumeProfile { poc, value_area } => {
+                    let poc_checkbox = iced::widget::checkbox("Point of control", poc).on_toggle(
+                        move |is_checked| {
+                            on_change(FootprintStudy::VolumeProfile {
+                                poc: is_checked,
+                                value_area,
+                            })
+                        },
+                    );
+
+                    let value_area_checkbox =
+                        iced::widget::checkbox("Value area high/low", value_area.is_some())
+                            .on_toggle(move |is_checked| {
+                                on_change(FootprintStudy::VolumeProfile {
+                                    poc,
+                                    value_area: is_checked.then_some(70),
+                                })
+                            });
+
+                    let mut content = column![poc_checkbox, value_area_checkbox].spacing(8);
+
+                    if let Some(percent) = value_area {
+                        content = content.push(
+                            column![
+                                text(format!("Value area: {percent}% of volume")),
+                                slider(50.0..=95.0, percent as f32, move |new_value| {
+                                    on_change(FootprintStudy::VolumeProfile {
+                                        poc,
+                                        value_area: Some(new_value as usize),
+                                    })
+                                })
+                                .step(5.0),
+                            ]
+                            .spacing(2),
+                        );
+                    }
+
+                    content.padding(8).into()
+                }
+                FootprintStudy::BollingerBands { period, std_dev } => {
+                    let period_slider = column![
+                        text(format!("Period: {period} bars")),
+                        slider(5.0..=100.0, period as f32, move |new_value| {
+                            on_change(FootprintStudy::BollingerBands {
+                                period: new_value as usize,
+                                std_dev,
+                            })
+                        })
+                        .step(1.0),
+                    ]
+                    .spacing(2);
+
+                    let std_dev_slider = column![
+                        text(format!(
+                            "Band width: {:.1} standard deviations",
+                            std_dev as f32 / 10.0
+                        )),
+                        slider(5.0..=40.0, std_dev as f32, move |new_value| {
+                            on_change(FootprintStudy::BollingerBands {
+                                period,
+                                std_dev: new_value as usize,
+                            })
+                        })
+                        .step(5.0),
+                    ]
+                    .spacing(2);
+
+                    column![period_slider, std_dev_slider]
+                        .padding(8)
+                        .spacing(8)
+                        .into()
+                }
+                FootprintStudy::DeltaDivergence {
+                    lookback,
+                    threshold,
+                } => {
+                    let lookback_slider = column![
+                        text(format!("Lookback: {lookback} bars")),
+                        slider(3.0..=50.0, lookback as f32, move |new_value| {
+                            on_change(FootprintStudy::DeltaDivergence {
+                                lookback: new_value as usize,
+                                threshold,
+                            })
+                        })
+                        .step(1.0),
+                    ]
+                    .spacing(2);
+
+                    let threshold_slider = column![
+                        text(format!("Delta lag: {threshold}% of its range")),
+                        slider(0.0..=100.0, threshold as f32, move |new_value| {
+                            on_change(FootprintStudy::DeltaDivergence {
+                                lookback,
+                                threshold: new_value as usize,
+                            })
+                        })
+                        .step(5.0),
+                    ]
+                    .spacing(2);
+
+                    column![lookback_slider, threshold_slider]
+                        .padding(8)
+                        .spacing(8)
+                        .into()
+                }
+                FootprintStudy::UnfinishedAuction => {
+                    column![text("Marks bar highs and lows both buyers and sellers traded at, price tends to revisit them").size(12)]
+                        .padding(8)
+                        .into()
+                }
+                FootprintStudy::HighVolumeNodes { threshold } => column![
+                    text(format!("Volume: {threshold}% of the bar's average level")),
+                    slider(120.0..=500.0, threshold as f32, move |new_value| {
+                        on_change(FootprintStudy::HighVolumeNodes {
+                            threshold: new_value as usize,
+                        })
+                    })
+                    .step(10.0),
+                ]
+                .spacing(2)
+                .padding(8)
+                .into(),
             }
         }
     }
@@ -561,7 +1386,7 @@ pub mod study {
         ) -> Element<'a, Message<Self>> {
             let interval_ms = match basis {
                 data::chart::Basis::Time(interval) => interval.to_milliseconds(),
-                data::chart::Basis::Tick(_) => {
+                data::chart::Basis::Tick(_) | data::chart::Basis::Range(_) => {
                     return iced::widget::center(text(
                         "Heatmap studies are not supported for tick-based charts",
                     ))
@@ -3,7 +3,10 @@ use crate::{
     widget::numeric_input_box,
 };
 
-use data::chart::Basis;
+use data::{
+    aggr::{RangeBar, RangeKind},
+    chart::Basis,
+};
 use exchange::{TickMultiplier, Ticker, Timeframe, adapter::Exchange};
 use iced::{
     Element, Length,
@@ -117,6 +120,9 @@ pub enum SelectedTab {
         parsed_input: Option<data::aggr::TickCount>,
         is_input_valid: bool,
     },
+    Range {
+        kind: RangeKind,
+    },
 }
 
 pub enum Action {
@@ -203,7 +209,7 @@ impl Modifier {
         match message {
             Message::TabSelected(tab) => Some(Action::TabSelected(tab)),
             Message::BasisSelected(basis) => match basis {
-                Basis::Time(_) => Some(Action::BasisSelected(basis)),
+                Basis::Time(_) | Basis::Range(_) => Some(Action::BasisSelected(basis)),
                 Basis::Tick(new_tc) => {
                     if let SelectedTab::TickCount {
                         raw_input_buf,
@@ -243,6 +249,8 @@ impl Modifier {
                 Some(Action::TicksizeSelected(new_ticksize))
             }
             Message::TicksizeInputChanged(value_str) => {
+                let base_ticksize = self.base_ticksize;
+
                 if let ViewMode::TicksizeSelection {
                     ref mut raw_input_buf,
                     ref mut parsed_input,
@@ -258,15 +266,8 @@ impl Modifier {
                     if raw_input_buf.is_empty() {
                         *is_input_valid = true;
                     } else {
-                        match parsed_input {
-                            Some(tm) => {
-                                *is_input_valid =
-                                    tm.0 >= TICK_MULTIPLIER_MIN && tm.0 <= TICK_MULTIPLIER_MAX;
-                            }
-                            None => {
-                                *is_input_valid = false;
-                            }
-                        }
+                        *is_input_valid =
+                            parsed_input.is_some_and(|tm| is_valid_multiplier(tm, base_ticksize));
                     }
                 }
                 None
@@ -337,10 +338,10 @@ impl Modifier {
                 };
 
                 if selected_basis.is_some() {
-                    let (timeframe_tab_is_selected, tick_count_tab_is_selected) = match self.tab {
-                        SelectedTab::Timeframe => (true, false),
-                        SelectedTab::TickCount { .. } => (false, true),
-                    };
+                    let timeframe_tab_is_selected = matches!(self.tab, SelectedTab::Timeframe);
+                    let tick_count_tab_is_selected =
+                        matches!(self.tab, SelectedTab::TickCount { .. });
+                    let range_tab_is_selected = matches!(self.tab, SelectedTab::Range { .. });
 
                     let tabs_row = {
                         if is_kline_chart {
@@ -400,16 +401,38 @@ impl Modifier {
                                                 parsed_input,
                                                 is_input_valid,
                                             },
-                                            _ => SelectedTab::TickCount {
-                                                raw_input_buf: NumericInput::default(),
-                                                parsed_input: None,
-                                                is_input_valid: true,
-                                            },
+                                            // prefilled with the default, a submit away
+                                            _ => {
+                                                let tick_count =
+                                                    data::config::chart_defaults().tick_count;
+
+                                                SelectedTab::TickCount {
+                                                    raw_input_buf: NumericInput::from_tick_count(
+                                                        tick_count,
+                                                    ),
+                                                    parsed_input: Some(tick_count),
+                                                    is_input_valid: true,
+                                                }
+                                            }
                                         };
                                         Some(Message::TabSelected(tick_count_tab))
                                     },
                                     !tick_count_tab_is_selected,
-                                    !is_timeframe_selected,
+                                    matches!(selected_basis, Some(Basis::Tick(_))),
+                                ),
+                                tab_button(
+                                    text("Range"),
+                                    if range_tab_is_selected {
+                                        None
+                                    } else {
+                                        let kind = match selected_basis {
+                                            Some(Basis::Range(range)) => range.kind,
+                                            _ => RangeKind::Range,
+                                        };
+                                        Some(Message::TabSelected(SelectedTab::Range { kind }))
+                                    },
+                                    !range_tab_is_selected,
+                                    matches!(selected_basis, Some(Basis::Range(_))),
                                 ),
                             ]
                             .spacing(4)
@@ -431,8 +454,17 @@ impl Modifier {
                         };
 
                         if is_kline_chart {
+                            let kline_timeframes: Vec<Timeframe> = Timeframe::KLINE
+                                .iter()
+                                .copied()
+                                .filter(|tf| {
+                                    ticker_info.is_none_or(|(exchange, _)| {
+                                        exchange.supports_kline_timeframe(*tf)
+                                    })
+                                })
+                                .collect();
                             let kline_timeframe_grid = modifiers_grid(
-                                &Timeframe::KLINE,
+                                &kline_timeframes,
                                 selected_tf,
                                 |tf| Message::BasisSelected(tf.into()),
                                 &create_button,
@@ -494,6 +526,37 @@ impl Modifier {
                         basis_selection_column = basis_selection_column.push(custom_input);
                         basis_selection_column = basis_selection_column.push(tick_count_grid);
                     }
+                    SelectedTab::Range { kind } => {
+                        let selected_range = match selected_basis {
+                            Some(Basis::Range(range)) if range.kind == kind => Some(range),
+                            _ => None,
+                        };
+
+                        let kind_grid = modifiers_grid(
+                            &RangeKind::ALL,
+                            Some(kind),
+                            |kind| Message::TabSelected(SelectedTab::Range { kind }),
+                            &create_button,
+                            2,
+                        );
+
+                        let ranges = RangeBar::TICKS
+                            .iter()
+                            .map(|ticks| RangeBar::new(kind, *ticks))
+                            .collect::<Vec<_>>();
+                        let range_grid = modifiers_grid(
+                            &ranges,
+                            selected_range,
+                            |range| Message::BasisSelected(Basis::Range(range)),
+                            &create_button,
+                            3,
+                        );
+
+                        basis_selection_column = basis_selection_column
+                            .push(kind_grid)
+                            .push(text("Size in ticks").size(11))
+                            .push(range_grid);
+                    }
                 }
 
                 container(scrollable::Scrollable::with_direction(
@@ -503,7 +566,7 @@ impl Modifier {
                     ),
                 ))
                 .max_width(240)
-                .padding(16)
+                .padding(style::dense(16.0))
                 .style(style::chart_modal)
                 .into()
             }
@@ -529,9 +592,8 @@ impl Modifier {
                     );
 
                     let custom_input = {
-                        let tick_multiplier_to_submit = parsed_input.filter(|tm| {
-                            tm.0 >= TICK_MULTIPLIER_MIN && tm.0 <= TICK_MULTIPLIER_MAX
-                        });
+                        let tick_multiplier_to_submit =
+                            parsed_input.filter(|tm| is_valid_multiplier(*tm, self.base_ticksize));
 
                         numeric_input_box::<_, Message>(
                             "Custom: ",
@@ -547,18 +609,24 @@ impl Modifier {
                     ticksizes_column = ticksizes_column.push(tick_multiplier_grid);
 
                     if let Some(base_ticksize) = self.base_ticksize {
+                        let label = match parsed_input {
+                            Some(tm) if is_input_valid => format!(
+                                "Base: {base_ticksize} → {}",
+                                grouped_ticksize(base_ticksize, tm)
+                            ),
+                            _ => format!("Base: {base_ticksize}"),
+                        };
+
                         ticksizes_column = ticksizes_column.push(
                             row![
                                 iced::widget::horizontal_space(),
-                                text(format!("Base: {}", base_ticksize)).style(
-                                    |theme: &iced::Theme| {
-                                        iced::widget::text::Style {
-                                            color: Some(
-                                                theme.extended_palette().background.strongest.color,
-                                            ),
-                                        }
+                                text(label).style(|theme: &iced::Theme| {
+                                    iced::widget::text::Style {
+                                        color: Some(
+                                            theme.extended_palette().background.strongest.color,
+                                        ),
                                     }
-                                ),
+                                }),
                             ]
                             .padding(padding::top(8).right(4)),
                         );
@@ -571,12 +639,12 @@ impl Modifier {
                         ),
                     ))
                     .max_width(240)
-                    .padding(16)
+                    .padding(style::dense(16.0))
                     .style(style::chart_modal)
                     .into()
                 } else {
                     container(text("No ticksize available for this chart type"))
-                        .padding(16)
+                        .padding(style::dense(16.0))
                         .style(style::chart_modal)
                         .into()
                 }
@@ -585,6 +653,24 @@ impl Modifier {
     }
 }
 
+/// Price step of one row for the multiplier, rounded to the decimals of the base tick size
+fn grouped_ticksize(base_ticksize: f32, multiplier: TickMultiplier) -> f32 {
+    let decimals = (-base_ticksize.log10()).ceil().max(0.0) as i32;
+    let power = 10f32.powi(decimals);
+
+    (base_ticksize * f32::from(multiplier.0) * power).round() / power
+}
+
+/// Multipliers have to stay in range and, once the base tick size is known, give a step the pane
+/// can tell the base tick size back from, as it does on every later ticksize change
+fn is_valid_multiplier(multiplier: TickMultiplier, base_ticksize: Option<f32>) -> bool {
+    (TICK_MULTIPLIER_MIN..=TICK_MULTIPLIER_MAX).contains(&multiplier.0)
+        && base_ticksize.is_none_or(|base| {
+            let step = grouped_ticksize(base, multiplier);
+            step.is_finite() && (multiplier.base(step) - base).abs() <= base * 1e-3
+        })
+}
+
 fn modifiers_grid<'a, T, FMsg>(
     items_source: &[T],
     selected_value: Option<T>,
@@ -641,6 +727,7 @@ impl From<&ModifierKind> for SelectedTab {
                     parsed_input: if tc.is_custom() { Some(*tc) } else { None },
                     is_input_valid: true,
                 },
+                Basis::Range(range) => SelectedTab::Range { kind: range.kind },
             },
         }
     }
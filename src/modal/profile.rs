@@ -0,0 +1,189 @@
+use crate::style;
+use data::UserTimezone;
+use data::chart::kline::VolumeProfile;
+use exchange::{Ticker, TickerInfo};
+
+use iced::widget::{
+    Space, button, column, container, horizontal_space, pick_list, row, scrollable, text,
+};
+use iced::{Alignment, Element, Length};
+
+/// Rows the profile is grouped into, levels get merged until they fit
+const MAX_ROWS: usize = 48;
+const BAR_WIDTH: f32 = 180.0;
+const VALUE_AREA: usize = 70;
+
+/// Ticker with a kline chart open that a profile can be built of
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickerOption(Ticker);
+
+impl std::fmt::Display for TickerOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (symbol, market) = self.0.display_symbol_and_type();
+        write!(f, "{symbol} ({market}, {})", self.0.exchange)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+    TickerSelected(TickerOption),
+    Build,
+}
+
+pub enum Action {
+    Build(Ticker),
+}
+
+/// Volume profile of one ticker, merged from all of its loaded charts
+pub struct CompositeProfile {
+    pub ticker: Ticker,
+    pub profile: VolumeProfile,
+    pub tick_size: f32,
+    /// How many charts the profile was merged from
+    pub charts: usize,
+    pub span: (u64, u64),
+}
+
+#[derive(Default)]
+pub struct ProfileBuilder {
+    selected: Option<Ticker>,
+    result: Option<CompositeProfile>,
+}
+
+impl ProfileBuilder {
+    pub fn update(&mut self, message: Message) -> Option<Action> {
+        match message {
+            Message::TickerSelected(TickerOption(ticker)) => {
+                self.selected = Some(ticker);
+                Some(Action::Build(ticker))
+            }
+            Message::Build => self.selected.map(Action::Build),
+        }
+    }
+
+    pub fn set_result(&mut self, result: Option<CompositeProfile>) {
+        self.result = result;
+    }
+
+    pub fn view(&self, tickers: Vec<TickerInfo>, timezone: UserTimezone) -> Element<'_, Message> {
+        let options = tickers
+            .iter()
+            .map(|info| TickerOption(info.ticker))
+            .collect::<Vec<_>>();
+
+        let picker = row![
+            pick_list(
+                options,
+                self.selected.map(TickerOption),
+                Message::TickerSelected
+            )
+            .placeholder("Choose a ticker")
+            .text_size(12),
+            horizontal_space(),
+            button(text("Rebuild").size(12)).on_press_maybe(self.selected.map(|_| Message::Build)),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(8);
+
+        let body: Element<_> = match &self.result {
+            Some(result) if Some(result.ticker) == self.selected => profile_view(result, timezone),
+            _ if tickers.is_empty() => text("Open a kline chart to build a profile from").into(),
+            _ if self.selected.is_some() => text("Nothing loaded for this ticker yet").into(),
+            _ => text("Merges the bars of all charts of a ticker into one profile").into(),
+        };
+
+        container(column![text("Composite volume profile").size(14), picker, body].spacing(12))
+            .max_width(340)
+            .max_height(640)
+            .padding(style::dense(24.0))
+            .style(style::dashboard_modal)
+            .into()
+    }
+}
+
+fn profile_view(result: &CompositeProfile, timezone: UserTimezone) -> Element<'_, Message> {
+    let profile = &result.profile;
+
+    let Some((&lowest, &highest)) = profile
+        .levels
+        .keys()
+        .next()
+        .zip(profile.levels.keys().last())
+    else {
+        return text("No volume in the loaded bars").into();
+    };
+
+    let levels = ((highest.0 - lowest.0) / result.tick_size).round() as usize + 1;
+    let row_size = levels.div_ceil(MAX_ROWS).max(1) as f32 * result.tick_size;
+
+    // rows from the highest price down, each summing the levels at or above its price
+    let mut rows: Vec<(f32, f32)> = vec![];
+    for (price, (buy, sell)) in profile.levels.iter().rev() {
+        let row_price = lowest.0 + ((price.0 - lowest.0) / row_size).floor() * row_size;
+
+        match rows.last_mut() {
+            Some((last_price, volume)) if (*last_price - row_price).abs() < row_size / 2.0 => {
+                *volume += buy + sell;
+            }
+            _ => rows.push((row_price, buy + sell)),
+        }
+    }
+
+    let max_volume = rows.iter().map(|(_, volume)| *volume).fold(0.0, f32::max);
+    let poc = profile.poc();
+    let value_area = profile.value_area(VALUE_AREA);
+
+    let decimals = data::util::count_decimals(result.tick_size);
+
+    let mut list = column![].spacing(1);
+    for (price, volume) in rows {
+        let contains = |level: f32| level >= price && level < price + row_size;
+        let is_poc = poc.is_some_and(contains);
+        let is_value = value_area.is_some_and(|(val, vah)| price + row_size > val && price <= vah);
+
+        let bar = container(Space::new(
+            Length::Fixed((volume / max_volume).max(0.0) * BAR_WIDTH),
+            Length::Fixed(8.0),
+        ))
+        .style(move |theme: &iced::Theme| {
+            let palette = theme.extended_palette();
+            let color = if is_poc {
+                palette.warning.strong.color
+            } else if is_value {
+                palette.primary.base.color
+            } else {
+                palette.secondary.weak.color
+            };
+
+            container::Style {
+                background: Some(color.into()),
+                ..Default::default()
+            }
+        });
+
+        list = list.push(
+            row![
+                text(format!("{price:.decimals$}")).size(11).width(72),
+                bar,
+                horizontal_space(),
+                text(data::util::abbr_large_numbers(volume)).size(11),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(4),
+        );
+    }
+
+    let (start, end) = result.span;
+    let summary = text(format!(
+        "{} chart{} · {} to {}",
+        result.charts,
+        if result.charts == 1 { "" } else { "s" },
+        timezone.format_crosshair_timestamp(start as i64, u64::MAX),
+        timezone.format_crosshair_timestamp(end as i64, u64::MAX),
+    ))
+    .size(11);
+
+    column![summary, scrollable(list).height(Length::Shrink)]
+        .spacing(8)
+        .into()
+}
@@ -186,7 +186,7 @@ impl ThemeEditor {
 
         container(content)
             .max_width(380)
-            .padding(24)
+            .padding(style::dense(24.0))
             .style(style::dashboard_modal)
             .into()
     }
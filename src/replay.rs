@@ -0,0 +1,390 @@
+use std::time::Instant;
+
+use chrono::NaiveDate;
+use exchange::{
+    Trade,
+    adapter::{self, Exchange, StreamKind, binance},
+    depth::Depth,
+};
+use iced::widget::{button, container, horizontal_space, pick_list, row, text};
+use iced::{Alignment, Element, Length, Task, padding};
+
+use crate::style::{self, Icon, icon_text};
+
+/// Playback pace relative to how the trades originally came in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Speed {
+    #[default]
+    X1,
+    X5,
+    X10,
+    X25,
+    X50,
+    X100,
+}
+
+impl Speed {
+    pub const ALL: [Speed; 6] = [
+        Speed::X1,
+        Speed::X5,
+        Speed::X10,
+        Speed::X25,
+        Speed::X50,
+        Speed::X100,
+    ];
+
+    fn multiplier(self) -> u64 {
+        match self {
+            Speed::X1 => 1,
+            Speed::X5 => 5,
+            Speed::X10 => 10,
+            Speed::X25 => 25,
+            Speed::X50 => 50,
+            Speed::X100 => 100,
+        }
+    }
+}
+
+impl std::fmt::Display for Speed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x", self.multiplier())
+    }
+}
+
+/// Trades of a day and the orderbooks the recorder sampled during it, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct Day {
+    trades: Vec<Trade>,
+    books: Vec<(u64, Depth)>,
+}
+
+/// Plays stored trades and recorded books back as depth stream events, paced by a replay clock
+struct Replay {
+    trades: Vec<Trade>,
+    cursor: usize,
+    books: Vec<(u64, Depth)>,
+    /// Books before it are due, the last of those is the one shown
+    book_cursor: usize,
+    clock: u64,
+    speed: Speed,
+    is_playing: bool,
+    last_step: Option<Instant>,
+}
+
+impl Replay {
+    fn new(day: Day, speed: Speed) -> Self {
+        let first_trade = day.trades.first().map(|trade| trade.time);
+        let first_book = day.books.first().map(|(time, _)| *time);
+
+        Self {
+            clock: first_trade
+                .into_iter()
+                .chain(first_book)
+                .min()
+                .unwrap_or_default(),
+            trades: day.trades,
+            cursor: 0,
+            books: day.books,
+            book_cursor: 0,
+            speed,
+            is_playing: true,
+            last_step: None,
+        }
+    }
+
+    fn has_books(&self) -> bool {
+        !self.books.is_empty()
+    }
+
+    fn set_playing(&mut self, is_playing: bool) {
+        self.is_playing = is_playing;
+        // resuming shouldn't catch up on the time spent paused
+        self.last_step = None;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.cursor >= self.trades.len()
+    }
+
+    fn progress(&self) -> f32 {
+        if self.trades.is_empty() {
+            return 1.0;
+        }
+        self.cursor as f32 / self.trades.len() as f32
+    }
+
+    /// Trades due since the last step and the latest book due, along with the replay clock
+    /// they're stamped at
+    fn step(&mut self, now: Instant) -> Option<(u64, Depth, Box<[Trade]>)> {
+        if !self.is_playing || self.is_finished() {
+            return None;
+        }
+
+        if let Some(last_step) = self.last_step.replace(now) {
+            let elapsed = now.saturating_duration_since(last_step).as_millis() as u64;
+            self.clock += elapsed * self.speed.multiplier();
+        }
+
+        let due = self.trades[self.cursor..].partition_point(|trade| trade.time <= self.clock);
+        let batch = self.trades[self.cursor..self.cursor + due].into();
+        self.cursor += due;
+
+        self.book_cursor +=
+            self.books[self.book_cursor..].partition_point(|(time, _)| *time <= self.clock);
+        let depth = self
+            .book_cursor
+            .checked_sub(1)
+            .map(|index| self.books[index].1.clone())
+            .unwrap_or_default();
+
+        if self.is_finished() {
+            self.is_playing = false;
+        }
+
+        Some((self.clock, depth, batch))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    DateSelected(NaiveDate),
+    Loaded(NaiveDate, Result<Day, String>),
+    TogglePlaying,
+    SpeedSelected(Speed),
+    Close,
+}
+
+pub enum Action {
+    /// Trades are loaded, the layout's charts of the stream should clear out for playback
+    Started,
+    Closed,
+    ErrorOccurred(String),
+}
+
+/// Bar controlling the replay of a stream's downloaded trade archives and recordings, so
+/// heatmaps and footprints can be reviewed without a live connection
+pub struct ReplayPanel {
+    pub layout_id: uuid::Uuid,
+    pub stream: StreamKind,
+    dates: Vec<NaiveDate>,
+    /// Days with a downloaded trade archive, the rest only have recordings
+    archived: Vec<NaiveDate>,
+    selected: Option<NaiveDate>,
+    is_loading: bool,
+    speed: Speed,
+    replay: Option<Replay>,
+}
+
+impl ReplayPanel {
+    /// `None` when there's nothing stored for the stream to play back
+    pub fn new(layout_id: uuid::Uuid, stream: StreamKind) -> Option<Self> {
+        let StreamKind::DepthAndTrades { exchange, ticker } = stream else {
+            return None;
+        };
+
+        let archived = match exchange {
+            // the daily archives only hold mainnet trades
+            Exchange::BinanceSpot | Exchange::BinanceLinear | Exchange::BinanceInverse
+                if !adapter::is_testnet(exchange) =>
+            {
+                binance::cached_trade_dates(ticker, &data::data_path(Some("market_data/binance/")))
+            }
+            _ => vec![],
+        };
+
+        let mut dates = data::recorder::recorded_dates(exchange, ticker);
+        dates.extend(&archived);
+        dates.sort();
+        dates.dedup();
+
+        if dates.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            layout_id,
+            stream,
+            dates,
+            archived,
+            selected: None,
+            is_loading: false,
+            speed: Speed::default(),
+            replay: None,
+        })
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    pub fn update(&mut self, message: Message) -> (Task<Message>, Option<Action>) {
+        match message {
+            Message::DateSelected(date) => {
+                let StreamKind::DepthAndTrades { exchange, ticker } = self.stream else {
+                    return (Task::none(), None);
+                };
+
+                self.selected = Some(date);
+                self.is_loading = true;
+
+                let is_archived = self.archived.contains(&date);
+
+                let load = Task::perform(
+                    load_day(exchange, ticker, date, is_archived),
+                    move |result| Message::Loaded(date, result),
+                );
+
+                return (load, None);
+            }
+            Message::Loaded(date, result) => {
+                if self.selected != Some(date) {
+                    return (Task::none(), None);
+                }
+                self.is_loading = false;
+
+                match result {
+                    Ok(day) => {
+                        self.replay = Some(Replay::new(day, self.speed));
+                        return (Task::none(), Some(Action::Started));
+                    }
+                    Err(err) => {
+                        self.selected = None;
+                        return (
+                            Task::none(),
+                            Some(Action::ErrorOccurred(format!(
+                                "Failed to load trades of {date}: {err}"
+                            ))),
+                        );
+                    }
+                }
+            }
+            Message::TogglePlaying => {
+                if let Some(replay) = &mut self.replay {
+                    replay.set_playing(!replay.is_playing);
+                }
+            }
+            Message::SpeedSelected(speed) => {
+                self.speed = speed;
+                if let Some(replay) = &mut self.replay {
+                    replay.speed = speed;
+                }
+            }
+            Message::Close => return (Task::none(), Some(Action::Closed)),
+        }
+
+        (Task::none(), None)
+    }
+
+    /// Next batch of replayed trades with the book at that time, in the shape the live depth
+    /// streams deliver them
+    pub fn step(&mut self, now: Instant) -> Option<exchange::Event> {
+        let (clock, depth, trades) = self.replay.as_mut()?.step(now)?;
+
+        Some(exchange::Event::DepthReceived(
+            self.stream,
+            clock,
+            depth,
+            trades,
+        ))
+    }
+
+    pub fn view<'a>(&'a self, timezone: data::UserTimezone) -> Element<'a, Message> {
+        let (_, ticker) = self.stream.exchange_and_ticker();
+        let (symbol, _) = ticker.display_symbol_and_type();
+
+        let date_picklist = pick_list(self.dates.as_slice(), self.selected, Message::DateSelected)
+            .placeholder("Select a day")
+            .text_size(12);
+
+        let status: Element<_> = match &self.replay {
+            _ if self.is_loading => text("Loading trades...").size(12).into(),
+            Some(replay) => {
+                let time = timezone.format_crosshair_timestamp(replay.clock as i64, 60_000);
+
+                row![
+                    button(text(if replay.is_playing { "Pause" } else { "Play" }).size(12))
+                        .on_press_maybe((!replay.is_finished()).then_some(Message::TogglePlaying))
+                        .style(|theme, status| style::button::transparent(theme, status, false)),
+                    text(time).size(12),
+                    text(format!("{:.0}%", replay.progress() * 100.0)).size(12),
+                    text(if replay.has_books() {
+                        ""
+                    } else {
+                        "No recorded orderbook that day, heatmaps only show trades"
+                    })
+                    .size(11),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .into()
+            }
+            None => text("Streams pause while replaying").size(11).into(),
+        };
+
+        let content = row![
+            text(format!("Replay {symbol}")).size(12),
+            date_picklist,
+            pick_list(Speed::ALL, Some(self.speed), Message::SpeedSelected).text_size(12),
+            status,
+            horizontal_space(),
+            button(icon_text(Icon::Close, 10))
+                .on_press(Message::Close)
+                .style(|theme, status| style::button::transparent(theme, status, false)),
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .padding(padding::left(12).right(12).bottom(4))
+            .style(style::status_bar)
+            .into()
+    }
+}
+
+/// Trades of the day from its downloaded archive when there is one, or else from the recording,
+/// along with the books recorded that day
+async fn load_day(
+    exchange: Exchange,
+    ticker: exchange::Ticker,
+    date: NaiveDate,
+    is_archived: bool,
+) -> Result<Day, String> {
+    let day_start = date
+        .and_hms_opt(0, 0, 0)
+        .expect("Midnight is a valid time")
+        .and_utc()
+        .timestamp_millis() as u64;
+    let day_end = day_start + 24 * 60 * 60 * 1000;
+
+    let points = match data::recorder::load_since(exchange, ticker, day_start) {
+        Ok(points) => points,
+        Err(err) if is_archived => {
+            log::warn!("Replaying without recorded books: {err}");
+            vec![]
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+    let points = points
+        .into_iter()
+        .take_while(|point| point.time < day_end)
+        .collect::<Vec<_>>();
+
+    let trades = if is_archived {
+        binance::read_cached_trades(ticker, date, data::data_path(Some("market_data/binance/")))
+            .await
+            .map_err(|err| err.to_string())?
+    } else {
+        points
+            .iter()
+            .flat_map(|point| point.trades.iter().copied())
+            .collect()
+    };
+
+    let books = points
+        .into_iter()
+        .map(|point| (point.time, point.depth))
+        .collect();
+
+    Ok(Day { trades, books })
+}
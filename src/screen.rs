@@ -4,8 +4,20 @@ pub mod dashboard;
 pub enum DashboardError {
     #[error("Fetch error: {0}")]
     Fetch(String),
+    /// The exchange answered with an error of its own, shown as is
+    #[error("{0}")]
+    Exchange(String),
     #[error("Pane set error: {0}")]
     PaneSet(String),
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
+
+impl From<exchange::adapter::AdapterError> for DashboardError {
+    fn from(err: exchange::adapter::AdapterError) -> Self {
+        match err {
+            exchange::adapter::AdapterError::Api(err) => DashboardError::Exchange(err.to_string()),
+            err => DashboardError::Fetch(err.to_string()),
+        }
+    }
+}
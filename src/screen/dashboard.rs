@@ -1,3 +1,4 @@
+mod kline_store;
 pub mod pane;
 pub mod panel;
 pub mod sidebar;
@@ -5,6 +6,8 @@ pub mod tickers_table;
 
 pub use sidebar::Sidebar;
 
+use kline_store::KlineStore;
+
 use super::DashboardError;
 use crate::{
     chart,
@@ -13,14 +16,25 @@ use crate::{
     widget::toast::Toast,
     window::{self, Window},
 };
-use data::{UserTimezone, chart::Basis, layout::WindowSpec};
+use data::{
+    UserTimezone,
+    chart::{
+        Basis,
+        day_levels::DayLevels,
+        indicator::{HeatmapIndicator, Indicator, KlineIndicator},
+        kline::ImbalancePresets,
+    },
+    config::theme::ChartThemes,
+    layout::WindowSpec,
+};
 use exchange::{
-    Kline, Ticker, TickerInfo, Timeframe, Trade,
+    FundingRate, Kline, Liquidation, SerTicker, Ticker, TickerInfo, TickerStats, Timeframe, Trade,
     adapter::{
-        self, AdapterError, Exchange, StreamConfig, StreamKind, UniqueStreams, binance, bybit,
+        self, AdapterError, Exchange, KlineSource, StreamConfig, StreamKind, UniqueStreams,
+        binance, bybit, okx,
     },
     depth::Depth,
-    fetcher::{FetchRange, FetchedData},
+    fetcher::{FetchCoalescer, FetchRange, FetchedData, KlineFetch},
 };
 
 use iced::{
@@ -32,7 +46,17 @@ use iced::{
     },
 };
 use iced_futures::futures::TryFutureExt;
-use std::{collections::HashMap, path::PathBuf, time::Instant, vec};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::Instant,
+    vec,
+};
+
+/// How long a failed fetch of a ticker's day levels waits before it's tried again
+const DAY_LEVELS_RETRY: std::time::Duration = std::time::Duration::from_secs(60);
+/// Funding rates move with the premium, so they're fetched again this often
+const FUNDING_REFRESH: std::time::Duration = std::time::Duration::from_secs(60);
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -40,6 +64,8 @@ pub enum Message {
     ChangePaneStatus(uuid::Uuid, pane::Status),
     SavePopoutSpecs(HashMap<window::Id, WindowSpec>),
     ErrorOccurred(Option<uuid::Uuid>, DashboardError),
+    /// Klines the pane asked for with the request id couldn't be fetched
+    KlinesFetchFailed(uuid::Uuid, Option<uuid::Uuid>, StreamKind, DashboardError),
     Notification(Toast),
     DistributeFetchedData {
         layout_id: uuid::Uuid,
@@ -47,6 +73,28 @@ pub enum Message {
         stream: StreamKind,
         data: FetchedData,
     },
+    RequestKlines {
+        pane_id: uuid::Uuid,
+        stream: StreamKind,
+        req_id: uuid::Uuid,
+        range: (u64, u64),
+    },
+    /// A heatmap was opened on the stream, the recording of its last minutes can fill it in
+    BackfillRequested(StreamKind),
+    RecordingLoaded(
+        StreamKind,
+        Result<Vec<data::recorder::RecordedPoint>, String>,
+    ),
+    PaneCaptured(
+        window::Id,
+        pane_grid::Pane,
+        pane::ImageTarget,
+        iced::window::Screenshot,
+    ),
+    WatchlistStats(uuid::Uuid, Exchange, HashMap<Ticker, TickerStats>),
+    WatchlistSparkline(uuid::Uuid, Ticker, Vec<Kline>),
+    DayLevelsFetched(Ticker, Result<Vec<Kline>, String>),
+    FundingRateFetched(Ticker, Result<FundingRate, String>),
 }
 
 pub struct Dashboard {
@@ -54,7 +102,21 @@ pub struct Dashboard {
     pub focus: Option<(window::Id, pane_grid::Pane)>,
     pub popout: HashMap<window::Id, (pane_grid::State<pane::State>, WindowSpec)>,
     pub streams: UniqueStreams,
+    lazy_streams: bool,
+    streams_paused: bool,
+    /// Set whenever the panes' streams get refreshed, until the crash context picks it up
+    panes_changed: bool,
+    hidden_popouts: HashSet<window::Id>,
+    kline_store: KlineStore,
     layout_id: uuid::Uuid,
+    /// Session levels of the tickers whose charts draw them
+    day_levels: HashMap<Ticker, DayLevels>,
+    day_levels_requested: HashMap<Ticker, Instant>,
+    /// Funding of the perp tickers open in a pane, shown in their headers
+    funding_rates: HashMap<Ticker, FundingRate>,
+    funding_requested: HashMap<Ticker, Instant>,
+    /// Ranged kline fetches in flight, led by the pane and request id that made them
+    kline_fetches: FetchCoalescer<KlineFetch, (uuid::Uuid, Option<uuid::Uuid>)>,
 }
 
 impl Default for Dashboard {
@@ -63,6 +125,16 @@ impl Default for Dashboard {
             panes: pane_grid::State::with_configuration(Self::default_pane_config()),
             focus: None,
             streams: UniqueStreams::default(),
+            lazy_streams: false,
+            streams_paused: false,
+            panes_changed: false,
+            hidden_popouts: HashSet::new(),
+            kline_store: KlineStore::default(),
+            day_levels: HashMap::new(),
+            day_levels_requested: HashMap::new(),
+            funding_rates: HashMap::new(),
+            funding_requested: HashMap::new(),
+            kline_fetches: FetchCoalescer::default(),
             popout: HashMap::new(),
             layout_id: uuid::Uuid::new_v4(),
         }
@@ -72,6 +144,12 @@ impl Default for Dashboard {
 #[derive(Debug, Clone)]
 pub enum Event {
     Notification(Toast),
+    TagAdded(data::journal::Tag),
+    ImbalancePresetSaved(data::chart::kline::ImbalancePreset),
+    ReplayRequested(StreamKind),
+    BackfillRequested(StreamKind),
+    /// A price alert's line was dragged to a new level on one of the charts
+    AlertMoved(u64, f32),
     DistributeFetchedData {
         layout_id: uuid::Uuid,
         pane_id: uuid::Uuid,
@@ -125,6 +203,16 @@ impl Dashboard {
             panes,
             focus: None,
             streams: UniqueStreams::default(),
+            lazy_streams: false,
+            streams_paused: false,
+            panes_changed: false,
+            hidden_popouts: HashSet::new(),
+            kline_store: KlineStore::default(),
+            day_levels: HashMap::new(),
+            day_levels_requested: HashMap::new(),
+            funding_rates: HashMap::new(),
+            funding_requested: HashMap::new(),
+            kline_fetches: FetchCoalescer::default(),
             popout,
             layout_id,
         }
@@ -172,6 +260,34 @@ impl Dashboard {
         layout_id: &uuid::Uuid,
     ) -> (Task<Message>, Option<Event>) {
         match message {
+            Message::WatchlistStats(pane_id, exchange, stats) => {
+                if let Some(state) = self.get_mut_pane_state_by_uuid(main_window.id, pane_id) {
+                    if let pane::Content::Watchlist(watchlist) = &mut state.content {
+                        watchlist.set_stats(exchange, stats);
+                    }
+                }
+            }
+            Message::DayLevelsFetched(ticker, result) => match result {
+                Ok(klines) => {
+                    if let Some(levels) = DayLevels::from_daily_klines(&klines) {
+                        self.day_levels.insert(ticker, levels);
+                    }
+                }
+                Err(err) => log::warn!("Failed to fetch daily klines of {ticker:?}: {err}"),
+            },
+            Message::FundingRateFetched(ticker, result) => match result {
+                Ok(funding) => {
+                    self.funding_rates.insert(ticker, funding);
+                }
+                Err(err) => log::warn!("Failed to fetch funding rate of {ticker:?}: {err}"),
+            },
+            Message::WatchlistSparkline(pane_id, ticker, klines) => {
+                if let Some(state) = self.get_mut_pane_state_by_uuid(main_window.id, pane_id) {
+                    if let pane::Content::Watchlist(watchlist) = &mut state.content {
+                        watchlist.set_sparkline(ticker, &klines);
+                    }
+                }
+            }
             Message::SavePopoutSpecs(specs) => {
                 for (window_id, new_spec) in specs {
                     if let Some((_, spec)) = self.popout.get_mut(&window_id) {
@@ -179,13 +295,41 @@ impl Dashboard {
                     }
                 }
             }
-            Message::ErrorOccurred(pane_id, err) => match pane_id {
-                Some(id) => {
-                    if let Some(state) = self.get_mut_pane_state_by_uuid(main_window.id, id) {
-                        state.status = pane::Status::Ready;
-                        state.notifications.push(Toast::error(err.to_string()));
-                    }
+            Message::PaneCaptured(window, pane, target, screenshot) => {
+                if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                    let Some(bounds) = state.content.set_export(None) else {
+                        return (Task::none(), None);
+                    };
+
+                    let label = state
+                        .settings
+                        .ticker_info
+                        .map_or("chart".to_string(), |info| {
+                            info.ticker.display_symbol_and_type().0
+                        });
+
+                    let result =
+                        crate::screenshot::crop(&screenshot, bounds).and_then(
+                            |image| match target {
+                                pane::ImageTarget::File => {
+                                    crate::screenshot::save_pane(&image, &label)
+                                        .map(|path| format!("Saved chart to {}", path.display()))
+                                }
+                                pane::ImageTarget::Clipboard => {
+                                    crate::screenshot::copy_to_clipboard(&image)
+                                        .map(|()| "Copied chart image".to_string())
+                                }
+                            },
+                        );
+
+                    state.notifications.push(match result {
+                        Ok(msg) => Toast::info(msg),
+                        Err(err) => Toast::error(format!("Failed to export chart: {err}")),
+                    });
                 }
+            }
+            Message::ErrorOccurred(pane_id, err) => match pane_id {
+                Some(id) => self.set_pane_error(main_window.id, id, &err),
                 _ => {
                     return (
                         Task::done(Message::Notification(Toast::error(err.to_string()))),
@@ -193,6 +337,34 @@ impl Dashboard {
                     );
                 }
             },
+            Message::KlinesFetchFailed(pane_id, req_id, stream, err) => {
+                // the panes waiting on the same fetch won't get their klines either
+                let mut failed = self
+                    .kline_fetches
+                    .finish(&(pane_id, req_id))
+                    .into_iter()
+                    .map(|(id, _)| id)
+                    .collect::<Vec<_>>();
+                failed.push(pane_id);
+
+                // nor do the ones a shared initial fetch was made for
+                if req_id.is_none() && self.kline_store.finish_shared_fetch(&stream) {
+                    failed.extend(
+                        self.iter_all_panes(main_window.id)
+                            .filter(|(_, _, pane_state)| {
+                                pane_state.matches_stream(&stream)
+                                    && pane_state.spread_legs().is_none()
+                            })
+                            .map(|(_, _, pane_state)| pane_state.unique_id()),
+                    );
+                    failed.sort_unstable();
+                    failed.dedup();
+                }
+
+                for id in failed {
+                    self.set_pane_error(main_window.id, id, &err);
+                }
+            }
             Message::Pane(window, message) => match message {
                 pane::Message::PaneClicked(pane) => {
                     self.focus = Some((window, pane));
@@ -225,9 +397,21 @@ impl Dashboard {
                 }
                 pane::Message::MaximizePane(pane) => {
                     self.panes.maximize(pane);
+
+                    if self.lazy_streams {
+                        return (self.refresh_streams(main_window.id), None);
+                    }
                 }
                 pane::Message::Restore => {
                     self.panes.restore();
+
+                    if self.lazy_streams {
+                        return (
+                            self.refresh_streams(main_window.id)
+                                .chain(self.klines_fetch_all_task(*layout_id, main_window.id)),
+                            None,
+                        );
+                    }
                 }
                 pane::Message::ReplacePane(pane) => {
                     if let Some(pane) = self.panes.get_mut(pane) {
@@ -254,6 +438,11 @@ impl Dashboard {
                     }
                 }
                 pane::Message::ChartInteraction(pane, msg) => {
+                    let moved_alert = match msg {
+                        chart::Message::AlertLineReleased(id, price) => Some((id, price)),
+                        _ => None,
+                    };
+
                     if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
                         match state.content {
                             pane::Content::Heatmap(ref mut chart, _) => {
@@ -265,11 +454,68 @@ impl Dashboard {
                             _ => {}
                         }
                     }
+
+                    if let Some((id, price)) = moved_alert {
+                        return (Task::none(), Some(Event::AlertMoved(id, price)));
+                    }
+                }
+                pane::Message::PanelInteraction(pane, panel::Message::CopySelection) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::TimeAndSales(panel) = &state.content {
+                            let trades = panel.selected_trades();
+
+                            if !trades.is_empty() {
+                                let table = data::chart::timeandsales::trades_table(
+                                    trades.iter().copied(),
+                                    '\t',
+                                );
+                                state
+                                    .notifications
+                                    .push(Toast::info(format!("Copied {} trades", trades.len())));
+                                return (iced::clipboard::write(table), None);
+                            }
+                        }
+                    }
+                }
+                pane::Message::Watchlist(pane, panel::watchlist::Message::Open(ticker_info)) => {
+                    let task = self.open_from_watchlist(main_window.id, window, pane, ticker_info);
+                    return (self.refresh_streams(main_window.id).chain(task), None);
+                }
+                pane::Message::Watchlist(pane, panel::watchlist::Message::Remove(ticker)) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::Watchlist(watchlist) = &mut state.content {
+                            watchlist.remove(ticker);
+                        }
+                    }
+                }
+                pane::Message::WhaleTape(pane, msg) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::WhaleTape(tape) = &mut state.content {
+                            tape.update(msg);
+                        }
+                    }
+                }
+                pane::Message::OpenWhaleTape(pane) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.content = pane::Content::WhaleTape(
+                            panel::whale_tape::WhaleTape::new(panel::whale_tape::DEFAULT_THRESHOLD),
+                        );
+                        state.settings.ticker_info = None;
+                        state.streams.clear();
+                        state.modal = None;
+                    }
+                    return (self.refresh_streams(main_window.id), None);
                 }
                 pane::Message::PanelInteraction(pane, msg) => {
                     if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
-                        if let pane::Content::TimeAndSales(ref mut panel) = state.content {
-                            panel::update(panel, msg);
+                        match state.content {
+                            pane::Content::TimeAndSales(ref mut panel) => {
+                                panel::update(panel, msg);
+                            }
+                            pane::Content::DomLadder(ref mut panel) => {
+                                panel::update(panel, msg);
+                            }
+                            _ => {}
                         }
                     }
                 }
@@ -305,6 +551,9 @@ impl Dashboard {
                                             ) | (
                                                 data::chart::VisualConfig::TimeAndSales(_),
                                                 pane::Content::TimeAndSales(_)
+                                            ) | (
+                                                data::chart::VisualConfig::Ladder(_),
+                                                pane::Content::DomLadder(_)
                                             )
                                         ),
                                     };
@@ -331,6 +580,9 @@ impl Dashboard {
                         state.settings.visual_config = Some(cfg);
                         state.content.change_visual_config(cfg);
                     }
+
+                    // toggling liquidations on a chart adds or drops its stream
+                    return (self.refresh_streams(main_window.id), None);
                 }
                 pane::Message::SwitchLinkGroup(pane, group) => {
                     if group.is_none() {
@@ -367,8 +619,11 @@ impl Dashboard {
                                         for stream in &streams {
                                             if let StreamKind::Kline { .. } = stream {
                                                 return (
-                                                    kline_fetch_task(
-                                                        *layout_id, pane_id, *stream, None, None,
+                                                    self.kline_init_task(
+                                                        main_window.id,
+                                                        *layout_id,
+                                                        pane_id,
+                                                        *stream,
                                                     ),
                                                     None,
                                                 );
@@ -396,6 +651,11 @@ impl Dashboard {
                         pane_state.notifications.remove(idx);
                     }
                 }
+                pane::Message::ToggleNotificationDetails(pane, idx) => {
+                    if let Some(pane_state) = self.get_mut_pane(main_window.id, window, pane) {
+                        pane_state.notifications.toggle_details(idx);
+                    }
+                }
                 pane::Message::ReorderIndicator(pane, event) => {
                     if let Some(pane_state) = self.get_mut_pane(main_window.id, window, pane) {
                         pane_state.content.reorder_indicators(&event);
@@ -408,6 +668,18 @@ impl Dashboard {
                         }
                     }
                 }
+                pane::Message::SpreadModeSelected(pane, mode) => {
+                    if let Some(pane_state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::Kline(chart, _) = &mut pane_state.content {
+                            chart.set_spread_mode(mode);
+                        }
+
+                        let pane_id = pane_state.unique_id();
+                        if let Some((first, _, _)) = pane_state.spread_legs() {
+                            self.insert_spread_klines(main_window.id, pane_id, &first, None, false);
+                        }
+                    }
+                }
                 pane::Message::StudyConfigurator(pane, study_msg) => {
                     if let Some(pane_state) = self.get_mut_pane(main_window.id, window, pane) {
                         match study_msg {
@@ -436,12 +708,47 @@ impl Dashboard {
                                     state.modal = Some(pane::Modal::StreamModifier(modifier));
                                 }
                                 Some(modal::stream::Action::BasisSelected(new_basis)) => {
+                                    let time_only = match state.content.chart_kind() {
+                                        Some(data::chart::KlineChartKind::Tpo) => Some("TPO"),
+                                        Some(data::chart::KlineChartKind::Spread(_)) => {
+                                            Some("Spread")
+                                        }
+                                        _ => None,
+                                    };
+                                    if let Some(kind) = time_only.filter(|_| !new_basis.is_time()) {
+                                        return (
+                                            Task::done(Message::Notification(Toast::warn(
+                                                format!("{kind} charts need a time based basis"),
+                                            ))),
+                                            None,
+                                        );
+                                    }
+
                                     modifier.update_kind_with_basis(new_basis);
 
                                     state.modal = Some(pane::Modal::StreamModifier(modifier));
 
                                     state.settings.selected_basis = Some(new_basis);
 
+                                    // both legs move to the new timeframe together
+                                    if let Some(ticker_info) = state
+                                        .settings
+                                        .ticker_info
+                                        .filter(|_| state.spread_legs().is_some())
+                                    {
+                                        let task = self.init_pane(
+                                            main_window.id,
+                                            window,
+                                            pane,
+                                            ticker_info,
+                                            "spread",
+                                        );
+                                        return (
+                                            self.refresh_streams(main_window.id).chain(task),
+                                            None,
+                                        );
+                                    }
+
                                     if let pane::Content::Heatmap(ref mut chart, _) = state.content
                                     {
                                         chart.set_basis(new_basis);
@@ -451,10 +758,7 @@ impl Dashboard {
                                     if let Some((exchange, ticker)) = state.stream_pair() {
                                         let chart_kind =
                                             state.content.chart_kind().unwrap_or_default();
-                                        let is_footprint = matches!(
-                                            chart_kind,
-                                            data::chart::KlineChartKind::Footprint { .. }
-                                        );
+                                        let is_order_flow = chart_kind.is_order_flow();
 
                                         match new_basis {
                                             Basis::Time(new_tf) => {
@@ -462,9 +766,10 @@ impl Dashboard {
                                                     exchange,
                                                     ticker,
                                                     timeframe: new_tf,
+                                                    source: state.kline_source(),
                                                 }];
 
-                                                if is_footprint {
+                                                if is_order_flow {
                                                     streams.push(StreamKind::DepthAndTrades {
                                                         exchange,
                                                         ticker,
@@ -499,12 +804,12 @@ impl Dashboard {
                                                             if let StreamKind::Kline { .. } =
                                                                 stream_type
                                                             {
-                                                                let task = kline_fetch_task(
+                                                                let stream = *stream_type;
+                                                                let task = self.kline_init_task(
+                                                                    main_window.id,
                                                                     *layout_id,
                                                                     pane_id,
-                                                                    *stream_type,
-                                                                    None,
-                                                                    None,
+                                                                    stream,
                                                                 );
                                                                 return (
                                                                     self.refresh_streams(
@@ -518,7 +823,7 @@ impl Dashboard {
                                                     }
                                                 }
                                             }
-                                            Basis::Tick(interval) => {
+                                            Basis::Tick(_) | Basis::Range(_) => {
                                                 state.streams = vec![StreamKind::DepthAndTrades {
                                                     exchange,
                                                     ticker,
@@ -530,7 +835,7 @@ impl Dashboard {
                                                     if let pane::Content::Kline(chart, _) =
                                                         &mut pane_state.content
                                                     {
-                                                        chart.set_tick_basis(interval);
+                                                        chart.set_trade_basis(new_basis);
                                                     }
                                                 }
                                             }
@@ -572,6 +877,319 @@ impl Dashboard {
                         }
                     }
                 }
+                pane::Message::ExportSnapshot(pane) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::Heatmap(chart, _) = &state.content {
+                            let toast = match chart.snapshot().map(|snapshot| snapshot.save()) {
+                                Some(Ok(file_name)) => {
+                                    Toast::info(format!("Saved snapshot {file_name}"))
+                                }
+                                Some(Err(err)) => Toast::error(err.to_string()),
+                                None => Toast::warn("Nothing to export yet"),
+                            };
+                            state.notifications.push(toast);
+                        }
+                    }
+                }
+                pane::Message::ExportTrades(pane) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::TimeAndSales(panel) = &state.content {
+                            let label = state
+                                .settings
+                                .ticker_info
+                                .map_or("trades".to_string(), |info| {
+                                    info.ticker.display_symbol_and_type().0
+                                });
+
+                            let toast = match data::chart::timeandsales::export_csv(
+                                panel.selected_trades(),
+                                &label,
+                            ) {
+                                Ok(path) => Toast::info(format!("Saved {}", path.display())),
+                                Err(err) => Toast::error(format!("Failed to export trades: {err}")),
+                            };
+                            state.notifications.push(toast);
+                        }
+                    }
+                }
+                pane::Message::ImbalancePresetSelected(pane, preset) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::Kline(chart, _) = &mut state.content {
+                            if let Some(mut studies) = chart.studies() {
+                                studies.retain(|study| {
+                                    !matches!(
+                                        study,
+                                        data::chart::kline::FootprintStudy::Imbalance { .. }
+                                    )
+                                });
+                                studies.push(preset.study());
+                                chart.set_studies(studies);
+                            }
+                        }
+                    }
+                }
+                pane::Message::SaveImbalancePreset(pane) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::Kline(chart, _) = &state.content {
+                            let imbalance = chart.studies().and_then(|studies| {
+                                studies.into_iter().find_map(|study| match study {
+                                    data::chart::kline::FootprintStudy::Imbalance {
+                                        threshold,
+                                        color_scale,
+                                        ignore_zeros,
+                                    } => Some(data::chart::kline::ImbalancePreset::new(
+                                        threshold,
+                                        color_scale,
+                                        ignore_zeros,
+                                    )),
+                                    _ => None,
+                                })
+                            });
+
+                            if let Some(preset) = imbalance {
+                                return (Task::none(), Some(Event::ImbalancePresetSaved(preset)));
+                            }
+                        }
+                    }
+                }
+                pane::Message::CopyStudies(pane) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        match state
+                            .content
+                            .study_clipboard()
+                            .map(|copied| copied.to_clipboard())
+                        {
+                            Some(Ok(contents)) => {
+                                state.notifications.push(Toast::info("Copied studies"));
+                                return (iced::clipboard::write(contents), None);
+                            }
+                            Some(Err(err)) => state
+                                .notifications
+                                .push(Toast::error(format!("Failed to copy studies: {err}"))),
+                            None => state
+                                .notifications
+                                .push(Toast::warn("This chart has no studies to copy")),
+                        }
+                    }
+                }
+                pane::Message::PasteStudies(pane) => {
+                    return (
+                        iced::clipboard::read().map(move |contents| {
+                            Message::Pane(window, pane::Message::StudiesPasted(pane, contents))
+                        }),
+                        None,
+                    );
+                }
+                pane::Message::StudiesPasted(pane, contents) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        let copied = contents
+                            .as_deref()
+                            .and_then(data::chart::StudyClipboard::from_clipboard);
+
+                        match copied {
+                            Some(copied) => {
+                                if !state.content.paste_studies(copied) {
+                                    state.notifications.push(Toast::warn(
+                                        "Copied studies belong to a different kind of chart",
+                                    ));
+                                }
+                            }
+                            None => state
+                                .notifications
+                                .push(Toast::warn("Clipboard has no copied studies")),
+                        }
+                    }
+                }
+                pane::Message::ReplayRequested(pane) => {
+                    if let Some(stream) = self
+                        .get_pane(main_window.id, window, pane)
+                        .and_then(pane::State::depth_stream)
+                    {
+                        return (Task::none(), Some(Event::ReplayRequested(stream)));
+                    }
+                }
+                pane::Message::RetryFetch(pane) => {
+                    let Some(state) = self.get_mut_pane(main_window.id, window, pane) else {
+                        return (Task::none(), None);
+                    };
+                    let pane::Status::TimedOut(info) = state.status else {
+                        return (Task::none(), None);
+                    };
+                    state.status = pane::Status::Ready;
+
+                    // later ranges get requested again on the next tick by the chart itself,
+                    // only the initial klines need a fetch of their own
+                    let needs_klines = matches!(info, pane::InfoType::FetchingKlines)
+                        && matches!(&state.content, pane::Content::Kline(chart, _) if chart.is_empty());
+
+                    if needs_klines {
+                        let (pane_id, streams) = (state.unique_id(), state.streams.clone());
+                        return (
+                            self.kline_init_tasks(main_window.id, pane_id, &streams),
+                            None,
+                        );
+                    }
+                }
+                pane::Message::ToggleAutoSnapshot(pane, is_enabled) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.settings.auto_snapshot = is_enabled;
+                    }
+                }
+                pane::Message::ToggleExportWatermark(pane, is_enabled) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.settings.watermark_exports = is_enabled;
+                    }
+                }
+                pane::Message::BarReplayChanged(pane, cutoff) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::Kline(chart, _) = &mut state.content {
+                            chart.set_replay_cutoff(cutoff);
+                        }
+                    }
+                }
+                pane::Message::StepBarReplay(pane, bars) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if let pane::Content::Kline(chart, _) = &mut state.content {
+                            chart.step_replay(bars);
+                        }
+                    }
+                }
+                pane::Message::ToggleCleanMode(pane) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.toggle_clean_mode();
+                    }
+                }
+                pane::Message::ToggleCleanAxes(pane, is_enabled) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.settings.clean_hides_axes = is_enabled;
+                    }
+                }
+                pane::Message::ExportImage(pane, target) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        let watermark = state.export_watermark();
+                        state.modal = None;
+
+                        // the crosshair is hidden and the watermark drawn on the frame captured
+                        if state.content.set_export(Some(watermark)).is_some() {
+                            return (
+                                iced::window::screenshot(window).map(move |screenshot| {
+                                    Message::PaneCaptured(window, pane, target, screenshot)
+                                }),
+                                None,
+                            );
+                        }
+                    }
+                }
+                pane::Message::FailoverSelected(pane, exchange) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.settings.failover = exchange;
+                    }
+                }
+                pane::Message::KlineSourceSelected(pane, source) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if state.settings.kline_source == source {
+                            return (Task::none(), None);
+                        }
+                        state.settings.kline_source = source;
+
+                        // klines of the other price are a different stream, fetched anew
+                        if let Some(ticker_info) = state.settings.ticker_info {
+                            let task = self.init_pane(
+                                main_window.id,
+                                window,
+                                pane,
+                                ticker_info,
+                                "candlestick",
+                            );
+                            return (self.refresh_streams(main_window.id).chain(task), None);
+                        }
+                    }
+                }
+                pane::Message::TagInputChanged(pane, input) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.tag_input = input;
+                    }
+                }
+                pane::Message::AddTag(pane, label) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        if label.is_empty() {
+                            return (Task::none(), None);
+                        }
+
+                        let tag = state
+                            .stream_pair()
+                            .zip(state.content.visible_timerange())
+                            .map(|((exchange, ticker), (start_time, end_time))| {
+                                data::journal::Tag {
+                                    exchange,
+                                    ticker,
+                                    start_time,
+                                    end_time,
+                                    label,
+                                }
+                            });
+
+                        state.modal = None;
+                        state.tag_input.clear();
+
+                        return (Task::none(), tag.map(Event::TagAdded));
+                    }
+                }
+                pane::Message::GoToInputChanged(pane, input) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.goto_input = input;
+                    }
+                }
+                pane::Message::GoToTime(pane, time) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        match &mut state.content {
+                            pane::Content::Heatmap(chart, _) => {
+                                // depth history can't be backfilled, only what was recorded
+                                if chart.timerange().is_none_or(|(earliest, latest)| {
+                                    time < earliest || time > latest
+                                }) {
+                                    state.notifications.push(Toast::warn(
+                                        "Heatmap has no data recorded at that time",
+                                    ));
+                                    return (Task::none(), None);
+                                }
+                                chart::update(chart, chart::Message::ScrollToTime(time));
+                            }
+                            pane::Content::Kline(chart, _) => {
+                                chart::update(chart, chart::Message::ScrollToTime(time));
+                            }
+                            _ => {}
+                        }
+
+                        state.modal = None;
+
+                        // request any klines/trades missing around the new position right away
+                        if let Some(pane::Action::Chart(chart::Action::FetchRequested(
+                            req_id,
+                            fetch,
+                        ))) = state.invalidate(Instant::now())
+                        {
+                            return (request_fetch(state, *layout_id, req_id, fetch), None);
+                        }
+                    }
+                }
+                pane::Message::ListSnapshots(pane) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.snapshots = data::chart::heatmap::snapshot::list();
+                        state.modal = Some(pane::Modal::Snapshots);
+                    }
+                }
+                pane::Message::OpenSnapshot(pane, file_name) => {
+                    if let Some(state) = self.get_mut_pane(main_window.id, window, pane) {
+                        state.modal = None;
+
+                        if let Err(err) = state.open_snapshot(file_name) {
+                            state.notifications.push(Toast::error(err.to_string()));
+                        }
+                    }
+
+                    return (self.refresh_streams(main_window.id), None);
+                }
             },
             Message::ChangePaneStatus(pane_id, status) => {
                 if let Some(pane_state) = self.get_mut_pane_state_by_uuid(main_window.id, pane_id) {
@@ -594,9 +1212,63 @@ impl Dashboard {
                     }),
                 );
             }
+            Message::RequestKlines {
+                pane_id,
+                stream,
+                req_id,
+                range: (from, to),
+            } => {
+                if let Some(klines) = self.kline_store.klines_in_range(&stream, from, to) {
+                    return (
+                        Task::done(Message::DistributeFetchedData {
+                            layout_id: *layout_id,
+                            pane_id,
+                            stream,
+                            data: FetchedData::Klines {
+                                data: klines,
+                                req_id: Some(req_id),
+                            },
+                        }),
+                        None,
+                    );
+                }
+
+                // the same range of the same stream is already being fetched for another pane
+                if !self
+                    .kline_fetches
+                    .join((stream, Some((from, to))), (pane_id, Some(req_id)))
+                {
+                    return (
+                        Task::done(Message::ChangePaneStatus(
+                            pane_id,
+                            pane::Status::Loading(pane::InfoType::FetchingKlines),
+                        )),
+                        None,
+                    );
+                }
+
+                return (
+                    kline_fetch_task(*layout_id, pane_id, stream, Some(req_id), Some((from, to))),
+                    None,
+                );
+            }
             Message::Notification(toast) => {
                 return (Task::none(), Some(Event::Notification(toast)));
             }
+            Message::BackfillRequested(stream) => {
+                return (Task::none(), Some(Event::BackfillRequested(stream)));
+            }
+            Message::RecordingLoaded(stream, result) => match result {
+                Ok(points) => self.insert_recording(&stream, &points, main_window.id),
+                Err(err) => {
+                    return (
+                        Task::none(),
+                        Some(Event::Notification(Toast::error(format!(
+                            "Failed to restore recording: {err}"
+                        )))),
+                    );
+                }
+            },
         }
 
         (Task::none(), None)
@@ -740,6 +1412,19 @@ impl Dashboard {
             .map(|(_, _, state)| state)
     }
 
+    /// One line per pane with its content and ticker, for crash reports
+    pub fn pane_summary(&self, main_window: window::Id) -> Vec<String> {
+        self.iter_all_panes(main_window)
+            .map(|(_, _, state)| match state.stream_pair() {
+                Some((exchange, ticker)) => {
+                    let (symbol, _) = ticker.display_symbol_and_type();
+                    format!("{} {symbol} ({exchange})", state.content)
+                }
+                None => state.content.to_string(),
+            })
+            .collect()
+    }
+
     fn iter_all_panes(
         &self,
         main_window: window::Id,
@@ -752,13 +1437,178 @@ impl Dashboard {
             }))
     }
 
-    fn iter_all_panes_mut(
-        &mut self,
+    /// Indicators the focused pane's chart can toggle, named the way the pane takes them
+    pub fn focused_indicators(
+        &self,
         main_window: window::Id,
-    ) -> impl Iterator<Item = (window::Id, pane_grid::Pane, &mut pane::State)> {
-        self.panes
-            .iter_mut()
-            .map(move |(pane, state)| (main_window, *pane, state))
+    ) -> Option<(window::Id, pane_grid::Pane, Vec<String>)> {
+        let (window, pane) = self.focus?;
+        let state = self.get_pane(main_window, window, pane)?;
+        let market = state.settings.ticker_info?.market_type();
+
+        let indicators = match &state.content {
+            pane::Content::Heatmap(_, _) => HeatmapIndicator::for_market(market)
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            pane::Content::Kline(_, _) => KlineIndicator::for_market(market)
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            _ => return None,
+        };
+
+        Some((window, pane, indicators))
+    }
+
+    /// Turns an indicator on or off on every kline chart of every window whose market has it,
+    /// returns how many charts changed
+    pub fn set_kline_indicator_on_all(
+        &mut self,
+        main_window: window::Id,
+        indicator: KlineIndicator,
+        is_enabled: bool,
+    ) -> usize {
+        let mut changed = 0;
+
+        for (_, _, state) in self.iter_all_panes_mut(main_window) {
+            let Some(market) = state.settings.ticker_info.map(|info| info.market_type()) else {
+                continue;
+            };
+            let pane::Content::Kline(_, indicators) = &state.content else {
+                continue;
+            };
+
+            if KlineIndicator::for_market(market).contains(&indicator)
+                && indicators.contains(&indicator) != is_enabled
+            {
+                state.content.toggle_indicator(&indicator.to_string());
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+
+    /// Tickers with a kline chart open in any window, a composite profile can be built of
+    pub fn profile_tickers(&self, main_window: window::Id) -> Vec<TickerInfo> {
+        let mut tickers = vec![];
+
+        for (_, _, state) in self.iter_all_panes(main_window) {
+            if let (pane::Content::Kline(chart, _), Some(info)) =
+                (&state.content, state.settings.ticker_info)
+            {
+                if !matches!(chart.kind(), data::chart::KlineChartKind::Spread(_))
+                    && !tickers.contains(&info)
+                {
+                    tickers.push(info);
+                }
+            }
+        }
+
+        tickers
+    }
+
+    /// Volume profile of a ticker merged from the bars of all its kline charts. Where their
+    /// spans overlap, only the most detailed chart counts: the ones with footprints first,
+    /// then the smaller timeframes.
+    pub fn composite_profile(
+        &self,
+        ticker: Ticker,
+        main_window: window::Id,
+    ) -> Option<modal::profile::CompositeProfile> {
+        let mut sources = self
+            .iter_all_panes(main_window)
+            .filter_map(|(_, _, state)| {
+                state
+                    .settings
+                    .ticker_info
+                    .filter(|info| info.ticker == ticker)?;
+
+                match &state.content {
+                    pane::Content::Kline(chart, _)
+                        if !matches!(chart.kind(), data::chart::KlineChartKind::Spread(_)) =>
+                    {
+                        let bars = chart.bars();
+                        let has_trades = bars
+                            .iter()
+                            .any(|(_, footprint)| !footprint.trades.is_empty());
+                        // tick based bars are at least as detailed as any timeframe
+                        let interval = match state.settings.selected_basis {
+                            Some(Basis::Time(timeframe)) => timeframe.to_milliseconds(),
+                            _ => 0,
+                        };
+
+                        Some((has_trades, interval, chart.tick_size(), bars))
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        sources.sort_by_key(|(has_trades, interval, _, _)| (!has_trades, *interval));
+
+        // levels of the coarsest chart, so every chart's levels line up with them
+        let tick_size = sources
+            .iter()
+            .map(|(_, _, tick_size, _)| *tick_size)
+            .fold(0.0, f32::max);
+
+        if tick_size <= 0.0 {
+            return None;
+        }
+
+        let mut profile = data::chart::kline::VolumeProfile::default();
+        let mut covered: Vec<(u64, u64)> = vec![];
+
+        for (_, interval, _, bars) in &sources {
+            let mut span: Option<(u64, u64)> = None;
+
+            for (kline, footprint) in bars {
+                if covered
+                    .iter()
+                    .any(|(start, end)| kline.time >= *start && kline.time < *end)
+                {
+                    continue;
+                }
+
+                if footprint.trades.is_empty() {
+                    profile.add_kline(kline, tick_size);
+                } else {
+                    profile.add_footprint(footprint, tick_size);
+                }
+
+                let end = kline.time + (*interval).max(1);
+                span = Some(span.map_or((kline.time, end), |(start, last)| {
+                    (start.min(kline.time), last.max(end))
+                }));
+            }
+
+            covered.extend(span);
+        }
+
+        let span = covered.iter().fold(None, |span, (start, end)| {
+            Some(span.map_or((*start, *end), |(first, last): (u64, u64)| {
+                (first.min(*start), last.max(*end))
+            }))
+        })?;
+
+        Some(modal::profile::CompositeProfile {
+            ticker,
+            profile,
+            tick_size,
+            charts: sources.len(),
+            span,
+        })
+    }
+
+    fn iter_all_panes_mut(
+        &mut self,
+        main_window: window::Id,
+    ) -> impl Iterator<Item = (window::Id, pane_grid::Pane, &mut pane::State)> {
+        self.panes
+            .iter_mut()
+            .map(move |(pane, state)| (main_window, *pane, state))
             .chain(self.popout.iter_mut().flat_map(|(window_id, (panes, _))| {
                 panes
                     .iter_mut()
@@ -770,6 +1620,8 @@ impl Dashboard {
         &'a self,
         main_window: &'a Window,
         timezone: UserTimezone,
+        imbalance_presets: &'a ImbalancePresets,
+        chart_themes: &'a ChartThemes,
     ) -> Element<'a, Message> {
         let pane_grid: Element<_> = PaneGrid::new(&self.panes, |id, pane, maximized| {
             let is_focused = self.focus == Some((main_window.id, id));
@@ -781,6 +1633,10 @@ impl Dashboard {
                 main_window.id,
                 main_window,
                 timezone,
+                self.streams_paused,
+                imbalance_presets,
+                chart_themes,
+                self.funding_of(pane),
             )
         })
         .min_size(240)
@@ -794,11 +1650,18 @@ impl Dashboard {
         pane_grid.map(move |message| Message::Pane(main_window.id, message))
     }
 
+    fn funding_of(&self, pane: &pane::State) -> Option<FundingRate> {
+        pane.stream_pair()
+            .and_then(|(_, ticker)| self.funding_rates.get(&ticker).copied())
+    }
+
     pub fn view_window<'a>(
         &'a self,
         window: window::Id,
         main_window: &'a Window,
         timezone: UserTimezone,
+        imbalance_presets: &'a ImbalancePresets,
+        chart_themes: &'a ChartThemes,
     ) -> Element<'a, Message> {
         if let Some((state, _)) = self.popout.get(&window) {
             let content = container(
@@ -812,6 +1675,10 @@ impl Dashboard {
                         window,
                         main_window,
                         timezone,
+                        self.streams_paused,
+                        imbalance_presets,
+                        chart_themes,
+                        self.funding_of(pane),
                     )
                 })
                 .on_click(pane::Message::PaneClicked),
@@ -843,6 +1710,22 @@ impl Dashboard {
         false
     }
 
+    fn set_pane_error(
+        &mut self,
+        main_window: window::Id,
+        pane_id: uuid::Uuid,
+        err: &DashboardError,
+    ) {
+        if let Some(state) = self.get_mut_pane_state_by_uuid(main_window, pane_id) {
+            // what the exchange said stays in the title bar, until data comes in
+            state.status = match err {
+                DashboardError::Exchange(msg) => pane::Status::Stale(msg.clone()),
+                _ => pane::Status::Ready,
+            };
+            state.notifications.push(Toast::error(err.to_string()));
+        }
+    }
+
     fn handle_error(
         &mut self,
         pane_id: Option<uuid::Uuid>,
@@ -875,11 +1758,10 @@ impl Dashboard {
                     let pane_id = state.unique_id();
                     self.streams.extend(streams.iter());
 
-                    for stream in &streams {
-                        if let StreamKind::Kline { .. } = stream {
-                            return kline_fetch_task(self.layout_id, pane_id, *stream, None, None);
-                        }
-                    }
+                    return Task::batch([
+                        self.kline_init_tasks(main_window, pane_id, &streams),
+                        heatmap_backfill(content, &streams),
+                    ]);
                 }
                 Err(err) => {
                     state.status = pane::Status::Ready;
@@ -909,17 +1791,10 @@ impl Dashboard {
                         let pane_id = state.unique_id();
                         self.streams.extend(streams.iter());
 
-                        for stream in &streams {
-                            if let StreamKind::Kline { .. } = stream {
-                                return kline_fetch_task(
-                                    self.layout_id,
-                                    pane_id,
-                                    *stream,
-                                    None,
-                                    None,
-                                );
-                            }
-                        }
+                        return Task::batch([
+                            self.kline_init_tasks(main_window, pane_id, &streams),
+                            heatmap_backfill(content, &streams),
+                        ]);
                     }
                     Err(err) => {
                         state.status = pane::Status::Ready;
@@ -935,6 +1810,105 @@ impl Dashboard {
         )))
     }
 
+    /// Opens a spread chart of the ticker against `leg` in the focused pane
+    pub fn init_focused_spread(
+        &mut self,
+        main_window: window::Id,
+        ticker_info: TickerInfo,
+        leg: TickerInfo,
+    ) -> Task<Message> {
+        if let Some((window, pane)) = self.focus {
+            if let Some(state) = self.get_mut_pane(main_window, window, pane) {
+                state.settings.spread_leg = Some(leg);
+            }
+        }
+
+        self.init_focused_pane(main_window, ticker_info, "spread")
+    }
+
+    /// Loads a watchlist's ticker into the panes linked with it, or into a candlestick chart split
+    /// off the watchlist if nothing is linked to it
+    fn open_from_watchlist(
+        &mut self,
+        main_window: window::Id,
+        window: window::Id,
+        pane: pane_grid::Pane,
+        ticker_info: TickerInfo,
+    ) -> Task<Message> {
+        let link_group = self
+            .get_pane(main_window, window, pane)
+            .and_then(|state| state.link_group);
+
+        let linked: Vec<(window::Id, pane_grid::Pane, String)> = self
+            .iter_all_panes_mut(main_window)
+            .filter(|(_, _, state)| {
+                link_group.is_some()
+                    && state.link_group == link_group
+                    && !matches!(
+                        state.content,
+                        pane::Content::Watchlist(_)
+                            | pane::Content::WhaleTape(_)
+                            | pane::Content::Starter
+                    )
+            })
+            .map(|(window, pane, state)| (window, pane, state.content.identifier_str()))
+            .collect();
+
+        if !linked.is_empty() {
+            let tasks: Vec<Task<Message>> = linked
+                .iter()
+                .map(|(window, pane, content)| {
+                    self.init_pane(main_window, *window, *pane, ticker_info, content)
+                })
+                .collect();
+
+            return Task::batch(tasks);
+        }
+
+        let panes = if window == main_window {
+            Some(&mut self.panes)
+        } else {
+            self.popout.get_mut(&window).map(|(panes, _)| panes)
+        };
+
+        let Some((new_pane, _)) = panes
+            .and_then(|panes| panes.split(pane_grid::Axis::Vertical, pane, pane::State::new()))
+        else {
+            return Task::none();
+        };
+        self.focus = Some((window, new_pane));
+
+        self.init_pane(main_window, window, new_pane, ticker_info, "candlestick")
+    }
+
+    /// Steps the bar replay of the focused pane, for the hotkeys
+    pub fn step_focused_bar_replay(&mut self, main_window: window::Id, bars: i64) {
+        let Some((window, pane)) = self.focus else {
+            return;
+        };
+
+        if let Some(state) = self.get_mut_pane(main_window, window, pane) {
+            if let pane::Content::Kline(chart, _) = &mut state.content {
+                chart.step_replay(bars);
+            }
+        }
+    }
+
+    /// Clean mode of the focused pane, for the hotkey
+    pub fn toggle_focused_clean_mode(&mut self, main_window: window::Id) -> Task<Message> {
+        let Some((window, pane)) = self.focus else {
+            return Task::done(Message::Notification(Toast::warn(
+                "No focused pane found".to_string(),
+            )));
+        };
+
+        if let Some(state) = self.get_mut_pane(main_window, window, pane) {
+            state.toggle_clean_mode();
+        }
+
+        Task::none()
+    }
+
     pub fn switch_tickers_in_group(
         &mut self,
         main_window: window::Id,
@@ -981,13 +1955,140 @@ impl Dashboard {
         }
     }
 
-    pub fn toggle_trade_fetch(&mut self, is_enabled: bool, main_window: &Window) {
-        exchange::fetcher::toggle_trade_fetch(is_enabled);
+    /// Moves the panes of a down `exchange` that have a fallback market onto the same symbol
+    /// there. Their primary streams stay subscribed, so the first data on them switches back
+    pub fn fail_over(
+        &mut self,
+        exchange: Exchange,
+        fallback_info: impl Fn(Exchange, Ticker) -> Option<TickerInfo>,
+        main_window: window::Id,
+    ) -> Task<Message> {
+        let targets = self
+            .iter_all_panes(main_window)
+            .filter(|(_, _, state)| state.failover.is_none())
+            .filter_map(|(window, pane, state)| {
+                let ticker_info = state
+                    .settings
+                    .ticker_info
+                    .filter(|info| info.exchange() == exchange)?;
+                let fallback = state.settings.failover?;
+
+                Some((
+                    window,
+                    pane,
+                    ticker_info,
+                    fallback,
+                    state.content.identifier_str(),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let mut tasks = vec![];
+
+        for (window, pane, ticker_info, fallback, content) in targets {
+            let (symbol, _) = ticker_info.ticker.to_full_symbol_and_type();
+            let fallback_ticker = fallback_info(fallback, Ticker::new(&symbol, fallback));
+
+            let Some(state) = self.get_mut_pane(main_window, window, pane) else {
+                continue;
+            };
+
+            match fallback_ticker {
+                Some(fallback_ticker) => {
+                    log::warn!("{exchange} is down, failing a pane over to {fallback}");
+
+                    state.failover = Some(pane::Failover {
+                        ticker_info,
+                        streams: state.streams.clone(),
+                    });
+                    state.notifications.push(Toast::warn(format!(
+                        "{exchange} is down, streaming from {fallback} until it's back"
+                    )));
+
+                    tasks.push(self.init_pane(
+                        main_window,
+                        window,
+                        pane,
+                        fallback_ticker,
+                        &content,
+                    ));
+                }
+                None => {
+                    state.notifications.push(Toast::warn(format!(
+                        "{exchange} is down, but {fallback} doesn't list {symbol}"
+                    )));
+                }
+            }
+        }
+
+        Task::batch(tasks)
+    }
+
+    /// Switches the failed over panes watching `stream` back to it, `None` if there are none
+    fn restore_failed_over(
+        &mut self,
+        stream: &StreamKind,
+        main_window: window::Id,
+    ) -> Option<Task<Message>> {
+        let targets = self
+            .iter_all_panes_mut(main_window)
+            .filter_map(|(window, pane, state)| {
+                if !state
+                    .failover
+                    .as_ref()
+                    .is_some_and(|failover| failover.streams.contains(stream))
+                {
+                    return None;
+                }
+
+                let failover = state.failover.take()?;
+                let exchange = failover.ticker_info.exchange();
+
+                log::info!("{exchange} is back, restoring a failed over pane");
+                state.notifications.push(Toast::info(format!(
+                    "{exchange} is back, switched back to it"
+                )));
+
+                Some((
+                    window,
+                    pane,
+                    failover.ticker_info,
+                    state.content.identifier_str(),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        if targets.is_empty() {
+            return None;
+        }
+
+        let tasks = targets
+            .into_iter()
+            .map(|(window, pane, ticker_info, content)| {
+                self.init_pane(main_window, window, pane, ticker_info, &content)
+            })
+            .collect::<Vec<_>>();
+
+        Some(Task::batch(tasks).chain(self.refresh_streams(main_window)))
+    }
+
+    pub fn toggle_trade_fetch(
+        &mut self,
+        exchange: Exchange,
+        is_enabled: bool,
+        main_window: &Window,
+    ) {
+        exchange::fetcher::toggle_trade_fetch(exchange, is_enabled);
 
         self.iter_all_panes_mut(main_window.id)
+            .filter(|(_, _, state)| {
+                state
+                    .stream_pair()
+                    .is_some_and(|(pane_exchange, _)| pane_exchange == exchange)
+            })
             .for_each(|(_, _, state)| {
                 if let pane::Content::Kline(chart, _) = &mut state.content {
-                    if matches!(chart.kind(), data::chart::KlineChartKind::Footprint { .. }) {
+                    if chart.kind().is_order_flow() {
                         chart.reset_request_handler();
 
                         if !is_enabled {
@@ -1030,12 +2131,32 @@ impl Dashboard {
                 }
             }
             FetchedData::Klines { data, req_id } => {
-                if let Some(pane_state) = self.get_mut_pane_state_by_uuid(main_window, pane_id) {
-                    pane_state.status = pane::Status::Ready;
+                self.kline_store.insert(&stream_type, &data);
+
+                let is_shared =
+                    req_id.is_none() && self.kline_store.finish_shared_fetch(&stream_type);
+                self.insert_spread_klines(main_window, pane_id, &stream_type, req_id, is_shared);
 
+                if is_shared {
                     if let StreamKind::Kline { timeframe, .. } = stream_type {
-                        pane_state.insert_klines_vec(req_id, timeframe, &data);
+                        self.iter_all_panes_mut(main_window)
+                            .filter(|(_, _, pane_state)| {
+                                pane_state.matches_stream(&stream_type)
+                                    && pane_state.spread_legs().is_none()
+                            })
+                            .for_each(|(_, _, pane_state)| {
+                                pane_state.status = pane::Status::Ready;
+                                pane_state.insert_klines_vec(None, timeframe, &data);
+                            });
                     }
+                } else {
+                    self.insert_pane_klines(main_window, pane_id, &stream_type, req_id, &data);
+                }
+
+                // panes that asked for the same range while it was in flight get it too
+                for (pane_id, req_id) in self.kline_fetches.finish(&(pane_id, req_id)) {
+                    self.insert_spread_klines(main_window, pane_id, &stream_type, req_id, false);
+                    self.insert_pane_klines(main_window, pane_id, &stream_type, req_id, &data);
                 }
             }
             FetchedData::OI { data, req_id } => {
@@ -1052,6 +2173,66 @@ impl Dashboard {
         Task::none()
     }
 
+    /// Klines fetched for a single pane, spreads take theirs from the combined legs instead
+    fn insert_pane_klines(
+        &mut self,
+        main_window: window::Id,
+        pane_id: uuid::Uuid,
+        stream: &StreamKind,
+        req_id: Option<uuid::Uuid>,
+        klines: &[Kline],
+    ) {
+        if let Some(pane_state) = self
+            .get_mut_pane_state_by_uuid(main_window, pane_id)
+            .filter(|pane_state| pane_state.spread_legs().is_none())
+        {
+            pane_state.status = pane::Status::Ready;
+
+            if let StreamKind::Kline { timeframe, .. } = stream {
+                pane_state.insert_klines_vec(req_id, *timeframe, klines);
+            }
+        }
+    }
+
+    /// Recombines the legs of the spread panes on `stream` once either leg got klines. Older
+    /// history another pane fetched leaves them be, a rebuild would only reset their view.
+    fn insert_spread_klines(
+        &mut self,
+        main_window: window::Id,
+        pane_id: uuid::Uuid,
+        stream: &StreamKind,
+        req_id: Option<uuid::Uuid>,
+        is_shared: bool,
+    ) {
+        let targets = self
+            .iter_all_panes(main_window)
+            .filter(|(_, _, state)| is_shared || state.unique_id() == pane_id)
+            .filter(|(_, _, state)| state.matches_stream(stream))
+            .filter_map(|(_, _, state)| {
+                let (first, second, mode) = state.spread_legs()?;
+                Some((state.unique_id(), first, second, mode))
+            })
+            .collect::<Vec<_>>();
+
+        for (uid, first, second, mode) in targets {
+            let StreamKind::Kline { timeframe, .. } = first else {
+                continue;
+            };
+
+            let klines = self
+                .kline_store
+                .paired(&first, &second)
+                .iter()
+                .filter_map(|(first, second)| mode.combine(first, second))
+                .collect::<Vec<_>>();
+
+            if let Some(state) = self.get_mut_pane_state_by_uuid(main_window, uid) {
+                state.status = pane::Status::Ready;
+                state.insert_klines_vec(req_id, timeframe, &klines);
+            }
+        }
+    }
+
     fn insert_fetched_trades(
         &mut self,
         main_window: window::Id,
@@ -1099,13 +2280,44 @@ impl Dashboard {
         kline: &Kline,
         main_window: window::Id,
     ) -> Task<Message> {
+        if let Some(task) = self.restore_failed_over(stream, main_window) {
+            return task;
+        }
+
         let mut found_match = false;
 
+        self.kline_store.update_latest(stream, kline);
+
+        let (_, ticker) = stream.exchange_and_ticker();
+        if let Some(levels) = self.day_levels.get_mut(&ticker) {
+            if kline.time >= levels.session_start {
+                levels.update(kline.high);
+                levels.update(kline.low);
+            }
+        }
+
+        let spread_klines = self
+            .iter_all_panes(main_window)
+            .filter(|(_, _, state)| state.matches_stream(stream))
+            .filter_map(|(_, _, state)| {
+                let (first, second, mode) = state.spread_legs()?;
+                let (first, second) = self.kline_store.pair_at(&first, &second, kline.time)?;
+                Some((state.unique_id(), mode.combine(&first, &second)?))
+            })
+            .collect::<HashMap<_, _>>();
+
         self.iter_all_panes_mut(main_window)
             .for_each(|(_, _, pane_state)| {
                 if pane_state.matches_stream(stream) {
+                    let is_spread = pane_state.spread_legs().is_some();
+                    let uid = pane_state.unique_id();
+
                     if let pane::Content::Kline(chart, _) = &mut pane_state.content {
-                        chart.update_latest_kline(kline);
+                        if !is_spread {
+                            chart.update_latest_kline(kline);
+                        } else if let Some(combined) = spread_klines.get(&uid) {
+                            chart.update_latest_kline(combined);
+                        }
                     }
 
                     found_match = true;
@@ -1120,75 +2332,355 @@ impl Dashboard {
         }
     }
 
-    pub fn update_depth_and_trades(
+    /// Flags the panes of a depth stream while it runs on REST snapshots, clearing the flag
+    /// once `reason` is `None` again
+    pub fn set_depth_degraded(
         &mut self,
         stream: &StreamKind,
-        depth_update_t: u64,
-        depth: &Depth,
-        trades_buffer: &[Trade],
+        reason: Option<&str>,
         main_window: window::Id,
-    ) -> Task<Message> {
-        let mut found_match = false;
+    ) {
+        let refresh_secs = exchange::depth::depth_fallback().refresh_secs;
 
         self.iter_all_panes_mut(main_window)
-            .for_each(|(_, _, pane_state)| {
-                if pane_state.matches_stream(stream) {
-                    match &mut pane_state.content {
-                        pane::Content::Heatmap(chart, _) => {
-                            chart.insert_datapoint(trades_buffer, depth_update_t, depth);
-                        }
-                        pane::Content::Kline(chart, _) => {
-                            chart.insert_trades_buffer(trades_buffer);
-                        }
-                        pane::Content::TimeAndSales(panel) => {
-                            panel.insert_buffer(trades_buffer);
-                        }
-                        _ => {
-                            log::error!("No chart found for the stream: {stream:?}");
-                        }
+            .filter(|(_, _, state)| state.matches_stream(stream))
+            .for_each(|(_, _, state)| match reason {
+                Some(reason) => {
+                    log::debug!("Depth of {stream:?} degraded: {reason}");
+                    state.status = pane::Status::Stale(format!(
+                        "Degraded: depth refreshes every {refresh_secs}s"
+                    ));
+                }
+                None => {
+                    if matches!(state.status, pane::Status::Stale(_)) {
+                        state.status = pane::Status::Ready;
                     }
-                    found_match = true;
                 }
             });
-
-        if found_match {
-            Task::none()
-        } else {
-            log::debug!("No matching pane found for the stream: {stream:?}");
-            self.refresh_streams(main_window)
-        }
     }
 
-    pub fn invalidate_all_panes(&mut self, main_window: window::Id) {
+    /// Shows on the panes of a dropped stream when it's tried again, see
+    /// [`exchange::Event::Reconnecting`]
+    pub fn set_reconnecting(
+        &mut self,
+        stream: &StreamKind,
+        delay: Option<std::time::Duration>,
+        main_window: window::Id,
+    ) {
         self.iter_all_panes_mut(main_window)
+            .filter(|(_, _, state)| state.matches_stream(stream))
             .for_each(|(_, _, state)| {
-                let _ = state.invalidate(Instant::now());
+                state.status =
+                    pane::Status::Reconnecting(delay.map(|delay| delay.as_secs().max(1)));
             });
     }
 
-    pub fn tick(&mut self, now: Instant, main_window: window::Id) -> Task<Message> {
-        let mut tasks = vec![];
-        let layout_id = self.layout_id;
+    /// Clears the reconnect waits of the exchange's panes once a stream of it is back up
+    pub fn clear_reconnecting(&mut self, exchange: Exchange, main_window: window::Id) {
+        self.iter_all_panes_mut(main_window)
+            .filter(|(_, _, state)| matches!(state.status, pane::Status::Reconnecting(_)))
+            .filter(|(_, _, state)| {
+                state
+                    .streams
+                    .iter()
+                    .any(|stream| stream.exchange_and_ticker().0 == exchange)
+            })
+            .for_each(|(_, _, state)| state.status = pane::Status::Ready);
+    }
 
+    /// Hands the price alert lines to the charts of their ticker, leaving out the ones that
+    /// don't plot its price
+    pub fn set_alert_lines(
+        &mut self,
+        lines: &[(SerTicker, chart::AlertLine)],
+        main_window: window::Id,
+    ) {
         self.iter_all_panes_mut(main_window)
-            .for_each(|(_, _, state)| match state.tick(now) {
-                Some(pane::Action::Chart(action)) => match action {
-                    chart::Action::ErrorOccurred(err) => {
-                        state.status = pane::Status::Ready;
-                        state.notifications.push(Toast::error(err.to_string()));
+            .for_each(|(_, _, state)| {
+                let ticker = state
+                    .stream_pair()
+                    .map(|(exchange, ticker)| SerTicker::from_parts(exchange, ticker));
+                let lines_of = |ticker: Option<&SerTicker>| {
+                    lines
+                        .iter()
+                        .filter(|(alert_ticker, _)| Some(alert_ticker) == ticker)
+                        .map(|(_, line)| *line)
+                        .collect::<Vec<_>>()
+                };
+
+                match &mut state.content {
+                    pane::Content::Heatmap(chart, _) => {
+                        chart::set_alert_lines(chart, lines_of(ticker.as_ref()));
                     }
-                    chart::Action::FetchRequested(req_id, fetch) => {
-                        tasks.push(request_fetch(state, layout_id, req_id, fetch));
+                    pane::Content::Kline(chart, _) => {
+                        let plots_price = !matches!(
+                            chart.kind(),
+                            data::chart::KlineChartKind::DeltaCandles
+                                | data::chart::KlineChartKind::Spread(_)
+                        );
+                        chart::set_alert_lines(
+                            chart,
+                            lines_of(ticker.as_ref().filter(|_| plots_price)),
+                        );
                     }
-                },
-                Some(pane::Action::Panel(_action)) => {}
-                None => {}
+                    _ => {}
+                }
+            });
+    }
+
+    pub fn update_liquidations(
+        &mut self,
+        stream: &StreamKind,
+        liquidations: &[Liquidation],
+        main_window: window::Id,
+    ) -> Task<Message> {
+        let mut found_match = false;
+
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, pane_state)| {
+                if pane_state.liquidation_stream().as_ref() == Some(stream) {
+                    match &mut pane_state.content {
+                        pane::Content::Heatmap(chart, _) => {
+                            chart.insert_liquidations(liquidations);
+                        }
+                        pane::Content::Kline(chart, _) => {
+                            chart.insert_liquidations(liquidations);
+                        }
+                        pane::Content::TimeAndSales(_)
+                        | pane::Content::DomLadder(_)
+                        | pane::Content::Watchlist(_)
+                        | pane::Content::WhaleTape(_)
+                        | pane::Content::Starter => {}
+                    }
+
+                    found_match = true;
+                }
+            });
+
+        if found_match {
+            Task::none()
+        } else {
+            log::debug!("{stream:?} stream had no matching panes - dropping");
+            self.refresh_streams(main_window)
+        }
+    }
+
+    pub fn update_depth_and_trades(
+        &mut self,
+        stream: &StreamKind,
+        depth_update_t: u64,
+        depth: &Depth,
+        trades_buffer: &[Trade],
+        main_window: window::Id,
+    ) -> Task<Message> {
+        if let Some(task) = self.restore_failed_over(stream, main_window) {
+            return task;
+        }
+
+        let mut found_match = false;
+
+        let (_, ticker) = stream.exchange_and_ticker();
+        if let Some(levels) = self.day_levels.get_mut(&ticker) {
+            for trade in trades_buffer {
+                levels.update(trade.price);
+            }
+        }
+
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, pane_state)| {
+                // streams nothing itself, it taps into whatever the other panes are streaming
+                if let pane::Content::WhaleTape(tape) = &mut pane_state.content {
+                    tape.insert_trades(stream, trades_buffer);
+                }
+
+                if pane_state.matches_stream(stream) {
+                    match &mut pane_state.content {
+                        pane::Content::Heatmap(chart, _) => {
+                            chart.insert_datapoint(trades_buffer, depth_update_t, depth);
+                        }
+                        pane::Content::Kline(chart, _) => {
+                            chart.insert_trades_buffer(trades_buffer);
+                        }
+                        pane::Content::TimeAndSales(panel) => {
+                            panel.insert_buffer(trades_buffer);
+                        }
+                        pane::Content::DomLadder(panel) => {
+                            panel.insert_datapoint(trades_buffer, depth);
+                        }
+                        _ => {
+                            log::error!("No chart found for the stream: {stream:?}");
+                        }
+                    }
+                    found_match = true;
+                }
+            });
+
+        if found_match {
+            Task::none()
+        } else {
+            log::debug!("No matching pane found for the stream: {stream:?}");
+            self.refresh_streams(main_window)
+        }
+    }
+
+    pub fn invalidate_all_panes(&mut self, main_window: window::Id) {
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, state)| {
+                let _ = state.invalidate(Instant::now());
+            });
+    }
+
+    pub fn tick(&mut self, now: Instant, main_window: window::Id) -> Task<Message> {
+        let mut tasks = vec![];
+        let mut wanted_levels = HashMap::new();
+        let mut perps = HashMap::new();
+        let layout_id = self.layout_id;
+
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, state)| {
+                if let Some(info) = state.check_fetch_timeout(now) {
+                    log::warn!("{info:?} timed out for pane {}", state.unique_id());
+                }
+
+                let pane_id = state.unique_id();
+                if let pane::Content::Watchlist(watchlist) = &mut state.content {
+                    tasks.extend(watchlist_fetch_tasks(pane_id, watchlist.due_fetches(now)));
+                }
+
+                if let Some((exchange, ticker)) = state.stream_pair() {
+                    if state.wants_day_levels() {
+                        wanted_levels.insert(ticker, exchange);
+                    }
+                    if ticker.is_perps() {
+                        perps.insert(ticker, exchange);
+                    }
+                }
+
+                match state.tick(now) {
+                    Some(pane::Action::Chart(action)) => match action {
+                        chart::Action::ErrorOccurred(err) => {
+                            state.status = pane::Status::Ready;
+                            state.notifications.push(Toast::error(err.to_string()));
+                        }
+                        chart::Action::FetchRequested(req_id, fetch) => {
+                            tasks.push(request_fetch(state, layout_id, req_id, fetch));
+                        }
+                    },
+                    Some(pane::Action::Panel(_action)) => {}
+                    None => {}
+                }
             });
 
+        tasks.extend(self.day_levels_fetch_tasks(&wanted_levels, now));
+        tasks.extend(self.funding_fetch_tasks(&perps, now));
+        self.sync_day_levels(main_window);
+
         Task::batch(tasks)
     }
 
+    /// Daily klines of the tickers drawing day levels that don't have current ones, retried
+    /// once a minute at most
+    fn day_levels_fetch_tasks(
+        &mut self,
+        wanted: &HashMap<Ticker, Exchange>,
+        now: Instant,
+    ) -> Vec<Task<Message>> {
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+        let mut tasks = vec![];
+
+        for (ticker, exchange) in wanted {
+            let is_current = self
+                .day_levels
+                .get(ticker)
+                .is_some_and(|levels| !levels.is_stale(now_ms));
+            let is_recently_requested = self
+                .day_levels_requested
+                .get(ticker)
+                .is_some_and(|at| now.duration_since(*at) < DAY_LEVELS_RETRY);
+
+            if is_current || is_recently_requested {
+                continue;
+            }
+            self.day_levels_requested.insert(*ticker, now);
+
+            let ticker = *ticker;
+            let range = (now_ms - 2 * Timeframe::D1.to_milliseconds(), now_ms);
+
+            tasks.push(Task::perform(
+                adapter::fetch_klines(
+                    *exchange,
+                    ticker,
+                    Timeframe::D1,
+                    KlineSource::Last,
+                    Some(range),
+                )
+                .map_err(|err| format!("{err}")),
+                move |result| Message::DayLevelsFetched(ticker, result),
+            ));
+        }
+
+        tasks
+    }
+
+    /// Funding of the open perp tickers, each fetched again once [`FUNDING_REFRESH`] has passed
+    fn funding_fetch_tasks(
+        &mut self,
+        perps: &HashMap<Ticker, Exchange>,
+        now: Instant,
+    ) -> Vec<Task<Message>> {
+        self.funding_rates
+            .retain(|ticker, _| perps.contains_key(ticker));
+        self.funding_requested
+            .retain(|ticker, _| perps.contains_key(ticker));
+
+        let mut tasks = vec![];
+
+        for (ticker, exchange) in perps {
+            let is_recently_requested = self
+                .funding_requested
+                .get(ticker)
+                .is_some_and(|at| now.duration_since(*at) < FUNDING_REFRESH);
+
+            if is_recently_requested {
+                continue;
+            }
+            self.funding_requested.insert(*ticker, now);
+
+            let ticker = *ticker;
+
+            tasks.push(Task::perform(
+                adapter::fetch_funding_rate(*exchange, ticker).map_err(|err| format!("{err}")),
+                move |result| Message::FundingRateFetched(ticker, result),
+            ));
+        }
+
+        tasks
+    }
+
+    /// Hands each chart the levels of its ticker, or takes them away when it doesn't draw them
+    fn sync_day_levels(&mut self, main_window: window::Id) {
+        let day_levels = &self.day_levels;
+
+        self.iter_all_panes_mut(main_window)
+            .for_each(|(_, _, state)| {
+                let levels = state
+                    .stream_pair()
+                    .filter(|_| state.wants_day_levels())
+                    .and_then(|(_, ticker)| day_levels.get(&ticker).copied());
+
+                match &mut state.content {
+                    pane::Content::Heatmap(chart, _) => chart::set_day_levels(chart, levels),
+                    pane::Content::Kline(chart, _) => chart::set_day_levels(chart, levels),
+                    _ => {}
+                }
+            });
+    }
+
     pub fn market_subscriptions(&self) -> Subscription<exchange::Event> {
+        if self.streams_paused {
+            return Subscription::none();
+        }
+
         let unique_streams = self
             .streams
             .combined()
@@ -1211,13 +2703,20 @@ impl Dashboard {
                 let kline_params = specs
                     .kline
                     .iter()
-                    .map(|(_, ticker, timeframe)| (*ticker, *timeframe))
+                    .map(|(_, ticker, timeframe, source)| (*ticker, *timeframe, *source))
                     .collect::<Vec<_>>();
 
                 if !kline_params.is_empty() {
                     subs.push(kline_subscription(*exchange, kline_params));
                 }
 
+                subs.extend(
+                    specs
+                        .liquidations
+                        .iter()
+                        .filter_map(|(_, ticker)| liquidation_subscription(*exchange, *ticker)),
+                );
+
                 subs
             })
             .collect::<Vec<Subscription<exchange::Event>>>();
@@ -1228,24 +2727,393 @@ impl Dashboard {
     fn refresh_streams(&mut self, main_window: window::Id) -> Task<Message> {
         let all_pane_streams = self
             .iter_all_panes(main_window)
-            .flat_map(|(_, _, pane_state)| &pane_state.streams);
-        self.streams = UniqueStreams::from(all_pane_streams);
+            .filter(|(window, pane, _)| {
+                !self.lazy_streams || self.is_pane_visible(main_window, *window, *pane)
+            })
+            .flat_map(|(window, _, pane_state)| {
+                let is_hidden = self.hidden_popouts.contains(&window);
+
+                pane_state
+                    .streams
+                    .iter()
+                    .copied()
+                    .chain(pane_state.liquidation_stream())
+                    // kept alive to notice when the primary exchange is reachable again
+                    .chain(
+                        pane_state
+                            .failover
+                            .iter()
+                            .flat_map(|failover| failover.streams.iter().copied()),
+                    )
+                    .filter(move |stream| {
+                        !(is_hidden
+                            && matches!(
+                                stream,
+                                StreamKind::DepthAndTrades { .. } | StreamKind::Liquidations { .. }
+                            ))
+                    })
+            })
+            .collect::<Vec<_>>();
+        self.streams = UniqueStreams::from(all_pane_streams.iter());
+        self.sync_kline_store(main_window);
+        self.panes_changed = true;
 
         Task::none()
     }
 
-    fn klines_fetch_all_task(
+    /// Kline subscribers are counted over every pane, hidden ones included, so their klines
+    /// stay around for when they're shown again
+    fn sync_kline_store(&mut self, main_window: window::Id) {
+        let kline_streams = self
+            .iter_all_panes(main_window)
+            .flat_map(|(_, _, pane_state)| pane_state.streams.iter().copied())
+            .collect::<Vec<_>>();
+
+        self.kline_store.sync_subscribers(kline_streams.iter());
+    }
+
+    /// Initial klines of a pane that just subscribed to the stream, served from the shared
+    /// store when another pane already has them loaded
+    fn kline_init_task(
+        &mut self,
+        main_window: window::Id,
+        layout_id: uuid::Uuid,
+        pane_id: uuid::Uuid,
+        stream: StreamKind,
+    ) -> Task<Message> {
+        self.sync_kline_store(main_window);
+
+        match self.kline_store.klines(&stream) {
+            Some(klines) => Task::done(Message::DistributeFetchedData {
+                layout_id,
+                pane_id,
+                stream,
+                data: FetchedData::Klines {
+                    data: klines,
+                    req_id: None,
+                },
+            }),
+            None => kline_fetch_task(layout_id, pane_id, stream, None, None),
+        }
+    }
+
+    /// Initial klines of every kline stream a pane just got, both legs for a spread
+    fn kline_init_tasks(
+        &mut self,
+        main_window: window::Id,
+        pane_id: uuid::Uuid,
+        streams: &[StreamKind],
+    ) -> Task<Message> {
+        let layout_id = self.layout_id;
+
+        let tasks = streams
+            .iter()
+            .filter(|stream| matches!(stream, StreamKind::Kline { .. }))
+            .map(|stream| self.kline_init_task(main_window, layout_id, pane_id, *stream))
+            .collect::<Vec<_>>();
+
+        Task::batch(tasks)
+    }
+
+    fn is_pane_visible(
         &self,
+        main_window: window::Id,
+        window: window::Id,
+        pane: pane_grid::Pane,
+    ) -> bool {
+        if window == main_window {
+            self.panes
+                .maximized()
+                .is_none_or(|maximized| maximized == pane)
+        } else {
+            !self.hidden_popouts.contains(&window)
+        }
+    }
+
+    /// Pauses depth streams of a hidden popout window, resuming them once it's shown again
+    pub fn set_popout_hidden(
+        &mut self,
+        window: window::Id,
+        is_hidden: bool,
+        main_window: window::Id,
+    ) -> Task<Message> {
+        if !self.popout.contains_key(&window) {
+            return Task::none();
+        }
+
+        let changed = if is_hidden {
+            self.hidden_popouts.insert(window)
+        } else {
+            self.hidden_popouts.remove(&window)
+        };
+
+        if !changed {
+            return Task::none();
+        }
+
+        log::info!(
+            "{} streams of popout window {window:?}",
+            if is_hidden { "Pausing" } else { "Resuming" }
+        );
+
+        let refresh = self.refresh_streams(main_window);
+
+        if is_hidden || !self.lazy_streams {
+            refresh
+        } else {
+            refresh.chain(self.klines_fetch_all_task(self.layout_id, main_window))
+        }
+    }
+
+    pub fn resume_hidden_popouts(&mut self, main_window: window::Id) -> Task<Message> {
+        if self.hidden_popouts.is_empty() {
+            return Task::none();
+        }
+        self.hidden_popouts.clear();
+
+        self.refresh_streams(main_window)
+    }
+
+    /// Pans the first time based chart of the ticker to the given time and focuses it
+    pub fn scroll_to_time(
+        &mut self,
+        main_window: window::Id,
+        exchange: Exchange,
+        ticker: Ticker,
+        time: u64,
+    ) -> bool {
+        let target = self
+            .iter_all_panes(main_window)
+            .find(|(_, _, state)| {
+                state.stream_pair() == Some((exchange, ticker))
+                    && state.content.visible_timerange().is_some()
+            })
+            .map(|(window, pane, _)| (window, pane));
+
+        let Some((window, pane)) = target else {
+            return false;
+        };
+
+        if let Some(state) = self.get_mut_pane(main_window, window, pane) {
+            match &mut state.content {
+                pane::Content::Heatmap(chart, _) => {
+                    chart::update(chart, chart::Message::ScrollToTime(time));
+                }
+                pane::Content::Kline(chart, _) => {
+                    chart::update(chart, chart::Message::ScrollToTime(time));
+                }
+                _ => {}
+            }
+        }
+
+        self.focus = Some((window, pane));
+        true
+    }
+
+    /// Charts marked for scheduled snapshots, grouped by the window they're captured from and
+    /// each with its bounds in there and the ticker its file is named after
+    pub fn auto_snapshot_targets(
+        &self,
+        main_window: window::Id,
+    ) -> Vec<(window::Id, Vec<(iced::Rectangle, String)>)> {
+        let mut targets: Vec<(window::Id, Vec<(iced::Rectangle, String)>)> = vec![];
+        let mut labels: Vec<String> = vec![];
+
+        for (window, _, state) in self.iter_all_panes(main_window) {
+            if !state.settings.auto_snapshot {
+                continue;
+            }
+            let Some(bounds) = state.content.canvas_bounds() else {
+                continue;
+            };
+
+            let ticker = state
+                .settings
+                .ticker_info
+                .map_or("chart".to_string(), |info| {
+                    info.ticker.display_symbol_and_type().0
+                });
+
+            // charts of the same ticker would overwrite each other's file
+            let count = labels.iter().filter(|label| **label == ticker).count();
+            labels.push(ticker.clone());
+            let label = if count == 0 {
+                ticker
+            } else {
+                format!("{ticker}-{}", count + 1)
+            };
+
+            match targets.iter_mut().find(|(id, _)| *id == window) {
+                Some((_, panes)) => panes.push((bounds, label)),
+                None => targets.push((window, vec![(bounds, label)])),
+            }
+        }
+
+        targets
+    }
+
+    /// Counts the depth and kline streams every pane of the layout would need,
+    /// along with their estimated combined message rate
+    pub fn stream_cost(&self, main_window: window::Id) -> (usize, usize, f32) {
+        let streams = UniqueStreams::from(
+            self.iter_all_panes(main_window)
+                .flat_map(|(_, _, pane_state)| &pane_state.streams),
+        );
+
+        (
+            streams.depth_streams(None).len(),
+            streams.kline_streams(None).len(),
+            streams.estimated_msg_rate(),
+        )
+    }
+
+    pub fn set_lazy_streams(&mut self, is_enabled: bool, main_window: window::Id) -> Task<Message> {
+        if self.lazy_streams == is_enabled {
+            return Task::none();
+        }
+        self.lazy_streams = is_enabled;
+
+        let refresh = self.refresh_streams(main_window);
+
+        if is_enabled {
+            refresh
+        } else {
+            refresh.chain(self.klines_fetch_all_task(self.layout_id, main_window))
+        }
+    }
+
+    /// Whether panes were added, removed or changed streams since the last call
+    pub fn take_panes_changed(&mut self) -> bool {
+        std::mem::take(&mut self.panes_changed)
+    }
+
+    pub fn is_streams_paused(&self) -> bool {
+        self.streams_paused
+    }
+
+    /// Drops every market subscription while keeping the panes and their stream specs intact,
+    /// resuming refetches klines so the charts catch up on what was missed meanwhile
+    pub fn set_streams_paused(&mut self, paused: bool, main_window: window::Id) -> Task<Message> {
+        if self.streams_paused == paused {
+            return Task::none();
+        }
+        self.streams_paused = paused;
+
+        if paused {
+            Task::none()
+        } else {
+            self.refresh_streams(main_window)
+                .chain(self.klines_fetch_all_task(self.layout_id, main_window))
+        }
+    }
+
+    /// Loads what the recorder stored since `since` for each depth stream shown on the layout
+    pub fn restore_recordings(&self, main_window: window::Id, since: u64) -> Task<Message> {
+        let streams = self
+            .iter_all_panes(main_window)
+            .filter(|(_, _, state)| {
+                matches!(
+                    state.content,
+                    pane::Content::Heatmap(_, _) | pane::Content::Kline(_, _)
+                )
+            })
+            .filter_map(|(_, _, state)| state.depth_stream())
+            .collect::<HashSet<_>>();
+
+        Task::batch(
+            streams
+                .into_iter()
+                .map(|stream| Self::load_recording(stream, since)),
+        )
+    }
+
+    /// Loads what the recorder stored for the stream since `since`
+    pub fn load_recording(stream: StreamKind, since: u64) -> Task<Message> {
+        let (exchange, ticker) = stream.exchange_and_ticker();
+
+        Task::perform(
+            async move {
+                data::recorder::load_since(exchange, ticker, since).map_err(|err| err.to_string())
+            },
+            move |result| Message::RecordingLoaded(stream, result),
+        )
+    }
+
+    /// Rebuilds heatmaps from scratch with the recorded points, since they only take datapoints
+    /// in order, and adds the recorded trades to footprints unless trade fetching covers them
+    fn insert_recording(
+        &mut self,
+        stream: &StreamKind,
+        points: &[data::recorder::RecordedPoint],
+        main_window: window::Id,
+    ) {
+        if points.is_empty() {
+            return;
+        }
+        let (exchange, _) = stream.exchange_and_ticker();
+        let fetches_trades = exchange::fetcher::is_trade_fetch_enabled(exchange);
+
+        self.iter_all_panes_mut(main_window)
+            .filter(|(_, _, state)| state.matches_stream(stream))
+            .for_each(|(_, _, state)| {
+                if matches!(&state.content, pane::Content::Heatmap(_, _)) {
+                    state.reset_content();
+                }
+
+                match &mut state.content {
+                    pane::Content::Heatmap(chart, _) if chart.snapshot_source().is_none() => {
+                        for point in points {
+                            chart.insert_datapoint(&point.trades, point.time, &point.depth);
+                        }
+                    }
+                    pane::Content::Kline(chart, _) if !fetches_trades => {
+                        let trades = points
+                            .iter()
+                            .flat_map(|point| point.trades.iter().copied())
+                            .collect();
+                        chart.insert_raw_trades(trades, false);
+                    }
+                    _ => {}
+                }
+            });
+    }
+
+    /// Pauses the live streams and clears the panes of the stream, leaving them to the replayed
+    /// trades until `end_replay`
+    pub fn begin_replay(&mut self, stream: &StreamKind, main_window: window::Id) -> Task<Message> {
+        let task = self.set_streams_paused(true, main_window);
+
+        self.iter_all_panes_mut(main_window)
+            .filter(|(_, _, state)| state.matches_stream(stream))
+            .for_each(|(_, _, state)| state.reset_content());
+
+        task
+    }
+
+    /// Drops what the replay drew and resumes the live streams, refetching what the panes show
+    pub fn end_replay(&mut self, stream: &StreamKind, main_window: window::Id) -> Task<Message> {
+        self.iter_all_panes_mut(main_window)
+            .filter(|(_, _, state)| state.matches_stream(stream))
+            .for_each(|(_, _, state)| state.reset_content());
+
+        self.set_streams_paused(false, main_window)
+    }
+
+    fn klines_fetch_all_task(
+        &mut self,
         layout_id: uuid::Uuid,
         main_window_id: window::Id,
     ) -> Task<Message> {
         let mut tasks: Vec<Task<Message>> = vec![];
 
-        for (exchange, ticker, timeframe) in self.streams.kline_streams(None) {
+        self.kline_store.clear();
+
+        for (exchange, ticker, timeframe, source) in self.streams.kline_streams(None) {
             let stream_kind = StreamKind::Kline {
                 exchange,
                 ticker,
                 timeframe,
+                source,
             };
 
             let matching_panes = self
@@ -1256,7 +3124,7 @@ impl Dashboard {
 
             if matching_panes.is_empty() {
                 let fetch_task = Task::perform(
-                    adapter::fetch_klines(exchange, ticker, timeframe, None)
+                    adapter::fetch_klines(exchange, ticker, timeframe, source, None)
                         .map_err(|err| format!("{err}")),
                     move |result| match result {
                         Ok(_) => Message::Notification(Toast::warn(format!(
@@ -1271,15 +3139,23 @@ impl Dashboard {
                 );
                 tasks.push(fetch_task);
             } else {
-                for pane_uid in matching_panes {
-                    tasks.push(kline_fetch_task(
-                        layout_id,
-                        pane_uid,
-                        stream_kind,
-                        None,
-                        None,
-                    ));
+                // fetched once on behalf of the first pane, the result goes to all of them
+                self.kline_store.begin_shared_fetch(stream_kind);
+
+                for pane_uid in &matching_panes[1..] {
+                    tasks.push(Task::done(Message::ChangePaneStatus(
+                        *pane_uid,
+                        pane::Status::Loading(pane::InfoType::FetchingKlines),
+                    )));
                 }
+
+                tasks.push(kline_fetch_task(
+                    layout_id,
+                    matching_panes[0],
+                    stream_kind,
+                    None,
+                    None,
+                ));
             }
         }
 
@@ -1297,23 +3173,22 @@ fn request_fetch(
 
     match fetch {
         FetchRange::Kline(from, to) => {
-            let kline_stream = {
-                state
-                    .streams
-                    .iter()
-                    .find(|stream| matches!(stream, StreamKind::Kline { .. }))
-                    .map(|stream| (*stream, pane_id))
-            };
+            // a spread needs the range of both its legs
+            let requests = state
+                .streams
+                .iter()
+                .filter(|stream| matches!(stream, StreamKind::Kline { .. }))
+                .map(|stream| {
+                    Task::done(Message::RequestKlines {
+                        pane_id,
+                        stream: *stream,
+                        req_id,
+                        range: (from, to),
+                    })
+                })
+                .collect::<Vec<_>>();
 
-            if let Some((stream, pane_uid)) = kline_stream {
-                return kline_fetch_task(
-                    layout_id,
-                    pane_uid,
-                    stream,
-                    Some(req_id),
-                    Some((from, to)),
-                );
-            }
+            return Task::batch(requests);
         }
         FetchRange::OpenInterest(from, to) => {
             let kline_stream = {
@@ -1338,12 +3213,7 @@ fn request_fetch(
             });
 
             if let Some((exchange, ticker, pane_id, stream)) = trade_info {
-                let is_binance = matches!(
-                    exchange,
-                    Exchange::BinanceSpot | Exchange::BinanceLinear | Exchange::BinanceInverse
-                );
-
-                if is_binance {
+                if exchange.supports_trade_fetch() {
                     let data_path = data::data_path(Some("market_data/binance/"));
 
                     let (task, handle) = Task::sip(
@@ -1362,10 +3232,7 @@ fn request_fetch(
                         },
                         move |result| match result {
                             Ok(()) => Message::ChangePaneStatus(pane_id, pane::Status::Ready),
-                            Err(err) => Message::ErrorOccurred(
-                                Some(pane_id),
-                                DashboardError::Fetch(err.to_string()),
-                            ),
+                            Err(err) => Message::ErrorOccurred(Some(pane_id), err.into()),
                         },
                     )
                     .abortable();
@@ -1400,9 +3267,9 @@ fn oi_fetch_task(
             exchange,
             timeframe,
             ticker,
+            ..
         } => Task::perform(
-            adapter::fetch_open_interest(exchange, ticker, timeframe, range)
-                .map_err(|err| format!("{err}")),
+            fetch_oi_cached(exchange, ticker, timeframe, range),
             move |result| match result {
                 Ok(oi) => {
                     let data = FetchedData::OI { data: oi, req_id };
@@ -1413,7 +3280,7 @@ fn oi_fetch_task(
                         stream,
                     }
                 }
-                Err(err) => Message::ErrorOccurred(Some(pane_id), DashboardError::Fetch(err)),
+                Err(err) => Message::ErrorOccurred(Some(pane_id), err),
             },
         ),
         _ => Task::none(),
@@ -1422,6 +3289,101 @@ fn oi_fetch_task(
     update_status.chain(fetch_task)
 }
 
+/// Fetches open interest through the local cache, which also fills in the older history the
+/// exchange doesn't serve anymore
+async fn fetch_oi_cached(
+    exchange: Exchange,
+    ticker: Ticker,
+    timeframe: Timeframe,
+    range: Option<(u64, u64)>,
+) -> Result<Vec<exchange::OpenInterest>, DashboardError> {
+    // testnet positions would end up mixed into the real market's history
+    if adapter::is_testnet(exchange) {
+        return adapter::fetch_open_interest(exchange, ticker, timeframe, range)
+            .await
+            .map_err(DashboardError::from);
+    }
+
+    let fetched = adapter::fetch_open_interest(exchange, ticker, timeframe, range).await;
+
+    let cached = match &fetched {
+        Ok(oi) => data::oi_cache::store(exchange, ticker, timeframe, oi).unwrap_or_else(|err| {
+            log::warn!("Failed to cache open interest: {err}");
+            oi.iter().map(|point| (point.time, point.value)).collect()
+        }),
+        Err(_) => data::oi_cache::load(exchange, ticker, timeframe),
+    };
+
+    let points = data::oi_cache::in_range(&cached, range);
+
+    match fetched {
+        Ok(_) => Ok(points),
+        Err(err) if points.is_empty() => Err(DashboardError::from(err)),
+        Err(err) => {
+            log::warn!("Open interest fetch failed, using cached points instead: {err}");
+            Ok(points)
+        }
+    }
+}
+
+/// Fetches klines through the local cache, only the bars it doesn't have yet get requested
+async fn fetch_klines_cached(
+    exchange: Exchange,
+    ticker: Ticker,
+    timeframe: Timeframe,
+    source: KlineSource,
+    range: Option<(u64, u64)>,
+) -> Result<Vec<Kline>, DashboardError> {
+    // testnet prices have nothing to do with the real market's
+    if adapter::is_testnet(exchange) {
+        return adapter::fetch_klines(exchange, ticker, timeframe, source, range)
+            .await
+            .map_err(DashboardError::from);
+    }
+
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let mut cached = data::kline_cache::load(exchange, ticker, timeframe, source);
+
+    let fetch_range = match data::kline_cache::missing(&cached, timeframe, range, now) {
+        data::kline_cache::Missing::Nothing => {
+            return Ok(data::kline_cache::in_range(&cached, timeframe, range));
+        }
+        data::kline_cache::Missing::Range(from, to) => Some((from, to)),
+        data::kline_cache::Missing::Everything => range,
+    };
+
+    match adapter::fetch_klines(exchange, ticker, timeframe, source, fetch_range).await {
+        Ok(fetched) => {
+            if let Err(err) = data::kline_cache::store(
+                exchange,
+                ticker,
+                timeframe,
+                source,
+                &mut cached,
+                &fetched,
+                now,
+            ) {
+                log::warn!("Failed to cache klines: {err}");
+            }
+
+            // the bar still forming isn't cached, it only comes with the fetch
+            cached.extend(fetched.iter().map(|kline| (kline.time, *kline)));
+
+            Ok(data::kline_cache::in_range(&cached, timeframe, range))
+        }
+        Err(err) => {
+            let klines = data::kline_cache::in_range(&cached, timeframe, range);
+
+            if klines.is_empty() {
+                return Err(DashboardError::from(err));
+            }
+
+            log::warn!("Kline fetch failed, using cached klines instead: {err}");
+            Ok(klines)
+        }
+    }
+}
+
 fn kline_fetch_task(
     layout_id: uuid::Uuid,
     pane_id: uuid::Uuid,
@@ -1439,9 +3401,9 @@ fn kline_fetch_task(
             exchange,
             ticker,
             timeframe,
+            source,
         } => Task::perform(
-            adapter::fetch_klines(exchange, ticker, timeframe, range)
-                .map_err(|err| format!("{err}")),
+            fetch_klines_cached(exchange, ticker, timeframe, source, range),
             move |result| match result {
                 Ok(klines) => {
                     let data = FetchedData::Klines {
@@ -1455,7 +3417,7 @@ fn kline_fetch_task(
                         stream,
                     }
                 }
-                Err(err) => Message::ErrorOccurred(Some(pane_id), DashboardError::Fetch(err)),
+                Err(err) => Message::KlinesFetchFailed(pane_id, req_id, stream, err),
             },
         ),
         _ => Task::none(),
@@ -1464,6 +3426,54 @@ fn kline_fetch_task(
     update_status.chain(fetch_task)
 }
 
+/// Asks for the recording of a freshly opened heatmap's ticker, so it doesn't start out empty
+fn heatmap_backfill(content: &str, streams: &[StreamKind]) -> Task<Message> {
+    match streams
+        .iter()
+        .find(|stream| matches!(stream, StreamKind::DepthAndTrades { .. }))
+    {
+        Some(stream) if content == "heatmap" => Task::done(Message::BackfillRequested(*stream)),
+        _ => Task::none(),
+    }
+}
+
+/// Ticker stats of each market and sparkline klines of each ticker a watchlist is due
+fn watchlist_fetch_tasks(
+    pane_id: uuid::Uuid,
+    due: panel::watchlist::DueFetches,
+) -> Vec<Task<Message>> {
+    let stats = due.stats.into_iter().map(move |exchange| {
+        Task::perform(
+            adapter::fetch_ticker_prices(exchange),
+            move |result| match result {
+                Ok(stats) => Message::WatchlistStats(pane_id, exchange, stats),
+                Err(err) => Message::ErrorOccurred(Some(pane_id), err.into()),
+            },
+        )
+    });
+
+    let sparklines = due.sparklines.into_iter().map(move |info| {
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        let range = (now - Timeframe::D1.to_milliseconds(), now);
+
+        Task::perform(
+            adapter::fetch_klines(
+                info.exchange(),
+                info.ticker,
+                Timeframe::H1,
+                KlineSource::Last,
+                Some(range),
+            ),
+            move |result| match result {
+                Ok(klines) => Message::WatchlistSparkline(pane_id, info.ticker, klines),
+                Err(err) => Message::ErrorOccurred(Some(pane_id), err.into()),
+            },
+        )
+    });
+
+    stats.chain(sparklines).collect()
+}
+
 pub fn fetch_trades_batched(
     ticker: Ticker,
     from_time: u64,
@@ -1503,26 +3513,59 @@ pub fn depth_subscription(exchange: Exchange, ticker: Ticker) -> Subscription<ex
             let builder = |cfg: &StreamConfig<Ticker>| bybit::connect_market_stream(cfg.id);
             Subscription::run_with(config, builder)
         }
+        Exchange::OkxSpot | Exchange::OkxLinear | Exchange::OkxInverse => {
+            let builder = |cfg: &StreamConfig<Ticker>| okx::connect_market_stream(cfg.id);
+            Subscription::run_with(config, builder)
+        }
+    }
+}
+
+pub fn liquidation_subscription(
+    exchange: Exchange,
+    ticker: Ticker,
+) -> Option<Subscription<exchange::Event>> {
+    let config = StreamConfig::new(ticker, exchange);
+    match exchange {
+        Exchange::BinanceInverse | Exchange::BinanceLinear => {
+            let builder = |cfg: &StreamConfig<Ticker>| binance::connect_liquidation_stream(cfg.id);
+            Some(Subscription::run_with(config, builder))
+        }
+        _ => None,
     }
 }
 
 pub fn kline_subscription(
     exchange: Exchange,
-    kline_subs: Vec<(Ticker, Timeframe)>,
+    kline_subs: Vec<(Ticker, Timeframe, KlineSource)>,
 ) -> Subscription<exchange::Event> {
     let config = StreamConfig::new(kline_subs, exchange);
     match exchange {
         Exchange::BinanceSpot | Exchange::BinanceInverse | Exchange::BinanceLinear => {
-            let builder = |cfg: &StreamConfig<Vec<(Ticker, Timeframe)>>| {
+            let builder = |cfg: &StreamConfig<Vec<(Ticker, Timeframe, KlineSource)>>| {
                 binance::connect_kline_stream(cfg.id.clone(), cfg.market_type)
             };
             Subscription::run_with(config, builder)
         }
+        // only last price klines are streamed from these
         Exchange::BybitSpot | Exchange::BybitInverse | Exchange::BybitLinear => {
-            let builder = |cfg: &StreamConfig<Vec<(Ticker, Timeframe)>>| {
-                bybit::connect_kline_stream(cfg.id.clone(), cfg.market_type)
+            let builder = |cfg: &StreamConfig<Vec<(Ticker, Timeframe, KlineSource)>>| {
+                bybit::connect_kline_stream(last_price_streams(&cfg.id), cfg.market_type)
+            };
+            Subscription::run_with(config, builder)
+        }
+        Exchange::OkxSpot | Exchange::OkxInverse | Exchange::OkxLinear => {
+            let builder = |cfg: &StreamConfig<Vec<(Ticker, Timeframe, KlineSource)>>| {
+                okx::connect_kline_stream(last_price_streams(&cfg.id), cfg.market_type)
             };
             Subscription::run_with(config, builder)
         }
     }
 }
+
+fn last_price_streams(kline_subs: &[(Ticker, Timeframe, KlineSource)]) -> Vec<(Ticker, Timeframe)> {
+    kline_subs
+        .iter()
+        .filter(|(_, _, source)| *source == KlineSource::Last)
+        .map(|(ticker, timeframe, _)| (*ticker, *timeframe))
+        .collect()
+}
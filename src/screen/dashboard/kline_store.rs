@@ -0,0 +1,152 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use exchange::{Kline, adapter::StreamKind};
+
+/// Most recent klines kept per stream, older ones are dropped as newer ones come in
+const MAX_KLINES: usize = 5_000;
+
+struct Entry {
+    klines: BTreeMap<u64, Kline>,
+    subscribers: usize,
+}
+
+impl Entry {
+    fn trim(&mut self) {
+        while self.klines.len() > MAX_KLINES {
+            self.klines.pop_first();
+        }
+    }
+}
+
+/// Recent klines of every kline stream on a dashboard, kept once per stream no matter how
+/// many panes show it. Panes still build their own series, the store only shares fetched and
+/// live bars between them and holds at most `MAX_KLINES` of each stream, so history scrolled
+/// back past that gets fetched again.
+#[derive(Default)]
+pub struct KlineStore {
+    entries: HashMap<StreamKind, Entry>,
+    /// Streams with an initial fetch in flight whose result goes to every subscribed pane
+    shared_fetches: HashSet<StreamKind>,
+}
+
+impl KlineStore {
+    /// Recounts subscribers from the panes' streams and drops the streams nobody shows anymore
+    pub fn sync_subscribers<'a>(&mut self, streams: impl Iterator<Item = &'a StreamKind>) {
+        self.entries
+            .values_mut()
+            .for_each(|entry| entry.subscribers = 0);
+
+        for stream in streams.filter(|stream| matches!(stream, StreamKind::Kline { .. })) {
+            self.entries
+                .entry(*stream)
+                .or_insert_with(|| Entry {
+                    klines: BTreeMap::new(),
+                    subscribers: 0,
+                })
+                .subscribers += 1;
+        }
+
+        self.entries.retain(|_, entry| entry.subscribers > 0);
+        self.shared_fetches
+            .retain(|stream| self.entries.contains_key(stream));
+    }
+
+    /// Everything stored for the stream, `None` until its first fetch lands
+    pub fn klines(&self, stream: &StreamKind) -> Option<Vec<Kline>> {
+        self.entries
+            .get(stream)
+            .filter(|entry| !entry.klines.is_empty())
+            .map(|entry| entry.klines.values().copied().collect())
+    }
+
+    /// Stored klines covering `from..=to` without any missing bar in between
+    pub fn klines_in_range(&self, stream: &StreamKind, from: u64, to: u64) -> Option<Vec<Kline>> {
+        let StreamKind::Kline { timeframe, .. } = stream else {
+            return None;
+        };
+        let interval = timeframe.to_milliseconds();
+
+        let entry = self.entries.get(stream)?;
+        let (earliest, latest) = (
+            *entry.klines.keys().next()?,
+            *entry.klines.keys().next_back()?,
+        );
+
+        if earliest > from || latest + interval < to {
+            return None;
+        }
+
+        let klines = entry
+            .klines
+            .range(from.saturating_sub(interval)..=to)
+            .map(|(_, kline)| *kline)
+            .collect::<Vec<_>>();
+
+        let has_gap = klines
+            .windows(2)
+            .any(|pair| pair[1].time - pair[0].time > interval);
+
+        (!has_gap && !klines.is_empty()).then_some(klines)
+    }
+
+    /// Klines of two streams paired up by their open time, bars only one of them has are left out
+    pub fn paired(&self, first: &StreamKind, second: &StreamKind) -> Vec<(Kline, Kline)> {
+        let (Some(first), Some(second)) = (self.entries.get(first), self.entries.get(second))
+        else {
+            return vec![];
+        };
+
+        first
+            .klines
+            .iter()
+            .filter_map(|(time, kline)| second.klines.get(time).map(|other| (*kline, *other)))
+            .collect()
+    }
+
+    /// Both streams' klines opening at `time`, once each of them has one
+    pub fn pair_at(
+        &self,
+        first: &StreamKind,
+        second: &StreamKind,
+        time: u64,
+    ) -> Option<(Kline, Kline)> {
+        Some((
+            *self.entries.get(first)?.klines.get(&time)?,
+            *self.entries.get(second)?.klines.get(&time)?,
+        ))
+    }
+
+    pub fn insert(&mut self, stream: &StreamKind, klines: &[Kline]) {
+        if let Some(entry) = self.entries.get_mut(stream) {
+            entry
+                .klines
+                .extend(klines.iter().map(|kline| (kline.time, *kline)));
+            entry.trim();
+        }
+    }
+
+    pub fn update_latest(&mut self, stream: &StreamKind, kline: &Kline) {
+        if let Some(entry) = self.entries.get_mut(stream) {
+            entry.klines.insert(kline.time, *kline);
+            entry.trim();
+        }
+    }
+
+    /// Marks the stream's next initial fetch as shared, so its result reaches every subscriber
+    pub fn begin_shared_fetch(&mut self, stream: StreamKind) {
+        self.shared_fetches.insert(stream);
+    }
+
+    /// Whether an initial fetch result should go to every pane of the stream
+    pub fn finish_shared_fetch(&mut self, stream: &StreamKind) -> bool {
+        self.shared_fetches.remove(stream)
+    }
+
+    /// Drops the stored klines so the next refetch starts over, keeping the subscriber counts
+    pub fn clear(&mut self) {
+        self.entries
+            .values_mut()
+            .for_each(|entry| entry.klines.clear());
+        self.shared_fetches.clear();
+    }
+}
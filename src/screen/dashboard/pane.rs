@@ -1,5 +1,5 @@
 use crate::{
-    chart::{self, heatmap::HeatmapChart, kline::KlineChart},
+    chart::{self, Chart, heatmap::HeatmapChart, kline::KlineChart},
     modal::{
         self, ModifierKind,
         pane::{
@@ -9,23 +9,33 @@ use crate::{
     },
     screen::{
         DashboardError,
-        dashboard::panel::{self, timeandsales::TimeAndSales},
+        dashboard::panel::{
+            self, ladder::DomLadder, timeandsales::TimeAndSales, watchlist::Watchlist,
+            whale_tape::WhaleTape,
+        },
     },
     style::{self, Icon, icon_text},
-    widget::{self, button_with_tooltip, column_drag, link_group_button, toast::Toast},
+    widget::{self, button_with_tooltip, column_drag, link_group_button, toast::Notifications},
     window::{self, Window},
 };
 use data::{
     UserTimezone,
     chart::{
         Basis, ViewConfig, VisualConfig,
+        drawing::Tool as DrawingTool,
+        heatmap::snapshot::Snapshot,
         indicator::{HeatmapIndicator, Indicator, KlineIndicator},
+        kline::{FootprintStudy, ImbalancePresets},
+    },
+    config::{
+        chart_defaults,
+        theme::{ChartThemes, ThemedChart},
     },
     layout::pane::{LinkGroup, Settings},
 };
 use exchange::{
-    Kline, OpenInterest, TickMultiplier, Ticker, TickerInfo, Timeframe,
-    adapter::{Exchange, MarketKind, StreamKind},
+    FundingRate, Kline, OpenInterest, TickMultiplier, Ticker, TickerInfo, Timeframe,
+    adapter::{Exchange, KlineSource, MarketKind, StreamKind},
 };
 use iced::{
     Alignment, Element, Length, Renderer, Theme,
@@ -34,7 +44,10 @@ use iced::{
     widget::{button, center, column, container, pane_grid, row, text, tooltip},
 };
 use serde::{Deserialize, Serialize};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// How long a fetch may go without any progress before the pane stops waiting on it
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InfoType {
@@ -49,6 +62,10 @@ pub enum Status {
     Ready,
     Loading(InfoType),
     Stale(String),
+    /// Seconds until a dropped stream's next connection attempt, `None` once it gave up
+    Reconnecting(Option<u64>),
+    /// A fetch went without a response for too long, it can be retried from the title bar
+    TimedOut(InfoType),
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
@@ -58,6 +75,16 @@ pub enum Modal {
     Indicators,
     LinkGroup,
     Controls,
+    Snapshots,
+    Tag,
+    GoTo,
+}
+
+/// Where a pane was streaming from before it failed over to its fallback market
+#[derive(Debug, Clone)]
+pub struct Failover {
+    pub ticker_info: TickerInfo,
+    pub streams: Vec<StreamKind>,
 }
 
 pub enum Action {
@@ -79,16 +106,52 @@ pub enum Message {
     ReplacePane(pane_grid::Pane),
     ChartInteraction(pane_grid::Pane, chart::Message),
     PanelInteraction(pane_grid::Pane, panel::Message),
+    Watchlist(pane_grid::Pane, panel::watchlist::Message),
+    WhaleTape(pane_grid::Pane, panel::whale_tape::Message),
+    OpenWhaleTape(pane_grid::Pane),
     VisualConfigChanged(pane_grid::Pane, VisualConfig, bool),
     ToggleIndicator(pane_grid::Pane, String),
     Popout,
     Merge,
     DeleteNotification(pane_grid::Pane, usize),
+    ToggleNotificationDetails(pane_grid::Pane, usize),
     ReorderIndicator(pane_grid::Pane, column_drag::DragEvent),
     ClusterKindSelected(pane_grid::Pane, data::chart::kline::ClusterKind),
+    SpreadModeSelected(pane_grid::Pane, data::chart::kline::SpreadMode),
     StreamModifierChanged(pane_grid::Pane, modal::stream::Message),
     StudyConfigurator(pane_grid::Pane, modal::pane::settings::study::StudyMessage),
     SwitchLinkGroup(pane_grid::Pane, Option<LinkGroup>),
+    ExportSnapshot(pane_grid::Pane),
+    ExportTrades(pane_grid::Pane),
+    ListSnapshots(pane_grid::Pane),
+    OpenSnapshot(pane_grid::Pane, String),
+    ToggleAutoSnapshot(pane_grid::Pane, bool),
+    FailoverSelected(pane_grid::Pane, Option<Exchange>),
+    KlineSourceSelected(pane_grid::Pane, KlineSource),
+    TagInputChanged(pane_grid::Pane, String),
+    AddTag(pane_grid::Pane, String),
+    GoToInputChanged(pane_grid::Pane, String),
+    GoToTime(pane_grid::Pane, u64),
+    ImbalancePresetSelected(pane_grid::Pane, data::chart::kline::ImbalancePreset),
+    SaveImbalancePreset(pane_grid::Pane),
+    CopyStudies(pane_grid::Pane),
+    PasteStudies(pane_grid::Pane),
+    StudiesPasted(pane_grid::Pane, Option<String>),
+    ReplayRequested(pane_grid::Pane),
+    BarReplayChanged(pane_grid::Pane, Option<u64>),
+    StepBarReplay(pane_grid::Pane, i64),
+    RetryFetch(pane_grid::Pane),
+    ExportImage(pane_grid::Pane, ImageTarget),
+    ToggleExportWatermark(pane_grid::Pane, bool),
+    ToggleCleanMode(pane_grid::Pane),
+    ToggleCleanAxes(pane_grid::Pane, bool),
+}
+
+/// Where a chart exported as a PNG goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageTarget {
+    File,
+    Clipboard,
 }
 
 pub struct State {
@@ -96,10 +159,18 @@ pub struct State {
     pub modal: Option<Modal>,
     pub content: Content,
     pub settings: Settings,
-    pub notifications: Vec<Toast>,
+    pub notifications: Notifications,
     pub streams: Vec<StreamKind>,
     pub status: Status,
     pub link_group: Option<LinkGroup>,
+    pub snapshots: Vec<String>,
+    pub tag_input: String,
+    pub goto_input: String,
+    pub failover: Option<Failover>,
+    /// Modal that was open when the pane went into clean mode, shown again on the way out
+    modal_before_clean: Option<Modal>,
+    /// When the current loading status was last seen making progress
+    loading_since: Option<(Instant, InfoType)>,
 }
 
 impl State {
@@ -122,18 +193,91 @@ impl State {
         }
     }
 
+    /// Hides or brings back the pane's chrome, the modal that was open comes back with it
+    pub fn toggle_clean_mode(&mut self) {
+        self.settings.clean_mode = !self.settings.clean_mode;
+
+        if self.settings.clean_mode {
+            self.modal_before_clean = self.modal.take();
+        } else {
+            self.modal = self.modal_before_clean.take();
+        }
+    }
+
+    fn shows_axes(&self) -> bool {
+        !(self.settings.clean_mode && self.settings.clean_hides_axes)
+    }
+
+    /// Whether the pane's chart is set to draw the day's reference levels of its ticker
+    pub fn wants_day_levels(&self) -> bool {
+        match &self.content {
+            Content::Heatmap(chart, _) => chart.visual_config().day_levels,
+            Content::Kline(chart, _) => {
+                chart.visual_config().day_levels
+                    && !matches!(
+                        chart.kind(),
+                        data::chart::KlineChartKind::DeltaCandles
+                            | data::chart::KlineChartKind::Spread(_)
+                    )
+            }
+            _ => false,
+        }
+    }
+
     pub fn stream_pair(&self) -> Option<(Exchange, Ticker)> {
         self.streams
             .iter()
-            .map(|stream| match stream {
-                StreamKind::DepthAndTrades { exchange, ticker }
-                | StreamKind::Kline {
-                    exchange, ticker, ..
-                } => (*exchange, *ticker),
-            })
+            .map(StreamKind::exchange_and_ticker)
             .next()
     }
 
+    /// The pane's depth and trades stream, if it has one to replay
+    pub fn depth_stream(&self) -> Option<StreamKind> {
+        self.streams
+            .iter()
+            .find(|stream| matches!(stream, StreamKind::DepthAndTrades { .. }))
+            .copied()
+    }
+
+    /// Liquidations of the pane's ticker, for charts showing them on a venue that streams them
+    pub fn liquidation_stream(&self) -> Option<StreamKind> {
+        let (exchange, ticker) = self.stream_pair()?;
+
+        let is_shown = match &self.content {
+            Content::Heatmap(chart, _) => chart.visual_config().liquidation_scale.is_some(),
+            Content::Kline(chart, _) => chart.visual_config().liquidation_scale.is_some(),
+            Content::TimeAndSales(_)
+            | Content::DomLadder(_)
+            | Content::Watchlist(_)
+            | Content::WhaleTape(_)
+            | Content::Starter => false,
+        };
+
+        (is_shown && exchange.supports_liquidations())
+            .then_some(StreamKind::Liquidations { exchange, ticker })
+    }
+
+    /// Ticker, basis and exchange stamped on exported images, if the pane has watermarks on
+    pub fn export_watermark(&self) -> Option<String> {
+        if !self.settings.watermark_exports {
+            return None;
+        }
+        let ticker_info = self.settings.ticker_info?;
+
+        let symbol = {
+            let symbol = ticker_info.ticker.display_symbol_and_type().0;
+            match ticker_info.market_type() {
+                MarketKind::Spot => symbol,
+                MarketKind::LinearPerps | MarketKind::InversePerps => symbol + " PERP",
+            }
+        };
+
+        Some(match self.settings.selected_basis {
+            Some(basis) => format!("{symbol} · {basis} · {}", ticker_info.exchange()),
+            None => format!("{symbol} · {}", ticker_info.exchange()),
+        })
+    }
+
     pub fn set_content_and_streams(
         &mut self,
         ticker_info: TickerInfo,
@@ -145,6 +289,17 @@ impl State {
             self.settings.selected_basis = None;
         }
 
+        // a watchlist streams nothing, a ticker picked for it is added to the list
+        if content_str == "watchlist" {
+            match &mut self.content {
+                Content::Watchlist(watchlist) => watchlist.add(ticker_info),
+                _ => self.content = Content::Watchlist(Watchlist::new(vec![ticker_info])),
+            }
+            self.settings.ticker_info = None;
+            self.streams.clear();
+            return Ok(vec![]);
+        }
+
         self.settings.ticker_info = Some(ticker_info);
         let (exchange, ticker) = (ticker_info.exchange(), ticker_info.ticker);
 
@@ -168,6 +323,11 @@ impl State {
                     tm.multiply_with_min_tick_size(ticker_info)
                 });
 
+                let basis = *self
+                    .settings
+                    .selected_basis
+                    .get_or_insert(chart_defaults().footprint.into());
+
                 let content = Content::new_kline(
                     content_str,
                     &self.content,
@@ -176,7 +336,6 @@ impl State {
                     tick_size,
                 );
 
-                let basis = self.settings.selected_basis.unwrap_or(Timeframe::M5.into());
                 let streams = match basis {
                     Basis::Time(timeframe) => vec![
                         StreamKind::DepthAndTrades { exchange, ticker },
@@ -184,9 +343,12 @@ impl State {
                             exchange,
                             ticker,
                             timeframe,
+                            source: KlineSource::Last,
                         },
                     ],
-                    Basis::Tick(_) => vec![StreamKind::DepthAndTrades { exchange, ticker }],
+                    Basis::Tick(_) | Basis::Range(_) => {
+                        vec![StreamKind::DepthAndTrades { exchange, ticker }]
+                    }
                 };
                 Ok((content, streams))
             }
@@ -194,6 +356,11 @@ impl State {
                 self.settings.tick_multiply = None;
                 let tick_size = ticker_info.min_ticksize;
 
+                let basis = *self
+                    .settings
+                    .selected_basis
+                    .get_or_insert(chart_defaults().candlestick.into());
+
                 let content = Content::new_kline(
                     content_str,
                     &self.content,
@@ -202,20 +369,121 @@ impl State {
                     tick_size,
                 );
 
-                let basis = self
-                    .settings
-                    .selected_basis
-                    .unwrap_or(Timeframe::M15.into());
                 let streams = match basis {
                     Basis::Time(timeframe) => vec![StreamKind::Kline {
                         exchange,
                         ticker,
                         timeframe,
+                        source: self.settings.kline_source.fallback_for(exchange),
                     }],
-                    Basis::Tick(_) => vec![StreamKind::DepthAndTrades { exchange, ticker }],
+                    Basis::Tick(_) | Basis::Range(_) => {
+                        vec![StreamKind::DepthAndTrades { exchange, ticker }]
+                    }
                 };
                 Ok((content, streams))
             }
+            "delta" => {
+                self.settings.tick_multiply = None;
+                let tick_size = ticker_info.min_ticksize;
+
+                let basis = *self
+                    .settings
+                    .selected_basis
+                    .get_or_insert(chart_defaults().delta.into());
+
+                let content = Content::new_kline(
+                    content_str,
+                    &self.content,
+                    ticker_info,
+                    &self.settings,
+                    tick_size,
+                );
+
+                let streams = match basis {
+                    Basis::Time(timeframe) => vec![
+                        StreamKind::DepthAndTrades { exchange, ticker },
+                        StreamKind::Kline {
+                            exchange,
+                            ticker,
+                            timeframe,
+                            source: KlineSource::Last,
+                        },
+                    ],
+                    Basis::Tick(_) | Basis::Range(_) => {
+                        vec![StreamKind::DepthAndTrades { exchange, ticker }]
+                    }
+                };
+                Ok((content, streams))
+            }
+            "spread" => match self.settings.spread_leg.filter(|leg| leg.ticker != ticker) {
+                Some(leg) => {
+                    self.settings.tick_multiply = None;
+
+                    // bars of the two legs only line up when they're cut by time
+                    let timeframe = match self.settings.selected_basis {
+                        Some(Basis::Time(timeframe)) => timeframe,
+                        _ => chart_defaults().candlestick,
+                    };
+                    self.settings.selected_basis = Some(Basis::Time(timeframe));
+
+                    let content = Content::new_kline(
+                        content_str,
+                        &self.content,
+                        ticker_info,
+                        &self.settings,
+                        ticker_info.min_ticksize,
+                    );
+
+                    let streams = vec![
+                        StreamKind::Kline {
+                            exchange,
+                            ticker,
+                            timeframe,
+                            source: KlineSource::Last,
+                        },
+                        StreamKind::Kline {
+                            exchange: leg.exchange(),
+                            ticker: leg.ticker,
+                            timeframe,
+                            source: KlineSource::Last,
+                        },
+                    ];
+                    Ok((content, streams))
+                }
+                None => Err(DashboardError::PaneSet(
+                    "A spread needs a second ticker to combine with".to_string(),
+                )),
+            },
+            "tpo" => {
+                let tick_multiplier = Some(TickMultiplier(10));
+                self.settings.tick_multiply = tick_multiplier;
+                let tick_size = tick_multiplier.map_or(ticker_info.min_ticksize, |tm| {
+                    tm.multiply_with_min_tick_size(ticker_info)
+                });
+
+                // brackets are cut by time, bars of a tick basis have none to go by
+                let timeframe = match self.settings.selected_basis {
+                    Some(Basis::Time(timeframe)) => timeframe,
+                    _ => chart_defaults().tpo,
+                };
+                self.settings.selected_basis = Some(Basis::Time(timeframe));
+
+                let content = Content::new_kline(
+                    content_str,
+                    &self.content,
+                    ticker_info,
+                    &self.settings,
+                    tick_size,
+                );
+
+                let streams = vec![StreamKind::Kline {
+                    exchange,
+                    ticker,
+                    timeframe,
+                    source: KlineSource::Last,
+                }];
+                Ok((content, streams))
+            }
             "time&sales" => {
                 let config = self
                     .settings
@@ -225,6 +493,12 @@ impl State {
                 let streams = vec![StreamKind::DepthAndTrades { exchange, ticker }];
                 Ok((content, streams))
             }
+            "ladder" => {
+                let config = self.settings.visual_config.and_then(|cfg| cfg.ladder());
+                let content = Content::DomLadder(DomLadder::new(config, Some(ticker_info)));
+                let streams = vec![StreamKind::DepthAndTrades { exchange, ticker }];
+                Ok((content, streams))
+            }
             _ => Err(DashboardError::PaneSet(format!(
                 "A content must be set first."
             ))),
@@ -240,6 +514,73 @@ impl State {
         }
     }
 
+    /// Rebuilds the content empty with the same settings, dropping every datapoint it held
+    pub fn reset_content(&mut self) {
+        let Some(ticker_info) = self.settings.ticker_info else {
+            return;
+        };
+        let tick_size = self
+            .settings
+            .tick_multiply
+            .map_or(ticker_info.min_ticksize, |tm| {
+                tm.multiply_with_min_tick_size(ticker_info)
+            });
+
+        self.content = match &self.content {
+            Content::Heatmap(chart, _) if chart.snapshot_source().is_none() => {
+                Content::new_heatmap(&self.content, ticker_info, &self.settings, tick_size)
+            }
+            Content::Kline(_, _) => Content::new_kline(
+                &self.content.identifier_str(),
+                &self.content,
+                ticker_info,
+                &self.settings,
+                tick_size,
+            ),
+            Content::TimeAndSales(_) => {
+                let config = self
+                    .settings
+                    .visual_config
+                    .and_then(|cfg| cfg.time_and_sales());
+                Content::TimeAndSales(TimeAndSales::new(config, Some(ticker_info)))
+            }
+            Content::DomLadder(_) => {
+                let config = self.settings.visual_config.and_then(|cfg| cfg.ladder());
+                Content::DomLadder(DomLadder::new(config, Some(ticker_info)))
+            }
+            Content::Heatmap(_, _)
+            | Content::Watchlist(_)
+            | Content::WhaleTape(_)
+            | Content::Starter => return,
+        };
+    }
+
+    /// Turns the pane into a static viewer of an exported heatmap snapshot
+    pub fn open_snapshot(&mut self, file_name: String) -> Result<(), DashboardError> {
+        let snapshot =
+            Snapshot::load(&file_name).map_err(|err| DashboardError::PaneSet(err.to_string()))?;
+
+        self.settings.ticker_info = Some(snapshot.ticker_info);
+        self.settings.selected_basis = Some(snapshot.basis);
+
+        let indicators = vec![HeatmapIndicator::Volume];
+        let layout = ViewConfig {
+            splits: vec![],
+            autoscale: Some(data::chart::Autoscale::CenterLatest),
+            drawings: vec![],
+        };
+        let config = self.settings.visual_config.and_then(|cfg| cfg.heatmap());
+
+        self.content = Content::Heatmap(
+            HeatmapChart::from_snapshot(layout, &snapshot, file_name, &indicators, config, vec![]),
+            indicators,
+        );
+        self.streams.clear();
+        self.snapshots.clear();
+
+        Ok(())
+    }
+
     pub fn insert_oi_vec(&mut self, req_id: Option<uuid::Uuid>, oi: &[OpenInterest]) {
         match &mut self.content {
             Content::Kline(chart, _) => {
@@ -275,7 +616,8 @@ impl State {
                         indicators,
                         ticker_info,
                         chart.kind(),
-                    );
+                    )
+                    .with_visual_config(Some(chart.visual_config()));
                 }
             }
             _ => {
@@ -293,6 +635,10 @@ impl State {
         window: window::Id,
         main_window: &'a Window,
         timezone: UserTimezone,
+        streams_paused: bool,
+        imbalance_presets: &'a ImbalancePresets,
+        chart_themes: &'a ChartThemes,
+        funding: Option<FundingRate>,
     ) -> pane_grid::Content<'a, Message, Theme, Renderer> {
         let mut stream_info_element = if Content::Starter == self.content {
             row![]
@@ -303,7 +649,7 @@ impl State {
         };
 
         if let Some((exchange, ticker)) = self.stream_pair() {
-            let exchange_icon = icon_text(style::exchange_icon(exchange), 14);
+            let exchange_icon = style::exchange_icon_text(exchange, 14);
 
             let ticker_str = {
                 let symbol = ticker.display_symbol_and_type().0;
@@ -318,6 +664,14 @@ impl State {
                     .align_y(Vertical::Center)
                     .spacing(4),
             );
+
+            if let Some(funding) = funding.filter(|_| ticker.market_type() != MarketKind::Spot) {
+                stream_info_element = stream_info_element.push(widget::tooltip(
+                    text(funding_label(funding)).size(12),
+                    Some("Funding rate and time left to the next funding"),
+                    tooltip::Position::Bottom,
+                ));
+            }
         }
 
         let modifier: Option<modal::stream::Modifier> = self.modal.and_then(|m| {
@@ -340,16 +694,34 @@ impl State {
 
         let body = match &self.content {
             Content::Starter => {
+                let open_snapshot = button(text("Open heatmap snapshot").size(12))
+                    .on_press(Message::ListSnapshots(id))
+                    .style(|theme, status| style::button::transparent(theme, status, false));
+                let open_whale_tape = button(text("Open whale tape").size(12))
+                    .on_press(Message::OpenWhaleTape(id))
+                    .style(|theme, status| style::button::transparent(theme, status, false));
+
                 let base: Element<_> = widget::toast::Manager::new(
-                    center(text("select a ticker to start").size(16)),
+                    center(
+                        column![
+                            text("select a ticker to start").size(16),
+                            open_snapshot,
+                            open_whale_tape
+                        ]
+                        .spacing(8)
+                        .align_x(Alignment::Center),
+                    ),
                     &self.notifications,
                     Alignment::End,
                     move |msg| Message::DeleteNotification(id, msg),
+                    move |msg| Message::ToggleNotificationDetails(id, msg),
                 )
                 .into();
 
                 if let Some(Modal::LinkGroup) = self.modal {
                     link_group_modal(base, id, self.link_group)
+                } else if let Some(Modal::Snapshots) = self.modal {
+                    snapshots_modal(base, id, &self.snapshots)
                 } else if self.modal == Some(Modal::Controls) {
                     stack_modal(
                         base,
@@ -376,6 +748,38 @@ impl State {
 
                 self.compose_panel_view(base, id, compact_controls, settings_modal)
             }
+            Content::DomLadder(panel) => {
+                let base = panel::view(panel, timezone)
+                    .map(move |message| Message::PanelInteraction(id, message));
+
+                let settings_modal = || modal::pane::settings::ladder_cfg_view(panel.config, id);
+
+                self.compose_panel_view(base, id, compact_controls, settings_modal)
+            }
+            Content::Watchlist(watchlist) => {
+                stream_info_element = stream_info_element.push(text("Watchlist").size(14));
+
+                let base = watchlist
+                    .view()
+                    .map(move |message| Message::Watchlist(id, message));
+
+                // has no settings to show, its controls leave the button out
+                self.compose_panel_view(base, id, compact_controls, || column![].into())
+            }
+            Content::WhaleTape(tape) => {
+                stream_info_element = stream_info_element.push(text("Whale tape").size(14));
+
+                let base = tape
+                    .view()
+                    .map(move |message| Message::WhaleTape(id, message));
+
+                let settings_modal = || {
+                    tape.settings_view()
+                        .map(move |message| Message::WhaleTape(id, message))
+                };
+
+                self.compose_panel_view(base, id, compact_controls, settings_modal)
+            }
             Content::Heatmap(chart, indicators) => {
                 let selected_basis = self
                     .settings
@@ -386,16 +790,24 @@ impl State {
 
                 let base_ticksize = tick_multiply.base(chart.tick_size());
 
-                let modifiers = row![
-                    basis_modifier(id, selected_basis, modifier, kind),
-                    ticksize_modifier(id, base_ticksize, tick_multiply, modifier, kind),
-                ]
-                .spacing(4);
+                if let Some(file_name) = chart.snapshot_source() {
+                    stream_info_element =
+                        stream_info_element.push(text(format!("Snapshot: {file_name}")).size(13));
+                } else {
+                    let modifiers = row![
+                        basis_modifier(id, selected_basis, modifier, kind),
+                        ticksize_modifier(id, base_ticksize, tick_multiply, modifier, kind),
+                    ]
+                    .spacing(4);
 
-                stream_info_element = stream_info_element.push(modifiers);
+                    stream_info_element = stream_info_element.push(modifiers);
+                }
 
-                let base = chart::view(chart, indicators, timezone)
-                    .map(move |message| Message::ChartInteraction(id, message));
+                let base = themed(
+                    chart::view(chart, indicators, timezone, self.shows_axes())
+                        .map(move |message| Message::ChartInteraction(id, message)),
+                    chart_themes.get(ThemedChart::Heatmap),
+                );
                 let settings_modal = || {
                     heatmap_cfg_view(
                         chart.visual_config(),
@@ -406,13 +818,20 @@ impl State {
                     )
                 };
 
-                self.compose_chart_view(base, id, indicators, compact_controls, settings_modal)
+                self.compose_chart_view(
+                    base,
+                    id,
+                    indicators,
+                    compact_controls,
+                    settings_modal,
+                    timezone,
+                )
             }
             Content::Kline(chart, indicators) => {
                 let chart_kind = chart.kind();
 
                 match chart_kind {
-                    data::chart::KlineChartKind::Footprint { .. } => {
+                    data::chart::KlineChartKind::Footprint { studies, .. } => {
                         let selected_basis =
                             self.settings.selected_basis.unwrap_or(Timeframe::M5.into());
                         let tick_multiply =
@@ -424,6 +843,7 @@ impl State {
                         let modifiers = row![
                             basis_modifier(id, selected_basis, modifier, kind),
                             ticksize_modifier(id, base_ticksize, tick_multiply, modifier, kind),
+                            imbalance_preset_picker(id, studies, imbalance_presets),
                         ]
                         .spacing(4);
 
@@ -439,23 +859,89 @@ impl State {
                         let modifiers =
                             row![basis_modifier(id, selected_basis, modifier, kind),].spacing(4);
 
+                        stream_info_element = stream_info_element.push(modifiers);
+                    }
+                    data::chart::KlineChartKind::Spread(mode) => {
+                        let selected_basis = self
+                            .settings
+                            .selected_basis
+                            .unwrap_or(Timeframe::M15.into());
+                        let kind = ModifierKind::Candlestick(selected_basis);
+
+                        let mut modifiers =
+                            row![basis_modifier(id, selected_basis, modifier, kind),].spacing(4);
+
+                        if let Some(leg) = self.settings.spread_leg {
+                            let (symbol, _) = leg.ticker.display_symbol_and_type();
+                            modifiers = modifiers.push(
+                                text(format!("{mode} vs {symbol} ({})", leg.exchange())).size(13),
+                            );
+                        }
+
+                        stream_info_element = stream_info_element.push(modifiers);
+                    }
+                    data::chart::KlineChartKind::DeltaCandles => {
+                        let selected_basis =
+                            self.settings.selected_basis.unwrap_or(Timeframe::M5.into());
+                        let kind = ModifierKind::Candlestick(selected_basis);
+
+                        let modifiers =
+                            row![basis_modifier(id, selected_basis, modifier, kind),].spacing(4);
+
+                        stream_info_element = stream_info_element.push(modifiers);
+                    }
+                    data::chart::KlineChartKind::Tpo => {
+                        let selected_basis = self
+                            .settings
+                            .selected_basis
+                            .unwrap_or(Timeframe::M30.into());
+                        let tick_multiply =
+                            self.settings.tick_multiply.unwrap_or(TickMultiplier(10));
+                        // same basis and tick size controls as footprints
+                        let kind = ModifierKind::Footprint(selected_basis, tick_multiply);
+
+                        let base_ticksize = tick_multiply.base(chart.tick_size());
+
+                        let modifiers = row![
+                            basis_modifier(id, selected_basis, modifier, kind),
+                            ticksize_modifier(id, base_ticksize, tick_multiply, modifier, kind),
+                        ]
+                        .spacing(4);
+
                         stream_info_element = stream_info_element.push(modifiers);
                     }
                 }
 
-                let base = chart::view(chart, indicators, timezone)
-                    .map(move |message| Message::ChartInteraction(id, message));
+                let mut base = themed(
+                    chart::view(chart, indicators, timezone, self.shows_axes())
+                        .map(move |message| Message::ChartInteraction(id, message)),
+                    chart_themes.get(ThemedChart::from(chart_kind)),
+                );
+                if let (Some(cutoff), Some(bounds)) = (chart.replay_cutoff(), chart.replay_bounds())
+                {
+                    base = column![base, bar_replay_controls(id, cutoff, bounds, timezone)].into();
+                }
+
                 let settings_modal = || {
                     kline_cfg_view(
                         chart.study_configurator(),
-                        data::chart::kline::Config {},
+                        chart.visual_config(),
                         chart_kind,
                         id,
                         chart.basis(),
+                        imbalance_presets,
+                        self.settings.ticker_info.map(|info| info.market_type()),
                     )
                 };
 
-                self.compose_chart_view(base, id, indicators, compact_controls, settings_modal)
+                self.compose_chart_view(
+                    base,
+                    id,
+                    indicators,
+                    compact_controls,
+                    settings_modal,
+                    timezone,
+                )
             }
         };
 
@@ -473,12 +959,61 @@ impl State {
             Status::Stale(msg) => {
                 stream_info_element = stream_info_element.push(text(msg));
             }
+            Status::Reconnecting(Some(secs)) => {
+                stream_info_element =
+                    stream_info_element.push(text(format!("Reconnecting in {secs}s")));
+            }
+            Status::Reconnecting(None) => {
+                stream_info_element = stream_info_element.push(text("Gave up reconnecting"));
+            }
+            Status::TimedOut(info) => {
+                let what = match info {
+                    InfoType::FetchingKlines => "Klines",
+                    InfoType::FetchingTrades(_) => "Trades",
+                    InfoType::FetchingOI => "Open Interest",
+                };
+
+                stream_info_element = stream_info_element.push(
+                    row![
+                        text(format!("{what} fetch timed out")),
+                        button(text("Retry").size(12))
+                            .on_press(Message::RetryFetch(id))
+                            .style(|theme, status| style::button::transparent(
+                                theme, status, false
+                            )),
+                    ]
+                    .spacing(4)
+                    .align_y(Vertical::Center),
+                );
+            }
             Status::Ready => {}
         }
 
+        if let Some(failover) = &self.failover {
+            stream_info_element = stream_info_element.push(
+                container(
+                    text(format!("Failover from {}", failover.ticker_info.exchange())).size(11),
+                )
+                .padding(padding::left(4).right(4))
+                .style(style::failover_badge),
+            );
+        }
+
+        if streams_paused && !self.streams.is_empty() {
+            stream_info_element = stream_info_element.push(
+                container(text("Paused").size(11))
+                    .padding(padding::left(4).right(4))
+                    .style(style::paused_badge),
+            );
+        }
+
         let content = pane_grid::Content::new(body)
             .style(move |theme| style::pane_background(theme, is_focused));
 
+        if self.settings.clean_mode {
+            return content;
+        }
+
         let controls = {
             let compact_control = container(
                 button(text("...").size(13).align_y(Alignment::End))
@@ -488,7 +1023,7 @@ impl State {
                     }),
             )
             .align_y(Alignment::Center)
-            .height(Length::Fixed(32.0))
+            .height(style::dense(32.0))
             .padding(4);
 
             if self.modal == Some(Modal::Controls) {
@@ -506,7 +1041,7 @@ impl State {
                 .padding(padding::left(4).top(1))
                 .align_y(Vertical::Center)
                 .spacing(8)
-                .height(Length::Fixed(32.0)),
+                .height(style::dense(32.0)),
         )
         .controls(controls)
         .style(style::pane_title_bar);
@@ -541,7 +1076,7 @@ impl State {
         let tooltip_pos = tooltip::Position::Bottom;
         let mut buttons = row![];
 
-        if !matches!(&self.content, Content::Starter) {
+        if !matches!(&self.content, Content::Starter | Content::Watchlist(_)) {
             buttons = buttons.push(button_with_tooltip(
                 icon_text(Icon::Cog, 12),
                 Message::ShowModal(pane, Modal::Settings),
@@ -561,6 +1096,147 @@ impl State {
             ));
         }
 
+        if self.content.visible_timerange().is_some() {
+            buttons = buttons.push(button_with_tooltip(
+                icon_text(Icon::Return, 12),
+                Message::ShowModal(pane, Modal::GoTo),
+                Some("Go to date/time"),
+                tooltip_pos,
+                modal_btn_style(Modal::GoTo),
+            ));
+        }
+
+        if self.stream_pair().is_some() && self.content.visible_timerange().is_some() {
+            buttons = buttons.push(button_with_tooltip(
+                icon_text(Icon::Edit, 12),
+                Message::ShowModal(pane, Modal::Tag),
+                Some("Tag visible range"),
+                tooltip_pos,
+                modal_btn_style(Modal::Tag),
+            ));
+        }
+
+        if let Content::Heatmap(chart, _) = &self.content {
+            if chart.snapshot_source().is_none() {
+                buttons = buttons.push(button_with_tooltip(
+                    icon_text(Icon::Folder, 12),
+                    Message::ExportSnapshot(pane),
+                    Some("Export snapshot"),
+                    tooltip_pos,
+                    control_btn_style(false),
+                ));
+            }
+        }
+
+        let drawing_tool = match &self.content {
+            Content::Heatmap(chart, _) => Some(chart::drawing_tool(chart)),
+            Content::Kline(chart, _) => Some(chart::drawing_tool(chart)),
+            _ => None,
+        };
+
+        // drawings are pinned to timestamps, tick based bars have none to pin them to
+        if let (Some(active), Some(Basis::Time(_))) = (drawing_tool, self.settings.selected_basis) {
+            for (tool, label) in [
+                (DrawingTool::TrendLine, "/"),
+                (DrawingTool::HorizontalRay, "-"),
+                (DrawingTool::Rectangle, "[]"),
+            ] {
+                let is_active = active == Some(tool);
+
+                buttons = buttons.push(button_with_tooltip(
+                    text(label).size(11),
+                    Message::ChartInteraction(
+                        pane,
+                        chart::Message::DrawingToolSelected((!is_active).then_some(tool)),
+                    ),
+                    Some(match tool {
+                        DrawingTool::TrendLine => "Draw trendline",
+                        DrawingTool::HorizontalRay => "Draw horizontal ray",
+                        DrawingTool::Rectangle => "Draw rectangle",
+                    }),
+                    tooltip_pos,
+                    control_btn_style(is_active),
+                ));
+            }
+        }
+
+        if let Content::TimeAndSales(panel) = &self.content {
+            if panel.has_selection() {
+                buttons = buttons.push(button_with_tooltip(
+                    text("CSV").size(11),
+                    Message::ExportTrades(pane),
+                    Some("Export selected trades"),
+                    tooltip_pos,
+                    control_btn_style(false),
+                ));
+                buttons = buttons.push(button_with_tooltip(
+                    icon_text(Icon::Clone, 12),
+                    Message::PanelInteraction(pane, panel::Message::CopySelection),
+                    Some("Copy selected trades"),
+                    tooltip_pos,
+                    control_btn_style(false),
+                ));
+            }
+        }
+
+        if !matches!(&self.content, Content::Starter) {
+            buttons = buttons.push(button_with_tooltip(
+                text("Clean").size(11),
+                Message::ToggleCleanMode(pane),
+                Some(if cfg!(target_os = "macos") {
+                    "Hide the pane's chrome, Cmd+Shift+H brings it back"
+                } else {
+                    "Hide the pane's chrome, Ctrl+Shift+H brings it back"
+                }),
+                tooltip_pos,
+                control_btn_style(false),
+            ));
+        }
+
+        if matches!(&self.content, Content::Heatmap(_, _) | Content::Kline(_, _)) {
+            buttons = buttons.push(button_with_tooltip(
+                text("PNG").size(11),
+                Message::ExportImage(pane, ImageTarget::File),
+                Some("Save chart as PNG"),
+                tooltip_pos,
+                control_btn_style(false),
+            ));
+            buttons = buttons.push(button_with_tooltip(
+                icon_text(Icon::Clone, 12),
+                Message::ExportImage(pane, ImageTarget::Clipboard),
+                Some("Copy chart image"),
+                tooltip_pos,
+                control_btn_style(false),
+            ));
+        }
+
+        if self.depth_stream().is_some() {
+            buttons = buttons.push(button_with_tooltip(
+                text("Replay").size(11),
+                Message::ReplayRequested(pane),
+                Some("Replay stored trades"),
+                tooltip_pos,
+                control_btn_style(false),
+            ));
+        }
+
+        if let Content::Kline(chart, _) = &self.content {
+            if let Some((first, last, _)) = chart.replay_bounds() {
+                let is_replaying = chart.replay_cutoff().is_some();
+
+                buttons = buttons.push(button_with_tooltip(
+                    text("Bars").size(11),
+                    Message::BarReplayChanged(
+                        pane,
+                        (!is_replaying).then_some(first + (last - first) / 2),
+                    ),
+                    Some("Replay the loaded bars one by one"),
+                    tooltip_pos,
+                    control_btn_style(is_replaying),
+                ));
+            }
+        }
+
         if is_popout {
             buttons = buttons.push(button_with_tooltip(
                 icon_text(Icon::Popout, 12),
@@ -606,7 +1282,7 @@ impl State {
         buttons
             .padding(padding::right(4).left(4))
             .align_y(Vertical::Center)
-            .height(Length::Fixed(32.0))
+            .height(style::dense(32.0))
             .into()
     }
 
@@ -617,15 +1293,12 @@ impl State {
         indicators: &'a [impl Indicator],
         compact_controls: Option<Element<'a, Message>>,
         settings_modal: F,
+        timezone: UserTimezone,
     ) -> Element<'a, Message>
     where
         F: FnOnce() -> Element<'a, Message>,
     {
-        let base =
-            widget::toast::Manager::new(base, &self.notifications, Alignment::End, move |msg| {
-                Message::DeleteNotification(pane, msg)
-            })
-            .into();
+        let base = self.with_notifications(base, pane);
 
         let stack_padding = padding::right(12).left(12);
 
@@ -646,13 +1319,27 @@ impl State {
                 stack_padding,
                 Alignment::End,
             ),
-            Some(Modal::Settings) => stack_modal(
-                base,
-                settings_modal(),
-                Message::HideModal(pane),
-                stack_padding,
-                Alignment::End,
-            ),
+            Some(Modal::Settings) => {
+                let mut settings = column![
+                    settings_modal(),
+                    self.auto_snapshot_toggle(pane),
+                    self.failover_picker(pane)
+                ]
+                .spacing(4)
+                .align_x(Alignment::End);
+
+                if let Some(picker) = self.kline_source_picker(pane) {
+                    settings = settings.push(picker);
+                }
+
+                stack_modal(
+                    base,
+                    settings,
+                    Message::HideModal(pane),
+                    stack_padding,
+                    Alignment::End,
+                )
+            }
             Some(Modal::LinkGroup) => link_group_modal(base, pane, self.link_group),
             Some(Modal::Controls) => stack_modal(
                 base,
@@ -665,10 +1352,139 @@ impl State {
                 padding::left(12),
                 Alignment::End,
             ),
-            None => base,
+            Some(Modal::Tag) => tag_modal(base, pane, &self.tag_input),
+            Some(Modal::GoTo) => goto_modal(base, pane, &self.goto_input, timezone),
+            Some(Modal::Snapshots) | None => base,
         }
     }
 
+    /// Toasts stacked over the content, left out in clean mode so they don't end up in captures
+    fn with_notifications<'a>(
+        &'a self,
+        base: Element<'a, Message>,
+        pane: pane_grid::Pane,
+    ) -> Element<'a, Message> {
+        if self.settings.clean_mode {
+            return base;
+        }
+
+        widget::toast::Manager::new(
+            base,
+            &self.notifications,
+            Alignment::End,
+            move |msg| Message::DeleteNotification(pane, msg),
+            move |msg| Message::ToggleNotificationDetails(pane, msg),
+        )
+        .into()
+    }
+
+    fn auto_snapshot_toggle(&self, pane: pane_grid::Pane) -> Element<'_, Message> {
+        let mut options = column![].spacing(4);
+
+        // only charts have a canvas to cut the snapshot out of
+        if self.content.canvas_bounds().is_some() {
+            options = options.push(
+                iced::widget::checkbox("Scheduled snapshots", self.settings.auto_snapshot)
+                    .on_toggle(move |is_enabled| Message::ToggleAutoSnapshot(pane, is_enabled)),
+            );
+        }
+
+        options = options
+            .push(
+                iced::widget::checkbox(
+                    "Watermark exported images",
+                    self.settings.watermark_exports,
+                )
+                .on_toggle(move |is_enabled| Message::ToggleExportWatermark(pane, is_enabled)),
+            )
+            .push(
+                iced::widget::checkbox("Hide axes in clean mode", self.settings.clean_hides_axes)
+                    .on_toggle(move |is_enabled| Message::ToggleCleanAxes(pane, is_enabled)),
+            );
+
+        container(options)
+            .padding(style::dense(12.0))
+            .style(style::chart_modal)
+            .into()
+    }
+
+    fn failover_picker(&self, pane: pane_grid::Pane) -> Element<'_, Message> {
+        let primary = self
+            .failover
+            .as_ref()
+            .map(|failover| failover.ticker_info)
+            .or(self.settings.ticker_info)
+            .map(|info| info.exchange());
+
+        let options = primary.map_or_else(Vec::new, |primary| {
+            Exchange::ALL
+                .into_iter()
+                .filter(|exchange| {
+                    *exchange != primary && exchange.market_type() == primary.market_type()
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let mut picker = row![
+            text("Failover").size(13),
+            iced::widget::pick_list(options, self.settings.failover, move |exchange| {
+                Message::FailoverSelected(pane, Some(exchange))
+            })
+            .placeholder("None")
+            .text_size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        if self.settings.failover.is_some() {
+            picker = picker.push(
+                button(text("Clear").size(12))
+                    .on_press(Message::FailoverSelected(pane, None))
+                    .style(|theme, status| style::button::transparent(theme, status, false)),
+            );
+        }
+
+        container(picker)
+            .padding(style::dense(12.0))
+            .style(style::chart_modal)
+            .into()
+    }
+
+    /// Shown for candlestick charts of a market with more than one price to draw them from
+    fn kline_source_picker(&self, pane: pane_grid::Pane) -> Option<Element<'_, Message>> {
+        let (exchange, _) = self.stream_pair()?;
+
+        let is_candles = matches!(
+            self.content.chart_kind(),
+            Some(data::chart::KlineChartKind::Candles)
+        );
+        let options = KlineSource::ALL
+            .into_iter()
+            .filter(|source| source.is_supported(exchange))
+            .collect::<Vec<_>>();
+
+        if !is_candles || options.len() < 2 {
+            return None;
+        }
+
+        let picker = row![
+            text("Price").size(13),
+            iced::widget::pick_list(options, Some(self.kline_source()), move |source| {
+                Message::KlineSourceSelected(pane, source)
+            })
+            .text_size(12),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        Some(
+            container(picker)
+                .padding(style::dense(12.0))
+                .style(style::chart_modal)
+                .into(),
+        )
+    }
+
     fn compose_panel_view<'a, F>(
         &'a self,
         base: Element<'a, Message>,
@@ -679,18 +1495,16 @@ impl State {
     where
         F: FnOnce() -> Element<'a, Message>,
     {
-        let base: Element<_> =
-            widget::toast::Manager::new(base, &self.notifications, Alignment::End, move |msg| {
-                Message::DeleteNotification(pane, msg)
-            })
-            .into();
+        let base = self.with_notifications(base, pane);
 
         let stack_padding = padding::right(12).left(12);
 
         match self.modal {
             Some(Modal::Settings) => stack_modal(
                 base,
-                settings_modal(),
+                column![settings_modal(), self.failover_picker(pane)]
+                    .spacing(4)
+                    .align_x(Alignment::End),
                 Message::HideModal(pane),
                 stack_padding,
                 Alignment::End,
@@ -711,6 +1525,33 @@ impl State {
         }
     }
 
+    /// Kline streams of a spread chart's two legs, its own ticker's first, and how they combine
+    pub fn spread_legs(&self) -> Option<(StreamKind, StreamKind, data::chart::kline::SpreadMode)> {
+        let Content::Kline(chart, _) = &self.content else {
+            return None;
+        };
+        let data::chart::KlineChartKind::Spread(mode) = chart.kind() else {
+            return None;
+        };
+
+        let mut legs = self
+            .streams
+            .iter()
+            .filter(|stream| matches!(stream, StreamKind::Kline { .. }));
+
+        Some((*legs.next()?, *legs.next()?, *mode))
+    }
+
+    /// Price the pane's klines are built from, only candlestick charts go by anything but trades
+    pub fn kline_source(&self) -> KlineSource {
+        match (self.content.chart_kind(), self.stream_pair()) {
+            (Some(data::chart::KlineChartKind::Candles), Some((exchange, _))) => {
+                self.settings.kline_source.fallback_for(exchange)
+            }
+            _ => KlineSource::Last,
+        }
+    }
+
     pub fn matches_stream(&self, stream: &StreamKind) -> bool {
         self.streams.iter().any(|existing| existing == stream)
     }
@@ -720,7 +1561,8 @@ impl State {
             Content::Heatmap(chart, _) => chart.invalidate(Some(now)).map(Action::Chart),
             Content::Kline(chart, _) => chart.invalidate(Some(now)).map(Action::Chart),
             Content::TimeAndSales(panel) => panel.invalidate(Some(now)).map(Action::Panel),
-            Content::Starter => None,
+            Content::DomLadder(panel) => panel.invalidate(Some(now)).map(Action::Panel),
+            Content::Watchlist(_) | Content::WhaleTape(_) | Content::Starter => None,
         }
     }
 
@@ -729,7 +1571,12 @@ impl State {
             Content::Kline(_, _) => Some(1000),
             Content::Heatmap(chart, _) => chart.basis_interval(),
             Content::TimeAndSales(_) => Some(100),
-            Content::Starter => None,
+            // redrawn at the pace a heatmap of the ticker would be
+            Content::DomLadder(_) => match Basis::default_heatmap_time(self.settings.ticker_info) {
+                Basis::Time(interval) => Some(interval.into()),
+                Basis::Tick(_) | Basis::Range(_) => None,
+            },
+            Content::Watchlist(_) | Content::WhaleTape(_) | Content::Starter => None,
         }
     }
 
@@ -738,6 +1585,10 @@ impl State {
     }
 
     pub fn tick(&mut self, now: Instant) -> Option<Action> {
+        if let Content::Kline(chart, _) = &mut self.content {
+            chart.tick_countdown();
+        }
+
         let invalidate_interval: Option<u64> = self.update_interval();
         let last_tick: Option<Instant> = self.last_tick();
 
@@ -764,6 +1615,36 @@ impl State {
     pub fn unique_id(&self) -> uuid::Uuid {
         self.id
     }
+
+    /// Gives up on a fetch that didn't progress for `FETCH_TIMEOUT`, so the pane doesn't show
+    /// it loading forever when an endpoint stalls. Returns what timed out, if anything did
+    pub fn check_fetch_timeout(&mut self, now: Instant) -> Option<InfoType> {
+        let Status::Loading(info) = self.status else {
+            self.loading_since = None;
+            return None;
+        };
+
+        match self.loading_since {
+            // a trades fetch counts up with every batch, which resets the wait
+            Some((since, last_info)) if last_info == info => {
+                if now.duration_since(since) < FETCH_TIMEOUT {
+                    return None;
+                }
+
+                self.status = Status::TimedOut(info);
+                self.loading_since = None;
+
+                if let Content::Kline(chart, _) = &mut self.content {
+                    chart.reset_request_handler();
+                }
+                Some(info)
+            }
+            _ => {
+                self.loading_since = Some((now, info));
+                None
+            }
+        }
+    }
 }
 
 impl Default for State {
@@ -774,9 +1655,15 @@ impl Default for State {
             content: Content::Starter,
             settings: Settings::default(),
             streams: vec![],
-            notifications: vec![],
+            notifications: Notifications::default(),
             status: Status::Ready,
             link_group: None,
+            snapshots: vec![],
+            tag_input: String::new(),
+            goto_input: String::new(),
+            failover: None,
+            modal_before_clean: None,
+            loading_since: None,
         }
     }
 }
@@ -788,6 +1675,9 @@ pub enum Content {
     Heatmap(HeatmapChart, Vec<HeatmapIndicator>),
     Kline(KlineChart, Vec<KlineIndicator>),
     TimeAndSales(TimeAndSales),
+    DomLadder(DomLadder),
+    Watchlist(Watchlist),
+    WhaleTape(WhaleTape),
 }
 
 impl Content {
@@ -806,6 +1696,7 @@ impl Content {
                     ViewConfig {
                         splits: vec![],
                         autoscale: Some(data::chart::Autoscale::CenterLatest),
+                        drawings: vec![],
                     },
                     vec![],
                 )
@@ -831,7 +1722,7 @@ impl Content {
     }
 
     fn new_kline(
-        content_str: &str, // "footprint" or "candlestick"
+        content_str: &str, // "footprint", "candlestick", "tpo", "delta" or "spread"
         current_content: &Content,
         ticker_info: TickerInfo,
         settings: &Settings,
@@ -848,9 +1739,10 @@ impl Content {
                 (None, None, None)
             };
 
+        let defaults = chart_defaults();
         let (default_tf, determined_chart_kind) = match content_str {
             "footprint" => (
-                Timeframe::M5,
+                defaults.footprint,
                 prev_kind_opt
                     .filter(|k| matches!(k, data::chart::KlineChartKind::Footprint { .. }))
                     .unwrap_or_else(|| data::chart::KlineChartKind::Footprint {
@@ -858,15 +1750,36 @@ impl Content {
                         studies: vec![],
                     }),
             ),
+            "tpo" => (defaults.tpo, data::chart::KlineChartKind::Tpo),
+            "delta" => (defaults.delta, data::chart::KlineChartKind::DeltaCandles),
+            "spread" => (
+                defaults.candlestick,
+                prev_kind_opt
+                    .filter(|k| matches!(k, data::chart::KlineChartKind::Spread(_)))
+                    .unwrap_or_else(|| {
+                        let mode = settings.spread_leg.map_or_else(Default::default, |leg| {
+                            data::chart::kline::SpreadMode::default_for(
+                                ticker_info.market_type(),
+                                leg.market_type(),
+                            )
+                        });
+                        data::chart::KlineChartKind::Spread(mode)
+                    }),
+            ),
             _ => (
                 // "candlestick"
-                Timeframe::M15,
+                defaults.candlestick,
                 data::chart::KlineChartKind::Candles,
             ),
         };
 
         let basis = settings.selected_basis.unwrap_or(Basis::Time(default_tf));
 
+        let tick_size = match &determined_chart_kind {
+            data::chart::KlineChartKind::Spread(mode) => mode.tick_size(tick_size),
+            _ => tick_size,
+        };
+
         let enabled_indicators = {
             let available = KlineIndicator::for_market(ticker_info.market_type());
             prev_indis.map_or_else(
@@ -907,6 +1820,7 @@ impl Content {
             .unwrap_or(ViewConfig {
                 splits,
                 autoscale: Some(data::chart::Autoscale::FitToVisible),
+                drawings: vec![],
             });
 
         Content::Kline(
@@ -919,7 +1833,8 @@ impl Content {
                 &enabled_indicators,
                 Some(ticker_info),
                 &determined_chart_kind,
-            ),
+            )
+            .with_visual_config(settings.visual_config.and_then(|cfg| cfg.kline())),
             enabled_indicators,
         )
     }
@@ -929,7 +1844,20 @@ impl Content {
             Content::Heatmap(chart, _) => Some(chart.last_update()),
             Content::Kline(chart, _) => Some(chart.last_update()),
             Content::TimeAndSales(panel) => Some(panel.last_update()),
-            Content::Starter => None,
+            Content::DomLadder(panel) => Some(panel.last_update()),
+            Content::Watchlist(_) | Content::WhaleTape(_) | Content::Starter => None,
+        }
+    }
+
+    /// Time range visible on a time based chart
+    pub fn visible_timerange(&self) -> Option<(u64, u64)> {
+        match self {
+            Content::Heatmap(chart, _) => Some(chart.visible_timerange()),
+            Content::Kline(chart, _) => match chart.basis() {
+                Basis::Time(_) => Some(chart.visible_timerange()),
+                Basis::Tick(_) | Basis::Range(_) => None,
+            },
+            _ => None,
         }
     }
 
@@ -945,6 +1873,7 @@ impl Content {
             Content::Heatmap(chart, indicators) => {
                 let indicator = match indicator_str {
                     "Volume" => HeatmapIndicator::Volume,
+                    "Imbalance" => HeatmapIndicator::Imbalance,
                     _ => {
                         panic!("heatmap indicator requested to toggle not found: {indicator_str}",);
                     }
@@ -962,6 +1891,9 @@ impl Content {
                 let indicator = match indicator_str {
                     "Volume" => KlineIndicator::Volume,
                     "Open Interest" => KlineIndicator::OpenInterest,
+                    "Relative Volume" => KlineIndicator::RelativeVolume,
+                    "RSI" => KlineIndicator::Rsi,
+                    "EMA" => KlineIndicator::Ema,
                     _ => {
                         panic!("kline indicator requested to toggle not found: {indicator_str}",);
                     }
@@ -975,7 +1907,11 @@ impl Content {
 
                 chart.toggle_indicator(indicator);
             }
-            Content::Starter | Content::TimeAndSales(_) => {
+            Content::Starter
+            | Content::TimeAndSales(_)
+            | Content::DomLadder(_)
+            | Content::Watchlist(_)
+            | Content::WhaleTape(_) => {
                 panic!("indicator toggle on {} pane", self)
             }
         }
@@ -985,7 +1921,11 @@ impl Content {
         match self {
             Content::Heatmap(_, indicator) => column_drag::reorder_vec(indicator, event),
             Content::Kline(_, indicator) => column_drag::reorder_vec(indicator, event),
-            Content::TimeAndSales(_) | Content::Starter => {
+            Content::TimeAndSales(_)
+            | Content::DomLadder(_)
+            | Content::Watchlist(_)
+            | Content::WhaleTape(_)
+            | Content::Starter => {
                 panic!("indicator reorder on {} pane", self)
             }
         }
@@ -999,6 +1939,12 @@ impl Content {
             (Content::TimeAndSales(panel), VisualConfig::TimeAndSales(cfg)) => {
                 panel.config = cfg;
             }
+            (Content::DomLadder(panel), VisualConfig::Ladder(cfg)) => {
+                panel.set_config(cfg);
+            }
+            (Content::Kline(chart, _), VisualConfig::Kline(cfg)) => {
+                chart.set_visual_config(cfg);
+            }
             _ => {}
         }
     }
@@ -1007,7 +1953,10 @@ impl Content {
         match &self {
             Content::Heatmap(chart, _) => Some(data::chart::Study::Heatmap(chart.studies.clone())),
             Content::Kline(chart, _) => chart.studies().map(data::chart::Study::Footprint),
-            Content::TimeAndSales(_) => None,
+            Content::TimeAndSales(_)
+            | Content::DomLadder(_)
+            | Content::Watchlist(_)
+            | Content::WhaleTape(_) => None,
             Content::Starter => None,
         }
     }
@@ -1024,6 +1973,75 @@ impl Content {
         }
     }
 
+    pub fn study_clipboard(&self) -> Option<data::chart::StudyClipboard> {
+        let clusters = match self {
+            Content::Kline(chart, _) => match chart.kind() {
+                data::chart::KlineChartKind::Footprint { clusters, .. } => Some(*clusters),
+                data::chart::KlineChartKind::Candles
+                | data::chart::KlineChartKind::Tpo
+                | data::chart::KlineChartKind::DeltaCandles
+                | data::chart::KlineChartKind::Spread(_) => None,
+            },
+            _ => None,
+        };
+
+        self.studies()
+            .map(|studies| data::chart::StudyClipboard { studies, clusters })
+    }
+
+    /// Applies copied studies, `false` if they were copied from a different kind of chart
+    pub fn paste_studies(&mut self, copied: data::chart::StudyClipboard) -> bool {
+        match (self, copied.studies) {
+            (Content::Heatmap(chart, _), data::chart::Study::Heatmap(studies)) => {
+                chart.studies = studies;
+                chart.invalidate(None);
+                true
+            }
+            (Content::Kline(chart, _), data::chart::Study::Footprint(studies))
+                if chart.studies().is_some() =>
+            {
+                chart.set_studies(studies);
+                if let Some(clusters) = copied.clusters {
+                    chart.set_cluster_kind(clusters);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Where the chart's canvas sits in its window, `None` for panels which have no canvas
+    pub fn canvas_bounds(&self) -> Option<iced::Rectangle> {
+        match self {
+            Content::Heatmap(chart, _) => Some(chart::canvas_bounds(chart)),
+            Content::Kline(chart, _) => Some(chart::canvas_bounds(chart)),
+            Content::TimeAndSales(_)
+            | Content::DomLadder(_)
+            | Content::Watchlist(_)
+            | Content::WhaleTape(_)
+            | Content::Starter => None,
+        }
+    }
+
+    /// Puts a chart in or out of its PNG export look, `None` for panels which have no canvas
+    pub fn set_export(&mut self, export: Option<Option<String>>) -> Option<iced::Rectangle> {
+        match self {
+            Content::Heatmap(chart, _) => {
+                chart::set_export(chart, export);
+                Some(chart::canvas_bounds(chart))
+            }
+            Content::Kline(chart, _) => {
+                chart::set_export(chart, export);
+                Some(chart::canvas_bounds(chart))
+            }
+            Content::TimeAndSales(_)
+            | Content::DomLadder(_)
+            | Content::Watchlist(_)
+            | Content::WhaleTape(_)
+            | Content::Starter => None,
+        }
+    }
+
     pub fn identifier_str(&self) -> String {
         match self {
             Content::Starter => "starter".to_string(),
@@ -1031,8 +2049,14 @@ impl Content {
             Content::Kline(chart, _) => match chart.kind() {
                 data::chart::KlineChartKind::Footprint { .. } => "footprint".to_string(),
                 data::chart::KlineChartKind::Candles => "candlestick".to_string(),
+                data::chart::KlineChartKind::Tpo => "tpo".to_string(),
+                data::chart::KlineChartKind::DeltaCandles => "delta".to_string(),
+                data::chart::KlineChartKind::Spread(_) => "spread".to_string(),
             },
             Content::TimeAndSales(_) => "time&sales".to_string(),
+            Content::DomLadder(_) => "ladder".to_string(),
+            Content::Watchlist(_) => "watchlist".to_string(),
+            Content::WhaleTape(_) => "whale tape".to_string(),
         }
     }
 }
@@ -1045,8 +2069,14 @@ impl std::fmt::Display for Content {
             Content::Kline(chart, _) => match chart.kind() {
                 data::chart::KlineChartKind::Footprint { .. } => write!(f, "Footprint chart"),
                 data::chart::KlineChartKind::Candles => write!(f, "Candlestick chart"),
+                data::chart::KlineChartKind::Tpo => write!(f, "TPO chart"),
+                data::chart::KlineChartKind::DeltaCandles => write!(f, "Delta candles chart"),
+                data::chart::KlineChartKind::Spread(_) => write!(f, "Spread chart"),
             },
             Content::TimeAndSales(_) => write!(f, "Time&Sales"),
+            Content::DomLadder(_) => write!(f, "DOM Ladder"),
+            Content::Watchlist(_) => write!(f, "Watchlist"),
+            Content::WhaleTape(_) => write!(f, "Whale tape"),
         }
     }
 }
@@ -1058,11 +2088,72 @@ impl PartialEq for Content {
             (Content::Heatmap(_, _), Content::Heatmap(_, _)) => true,
             (Content::Kline(_, _), Content::Kline(_, _)) => true,
             (Content::TimeAndSales(_), Content::TimeAndSales(_)) => true,
+            (Content::DomLadder(_), Content::DomLadder(_)) => true,
+            (Content::Watchlist(_), Content::Watchlist(_)) => true,
+            (Content::WhaleTape(_), Content::WhaleTape(_)) => true,
             _ => false,
         }
     }
 }
 
+/// Scrub slider and step buttons shown under a chart while its bars are replayed
+fn bar_replay_controls<'a>(
+    pane: pane_grid::Pane,
+    cutoff: u64,
+    (first, last, interval): (u64, u64, u64),
+    timezone: UserTimezone,
+) -> Element<'a, Message> {
+    let step_button = |label: &'static str, bars: i64| {
+        button(text(label).size(11))
+            .on_press(Message::StepBarReplay(pane, bars))
+            .style(|theme, status| style::button::transparent(theme, status, false))
+    };
+
+    let scrub = iced::widget::slider(first as f64..=last as f64, cutoff as f64, move |time| {
+        Message::BarReplayChanged(pane, Some(time as u64))
+    })
+    .step(interval as f64);
+
+    container(
+        row![
+            step_button("<", -1),
+            scrub,
+            step_button(">", 1),
+            text(timezone.format_crosshair_timestamp(cutoff as i64, interval)).size(11),
+            button(text("Exit").size(11))
+                .on_press(Message::BarReplayChanged(pane, None))
+                .style(|theme, status| style::button::transparent(theme, status, false)),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center),
+    )
+    .padding(padding::left(4).right(4))
+    .into()
+}
+
+/// Draws the chart in its own theme when one is assigned to its kind
+/// Rate in percent and the time left to the next funding, as `+0.0100% 02:13:45`
+fn funding_label(funding: FundingRate) -> String {
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let remaining_seconds = funding.next_funding_time.saturating_sub(now) / 1000;
+
+    let hours = remaining_seconds / 3600;
+    let minutes = (remaining_seconds % 3600) / 60;
+    let seconds = remaining_seconds % 60;
+
+    format!(
+        "{:+.4}% {hours:02}:{minutes:02}:{seconds:02}",
+        funding.rate * 100.0
+    )
+}
+
+fn themed<'a>(base: Element<'a, Message>, theme: Option<&data::Theme>) -> Element<'a, Message> {
+    match theme {
+        Some(theme) => iced::widget::themer(theme.0.clone(), base).into(),
+        None => base,
+    }
+}
+
 fn link_group_modal<'a>(
     base: Element<'a, Message>,
     pane: pane_grid::Pane,
@@ -1076,7 +2167,9 @@ fn link_group_modal<'a>(
 
         for &group in row_groups {
             let is_selected = selected_group == Some(group);
-            let btn_content = text(group.to_string()).font(style::AZERET_MONO);
+            let btn_content = text(group.to_string())
+                .font(style::chart_font())
+                .color(style::link_group_color(group));
 
             let btn = if is_selected {
                 button_with_tooltip(
@@ -1101,7 +2194,7 @@ fn link_group_modal<'a>(
 
     let content: Element<_> = container(grid)
         .max_width(240)
-        .padding(16)
+        .padding(style::dense(16.0))
         .style(style::chart_modal)
         .into();
 
@@ -1114,6 +2207,131 @@ fn link_group_modal<'a>(
     )
 }
 
+fn goto_modal<'a>(
+    base: Element<'a, Message>,
+    pane: pane_grid::Pane,
+    input: &'a str,
+    timezone: UserTimezone,
+) -> Element<'a, Message> {
+    let parsed = timezone
+        .parse_datetime(input)
+        .and_then(|time| u64::try_from(time).ok());
+
+    let mut input_field = iced::widget::text_input("YYYY-MM-DD HH:MM", input)
+        .on_input(move |value| Message::GoToInputChanged(pane, value));
+    let mut go_btn = button(text("Go"));
+
+    if let Some(time) = parsed {
+        input_field = input_field.on_submit(Message::GoToTime(pane, time));
+        go_btn = go_btn.on_press(Message::GoToTime(pane, time));
+    }
+
+    let content: Element<_> = container(
+        column![
+            text(format!("Go to date/time ({timezone})")).size(14),
+            row![input_field, go_btn].spacing(4),
+        ]
+        .spacing(8),
+    )
+    .max_width(280)
+    .padding(style::dense(16.0))
+    .style(style::chart_modal)
+    .into();
+
+    stack_modal(
+        base,
+        content,
+        Message::HideModal(pane),
+        padding::right(12).left(12),
+        Alignment::End,
+    )
+}
+
+fn tag_modal<'a>(
+    base: Element<'a, Message>,
+    pane: pane_grid::Pane,
+    input: &'a str,
+) -> Element<'a, Message> {
+    let mut presets = column![].spacing(4);
+
+    for label in data::journal::PRESET_LABELS {
+        presets = presets.push(
+            button(text(label).size(13))
+                .width(Length::Fill)
+                .on_press(Message::AddTag(pane, label.to_string()))
+                .style(move |theme, status| style::button::menu_body(theme, status, false)),
+        );
+    }
+
+    let custom = {
+        let mut add_btn = button(text("Add"));
+        if !input.trim().is_empty() {
+            add_btn = add_btn.on_press(Message::AddTag(pane, input.trim().to_string()));
+        }
+
+        row![
+            iced::widget::text_input("Custom label", input)
+                .on_input(move |value| Message::TagInputChanged(pane, value))
+                .on_submit(Message::AddTag(pane, input.trim().to_string())),
+            add_btn,
+        ]
+        .spacing(4)
+    };
+
+    let content: Element<_> =
+        container(column![text("Tag visible range").size(14), presets, custom].spacing(8))
+            .max_width(240)
+            .padding(style::dense(16.0))
+            .style(style::chart_modal)
+            .into();
+
+    stack_modal(
+        base,
+        content,
+        Message::HideModal(pane),
+        padding::right(12).left(12),
+        Alignment::End,
+    )
+}
+
+fn snapshots_modal<'a>(
+    base: Element<'a, Message>,
+    pane: pane_grid::Pane,
+    snapshots: &'a [String],
+) -> Element<'a, Message> {
+    let list: Element<_> = if snapshots.is_empty() {
+        text("No snapshots exported yet").size(13).into()
+    } else {
+        let mut list = column![].spacing(4);
+
+        for file_name in snapshots {
+            list = list.push(
+                button(text(file_name).size(13))
+                    .width(Length::Fill)
+                    .on_press(Message::OpenSnapshot(pane, file_name.clone()))
+                    .style(move |theme, status| style::button::menu_body(theme, status, false)),
+            );
+        }
+
+        iced::widget::scrollable(list).into()
+    };
+
+    let content: Element<_> = container(list)
+        .max_width(320)
+        .max_height(360)
+        .padding(style::dense(16.0))
+        .style(style::chart_modal)
+        .into();
+
+    stack_modal(
+        base,
+        content,
+        Message::HideModal(pane),
+        padding::right(12).left(12),
+        Alignment::Center,
+    )
+}
+
 fn ticksize_modifier<'a>(
     id: pane_grid::Pane,
     base_ticksize: f32,
@@ -1156,3 +2374,24 @@ fn basis_modifier<'a>(
         .on_press(Message::ShowModal(id, modifier_modal))
         .into()
 }
+
+/// Switches the footprint's imbalance study between the saved presets
+fn imbalance_preset_picker<'a>(
+    id: pane_grid::Pane,
+    studies: &[FootprintStudy],
+    presets: &'a ImbalancePresets,
+) -> Element<'a, Message> {
+    if presets.presets.is_empty() {
+        return row![].into();
+    }
+
+    iced::widget::pick_list(
+        presets.presets.as_slice(),
+        presets.matching(studies).cloned(),
+        move |preset| Message::ImbalancePresetSelected(id, preset),
+    )
+    .placeholder("Imbalance")
+    .text_size(12)
+    .padding([2, 6])
+    .into()
+}
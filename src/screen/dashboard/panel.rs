@@ -1,4 +1,7 @@
+pub mod ladder;
 pub mod timeandsales;
+pub mod watchlist;
+pub mod whale_tape;
 
 use iced::{
     Element, padding,
@@ -11,6 +14,8 @@ pub enum Message {
     Scrolled(f32),
     ResetScroll,
     Invalidate(Option<Instant>),
+    Select(Option<(usize, usize)>),
+    CopySelection,
 }
 
 pub enum Action {}
@@ -21,6 +26,8 @@ pub trait Panel: canvas::Program<Message> {
     fn reset_scroll(&mut self);
 
     fn invalidate(&mut self, now: Option<Instant>) -> Option<Action>;
+
+    fn select(&mut self, _selection: Option<(usize, usize)>) {}
 }
 
 pub fn view<T: Panel>(panel: &T, _timezone: data::UserTimezone) -> Element<Message> {
@@ -44,5 +51,10 @@ pub fn update<T: Panel>(panel: &mut T, message: Message) {
         Message::Invalidate(now) => {
             panel.invalidate(now);
         }
+        Message::Select(selection) => {
+            panel.select(selection);
+        }
+        // needs the clipboard, handled by the dashboard
+        Message::CopySelection => {}
     }
 }
@@ -0,0 +1,420 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Instant;
+
+use super::Message;
+use crate::style;
+pub use data::chart::ladder::Config;
+use data::util::{abbr_large_numbers, count_decimals};
+use exchange::{TickerInfo, Trade, depth::Depth};
+use ordered_float::OrderedFloat;
+
+use iced::widget::canvas::{self, Text};
+use iced::{Alignment, Event, Point, Rectangle, Renderer, Size, Theme, mouse};
+
+const TEXT_SIZE: iced::Pixels = iced::Pixels(11.0);
+const HEADER_HEIGHT: f32 = 16.0;
+const ROW_HEIGHT: f32 = 16.0;
+
+/// Left edges of the bids, price, asks and traded volume columns, as a share of the width
+const BIDS_X: f32 = 0.0;
+const PRICE_X: f32 = 0.28;
+const ASKS_X: f32 = 0.5;
+const VOLUME_X: f32 = 0.78;
+
+impl super::Panel for DomLadder {
+    fn scroll(&mut self, delta: f32) {
+        self.scroll_offset += delta;
+        self.invalidate(Some(Instant::now()));
+    }
+
+    fn reset_scroll(&mut self) {
+        self.scroll_offset = 0.0;
+        self.invalidate(Some(Instant::now()));
+    }
+
+    fn invalidate(&mut self, now: Option<Instant>) -> Option<super::Action> {
+        self.invalidate(now)
+    }
+}
+
+/// Vertical price ladder of the resting orderbook around the last traded price, with the
+/// volume traded at each level over the configured window
+pub struct DomLadder {
+    depth: Depth,
+    recent_trades: VecDeque<Trade>,
+    /// Buy and sell volume of the recent trades, by row
+    traded: BTreeMap<i64, (f32, f32)>,
+    last_trade: Option<Trade>,
+    ticker_info: Option<TickerInfo>,
+    tick_size: f32,
+    pub config: Config,
+    cache: canvas::Cache,
+    last_tick: Instant,
+    scroll_offset: f32,
+}
+
+impl DomLadder {
+    pub fn new(config: Option<Config>, ticker_info: Option<TickerInfo>) -> Self {
+        let config = config.unwrap_or_default();
+
+        Self {
+            depth: Depth::default(),
+            recent_trades: VecDeque::new(),
+            traded: BTreeMap::new(),
+            last_trade: None,
+            tick_size: ticker_info.map_or(1.0, |info| {
+                config.tick_multiply.multiply_with_min_tick_size(info)
+            }),
+            ticker_info,
+            config,
+            cache: canvas::Cache::default(),
+            last_tick: Instant::now(),
+            scroll_offset: 0.0,
+        }
+    }
+
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+
+        if let Some(ticker_info) = self.ticker_info {
+            self.tick_size = config
+                .tick_multiply
+                .multiply_with_min_tick_size(ticker_info);
+        }
+
+        self.expire_trades();
+        self.rebuild_traded();
+        self.invalidate(None);
+    }
+
+    pub fn insert_datapoint(&mut self, trades_buffer: &[Trade], depth: &Depth) {
+        // replayed trades come without an orderbook, the last one received stays up meanwhile
+        if !depth.bids.is_empty() || !depth.asks.is_empty() {
+            self.depth.clone_from(depth);
+        }
+
+        for trade in trades_buffer {
+            let row = self.trade_row(trade.price);
+            let volume = self.traded.entry(row).or_default();
+            if trade.is_sell {
+                volume.1 += trade.qty;
+            } else {
+                volume.0 += trade.qty;
+            }
+            self.recent_trades.push_back(*trade);
+        }
+
+        if let Some(last) = trades_buffer.last() {
+            self.last_trade = Some(*last);
+        }
+
+        self.expire_trades();
+    }
+
+    fn expire_trades(&mut self) {
+        let Some(latest) = self.recent_trades.back().map(|trade| trade.time) else {
+            return;
+        };
+        let window = u64::from(self.config.volume_window_mins) * 60_000;
+
+        while let Some(trade) = self.recent_trades.front().copied() {
+            if latest.saturating_sub(trade.time) <= window {
+                break;
+            }
+            self.recent_trades.pop_front();
+
+            let row = self.trade_row(trade.price);
+            if let Some(volume) = self.traded.get_mut(&row) {
+                if trade.is_sell {
+                    volume.1 = (volume.1 - trade.qty).max(0.0);
+                } else {
+                    volume.0 = (volume.0 - trade.qty).max(0.0);
+                }
+
+                if volume.0 <= f32::EPSILON && volume.1 <= f32::EPSILON {
+                    self.traded.remove(&row);
+                }
+            }
+        }
+    }
+
+    fn rebuild_traded(&mut self) {
+        self.traded.clear();
+
+        for trade in &self.recent_trades {
+            let row = (trade.price / self.tick_size).round() as i64;
+            let volume = self.traded.entry(row).or_default();
+            if trade.is_sell {
+                volume.1 += trade.qty;
+            } else {
+                volume.0 += trade.qty;
+            }
+        }
+    }
+
+    fn trade_row(&self, price: f32) -> i64 {
+        (price / self.tick_size).round() as i64
+    }
+
+    /// Row the ladder centers on, the last traded price or the mid of the book before any trade
+    fn center_row(&self) -> Option<i64> {
+        if let Some(trade) = self.last_trade {
+            return Some(self.trade_row(trade.price));
+        }
+
+        let best_bid = self.depth.bids.keys().next_back()?;
+        let best_ask = self.depth.asks.keys().next()?;
+
+        Some(self.trade_row((best_bid.0 + best_ask.0) / 2.0))
+    }
+
+    /// Resting quantity of each side grouped into the rows between `bottom` and `top`
+    fn grouped_depth(&self, bottom: i64, top: i64) -> (HashMap<i64, f32>, HashMap<i64, f32>) {
+        let low = OrderedFloat((bottom - 1) as f32 * self.tick_size);
+        let high = OrderedFloat((top + 1) as f32 * self.tick_size);
+
+        let mut bids = HashMap::new();
+        for (price, qty) in self.depth.bids.range(low..=high) {
+            let row = (price.0 / self.tick_size).floor() as i64;
+            if (bottom..=top).contains(&row) {
+                *bids.entry(row).or_default() += qty;
+            }
+        }
+
+        let mut asks = HashMap::new();
+        for (price, qty) in self.depth.asks.range(low..=high) {
+            let row = (price.0 / self.tick_size).ceil() as i64;
+            if (bottom..=top).contains(&row) {
+                *asks.entry(row).or_default() += qty;
+            }
+        }
+
+        (bids, asks)
+    }
+
+    pub fn last_update(&self) -> Instant {
+        self.last_tick
+    }
+
+    pub fn invalidate(&mut self, now: Option<Instant>) -> Option<super::Action> {
+        self.cache.clear();
+        if let Some(now) = now {
+            self.last_tick = now;
+        }
+        None
+    }
+}
+
+impl canvas::Program<Message> for DomLadder {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: &iced::Event,
+        bounds: iced::Rectangle,
+        cursor: iced_core::mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        cursor.position_in(bounds)?;
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Middle)) => {
+                Some(canvas::Action::publish(Message::ResetScroll).and_capture())
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let scroll_amount = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => *y * ROW_HEIGHT * 3.0,
+                    mouse::ScrollDelta::Pixels { y, .. } => *y,
+                };
+
+                Some(canvas::Action::publish(Message::Scrolled(scroll_amount)).and_capture())
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let Some(center_row) = self.center_row() else {
+            return vec![];
+        };
+
+        let palette = theme.extended_palette();
+
+        let content = self.cache.draw(renderer, bounds.size(), |frame| {
+            let width = bounds.width;
+
+            let visible_rows = ((bounds.height - HEADER_HEIGHT) / ROW_HEIGHT).ceil() as i64;
+            let offset_rows = (self.scroll_offset / ROW_HEIGHT).round() as i64;
+
+            let top = center_row + visible_rows / 2 + offset_rows;
+            let bottom = top - visible_rows + 1;
+
+            let (bids, asks) = self.grouped_depth(bottom, top);
+
+            let max_qty = bids
+                .values()
+                .chain(asks.values())
+                .fold(0.0_f32, |max, qty| max.max(*qty));
+            let max_traded = self
+                .traded
+                .range(bottom..=top)
+                .fold(0.0_f32, |max, (_, (buy, sell))| max.max(buy + sell));
+
+            let decimals = count_decimals(self.tick_size);
+
+            let create_text =
+                |content: String, x: f32, y: f32, align_x: Alignment, color: iced::Color| Text {
+                    content,
+                    position: Point { x, y },
+                    size: TEXT_SIZE,
+                    font: style::chart_font(),
+                    color,
+                    align_x: align_x.into(),
+                    align_y: Alignment::Center.into(),
+                    ..Default::default()
+                };
+
+            let header_y = HEADER_HEIGHT / 2.0;
+            let header_color = palette.background.base.text.scale_alpha(0.6);
+            for (label, x, align_x) in [
+                ("Bids", PRICE_X - 0.02, Alignment::End),
+                ("Price", (PRICE_X + ASKS_X) / 2.0, Alignment::Center),
+                ("Asks", ASKS_X + 0.02, Alignment::Start),
+                ("Volume", 0.98, Alignment::End),
+            ] {
+                frame.fill_text(create_text(
+                    label.to_string(),
+                    width * x,
+                    header_y,
+                    align_x,
+                    header_color,
+                ));
+            }
+
+            let last_trade_row = self.last_trade.map(|trade| self.trade_row(trade.price));
+
+            for (i, row) in (bottom..=top).rev().enumerate() {
+                let y = HEADER_HEIGHT + i as f32 * ROW_HEIGHT;
+                let text_y = y + ROW_HEIGHT / 2.0;
+
+                if Some(row) == last_trade_row {
+                    frame.fill_rectangle(
+                        Point { x: 0.0, y },
+                        Size {
+                            width,
+                            height: ROW_HEIGHT,
+                        },
+                        palette.background.strong.color.scale_alpha(0.6),
+                    );
+                }
+
+                if let Some(qty) = bids.get(&row) {
+                    let bar_width = (PRICE_X - BIDS_X) * width * (qty / max_qty);
+                    frame.fill_rectangle(
+                        Point {
+                            x: PRICE_X * width - bar_width,
+                            y,
+                        },
+                        Size {
+                            width: bar_width,
+                            height: ROW_HEIGHT - 1.0,
+                        },
+                        palette.success.weak.color.scale_alpha(0.5),
+                    );
+                    frame.fill_text(create_text(
+                        abbr_large_numbers(*qty),
+                        width * (PRICE_X - 0.02),
+                        text_y,
+                        Alignment::End,
+                        palette.background.base.text,
+                    ));
+                }
+
+                if let Some(qty) = asks.get(&row) {
+                    let bar_width = (VOLUME_X - ASKS_X) * width * (qty / max_qty);
+                    frame.fill_rectangle(
+                        Point {
+                            x: ASKS_X * width,
+                            y,
+                        },
+                        Size {
+                            width: bar_width,
+                            height: ROW_HEIGHT - 1.0,
+                        },
+                        palette.danger.weak.color.scale_alpha(0.5),
+                    );
+                    frame.fill_text(create_text(
+                        abbr_large_numbers(*qty),
+                        width * (ASKS_X + 0.02),
+                        text_y,
+                        Alignment::Start,
+                        palette.background.base.text,
+                    ));
+                }
+
+                if let Some((buy, sell)) = self.traded.get(&row) {
+                    let column_width = (1.0 - VOLUME_X) * width;
+                    let buy_width = column_width * (buy / max_traded);
+                    let sell_width = column_width * (sell / max_traded);
+
+                    frame.fill_rectangle(
+                        Point {
+                            x: VOLUME_X * width,
+                            y,
+                        },
+                        Size {
+                            width: buy_width,
+                            height: ROW_HEIGHT - 1.0,
+                        },
+                        palette.success.base.color.scale_alpha(0.4),
+                    );
+                    frame.fill_rectangle(
+                        Point {
+                            x: VOLUME_X * width + buy_width,
+                            y,
+                        },
+                        Size {
+                            width: sell_width,
+                            height: ROW_HEIGHT - 1.0,
+                        },
+                        palette.danger.base.color.scale_alpha(0.4),
+                    );
+                    frame.fill_text(create_text(
+                        abbr_large_numbers(buy + sell),
+                        width * 0.98,
+                        text_y,
+                        Alignment::End,
+                        palette.background.base.text,
+                    ));
+                }
+
+                let price_color = match self.last_trade {
+                    Some(trade) if Some(row) == last_trade_row => {
+                        if trade.is_sell {
+                            palette.danger.base.color
+                        } else {
+                            palette.success.base.color
+                        }
+                    }
+                    _ => palette.background.base.text.scale_alpha(0.8),
+                };
+                frame.fill_text(create_text(
+                    format!("{:.*}", decimals, row as f32 * self.tick_size),
+                    width * (PRICE_X + ASKS_X) / 2.0,
+                    text_y,
+                    Alignment::Center,
+                    price_color,
+                ));
+            }
+        });
+
+        vec![content]
+    }
+}
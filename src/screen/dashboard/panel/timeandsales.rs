@@ -3,7 +3,7 @@ use std::time::Instant;
 use super::Message;
 use crate::style;
 pub use data::chart::timeandsales::Config;
-use data::chart::timeandsales::TradeDisplay;
+use data::chart::timeandsales::{PrintSize, TradeDisplay};
 use data::config::theme::{darken, lighten};
 use exchange::adapter::MarketKind;
 use exchange::{TickerInfo, Trade};
@@ -47,6 +47,11 @@ impl super::Panel for TimeAndSales {
     fn invalidate(&mut self, now: Option<Instant>) -> Option<super::Action> {
         self.invalidate(now)
     }
+
+    fn select(&mut self, selection: Option<(usize, usize)>) {
+        self.selection = selection;
+        self.invalidate(None);
+    }
 }
 
 pub struct TimeAndSales {
@@ -59,6 +64,14 @@ pub struct TimeAndSales {
     cache: canvas::Cache,
     last_tick: Instant,
     scroll_offset: f32,
+    /// Anchor and end of the selected rows, as indices into `recent_trades`
+    selection: Option<(usize, usize)>,
+}
+
+#[derive(Default)]
+pub struct SelectState {
+    modifiers: iced::keyboard::Modifiers,
+    is_dragging: bool,
 }
 
 impl TimeAndSales {
@@ -73,11 +86,12 @@ impl TimeAndSales {
             cache: canvas::Cache::default(),
             last_tick: Instant::now(),
             scroll_offset: 0.0,
+            selection: None,
         }
     }
 
     pub fn insert_buffer(&mut self, trades_buffer: &[Trade]) {
-        let size_filter = self.config.trade_size_filter;
+        let config = self.config;
 
         let market_type = match self.ticker_info {
             Some(ref ticker_info) => ticker_info.market_type(),
@@ -96,18 +110,14 @@ impl TimeAndSales {
                 (trade.time % 1000) as u32 * 1_000_000,
             ) {
                 let converted_trade = TradeDisplay {
+                    time: trade.time,
                     time_str: trade_time.format("%M:%S.%3f").to_string(),
                     price: trade.price,
                     qty: trade.qty,
                     is_sell: trade.is_sell,
                 };
 
-                let trade_size = match market_type {
-                    MarketKind::InversePerps => converted_trade.qty,
-                    _ => converted_trade.qty * converted_trade.price,
-                };
-
-                if trade_size >= size_filter {
+                if config.shows(&converted_trade, market_type) {
                     self.max_filtered_qty = self.max_filtered_qty.max(converted_trade.qty);
                 }
 
@@ -123,21 +133,64 @@ impl TimeAndSales {
 
                 self.max_filtered_qty = self.recent_trades[drain_amount..]
                     .iter()
-                    .filter(|t| {
-                        let trade_size = match market_type {
-                            MarketKind::InversePerps => t.qty,
-                            _ => t.qty * t.price,
-                        };
-                        trade_size >= size_filter
-                    })
+                    .filter(|t| config.shows(t, market_type))
                     .map(|t| t.qty)
                     .fold(0.0, f32::max);
 
                 self.recent_trades.drain(0..drain_amount);
+
+                self.selection = self.selection.and_then(|(anchor, end)| {
+                    Some((
+                        anchor.checked_sub(drain_amount)?,
+                        end.checked_sub(drain_amount)?,
+                    ))
+                });
             }
         }
     }
 
+    fn passes_filter(&self, trade: &TradeDisplay) -> bool {
+        let market_type = self
+            .ticker_info
+            .map_or(MarketKind::Spot, |info| info.market_type());
+
+        self.config.shows(trade, market_type)
+    }
+
+    /// Index into `recent_trades` of the row drawn at `y`, counting from the panel's top
+    fn row_at(&self, y: f32) -> Option<usize> {
+        let offset = y + self.scroll_offset - HISTOGRAM_HEIGHT;
+        if offset < 0.0 {
+            return None;
+        }
+
+        self.recent_trades
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| self.passes_filter(t))
+            .rev()
+            .nth((offset / TRADE_ROW_HEIGHT) as usize)
+            .map(|(idx, _)| idx)
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.selection.is_some()
+    }
+
+    /// Selected trades passing the filters, in chronological order
+    pub fn selected_trades(&self) -> Vec<&TradeDisplay> {
+        let Some((anchor, end)) = self.selection else {
+            return vec![];
+        };
+
+        self.recent_trades
+            .iter()
+            .take(anchor.max(end) + 1)
+            .skip(anchor.min(end))
+            .filter(|t| self.passes_filter(t))
+            .collect()
+    }
+
     pub fn last_update(&self) -> Instant {
         self.last_tick
     }
@@ -152,15 +205,25 @@ impl TimeAndSales {
 }
 
 impl canvas::Program<Message> for TimeAndSales {
-    type State = ();
+    type State = SelectState;
 
     fn update(
         &self,
-        _state: &mut Self::State,
+        state: &mut Self::State,
         event: &iced::Event,
         bounds: iced::Rectangle,
         cursor: iced_core::mouse::Cursor,
     ) -> Option<canvas::Action<Message>> {
+        match event {
+            Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = *modifiers;
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                state.is_dragging = false;
+            }
+            _ => {}
+        }
+
         let cursor_position = cursor.position_in(bounds)?;
 
         let paused_box = Rectangle {
@@ -178,10 +241,20 @@ impl canvas::Program<Message> for TimeAndSales {
                     }
                     mouse::Button::Left => {
                         if self.is_paused && paused_box.contains(cursor_position) {
-                            Some(canvas::Action::publish(Message::ResetScroll).and_capture())
-                        } else {
-                            None
+                            return Some(
+                                canvas::Action::publish(Message::ResetScroll).and_capture(),
+                            );
                         }
+
+                        let selection =
+                            self.row_at(cursor_position.y)
+                                .map(|row| match self.selection {
+                                    Some((anchor, _)) if state.modifiers.shift() => (anchor, row),
+                                    _ => (row, row),
+                                });
+                        state.is_dragging = selection.is_some();
+
+                        Some(canvas::Action::publish(Message::Select(selection)).and_capture())
                     }
                     _ => None,
                 },
@@ -194,6 +267,17 @@ impl canvas::Program<Message> for TimeAndSales {
                     Some(canvas::Action::publish(Message::Scrolled(scroll_amount)).and_capture())
                 }
                 mouse::Event::CursorMoved { .. } => {
+                    if state.is_dragging {
+                        if let (Some((anchor, end)), Some(row)) =
+                            (self.selection, self.row_at(cursor_position.y))
+                        {
+                            return (row != end).then(|| {
+                                canvas::Action::publish(Message::Select(Some((anchor, row))))
+                                    .and_capture()
+                            });
+                        }
+                    }
+
                     if self.is_paused {
                         let now = Some(Instant::now());
                         Some(canvas::Action::publish(Message::Invalidate(now)).and_capture())
@@ -203,6 +287,21 @@ impl canvas::Program<Message> for TimeAndSales {
                 }
                 _ => None,
             },
+            Event::Keyboard(iced::keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                match key.as_ref() {
+                    iced::keyboard::Key::Character("c")
+                        if modifiers.command() && self.selection.is_some() =>
+                    {
+                        Some(canvas::Action::publish(Message::CopySelection).and_capture())
+                    }
+                    iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape)
+                        if self.selection.is_some() =>
+                    {
+                        Some(canvas::Action::publish(Message::Select(None)).and_capture())
+                    }
+                    _ => None,
+                }
+            }
             _ => None,
         }
     }
@@ -272,16 +371,23 @@ impl canvas::Program<Message> for TimeAndSales {
             let start_index = (row_scroll_offset / row_height).floor() as usize;
             let visible_rows = (bounds.height / row_height).ceil() as usize;
 
+            let selected = self
+                .selection
+                .map(|(anchor, end)| anchor.min(end)..=anchor.max(end));
+
+            // only the venue's name, the market is the same for every row
+            let venue = self.config.show_exchange.then(|| {
+                self.ticker_info
+                    .map(|info| info.exchange().to_string())
+                    .and_then(|name| name.split_whitespace().next().map(str::to_string))
+                    .unwrap_or_default()
+            });
+
             let trades_to_draw = self
                 .recent_trades
                 .iter()
-                .filter(|t| {
-                    let trade_size = match market_type {
-                        MarketKind::InversePerps => t.qty,
-                        _ => t.qty * t.price,
-                    };
-                    trade_size >= self.config.trade_size_filter
-                })
+                .enumerate()
+                .filter(|(_, t)| self.config.shows(t, market_type))
                 .rev()
                 .skip(start_index)
                 .take(visible_rows + 2);
@@ -291,13 +397,13 @@ impl canvas::Program<Message> for TimeAndSales {
                     content,
                     position,
                     size: TEXT_SIZE,
-                    font: style::AZERET_MONO,
+                    font: style::chart_font(),
                     color,
                     align_x: align_x.into(),
                     ..Default::default()
                 };
 
-            for (i, trade) in trades_to_draw.enumerate() {
+            for (i, (idx, trade)) in trades_to_draw.enumerate() {
                 let y_position =
                     content_top_y + HISTOGRAM_HEIGHT + ((start_index + i) as f32 * row_height);
 
@@ -313,10 +419,16 @@ impl canvas::Program<Message> for TimeAndSales {
 
                 let bg_color_alpha = (trade.qty / self.max_filtered_qty).clamp(0.02, 1.0);
 
-                let mut text_color = if palette.is_dark {
-                    lighten(bg_color, bg_color_alpha.max(0.1))
-                } else {
-                    darken(bg_color, (bg_color_alpha * 0.8).max(0.1))
+                let print_size = self.config.print_size(trade, market_type);
+
+                let mut text_color = match print_size {
+                    PrintSize::Huge => palette.warning.strong.color,
+                    PrintSize::Large | PrintSize::Regular if palette.is_dark => {
+                        lighten(bg_color, bg_color_alpha.max(0.1))
+                    }
+                    PrintSize::Large | PrintSize::Regular => {
+                        darken(bg_color, (bg_color_alpha * 0.8).max(0.1))
+                    }
                 };
 
                 if is_scroll_paused && y_position < HISTOGRAM_HEIGHT + (TRADE_ROW_HEIGHT * 0.8) {
@@ -335,38 +447,64 @@ impl canvas::Program<Message> for TimeAndSales {
                     bg_color.scale_alpha(bg_color_alpha.min(0.9)),
                 );
 
-                let trade_time = create_text(
-                    trade.time_str.clone(),
-                    Point {
-                        x: row_width * 0.1,
-                        y: y_position,
-                    },
-                    Alignment::Start,
-                    text_color,
-                );
-                frame.fill_text(trade_time);
+                if selected.as_ref().is_some_and(|range| range.contains(&idx)) {
+                    frame.fill_rectangle(
+                        Point {
+                            x: 0.0,
+                            y: y_position,
+                        },
+                        Size {
+                            width: row_width,
+                            height: row_height,
+                        },
+                        palette.primary.weak.color.scale_alpha(0.5),
+                    );
+                }
 
-                let trade_price = create_text(
-                    trade.price.to_string(),
-                    Point {
-                        x: row_width * 0.67,
-                        y: y_position,
-                    },
-                    Alignment::End,
-                    text_color,
-                );
-                frame.fill_text(trade_price);
+                // marker at the row's edge, so large prints stand out while scanning the tape
+                if print_size != PrintSize::Regular {
+                    frame.fill_rectangle(
+                        Point {
+                            x: 0.0,
+                            y: y_position,
+                        },
+                        Size {
+                            width: 3.0,
+                            height: row_height,
+                        },
+                        palette.warning.base.color,
+                    );
+                }
 
-                let trade_qty = create_text(
-                    data::util::abbr_large_numbers(trade.qty),
-                    Point {
-                        x: row_width * 0.9,
-                        y: y_position,
-                    },
-                    Alignment::End,
-                    text_color,
-                );
-                frame.fill_text(trade_qty);
+                let mut columns = vec![];
+                if let Some(venue) = &venue {
+                    columns.push((venue.clone(), Alignment::Start));
+                }
+                columns.push((trade.time_str.clone(), Alignment::Start));
+                columns.push((trade.price.to_string(), Alignment::End));
+                columns.push((data::util::abbr_large_numbers(trade.qty), Alignment::End));
+                if self.config.show_notional {
+                    columns.push((
+                        data::util::abbr_large_numbers(trade.notional(market_type)),
+                        Alignment::End,
+                    ));
+                }
+
+                let column_width = (row_width * 0.9) / columns.len() as f32;
+
+                for (i, (content, align_x)) in columns.into_iter().enumerate() {
+                    let x = match align_x {
+                        Alignment::Start => row_width * 0.05 + column_width * i as f32,
+                        _ => row_width * 0.05 + column_width * (i + 1) as f32,
+                    };
+
+                    frame.fill_text(create_text(
+                        content,
+                        Point { x, y: y_position },
+                        align_x,
+                        text_color,
+                    ));
+                }
             }
 
             if is_scroll_paused {
@@ -411,7 +549,7 @@ impl canvas::Program<Message> for TimeAndSales {
                         y: pause_box_y + (pause_box_height / 2.0),
                     },
                     size: 12.0.into(),
-                    font: style::AZERET_MONO,
+                    font: style::chart_font(),
                     color: palette.background.strong.text,
                     align_x: Alignment::Center.into(),
                     align_y: Alignment::Center.into(),
@@ -425,7 +563,7 @@ impl canvas::Program<Message> for TimeAndSales {
 
     fn mouse_interaction(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
         bounds: iced::Rectangle,
         cursor: iced_core::mouse::Cursor,
     ) -> iced_core::mouse::Interaction {
@@ -442,6 +580,10 @@ impl canvas::Program<Message> for TimeAndSales {
             }
         }
 
+        if state.is_dragging {
+            return mouse::Interaction::Text;
+        }
+
         mouse::Interaction::default()
     }
 }
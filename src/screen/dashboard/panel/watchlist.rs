@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::style;
+use exchange::{Kline, Ticker, TickerInfo, TickerStats, adapter::Exchange};
+
+use iced::widget::canvas::{self, Path, Stroke};
+use iced::widget::{
+    button, canvas as canvas_widget, center, column, container, horizontal_space, row, scrollable,
+    text,
+};
+use iced::{Alignment, Element, Length, Point, Rectangle, Renderer, Theme, mouse};
+
+/// Prices and daily changes come with the exchange's ticker stats, one request covers a market
+const STATS_INTERVAL: Duration = Duration::from_secs(10);
+/// Sparklines only span the last day of hourly closes, they go stale slowly
+const SPARKLINE_INTERVAL: Duration = Duration::from_secs(300);
+
+const SPARKLINE_WIDTH: f32 = 72.0;
+const ROW_HEIGHT: f32 = 24.0;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+    Open(TickerInfo),
+    Remove(Ticker),
+}
+
+/// What the watchlist needs fetched to stay current
+#[derive(Default)]
+pub struct DueFetches {
+    pub stats: Vec<Exchange>,
+    pub sparklines: Vec<TickerInfo>,
+}
+
+/// Tickers picked by the user, each listed with its last price, daily change and a sparkline
+/// of the past day
+pub struct Watchlist {
+    tickers: Vec<TickerInfo>,
+    stats: HashMap<Ticker, TickerStats>,
+    sparklines: HashMap<Ticker, Vec<f32>>,
+    last_stats_fetch: Option<Instant>,
+    last_sparkline_fetch: Option<Instant>,
+}
+
+impl Watchlist {
+    pub fn new(tickers: Vec<TickerInfo>) -> Self {
+        Self {
+            tickers,
+            stats: HashMap::new(),
+            sparklines: HashMap::new(),
+            last_stats_fetch: None,
+            last_sparkline_fetch: None,
+        }
+    }
+
+    pub fn tickers(&self) -> &[TickerInfo] {
+        &self.tickers
+    }
+
+    /// Adds the ticker to the end of the list, its stats get fetched on the next tick
+    pub fn add(&mut self, ticker_info: TickerInfo) {
+        if self
+            .tickers
+            .iter()
+            .any(|info| info.ticker == ticker_info.ticker)
+        {
+            return;
+        }
+
+        self.tickers.push(ticker_info);
+        self.last_stats_fetch = None;
+    }
+
+    pub fn remove(&mut self, ticker: Ticker) {
+        self.tickers.retain(|info| info.ticker != ticker);
+        self.stats.remove(&ticker);
+        self.sparklines.remove(&ticker);
+    }
+
+    /// Markets whose stats and tickers whose sparklines are due a refresh, marking them as fetched
+    pub fn due_fetches(&mut self, now: Instant) -> DueFetches {
+        let mut due = DueFetches::default();
+
+        if self
+            .last_stats_fetch
+            .is_none_or(|last| now.duration_since(last) >= STATS_INTERVAL)
+        {
+            for info in &self.tickers {
+                let exchange = info.exchange();
+                if !due.stats.contains(&exchange) {
+                    due.stats.push(exchange);
+                }
+            }
+            self.last_stats_fetch = Some(now);
+        }
+
+        let sparklines_stale = self
+            .last_sparkline_fetch
+            .is_none_or(|last| now.duration_since(last) >= SPARKLINE_INTERVAL);
+
+        due.sparklines = self
+            .tickers
+            .iter()
+            .filter(|info| sparklines_stale || !self.sparklines.contains_key(&info.ticker))
+            .copied()
+            .collect();
+
+        if sparklines_stale {
+            self.last_sparkline_fetch = Some(now);
+        }
+        // an empty line marks the fetch as in flight, so it isn't requested again every tick
+        for info in &due.sparklines {
+            self.sparklines.entry(info.ticker).or_default();
+        }
+
+        due
+    }
+
+    pub fn set_stats(&mut self, exchange: Exchange, mut stats: HashMap<Ticker, TickerStats>) {
+        for info in self
+            .tickers
+            .iter()
+            .filter(|info| info.exchange() == exchange)
+        {
+            if let Some(ticker_stats) = stats.remove(&info.ticker) {
+                self.stats.insert(info.ticker, ticker_stats);
+            }
+        }
+    }
+
+    pub fn set_sparkline(&mut self, ticker: Ticker, klines: &[Kline]) {
+        if self.tickers.iter().any(|info| info.ticker == ticker) {
+            self.sparklines
+                .insert(ticker, klines.iter().map(|kline| kline.close).collect());
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        if self.tickers.is_empty() {
+            return center(
+                text("Select tickers from the sidebar to add them to the watchlist").size(12),
+            )
+            .into();
+        }
+
+        let mut list = column![].spacing(2);
+
+        for info in &self.tickers {
+            let (symbol, market) = info.ticker.display_symbol_and_type();
+            let stats = self.stats.get(&info.ticker);
+
+            let price = stats.map_or("-".to_string(), |stats| {
+                data::util::format_with_commas(stats.mark_price)
+            });
+            let change = stats.map(|stats| stats.daily_price_chg);
+
+            let change_text = text(change.map_or(String::new(), data::util::pct_change))
+                .size(12)
+                .width(60)
+                .align_x(Alignment::End)
+                .style(move |theme: &Theme| text::Style {
+                    color: change.map(|change| change_color(theme, change)),
+                });
+
+            let sparkline: Element<_> = match self.sparklines.get(&info.ticker) {
+                Some(closes) if closes.len() > 1 => canvas_widget(Sparkline { closes, change })
+                    .width(SPARKLINE_WIDTH)
+                    .height(ROW_HEIGHT - 6.0)
+                    .into(),
+                _ => horizontal_space().width(SPARKLINE_WIDTH).into(),
+            };
+
+            let remove = button(style::icon_text(style::Icon::Close, 10))
+                .on_press(Message::Remove(info.ticker))
+                .style(|theme, status| style::button::transparent(theme, status, false));
+
+            let ticker_row = row![
+                style::exchange_icon_text(info.exchange(), 12),
+                text(format!("{symbol} {market}")).size(12),
+                horizontal_space(),
+                text(price).size(12),
+                change_text,
+                sparkline,
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center);
+
+            list = list.push(
+                row![
+                    button(ticker_row)
+                        .on_press(Message::Open(*info))
+                        .width(Length::Fill)
+                        .height(ROW_HEIGHT)
+                        .padding([2, 4])
+                        .style(|theme, status| style::button::transparent(theme, status, false)),
+                    remove,
+                ]
+                .align_y(Alignment::Center),
+            );
+        }
+
+        container(scrollable(list))
+            .padding(4)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+fn change_color(theme: &Theme, change: f32) -> iced::Color {
+    let palette = theme.extended_palette();
+
+    if change >= 0.0 {
+        palette.success.base.color
+    } else {
+        palette.danger.base.color
+    }
+}
+
+/// Line through a ticker's closes, scaled to fill its bounds
+struct Sparkline<'a> {
+    closes: &'a [f32],
+    change: Option<f32>,
+}
+
+impl canvas::Program<Message> for Sparkline<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+
+        let (low, high) = self
+            .closes
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(low, high), close| {
+                (low.min(*close), high.max(*close))
+            });
+        let span = (high - low).max(f32::EPSILON);
+        let step = bounds.width / (self.closes.len() - 1) as f32;
+
+        let line = Path::new(|builder| {
+            for (index, close) in self.closes.iter().enumerate() {
+                let position = Point::new(
+                    index as f32 * step,
+                    bounds.height - (close - low) / span * bounds.height,
+                );
+
+                if index == 0 {
+                    builder.move_to(position);
+                } else {
+                    builder.line_to(position);
+                }
+            }
+        });
+
+        let color = match self.change {
+            Some(change) => change_color(theme, change),
+            None => theme.extended_palette().secondary.strong.color,
+        };
+        frame.stroke(
+            &line,
+            Stroke::with_color(
+                Stroke {
+                    width: 1.0,
+                    ..Default::default()
+                },
+                color,
+            ),
+        );
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+
+use crate::style;
+use crate::widget::labeled_slider;
+use data::util::{abbr_large_numbers, format_with_commas};
+use exchange::{Ticker, Trade, adapter::MarketKind, adapter::StreamKind};
+
+use iced::widget::{button, column, container, horizontal_space, row, scrollable, text};
+use iced::{Alignment, Element, Length, Theme, padding};
+
+/// Minimum notional of a print for a freshly opened tape
+pub const DEFAULT_THRESHOLD: f32 = 100_000.0;
+
+/// Prints kept on the tape, older ones drop off the bottom
+const MAX_PRINTS: usize = 300;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+    ThresholdChanged(f32),
+    ResetCounters,
+}
+
+struct Print {
+    time_str: String,
+    ticker: Ticker,
+    price: f32,
+    notional: f32,
+    is_sell: bool,
+}
+
+#[derive(Default)]
+struct Counter {
+    count: usize,
+    notional: f32,
+}
+
+/// Trades above a notional threshold from every stream the dashboard is subscribed to
+pub struct WhaleTape {
+    pub threshold: f32,
+    prints: VecDeque<Print>,
+    buys: Counter,
+    sells: Counter,
+}
+
+impl WhaleTape {
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            prints: VecDeque::new(),
+            buys: Counter::default(),
+            sells: Counter::default(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::ThresholdChanged(threshold) => self.threshold = threshold,
+            Message::ResetCounters => {
+                self.buys = Counter::default();
+                self.sells = Counter::default();
+            }
+        }
+    }
+
+    pub fn insert_trades(&mut self, stream: &StreamKind, trades: &[Trade]) {
+        let (_, ticker) = stream.exchange_and_ticker();
+
+        for trade in trades {
+            let notional = match ticker.market_type() {
+                MarketKind::InversePerps => trade.qty,
+                MarketKind::Spot | MarketKind::LinearPerps => trade.qty * trade.price,
+            };
+
+            if notional < self.threshold {
+                continue;
+            }
+
+            let counter = if trade.is_sell {
+                &mut self.sells
+            } else {
+                &mut self.buys
+            };
+            counter.count += 1;
+            counter.notional += notional;
+
+            let time_str = chrono::DateTime::from_timestamp_millis(trade.time as i64)
+                .map(|time| time.format("%H:%M:%S").to_string())
+                .unwrap_or_default();
+
+            self.prints.push_front(Print {
+                time_str,
+                ticker,
+                price: trade.price,
+                notional,
+                is_sell: trade.is_sell,
+            });
+        }
+
+        self.prints.truncate(MAX_PRINTS);
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let counter = |label: &str, side: &Counter, is_sell: bool| {
+            text(format!(
+                "{label} {} · ${}",
+                side.count,
+                abbr_large_numbers(side.notional)
+            ))
+            .size(12)
+            .style(move |theme: &Theme| text::Style {
+                color: Some(side_color(theme, is_sell)),
+            })
+        };
+
+        let header = row![
+            counter("Buys", &self.buys, false),
+            counter("Sells", &self.sells, true),
+            horizontal_space(),
+            button(text("Reset").size(11))
+                .on_press(Message::ResetCounters)
+                .style(|theme, status| style::button::transparent(theme, status, false)),
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center);
+
+        let mut list = column![].spacing(2);
+        for print in &self.prints {
+            let (symbol, _) = print.ticker.display_symbol_and_type();
+            let is_sell = print.is_sell;
+
+            list = list.push(
+                row![
+                    text(&print.time_str).size(11).width(56),
+                    style::exchange_icon_text(print.ticker.exchange, 11),
+                    text(symbol).size(11),
+                    horizontal_space(),
+                    text(format_with_commas(print.price)).size(11),
+                    text(format!("${}", abbr_large_numbers(print.notional)))
+                        .size(11)
+                        .width(64)
+                        .align_x(Alignment::End)
+                        .style(move |theme: &Theme| text::Style {
+                            color: Some(side_color(theme, is_sell)),
+                        }),
+                ]
+                .spacing(6)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        if self.prints.is_empty() {
+            list = list.push(
+                text(format!(
+                    "Waiting for trades above ${} on the open streams",
+                    format_with_commas(self.threshold)
+                ))
+                .size(12),
+            );
+        }
+
+        column![header, scrollable(list).height(Length::Fill)]
+            .spacing(8)
+            .padding(padding::all(8))
+            .into()
+    }
+
+    pub fn settings_view(&self) -> Element<'_, Message> {
+        let slider = labeled_slider(
+            "Notional",
+            10_000.0..=2_000_000.0,
+            self.threshold,
+            Message::ThresholdChanged,
+            |value| format!(">${}", format_with_commas(*value)),
+            Some(10_000.0),
+        );
+
+        container(column![text("Minimum print size").size(14), slider].spacing(8))
+            .width(Length::Shrink)
+            .max_width(320)
+            .padding(style::dense(28.0))
+            .style(style::chart_modal)
+            .into()
+    }
+}
+
+fn side_color(theme: &Theme, is_sell: bool) -> iced::Color {
+    let palette = theme.extended_palette();
+
+    if is_sell {
+        palette.danger.base.color
+    } else {
+        palette.success.base.color
+    }
+}
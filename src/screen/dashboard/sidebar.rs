@@ -2,42 +2,71 @@ use super::tickers_table::{self, TickersTable};
 use crate::{
     TooltipPosition,
     layout::SavedState,
-    style::{Icon, icon_text},
+    style::{self, Icon, icon_text},
     widget::button_with_tooltip,
 };
-use data::sidebar;
+use data::sidebar::{self, TableColumn};
 
 use iced::{
-    Alignment, Element, Length, Subscription, Task,
+    Alignment, Element, Length, Subscription, Task, mouse,
     widget::responsive,
-    widget::{Space, column, row},
+    widget::{Space, column, container, mouse_area, row},
 };
 
 #[derive(Debug, Clone)]
 pub enum Message {
     ToggleSidebarMenu(Option<sidebar::Menu>),
     SetSidebarPosition(sidebar::Position),
+    SetAutoHide(bool),
+    Reveal(bool),
+    ToggleReveal,
+    ToggleStreamsPaused,
     TickersTable(super::tickers_table::Message),
+    ResizeStarted(ResizeTarget),
+    ResizeMoved(f32),
+    ResizeEnded,
+}
+
+/// Edges of the tickers table that can be dragged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResizeTarget {
+    Table,
+    Column(TableColumn),
+}
+
+struct Resizing {
+    target: ResizeTarget,
+    /// Cursor position of the last move, the first one of a drag only sets it
+    last_x: Option<f32>,
 }
 
 pub struct Sidebar {
     pub state: data::Sidebar,
     tickers_table: TickersTable,
+    is_revealed: bool,
+    resizing: Option<Resizing>,
 }
 
 pub enum Action {
     TickerSelected(exchange::TickerInfo, Option<String>),
     ErrorOccurred(data::InternalError),
+    ToggleStreamsPaused,
 }
 
 impl Sidebar {
     pub fn new(state: &SavedState) -> (Self, Task<Message>) {
-        let (tickers_table, initial_fetch) = TickersTable::new(state.favorited_tickers.clone());
+        let (tickers_table, initial_fetch) = TickersTable::new(
+            state.favorited_tickers.clone(),
+            state.recent_tickers.clone(),
+            state.ticker_hotkeys.clone(),
+        );
 
         (
             Self {
                 state: state.sidebar,
                 tickers_table,
+                is_revealed: false,
+                resizing: None,
             },
             initial_fetch.map(Message::TickersTable),
         )
@@ -51,6 +80,48 @@ impl Sidebar {
             Message::SetSidebarPosition(position) => {
                 self.state.position = position;
             }
+            Message::SetAutoHide(is_enabled) => {
+                self.state.auto_hide = is_enabled;
+                self.is_revealed = false;
+            }
+            Message::Reveal(is_revealed) => {
+                self.is_revealed = is_revealed;
+            }
+            Message::ToggleReveal => {
+                self.is_revealed = !self.is_revealed;
+            }
+            Message::ToggleStreamsPaused => {
+                return (Task::none(), Some(Action::ToggleStreamsPaused));
+            }
+            Message::ResizeStarted(target) => {
+                self.resizing = Some(Resizing {
+                    target,
+                    last_x: None,
+                });
+            }
+            Message::ResizeMoved(x) => {
+                if let Some(resizing) = &mut self.resizing {
+                    if let Some(last_x) = resizing.last_x.replace(x) {
+                        let delta = x - last_x;
+                        let sizing = &mut self.state.tickers_table;
+
+                        match resizing.target {
+                            // the table grows away from the nav buttons it's attached to
+                            ResizeTarget::Table => match self.state.position {
+                                sidebar::Position::Left => sizing.resize(delta),
+                                sidebar::Position::Right => sizing.resize(-delta),
+                            },
+                            ResizeTarget::Column(column) => sizing.resize_column(column, -delta),
+                        }
+                    }
+                }
+            }
+            Message::ResizeEnded => {
+                self.resizing = None;
+            }
+            Message::TickersTable(super::tickers_table::Message::ColumnResizeStarted(column)) => {
+                return self.update(Message::ResizeStarted(ResizeTarget::Column(column)));
+            }
             Message::TickersTable(msg) => {
                 let action = self.tickers_table.update(msg);
 
@@ -75,7 +146,7 @@ impl Sidebar {
         (Task::none(), None)
     }
 
-    pub fn view(&self, audio_volume: Option<f32>) -> Element<'_, Message> {
+    pub fn view(&self, audio_volume: Option<f32>, streams_paused: bool) -> Element<'_, Message> {
         let state = &self.state;
 
         let tooltip_position = if state.position == sidebar::Position::Left {
@@ -86,34 +157,90 @@ impl Sidebar {
 
         let is_table_open = self.tickers_table.is_shown;
 
-        let nav_buttons = self.nav_buttons(is_table_open, audio_volume, tooltip_position);
+        if self.is_collapsed() {
+            let strip = container(Space::new(6, Length::Fill)).style(style::sidebar_strip);
+
+            return mouse_area(strip).on_enter(Message::Reveal(true)).into();
+        }
+
+        let nav_buttons = self.nav_buttons(
+            is_table_open,
+            audio_volume,
+            streams_paused,
+            tooltip_position,
+        );
+
+        let sizing = state.tickers_table;
 
         let tickers_table = if is_table_open {
             column![responsive(move |size| self
                 .tickers_table
-                .view(size)
+                .view(size, sizing)
                 .map(Message::TickersTable))]
-            .width(200)
+            .width(sizing.width)
         } else {
             column![]
         };
 
-        match state.position {
-            sidebar::Position::Left => row![nav_buttons, tickers_table],
-            sidebar::Position::Right => row![tickers_table, nav_buttons],
+        let content = if is_table_open {
+            let handle = mouse_area(Space::new(4, Length::Fill))
+                .interaction(mouse::Interaction::ResizingHorizontally)
+                .on_press(Message::ResizeStarted(ResizeTarget::Table));
+
+            match state.position {
+                sidebar::Position::Left => row![nav_buttons, tickers_table, handle],
+                sidebar::Position::Right => row![handle, tickers_table, nav_buttons],
+            }
+            .spacing(4)
+        } else {
+            match state.position {
+                sidebar::Position::Left => row![nav_buttons, tickers_table],
+                sidebar::Position::Right => row![tickers_table, nav_buttons],
+            }
+            .spacing(4)
+        };
+
+        if state.auto_hide {
+            mouse_area(content).on_exit(Message::Reveal(false)).into()
+        } else {
+            content.into()
         }
-        .spacing(if is_table_open { 8 } else { 4 })
-        .into()
+    }
+
+    /// Auto-hide keeps the sidebar out while a menu or the tickers table is open on it
+    fn is_collapsed(&self) -> bool {
+        self.state.auto_hide
+            && !self.is_revealed
+            && self.state.active_menu.is_none()
+            && !self.tickers_table.is_shown
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        self.tickers_table.subscription().map(Message::TickersTable)
+        let table = self.tickers_table.subscription().map(Message::TickersTable);
+
+        if self.resizing.is_none() {
+            return table;
+        }
+
+        // the cursor leaves the small handles while dragging, so the drag follows window events
+        let drag = iced::event::listen_with(|event, _status, _window| match event {
+            iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                Some(Message::ResizeMoved(position.x))
+            }
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                Some(Message::ResizeEnded)
+            }
+            _ => None,
+        });
+
+        Subscription::batch([table, drag])
     }
 
     fn nav_buttons(
         &self,
         is_table_open: bool,
         audio_volume: Option<f32>,
+        streams_paused: bool,
         tooltip_position: TooltipPosition,
     ) -> iced::widget::Column<'_, Message> {
         let settings_modal_button = {
@@ -177,10 +304,78 @@ impl Sidebar {
             )
         };
 
+        let pause_streams_button = {
+            let tooltip = if streams_paused {
+                "Resume streaming"
+            } else {
+                "Pause all streaming"
+            };
+
+            button_with_tooltip(
+                iced::widget::text("II")
+                    .size(12)
+                    .width(24)
+                    .align_x(Alignment::Center),
+                Message::ToggleStreamsPaused,
+                Some(tooltip),
+                tooltip_position,
+                move |theme, status| {
+                    crate::style::button::transparent(theme, status, streams_paused)
+                },
+            )
+        };
+
+        let journal_button = {
+            let is_active = self.is_menu_active(sidebar::Menu::Journal);
+
+            button_with_tooltip(
+                icon_text(Icon::Edit, 14)
+                    .width(24)
+                    .align_x(Alignment::Center),
+                Message::ToggleSidebarMenu(Some(sidebar::Menu::Journal)),
+                None,
+                tooltip_position,
+                move |theme, status| crate::style::button::transparent(theme, status, is_active),
+            )
+        };
+
+        let alerts_button = {
+            let is_active = self.is_menu_active(sidebar::Menu::Alerts);
+
+            button_with_tooltip(
+                iced::widget::text("!")
+                    .size(13)
+                    .width(24)
+                    .align_x(Alignment::Center),
+                Message::ToggleSidebarMenu(Some(sidebar::Menu::Alerts)),
+                Some("Alerts"),
+                tooltip_position,
+                move |theme, status| crate::style::button::transparent(theme, status, is_active),
+            )
+        };
+
+        let profile_button = {
+            let is_active = self.is_menu_active(sidebar::Menu::Profile);
+
+            button_with_tooltip(
+                icon_text(Icon::ChartOutline, 14)
+                    .width(24)
+                    .align_x(Alignment::Center),
+                Message::ToggleSidebarMenu(Some(sidebar::Menu::Profile)),
+                Some("Composite volume profile"),
+                tooltip_position,
+                move |theme, status| crate::style::button::transparent(theme, status, is_active),
+            )
+        };
+
         column![
             ticker_search_button,
             layout_modal_button,
             audio_btn,
+            journal_button,
+            alerts_button,
+            profile_button,
+            pause_streams_button,
             Space::with_height(Length::Fill),
             settings_modal_button,
         ]
@@ -225,4 +420,26 @@ impl Sidebar {
             .map(|(exchange, ticker)| (*exchange, *ticker))
             .collect()
     }
+
+    pub fn ticker_hotkeys(
+        &self,
+    ) -> &std::collections::BTreeMap<u8, (exchange::adapter::Exchange, exchange::Ticker)> {
+        &self.tickers_table.hotkeys
+    }
+
+    pub fn recent_tickers(&self) -> Vec<(exchange::adapter::Exchange, exchange::Ticker)> {
+        self.tickers_table.recent_tickers.clone()
+    }
+
+    pub fn tickers_info(&self) -> Vec<exchange::TickerInfo> {
+        self.tickers_table.tickers_info().collect()
+    }
+
+    pub fn ticker_info(
+        &self,
+        exchange: exchange::adapter::Exchange,
+        ticker: exchange::Ticker,
+    ) -> Option<exchange::TickerInfo> {
+        self.tickers_table.ticker_info(exchange, ticker)
+    }
 }
@@ -1,22 +1,29 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
 
+use super::fetch_klines_cached;
 use crate::{
+    screen::DashboardError,
     style::{self, ICONS_FONT, Icon, icon_text},
     widget::button_with_tooltip,
 };
 use data::InternalError;
+use data::metrics::{self, RVOL_LOOKBACK_DAYS, VOLATILITY_PERIOD};
+use data::sidebar::{TableColumn, TableSizing};
+use data::ticker_specs::SpecsCache;
 use exchange::{
-    Ticker, TickerInfo, TickerStats,
-    adapter::{Exchange, MarketKind, fetch_ticker_info, fetch_ticker_prices},
+    Ticker, TickerInfo, TickerSpecs, TickerStats, Timeframe,
+    adapter::{Exchange, KlineSource, MarketKind, fetch_ticker_info, fetch_ticker_prices},
 };
 use iced::{
     Alignment, Element, Length, Renderer, Size, Subscription, Task, Theme,
     alignment::{self, Horizontal, Vertical},
-    padding,
+    mouse, padding,
     widget::{
-        Button, Space, Text, button, column, container, horizontal_rule, horizontal_space, row,
+        Button, Space, Text, button, column, container, horizontal_rule, horizontal_space,
+        mouse_area, pick_list, row,
         scrollable::{self, AbsoluteOffset},
-        text, text_input,
+        text, text_input, vertical_rule,
     },
 };
 
@@ -25,13 +32,52 @@ const INACTIVE_UPDATE_INTERVAL: u64 = 300;
 
 const TICKER_CARD_HEIGHT: f32 = 64.0;
 const SEARCH_BAR_HEIGHT: f32 = 120.0;
+const COLUMN_HEADER_HEIGHT: f32 = 20.0;
+/// Exchange headers the search results are grouped under
+const GROUP_HEADER_HEIGHT: f32 = 20.0;
+
+/// Metrics are fetched per ticker, so only this many of the rows in view are refreshed per tick
+const METRICS_BATCH_SIZE: usize = 12;
+const METRICS_REFRESH_INTERVAL: Duration = Duration::from_secs(600);
+
+const RECENT_TICKERS_LIMIT: usize = 12;
+
+/// A `Ctrl+N` (`Cmd+N` on macOS) binding that loads a favorite into the focused pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotkeySlot(pub u8);
+
+impl HotkeySlot {
+    pub const ALL: [HotkeySlot; 9] = [
+        HotkeySlot(1),
+        HotkeySlot(2),
+        HotkeySlot(3),
+        HotkeySlot(4),
+        HotkeySlot(5),
+        HotkeySlot(6),
+        HotkeySlot(7),
+        HotkeySlot(8),
+        HotkeySlot(9),
+    ];
+}
+
+impl std::fmt::Display for HotkeySlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if cfg!(target_os = "macos") {
+            write!(f, "Cmd+{}", self.0)
+        } else {
+            write!(f, "Ctrl+{}", self.0)
+        }
+    }
+}
 
 pub fn fetch_tickers_info() -> Task<Message> {
     let fetch_tasks = Exchange::ALL
         .iter()
         .map(|exchange| {
             Task::perform(fetch_ticker_info(*exchange), move |result| match result {
-                Ok(ticker_info) => Message::UpdateTickersInfo(*exchange, ticker_info),
+                Ok((ticker_info, specs)) => {
+                    Message::UpdateTickersInfo(*exchange, ticker_info, specs)
+                }
                 Err(err) => Message::ErrorOccurred(InternalError::Fetch(err.to_string())),
             })
         })
@@ -40,6 +86,59 @@ pub fn fetch_tickers_info() -> Task<Message> {
     Task::batch(fetch_tasks)
 }
 
+/// Relative volume needs the hourly klines of the baseline days on top of today's,
+/// volatility metrics are measured on daily klines
+fn fetch_ticker_metrics(exchange: Exchange, ticker: Ticker) -> Task<Message> {
+    Task::perform(
+        async move {
+            let now = chrono::Utc::now().timestamp_millis() as u64;
+
+            let hourly = Timeframe::H1.to_milliseconds();
+            let hourly_start = now - metrics::rvol_baseline_span(hourly, RVOL_LOOKBACK_DAYS + 1);
+            let hourly_klines = fetch_klines_cached(
+                exchange,
+                ticker,
+                Timeframe::H1,
+                KlineSource::Last,
+                Some((hourly_start, now)),
+            )
+            .await?;
+
+            // today's bar is still forming, its range would understate the day's volatility
+            let today_open = Timeframe::D1.open_time(now);
+            let daily_start =
+                today_open - Timeframe::D1.to_milliseconds() * (VOLATILITY_PERIOD as u64 + 1);
+            let mut daily_klines = fetch_klines_cached(
+                exchange,
+                ticker,
+                Timeframe::D1,
+                KlineSource::Last,
+                Some((daily_start, now)),
+            )
+            .await?;
+            daily_klines.retain(|kline| kline.time < today_open);
+
+            Ok::<_, DashboardError>(TickerMetrics {
+                rvol: metrics::session_relative_volume(
+                    &hourly_klines,
+                    hourly,
+                    now,
+                    RVOL_LOOKBACK_DAYS,
+                ),
+                atr_pct: metrics::atr_pct(&daily_klines, VOLATILITY_PERIOD),
+                realized_vol: metrics::realized_volatility(&daily_klines, VOLATILITY_PERIOD),
+            })
+        },
+        move |result| match result {
+            Ok(metrics) => Message::UpdateTickerMetrics(exchange, ticker, metrics),
+            Err(err) => {
+                log::warn!("Failed to fetch metrics for {ticker:?} on {exchange:?}: {err}");
+                Message::UpdateTickerMetrics(exchange, ticker, TickerMetrics::default())
+            }
+        },
+    )
+}
+
 pub enum Action {
     TickerSelected(TickerInfo, Option<String>),
     ErrorOccurred(data::InternalError),
@@ -51,7 +150,9 @@ pub enum TickerTab {
     All,
     Bybit,
     Binance,
+    Okx,
     Favorites,
+    Recent,
 }
 
 #[derive(Clone)]
@@ -63,6 +164,49 @@ struct TickerRowData {
     is_favorited: bool,
 }
 
+/// The same symbol on the other market (spot or perp) of the venue
+#[derive(Clone)]
+struct Counterpart {
+    exchange: Exchange,
+    ticker: Ticker,
+    volume_display: String,
+    is_selected: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TickerMetrics {
+    rvol: Option<f32>,
+    atr_pct: Option<f32>,
+    realized_vol: Option<f32>,
+}
+
+impl TickerMetrics {
+    fn rvol_display(&self) -> Option<String> {
+        self.rvol.map(|rvol| format!("{rvol:.2}x"))
+    }
+
+    fn atr_display(&self) -> Option<String> {
+        self.atr_pct.map(|atr| format!("{atr:.2}%"))
+    }
+
+    fn realized_vol_display(&self) -> Option<String> {
+        self.realized_vol.map(|vol| format!("{vol:.1}%"))
+    }
+
+    /// Compact cards have room for one metric, the one being sorted on if any
+    fn card_display(&self, sort: SortOptions) -> Option<String> {
+        match sort {
+            SortOptions::AtrAsc | SortOptions::AtrDesc => {
+                self.atr_display().map(|atr| format!("ATR {atr}"))
+            }
+            SortOptions::VolatilityAsc | SortOptions::VolatilityDesc => {
+                self.realized_vol_display().map(|vol| format!("RV {vol}"))
+            }
+            _ => self.rvol_display().map(|rvol| format!("RVOL {rvol}")),
+        }
+    }
+}
+
 #[derive(Clone)]
 struct TickerDisplayData {
     display_ticker: String,
@@ -81,6 +225,10 @@ pub enum SortOptions {
     VolumeDesc,
     ChangeAsc,
     ChangeDesc,
+    AtrAsc,
+    AtrDesc,
+    VolatilityAsc,
+    VolatilityDesc,
 }
 
 #[derive(Debug, Clone)]
@@ -92,18 +240,32 @@ pub enum Message {
     TickerSelected(Ticker, Exchange, Option<String>),
     ExpandTickerCard(Option<(Ticker, Exchange)>),
     FavoriteTicker(Exchange, Ticker),
+    ToggleCounterpart(Exchange, Ticker),
+    AssignHotkey(Exchange, Ticker, HotkeySlot),
+    ClearHotkey(u8),
+    HotkeyPressed(u8),
     Scrolled(scrollable::Viewport),
     SetMarketFilter(Option<MarketKind>),
     ToggleTable,
     FetchForTickerStats(Option<Exchange>),
-    UpdateTickersInfo(Exchange, HashMap<Ticker, Option<TickerInfo>>),
+    UpdateTickersInfo(
+        Exchange,
+        HashMap<Ticker, Option<TickerInfo>>,
+        HashMap<Ticker, TickerSpecs>,
+    ),
     UpdateTickerStats(Exchange, HashMap<Ticker, TickerStats>),
+    UpdateTickerMetrics(Exchange, Ticker, TickerMetrics),
     ErrorOccurred(data::InternalError),
+    /// Handled by the sidebar, which tracks the drag and owns the persisted widths
+    ColumnResizeStarted(TableColumn),
 }
 
 pub struct TickersTable {
     ticker_rows: Vec<TickerRowData>,
     pub favorited_tickers: HashSet<(Exchange, Ticker)>,
+    /// Tickers last opened from the table, most recent first
+    pub recent_tickers: Vec<(Exchange, Ticker)>,
+    pub hotkeys: BTreeMap<u8, (Exchange, Ticker)>,
     display_cache: HashMap<(Exchange, Ticker), TickerDisplayData>,
     selected_tab: TickerTab,
     search_query: String,
@@ -114,15 +276,26 @@ pub struct TickersTable {
     scroll_offset: AbsoluteOffset,
     pub is_shown: bool,
     tickers_info: HashMap<Exchange, HashMap<Ticker, Option<TickerInfo>>>,
+    specs: SpecsCache,
+    metrics: HashMap<(Exchange, Ticker), (Instant, TickerMetrics)>,
+    counterpart_selected: HashSet<(Exchange, Ticker)>,
 }
 
 impl TickersTable {
-    pub fn new(favorited_tickers: Vec<(Exchange, Ticker)>) -> (Self, Task<Message>) {
+    pub fn new(
+        favorited_tickers: Vec<(Exchange, Ticker)>,
+        mut recent_tickers: Vec<(Exchange, Ticker)>,
+        hotkeys: BTreeMap<u8, (Exchange, Ticker)>,
+    ) -> (Self, Task<Message>) {
+        recent_tickers.truncate(RECENT_TICKERS_LIMIT);
+
         (
             Self {
                 ticker_rows: Vec::new(),
                 display_cache: HashMap::new(),
                 favorited_tickers: favorited_tickers.into_iter().collect(),
+                recent_tickers,
+                hotkeys,
                 selected_tab: TickerTab::All,
                 search_query: String::new(),
                 show_sort_options: false,
@@ -132,6 +305,9 @@ impl TickersTable {
                 selected_market: None,
                 is_shown: false,
                 tickers_info: HashMap::new(),
+                specs: SpecsCache::default(),
+                metrics: HashMap::new(),
+                counterpart_selected: HashSet::new(),
             },
             fetch_tickers_info(),
         )
@@ -205,9 +381,41 @@ impl TickersTable {
                         .unwrap_or(std::cmp::Ordering::Equal)
                 });
             }
+            SortOptions::AtrAsc | SortOptions::AtrDesc => {
+                let descending = self.selected_sort_option == SortOptions::AtrDesc;
+                self.sort_by_metric(descending, |metrics| metrics.atr_pct);
+            }
+            SortOptions::VolatilityAsc | SortOptions::VolatilityDesc => {
+                let descending = self.selected_sort_option == SortOptions::VolatilityDesc;
+                self.sort_by_metric(descending, |metrics| metrics.realized_vol);
+            }
         }
     }
 
+    /// Rows whose metric isn't fetched yet stay at the bottom in both directions
+    fn sort_by_metric(&mut self, descending: bool, metric: impl Fn(&TickerMetrics) -> Option<f32>) {
+        let metrics = &self.metrics;
+        let value = |row: &TickerRowData| {
+            metrics
+                .get(&(row.exchange, row.ticker))
+                .and_then(|(_, m)| metric(m))
+        };
+
+        self.ticker_rows.sort_by(|a, b| match (value(a), value(b)) {
+            (Some(a), Some(b)) => {
+                let ordering = a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal);
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+    }
+
     fn change_sort_option(&mut self, option: SortOptions) {
         if self.selected_sort_option == option {
             self.selected_sort_option = match self.selected_sort_option {
@@ -215,6 +423,10 @@ impl TickersTable {
                 SortOptions::VolumeAsc => SortOptions::VolumeDesc,
                 SortOptions::ChangeDesc => SortOptions::ChangeAsc,
                 SortOptions::ChangeAsc => SortOptions::ChangeDesc,
+                SortOptions::AtrDesc => SortOptions::AtrAsc,
+                SortOptions::AtrAsc => SortOptions::AtrDesc,
+                SortOptions::VolatilityDesc => SortOptions::VolatilityAsc,
+                SortOptions::VolatilityAsc => SortOptions::VolatilityDesc,
             };
         } else {
             self.selected_sort_option = option;
@@ -235,10 +447,18 @@ impl TickersTable {
                 self.favorited_tickers.insert((exchange, ticker));
             } else {
                 self.favorited_tickers.remove(&(exchange, ticker));
+                self.hotkeys.retain(|_, bound| *bound != (exchange, ticker));
             }
         }
     }
 
+    fn push_recent(&mut self, exchange: Exchange, ticker: Ticker) {
+        self.recent_tickers
+            .retain(|recent| *recent != (exchange, ticker));
+        self.recent_tickers.insert(0, (exchange, ticker));
+        self.recent_tickers.truncate(RECENT_TICKERS_LIMIT);
+    }
+
     fn matches_exchange(ex: Exchange, tab: &TickerTab) -> bool {
         match tab {
             TickerTab::Bybit => matches!(
@@ -249,16 +469,170 @@ impl TickersTable {
                 ex,
                 Exchange::BinanceLinear | Exchange::BinanceInverse | Exchange::BinanceSpot
             ),
+            TickerTab::Okx => matches!(
+                ex,
+                Exchange::OkxLinear | Exchange::OkxInverse | Exchange::OkxSpot
+            ),
             _ => false,
         }
     }
 
-    fn is_container_visible(&self, index: usize, bounds: Size) -> bool {
-        let item_top = SEARCH_BAR_HEIGHT + (index as f32 * TICKER_CARD_HEIGHT);
-        let item_bottom = item_top + TICKER_CARD_HEIGHT;
+    /// A search looks through every exchange and market, only favorites and recents stay
+    /// limited to their own lists
+    fn is_searching_all(&self) -> bool {
+        !self.search_query.is_empty()
+            && !matches!(self.selected_tab, TickerTab::Favorites | TickerTab::Recent)
+    }
+
+    fn matches_filters(&self, row: &TickerRowData) -> bool {
+        let (ticker_str, market) = row.ticker.to_full_symbol_and_type();
+        let search_match = ticker_str.contains(&self.search_query);
+        if self.is_searching_all() {
+            return search_match;
+        }
+
+        let market_match = match self.selected_market {
+            Some(market_type) => market == market_type,
+            None => true,
+        };
+
+        let tab_match = match self.selected_tab {
+            TickerTab::All => true,
+            TickerTab::Favorites => row.is_favorited,
+            TickerTab::Recent => self.recent_tickers.contains(&(row.exchange, row.ticker)),
+            _ => Self::matches_exchange(row.exchange, &self.selected_tab),
+        };
+
+        search_match && market_match && tab_match
+    }
+
+    /// Rows as listed, the recent tab keeps the order they were opened in rather than the sort option,
+    /// search results are grouped by exchange and ranked by volume
+    fn filtered_rows(&self) -> Box<dyn Iterator<Item = &TickerRowData> + '_> {
+        if self.is_searching_all() {
+            let mut rows = self
+                .ticker_rows
+                .iter()
+                .filter(|row| self.matches_filters(row))
+                .collect::<Vec<_>>();
+
+            // the exchange with the most volume across its matches leads
+            let mut group_volumes: HashMap<Exchange, f32> = HashMap::new();
+            for row in &rows {
+                *group_volumes.entry(row.exchange).or_default() += row.stats.daily_volume;
+            }
+
+            rows.sort_by(|a, b| {
+                group_volumes[&b.exchange]
+                    .partial_cmp(&group_volumes[&a.exchange])
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.exchange.to_string().cmp(&b.exchange.to_string()))
+                    .then_with(|| {
+                        b.stats
+                            .daily_volume
+                            .partial_cmp(&a.stats.daily_volume)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+            });
+
+            Box::new(rows.into_iter())
+        } else if self.selected_tab == TickerTab::Recent {
+            Box::new(self.recent_tickers.iter().filter_map(|(exchange, ticker)| {
+                self.ticker_rows
+                    .iter()
+                    .find(|row| row.exchange == *exchange && row.ticker == *ticker)
+                    .filter(|row| self.matches_filters(row))
+            }))
+        } else {
+            Box::new(
+                self.ticker_rows
+                    .iter()
+                    .filter(|row| self.matches_filters(row)),
+            )
+        }
+    }
+
+    /// Requests metrics for the rows around the scroll position that have none or stale ones,
+    /// marking them as fetched right away so a slow response isn't requested twice
+    fn fetch_stale_metrics(&mut self) -> Option<Task<Message>> {
+        let card_height = style::dense(TICKER_CARD_HEIGHT);
+        let first_row = ((self.scroll_offset.y - SEARCH_BAR_HEIGHT) / card_height).max(0.0);
+
+        let stale = self
+            .filtered_rows()
+            .skip(first_row as usize)
+            .take(METRICS_BATCH_SIZE)
+            .filter(|row| {
+                self.metrics
+                    .get(&(row.exchange, row.ticker))
+                    .is_none_or(|(fetched_at, _)| fetched_at.elapsed() > METRICS_REFRESH_INTERVAL)
+            })
+            .map(|row| (row.exchange, row.ticker))
+            .collect::<Vec<_>>();
+
+        if stale.is_empty() {
+            return None;
+        }
+
+        let now = Instant::now();
+        let tasks = stale
+            .into_iter()
+            .map(|(exchange, ticker)| {
+                let previous = self
+                    .metrics
+                    .get(&(exchange, ticker))
+                    .map(|(_, metrics)| *metrics)
+                    .unwrap_or_default();
+                self.metrics.insert((exchange, ticker), (now, previous));
+
+                fetch_ticker_metrics(exchange, ticker)
+            })
+            .collect::<Vec<_>>();
 
-        (item_bottom >= (self.scroll_offset.y - (3.0 * TICKER_CARD_HEIGHT)))
-            && (item_top <= (self.scroll_offset.y + bounds.height + (4.0 * TICKER_CARD_HEIGHT)))
+        Some(Task::batch(tasks))
+    }
+
+    fn counterpart(&self, exchange: Exchange, ticker: Ticker) -> Option<Counterpart> {
+        let counterpart_exchange = exchange.spot_perp_counterpart()?;
+        let (symbol, _) = ticker.to_full_symbol_and_type();
+        let counterpart_ticker = Ticker::new(&symbol, counterpart_exchange);
+
+        let row = self
+            .ticker_rows
+            .iter()
+            .find(|row| row.exchange == counterpart_exchange && row.ticker == counterpart_ticker)?;
+
+        Some(Counterpart {
+            exchange: row.exchange,
+            ticker: row.ticker,
+            volume_display: self
+                .display_cache
+                .get(&(row.exchange, row.ticker))
+                .map_or_else(
+                    || data::util::currency_abbr(row.stats.daily_volume),
+                    |display| display.volume_display.clone(),
+                ),
+            is_selected: self.counterpart_selected.contains(&(exchange, ticker)),
+        })
+    }
+
+    fn ticker_metrics(&self, exchange: Exchange, ticker: Ticker) -> TickerMetrics {
+        self.metrics
+            .get(&(exchange, ticker))
+            .map(|(_, metrics)| *metrics)
+            .unwrap_or_default()
+    }
+
+    fn is_container_visible(&self, index: usize, headers_above: usize, bounds: Size) -> bool {
+        let card_height = style::dense(TICKER_CARD_HEIGHT);
+        let item_top = SEARCH_BAR_HEIGHT
+            + COLUMN_HEADER_HEIGHT
+            + (headers_above as f32 * GROUP_HEADER_HEIGHT)
+            + (index as f32 * card_height);
+        let item_bottom = item_top + card_height;
+
+        (item_bottom >= (self.scroll_offset.y - (3.0 * card_height)))
+            && (item_top <= (self.scroll_offset.y + bounds.height + (4.0 * card_height)))
     }
 
     pub fn update_ticker_info(
@@ -287,6 +661,21 @@ impl TickersTable {
         Action::Fetch(task)
     }
 
+    /// Info of every ticker listed across the exchanges
+    pub fn tickers_info(&self) -> impl Iterator<Item = TickerInfo> + '_ {
+        self.tickers_info
+            .values()
+            .flat_map(|tickers| tickers.values().flatten().copied())
+    }
+
+    pub fn ticker_info(&self, exchange: Exchange, ticker: Ticker) -> Option<TickerInfo> {
+        self.tickers_info
+            .get(&exchange)
+            .and_then(|info| info.get(&ticker))
+            .copied()
+            .flatten()
+    }
+
     pub fn update_ticker_rows(&mut self, exchange: Exchange, stats: HashMap<Ticker, TickerStats>) {
         let tickers_set: HashSet<_> = self
             .tickers_info
@@ -322,6 +711,23 @@ impl TickersTable {
             Message::FavoriteTicker(exchange, ticker) => {
                 self.favorite_ticker(exchange, ticker);
             }
+            Message::AssignHotkey(exchange, ticker, slot) => {
+                self.hotkeys.retain(|_, bound| *bound != (exchange, ticker));
+                self.hotkeys.insert(slot.0, (exchange, ticker));
+            }
+            Message::ClearHotkey(slot) => {
+                self.hotkeys.remove(&slot);
+            }
+            Message::HotkeyPressed(slot) => {
+                if let Some((exchange, ticker)) = self.hotkeys.get(&slot).copied() {
+                    return self.update(Message::TickerSelected(ticker, exchange, None));
+                }
+            }
+            Message::ToggleCounterpart(exchange, ticker) => {
+                if !self.counterpart_selected.remove(&(exchange, ticker)) {
+                    self.counterpart_selected.insert((exchange, ticker));
+                }
+            }
             Message::Scrolled(viewport) => {
                 self.scroll_offset = viewport.absolute_offset();
             }
@@ -333,14 +739,8 @@ impl TickersTable {
                 }
             }
             Message::TickerSelected(ticker, exchange, content) => {
-                let ticker_info = self
-                    .tickers_info
-                    .get(&exchange)
-                    .and_then(|info| info.get(&ticker))
-                    .copied()
-                    .flatten();
-
-                if let Some(ticker_info) = ticker_info {
+                if let Some(ticker_info) = self.ticker_info(exchange, ticker) {
+                    self.push_recent(exchange, ticker);
                     return Some(Action::TickerSelected(ticker_info, content));
                 } else {
                     log::warn!("Ticker info not found for {ticker:?} on {exchange:?}");
@@ -367,7 +767,7 @@ impl TickersTable {
                         Err(err) => Message::ErrorOccurred(InternalError::Fetch(err.to_string())),
                     })
                 } else {
-                    let fetch_tasks = {
+                    let mut fetch_tasks = {
                         self.tickers_info
                             .keys()
                             .map(|exchange| {
@@ -386,6 +786,10 @@ impl TickersTable {
                             .collect::<Vec<Task<Message>>>()
                     };
 
+                    if self.is_shown {
+                        fetch_tasks.extend(self.fetch_stale_metrics());
+                    }
+
                     Task::batch(fetch_tasks)
                 };
 
@@ -394,8 +798,23 @@ impl TickersTable {
             Message::UpdateTickerStats(exchange, stats) => {
                 self.update_ticker_rows(exchange, stats);
             }
-            Message::UpdateTickersInfo(exchange, info) => {
+            Message::UpdateTickerMetrics(exchange, ticker, metrics) => {
+                self.metrics
+                    .insert((exchange, ticker), (Instant::now(), metrics));
+
+                if matches!(
+                    self.selected_sort_option,
+                    SortOptions::AtrAsc
+                        | SortOptions::AtrDesc
+                        | SortOptions::VolatilityAsc
+                        | SortOptions::VolatilityDesc
+                ) {
+                    self.sort_ticker_rows();
+                }
+            }
+            Message::UpdateTickersInfo(exchange, info, specs) => {
                 self.update_ticker_info(exchange, info);
+                self.specs.update(exchange, specs);
 
                 let task =
                     Task::perform(fetch_ticker_prices(exchange), move |result| match result {
@@ -409,19 +828,32 @@ impl TickersTable {
                 log::error!("Error occurred: {err}");
                 return Some(Action::ErrorOccurred(err));
             }
+            Message::ColumnResizeStarted(_) => {}
         }
 
         None
     }
 
-    pub fn view(&self, bounds: Size) -> Element<'_, Message> {
+    pub fn view(&self, bounds: Size, sizing: TableSizing) -> Element<'_, Message> {
         let show_sorting_button = button(icon_text(Icon::Sort, 14).align_x(Horizontal::Center))
             .on_press(Message::ShowSortingOptions);
 
         let expanded_card = self.expand_ticker_card;
+        let expanded_counterpart =
+            expanded_card.and_then(|(ticker, exchange)| self.counterpart(exchange, ticker));
+        let expanded_hotkey = expanded_card.and_then(|(ticker, exchange)| {
+            self.hotkeys
+                .iter()
+                .find(|(_, bound)| **bound == (exchange, ticker))
+                .map(|(slot, _)| HotkeySlot(*slot))
+        });
+        let expanded_specs = expanded_card.and_then(|(ticker, exchange)| {
+            self.ticker_info(exchange, ticker)
+                .map(|info| (info, self.specs.get(&ticker)))
+        });
 
         let search_bar_row = row![
-            text_input("Search for a ticker...", &self.search_query)
+            text_input("Search tickers on all exchanges...", &self.search_query)
                 .style(|theme, status| style::validated_text_input(theme, status, true))
                 .on_input(Message::UpdateSearchQuery)
                 .align_x(Horizontal::Left)
@@ -454,6 +886,13 @@ impl TickersTable {
                 sort_button("Volume", SortOptions::VolumeAsc, self.selected_sort_option);
             let change_sort_button =
                 sort_button("Change", SortOptions::ChangeAsc, self.selected_sort_option);
+            let atr_sort_button =
+                sort_button("ATR%", SortOptions::AtrAsc, self.selected_sort_option);
+            let volatility_sort_button = sort_button(
+                "Volatility",
+                SortOptions::VolatilityAsc,
+                self.selected_sort_option,
+            );
 
             column![
                 row![
@@ -481,6 +920,31 @@ impl TickersTable {
                     }),
                     Space::new(Length::FillPortion(2), Length::Shrink),
                 ],
+                row![
+                    Space::new(Length::FillPortion(2), Length::Shrink),
+                    atr_sort_button.style(move |theme, status| {
+                        style::button::transparent(
+                            theme,
+                            status,
+                            matches!(
+                                self.selected_sort_option,
+                                SortOptions::AtrAsc | SortOptions::AtrDesc
+                            ),
+                        )
+                    }),
+                    Space::new(Length::FillPortion(1), Length::Shrink),
+                    volatility_sort_button.style(move |theme, status| {
+                        style::button::transparent(
+                            theme,
+                            status,
+                            matches!(
+                                self.selected_sort_option,
+                                SortOptions::VolatilityAsc | SortOptions::VolatilityDesc
+                            ),
+                        )
+                    }),
+                    Space::new(Length::FillPortion(2), Length::Shrink),
+                ],
                 row![
                     Space::new(Length::FillPortion(1), Length::Shrink),
                     spot_market_button.style(move |theme, status| {
@@ -518,20 +982,26 @@ impl TickersTable {
             let bybit_button = tab_button(text("Bybit"), &self.selected_tab, TickerTab::Bybit);
             let binance_button =
                 tab_button(text("Binance"), &self.selected_tab, TickerTab::Binance);
+            let okx_button = tab_button(text("OKX"), &self.selected_tab, TickerTab::Okx);
             let favorites_button = tab_button(
                 text(char::from(Icon::StarFilled).to_string()).font(ICONS_FONT),
                 &self.selected_tab,
                 TickerTab::Favorites,
             );
+            let recent_button = tab_button(text("Recent"), &self.selected_tab, TickerTab::Recent);
 
             row![
                 favorites_button,
                 horizontal_space(),
+                recent_button,
+                horizontal_space(),
                 all_button,
                 horizontal_space(),
                 bybit_button,
                 horizontal_space(),
                 binance_button,
+                horizontal_space(),
+                okx_button,
             ]
         };
 
@@ -545,47 +1015,60 @@ impl TickersTable {
         };
 
         content = content.push(exchange_filters_row);
+        content = content.push(column_header(sizing));
 
         let mut ticker_cards = column![].spacing(4);
 
-        let filter_predicate = |row: &TickerRowData| -> bool {
-            let (ticker_str, market) = row.ticker.to_full_symbol_and_type();
-            let search_match = ticker_str.contains(&self.search_query);
-            let market_match = match self.selected_market {
-                Some(market_type) => market == market_type,
-                None => true,
-            };
+        let is_searching_all = self.is_searching_all();
+        let mut current_group: Option<Exchange> = None;
+        let mut headers_above = 0;
 
-            let tab_match = match self.selected_tab {
-                TickerTab::All => true,
-                TickerTab::Favorites => row.is_favorited,
-                _ => Self::matches_exchange(row.exchange, &self.selected_tab),
+        for (index, row) in self.filtered_rows().enumerate() {
+            let Some(display_data) = self.display_cache.get(&(row.exchange, row.ticker)) else {
+                continue;
             };
 
-            search_match && market_match && tab_match
-        };
+            if is_searching_all && current_group != Some(row.exchange) {
+                current_group = Some(row.exchange);
+                headers_above += 1;
+                ticker_cards = ticker_cards.push(search_group_header(row.exchange));
+            }
 
-        ticker_cards = self
-            .ticker_rows
-            .iter()
-            .filter(|row| filter_predicate(row))
-            .enumerate()
-            .fold(ticker_cards, |ticker_cards, (index, row)| {
-                if let Some(display_data) = self.display_cache.get(&(row.exchange, row.ticker)) {
-                    let is_visible = self.is_container_visible(index, bounds);
-
-                    ticker_cards.push(ticker_card_container(
-                        is_visible,
-                        row.exchange,
-                        &row.ticker,
-                        display_data,
-                        expanded_card,
-                        row.is_favorited,
-                    ))
-                } else {
-                    ticker_cards
+            let is_visible = self.is_container_visible(index, headers_above, bounds);
+
+            if self.selected_tab == TickerTab::Recent {
+                // recent tickers re-open into the selected pane with a single click
+                if !is_visible {
+                    ticker_cards = ticker_cards.push(Space::new(Length::Fill, style::dense(60.0)));
+                    continue;
                 }
-            });
+
+                ticker_cards = ticker_cards.push(create_ticker_card(
+                    row.exchange,
+                    display_data,
+                    sizing,
+                    self.ticker_metrics(row.exchange, row.ticker)
+                        .card_display(self.selected_sort_option),
+                    Message::TickerSelected(row.ticker, row.exchange, None),
+                ));
+                continue;
+            }
+
+            ticker_cards = ticker_cards.push(ticker_card_container(
+                is_visible,
+                row.exchange,
+                &row.ticker,
+                display_data,
+                self.ticker_metrics(row.exchange, row.ticker),
+                self.selected_sort_option,
+                expanded_card,
+                expanded_counterpart.as_ref(),
+                expanded_hotkey,
+                expanded_specs,
+                row.is_favorited,
+                sizing,
+            ));
+        }
 
         content = content.push(ticker_cards);
 
@@ -610,18 +1093,36 @@ impl TickersTable {
     }
 }
 
+fn search_group_header<'a>(exchange: Exchange) -> Element<'a, Message> {
+    row![
+        style::exchange_icon_text(exchange, 12),
+        text(exchange.to_string()).size(12),
+    ]
+    .spacing(6)
+    .height(GROUP_HEADER_HEIGHT)
+    .align_y(Vertical::Center)
+    .padding(padding::left(4))
+    .into()
+}
+
 fn ticker_card_container<'a>(
     is_visible: bool,
     exchange: Exchange,
     ticker: &'a Ticker,
     display_data: &'a TickerDisplayData,
+    metrics: TickerMetrics,
+    sort: SortOptions,
     expanded_card: Option<(Ticker, Exchange)>,
+    counterpart: Option<&Counterpart>,
+    hotkey: Option<HotkeySlot>,
+    specs: Option<(TickerInfo, Option<&TickerSpecs>)>,
     is_fav: bool,
+    sizing: TableSizing,
 ) -> Element<'a, Message> {
     if !is_visible {
         return column![]
             .width(Length::Fill)
-            .height(Length::Fixed(60.0))
+            .height(style::dense(60.0))
             .into();
     }
 
@@ -631,22 +1132,40 @@ fn ticker_card_container<'a>(
                 exchange,
                 ticker,
                 display_data,
+                metrics,
+                counterpart,
+                hotkey,
+                specs,
                 is_fav,
             ))
             .style(style::ticker_card)
             .into()
         } else {
-            create_ticker_card(exchange, ticker, display_data)
+            create_ticker_card(
+                exchange,
+                display_data,
+                sizing,
+                metrics.card_display(sort),
+                Message::ExpandTickerCard(Some((*ticker, exchange))),
+            )
         }
     } else {
-        create_ticker_card(exchange, ticker, display_data)
+        create_ticker_card(
+            exchange,
+            display_data,
+            sizing,
+            metrics.card_display(sort),
+            Message::ExpandTickerCard(Some((*ticker, exchange))),
+        )
     }
 }
 
 fn create_ticker_card<'a>(
     exchange: Exchange,
-    ticker: &Ticker,
     display_data: &'a TickerDisplayData,
+    sizing: TableSizing,
+    metric_display: Option<String>,
+    on_press: Message,
 ) -> Element<'a, Message> {
     let color_column = container(column![])
         .height(Length::Fill)
@@ -671,27 +1190,32 @@ fn create_ticker_card<'a>(
         ]
     };
 
-    let icon = icon_text(style::exchange_icon(exchange), 12);
+    let icon = style::exchange_icon_text(exchange, 12);
 
     container(
         button(
             row![
                 color_column,
-                column![
-                    row![
+                row![
+                    column![
                         row![icon, text(&display_data.display_ticker),]
                             .spacing(2)
                             .align_y(alignment::Vertical::Center),
-                        Space::new(Length::Fill, Length::Shrink),
-                        text(&display_data.daily_change_pct),
-                    ]
-                    .spacing(4)
-                    .align_y(alignment::Vertical::Center),
-                    row![
                         price_display,
-                        Space::new(Length::Fill, Length::Shrink),
+                    ]
+                    .width(Length::Fill)
+                    .spacing(4),
+                    container(metrics_text(metric_display))
+                        .width(sizing.metric_column)
+                        .height(Length::Fill)
+                        .align_x(Horizontal::Right)
+                        .align_y(Vertical::Bottom),
+                    column![
+                        text(&display_data.daily_change_pct),
                         text(&display_data.volume_display),
                     ]
+                    .width(sizing.stats_column)
+                    .align_x(Horizontal::Right)
                     .spacing(4),
                 ]
                 .padding(padding::left(8).right(8).bottom(4).top(4))
@@ -700,9 +1224,9 @@ fn create_ticker_card<'a>(
             .align_y(Alignment::Center),
         )
         .style(style::button::ticker_card)
-        .on_press(Message::ExpandTickerCard(Some((*ticker, exchange)))),
+        .on_press(on_press),
     )
-    .height(Length::Fixed(56.0))
+    .height(style::dense(56.0))
     .into()
 }
 
@@ -710,39 +1234,135 @@ fn create_expanded_ticker_card<'a>(
     exchange: Exchange,
     ticker: &Ticker,
     display_data: &'a TickerDisplayData,
+    metrics: TickerMetrics,
+    counterpart: Option<&Counterpart>,
+    hotkey: Option<HotkeySlot>,
+    specs: Option<(TickerInfo, Option<&TickerSpecs>)>,
     is_fav: bool,
 ) -> Element<'a, Message> {
     let (ticker_str, market) = ticker.display_symbol_and_type();
 
-    column![
-        row![
-            button(icon_text(Icon::Return, 11))
-                .on_press(Message::ExpandTickerCard(None))
-                .style(move |theme, status| style::button::transparent(theme, status, false)),
-            button(if is_fav {
-                icon_text(Icon::StarFilled, 11)
+    let (open_exchange, open_ticker) = match counterpart {
+        Some(counterpart) if counterpart.is_selected => (counterpart.exchange, counterpart.ticker),
+        _ => (exchange, *ticker),
+    };
+
+    let volume_row = match counterpart {
+        Some(counterpart) => {
+            let (spot_volume, perp_volume) = match market {
+                MarketKind::Spot => (
+                    display_data.volume_display.clone(),
+                    counterpart.volume_display.clone(),
+                ),
+                _ => (
+                    counterpart.volume_display.clone(),
+                    display_data.volume_display.clone(),
+                ),
+            };
+
+            row![
+                text("Daily Volume: ").size(11),
+                Space::new(Length::Fill, Length::Shrink),
+                text(format!("Spot {spot_volume} | Perp {perp_volume}")),
+            ]
+        }
+        None => row![
+            text("Daily Volume: ").size(11),
+            Space::new(Length::Fill, Length::Shrink),
+            text(&display_data.volume_display),
+        ],
+    };
+
+    let market_toggle = counterpart.map(|counterpart| {
+        let is_perp_selected = (market == MarketKind::Spot) == counterpart.is_selected;
+
+        let market_button = |label: &'static str, is_active: bool| {
+            let btn = button(text(label).size(11))
+                .style(move |theme, status| style::button::transparent(theme, status, is_active));
+
+            if is_active {
+                btn
             } else {
-                icon_text(Icon::Star, 11)
-            })
-            .on_press(Message::FavoriteTicker(exchange, *ticker))
-            .style(move |theme, status| style::button::transparent(theme, status, false)),
-            horizontal_space(),
-            button_with_tooltip(
-                icon_text(Icon::Link, 11),
-                Message::TickerSelected(*ticker, exchange, None),
-                Some("Use this ticker on selected pane/group"),
-                iced::widget::tooltip::Position::Top,
-                move |theme, status| style::button::transparent(theme, status, false)
-            ),
+                btn.on_press(Message::ToggleCounterpart(exchange, *ticker))
+            }
+        };
+
+        row![
+            text("Open as: ").size(11),
+            Space::new(Length::Fill, Length::Shrink),
+            market_button("Spot", !is_perp_selected),
+            market_button("Perp", is_perp_selected),
         ]
-        .spacing(2),
+        .spacing(2)
+        .align_y(Vertical::Center)
+    });
+
+    let open_button = |label, content, width| {
+        init_content_button(label, content, open_ticker, open_exchange, width)
+    };
+
+    let mut content_buttons = column![].width(Length::Fill).spacing(2);
+    if is_fav {
+        let ticker = *ticker;
+        content_buttons = content_buttons.push(
+            row![
+                text("Hotkey: ").size(11),
+                Space::new(Length::Fill, Length::Shrink),
+                pick_list(HotkeySlot::ALL, hotkey, move |slot| {
+                    Message::AssignHotkey(exchange, ticker, slot)
+                })
+                .placeholder("None")
+                .text_size(11),
+            ]
+            .align_y(Vertical::Center),
+        );
+    }
+    if let Some(market_toggle) = market_toggle {
+        content_buttons = content_buttons.push(market_toggle);
+    }
+    content_buttons = content_buttons
+        .push(open_button("Heatmap Chart", "heatmap", 180.0))
+        .push(open_button("Footprint Chart", "footprint", 180.0))
+        .push(open_button("Candlestick Chart", "candlestick", 180.0))
+        .push(open_button("TPO Chart", "tpo", 180.0))
+        .push(open_button("Delta Candles Chart", "delta", 180.0))
+        .push(open_button("Spot/Perp Spread Chart", "spread", 180.0))
+        .push(open_button("Time&Sales", "time&sales", 160.0))
+        .push(open_button("DOM Ladder", "ladder", 160.0))
+        .push(open_button("Add to Watchlist", "watchlist", 160.0));
+
+    let mut header = row![
+        button(icon_text(Icon::Return, 11))
+            .on_press(Message::ExpandTickerCard(None))
+            .style(move |theme, status| style::button::transparent(theme, status, false)),
+        button(if is_fav {
+            icon_text(Icon::StarFilled, 11)
+        } else {
+            icon_text(Icon::Star, 11)
+        })
+        .on_press(Message::FavoriteTicker(exchange, *ticker))
+        .style(move |theme, status| style::button::transparent(theme, status, false)),
+        horizontal_space(),
+    ]
+    .spacing(2)
+    .align_y(Vertical::Center);
+
+    if let Some((info, specs)) = specs {
+        header = header.push(specs_popover(&info, specs));
+    }
+
+    header = header.push(button_with_tooltip(
+        icon_text(Icon::Link, 11),
+        Message::TickerSelected(open_ticker, open_exchange, None),
+        Some("Use this ticker on selected pane/group"),
+        iced::widget::tooltip::Position::Top,
+        move |theme, status| style::button::transparent(theme, status, false),
+    ));
+
+    column![
+        header,
         row![
-            match exchange {
-                Exchange::BybitInverse | Exchange::BybitLinear | Exchange::BybitSpot =>
-                    icon_text(Icon::BybitLogo, 12),
-                Exchange::BinanceInverse | Exchange::BinanceLinear | Exchange::BinanceSpot =>
-                    icon_text(Icon::BinanceLogo, 12),
-            },
+            style::exchange_icon_text(exchange, 12),
             text(
                 ticker_str
                     + " "
@@ -766,10 +1386,25 @@ fn create_expanded_ticker_card<'a>(
                     Space::new(Length::Fill, Length::Shrink),
                     text(&display_data.daily_change_pct),
                 ],
+                volume_row,
+                row![
+                    text(format!("Relative Volume ({RVOL_LOOKBACK_DAYS}d): ")).size(11),
+                    Space::new(Length::Fill, Length::Shrink),
+                    text(metrics.rvol_display().unwrap_or_else(|| "-".to_string())),
+                ],
+                row![
+                    text(format!("ATR ({VOLATILITY_PERIOD}d): ")).size(11),
+                    Space::new(Length::Fill, Length::Shrink),
+                    text(metrics.atr_display().unwrap_or_else(|| "-".to_string())),
+                ],
                 row![
-                    text("Daily Volume: ").size(11),
+                    text(format!("Realized Volatility ({VOLATILITY_PERIOD}d): ")).size(11),
                     Space::new(Length::Fill, Length::Shrink),
-                    text(&display_data.volume_display),
+                    text(
+                        metrics
+                            .realized_vol_display()
+                            .unwrap_or_else(|| "-".to_string())
+                    ),
                 ],
             ]
             .spacing(2)
@@ -781,20 +1416,91 @@ fn create_expanded_ticker_card<'a>(
                 ..Default::default()
             }
         }),
-        column![
-            init_content_button("Heatmap Chart", "heatmap", *ticker, exchange, 180.0),
-            init_content_button("Footprint Chart", "footprint", *ticker, exchange, 180.0),
-            init_content_button("Candlestick Chart", "candlestick", *ticker, exchange, 180.0),
-            init_content_button("Time&Sales", "time&sales", *ticker, exchange, 160.0),
-        ]
-        .width(Length::Fill)
-        .spacing(2)
+        content_buttons
     ]
     .padding(padding::top(8).right(16).left(16).bottom(16))
     .spacing(12)
     .into()
 }
 
+/// Contract specs of the ticker, shown while hovering its label
+fn specs_popover<'a>(info: &TickerInfo, specs: Option<&TickerSpecs>) -> Element<'a, Message> {
+    let rows = data::ticker_specs::spec_rows(info, specs)
+        .into_iter()
+        .map(|(label, value)| {
+            row![
+                text(label).size(11),
+                Space::new(Length::Fill, Length::Shrink),
+                text(value).size(11),
+            ]
+            .spacing(12)
+            .into()
+        })
+        .collect::<Vec<Element<_>>>();
+
+    iced::widget::tooltip(
+        container(text("Specs").size(11)).padding([2, 6]),
+        container(column(rows).spacing(2).width(200))
+            .style(style::tooltip)
+            .padding(8),
+        iced::widget::tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+/// Labels lined up with the card columns, with handles to drag the fixed ones wider
+fn column_header<'a>(sizing: TableSizing) -> Element<'a, Message> {
+    let label = |content: &'static str| {
+        text(content)
+            .size(11)
+            .style(|theme: &Theme| iced::widget::text::Style {
+                color: Some(theme.extended_palette().background.weak.text),
+            })
+    };
+
+    // the card's color bar and padding come before its first column
+    row![
+        label("Ticker").width(Length::Fill),
+        column_resize_handle(TableColumn::Metric),
+        label("Metric")
+            .width(sizing.metric_column)
+            .align_x(Horizontal::Right),
+        column_resize_handle(TableColumn::Stats),
+        label("Chg / Vol")
+            .width(sizing.stats_column)
+            .align_x(Horizontal::Right),
+    ]
+    .height(COLUMN_HEADER_HEIGHT)
+    .padding(padding::left(10).right(8))
+    .align_y(Vertical::Center)
+    .into()
+}
+
+/// Sits in the spacing left of a fixed column, dragging it left widens the column
+fn column_resize_handle<'a>(column: TableColumn) -> Element<'a, Message> {
+    mouse_area(
+        container(vertical_rule(1.0).style(style::split_ruler))
+            .width(4)
+            .height(12)
+            .align_x(Horizontal::Center),
+    )
+    .interaction(mouse::Interaction::ResizingHorizontally)
+    .on_press(Message::ColumnResizeStarted(column))
+    .into()
+}
+
+fn metrics_text<'a>(content: Option<String>) -> Element<'a, Message> {
+    match content {
+        Some(content) => text(content)
+            .size(11)
+            .style(|theme: &Theme| iced::widget::text::Style {
+                color: Some(theme.extended_palette().background.weak.text),
+            })
+            .into(),
+        None => Space::new(Length::Shrink, Length::Shrink).into(),
+    }
+}
+
 fn tab_button<'a>(
     text: Text<'a, Theme, Renderer>,
     current_tab: &TickerTab,
@@ -816,6 +1522,8 @@ fn sort_button(
     let (asc_variant, desc_variant) = match sort_option {
         SortOptions::VolumeAsc => (SortOptions::VolumeAsc, SortOptions::VolumeDesc),
         SortOptions::ChangeAsc => (SortOptions::ChangeAsc, SortOptions::ChangeDesc),
+        SortOptions::AtrAsc => (SortOptions::AtrAsc, SortOptions::AtrDesc),
+        SortOptions::VolatilityAsc => (SortOptions::VolatilityAsc, SortOptions::VolatilityDesc),
         _ => (sort_option, sort_option), // fallback
     };
 
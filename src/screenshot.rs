@@ -0,0 +1,82 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use iced::Rectangle;
+use iced::window::Screenshot;
+
+/// Encodes a window screenshot as a PNG file
+pub fn save_png(screenshot: &Screenshot, path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+
+    let file = File::create(path).map_err(|err| err.to_string())?;
+
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        screenshot.size.width,
+        screenshot.size.height,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+    writer
+        .write_image_data(&screenshot.rgba)
+        .map_err(|err| err.to_string())
+}
+
+/// Saves a scheduled capture of a chart into the journal folder, named after its ticker and the
+/// slot time
+pub async fn save_scheduled(
+    screenshot: Screenshot,
+    label: String,
+    slot: chrono::NaiveDateTime,
+) -> Result<PathBuf, String> {
+    let file_name = format!("{label}_{}.png", slot.format("%Y-%m-%d_%H%M"));
+    let path = data::journal_path(Some(&file_name));
+
+    save_png(&screenshot, &path)?;
+    Ok(path)
+}
+
+/// Cuts a region out of a window screenshot, `bounds` given in logical pixels
+pub fn crop(screenshot: &Screenshot, bounds: Rectangle) -> Result<Screenshot, String> {
+    let scale = screenshot.scale_factor as f32;
+
+    let region = Rectangle {
+        x: (bounds.x * scale).round() as u32,
+        y: (bounds.y * scale).round() as u32,
+        width: (bounds.width * scale).round() as u32,
+        height: (bounds.height * scale).round() as u32,
+    };
+
+    screenshot.crop(region).map_err(|err| err.to_string())
+}
+
+/// Saves a pane's exported chart into the screenshots folder, named after its ticker
+pub fn save_pane(screenshot: &Screenshot, label: &str) -> Result<PathBuf, String> {
+    let file_name = format!(
+        "{label}_{}.png",
+        chrono::Local::now().format("%Y-%m-%d_%H%M%S")
+    );
+    let path = data::data_path(Some("screenshots")).join(file_name);
+
+    save_png(screenshot, &path)?;
+    Ok(path)
+}
+
+/// Puts the image on the system clipboard, iced's clipboard only takes text
+pub fn copy_to_clipboard(screenshot: &Screenshot) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|err| err.to_string())?;
+
+    clipboard
+        .set_image(arboard::ImageData {
+            width: screenshot.size.width as usize,
+            height: screenshot.size.height as usize,
+            bytes: Cow::Borrowed(&screenshot.rgba),
+        })
+        .map_err(|err| err.to_string())
+}
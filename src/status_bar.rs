@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use exchange::adapter::StreamKind;
+use iced::widget::{button, column, container, horizontal_space, row, scrollable, text};
+use iced::{Alignment, Element, Length, padding};
+
+use crate::style;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// Process CPU and memory are only read from procfs, elsewhere the field isn't shown
+const SHOWS_USAGE: bool = cfg!(target_os = "linux");
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ToggleStreamsPanel,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ConnectionState {
+    Connected,
+    Disconnected(String),
+    /// Tried again after the delay, `None` once it gave up
+    Reconnecting(Option<Duration>),
+}
+
+/// What the streams panel shows for one stream
+struct StreamStatus {
+    state: ConnectionState,
+    last_message: Option<Instant>,
+    msg_count: u64,
+    msg_rate: f32,
+}
+
+impl StreamStatus {
+    fn new(state: ConnectionState) -> Self {
+        Self {
+            state,
+            last_message: None,
+            msg_count: 0,
+            msg_rate: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ProcessUsage {
+    cpu_pct: f32,
+    memory_bytes: u64,
+}
+
+/// Counters behind the optional status bar at the bottom of the main window
+pub struct StatusBar {
+    streams: HashMap<StreamKind, StreamStatus>,
+    show_streams: bool,
+    msg_count: u64,
+    msg_rate: f32,
+    last_sample: Instant,
+    usage: Option<ProcessUsage>,
+    last_cpu_time: Option<Duration>,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self {
+            streams: HashMap::new(),
+            show_streams: false,
+            msg_count: 0,
+            msg_rate: 0.0,
+            last_sample: Instant::now(),
+            usage: None,
+            last_cpu_time: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::ToggleStreamsPanel => self.show_streams = !self.show_streams,
+        }
+    }
+
+    pub fn record_event(&mut self, event: &exchange::Event) {
+        match event {
+            exchange::Event::Connected(_, streams) => {
+                self.set_state(streams, ConnectionState::Connected);
+            }
+            exchange::Event::Disconnected(_, streams, reason) => {
+                self.set_state(streams, ConnectionState::Disconnected(reason.clone()));
+            }
+            exchange::Event::Reconnecting(stream, delay) => {
+                self.set_state(&[*stream], ConnectionState::Reconnecting(*delay));
+            }
+            exchange::Event::DepthReceived(stream, ..)
+            | exchange::Event::KlineReceived(stream, _)
+            | exchange::Event::LiquidationsReceived(stream, _) => {
+                self.msg_count += 1;
+
+                // a message can only come over an open connection, even if its event got lost
+                let status = self
+                    .streams
+                    .entry(*stream)
+                    .or_insert_with(|| StreamStatus::new(ConnectionState::Connected));
+                status.state = ConnectionState::Connected;
+                status.last_message = Some(Instant::now());
+                status.msg_count += 1;
+            }
+            exchange::Event::DepthDegraded(..) => {}
+        }
+    }
+
+    fn set_state(&mut self, streams: &[StreamKind], state: ConnectionState) {
+        for stream in streams {
+            self.streams
+                .entry(*stream)
+                .and_modify(|status| status.state = state.clone())
+                .or_insert_with(|| StreamStatus::new(state.clone()));
+        }
+    }
+
+    /// Samples the message rates, dropping the streams no layout subscribes to anymore
+    pub fn tick(&mut self, now: Instant, is_active: impl Fn(&StreamKind) -> bool) {
+        let elapsed = now.saturating_duration_since(self.last_sample);
+        if elapsed < SAMPLE_INTERVAL {
+            return;
+        }
+
+        self.msg_rate = self.msg_count as f32 / elapsed.as_secs_f32();
+        self.msg_count = 0;
+        self.last_sample = now;
+
+        self.streams.retain(|stream, _| is_active(stream));
+        for status in self.streams.values_mut() {
+            status.msg_rate = status.msg_count as f32 / elapsed.as_secs_f32();
+            status.msg_count = 0;
+        }
+
+        let cpu_time = process_cpu_time();
+
+        self.usage = match (cpu_time, self.last_cpu_time, process_memory()) {
+            (Some(cpu_time), Some(last_cpu_time), Some(memory_bytes)) => Some(ProcessUsage {
+                cpu_pct: (cpu_time.saturating_sub(last_cpu_time).as_secs_f32()
+                    / elapsed.as_secs_f32())
+                    * 100.0,
+                memory_bytes,
+            }),
+            _ => None,
+        };
+        self.last_cpu_time = cpu_time;
+    }
+
+    pub fn view<'a>(&self, timezone: data::UserTimezone) -> Element<'a, Message> {
+        let usage = match self.usage {
+            Some(usage) => format!(
+                "CPU {:.0}%  MEM {:.0} MB",
+                usage.cpu_pct,
+                usage.memory_bytes as f32 / (1024.0 * 1024.0)
+            ),
+            None => "CPU -  MEM -".to_string(),
+        };
+
+        let connected_streams = self
+            .streams
+            .values()
+            .filter(|status| status.state == ConnectionState::Connected)
+            .count();
+
+        let show_streams = self.show_streams;
+
+        let mut content = row![
+            button(
+                text(format!(
+                    "Streams {connected_streams}/{}",
+                    self.streams.len()
+                ))
+                .size(11)
+            )
+            .padding(0)
+            .on_press(Message::ToggleStreamsPanel)
+            .style(move |theme, status| {
+                style::button::transparent(theme, status, show_streams)
+            }),
+            text(format!("{:.0} msg/s", self.msg_rate)).size(11),
+        ]
+        .spacing(16)
+        .align_y(Alignment::Center);
+
+        if SHOWS_USAGE {
+            content = content.push(text(usage).size(11));
+        }
+
+        let content = content.push(horizontal_space()).push(
+            text(format!(
+                "{} {}",
+                timezone.now().format("%H:%M:%S"),
+                match timezone {
+                    data::UserTimezone::Utc => "UTC",
+                    data::UserTimezone::Local => "",
+                }
+            ))
+            .size(11),
+        );
+
+        let bar = container(content)
+            .width(Length::Fill)
+            .padding(padding::left(12).right(12).bottom(4))
+            .style(style::status_bar);
+
+        if self.show_streams {
+            column![self.streams_panel(), bar].into()
+        } else {
+            bar.into()
+        }
+    }
+
+    fn streams_panel<'a>(&self) -> Element<'a, Message> {
+        let mut streams = self.streams.iter().collect::<Vec<_>>();
+        streams.sort_by_key(|(stream, _)| stream_label(stream));
+
+        let mut list = column![].spacing(2);
+
+        if streams.is_empty() {
+            list = list.push(text("No active streams").size(11));
+        }
+
+        for (stream, status) in streams {
+            let state = match &status.state {
+                ConnectionState::Connected => "Connected".to_string(),
+                ConnectionState::Disconnected(reason) => format!("Disconnected: {reason}"),
+                ConnectionState::Reconnecting(Some(delay)) => {
+                    format!("Reconnecting in {}s", delay.as_secs())
+                }
+                ConnectionState::Reconnecting(None) => "Gave up reconnecting".to_string(),
+            };
+            let last_message = status.last_message.map_or("-".to_string(), |last| {
+                format!("{:.1}s ago", last.elapsed().as_secs_f32())
+            });
+
+            list = list.push(
+                row![
+                    text(stream_label(stream))
+                        .size(11)
+                        .width(Length::FillPortion(3)),
+                    text(state).size(11).width(Length::FillPortion(3)),
+                    text(last_message).size(11).width(Length::FillPortion(1)),
+                    text(format!("{:.1} msg/s", status.msg_rate))
+                        .size(11)
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(8),
+            );
+        }
+
+        container(scrollable(list).height(Length::Shrink))
+            .width(Length::Fill)
+            .max_height(200)
+            .padding(padding::left(12).right(12).top(4).bottom(4))
+            .style(style::status_bar)
+            .into()
+    }
+}
+
+fn stream_label(stream: &StreamKind) -> String {
+    let (exchange, ticker) = stream.exchange_and_ticker();
+    let (symbol, _) = ticker.display_symbol_and_type();
+
+    match stream {
+        StreamKind::Kline { timeframe, .. } => format!("{symbol} ({exchange}) kline {timeframe}"),
+        StreamKind::DepthAndTrades { .. } => format!("{symbol} ({exchange}) depth & trades"),
+        StreamKind::Liquidations { .. } => format!("{symbol} ({exchange}) liquidations"),
+    }
+}
+
+/// Process CPU time from `/proc/self/stat`, `utime` and `stime` are in clock ticks
+/// which are 100 per second on every mainstream Linux kernel
+#[cfg(target_os = "linux")]
+fn process_cpu_time() -> Option<Duration> {
+    const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // the command name may contain spaces, fields are only reliable after its closing paren
+    let fields = stat
+        .rsplit_once(')')?
+        .1
+        .split_whitespace()
+        .collect::<Vec<_>>();
+
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+
+    Some(Duration::from_millis(
+        (utime + stime) * 1000 / CLOCK_TICKS_PER_SEC,
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn process_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| {
+            value
+                .trim()
+                .trim_end_matches("kB")
+                .trim()
+                .parse::<u64>()
+                .ok()
+        })
+        .map(|kb| kb * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_cpu_time() -> Option<Duration> {
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_memory() -> Option<u64> {
+    None
+}
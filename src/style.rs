@@ -1,3 +1,7 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+use data::config::{ChartFont, ChartText, UiDensity};
+use data::layout::pane::LinkGroup;
 use exchange::adapter::Exchange;
 
 use iced::font::{Family, Stretch, Weight};
@@ -21,6 +25,58 @@ pub const AZERET_MONO: Font = Font {
 
 pub const TITLE_PADDING_TOP: f32 = if cfg!(target_os = "macos") { 20.0 } else { 0.0 };
 
+static IS_COMPACT: AtomicBool = AtomicBool::new(false);
+
+/// Views read the density while laying out, so it's kept app wide instead of threaded through
+pub fn set_density(density: UiDensity) {
+    IS_COMPACT.store(density == UiDensity::Compact, Ordering::Relaxed);
+}
+
+pub fn density() -> UiDensity {
+    if IS_COMPACT.load(Ordering::Relaxed) {
+        UiDensity::Compact
+    } else {
+        UiDensity::Comfortable
+    }
+}
+
+/// A padding or row height adjusted to the density setting
+pub fn dense(value: f32) -> f32 {
+    density().scale(value)
+}
+
+static CHART_FONT: AtomicUsize = AtomicUsize::new(0);
+static CHART_TEXT_SIZE: AtomicU32 = AtomicU32::new(12.0_f32.to_bits());
+
+/// Same as the density, canvases read these while drawing, so they need a redraw once changed
+pub fn set_chart_text(chart_text: ChartText) {
+    let index = ChartFont::ALL
+        .iter()
+        .position(|font| *font == chart_text.font)
+        .unwrap_or_default();
+
+    CHART_FONT.store(index, Ordering::Relaxed);
+    CHART_TEXT_SIZE.store(chart_text.clamped_size().to_bits(), Ordering::Relaxed);
+}
+
+/// Font of the chart, scale and panel text
+pub fn chart_font() -> Font {
+    let font = ChartFont::ALL
+        .get(CHART_FONT.load(Ordering::Relaxed))
+        .copied()
+        .unwrap_or_default();
+
+    Font {
+        family: font.family_name().map_or(Family::Monospace, Family::Name),
+        ..AZERET_MONO
+    }
+}
+
+/// Base size of the axis labels, other chart text is offset from it
+pub fn chart_text_size() -> f32 {
+    f32::from_bits(CHART_TEXT_SIZE.load(Ordering::Relaxed))
+}
+
 pub enum Icon {
     Locked,
     Unlocked,
@@ -95,15 +151,36 @@ pub fn icon_text<'a>(icon: Icon, size: u16) -> Text<'a, Theme, Renderer> {
         .size(iced::Pixels(size.into()))
 }
 
-pub fn exchange_icon(exchange: Exchange) -> Icon {
+/// Venue logo from the icon font, OKX has no glyph in it yet so it falls back to its name
+pub fn exchange_icon_text<'a>(exchange: Exchange, size: u16) -> Text<'a, Theme, Renderer> {
     match exchange {
-        Exchange::BybitInverse | Exchange::BybitLinear | Exchange::BybitSpot => Icon::BybitLogo,
+        Exchange::BybitInverse | Exchange::BybitLinear | Exchange::BybitSpot => {
+            icon_text(Icon::BybitLogo, size)
+        }
         Exchange::BinanceInverse | Exchange::BinanceLinear | Exchange::BinanceSpot => {
-            Icon::BinanceLogo
+            icon_text(Icon::BinanceLogo, size)
+        }
+        Exchange::OkxInverse | Exchange::OkxLinear | Exchange::OkxSpot => {
+            iced::widget::text("OKX").size(iced::Pixels(f32::from(size) * 0.75))
         }
     }
 }
 
+/// Color telling a link group's panes apart from the other groups at a glance
+pub fn link_group_color(group: LinkGroup) -> Color {
+    match group {
+        LinkGroup::A => Color::from_rgb8(0xE5, 0x48, 0x4D),
+        LinkGroup::B => Color::from_rgb8(0xF5, 0x9E, 0x0B),
+        LinkGroup::C => Color::from_rgb8(0xEA, 0xD3, 0x2A),
+        LinkGroup::D => Color::from_rgb8(0x30, 0xA4, 0x6C),
+        LinkGroup::E => Color::from_rgb8(0x12, 0xA5, 0x94),
+        LinkGroup::F => Color::from_rgb8(0x3E, 0x8E, 0xD0),
+        LinkGroup::G => Color::from_rgb8(0x6E, 0x56, 0xCF),
+        LinkGroup::H => Color::from_rgb8(0xD6, 0x40, 0x9F),
+        LinkGroup::I => Color::from_rgb8(0x8D, 0x8D, 0x8D),
+    }
+}
+
 #[cfg(target_os = "macos")]
 pub fn title_text(theme: &Theme) -> iced::widget::text::Style {
     let palette = theme.extended_palette();
@@ -306,6 +383,27 @@ pub mod button {
         }
     }
 
+    /// Pane's link group toggle, outlined and labeled in its group's color
+    pub fn link_group(
+        theme: &Theme,
+        status: Status,
+        group: Option<data::layout::pane::LinkGroup>,
+    ) -> Style {
+        let style = bordered_toggle(theme, status, group.is_some());
+
+        match group.map(super::link_group_color) {
+            Some(color) => Style {
+                text_color: color,
+                border: Border {
+                    color,
+                    ..style.border
+                },
+                ..style
+            },
+            None => style,
+        }
+    }
+
     pub fn info(theme: &Theme, _status: Status) -> Style {
         let palette = theme.extended_palette();
 
@@ -595,6 +693,56 @@ pub fn validated_text_input(
     }
 }
 
+pub fn status_bar(theme: &Theme) -> Style {
+    let palette = theme.extended_palette();
+
+    Style {
+        text_color: Some(palette.background.base.text.scale_alpha(0.7)),
+        ..Default::default()
+    }
+}
+
+pub fn sidebar_strip(theme: &Theme) -> Style {
+    let palette = theme.extended_palette();
+
+    Style {
+        background: Some(palette.background.weak.color.scale_alpha(0.6).into()),
+        border: Border {
+            radius: 2.0.into(),
+            ..Border::default()
+        },
+        ..Default::default()
+    }
+}
+
+pub fn paused_badge(theme: &Theme) -> Style {
+    let palette = theme.extended_palette();
+
+    Style {
+        text_color: Some(palette.warning.base.color),
+        border: Border {
+            radius: 3.0.into(),
+            width: 1.0,
+            color: palette.warning.weak.color,
+        },
+        ..Default::default()
+    }
+}
+
+pub fn failover_badge(theme: &Theme) -> Style {
+    let palette = theme.extended_palette();
+
+    Style {
+        text_color: Some(palette.danger.base.color),
+        border: Border {
+            radius: 3.0.into(),
+            width: 1.0,
+            color: palette.danger.weak.color,
+        },
+        ..Default::default()
+    }
+}
+
 pub fn ticker_card(theme: &Theme) -> Style {
     let palette = theme.extended_palette();
 
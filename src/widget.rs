@@ -62,7 +62,7 @@ pub fn confirm_dialog_container<'a, Message: 'a + Clone>(
         .align_x(Alignment::Center)
         .spacing(16),
     )
-    .padding(24)
+    .padding(style::dense(24.0))
     .style(style::dashboard_modal)
     .into()
 }
@@ -223,24 +223,20 @@ where
     Message: Clone + 'static,
     F: Fn(iced::widget::pane_grid::Pane) -> Message + 'static,
 {
-    let is_active = link_group.is_some();
-
     let icon = if let Some(group) = link_group {
         text(group.to_string())
-            .font(style::AZERET_MONO)
+            .font(style::chart_font())
             .align_x(Alignment::Start)
             .align_y(Alignment::Center)
     } else {
         text("-")
-            .font(style::AZERET_MONO)
+            .font(style::chart_font())
             .align_x(Alignment::Start)
             .align_y(Alignment::Center)
     };
 
     button(icon)
-        .style(move |theme: &Theme, status| {
-            style::button::bordered_toggle(theme, status, is_active)
-        })
+        .style(move |theme: &Theme, status| style::button::link_group(theme, status, link_group))
         .on_press(on_press(id))
         .width(28)
         .into()
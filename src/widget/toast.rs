@@ -14,6 +14,10 @@ use iced::{Border, mouse, padding, theme, window};
 use crate::style;
 
 pub const DEFAULT_TIMEOUT: u64 = 8;
+/// Toasts shown at once, the ones arriving after get folded into a summary toast
+const MAX_TOASTS: usize = 4;
+/// Folded toasts the summary keeps listing, the oldest ones get dropped first
+const MAX_FOLDED: usize = 20;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -38,26 +42,19 @@ pub struct Toast {
     title: String,
     body: String,
     status: Status,
+    /// Times the same toast came in again while this one was showing
+    repeats: usize,
+    /// Toasts folded into this one once too many were showing, with their repeats
+    folded: Vec<(String, usize)>,
+    is_expanded: bool,
 }
 
 impl Toast {
     pub fn new(context: Notification) -> Self {
         match context {
-            Notification::Error(body) => Self {
-                title: "Error".to_string(),
-                body,
-                status: Status::Danger,
-            },
-            Notification::Info(body) => Self {
-                title: "Info".to_string(),
-                body,
-                status: Status::Primary,
-            },
-            Notification::Warn(body) => Self {
-                title: "Warning".to_string(),
-                body,
-                status: Status::Warning,
-            },
+            Notification::Error(body) => Self::error(body),
+            Notification::Info(body) => Self::info(body),
+            Notification::Warn(body) => Self::warn(body),
         }
     }
 
@@ -66,6 +63,16 @@ impl Toast {
             title: "Error".to_string(),
             body: body.into(),
             status: Status::Danger,
+            ..Default::default()
+        }
+    }
+
+    pub fn info(body: impl Into<String>) -> Self {
+        Self {
+            title: "Info".to_string(),
+            body: body.into(),
+            status: Status::Primary,
+            ..Default::default()
         }
     }
 
@@ -74,6 +81,77 @@ impl Toast {
             title: "Warning".to_string(),
             body: body.into(),
             status: Status::Warning,
+            ..Default::default()
+        }
+    }
+
+    fn is_repeat_of(&self, other: &Toast) -> bool {
+        self.folded.is_empty()
+            && self.status == other.status
+            && self.title == other.title
+            && self.body == other.body
+    }
+
+    fn fold(&mut self, toast: &Toast) {
+        let line = format!("{}: {}", toast.title, toast.body);
+
+        match self.folded.iter_mut().find(|(folded, _)| *folded == line) {
+            Some((_, count)) => *count += 1,
+            None => {
+                if self.folded.len() >= MAX_FOLDED {
+                    self.folded.remove(0);
+                }
+                self.folded.push((line, 1));
+            }
+        }
+
+        let total = self.folded.iter().map(|(_, count)| count).sum::<usize>();
+        self.body = format!("{total} more notifications");
+    }
+}
+
+/// Toasts of the main window or of a pane, repeats get counted on the toast already showing
+/// and a burst of new ones gets folded into a summary instead of stacking up
+#[derive(Debug, Default)]
+pub struct Notifications {
+    toasts: Vec<Toast>,
+}
+
+impl Notifications {
+    pub fn push(&mut self, toast: Toast) {
+        if let Some(showing) = self.toasts.iter_mut().find(|t| t.is_repeat_of(&toast)) {
+            showing.repeats += 1;
+            return;
+        }
+
+        if self.toasts.len() < MAX_TOASTS {
+            self.toasts.push(toast);
+            return;
+        }
+
+        match self.toasts.iter_mut().find(|t| !t.folded.is_empty()) {
+            Some(summary) => summary.fold(&toast),
+            None => {
+                let mut summary = Toast {
+                    title: "Notifications".to_string(),
+                    status: Status::Secondary,
+                    ..Default::default()
+                };
+                summary.fold(&toast);
+                self.toasts.push(summary);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.toasts.len() {
+            self.toasts.remove(index);
+        }
+    }
+
+    pub fn toggle_details(&mut self, index: usize) {
+        if let Some(toast) = self.toasts.get_mut(index) {
+            toast.is_expanded = !toast.is_expanded;
         }
     }
 }
@@ -92,18 +170,53 @@ where
 {
     pub fn new(
         content: impl Into<Element<'a, Message>>,
-        toasts: &'a [Toast],
+        notifications: &'a Notifications,
         alignment: Alignment,
         on_close: impl Fn(usize) -> Message + 'a,
+        on_toggle_details: impl Fn(usize) -> Message + 'a,
     ) -> Self {
-        let toasts = toasts
+        let toasts = notifications
+            .toasts
             .iter()
             .enumerate()
             .map(|(index, toast)| {
+                let title = if toast.repeats > 0 {
+                    format!("{} \u{d7}{}", toast.title, toast.repeats + 1)
+                } else {
+                    toast.title.clone()
+                };
+
+                let mut body = column![text(toast.body.as_str())].spacing(4);
+
+                if !toast.folded.is_empty() {
+                    let label = if toast.is_expanded { "Hide" } else { "Details" };
+
+                    body = body.push(
+                        button(text(label).size(11))
+                            .on_press((on_toggle_details)(index))
+                            .style(|theme, status| style::button::transparent(theme, status, false))
+                            .padding(0),
+                    );
+
+                    if toast.is_expanded {
+                        body = body.push(
+                            column(toast.folded.iter().map(|(line, count)| {
+                                let line = if *count > 1 {
+                                    format!("{line} \u{d7}{count}")
+                                } else {
+                                    line.clone()
+                                };
+                                text(line).size(11).into()
+                            }))
+                            .spacing(2),
+                        );
+                    }
+                }
+
                 container(column![
                     container(
                         row![
-                            text(toast.title.as_str()),
+                            text(title),
                             horizontal_space(),
                             button("X")
                                 .on_press((on_close)(index))
@@ -123,7 +236,7 @@ where
                     })
                     .width(Fill)
                     .padding(4),
-                    container(text(toast.body.as_str())).width(Fill).padding(4)
+                    container(body).width(Fill).padding(4)
                 ])
                 .style(style::chart_modal)
                 .padding(4)
@@ -25,6 +25,8 @@ pub fn default_size() -> Size {
 #[derive(Debug, Clone, Copy)]
 pub enum Event {
     CloseRequested(window::Id),
+    Focused(window::Id),
+    Unfocused(window::Id),
 }
 
 pub fn events() -> Subscription<Event> {
@@ -40,6 +42,8 @@ fn filtered_events(
         iced::Event::Window(iced::window::Event::CloseRequested) => {
             Some(Event::CloseRequested(window))
         }
+        iced::Event::Window(iced::window::Event::Focused) => Some(Event::Focused(window)),
+        iced::Event::Window(iced::window::Event::Unfocused) => Some(Event::Unfocused(window)),
         _ => None,
     }
 }